@@ -57,7 +57,25 @@ export default defineConfig({
                     {label: 'Built-in Devices', link: '/devices/builtin/'},
                     {label: 'Identity', link: '/devices/identity/'},
                     {label: 'Request Filter', link: '/devices/request-filter/'},
+                    {label: 'OpenAPI', link: '/devices/openapi/'},
+                    {label: 'Rate Limit', link: '/devices/rate-limit/'},
+                    {label: 'JWT Auth', link: '/devices/jwt-auth/'},
+                    {label: 'Quota', link: '/devices/quota/'},
+                    {label: 'IP Reputation', link: '/devices/ip-reputation/'},
+                    {label: 'Body Digest', link: '/devices/body-digest/'},
+                    {label: 'Upstream Group', link: '/devices/upstream-group/'},
+                    {label: 'Experiment', link: '/devices/experiment/'},
+                    {label: 'Header Rewrite', link: '/devices/header-rewrite/'},
+                    {label: 'Query Rewrite', link: '/devices/query-rewrite/'},
+                    {label: 'CORS', link: '/devices/cors/'},
+                    {label: 'Idempotency', link: '/devices/idempotency/'},
+                    {label: 'Required Headers', link: '/devices/required-headers/'},
+                    {label: 'Status Remap', link: '/devices/status-remap/'},
+                    {label: 'Error Page', link: '/devices/error-page/'},
+                    {label: 'Response Headers', link: '/devices/response-headers/'},
+                    {label: 'Deprecation', link: '/devices/deprecation/'},
                     {label: 'Structured Logging', link: '/devices/structured-logging/'},
+                    {label: 'Lua', link: '/devices/lua/'},
                     {label: 'WASM Devices', link: '/devices/wasm/'},
                 ],
             },
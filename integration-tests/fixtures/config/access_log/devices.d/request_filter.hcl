@@ -0,0 +1,5 @@
+request_filter_device {
+  enable = true
+
+  deny_methods = ["DELETE"]
+}
@@ -0,0 +1,9 @@
+server {
+  version    = 1
+  access_log = true
+}
+
+include {
+  devices = "devices.d/*.hcl"
+  ingress = "ingress.d/*.hcl"
+}
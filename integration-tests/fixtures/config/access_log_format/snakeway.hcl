@@ -0,0 +1,10 @@
+server {
+  version           = 1
+  access_log        = true
+  access_log_format = "$remote_addr \"$method $path\" $status $device"
+}
+
+include {
+  devices = "devices.d/*.hcl"
+  ingress = "ingress.d/*.hcl"
+}
@@ -0,0 +1,43 @@
+bind = {
+  interface    = "127.0.0.1"
+  port         = 8080
+  enable_http2 = false
+}
+
+services = [
+  {
+    load_balancing_strategy = "round_robin"
+
+    health_check = {
+      enable                     = true
+      failure_threshold          = 3
+      unhealthy_cooldown_seconds = 10
+
+      active = {
+        path                  = "/healthz"
+        interval_milliseconds = 100
+        timeout_milliseconds  = 100
+        expected_status       = 200
+        failure_threshold     = 2
+        success_threshold     = 2
+      }
+    }
+
+    routes = [
+      {
+        path = "/api"
+      }
+    ]
+
+    upstreams = [
+      {
+        weight = 1
+        endpoint = { host = "127.0.0.1", port = 9001 }
+      },
+      {
+        weight = 1
+        endpoint = { host = "127.0.0.1", port = 9002 }
+      },
+    ]
+  }
+]
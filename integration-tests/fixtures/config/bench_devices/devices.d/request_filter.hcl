@@ -0,0 +1,28 @@
+request_filter_device {
+  enable = true
+
+  #----------------------------------------------------------------------------
+  # Method policy
+  #----------------------------------------------------------------------------
+  allow_methods = ["GET", "POST", "DELETE",]
+
+  #----------------------------------------------------------------------------
+  # Header policy
+  #----------------------------------------------------------------------------
+  deny_headers = [
+    "x-forwarded-host",
+    "x-original-url",
+  ]
+
+  required_headers = [
+    "host",
+  ]
+
+  #----------------------------------------------------------------------------
+  # Size limits
+  #----------------------------------------------------------------------------
+  max_header_bytes = 1024           # 1 KB
+  max_body_bytes = 16384            # 16 KB
+  max_suspicious_body_bytes = 1024  # 1 KB
+
+}
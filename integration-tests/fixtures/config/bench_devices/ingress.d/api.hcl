@@ -0,0 +1,24 @@
+bind = {
+  interface    = "127.0.0.1"
+  port         = 8080
+  enable_http2 = false
+}
+
+services = [
+  {
+    load_balancing_strategy = "round_robin"
+
+    routes = [
+      {
+        path = "/api"
+      }
+    ]
+
+    upstreams = [
+      {
+        weight = 1
+        endpoint = { host = "127.0.0.1", port = 9001 }
+      },
+    ]
+  }
+]
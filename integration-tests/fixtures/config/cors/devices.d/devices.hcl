@@ -0,0 +1,20 @@
+# The request filter requires credentials that browsers never send on preflights.
+request_filter_device {
+  enable = true
+
+  required_headers = [
+    "authorization",
+  ]
+
+  deny_status = 401 # Unauthorized
+}
+
+cors_device {
+  enable = true
+
+  allow_origins     = ["https://app.example.com"]
+  allow_methods     = ["GET", "POST"]
+  allow_headers     = ["authorization", "content-type"]
+  allow_credentials = true
+  max_age_seconds   = 600
+}
@@ -0,0 +1,5 @@
+idempotency_device {
+  enable  = true
+  methods = ["POST"]
+  paths   = ["/api"]
+}
@@ -0,0 +1,5 @@
+jwt_auth_device = {
+  enable = true
+
+  hmac_secret = "integration-secret"
+}
@@ -0,0 +1,11 @@
+identity_device = {
+  enable = true
+
+  enable_geoip = false
+
+  trusted_proxies = ["10.0.0.0/8"]
+
+  enable_user_agent = true
+
+  ua_engine = "woothee"
+}
\ No newline at end of file
@@ -0,0 +1,28 @@
+bind = {
+  interface    = "127.0.0.1"
+  port         = 8080
+  enable_http2 = false
+}
+
+services = [
+  {
+    load_balancing_strategy = "round_robin"
+
+    routes = [
+      {
+        path = "/api"
+      },
+      {
+        path                   = "/upload"
+        max_request_body_bytes = 4096
+      }
+    ]
+
+    upstreams = [
+      {
+        weight = 1
+        endpoint = { host = "127.0.0.1", port = 9001 }
+      },
+    ]
+  }
+]
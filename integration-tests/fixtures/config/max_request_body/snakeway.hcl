@@ -0,0 +1,10 @@
+server {
+  version                = 1
+  access_log             = true
+  max_request_body_bytes = 1024
+}
+
+include {
+  devices = "devices.d/*.hcl"
+  ingress = "ingress.d/*.hcl"
+}
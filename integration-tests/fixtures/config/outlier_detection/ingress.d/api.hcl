@@ -0,0 +1,35 @@
+bind = {
+  interface    = "127.0.0.1"
+  port         = 8080
+  enable_http2 = false
+}
+
+services = [
+  {
+    load_balancing_strategy = "round_robin"
+
+    outlier_detection = {
+      enable                          = true
+      consecutive_5xx                 = 3
+      base_ejection_time_milliseconds = 500
+      max_ejection_time_milliseconds  = 5000
+    }
+
+    routes = [
+      {
+        path = "/api"
+      }
+    ]
+
+    upstreams = [
+      {
+        weight = 1
+        endpoint = { host = "127.0.0.1", port = 9001 }
+      },
+      {
+        weight = 1
+        endpoint = { host = "127.0.0.1", port = 9002 }
+      },
+    ]
+  }
+]
@@ -0,0 +1,8 @@
+server {
+  version = 1
+}
+
+include {
+  devices = "devices.d/*.hcl"
+  ingress = "ingress.d/*.hcl"
+}
@@ -0,0 +1,11 @@
+quota_device = {
+  enable = true
+
+  quota_url      = "http://127.0.0.1:9100/quota"
+  api_key_header = "X-API-Key"
+
+  timeout_milliseconds   = 1000
+  cache_ttl_milliseconds = 60000
+
+  fail_open = false
+}
@@ -0,0 +1,8 @@
+rate_limit_device = {
+  enable = true
+
+  requests_per_second = 1
+  burst               = 5
+  key                 = "header"
+  key_header          = "X-API-Key"
+}
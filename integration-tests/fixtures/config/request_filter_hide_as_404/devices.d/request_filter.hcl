@@ -0,0 +1,7 @@
+request_filter_device {
+  enable = true
+
+  deny_headers = [
+    "x-forwarded-host",
+  ]
+}
@@ -0,0 +1,47 @@
+bind = {
+  interface    = "127.0.0.1"
+  port         = 8080
+  enable_http2 = false
+}
+
+services = [
+  {
+    load_balancing_strategy = "round_robin"
+
+    health_check = {
+      enable                     = false
+      failure_threshold          = 3
+      unhealthy_cooldown_seconds = 10
+    }
+
+    circuit_breaker = {
+      enable_auto_recovery       = false
+      failure_threshold          = 3
+      open_duration_milliseconds = 10000
+      half_open_max_requests     = 1
+      success_threshold          = 2
+      count_http_5xx_as_failure  = false
+    }
+
+    routes = [
+      {
+        path = "/api"
+      },
+      {
+        path        = "/public"
+        hide_as_404 = false
+      }
+    ]
+
+    upstreams = [
+      {
+        weight = 1
+        endpoint = { host = "127.0.0.1", port = 9001 }
+      },
+      {
+        weight = 1
+        endpoint = { host = "127.0.0.1", port = 9002 }
+      },
+    ]
+  }
+]
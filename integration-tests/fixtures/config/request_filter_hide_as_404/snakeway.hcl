@@ -0,0 +1,9 @@
+server {
+  version     = 1
+  hide_as_404 = true
+}
+
+include {
+  devices = "devices.d/*.hcl"
+  ingress = "ingress.d/*.hcl"
+}
@@ -0,0 +1,28 @@
+bind = {
+  interface    = "127.0.0.1"
+  port         = 8080
+  enable_http2 = false
+}
+
+services = [
+  {
+    load_balancing_strategy = "failover"
+
+    routes = [
+      {
+        path = "/api"
+      },
+      {
+        path                         = "/reports"
+        request_timeout_milliseconds = 1500
+      }
+    ]
+
+    upstreams = [
+      {
+        weight = 1
+        endpoint = { host = "127.0.0.1", port = 9001 }
+      }
+    ]
+  }
+]
@@ -0,0 +1,9 @@
+server {
+  version                      = 1
+  request_timeout_milliseconds = 300
+}
+
+include {
+  devices = "devices.d/*.hcl"
+  ingress = "ingress.d/*.hcl"
+}
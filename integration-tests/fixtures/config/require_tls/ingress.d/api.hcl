@@ -0,0 +1,40 @@
+bind = {
+  interface    = "127.0.0.1"
+  port         = 8080
+  enable_http2 = false
+}
+
+services = [
+  {
+    load_balancing_strategy = "round_robin"
+
+    routes = [
+      {
+        path = "/api"
+      },
+      {
+        path        = "/login"
+        require_tls = { action = "redirect" }
+      },
+      {
+        path        = "/account"
+        require_tls = { action = "reject" }
+      },
+      {
+        path        = "/billing"
+        require_tls = { action = "reject", reject_status = 400 }
+      }
+    ]
+
+    upstreams = [
+      {
+        weight = 1
+        endpoint = { host = "127.0.0.1", port = 9001 }
+      },
+      {
+        weight = 1
+        endpoint = { host = "127.0.0.1", port = 9002 }
+      },
+    ]
+  }
+]
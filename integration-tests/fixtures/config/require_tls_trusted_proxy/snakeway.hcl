@@ -0,0 +1,10 @@
+server {
+  version = 1
+
+  trusted_proxies = ["127.0.0.1/32"]
+}
+
+include {
+  devices = "devices.d/*.hcl"
+  ingress = "ingress.d/*.hcl"
+}
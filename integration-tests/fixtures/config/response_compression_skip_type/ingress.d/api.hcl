@@ -0,0 +1,29 @@
+bind = {
+  interface    = "127.0.0.1"
+  port         = 8080
+  enable_http2 = false
+}
+
+services = [
+  {
+    load_balancing_strategy = "failover"
+
+    upstream_accept_encoding                = "identity"
+    enable_response_compression             = true
+    response_compression_min_size           = 0
+    response_compression_skip_content_types = ["text/*"]
+
+    routes = [
+      {
+        path = "/api"
+      }
+    ]
+
+    upstreams = [
+      {
+        weight = 1
+        endpoint = { host = "127.0.0.1", port = 9001 }
+      }
+    ]
+  }
+]
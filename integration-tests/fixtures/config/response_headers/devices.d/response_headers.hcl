@@ -0,0 +1,9 @@
+response_headers_device = {
+  enable = true
+
+  headers = [
+    { name = "X-Request-Id", value = "{request_id}" },
+    { name = "X-Served-By", value = "{upstream}" },
+    { name = "X-Route", value = "{route}" },
+  ]
+}
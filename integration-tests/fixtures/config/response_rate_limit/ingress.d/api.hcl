@@ -0,0 +1,32 @@
+bind = {
+  interface    = "127.0.0.1"
+  port         = 8080
+  enable_http2 = false
+}
+
+services = [
+  {
+    load_balancing_strategy = "failover"
+
+    routes = [
+      {
+        path = "/api"
+      },
+      {
+        path = "/downloads"
+
+        response_rate_limit = {
+          bytes_per_second = 4096
+          burst_bytes      = 1024
+        }
+      }
+    ]
+
+    upstreams = [
+      {
+        weight = 1
+        endpoint = { host = "127.0.0.1", port = 9001 }
+      }
+    ]
+  }
+]
@@ -0,0 +1,10 @@
+server {
+  version    = 1
+  threads    = 4
+  reuse_port = true
+}
+
+include {
+  devices = "devices.d/*.hcl"
+  ingress = "ingress.d/*.hcl"
+}
@@ -0,0 +1,9 @@
+server {
+  version                  = 1
+  shutdown_timeout_seconds = 5
+}
+
+include {
+  devices = "devices.d/*.hcl"
+  ingress = "ingress.d/*.hcl"
+}
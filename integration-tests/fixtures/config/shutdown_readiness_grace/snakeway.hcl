@@ -0,0 +1,10 @@
+server {
+  version                          = 1
+  shutdown_timeout_seconds         = 5
+  shutdown_readiness_grace_seconds = 1
+}
+
+include {
+  devices = "devices.d/*.hcl"
+  ingress = "ingress.d/*.hcl"
+}
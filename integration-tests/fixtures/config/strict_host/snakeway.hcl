@@ -0,0 +1,9 @@
+server {
+  version     = 1
+  strict_host = true
+}
+
+include {
+  devices = "devices.d/*.hcl"
+  ingress = "ingress.d/*.hcl"
+}
@@ -0,0 +1,29 @@
+bind = {
+  interface    = "127.0.0.1"
+  port         = 8080
+  enable_http2 = false
+}
+
+services = [
+  {
+    load_balancing_strategy = "failover"
+
+    upstream_request_headers = [
+      { name = "X-Api-Key", value = "secret://file/secrets/backend_api_key" },
+      { name = "X-Static", value = "static-value" },
+    ]
+
+    routes = [
+      {
+        path = "/api"
+      }
+    ]
+
+    upstreams = [
+      {
+        weight = 1
+        endpoint = { host = "127.0.0.1", port = 9001 }
+      }
+    ]
+  }
+]
@@ -0,0 +1,9 @@
+request_filter_device {
+  enable = true
+
+  required_headers = [
+    "authorization",
+  ]
+
+  deny_status = 401 # Unauthorized
+}
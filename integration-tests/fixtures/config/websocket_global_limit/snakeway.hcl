@@ -0,0 +1,10 @@
+server {
+  version = 1
+
+  ws_max_connections = 1
+}
+
+include {
+  devices = "devices.d/*.hcl"
+  ingress = "ingress.d/*.hcl"
+}
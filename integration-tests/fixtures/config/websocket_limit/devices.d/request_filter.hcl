@@ -0,0 +1,5 @@
+request_filter_device {
+  enable = true
+
+  max_header_bytes = 4096
+}
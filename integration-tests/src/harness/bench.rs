@@ -0,0 +1,158 @@
+use crate::harness::TestServer;
+use reqwest::blocking::Client;
+use std::fmt;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Load generated against each target of a benchmark.
+#[derive(Debug, Clone)]
+pub struct LoadOptions {
+    /// Requests sent and timed, across all workers.
+    pub requests: usize,
+
+    /// Workers sending requests at the same time, each over a keep-alive connection of its own.
+    pub concurrency: usize,
+
+    /// Requests each worker sends before timing starts, to open connections and warm caches.
+    pub warmup: usize,
+}
+
+/// Latencies and throughput measured against one target.
+#[derive(Debug, Clone)]
+pub struct LoadStats {
+    /// Latency of every timed request, sorted.
+    pub latencies: Vec<Duration>,
+
+    /// Time it took all workers to send their timed requests.
+    pub elapsed: Duration,
+}
+
+impl LoadStats {
+    /// Latency of the given percentile of requests, e.g. `99.0` for p99.
+    pub fn percentile(&self, pct: f64) -> Duration {
+        if self.latencies.is_empty() {
+            return Duration::ZERO;
+        }
+
+        let rank = (pct / 100.0 * self.latencies.len() as f64).ceil() as usize;
+        self.latencies[rank.clamp(1, self.latencies.len()) - 1]
+    }
+
+    /// Requests per second all workers sustained together.
+    pub fn throughput(&self) -> f64 {
+        self.latencies.len() as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+/// Overhead of the proxy: the same load sent straight to the upstream, and through Snakeway.
+#[derive(Debug, Clone)]
+pub struct OverheadReport {
+    pub fixture: String,
+    pub direct: LoadStats,
+    pub proxied: LoadStats,
+}
+
+impl OverheadReport {
+    /// Latency the proxy adds at the given percentile.
+    pub fn overhead(&self, pct: f64) -> Duration {
+        self.proxied
+            .percentile(pct)
+            .saturating_sub(self.direct.percentile(pct))
+    }
+}
+
+impl fmt::Display for OverheadReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "proxy overhead ({}):", self.fixture)?;
+        for (name, stats) in [("direct", &self.direct), ("proxied", &self.proxied)] {
+            writeln!(
+                f,
+                "  {name:<8} p50={:?} p99={:?} throughput={:.0} req/s",
+                stats.percentile(50.0),
+                stats.percentile(99.0),
+                stats.throughput()
+            )?;
+        }
+        write!(
+            f,
+            "  overhead p50={:?} p99={:?}",
+            self.overhead(50.0),
+            self.overhead(99.0)
+        )
+    }
+}
+
+/// Measure the overhead of the proxy with the given fixture, against the bench upstream.
+///
+/// The fixture's first upstream is hit directly first, then through Snakeway, with the same
+/// load. Every request must succeed.
+pub fn measure_overhead(fixture: &str, path: &str, opts: &LoadOptions) -> OverheadReport {
+    let srv = TestServer::start_with_bench_upstream(fixture);
+    let upstream_port = *srv.upstream_ports().first().expect("no upstream");
+
+    let direct = run_load(&format!("http://127.0.0.1:{upstream_port}{path}"), opts);
+    let proxied = run_load(&format!("{}{path}", srv.base_url()), opts);
+
+    OverheadReport {
+        fixture: fixture.to_string(),
+        direct,
+        proxied,
+    }
+}
+
+/// Send `GET` requests to the URL from concurrent workers, and time each of them.
+pub fn run_load(url: &str, opts: &LoadOptions) -> LoadStats {
+    let concurrency = opts.concurrency.max(1);
+    let started = Instant::now();
+
+    let mut latencies = thread::scope(|scope| {
+        let workers = (0..concurrency)
+            .map(|worker| {
+                // Spread the requests that do not divide evenly over the first workers.
+                let requests =
+                    opts.requests / concurrency + usize::from(worker < opts.requests % concurrency);
+                scope.spawn(move || run_worker(url, requests, opts.warmup))
+            })
+            .collect::<Vec<_>>();
+
+        workers
+            .into_iter()
+            .flat_map(|w| w.join().expect("benchmark worker panicked"))
+            .collect::<Vec<_>>()
+    });
+
+    let elapsed = started.elapsed();
+    latencies.sort();
+
+    LoadStats { latencies, elapsed }
+}
+
+fn run_worker(url: &str, requests: usize, warmup: usize) -> Vec<Duration> {
+    let client = Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .expect("failed to build client");
+
+    let send = || {
+        let res = client.get(url).send().expect("benchmark request failed");
+        assert!(
+            res.status().is_success(),
+            "benchmark request failed with {}",
+            res.status()
+        );
+        // Read the body, so the connection is reused for the next request.
+        res.bytes().expect("failed to read benchmark response");
+    };
+
+    for _ in 0..warmup {
+        send();
+    }
+
+    (0..requests)
+        .map(|_| {
+            let started = Instant::now();
+            send();
+            started.elapsed()
+        })
+        .collect()
+}
@@ -1,7 +1,7 @@
 use snakeway_core::conf::RuntimeConfig;
 use url::Url;
 
-use snakeway_core::conf::types::RouteConfig;
+use snakeway_core::conf::types::{DeviceConfig, RouteConfig};
 use std::path::PathBuf;
 
 pub fn patch_runtime(cfg: &mut RuntimeConfig, listener_ports: &[u16], upstream_ports: &[u16]) {
@@ -9,6 +9,32 @@ pub fn patch_runtime(cfg: &mut RuntimeConfig, listener_ports: &[u16], upstream_p
     patch_paths(cfg);
 }
 
+/// Point every quota device at the mock quota service on the given port.
+pub fn patch_quota_devices(cfg: &mut RuntimeConfig, quota_port: u16) {
+    for device in &mut cfg.devices {
+        if let DeviceConfig::Quota(quota) = device {
+            let mut url = Url::parse(&quota.quota_url).expect("invalid quota URL in fixture");
+
+            url.set_port(Some(quota_port))
+                .expect("failed to set quota service port");
+
+            quota.quota_url = url.to_string();
+        }
+    }
+}
+
+/// Point every unix socket upstream at a socket path of its own.
+pub fn patch_unix_upstreams(cfg: &mut RuntimeConfig, socks: &[PathBuf]) {
+    let upstreams = cfg
+        .services
+        .values_mut()
+        .flat_map(|svc| svc.unix_upstreams.iter_mut());
+
+    for (up, sock) in upstreams.zip(socks) {
+        up.sock = sock.display().to_string();
+    }
+}
+
 fn patch_paths(cfg: &mut RuntimeConfig) {
     let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     let repo_root = manifest_dir.parent().expect("expected workspace root");
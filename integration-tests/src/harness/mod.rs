@@ -1,5 +1,7 @@
+pub mod bench;
 mod config;
 pub mod server;
+pub mod shutdown;
 pub mod tracing;
 pub mod upstream;
 
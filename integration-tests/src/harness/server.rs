@@ -1,15 +1,24 @@
-use crate::harness::config::patch_runtime;
-use crate::harness::upstream::{start_grpc_upstream, start_http_upstream, start_ws_upstream};
+use crate::harness::config::{patch_quota_devices, patch_runtime, patch_unix_upstreams};
+use crate::harness::upstream::{
+    start_bench_upstream, start_echo_upstream, start_grpc_upstream, start_http_upstream,
+    start_quota_service, start_slow_upstream, start_stalling_upstream, start_switchable_upstream,
+    start_unix_http_upstream, start_ws_upstream,
+};
 use crate::harness::{CapturedEvent, init_test_tracing};
 use arc_swap::ArcSwap;
+use reqwest::Method;
 use reqwest::blocking::{Client, RequestBuilder};
 use snakeway_core::conf::load_config;
+use snakeway_core::conf::types::DeviceConfig;
 use snakeway_core::runtime::build_runtime_state;
-use snakeway_core::server::{ReloadHandle, build_pingora_server};
+use snakeway_core::server::{
+    ReloadHandle, ShutdownCoordinator, ShutdownPhase, build_pingora_server,
+};
 use snakeway_core::traffic_management::{TrafficManager, TrafficSnapshot};
 use snakeway_core::ws_connection_management::WsConnectionManager;
 use std::net::TcpStream;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex, OnceLock};
 use std::thread;
 use std::time::{Duration, Instant};
@@ -17,7 +26,9 @@ use std::time::{Duration, Instant};
 /// Handle to a running Snakeway test server.
 pub struct TestServer {
     base_urls: Vec<String>,
+    upstream_ports: Vec<u16>,
     client: Client,
+    shutdown: Arc<ShutdownCoordinator>,
 }
 
 impl TestServer {
@@ -79,6 +90,31 @@ impl TestServer {
         // This is a bit of magic that ensures all the integration tests can be run in parallel.
         patch_runtime(&mut cfg, &listener_ports, &upstream_ports);
 
+        // Unix socket upstreams get a socket path of their own, and always serve plain HTTP.
+        let unix_socks = cfg
+            .services
+            .iter()
+            .flat_map(|(_, c)| c.unix_upstreams.iter())
+            .map(|_| free_sock_path())
+            .collect::<Vec<_>>();
+
+        for sock in &unix_socks {
+            start_unix_http_upstream(sock);
+        }
+
+        patch_unix_upstreams(&mut cfg, &unix_socks);
+
+        // Start a mock quota service if the fixture has a quota device.
+        if cfg
+            .devices
+            .iter()
+            .any(|d| matches!(d, DeviceConfig::Quota(_)))
+        {
+            let quota_port = free_port();
+            start_quota_service(quota_port);
+            patch_quota_devices(&mut cfg, quota_port);
+        }
+
         // Build the initial runtime state (static for tests).
         let runtime_state = build_runtime_state(&cfg).expect("failed to build runtime state");
         let state = Arc::new(ArcSwap::from_pointee(runtime_state));
@@ -89,18 +125,48 @@ impl TestServer {
         // Build server.
         let connection_manager = Arc::new(WsConnectionManager::new());
         let reload = Arc::new(ReloadHandle::new());
+        let shutdown = Arc::new(ShutdownCoordinator::new(
+            Duration::from_secs(cfg.server.shutdown_timeout_seconds),
+            Duration::from_secs(cfg.server.shutdown_readiness_grace_seconds),
+        ));
         let server = build_pingora_server(
             cfg.clone(),
-            state,
+            state.clone(),
             traffic_manager,
             connection_manager,
             reload,
+            shutdown.clone(),
         )
         .expect("failed to build snakeway server");
 
+        // Run the shutdown sequence in a background thread, like the control plane does.
+        // Unlike the real server, the process is not exited once the sequence completes.
+        thread::spawn({
+            let shutdown = shutdown.clone();
+            move || {
+                let rt = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .expect("failed to build shutdown runtime");
+
+                rt.block_on(async move {
+                    tokio::spawn({
+                        let shutdown = shutdown.clone();
+                        async move {
+                            let _ = shutdown.listen_for_sigterm().await;
+                        }
+                    });
+                    shutdown.run_sequence(&state).await;
+                });
+            }
+        });
+
         // Run server in a background thread.
-        thread::spawn(move || {
-            server.run_forever();
+        thread::spawn({
+            let shutdown = shutdown.clone();
+            move || {
+                server.run(shutdown.run_args());
+            }
         });
 
         let base_urls = cfg
@@ -119,7 +185,12 @@ impl TestServer {
             .build()
             .expect("failed to build client");
 
-        Self { base_urls, client }
+        Self {
+            base_urls,
+            upstream_ports,
+            client,
+            shutdown,
+        }
     }
 
     pub fn start_with_ws_upstream(fixture: &str) -> Self {
@@ -134,6 +205,26 @@ impl TestServer {
         Self::start_with(fixture, start_http_upstream)
     }
 
+    pub fn start_with_echo_upstream(fixture: &str) -> Self {
+        Self::start_with(fixture, start_echo_upstream)
+    }
+
+    pub fn start_with_slow_upstream(fixture: &str) -> Self {
+        Self::start_with(fixture, start_slow_upstream)
+    }
+
+    pub fn start_with_stalling_upstream(fixture: &str) -> Self {
+        Self::start_with(fixture, start_stalling_upstream)
+    }
+
+    pub fn start_with_switchable_upstream(fixture: &str) -> Self {
+        Self::start_with(fixture, start_switchable_upstream)
+    }
+
+    pub fn start_with_bench_upstream(fixture: &str) -> Self {
+        Self::start_with(fixture, start_bench_upstream)
+    }
+
     /// Convenience helper for GET requests.
     pub fn get(&self, path: &str) -> RequestBuilder {
         self.client.get(format!("{}{}", self.base_url(), path))
@@ -151,6 +242,80 @@ impl TestServer {
         self.client.delete(format!("{}{}", self.base_url(), path))
     }
 
+    pub fn options(&self, path: &str) -> RequestBuilder {
+        self.client
+            .request(Method::OPTIONS, format!("{}{}", self.base_url(), path))
+    }
+
+    /// Whether the server still reports ready.
+    pub fn is_ready(&self) -> bool {
+        self.shutdown.is_ready()
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.shutdown.is_draining()
+    }
+
+    /// Poll until the shutdown sequence has completed (or panic).
+    pub fn wait_for_shutdown(&self, timeout: Duration) {
+        let deadline = Instant::now() + timeout;
+
+        while self.shutdown.phase() != ShutdownPhase::Complete {
+            if Instant::now() > deadline {
+                panic!(
+                    "shutdown did not complete, stuck in phase {}",
+                    self.shutdown.phase()
+                );
+            }
+            thread::sleep(Duration::from_millis(25));
+        }
+    }
+
+    /// Shutdown phases emitted so far, in order.
+    pub fn shutdown_phases(&self) -> Vec<String> {
+        events()
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|e| {
+                e.fields
+                    .iter()
+                    .any(|(k, v)| k == "message" && v == "shutdown phase")
+            })
+            .filter_map(|e| {
+                e.fields
+                    .iter()
+                    .find(|(k, _)| k == "phase")
+                    .map(|(_, v)| v.clone())
+            })
+            .collect()
+    }
+
+    /// Structured log events with the given `event` name, e.g. "complete".
+    /// Events are logged after the response is sent, so this waits briefly for at least one.
+    pub fn http_events(&self, name: &str) -> Vec<CapturedEvent> {
+        let deadline = Instant::now() + Duration::from_secs(2);
+        loop {
+            let matching = events()
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|e| e.fields.iter().any(|(k, v)| k == "event" && v == name))
+                .cloned()
+                .collect::<Vec<_>>();
+
+            if !matching.is_empty() || Instant::now() > deadline {
+                return matching;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    /// Ports of the TCP upstreams, in the order they were started.
+    pub fn upstream_ports(&self) -> &[u16] {
+        &self.upstream_ports
+    }
+
     /// Returns the first configured base URL.
     pub fn base_url(&self) -> &str {
         self.base_urls.first().expect("no base url")
@@ -193,3 +358,10 @@ fn free_port() -> u16 {
         .unwrap()
         .port()
 }
+
+/// Allocate a unix socket path no other test in this run uses.
+fn free_sock_path() -> PathBuf {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    let n = NEXT.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!("snakeway-it-{}-{n}.sock", std::process::id()))
+}
@@ -0,0 +1,27 @@
+use std::net::TcpStream;
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Send SIGTERM to this test process; the server's shutdown coordinator handles it.
+pub fn send_sigterm() {
+    let status = Command::new("kill")
+        .args(["-TERM", &std::process::id().to_string()])
+        .status()
+        .expect("failed to run kill");
+    assert!(status.success());
+}
+
+/// Poll until connecting to the listener is refused, or panic after `timeout`.
+pub fn wait_for_refused_connection(base_url: &str, timeout: Duration) {
+    let addr = base_url.strip_prefix("http://").unwrap_or(base_url);
+    let deadline = Instant::now() + timeout;
+
+    while TcpStream::connect(addr).is_ok() {
+        assert!(
+            Instant::now() < deadline,
+            "listener still accepts connections"
+        );
+        thread::sleep(Duration::from_millis(10));
+    }
+}
@@ -1,5 +1,6 @@
 use std::sync::{Arc, Mutex, Once};
 
+use snakeway_core::server::TlsHandshakeFailureLayer;
 use tracing::field::{Field, Visit};
 use tracing::{Event, Subscriber};
 use tracing_subscriber::layer::Context;
@@ -27,6 +28,7 @@ pub fn init_test_tracing(events: Arc<Mutex<Vec<CapturedEvent>>>) {
         tracing_subscriber::registry()
             .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("trace")))
             .with(capture_layer)
+            .with(TlsHandshakeFailureLayer)
             .with(fmt::layer().with_test_writer().with_ansi(false))
             .init();
 
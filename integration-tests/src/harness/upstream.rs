@@ -18,6 +18,339 @@ pub fn start_http_upstream(port: u16) {
     thread::sleep(Duration::from_millis(25));
 }
 
+/// Body served by the unix socket upstream, so responses can be told apart from TCP ones.
+pub const UNIX_UPSTREAM_BODY: &str = "hello unix";
+
+/// Start an HTTP/1.1 upstream listening on a unix domain socket.
+pub fn start_unix_http_upstream(path: &std::path::Path) {
+    use std::io::{Read, Write};
+    use std::os::unix::net::UnixListener;
+    use std::thread;
+    use std::time::Duration;
+
+    // A socket file left behind by an earlier run would make the bind fail.
+    let _ = std::fs::remove_file(path);
+    let listener = UnixListener::bind(path).expect("failed to bind unix upstream");
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.expect("stream error");
+
+            let mut buf = [0u8; 1024];
+            let _ = stream.read(&mut buf);
+
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{UNIX_UPSTREAM_BODY}",
+                UNIX_UPSTREAM_BODY.len()
+            );
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+
+    // tiny delay so the listener is actually ready
+    thread::sleep(Duration::from_millis(25));
+}
+
+/// Start an HTTP/1.1 upstream that echoes the raw request head back as the response body.
+/// Useful for asserting exactly what Snakeway put on the wire (e.g., header-name casing).
+pub fn start_echo_upstream(port: u16) {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+    use std::time::Duration;
+
+    let addr = format!("127.0.0.1:{port}");
+
+    thread::spawn(move || {
+        let listener = TcpListener::bind(&addr).expect("failed to bind upstream");
+        for stream in listener.incoming() {
+            let mut stream = stream.expect("stream error");
+
+            // Read until the end of the request head.
+            let mut head = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+                match stream.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => head.extend_from_slice(&buf[..n]),
+                }
+            }
+
+            // Drain the request body, so the connection is not reset while unread bytes remain.
+            let head_end = head
+                .windows(4)
+                .position(|w| w == b"\r\n\r\n")
+                .map(|i| i + 4)
+                .unwrap_or(head.len());
+            let header = |wanted: &str| {
+                String::from_utf8_lossy(&head[..head_end])
+                    .lines()
+                    .filter_map(|line| line.split_once(':'))
+                    .find(|(name, _)| name.eq_ignore_ascii_case(wanted))
+                    .map(|(_, value)| value.trim().to_ascii_lowercase())
+            };
+
+            // A chunked body is only answered once its last chunk arrives, so a request cut off
+            // on the way here is never answered.
+            if header("transfer-encoding").is_some_and(|te| te.contains("chunked")) {
+                let mut body = head[head_end..].to_vec();
+                while !body.ends_with(b"0\r\n\r\n") {
+                    match stream.read(&mut buf) {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => body.extend_from_slice(&buf[..n]),
+                    }
+                }
+                if !body.ends_with(b"0\r\n\r\n") {
+                    continue;
+                }
+            }
+
+            let content_length = header("content-length")
+                .and_then(|value| value.parse::<usize>().ok())
+                .unwrap_or(0);
+            let mut remaining = content_length.saturating_sub(head.len() - head_end);
+            while remaining > 0 {
+                match stream.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => remaining = remaining.saturating_sub(n),
+                }
+            }
+            head.truncate(head_end);
+
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: {}\r\n\r\n",
+                head.len()
+            );
+            let _ = stream.write_all(response.as_bytes());
+            let _ = stream.write_all(&head);
+        }
+    });
+
+    // tiny delay so the listener is actually ready
+    thread::sleep(Duration::from_millis(25));
+}
+
+/// Start an HTTP/1.1 upstream that answers every request with a small, fixed response, and
+/// keeps connections alive. Useful for benchmarks, where the upstream should cost next to nothing.
+pub fn start_bench_upstream(port: u16) {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+    use std::time::Duration;
+
+    let addr = format!("127.0.0.1:{port}");
+
+    thread::spawn(move || {
+        let listener = TcpListener::bind(&addr).expect("failed to bind upstream");
+        for stream in listener.incoming() {
+            let mut stream = stream.expect("stream error");
+
+            thread::spawn(move || {
+                // Requests are bodiless GETs, so every request head ends a request.
+                let mut pending = Vec::new();
+                let mut buf = [0u8; 4096];
+                loop {
+                    match stream.read(&mut buf) {
+                        Ok(0) | Err(_) => return,
+                        Ok(n) => pending.extend_from_slice(&buf[..n]),
+                    }
+
+                    while let Some(i) = pending.windows(4).position(|w| w == b"\r\n\r\n") {
+                        pending.drain(..i + 4);
+                        if stream
+                            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 11\r\n\r\nhello world")
+                            .is_err()
+                        {
+                            return;
+                        }
+                    }
+                }
+            });
+        }
+    });
+
+    // tiny delay so the listener is actually ready
+    thread::sleep(Duration::from_millis(25));
+}
+
+/// Start an HTTP/1.1 upstream that waits before responding.
+/// Useful for keeping a request in flight while something else happens (e.g., shutdown).
+pub fn start_slow_upstream(port: u16) {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+    use std::time::Duration;
+
+    let addr = format!("127.0.0.1:{port}");
+
+    thread::spawn(move || {
+        let listener = TcpListener::bind(&addr).expect("failed to bind upstream");
+        for stream in listener.incoming() {
+            let mut stream = stream.expect("stream error");
+
+            thread::spawn(move || {
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf);
+
+                thread::sleep(SLOW_UPSTREAM_DELAY);
+                let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 4\r\n\r\nslow");
+            });
+        }
+    });
+
+    // tiny delay so the listener is actually ready
+    thread::sleep(Duration::from_millis(25));
+}
+
+/// How long the slow upstream waits before responding.
+pub const SLOW_UPSTREAM_DELAY: std::time::Duration = std::time::Duration::from_millis(750);
+
+/// Start an HTTP/1.1 upstream that sends its headers and part of the body, then stalls.
+/// Useful for asserting that a stuck response body is aborted.
+pub fn start_stalling_upstream(port: u16) {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+    use std::time::Duration;
+
+    let addr = format!("127.0.0.1:{port}");
+
+    thread::spawn(move || {
+        let listener = TcpListener::bind(&addr).expect("failed to bind upstream");
+        for stream in listener.incoming() {
+            let mut stream = stream.expect("stream error");
+
+            thread::spawn(move || {
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf);
+
+                let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 11\r\n\r\nhello");
+                thread::sleep(STALLING_UPSTREAM_DELAY);
+                let _ = stream.write_all(b" world");
+            });
+        }
+    });
+
+    // tiny delay so the listener is actually ready
+    thread::sleep(Duration::from_millis(25));
+}
+
+/// How long the stalling upstream waits before finishing its body.
+pub const STALLING_UPSTREAM_DELAY: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// API key the mock quota service always reports as over quota.
+pub const OVER_QUOTA_API_KEY: &str = "over-quota";
+
+/// Start a mock quota service.
+///
+/// Keys are passed in the `X-API-Key` header. `OVER_QUOTA_API_KEY` gets `429`, every other key
+/// gets `200`. `X-RateLimit-Remaining` counts down with each call, so callers can tell whether a
+/// decision came from the service or from a cache.
+pub fn start_quota_service(port: u16) {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+    use std::time::Duration;
+
+    let addr = format!("127.0.0.1:{port}");
+
+    thread::spawn(move || {
+        let listener = TcpListener::bind(&addr).expect("failed to bind quota service");
+        let mut remaining: u32 = 100;
+
+        for stream in listener.incoming() {
+            let mut stream = stream.expect("stream error");
+
+            // Read until the end of the request head.
+            let mut head = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+                match stream.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => head.extend_from_slice(&buf[..n]),
+                }
+            }
+
+            let head = String::from_utf8_lossy(&head).to_lowercase();
+            let api_key = head
+                .lines()
+                .find_map(|line| line.strip_prefix("x-api-key:"))
+                .map(str::trim)
+                .unwrap_or_default();
+
+            remaining = remaining.saturating_sub(1);
+            let (status, remaining) = if api_key == OVER_QUOTA_API_KEY {
+                ("429 Too Many Requests", 0)
+            } else {
+                ("200 OK", remaining)
+            };
+
+            let response = format!(
+                "HTTP/1.1 {status}\r\nX-RateLimit-Limit: 100\r\nX-RateLimit-Remaining: {remaining}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            );
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+
+    // tiny delay so the listener is actually ready
+    thread::sleep(Duration::from_millis(25));
+}
+
+/// Ports of switchable upstreams that are currently taken down.
+static DOWN_UPSTREAMS: std::sync::OnceLock<std::sync::Mutex<std::collections::HashSet<u16>>> =
+    std::sync::OnceLock::new();
+
+fn down_upstreams() -> std::sync::MutexGuard<'static, std::collections::HashSet<u16>> {
+    DOWN_UPSTREAMS.get_or_init(Default::default).lock().unwrap()
+}
+
+/// Start an HTTP/1.1 upstream that answers with its port as the body, or with
+/// `503 Service Unavailable` while taken down with `take_upstream_down`.
+/// Useful for asserting which upstream served a request.
+pub fn start_switchable_upstream(port: u16) {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+    use std::time::Duration;
+
+    let addr = format!("127.0.0.1:{port}");
+
+    thread::spawn(move || {
+        let listener = TcpListener::bind(&addr).expect("failed to bind upstream");
+        for stream in listener.incoming() {
+            let mut stream = stream.expect("stream error");
+
+            let mut buf = [0u8; 1024];
+            let _ = stream.read(&mut buf);
+
+            let body = port.to_string();
+            let status = if down_upstreams().contains(&port) {
+                "503 Service Unavailable"
+            } else {
+                "200 OK"
+            };
+            let response = format!(
+                "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+
+    // tiny delay so the listener is actually ready
+    thread::sleep(Duration::from_millis(25));
+}
+
+/// Make the switchable upstream on `port` answer every request with `503`.
+pub fn take_upstream_down(port: u16) {
+    down_upstreams().insert(port);
+}
+
+/// Make the switchable upstream on `port` answer normally again.
+pub fn bring_upstream_up(port: u16) {
+    down_upstreams().remove(&port);
+}
+
 pub mod helloworld {
     tonic::include_proto!("helloworld");
 }
@@ -0,0 +1,136 @@
+use integration_tests::harness::{CapturedEvent, TestServer};
+use pretty_assertions::assert_eq;
+use reqwest::StatusCode;
+
+fn field(event: &CapturedEvent, name: &str) -> String {
+    event
+        .fields
+        .iter()
+        .find(|(k, _)| k == name)
+        .map(|(_, v)| v.clone())
+        .unwrap_or_else(|| panic!("event has no {name} field"))
+}
+
+/// The event's field names, sorted, without the message.
+fn field_names(event: &CapturedEvent) -> Vec<&str> {
+    let mut names = event
+        .fields
+        .iter()
+        .map(|(k, _)| k.as_str())
+        .filter(|k| *k != "message")
+        .collect::<Vec<_>>();
+    names.sort_unstable();
+    names
+}
+
+#[test]
+fn proxied_request_is_logged_once_with_its_upstream() {
+    // Arrange
+    let srv = TestServer::start_with_http_upstream("access_log");
+
+    // Act
+    let res = srv.get("/api?page=1").send().expect("request failed");
+
+    // Assert
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let events = srv.http_events("access");
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert_eq!(
+        field_names(event),
+        vec![
+            "bytes_in",
+            "bytes_out",
+            "client_ip",
+            "event",
+            "latency_ms",
+            "method",
+            "path",
+            "request_id",
+            "service",
+            "status",
+            "upstream",
+        ]
+    );
+    assert_eq!(field(event, "method"), "GET");
+    assert_eq!(field(event, "path"), "/api");
+    assert_eq!(field(event, "status"), "200");
+    assert_eq!(field(event, "client_ip"), "127.0.0.1");
+    assert!(field(event, "latency_ms").parse::<u64>().is_ok());
+
+    let upstreams = srv
+        .upstream_ports()
+        .iter()
+        .map(|port| format!("127.0.0.1:{port}"))
+        .collect::<Vec<_>>();
+    assert!(upstreams.contains(&field(event, "upstream")));
+}
+
+#[test]
+fn request_answered_by_a_device_names_the_device() {
+    // Arrange
+    let srv = TestServer::start_with_http_upstream("access_log");
+
+    // Act
+    let res = srv.delete("/api").send().expect("request failed");
+
+    // Assert
+    assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+    let events = srv.http_events("access");
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert_eq!(
+        field_names(event),
+        vec![
+            "bytes_in",
+            "bytes_out",
+            "client_ip",
+            "device",
+            "event",
+            "latency_ms",
+            "method",
+            "path",
+            "request_id",
+            "status",
+        ]
+    );
+    assert_eq!(field(event, "method"), "DELETE");
+    assert_eq!(field(event, "status"), "405");
+    assert_eq!(field(event, "device"), "Request Filter");
+}
+
+#[test]
+fn custom_format_logs_only_its_fields() {
+    // Arrange
+    let srv = TestServer::start_with_http_upstream("access_log_format");
+
+    // Act
+    let res = srv.get("/api?page=1").send().expect("request failed");
+
+    // Assert
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let events = srv.http_events("access");
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert_eq!(
+        field_names(event),
+        vec!["client_ip", "event", "method", "path", "status"]
+    );
+    assert_eq!(field(event, "message"), "127.0.0.1 \"GET /api\" 200 -");
+}
+
+#[test]
+fn access_log_is_off_by_default() {
+    // Arrange
+    let srv = TestServer::start_with_http_upstream("basic");
+
+    // Act
+    let res = srv.get("/api").send().expect("request failed");
+
+    // Assert
+    assert_eq!(res.status(), StatusCode::OK);
+    assert!(srv.http_events("access").is_empty());
+}
@@ -0,0 +1,45 @@
+use integration_tests::harness::TestServer;
+use integration_tests::harness::upstream::{bring_upstream_up, take_upstream_down};
+use pretty_assertions::assert_eq;
+use reqwest::StatusCode;
+use std::collections::HashSet;
+use std::thread;
+use std::time::Duration;
+
+/// Long enough for the fixture's probes, 100ms apart, to cross either threshold.
+const PROBE_SETTLE_TIME: Duration = Duration::from_secs(1);
+
+/// Ports of the upstreams that served a round of requests.
+fn served_by(srv: &TestServer) -> HashSet<u16> {
+    (0..10)
+        .map(|_| {
+            let res = srv.get("/api").send().expect("request failed");
+            assert_eq!(res.status(), StatusCode::OK);
+            res.text().unwrap().parse().unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn upstream_failing_health_checks_stops_receiving_traffic() {
+    let srv = TestServer::start_with_switchable_upstream("active_health_check");
+    let (healthy, failing) = (srv.upstream_ports()[0], srv.upstream_ports()[1]);
+
+    take_upstream_down(failing);
+    thread::sleep(PROBE_SETTLE_TIME);
+
+    assert_eq!(served_by(&srv), HashSet::from([healthy]));
+}
+
+#[test]
+fn upstream_passing_health_checks_again_receives_traffic() {
+    let srv = TestServer::start_with_switchable_upstream("active_health_check");
+    let (healthy, recovering) = (srv.upstream_ports()[0], srv.upstream_ports()[1]);
+
+    take_upstream_down(recovering);
+    thread::sleep(PROBE_SETTLE_TIME);
+    bring_upstream_up(recovering);
+    thread::sleep(PROBE_SETTLE_TIME);
+
+    assert_eq!(served_by(&srv), HashSet::from([healthy, recovering]));
+}
@@ -0,0 +1,59 @@
+use integration_tests::harness::TestServer;
+use pretty_assertions::assert_eq;
+use reqwest::StatusCode;
+
+#[test]
+fn preflight_is_answered_before_auth_is_required() {
+    let srv = TestServer::start_with_echo_upstream("cors");
+
+    let res = srv
+        .options("/api")
+        .header("origin", "https://app.example.com")
+        .header("access-control-request-method", "POST")
+        .header("access-control-request-headers", "authorization")
+        .send()
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+    assert_eq!(
+        res.headers()["access-control-allow-origin"],
+        "https://app.example.com"
+    );
+    assert_eq!(res.headers()["access-control-allow-methods"], "GET, POST");
+    assert_eq!(res.headers()["access-control-max-age"], "600");
+}
+
+#[test]
+fn allowed_origin_is_reflected_on_response() {
+    let srv = TestServer::start_with_echo_upstream("cors");
+
+    let res = srv
+        .get("/api")
+        .header("origin", "https://app.example.com")
+        .header("authorization", "Bearer token")
+        .send()
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        res.headers()["access-control-allow-origin"],
+        "https://app.example.com"
+    );
+    assert_eq!(res.headers()["access-control-allow-credentials"], "true");
+    assert_eq!(res.headers()["vary"], "Origin");
+}
+
+#[test]
+fn disallowed_origin_gets_no_cors_headers() {
+    let srv = TestServer::start_with_echo_upstream("cors");
+
+    let res = srv
+        .get("/api")
+        .header("origin", "https://evil.example")
+        .header("authorization", "Bearer token")
+        .send()
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+    assert!(res.headers().get("access-control-allow-origin").is_none());
+}
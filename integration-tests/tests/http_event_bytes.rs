@@ -0,0 +1,43 @@
+use integration_tests::harness::{CapturedEvent, TestServer};
+use pretty_assertions::assert_eq;
+use reqwest::StatusCode;
+
+fn field(event: &CapturedEvent, name: &str) -> String {
+    event
+        .fields
+        .iter()
+        .find(|(k, _)| k == name)
+        .map(|(_, v)| v.clone())
+        .unwrap_or_else(|| panic!("event has no {name} field"))
+}
+
+#[test]
+fn complete_event_records_request_and_response_body_bytes() {
+    // Arrange
+    let srv = TestServer::start_with_echo_upstream("basic");
+    let request_body = "x".repeat(1500);
+
+    // Act
+    let res = srv
+        .post("/api")
+        .body(request_body.clone())
+        .send()
+        .expect("request failed");
+    let status = res.status();
+    let response_body = res.bytes().expect("failed to read body");
+
+    // Assert
+    assert_eq!(status, StatusCode::OK);
+
+    let events = srv.http_events("complete");
+    assert_eq!(events.len(), 1);
+    let bytes_in = field(&events[0], "bytes_in");
+    let bytes_out = field(&events[0], "bytes_out");
+    let bytes_total = field(&events[0], "bytes_total");
+    assert_eq!(bytes_in, request_body.len().to_string());
+    assert_eq!(bytes_out, response_body.len().to_string());
+    assert_eq!(
+        bytes_total,
+        (request_body.len() + response_body.len()).to_string()
+    );
+}
@@ -0,0 +1,54 @@
+use integration_tests::harness::TestServer;
+use pretty_assertions::assert_eq;
+use reqwest::StatusCode;
+
+#[test]
+fn duplicate_key_is_replayed_without_reaching_upstream() {
+    let srv = TestServer::start_with_echo_upstream("idempotency");
+
+    let first = srv
+        .post("/api")
+        .header("idempotency-key", "payment-1")
+        .header("x-attempt", "first")
+        .send()
+        .unwrap();
+    assert_eq!(first.status(), StatusCode::OK);
+    assert!(first.headers().get("idempotent-replayed").is_none());
+    let first_body = first.text().unwrap();
+
+    let second = srv
+        .post("/api")
+        .header("idempotency-key", "payment-1")
+        .header("x-attempt", "second")
+        .send()
+        .unwrap();
+
+    // The echo upstream would have echoed the second attempt's header.
+    assert_eq!(second.status(), StatusCode::OK);
+    assert_eq!(second.headers()["idempotent-replayed"], "true");
+    let second_body = second.text().unwrap();
+    assert_eq!(second_body, first_body);
+    assert!(
+        second_body
+            .to_ascii_lowercase()
+            .contains("x-attempt: first")
+    );
+}
+
+#[test]
+fn missing_key_is_rejected() {
+    let srv = TestServer::start_with_echo_upstream("idempotency");
+
+    let res = srv.post("/api").send().unwrap();
+
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[test]
+fn reads_need_no_key() {
+    let srv = TestServer::start_with_echo_upstream("idempotency");
+
+    let res = srv.get("/api").send().unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+}
@@ -0,0 +1,87 @@
+use integration_tests::harness::{CapturedEvent, TestServer};
+use pretty_assertions::assert_eq;
+use reqwest::StatusCode;
+use reqwest::blocking::Body;
+use std::io::Cursor;
+
+/// Whether the request's access log entry names an upstream, i.e. it was proxied.
+fn reached_upstream(events: &[CapturedEvent]) -> bool {
+    events
+        .iter()
+        .any(|event| event.fields.iter().any(|(k, _)| k == "upstream"))
+}
+
+#[test]
+fn body_over_the_server_limit_is_refused_without_reaching_upstream() {
+    // Arrange
+    let srv = TestServer::start_with_echo_upstream("max_request_body");
+
+    // Act
+    let res = srv.post("/api").body(vec![0u8; 2048]).send().unwrap();
+
+    // Assert
+    assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+    let events = srv.http_events("access");
+    assert_eq!(events.len(), 1);
+    assert!(!reached_upstream(&events));
+}
+
+#[test]
+fn body_within_the_limit_is_proxied() {
+    // Arrange
+    let srv = TestServer::start_with_echo_upstream("max_request_body");
+
+    // Act
+    let res = srv.post("/api").body(vec![0u8; 512]).send().unwrap();
+
+    // Assert
+    assert_eq!(res.status(), StatusCode::OK);
+    assert!(reached_upstream(&srv.http_events("access")));
+}
+
+#[test]
+fn route_limit_overrides_the_server_limit() {
+    // Arrange
+    let srv = TestServer::start_with_echo_upstream("max_request_body");
+
+    // Act
+    let within = srv.post("/upload").body(vec![0u8; 2048]).send().unwrap();
+    let over = srv.post("/upload").body(vec![0u8; 8192]).send().unwrap();
+
+    // Assert
+    assert_eq!(within.status(), StatusCode::OK);
+    assert_eq!(over.status(), StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+/// A chunked body has no declared length, so it is streamed to the upstream as it arrives, and
+/// the request is cut off once the body passes the limit. The echo upstream only answers once a
+/// chunked body is complete, so a `413` means it never received the whole request.
+#[test]
+fn streamed_body_over_the_limit_is_cut_off_before_the_upstream_has_it_all() {
+    // Arrange
+    let srv = TestServer::start_with_echo_upstream("max_request_body");
+
+    // A reader body has no declared length, so it is sent chunked.
+    let body = Body::new(Cursor::new(vec![0u8; 2048]));
+
+    // Act
+    let res = srv.post("/api").body(body).send().unwrap();
+
+    // Assert
+    assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[test]
+fn streamed_body_within_the_limit_is_proxied() {
+    // Arrange
+    let srv = TestServer::start_with_echo_upstream("max_request_body");
+    let body = Body::new(Cursor::new(vec![0u8; 512]));
+
+    // Act
+    let res = srv.post("/api").body(body).send().unwrap();
+
+    // Assert
+    assert_eq!(res.status(), StatusCode::OK);
+    assert!(reached_upstream(&srv.http_events("access")));
+}
@@ -0,0 +1,52 @@
+use integration_tests::harness::TestServer;
+use integration_tests::harness::upstream::{bring_upstream_up, take_upstream_down};
+use pretty_assertions::assert_eq;
+use reqwest::StatusCode;
+use std::collections::HashSet;
+use std::thread;
+use std::time::Duration;
+
+/// Longer than the fixture's first ejection, 500ms.
+const EJECTION_TIME: Duration = Duration::from_millis(700);
+
+/// Ports of the upstreams that served a round of requests.
+fn served_by(srv: &TestServer) -> HashSet<u16> {
+    (0..10)
+        .map(|_| {
+            let res = srv.get("/api").send().expect("request failed");
+            assert_eq!(res.status(), StatusCode::OK);
+            res.text().unwrap().parse().unwrap()
+        })
+        .collect()
+}
+
+/// Send a burst of requests, enough for a failing upstream to answer 3 of them with a 503.
+fn error_burst(srv: &TestServer) {
+    for _ in 0..8 {
+        srv.get("/api").send().expect("request failed");
+    }
+}
+
+#[test]
+fn upstream_answering_a_burst_of_errors_is_ejected() {
+    let srv = TestServer::start_with_switchable_upstream("outlier_detection");
+    let (healthy, failing) = (srv.upstream_ports()[0], srv.upstream_ports()[1]);
+
+    take_upstream_down(failing);
+    error_burst(&srv);
+
+    assert_eq!(served_by(&srv), HashSet::from([healthy]));
+}
+
+#[test]
+fn ejected_upstream_receives_traffic_after_its_ejection_time() {
+    let srv = TestServer::start_with_switchable_upstream("outlier_detection");
+    let (healthy, recovering) = (srv.upstream_ports()[0], srv.upstream_ports()[1]);
+
+    take_upstream_down(recovering);
+    error_burst(&srv);
+    bring_upstream_up(recovering);
+    thread::sleep(EJECTION_TIME);
+
+    assert_eq!(served_by(&srv), HashSet::from([healthy, recovering]));
+}
@@ -0,0 +1,72 @@
+use integration_tests::harness::TestServer;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+/// Read from the connection until it closes, or nothing more arrives for a second.
+fn read_available(stream: &mut TcpStream) -> String {
+    stream
+        .set_read_timeout(Some(Duration::from_secs(1)))
+        .unwrap();
+
+    let mut received = Vec::new();
+    let mut buf = [0u8; 4096];
+    loop {
+        match stream.read(&mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => received.extend_from_slice(&buf[..n]),
+        }
+    }
+    String::from_utf8_lossy(&received).into_owned()
+}
+
+/// Send `requests` in a single write, before any response arrives, and read the answers.
+fn send_pipelined(srv: &TestServer, requests: &[u8]) -> String {
+    let addr = srv.base_url().strip_prefix("http://").unwrap();
+    let mut stream = TcpStream::connect(addr).expect("failed to connect");
+    stream.write_all(requests).unwrap();
+    read_available(&mut stream)
+}
+
+/// Assert both requests were answered, with 200, in the order they were sent.
+fn assert_answered_in_order(received: &str) {
+    // The echo upstream answers with the request head it received.
+    assert_eq!(
+        received.matches("HTTP/1.1 200").count(),
+        2,
+        "expected two responses: {received}"
+    );
+    let first = received
+        .find("/api/first ")
+        .expect("first request was not answered");
+    let second = received
+        .find("/api/second ")
+        .expect("second request was not answered");
+    assert!(second > first, "responses out of order: {received}");
+}
+
+#[test]
+fn pipelined_requests_are_answered_in_order() {
+    let srv = TestServer::start_with_echo_upstream("basic");
+
+    let received = send_pipelined(
+        &srv,
+        b"GET /api/first HTTP/1.1\r\nHost: localhost\r\n\r\n\
+          GET /api/second HTTP/1.1\r\nHost: localhost\r\n\r\n",
+    );
+
+    assert_answered_in_order(&received);
+}
+
+#[test]
+fn request_pipelined_behind_a_body_is_answered_in_order() {
+    let srv = TestServer::start_with_echo_upstream("basic");
+
+    let received = send_pipelined(
+        &srv,
+        b"POST /api/first HTTP/1.1\r\nHost: localhost\r\nContent-Length: 5\r\n\r\nhello\
+          GET /api/second HTTP/1.1\r\nHost: localhost\r\n\r\n",
+    );
+
+    assert_answered_in_order(&received);
+}
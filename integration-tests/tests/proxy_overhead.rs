@@ -0,0 +1,67 @@
+//! Proxy overhead benchmark: latency and throughput of requests sent straight to the upstream,
+//! versus through Snakeway, with and without devices.
+//!
+//! The full benchmark is ignored by default because timings are only meaningful in release
+//! builds, without trace logging:
+//!
+//! `RUST_LOG=error cargo test -p integration-tests --release --test proxy_overhead -- --ignored --nocapture`
+
+use integration_tests::harness::bench::{LoadOptions, OverheadReport, measure_overhead};
+use pretty_assertions::assert_eq;
+
+fn assert_measured(report: &OverheadReport, opts: &LoadOptions) {
+    for stats in [&report.direct, &report.proxied] {
+        assert_eq!(stats.latencies.len(), opts.requests);
+        assert!(stats.percentile(50.0) > std::time::Duration::ZERO);
+        assert!(stats.percentile(99.0) >= stats.percentile(50.0));
+        assert!(stats.throughput() > 0.0);
+    }
+}
+
+#[test]
+fn overhead_benchmark_reports_latency_and_throughput() {
+    // Arrange
+    let opts = LoadOptions {
+        requests: 40,
+        concurrency: 2,
+        warmup: 2,
+    };
+
+    // Act
+    let without_devices = measure_overhead("bench", "/api", &opts);
+    let with_devices = measure_overhead("bench_devices", "/api", &opts);
+
+    // Assert
+    println!("{without_devices}\n{with_devices}");
+    assert_measured(&without_devices, &opts);
+    assert_measured(&with_devices, &opts);
+}
+
+#[test]
+#[ignore]
+fn proxy_overhead_benchmark() {
+    // Arrange
+    let opts = LoadOptions {
+        requests: 20_000,
+        concurrency: 16,
+        warmup: 50,
+    };
+
+    // Act
+    let without_devices = measure_overhead("bench", "/api", &opts);
+    let with_devices = measure_overhead("bench_devices", "/api", &opts);
+
+    // Assert
+    println!("{without_devices}\n{with_devices}");
+    println!(
+        "device overhead p50={:?} p99={:?}",
+        with_devices
+            .overhead(50.0)
+            .saturating_sub(without_devices.overhead(50.0)),
+        with_devices
+            .overhead(99.0)
+            .saturating_sub(without_devices.overhead(99.0))
+    );
+    assert_measured(&without_devices, &opts);
+    assert_measured(&with_devices, &opts);
+}
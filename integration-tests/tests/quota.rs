@@ -0,0 +1,72 @@
+use integration_tests::harness::TestServer;
+use integration_tests::harness::upstream::OVER_QUOTA_API_KEY;
+use pretty_assertions::assert_eq;
+use reqwest::StatusCode;
+
+/// Keys within quota are proxied, with the quota service's headers injected.
+#[test]
+fn key_within_quota_is_allowed_with_rate_limit_headers() {
+    let srv = TestServer::start_with_http_upstream("quota");
+
+    let res = srv
+        .get("/api")
+        .header("x-api-key", "key-within-quota")
+        .send()
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()["x-ratelimit-limit"], "100");
+    assert!(res.headers().contains_key("x-ratelimit-remaining"));
+    assert_eq!(res.text().unwrap(), "hello world");
+}
+
+/// Keys over quota are short-circuited with 429 before reaching the upstream.
+#[test]
+fn key_over_quota_is_rejected_with_429() {
+    let srv = TestServer::start_with_http_upstream("quota");
+
+    let res = srv
+        .get("/api")
+        .header("x-api-key", OVER_QUOTA_API_KEY)
+        .send()
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(res.headers()["x-ratelimit-limit"], "100");
+    assert_eq!(res.headers()["x-ratelimit-remaining"], "0");
+}
+
+/// Requests without an API key are not subject to quotas.
+#[test]
+fn request_without_api_key_is_not_checked() {
+    let srv = TestServer::start_with_http_upstream("quota");
+
+    let res = srv.get("/api").send().unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+    assert!(!res.headers().contains_key("x-ratelimit-limit"));
+}
+
+/// Decisions are cached per key, so the quota service is not called again within the TTL.
+#[test]
+fn decision_is_cached_within_ttl() {
+    let srv = TestServer::start_with_http_upstream("quota");
+
+    let first = srv
+        .get("/api")
+        .header("x-api-key", "cached-key")
+        .send()
+        .unwrap();
+    let second = srv
+        .get("/api")
+        .header("x-api-key", "cached-key")
+        .send()
+        .unwrap();
+
+    assert_eq!(first.status(), StatusCode::OK);
+    assert_eq!(second.status(), StatusCode::OK);
+    assert_eq!(
+        first.headers()["x-ratelimit-remaining"],
+        second.headers()["x-ratelimit-remaining"]
+    );
+}
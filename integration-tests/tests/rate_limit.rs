@@ -0,0 +1,60 @@
+use integration_tests::harness::TestServer;
+use pretty_assertions::assert_eq;
+use reqwest::StatusCode;
+
+#[test]
+fn burst_over_limit_is_rejected_with_429() {
+    let srv = TestServer::start_with_echo_upstream("rate_limit");
+
+    let statuses: Vec<StatusCode> = (0..6)
+        .map(|_| {
+            srv.get("/api")
+                .header("X-API-Key", "burst")
+                .send()
+                .expect("request failed")
+                .status()
+        })
+        .collect();
+
+    // The whole burst is allowed, and the next request is over the limit.
+    assert_eq!(&statuses[..5], &[StatusCode::OK; 5]);
+    assert_eq!(statuses[5], StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[test]
+fn rejected_request_has_retry_after() {
+    let srv = TestServer::start_with_echo_upstream("rate_limit");
+
+    let res = (0..6)
+        .map(|_| {
+            srv.get("/api")
+                .header("X-API-Key", "retry")
+                .send()
+                .expect("request failed")
+        })
+        .last()
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(res.headers().get("retry-after").unwrap(), "1");
+}
+
+#[test]
+fn keys_are_limited_separately() {
+    let srv = TestServer::start_with_echo_upstream("rate_limit");
+
+    for _ in 0..6 {
+        srv.get("/api")
+            .header("X-API-Key", "noisy")
+            .send()
+            .expect("request failed");
+    }
+
+    let res = srv
+        .get("/api")
+        .header("X-API-Key", "quiet")
+        .send()
+        .expect("request failed");
+
+    assert_eq!(res.status(), StatusCode::OK);
+}
@@ -130,3 +130,29 @@ fn request_filter_uses_custom_deny_status() {
 
     assert_eq!(res.status(), StatusCode::NOT_ACCEPTABLE);
 }
+
+#[test]
+fn request_filter_denial_is_hidden_as_not_found() {
+    let srv = TestServer::start_with_http_upstream("request_filter_hide_as_404");
+
+    let res = srv
+        .get("/api")
+        .header("x-forwarded-host", "evil.example")
+        .send()
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[test]
+fn request_filter_denial_is_forbidden_on_route_that_opts_out_of_hide_as_404() {
+    let srv = TestServer::start_with_http_upstream("request_filter_hide_as_404");
+
+    let res = srv
+        .get("/public")
+        .header("x-forwarded-host", "evil.example")
+        .send()
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+}
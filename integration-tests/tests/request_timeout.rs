@@ -0,0 +1,73 @@
+use integration_tests::harness::TestServer;
+use integration_tests::harness::upstream::SLOW_UPSTREAM_DELAY;
+use pretty_assertions::assert_eq;
+use reqwest::StatusCode;
+use std::time::{Duration, Instant};
+
+/// The fixture's server-wide request timeout.
+const REQUEST_TIMEOUT: Duration = Duration::from_millis(300);
+
+#[test]
+fn slow_upstream_times_out_with_504_at_the_deadline() {
+    // Arrange
+    let srv = TestServer::start_with_slow_upstream("request_timeout");
+
+    // Act
+    let started = Instant::now();
+    let res = srv.get("/api").send().expect("request failed");
+    let elapsed = started.elapsed();
+
+    // Assert
+    assert_eq!(res.status(), StatusCode::GATEWAY_TIMEOUT);
+    assert!(
+        elapsed >= REQUEST_TIMEOUT && elapsed < SLOW_UPSTREAM_DELAY,
+        "expected a 504 at the {REQUEST_TIMEOUT:?} deadline, took {elapsed:?}"
+    );
+}
+
+#[test]
+fn route_timeout_lets_a_slow_upstream_respond_in_time() {
+    // Arrange
+    let srv = TestServer::start_with_slow_upstream("request_timeout");
+
+    // Act
+    let res = srv.get("/reports").send().expect("request failed");
+
+    // Assert
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.text().unwrap(), "slow");
+}
+
+#[test]
+fn fast_upstream_is_not_timed_out() {
+    // Arrange
+    let srv = TestServer::start_with_http_upstream("request_timeout");
+
+    // Act
+    let res = srv.get("/api").send().expect("request failed");
+
+    // Assert
+    assert_eq!(res.status(), StatusCode::OK);
+}
+
+#[test]
+fn response_body_past_the_deadline_is_cut_off() {
+    // Arrange
+    let srv = TestServer::start_with_stalling_upstream("request_timeout");
+
+    // Act
+    let started = Instant::now();
+    let res = srv.get("/api").send().expect("request failed");
+
+    // Assert
+    // Headers were already sent, so the client connection is closed mid-body.
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = res.bytes();
+    assert!(body.is_err(), "expected a truncated body, got {body:?}");
+
+    let elapsed = started.elapsed();
+    assert!(
+        elapsed < Duration::from_secs(1),
+        "response was not cut off at the deadline, took {elapsed:?}"
+    );
+}
@@ -0,0 +1,97 @@
+use integration_tests::harness::TestServer;
+use pretty_assertions::assert_eq;
+use reqwest::StatusCode;
+use reqwest::blocking::{Client, Response};
+use reqwest::header::LOCATION;
+use reqwest::redirect::Policy;
+
+/// Send a GET without following redirects, so the redirect itself can be checked.
+fn get_without_redirects(srv: &TestServer, path: &str) -> Response {
+    Client::builder()
+        .redirect(Policy::none())
+        .build()
+        .expect("failed to build client")
+        .get(format!("{}{}", srv.base_url(), path))
+        .send()
+        .expect("request failed")
+}
+
+#[test]
+fn plaintext_request_to_redirect_route_is_redirected_to_https() {
+    let srv = TestServer::start_with_http_upstream("require_tls");
+
+    let res = get_without_redirects(&srv, "/login/form?next=%2Fhome");
+
+    assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+    assert_eq!(
+        res.headers().get(LOCATION).unwrap(),
+        "https://127.0.0.1/login/form?next=%2Fhome"
+    );
+}
+
+#[test]
+fn plaintext_request_to_reject_route_is_forbidden() {
+    let srv = TestServer::start_with_http_upstream("require_tls");
+
+    let res = get_without_redirects(&srv, "/account");
+
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+}
+
+#[test]
+fn plaintext_request_to_reject_route_uses_reject_status() {
+    let srv = TestServer::start_with_http_upstream("require_tls");
+
+    let res = get_without_redirects(&srv, "/billing");
+
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[test]
+fn plaintext_request_to_route_without_require_tls_is_proxied() {
+    let srv = TestServer::start_with_http_upstream("require_tls");
+
+    let res = get_without_redirects(&srv, "/api");
+
+    assert_eq!(res.status(), StatusCode::OK);
+}
+
+#[test]
+fn redirect_uses_forwarded_host_from_trusted_proxy() {
+    let srv = TestServer::start_with_http_upstream("require_tls_trusted_proxy");
+
+    let res = Client::builder()
+        .redirect(Policy::none())
+        .build()
+        .expect("failed to build client")
+        .get(format!("{}/login", srv.base_url()))
+        .header("X-Forwarded-Host", "www.example.com")
+        .send()
+        .expect("request failed");
+
+    assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+    assert_eq!(
+        res.headers().get(LOCATION).unwrap(),
+        "https://www.example.com/login"
+    );
+}
+
+#[test]
+fn redirect_ignores_forwarded_host_from_untrusted_peer() {
+    let srv = TestServer::start_with_http_upstream("require_tls");
+
+    let res = Client::builder()
+        .redirect(Policy::none())
+        .build()
+        .expect("failed to build client")
+        .get(format!("{}/login", srv.base_url()))
+        .header("X-Forwarded-Host", "www.example.com")
+        .send()
+        .expect("request failed");
+
+    assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+    assert_eq!(
+        res.headers().get(LOCATION).unwrap(),
+        "https://127.0.0.1/login"
+    );
+}
@@ -0,0 +1,35 @@
+use integration_tests::harness::TestServer;
+use pretty_assertions::assert_eq;
+use reqwest::StatusCode;
+
+#[test]
+fn skipped_content_type_is_not_compressed() {
+    let srv = TestServer::start_with_echo_upstream("response_compression_skip_type");
+
+    let res = srv
+        .get("/api")
+        .header("accept-encoding", "gzip")
+        .send()
+        .expect("request failed");
+
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers().get("content-encoding"), None);
+    let body = res.text().unwrap().to_lowercase();
+    assert!(body.starts_with("get /api"));
+}
+
+#[test]
+fn response_below_min_size_is_not_compressed() {
+    let srv = TestServer::start_with_echo_upstream("response_compression_min_size");
+
+    let res = srv
+        .get("/api")
+        .header("accept-encoding", "gzip")
+        .send()
+        .expect("request failed");
+
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers().get("content-encoding"), None);
+    let body = res.text().unwrap().to_lowercase();
+    assert!(body.starts_with("get /api"));
+}
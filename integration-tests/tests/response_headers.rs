@@ -0,0 +1,32 @@
+use integration_tests::harness::TestServer;
+use pretty_assertions::assert_eq;
+use reqwest::StatusCode;
+
+#[test]
+fn request_id_is_echoed_in_response() {
+    let srv = TestServer::start_with_echo_upstream("response_headers");
+
+    let res = srv.get("/api").send().expect("request failed");
+
+    assert_eq!(res.status(), StatusCode::OK);
+    let request_id = res.headers().get("x-request-id").unwrap();
+    assert!(!request_id.is_empty());
+
+    // Response headers are never sent upstream.
+    let upstream_head = res.text().unwrap().to_lowercase();
+    assert!(!upstream_head.contains("x-request-id"));
+}
+
+#[test]
+fn served_by_and_route_are_added_to_response() {
+    let srv = TestServer::start_with_echo_upstream("response_headers");
+
+    let res = srv.get("/api").send().expect("request failed");
+
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        res.headers().get("x-served-by").unwrap(),
+        "127.0.0.1:8080-service"
+    );
+    assert_eq!(res.headers().get("x-route").unwrap(), "/api");
+}
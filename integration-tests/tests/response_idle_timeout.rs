@@ -0,0 +1,24 @@
+use integration_tests::harness::TestServer;
+use pretty_assertions::assert_eq;
+use reqwest::StatusCode;
+use std::time::{Duration, Instant};
+
+#[test]
+fn stalled_response_body_is_aborted() {
+    let srv = TestServer::start_with_stalling_upstream("response_idle_timeout");
+
+    let started = Instant::now();
+    let res = srv.get("/api").send().expect("request failed");
+    assert_eq!(res.status(), StatusCode::OK);
+
+    // Headers were already sent, so the client connection is closed mid-body.
+    let body = res.bytes();
+    assert!(body.is_err(), "expected a truncated body, got {body:?}");
+
+    // The fixture's idle timeout is 300ms, well under the client's own 2s timeout.
+    let elapsed = started.elapsed();
+    assert!(
+        elapsed < Duration::from_secs(1),
+        "response was not aborted by the idle timeout, took {elapsed:?}"
+    );
+}
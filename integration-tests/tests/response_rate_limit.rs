@@ -0,0 +1,46 @@
+use integration_tests::harness::TestServer;
+use pretty_assertions::assert_eq;
+use reqwest::StatusCode;
+use std::time::{Duration, Instant};
+
+/// The echo upstream returns the request head, so padding the request sizes the response.
+const PADDING_BYTES: usize = 8 * 1024;
+
+#[test]
+fn throttled_response_takes_roughly_the_expected_time() {
+    let srv = TestServer::start_with_echo_upstream("response_rate_limit");
+
+    let started = Instant::now();
+    let res = srv
+        .get("/downloads")
+        .header("x-padding", "a".repeat(PADDING_BYTES))
+        .send()
+        .expect("request failed");
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = res.bytes().expect("body failed");
+    let elapsed = started.elapsed();
+
+    // At 4 KiB/s after a 1 KiB burst, 8+ KiB takes at least 1.75 seconds.
+    assert!(body.len() > PADDING_BYTES);
+    let expected = Duration::from_secs_f64((body.len() - 1024) as f64 / 4096.0);
+    assert!(
+        elapsed >= expected.mul_f64(0.9) && elapsed < expected * 3,
+        "expected about {expected:?}, took {elapsed:?}"
+    );
+}
+
+#[test]
+fn unthrottled_route_is_not_paced() {
+    let srv = TestServer::start_with_echo_upstream("response_rate_limit");
+
+    let started = Instant::now();
+    let res = srv
+        .get("/api")
+        .header("x-padding", "a".repeat(PADDING_BYTES))
+        .send()
+        .expect("request failed");
+    assert_eq!(res.status(), StatusCode::OK);
+    res.bytes().expect("body failed");
+
+    assert!(started.elapsed() < Duration::from_secs(1));
+}
@@ -0,0 +1,45 @@
+use integration_tests::harness::TestServer;
+use integration_tests::harness::upstream::take_upstream_down;
+use pretty_assertions::assert_eq;
+use reqwest::StatusCode;
+
+#[test]
+fn get_answered_with_503_is_retried_on_another_upstream() {
+    let srv = TestServer::start_with_switchable_upstream("retry_policy");
+    let (healthy, failing) = (srv.upstream_ports()[0], srv.upstream_ports()[1]);
+
+    take_upstream_down(failing);
+
+    for _ in 0..6 {
+        let res = srv.get("/api").send().expect("request failed");
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.text().unwrap(), healthy.to_string());
+    }
+}
+
+#[test]
+fn post_answered_with_503_is_not_retried_by_default() {
+    let srv = TestServer::start_with_switchable_upstream("retry_policy");
+    let failing = srv.upstream_ports()[1];
+
+    take_upstream_down(failing);
+
+    // Round robin sends every other request to the failing upstream.
+    let statuses: Vec<StatusCode> = (0..4)
+        .map(|_| srv.post("/api").send().expect("request failed").status())
+        .collect();
+
+    assert!(statuses.contains(&StatusCode::SERVICE_UNAVAILABLE));
+}
+
+#[test]
+fn request_out_of_retries_gets_the_last_upstream_response() {
+    let srv = TestServer::start_with_switchable_upstream("retry_policy");
+    for port in srv.upstream_ports() {
+        take_upstream_down(*port);
+    }
+
+    let res = srv.get("/api").send().expect("request failed");
+
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+}
@@ -0,0 +1,67 @@
+use integration_tests::harness::TestServer;
+use pretty_assertions::assert_eq;
+use reqwest::StatusCode;
+use reqwest::blocking::Client;
+#[cfg(target_os = "linux")]
+use std::collections::HashSet;
+use std::thread;
+
+/// Inodes of the listening TCP sockets bound to a port. Each socket has an inode of its own,
+/// so one socket shared by several listeners shows up once.
+#[cfg(target_os = "linux")]
+fn listening_socket_inodes(port: u16) -> HashSet<String> {
+    let table = std::fs::read_to_string("/proc/net/tcp").expect("failed to read /proc/net/tcp");
+    let port = format!(":{port:04X}");
+
+    // Columns: sl, local_address, rem_address, st, ..., inode - st 0A is LISTEN.
+    table
+        .lines()
+        .skip(1)
+        .map(|line| line.split_whitespace().collect::<Vec<_>>())
+        .filter(|cols| cols[1].ends_with(&port) && cols[3] == "0A")
+        .map(|cols| cols[9].to_string())
+        .collect()
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn reuse_port_binds_a_socket_per_worker_thread() {
+    let srv = TestServer::start_with_http_upstream("reuse_port");
+    let port: u16 = srv
+        .base_url()
+        .rsplit(':')
+        .next()
+        .and_then(|p| p.parse().ok())
+        .expect("base url has no port");
+
+    // The fixture runs 4 worker threads, so the kernel has 4 sockets to spread accepts across.
+    assert_eq!(listening_socket_inodes(port).len(), 4);
+}
+
+#[test]
+fn reuse_port_serves_concurrent_connections() {
+    let srv = TestServer::start_with_http_upstream("reuse_port");
+    let url = format!("{}/api", srv.base_url());
+
+    // Every request opens a new connection, so each one goes through an accept.
+    let workers = (0..8)
+        .map(|_| {
+            let url = url.clone();
+            thread::spawn(move || {
+                let client = Client::builder()
+                    .pool_max_idle_per_host(0)
+                    .build()
+                    .expect("failed to build client");
+
+                (0..25)
+                    .map(|_| client.get(&url).send().expect("request failed").status())
+                    .filter(|status| *status == StatusCode::OK)
+                    .count()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let served: usize = workers.into_iter().map(|w| w.join().unwrap()).sum();
+
+    assert_eq!(served, 200);
+}
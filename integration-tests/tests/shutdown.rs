@@ -0,0 +1,50 @@
+use integration_tests::harness::TestServer;
+use integration_tests::harness::shutdown::{send_sigterm, wait_for_refused_connection};
+use pretty_assertions::assert_eq;
+use reqwest::StatusCode;
+use std::thread;
+use std::time::Duration;
+
+/// SIGTERM flips readiness first, then drains an in-flight request before completing,
+/// while new connections are refused.
+#[test]
+fn sigterm_drains_in_flight_request_after_readiness_flips() {
+    let srv = TestServer::start_with_slow_upstream("shutdown");
+    assert!(srv.is_ready());
+
+    // Start a request that stays in flight on the slow upstream.
+    let in_flight = thread::spawn({
+        let req = srv.get("/api").timeout(Duration::from_secs(5));
+        move || req.send()
+    });
+    thread::sleep(Duration::from_millis(200));
+
+    send_sigterm();
+
+    // Readiness flips right away, while the request is still in flight.
+    thread::sleep(Duration::from_millis(100));
+    assert!(!srv.is_ready());
+    assert!(!in_flight.is_finished());
+
+    // New connections are refused while the request drains.
+    wait_for_refused_connection(srv.base_url(), Duration::from_secs(1));
+    assert!(!in_flight.is_finished());
+
+    // The in-flight request is drained, not dropped.
+    let res = in_flight.join().unwrap().expect("in-flight request failed");
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.text().unwrap(), "slow");
+
+    srv.wait_for_shutdown(Duration::from_secs(5));
+
+    assert_eq!(
+        srv.shutdown_phases(),
+        vec![
+            "not_ready",
+            "stop_accepting",
+            "draining",
+            "device_shutdown",
+            "flush_logs",
+        ]
+    );
+}
@@ -0,0 +1,44 @@
+use integration_tests::harness::TestServer;
+use integration_tests::harness::shutdown::{send_sigterm, wait_for_refused_connection};
+use pretty_assertions::assert_eq;
+use reqwest::StatusCode;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// After SIGTERM, the server reports draining and keeps serving new requests for the
+/// readiness grace period, then stops accepting.
+#[test]
+fn sigterm_keeps_serving_while_draining_for_the_readiness_grace() {
+    let srv = TestServer::start_with_http_upstream("shutdown_readiness_grace");
+    assert!(!srv.is_draining());
+
+    send_sigterm();
+    let sent = Instant::now();
+
+    // Readiness flips right away, and the server reports draining.
+    thread::sleep(Duration::from_millis(100));
+    assert!(!srv.is_ready());
+    assert!(srv.is_draining());
+
+    // New requests are still answered during the grace period.
+    let res = srv.get("/api").send().expect("request during grace failed");
+    assert_eq!(res.status(), StatusCode::OK);
+    assert!(srv.is_draining());
+
+    // Once the grace period has passed, new connections are refused.
+    wait_for_refused_connection(srv.base_url(), Duration::from_secs(2));
+    assert!(sent.elapsed() >= Duration::from_secs(1));
+
+    srv.wait_for_shutdown(Duration::from_secs(5));
+
+    assert_eq!(
+        srv.shutdown_phases(),
+        vec![
+            "not_ready",
+            "stop_accepting",
+            "draining",
+            "device_shutdown",
+            "flush_logs",
+        ]
+    );
+}
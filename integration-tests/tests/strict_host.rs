@@ -0,0 +1,39 @@
+use integration_tests::harness::TestServer;
+use pretty_assertions::assert_eq;
+use reqwest::StatusCode;
+
+#[test]
+fn declared_host_is_proxied() {
+    let srv = TestServer::start_with_http_upstream("strict_host");
+
+    let res = srv
+        .get("/api")
+        .header("host", "api.example.com")
+        .send()
+        .expect("request failed");
+
+    assert_eq!(res.status(), StatusCode::OK);
+}
+
+#[test]
+fn declared_host_with_port_is_proxied() {
+    let srv = TestServer::start_with_http_upstream("strict_host");
+
+    // The client sends `Host: 127.0.0.1:<port>`.
+    let res = srv.get("/api").send().expect("request failed");
+
+    assert_eq!(res.status(), StatusCode::OK);
+}
+
+#[test]
+fn unknown_host_is_rejected() {
+    let srv = TestServer::start_with_http_upstream("strict_host");
+
+    let res = srv
+        .get("/api")
+        .header("host", "internal.example.com")
+        .send()
+        .expect("request failed");
+
+    assert_eq!(res.status(), StatusCode::MISDIRECTED_REQUEST);
+}
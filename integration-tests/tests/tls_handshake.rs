@@ -0,0 +1,31 @@
+use integration_tests::harness::TestServer;
+use pretty_assertions::assert_eq;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+#[test]
+fn plain_http_on_tls_listener_logs_handshake_failure() {
+    let srv = TestServer::start_with_http_upstream("tls_handshake");
+
+    // Speak plain HTTP to the TLS listener, so the handshake fails.
+    let addr = srv.base_url().strip_prefix("http://").unwrap();
+    let mut stream = TcpStream::connect(addr).expect("failed to connect");
+    stream
+        .set_read_timeout(Some(Duration::from_secs(2)))
+        .unwrap();
+    stream
+        .write_all(b"GET /api HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .unwrap();
+    let _ = stream.read(&mut [0u8; 1024]);
+
+    let events = srv.http_events("tls_handshake_failed");
+
+    assert!(!events.is_empty(), "no tls_handshake_failed event captured");
+    let reason = events[0]
+        .fields
+        .iter()
+        .find(|(k, _)| k == "reason")
+        .map(|(_, v)| v.as_str());
+    assert_eq!(reason, Some("not_tls"));
+}
@@ -0,0 +1,23 @@
+use integration_tests::harness::TestServer;
+use integration_tests::harness::upstream::UNIX_UPSTREAM_BODY;
+use pretty_assertions::assert_eq;
+use reqwest::StatusCode;
+use std::collections::HashSet;
+
+#[test]
+fn requests_are_balanced_across_tcp_and_unix_upstreams() {
+    let srv = TestServer::start_with_http_upstream("unix_upstream");
+
+    let bodies = (0..4)
+        .map(|_| {
+            let res = srv.get("/api").send().expect("request failed");
+            assert_eq!(res.status(), StatusCode::OK);
+            res.text().expect("failed to read body")
+        })
+        .collect::<HashSet<_>>();
+
+    assert_eq!(
+        bodies,
+        HashSet::from(["hello world".to_string(), UNIX_UPSTREAM_BODY.to_string()])
+    );
+}
@@ -0,0 +1,33 @@
+use flate2::read::GzDecoder;
+use integration_tests::harness::TestServer;
+use pretty_assertions::assert_eq;
+use reqwest::StatusCode;
+use std::io::Read;
+
+#[test]
+fn upstream_receives_identity_and_client_gets_compressed_response() {
+    let srv = TestServer::start_with_echo_upstream("upstream_accept_encoding");
+
+    let res = srv
+        .get("/api")
+        .header("accept-encoding", "gzip")
+        .send()
+        .expect("request failed");
+
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        res.headers()
+            .get("content-encoding")
+            .and_then(|v| v.to_str().ok()),
+        Some("gzip")
+    );
+
+    // The echo upstream returns the request head it received.
+    let mut upstream_head = String::new();
+    GzDecoder::new(res.bytes().unwrap().as_ref())
+        .read_to_string(&mut upstream_head)
+        .expect("response is not valid gzip");
+    let upstream_head = upstream_head.to_lowercase();
+    assert!(upstream_head.contains("accept-encoding: identity\r\n"));
+    assert!(!upstream_head.contains("gzip"));
+}
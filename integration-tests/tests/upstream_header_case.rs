@@ -0,0 +1,35 @@
+use integration_tests::harness::TestServer;
+use pretty_assertions::assert_eq;
+use reqwest::StatusCode;
+
+#[test]
+fn configured_headers_reach_h1_upstream_with_requested_casing() {
+    let srv = TestServer::start_with_echo_upstream("upstream_header_case");
+
+    let res = srv
+        .get("/api")
+        .header("www-authenticate", "Basic realm=\"legacy\"")
+        .header("x-legacy-token", "abc123")
+        .send()
+        .expect("request failed");
+
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let upstream_head = res.text().unwrap();
+    assert!(upstream_head.contains("WWW-Authenticate: Basic realm=\"legacy\"\r\n"));
+    assert!(upstream_head.contains("X-LEGACY-Token: abc123\r\n"));
+}
+
+#[test]
+fn unconfigured_headers_keep_client_casing() {
+    let srv = TestServer::start_with_echo_upstream("upstream_header_case");
+
+    let res = srv
+        .get("/api")
+        .header("x-request-tag", "blue")
+        .send()
+        .expect("request failed");
+
+    assert_eq!(res.status(), StatusCode::OK);
+    assert!(res.text().unwrap().contains("x-request-tag: blue\r\n"));
+}
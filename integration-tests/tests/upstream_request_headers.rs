@@ -0,0 +1,34 @@
+use integration_tests::harness::TestServer;
+use pretty_assertions::assert_eq;
+use reqwest::StatusCode;
+
+#[test]
+fn upstream_receives_injected_headers() {
+    let srv = TestServer::start_with_echo_upstream("upstream_request_headers");
+
+    let res = srv.get("/api").send().expect("request failed");
+
+    assert_eq!(res.status(), StatusCode::OK);
+    assert!(res.headers().get("x-api-key").is_none());
+
+    // The echo upstream returns the request head it received.
+    let upstream_head = res.text().unwrap().to_lowercase();
+    assert!(upstream_head.contains("x-api-key: test-backend-key\r\n"));
+    assert!(upstream_head.contains("x-static: static-value\r\n"));
+}
+
+#[test]
+fn injected_header_replaces_client_value() {
+    let srv = TestServer::start_with_echo_upstream("upstream_request_headers");
+
+    let res = srv
+        .get("/api")
+        .header("x-api-key", "client-supplied")
+        .send()
+        .expect("request failed");
+
+    assert_eq!(res.status(), StatusCode::OK);
+    let upstream_head = res.text().unwrap().to_lowercase();
+    assert!(upstream_head.contains("x-api-key: test-backend-key\r\n"));
+    assert!(!upstream_head.contains("client-supplied"));
+}
@@ -1,15 +1,15 @@
 use futures_util::{SinkExt, StreamExt};
 use integration_tests::harness::TestServer;
 use pretty_assertions::assert_eq;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::StatusCode;
+use tokio_tungstenite::tungstenite::{Error, Message};
 
 #[test]
 fn websocket_echo_is_proxied() {
     let srv = TestServer::start_with_ws_upstream("basic");
 
-    let url = format!(
-        "ws://{}/ws",
-        srv.base_url().strip_prefix("http://").unwrap()
-    );
+    let url = ws_url(&srv);
 
     let rt = tokio::runtime::Runtime::new().unwrap();
     rt.block_on(async {
@@ -17,12 +17,106 @@ fn websocket_echo_is_proxied() {
             .await
             .expect("ws connect failed");
 
-        socket
-            .send(tokio_tungstenite::tungstenite::Message::Text("ping".into()))
+        socket.send(Message::Text("ping".into())).await.unwrap();
+
+        let msg = socket.next().await.unwrap().unwrap();
+        assert_eq!(msg.into_text().unwrap(), "ping");
+    });
+}
+
+fn ws_url(srv: &TestServer) -> String {
+    format!(
+        "ws://{}/ws",
+        srv.base_url().strip_prefix("http://").unwrap()
+    )
+}
+
+/// Status of an upgrade the gateway refused, or `101` if it went through.
+async fn upgrade_status(request: impl IntoClientRequest + Unpin) -> StatusCode {
+    match tokio_tungstenite::connect_async(request).await {
+        Ok((_, res)) => res.status(),
+        Err(Error::Http(res)) => res.status(),
+        Err(e) => panic!("ws connect failed: {e}"),
+    }
+}
+
+#[test]
+fn websocket_upgrade_without_auth_is_rejected_by_device() {
+    let srv = TestServer::start_with_ws_upstream("websocket_auth");
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let status = rt.block_on(upgrade_status(ws_url(&srv)));
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[test]
+fn websocket_upgrade_with_auth_is_proxied() {
+    let srv = TestServer::start_with_ws_upstream("websocket_auth");
+    let mut request = ws_url(&srv).into_client_request().unwrap();
+    request
+        .headers_mut()
+        .insert("authorization", "Bearer token".parse().unwrap());
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let (mut socket, _) = tokio_tungstenite::connect_async(request)
             .await
-            .unwrap();
+            .expect("ws connect failed");
+
+        socket.send(Message::Text("ping".into())).await.unwrap();
 
         let msg = socket.next().await.unwrap().unwrap();
         assert_eq!(msg.into_text().unwrap(), "ping");
     });
 }
+
+#[test]
+fn websocket_upgrade_with_oversized_headers_is_rejected() {
+    let srv = TestServer::start_with_ws_upstream("websocket_limit");
+    let mut request = ws_url(&srv).into_client_request().unwrap();
+    request
+        .headers_mut()
+        .insert("x-padding", "a".repeat(8192).parse().unwrap());
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let status = rt.block_on(upgrade_status(request));
+
+    assert_eq!(status, StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE);
+}
+
+#[test]
+fn websocket_upgrade_over_connection_limit_is_unavailable() {
+    let srv = TestServer::start_with_ws_upstream("websocket_limit");
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let (_held, _) = tokio_tungstenite::connect_async(ws_url(&srv))
+            .await
+            .expect("ws connect failed");
+
+        let status = upgrade_status(ws_url(&srv)).await;
+
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    });
+}
+
+#[test]
+fn websocket_upgrade_over_global_limit_keeps_open_connections() {
+    let srv = TestServer::start_with_ws_upstream("websocket_global_limit");
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let (mut held, _) = tokio_tungstenite::connect_async(ws_url(&srv))
+            .await
+            .expect("ws connect failed");
+
+        let status = upgrade_status(ws_url(&srv)).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+
+        // The connection holding the only slot is unaffected.
+        held.send(Message::Text("ping".into())).await.unwrap();
+        let msg = held.next().await.unwrap().unwrap();
+        assert_eq!(msg.into_text().unwrap(), "ping");
+    });
+}
@@ -47,11 +47,11 @@ services = [
     upstreams = [
       {
         weight = 1
-        endpoint = { interface = "127.0.0.1", port = 3443 }
+        endpoint = { host = "127.0.0.1", port = 3443 }
       },
       {
         weight = 1
-        endpoint = { interface = "127.0.0.1", port = 3444 }
+        endpoint = { host = "127.0.0.1", port = 3444 }
       },
       {
         weight = 1
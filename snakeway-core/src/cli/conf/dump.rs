@@ -1,4 +1,6 @@
-use crate::conf::{load_config, load_spec_config};
+use crate::conf::types::DeviceConfig;
+use crate::conf::{RuntimeConfig, load_config, load_spec_config};
+use crate::route::Router;
 use serde::Serialize;
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -18,16 +20,81 @@ pub fn dump(
         }
     } else if matches!(repr, RepresentationFormat::Runtime) {
         let cfg = load_config(&path)?;
+        let dump = RuntimeDump::new(&cfg.config);
         if yaml {
-            dump_yaml(&cfg.config)?;
+            dump_yaml(&dump)?;
         } else if json || !yaml {
-            dump_json(&cfg.config)?;
+            dump_json(&dump)?;
         }
     }
 
     Ok(())
 }
 
+/// Runtime representation, plus the device pipeline each route runs.
+#[derive(Debug, Serialize)]
+pub struct RuntimeDump<'a> {
+    #[serde(flatten)]
+    pub config: &'a RuntimeConfig,
+
+    pub pipelines: Vec<RoutePipeline<'a>>,
+}
+
+/// Devices that run for requests to a route, in execution order, with their resolved config.
+#[derive(Debug, Serialize)]
+pub struct RoutePipeline<'a> {
+    pub listener: &'a str,
+    pub route: &'a str,
+    pub devices: Vec<&'a DeviceConfig>,
+}
+
+impl<'a> RuntimeDump<'a> {
+    pub fn new(config: &'a RuntimeConfig) -> Self {
+        let pipelines = config
+            .routes
+            .iter()
+            .filter_map(|route| {
+                let listener = config
+                    .listeners
+                    .iter()
+                    .find(|l| l.name == route.listener())?;
+
+                Some(RoutePipeline {
+                    listener: &listener.name,
+                    route: route.path(),
+                    devices: config
+                        .device_pipeline(listener)
+                        .into_iter()
+                        .filter(|d| may_run_on_route(d.routes(), route.path()))
+                        .collect(),
+                })
+            })
+            .collect();
+
+        Self { config, pipelines }
+    }
+}
+
+/// Whether a device scoped to `scope` runs for some requests to `route`: those under the route
+/// are under the scope, or the scope is under the route.
+fn may_run_on_route(scope: &[String], route: &str) -> bool {
+    if scope.is_empty() {
+        return true;
+    }
+
+    let mut scope_router = Router::new();
+    for path in scope {
+        let _ = scope_router.add_route(path, ());
+    }
+    let mut route_router = Router::new();
+    let _ = route_router.add_route(route, ());
+
+    scope_router.match_route(route).is_ok()
+        || scope
+            .iter()
+            .any(|path| route_router.match_route(path).is_ok())
+}
+
 fn dump_json<T: Serialize>(value: &T) -> anyhow::Result<()> {
     let s = serde_json::to_string_pretty(value)?;
     println!("{s}");
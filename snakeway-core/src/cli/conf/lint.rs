@@ -0,0 +1,58 @@
+use crate::cli::conf::ConfigCheckOutputFormat;
+use crate::conf::load_spec_config;
+use crate::conf::validation::{ValidationReport, lint_spec, validate_spec};
+use std::path::PathBuf;
+
+pub fn lint(path: PathBuf, quiet: bool, format: ConfigCheckOutputFormat) -> anyhow::Result<()> {
+    let (server, devices, ingresses) = match load_spec_config(&path) {
+        Ok(spec) => spec,
+        Err(err) => {
+            if !quiet {
+                eprintln!("{}", err);
+            }
+            std::process::exit(1);
+        }
+    };
+
+    // Lints assume a valid config, so validation problems are reported first.
+    let validation_report = validate_spec(&server, &ingresses, &devices);
+    if validation_report.has_violations() {
+        if !quiet {
+            render(
+                &validation_report,
+                &format,
+                "configuration validation failed",
+            );
+        }
+        std::process::exit(1);
+    }
+
+    let lint_report = lint_spec(&ingresses, &devices);
+    if lint_report.has_violations() {
+        if !quiet {
+            render(&lint_report, &format, "configuration lint found issues");
+        }
+        std::process::exit(1);
+    }
+
+    if quiet {
+        // Print nothing.
+    } else if matches!(format, ConfigCheckOutputFormat::Json) {
+        let success_info = serde_json::json!({ "status": "success" });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&success_info).expect("could not format JSON")
+        );
+    } else {
+        println!("✔ No lint findings");
+    }
+    Ok(())
+}
+
+fn render(report: &ValidationReport, format: &ConfigCheckOutputFormat, summary: &str) {
+    match format {
+        ConfigCheckOutputFormat::Pretty => report.render_pretty_as(summary),
+        ConfigCheckOutputFormat::Plain => report.render_plain(),
+        ConfigCheckOutputFormat::Json => report.render_json(),
+    }
+}
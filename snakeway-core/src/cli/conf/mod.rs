@@ -1,11 +1,21 @@
 mod check;
 mod dump;
 mod init;
+mod lint;
+mod route;
+mod schema;
 
+#[cfg(test)]
+mod tests;
+
+use crate::conf::ConfigFileKind;
 pub use check::*;
 use clap::Subcommand;
 pub use dump::*;
 pub use init::*;
+pub use lint::*;
+pub use route::*;
+pub use schema::*;
 use std::path::PathBuf;
 
 #[derive(Subcommand, Debug)]
@@ -25,6 +35,21 @@ pub enum ConfigCmd {
         format: ConfigCheckOutputFormat,
     },
 
+    /// Validate configuration, then report best-practice warnings
+    Lint {
+        /// Path to config directory
+        #[arg(default_value = "config")]
+        path: PathBuf,
+
+        /// Suppresses all diagnostic
+        #[arg(short, long)]
+        quiet: bool,
+
+        /// Emit machine readable diagnostics
+        #[arg(short, long, default_value = "pretty", conflicts_with = "quiet")]
+        format: ConfigCheckOutputFormat,
+    },
+
     /// Print resolved configuration
     Dump {
         #[arg(default_value = "config")]
@@ -42,6 +67,19 @@ pub enum ConfigCmd {
         yaml: bool,
     },
 
+    /// List routes or resolve a sample request against them
+    Route {
+        #[command(subcommand)]
+        cmd: RouteCmd,
+    },
+
+    /// Print the JSON Schema of a config file
+    Schema {
+        /// Config file to describe: entrypoint, ingress, or devices
+        #[arg(short, long, default_value = "ingress")]
+        file: ConfigFileKind,
+    },
+
     /// Initialize a new config directory
     Init {
         /// Path to config directory
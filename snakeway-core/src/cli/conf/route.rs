@@ -0,0 +1,363 @@
+use crate::conf::types::LoadBalancingStrategy;
+use crate::conf::{RuntimeConfig, load_config};
+use crate::ctx::RequestCtx;
+use crate::device::core::pipeline::DevicePipeline;
+use crate::device::core::result::DeviceResult;
+use crate::route::RouteRuntime;
+use crate::runtime::{RuntimeState, build_runtime_state};
+use anyhow::{anyhow, bail};
+use clap::Subcommand;
+use http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode, Uri, Version, header};
+use serde::Serialize;
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::PathBuf;
+
+#[derive(Subcommand, Debug)]
+pub enum RouteCmd {
+    /// List routes per listener, in match order
+    List {
+        /// Path to config directory
+        #[arg(default_value = "config")]
+        path: PathBuf,
+    },
+
+    /// Resolve a sample request against the configuration, offline
+    Test {
+        /// Path to config directory
+        #[arg(default_value = "config")]
+        path: PathBuf,
+
+        /// Request path, optionally with a query string
+        #[arg(long)]
+        request_path: String,
+
+        /// Request method
+        #[arg(long, default_value = "GET")]
+        method: Method,
+
+        /// Request host, sent as the Host header
+        #[arg(long)]
+        host: Option<String>,
+
+        /// Request header as "name: value"; may be repeated
+        #[arg(short = 'H', long = "header", value_parser = parse_header)]
+        headers: Vec<(HeaderName, HeaderValue)>,
+
+        /// Listener name or bind address; required when more than one listener has routes
+        #[arg(long)]
+        listener: Option<String>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// A synthetic request resolved by `route test`.
+#[derive(Debug, Clone)]
+pub struct RouteTestRequest {
+    pub listener: Option<String>,
+    pub method: Method,
+    pub path: String,
+    pub host: Option<String>,
+    pub headers: HeaderMap,
+}
+
+/// What the proxy would do with a `RouteTestRequest`.
+#[derive(Debug, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum RouteTestOutcome {
+    /// The request reaches a route.
+    Matched(RouteMatch),
+
+    /// The proxy answers the request itself.
+    Rejected {
+        listener: String,
+        status: u16,
+        reason: String,
+    },
+}
+
+#[derive(Debug, Serialize)]
+pub struct RouteMatch {
+    pub listener: String,
+    pub route: String,
+    pub service: Option<String>,
+    pub file_dir: Option<PathBuf>,
+    pub websocket: bool,
+    pub strategy: Option<LoadBalancingStrategy>,
+    pub upstreams: Vec<UpstreamCandidate>,
+    pub devices: Vec<String>,
+}
+
+/// An upstream the traffic director may pick. Which one is picked at runtime also depends on
+/// health and circuit breaker state, so every candidate is listed.
+#[derive(Debug, Serialize)]
+pub struct UpstreamCandidate {
+    pub authority: String,
+    pub weight: u32,
+}
+
+pub fn route(cmd: RouteCmd) -> anyhow::Result<()> {
+    match cmd {
+        RouteCmd::List { path } => {
+            let (_, state) = load_runtime(path)?;
+            let mut listeners = state.routers.keys().collect::<Vec<_>>();
+            listeners.sort();
+
+            for listener in listeners {
+                println!("{listener}");
+                for route in state.routers[listener].routes() {
+                    println!("  {:<24} {}", route.path, route.kind.id().as_str());
+                }
+            }
+            Ok(())
+        }
+
+        RouteCmd::Test {
+            path,
+            request_path,
+            method,
+            host,
+            headers,
+            listener,
+            json,
+        } => {
+            let (cfg, state) = load_runtime(path)?;
+            let request = RouteTestRequest {
+                listener,
+                method,
+                path: request_path,
+                host,
+                headers: headers.into_iter().collect(),
+            };
+
+            let outcome = resolve_route(&cfg, &state, &request)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&outcome)?);
+            } else {
+                print_outcome(&outcome);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Resolve a request the way the public gateway does: normalization, strict host checking,
+/// TLS requirements, `on_request` devices, then route matching and websocket admission.
+///
+/// Async `on_request` devices (e.g. quota) may call out to other services, so they are listed
+/// but not run.
+pub fn resolve_route(
+    cfg: &RuntimeConfig,
+    state: &RuntimeState,
+    request: &RouteTestRequest,
+) -> anyhow::Result<RouteTestOutcome> {
+    let listener = select_listener(cfg, state, request.listener.as_deref())?;
+    let rejected = |status: StatusCode, reason: String| -> anyhow::Result<RouteTestOutcome> {
+        Ok(RouteTestOutcome::Rejected {
+            listener: listener.clone(),
+            status: status.as_u16(),
+            reason,
+        })
+    };
+
+    let mut headers = request.headers.clone();
+    if let Some(host) = &request.host {
+        headers.insert(header::HOST, HeaderValue::from_str(host)?);
+    }
+    let uri: Uri = request.path.parse()?;
+
+    // Pingora treats any HTTP/1.1 request with an Upgrade header as an upgrade request.
+    let is_upgrade_req = headers.contains_key(header::UPGRADE);
+
+    let mut ctx = RequestCtx::empty();
+    if let Err(e) = ctx.hydrate(
+        &uri,
+        &request.method,
+        &headers,
+        &Version::HTTP_11,
+        is_upgrade_req,
+        IpAddr::V4(Ipv4Addr::LOCALHOST),
+    ) {
+        // Normalization errors are not classified, so Pingora answers them with 500.
+        return rejected(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("request rejected during normalization: {e}"),
+        );
+    }
+
+    if !state.is_host_allowed(&listener, ctx.host()) {
+        return rejected(
+            StatusCode::MISDIRECTED_REQUEST,
+            "request host is not declared on this listener".to_string(),
+        );
+    }
+
+    if let Some(require_tls) = state.tls_required(&listener, ctx.canonical_path()) {
+        return rejected(
+            require_tls.status(),
+            "route requires TLS and the listener is plaintext HTTP".to_string(),
+        );
+    }
+
+    let devices = state.devices.for_request(&listener, ctx.canonical_path());
+    match DevicePipeline::run_on_request(&devices, &mut ctx) {
+        DeviceResult::Continue => {}
+        DeviceResult::Respond(resp) => {
+            return rejected(resp.status, "a device responded in on_request".to_string());
+        }
+        DeviceResult::Error(err) => {
+            return rejected(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("device error in on_request: {}", err.message),
+            );
+        }
+    }
+
+    let route = match state.routers[listener.as_str()].match_route(ctx.canonical_path()) {
+        Ok(route) => route,
+        Err(err) => return rejected(StatusCode::NOT_FOUND, err.to_string()),
+    };
+
+    let (service, file_dir, websocket) = match &route.kind {
+        RouteRuntime::Static { file_dir, .. } => {
+            if ctx.is_upgrade_req() {
+                return rejected(
+                    StatusCode::BAD_REQUEST,
+                    format!("static route {} does not accept upgrades", route.path),
+                );
+            }
+            (None, Some(file_dir.clone()), false)
+        }
+        RouteRuntime::Service {
+            upstream,
+            allow_websocket,
+            ..
+        } => {
+            if ctx.is_upgrade_req() && !allow_websocket {
+                return rejected(
+                    StatusCode::UPGRADE_REQUIRED,
+                    format!("route {} does not enable websockets", route.path),
+                );
+            }
+            (Some(upstream.clone()), None, ctx.is_upgrade_req())
+        }
+    };
+
+    let service_runtime = service.as_ref().and_then(|s| state.services.get(s));
+
+    Ok(RouteTestOutcome::Matched(RouteMatch {
+        listener: listener.clone(),
+        route: route.kind.id().as_str(),
+        service,
+        file_dir,
+        websocket,
+        strategy: service_runtime.map(|s| s.strategy.clone()),
+        upstreams: service_runtime
+            .map(|s| {
+                s.upstreams
+                    .iter()
+                    .map(|u| UpstreamCandidate {
+                        authority: u.authority(),
+                        weight: u.weight(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        devices: devices.iter().map(|d| d.name().to_string()).collect(),
+    }))
+}
+
+/// Pick the listener by name or bind address, or the only listener with routes.
+fn select_listener(
+    cfg: &RuntimeConfig,
+    state: &RuntimeState,
+    requested: Option<&str>,
+) -> anyhow::Result<String> {
+    let mut routed = cfg
+        .listeners
+        .iter()
+        .filter(|l| state.routers.contains_key(l.name.as_str()))
+        .collect::<Vec<_>>();
+
+    if let Some(requested) = requested {
+        return routed
+            .into_iter()
+            .find(|l| l.name == requested || l.addr == requested)
+            .map(|l| l.name.clone())
+            .ok_or_else(|| anyhow!("no listener with routes matches '{requested}'"));
+    }
+
+    match routed.len() {
+        0 => bail!("configuration has no routes"),
+        1 => Ok(routed.remove(0).name.clone()),
+        _ => {
+            let names = routed
+                .iter()
+                .map(|l| format!("{} ({})", l.name, l.addr))
+                .collect::<Vec<_>>();
+            bail!(
+                "more than one listener has routes, pick one with --listener: {}",
+                names.join(", ")
+            )
+        }
+    }
+}
+
+fn load_runtime(path: PathBuf) -> anyhow::Result<(RuntimeConfig, RuntimeState)> {
+    let validated = load_config(&path)?;
+    if !validated.is_valid() {
+        validated.validation_report.render_pretty();
+        bail!("configuration is invalid");
+    }
+
+    let state = build_runtime_state(&validated.config)?;
+    Ok((validated.config, state))
+}
+
+fn print_outcome(outcome: &RouteTestOutcome) {
+    match outcome {
+        RouteTestOutcome::Matched(m) => {
+            println!("listener:  {}", m.listener);
+            println!("route:     {}", m.route);
+            if let Some(service) = &m.service {
+                println!("service:   {service}");
+            }
+            if let Some(file_dir) = &m.file_dir {
+                println!("file_dir:  {}", file_dir.display());
+            }
+            if m.websocket {
+                println!("websocket: upgrade allowed");
+            }
+            if let Some(strategy) = &m.strategy {
+                println!("strategy:  {strategy:?}");
+            }
+            for upstream in &m.upstreams {
+                println!(
+                    "upstream:  {} (weight {})",
+                    upstream.authority, upstream.weight
+                );
+            }
+            println!("devices:   {}", m.devices.join(", "));
+        }
+        RouteTestOutcome::Rejected {
+            listener,
+            status,
+            reason,
+        } => {
+            println!("listener:  {listener}");
+            println!("rejected:  {status} ({reason})");
+        }
+    }
+}
+
+fn parse_header(s: &str) -> anyhow::Result<(HeaderName, HeaderValue)> {
+    let (name, value) = s
+        .split_once(':')
+        .ok_or_else(|| anyhow!("header must be \"name: value\": {s}"))?;
+    Ok((
+        HeaderName::from_bytes(name.trim().as_bytes())?,
+        HeaderValue::from_str(value.trim())?,
+    ))
+}
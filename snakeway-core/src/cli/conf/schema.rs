@@ -0,0 +1,7 @@
+use crate::conf::{ConfigFileKind, config_schema};
+
+pub fn schema(file: ConfigFileKind) -> anyhow::Result<()> {
+    let s = serde_json::to_string_pretty(&config_schema(file))?;
+    println!("{s}");
+    Ok(())
+}
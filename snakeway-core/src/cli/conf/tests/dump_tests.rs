@@ -0,0 +1,212 @@
+use crate::cli::conf::RuntimeDump;
+use crate::conf::load_config;
+use pretty_assertions::assert_eq;
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+const DEVICES: &str = r#"
+request_filter_device {
+  enable = true
+
+  deny_methods = ["DELETE"]
+}
+
+upstream_group_device {
+  enable = true
+
+  header = "X-Tenant"
+  groups = { premium = "premium" }
+}
+
+required_headers_device {
+  enable = true
+
+  headers = [{ name = "X-Correlation-Id" }]
+}
+"#;
+
+fn ingress(port: u16, path: &str, devices: &str) -> String {
+    format!(
+        r#"
+bind = {{
+  interface    = "127.0.0.1"
+  port         = {port}
+  enable_http2 = false
+  devices      = [{devices}]
+}}
+
+services = [
+  {{
+    load_balancing_strategy = "round_robin"
+
+    routes = [
+      {{
+        path = "{path}"
+      }}
+    ]
+
+    upstreams = [
+      {{
+        endpoint = {{ host = "127.0.0.1", port = 9001 }}
+      }}
+    ]
+  }}
+]
+"#
+    )
+}
+
+fn write_config(root: &Path) {
+    fs::create_dir_all(root.join("devices.d")).unwrap();
+    fs::create_dir_all(root.join("ingress.d")).unwrap();
+    fs::write(
+        root.join("snakeway.hcl"),
+        r#"
+server {
+  version = 1
+}
+
+include {
+  devices = "devices.d/*.hcl"
+  ingress = "ingress.d/*.hcl"
+}
+"#,
+    )
+    .unwrap();
+    fs::write(root.join("devices.d/devices.hcl"), DEVICES).unwrap();
+    fs::write(root.join("ingress.d/api.hcl"), ingress(8080, "/api", "")).unwrap();
+    fs::write(
+        root.join("ingress.d/tenants.hcl"),
+        ingress(8081, "/tenants", r#""upstream_group""#),
+    )
+    .unwrap();
+}
+
+#[test]
+fn runtime_dump_lists_route_pipelines_in_order_with_listener_scoping() {
+    // Arrange
+    let dir = tempdir().unwrap();
+    write_config(dir.path());
+    let validated = load_config(dir.path()).unwrap();
+
+    // Act
+    let dump = RuntimeDump::new(&validated.config);
+
+    // Assert
+    let mut pipelines = dump
+        .pipelines
+        .iter()
+        .map(|p| {
+            (
+                p.route,
+                p.devices.iter().map(|d| d.name()).collect::<Vec<_>>(),
+            )
+        })
+        .collect::<Vec<_>>();
+    pipelines.sort();
+    assert_eq!(
+        pipelines,
+        vec![
+            ("/api", vec!["request_filter", "required_headers"]),
+            (
+                "/tenants",
+                vec!["request_filter", "upstream_group", "required_headers"]
+            ),
+        ]
+    );
+}
+
+#[test]
+fn runtime_dump_skips_devices_scoped_to_other_routes() {
+    // Arrange
+    let dir = tempdir().unwrap();
+    write_config(dir.path());
+    let scoped = DEVICES.replace(
+        "required_headers_device {\n  enable = true\n",
+        "required_headers_device {\n  enable = true\n  routes = [\"/api\"]\n",
+    );
+    fs::write(dir.path().join("devices.d/devices.hcl"), scoped).unwrap();
+    let validated = load_config(dir.path()).unwrap();
+
+    // Act
+    let dump = RuntimeDump::new(&validated.config);
+
+    // Assert
+    let mut pipelines = dump
+        .pipelines
+        .iter()
+        .map(|p| {
+            (
+                p.route,
+                p.devices.iter().map(|d| d.name()).collect::<Vec<_>>(),
+            )
+        })
+        .collect::<Vec<_>>();
+    pipelines.sort();
+    assert_eq!(
+        pipelines,
+        vec![
+            ("/api", vec!["request_filter", "required_headers"]),
+            ("/tenants", vec!["request_filter", "upstream_group"]),
+        ]
+    );
+}
+
+#[test]
+fn runtime_dump_includes_resolved_device_config() {
+    // Arrange
+    let dir = tempdir().unwrap();
+    write_config(dir.path());
+    let validated = load_config(dir.path()).unwrap();
+
+    // Act
+    let json = serde_json::to_value(RuntimeDump::new(&validated.config)).unwrap();
+
+    // Assert
+    let pipeline = json["pipelines"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|p| p["route"] == "/tenants")
+        .unwrap();
+    assert_eq!(
+        pipeline["devices"][1]["upstream_group"]["header"],
+        "X-Tenant"
+    );
+    assert!(json["routes"].is_array());
+}
+
+#[test]
+fn runtime_dump_redacts_injected_upstream_headers() {
+    // Arrange
+    let dir = tempdir().unwrap();
+    write_config(dir.path());
+    fs::create_dir_all(dir.path().join("secrets")).unwrap();
+    fs::write(dir.path().join("secrets/api_key"), "sk-live-123\n").unwrap();
+    let api = ingress(8080, "/api", "").replace(
+        "    routes = [",
+        r#"    upstream_request_headers = [
+      { name = "X-Api-Key", value = "secret://file/secrets/api_key" },
+    ]
+
+    routes = ["#,
+    );
+    fs::write(dir.path().join("ingress.d/api.hcl"), api).unwrap();
+    let validated = load_config(dir.path()).unwrap();
+
+    // Act
+    let json = serde_json::to_string(&RuntimeDump::new(&validated.config)).unwrap();
+
+    // Assert
+    let header = validated
+        .config
+        .services
+        .values()
+        .flat_map(|s| &s.upstream_request_headers)
+        .next()
+        .unwrap();
+    assert_eq!(header.value.expose(), "sk-live-123");
+    assert!(!json.contains("sk-live-123"));
+    assert!(json.contains(r#""secret":"secret://file/secrets/api_key","value":"<redacted>""#));
+}
@@ -0,0 +1,3 @@
+mod dump_tests;
+mod route_tests;
+mod schema_tests;
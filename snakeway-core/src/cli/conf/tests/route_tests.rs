@@ -0,0 +1,153 @@
+use crate::cli::conf::{RouteTestOutcome, RouteTestRequest, resolve_route};
+use crate::conf::load_config;
+use crate::runtime::build_runtime_state;
+use http::{HeaderMap, HeaderValue, Method, header};
+use pretty_assertions::assert_eq;
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+const INGRESS: &str = r#"
+bind = {
+  interface    = "127.0.0.1"
+  port         = 8080
+  enable_http2 = false
+}
+
+services = [
+  {
+    load_balancing_strategy = "round_robin"
+
+    routes = [
+      {
+        path = "/api"
+      }
+    ]
+
+    upstreams = [
+      {
+        weight   = 3
+        endpoint = { host = "127.0.0.1", port = 9001 }
+      },
+      {
+        weight   = 1
+        endpoint = { host = "127.0.0.1", port = 9002 }
+      }
+    ]
+  }
+]
+"#;
+
+fn write_config(root: &Path, devices: &str) {
+    fs::create_dir_all(root.join("devices.d")).unwrap();
+    fs::create_dir_all(root.join("ingress.d")).unwrap();
+    fs::write(
+        root.join("snakeway.hcl"),
+        r#"
+server {
+  version = 1
+}
+
+include {
+  devices = "devices.d/*.hcl"
+  ingress = "ingress.d/*.hcl"
+}
+"#,
+    )
+    .unwrap();
+    fs::write(root.join("ingress.d/api.hcl"), INGRESS).unwrap();
+    if !devices.is_empty() {
+        fs::write(root.join("devices.d/devices.hcl"), devices).unwrap();
+    }
+}
+
+fn resolve(devices: &str, method: Method, path: &str, headers: HeaderMap) -> RouteTestOutcome {
+    let dir = tempdir().unwrap();
+    write_config(dir.path(), devices);
+
+    let validated = load_config(dir.path()).unwrap();
+    let state = build_runtime_state(&validated.config).unwrap();
+    let request = RouteTestRequest {
+        listener: None,
+        method,
+        path: path.to_string(),
+        host: Some("api.example.com".to_string()),
+        headers,
+    };
+
+    resolve_route(&validated.config, &state, &request).unwrap()
+}
+
+fn rejected_status(outcome: RouteTestOutcome) -> u16 {
+    match outcome {
+        RouteTestOutcome::Rejected { status, .. } => status,
+        RouteTestOutcome::Matched(m) => panic!("expected a rejection, matched {}", m.route),
+    }
+}
+
+#[test]
+fn matching_request_reports_route_service_and_upstream_candidates() {
+    // Act
+    let outcome = resolve("", Method::GET, "/api/users/42", HeaderMap::new());
+
+    // Assert
+    let RouteTestOutcome::Matched(m) = outcome else {
+        panic!("expected a match, got {outcome:?}");
+    };
+    assert_eq!(m.route, "service:/api:127.0.0.1:8080-service");
+    assert_eq!(m.service.as_deref(), Some("127.0.0.1:8080-service"));
+    assert_eq!(
+        m.upstreams
+            .iter()
+            .map(|u| (u.authority.as_str(), u.weight))
+            .collect::<Vec<_>>(),
+        vec![("127.0.0.1:9001", 3), ("127.0.0.1:9002", 1)]
+    );
+    assert!(!m.websocket);
+}
+
+#[test]
+fn unmatched_path_is_not_found() {
+    // Act
+    let outcome = resolve("", Method::GET, "/apiv2", HeaderMap::new());
+
+    // Assert
+    assert_eq!(rejected_status(outcome), 404);
+}
+
+#[test]
+fn websocket_upgrade_on_route_without_websockets_is_rejected() {
+    // Arrange
+    let mut headers = HeaderMap::new();
+    headers.insert(header::UPGRADE, HeaderValue::from_static("websocket"));
+    headers.insert(header::CONNECTION, HeaderValue::from_static("Upgrade"));
+
+    // Act
+    let outcome = resolve("", Method::GET, "/api/socket", headers);
+
+    // Assert
+    assert_eq!(rejected_status(outcome), 426);
+}
+
+#[test]
+fn method_denied_by_request_filter_device_is_rejected() {
+    // Arrange
+    let devices = r#"
+request_filter_device {
+  enable = true
+
+  deny_methods = ["DELETE"]
+}
+"#;
+
+    // Act
+    let denied = resolve(devices, Method::DELETE, "/api/users/42", HeaderMap::new());
+    let allowed = resolve(devices, Method::GET, "/api/users/42", HeaderMap::new());
+
+    // Assert
+    assert_eq!(rejected_status(denied), 405);
+    let RouteTestOutcome::Matched(m) = allowed else {
+        panic!("expected a match, got {allowed:?}");
+    };
+    assert_eq!(m.devices, vec!["Request Filter".to_string()]);
+}
@@ -0,0 +1,93 @@
+use crate::cli::conf::ConfigTemplates;
+use crate::conf::{ConfigFileKind, config_schema};
+use pretty_assertions::assert_eq;
+
+/// Schema violations of an HCL source, as messages.
+fn violations(kind: ConfigFileKind, source: &str) -> Vec<String> {
+    let schema = serde_json::to_value(config_schema(kind)).unwrap();
+    let validator = jsonschema::validator_for(&schema).unwrap();
+    let instance: serde_json::Value = hcl::from_str(source).unwrap();
+
+    validator
+        .iter_errors(&instance)
+        .map(|e| e.to_string())
+        .collect()
+}
+
+fn template_kind(path: &str) -> ConfigFileKind {
+    if path.starts_with("ingress.d/") {
+        ConfigFileKind::Ingress
+    } else if path.starts_with("devices.d/") {
+        ConfigFileKind::Devices
+    } else {
+        ConfigFileKind::Entrypoint
+    }
+}
+
+#[test]
+fn init_templates_are_valid_against_their_schema() {
+    for path in ConfigTemplates::iter() {
+        // Arrange
+        let file = ConfigTemplates::get(&path).unwrap();
+        let source = std::str::from_utf8(file.data.as_ref()).unwrap();
+
+        // Act
+        let errors = violations(template_kind(&path), source);
+
+        // Assert
+        assert_eq!(errors, Vec::<String>::new(), "{path}");
+    }
+}
+
+#[test]
+fn unknown_device_field_violates_devices_schema() {
+    // Arrange
+    let source = r#"
+request_filter_device {
+  enable       = true
+  deny_methodz = ["DELETE"]
+}
+"#;
+
+    // Act
+    let errors = violations(ConfigFileKind::Devices, source);
+
+    // Assert
+    assert!(!errors.is_empty());
+}
+
+#[test]
+fn missing_include_violates_entrypoint_schema() {
+    // Arrange
+    let source = r#"
+server {
+  version = 1
+}
+"#;
+
+    // Act
+    let errors = violations(ConfigFileKind::Entrypoint, source);
+
+    // Assert
+    assert!(!errors.is_empty());
+}
+
+#[test]
+fn unknown_load_balancing_strategy_violates_ingress_schema() {
+    // Arrange
+    let source = r#"
+services = [
+  {
+    load_balancing_strategy = "least_conn"
+    routes    = [{ path = "/api" }]
+    upstreams = [{ endpoint = { host = "127.0.0.1", port = 9001 } }]
+  }
+]
+"#;
+
+    // Act
+    let errors = violations(ConfigFileKind::Ingress, source);
+
+    // Assert
+    assert!(!errors.is_empty());
+}
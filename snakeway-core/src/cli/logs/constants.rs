@@ -4,3 +4,12 @@ pub const WINDOW: Duration = Duration::from_secs(10);
 pub const RENDER_TICK: Duration = Duration::from_secs(1);
 pub const IN_FLIGHT_TTL: Duration = Duration::from_secs(60);
 pub const LOOP_IDLE_SLEEP: Duration = Duration::from_millis(25);
+
+/// Adaptive stats show detail once the window's 5xx rate reaches this fraction of requests...
+pub const ADAPTIVE_ERROR_RATE: f64 = 0.05;
+/// ...or its estimated p99 latency exceeds this many milliseconds.
+pub const ADAPTIVE_P99_MS: u64 = 1000;
+/// Most recent 5xx responses kept for the detailed view.
+pub const RECENT_ERRORS: usize = 10;
+/// Busiest routes shown in the detailed view.
+pub const TOP_ROUTES: usize = 10;
@@ -1,13 +1,37 @@
+use anyhow::bail;
+
+/// Default latency bucket upper bounds, from sub-millisecond APIs to multi-second uploads.
+pub const DEFAULT_LATENCY_BUCKETS_MS: &[u64] =
+    &[1, 5, 10, 25, 50, 100, 250, 500, 1000, 2500, 5000, 10000];
+
+/// Check that bucket upper bounds are non-empty and strictly ascending.
+pub fn validate_buckets(buckets: &[u64]) -> anyhow::Result<()> {
+    if buckets.is_empty() {
+        bail!("histogram needs at least one bucket");
+    }
+
+    if let Some(pair) = buckets.windows(2).find(|pair| pair[0] >= pair[1]) {
+        bail!(
+            "histogram buckets must be ascending, but {} is followed by {}",
+            pair[0],
+            pair[1]
+        );
+    }
+
+    Ok(())
+}
+
 #[derive(Clone)]
 pub struct Histogram {
-    buckets: &'static [u64],
+    buckets: Vec<u64>,
     counts: Vec<u64>,
 }
 
 impl Histogram {
-    pub(crate) fn new(buckets: &'static [u64]) -> Self {
+    /// Buckets are upper bounds and must pass `validate_buckets`.
+    pub(crate) fn new(buckets: &[u64]) -> Self {
         Self {
-            buckets,
+            buckets: buckets.to_vec(),
             counts: vec![0; buckets.len() + 1], // +∞ bucket
         }
     }
@@ -36,6 +36,10 @@ mod parse;
 mod render;
 mod run;
 mod stats_aggregation;
+#[cfg(test)]
+mod tests;
 mod types;
 
+pub use histogram::DEFAULT_LATENCY_BUCKETS_MS;
+pub use render::StatsDetail;
 pub use run::run_logs;
@@ -3,7 +3,18 @@ use serde_json::Value;
 use std::time::SystemTime;
 
 fn is_snakeway_event(event: &Value) -> bool {
-    event.get("method").is_some() || event.get("uri").is_some() || event.get("status").is_some()
+    event.get("method").is_some()
+        || event.get("uri").is_some()
+        || event.get("status").is_some()
+        || event.get("bytes_out").is_some()
+}
+
+/// Byte counts are logged as numbers, but accept strings like every other log value.
+fn byte_count(event: &Value, field: &str) -> Option<u64> {
+    let value = event.get(field)?;
+    value
+        .as_u64()
+        .or_else(|| value.as_str().and_then(|s| s.parse().ok()))
 }
 
 pub fn parse_event(event: &Value) -> Option<LogEvent> {
@@ -85,6 +96,8 @@ pub fn parse_event(event: &Value) -> Option<LogEvent> {
                 .get("status")
                 .and_then(Value::as_str)
                 .and_then(|s| s.parse::<i64>().ok()),
+            bytes_in: byte_count(event, "bytes_in"),
+            bytes_out: byte_count(event, "bytes_out"),
         }))
     } else {
         Some(LogEvent::Generic(GenericEvent {
@@ -98,6 +111,14 @@ pub fn parse_event(event: &Value) -> Option<LogEvent> {
                 .get("target")
                 .and_then(Value::as_str)
                 .map(str::to_string),
+            name: event
+                .get("event")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            reason: event
+                .get("reason")
+                .and_then(Value::as_str)
+                .map(str::to_string),
         }))
     }
 }
@@ -3,7 +3,28 @@ use crate::cli::logs::types::LogEvent;
 use std::io;
 use std::io::Write;
 
-pub fn render_stats(snapshot: &StatsSnapshot) -> String {
+/// How much detail stats mode shows below the summary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum StatsDetail {
+    /// The summary only.
+    Summary,
+    /// The summary, the busiest routes, and recent 5xx responses.
+    Full,
+    /// Full detail while the window's 5xx rate or p99 latency is high, the summary otherwise.
+    Adaptive,
+}
+
+impl StatsDetail {
+    fn shows_detail(self, snapshot: &StatsSnapshot) -> bool {
+        match self {
+            StatsDetail::Summary => false,
+            StatsDetail::Full => true,
+            StatsDetail::Adaptive => snapshot.is_degraded(),
+        }
+    }
+}
+
+pub fn render_stats(snapshot: &StatsSnapshot, detail: StatsDetail) -> String {
     let mut out = String::new();
 
     let (_ok, _client, server) = snapshot.status;
@@ -15,6 +36,12 @@ pub fn render_stats(snapshot: &StatsSnapshot) -> String {
         snapshot.window_seconds, snapshot.rps, snapshot.window_events, server
     ));
 
+    out.push_str(&format!(
+        "Throughput: in={}/s out={}/s\n\n",
+        format_bytes(snapshot.bytes_in_per_sec),
+        format_bytes(snapshot.bytes_out_per_sec)
+    ));
+
     let total_latency: u64 = snapshot.latency.iter().map(|(_, c)| *c).sum();
     if total_latency > 0 {
         out.push_str("Latency (window):\n");
@@ -43,6 +70,17 @@ pub fn render_stats(snapshot: &StatsSnapshot) -> String {
         "\nStatus: 2xx={} 4xx={} 5xx={}\n",
         ok, client, server
     ));
+    // TLS semantics: failed handshakes in the window, by reason.
+    if !snapshot.tls_failure_counts.is_empty() {
+        let mut reasons: Vec<_> = snapshot.tls_failure_counts.iter().collect();
+        reasons.sort_by_key(|(k, _)| *k);
+        out.push_str("TLS handshake failures: ");
+        for (reason, c) in reasons {
+            out.push_str(&format!("{reason}={c} "));
+        }
+        out.push('\n');
+    }
+
     out.push_str("\n --------------------- \n");
     // Identity semantics: these are counts of events with bot info present.
     out.push_str(&format!(
@@ -106,9 +144,53 @@ pub fn render_stats(snapshot: &StatsSnapshot) -> String {
         }
         out.push('\n');
     }
+
+    if detail.shows_detail(snapshot) {
+        render_detail(snapshot, &mut out);
+    }
     out
 }
 
+/// Per-route breakdown and recent 5xx responses, for when something looks wrong.
+fn render_detail(snapshot: &StatsSnapshot, out: &mut String) {
+    out.push_str("\n --------------------- \n");
+    out.push_str(&format!(
+        "Error rate: {:.1}% | p99 ≈ {}ms\n",
+        snapshot.error_rate() * 100.0,
+        snapshot.p99_ms
+    ));
+
+    if !snapshot.routes.is_empty() {
+        out.push_str("Routes (window):\n");
+        for route in &snapshot.routes {
+            out.push_str(&format!(
+                "  {:<32} requests={} 5xx={}\n",
+                route.route, route.requests, route.errors
+            ));
+        }
+    }
+
+    if !snapshot.recent_errors.is_empty() {
+        out.push_str("Recent errors:\n");
+        for line in &snapshot.recent_errors {
+            out.push_str(&format!("  {line}\n"));
+        }
+    }
+}
+
+/// Human-readable byte count, e.g. `1.5 KiB`.
+fn format_bytes(bytes: f64) -> String {
+    const UNITS: &[&str] = &["B", "KiB", "MiB", "GiB"];
+
+    let mut value = bytes;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
 pub fn redraw(output: &str) {
     print!("\x1b[2J\x1b[H");
     println!("{output}");
@@ -125,10 +207,15 @@ pub fn render_pretty(event: &LogEvent) {
             if let Some(s) = e.status {
                 print!(" ({s})");
             }
+            if let (Some(i), Some(o)) = (e.bytes_in, e.bytes_out) {
+                print!(" in={i}B out={o}B");
+            }
             println!();
         }
         LogEvent::Generic(e) => {
-            if let Some(target) = &e.target {
+            if let Some(reason) = &e.reason {
+                println!("[{}] {} (reason={reason})", e.level, e.message);
+            } else if let Some(target) = &e.target {
                 println!("[{}] {} ({})", e.level, e.message, target);
             } else {
                 println!("[{}] {}", e.level, e.message);
@@ -1,6 +1,7 @@
 use crate::cli::logs::constants::{LOOP_IDLE_SLEEP, RENDER_TICK, WINDOW};
+use crate::cli::logs::histogram::validate_buckets;
 use crate::cli::logs::parse::parse_event;
-use crate::cli::logs::render::{redraw, render_pretty, render_stats};
+use crate::cli::logs::render::{StatsDetail, redraw, render_pretty, render_stats};
 use crate::cli::logs::stats_aggregation::StatsAggregator;
 use crate::cli::logs::types::LogEvent;
 use crate::logging::LogMode;
@@ -14,11 +15,13 @@ use std::time::Instant;
 
 static CTRL_C_INSTALLED: std::sync::Once = std::sync::Once::new();
 
-pub fn run_logs(mode: LogMode) -> Result<()> {
+/// `latency_buckets_ms` are the stats mode latency histogram's bucket upper bounds,
+/// and `detail` is how much stats mode shows below the summary.
+pub fn run_logs(mode: LogMode, latency_buckets_ms: &[u64], detail: StatsDetail) -> Result<()> {
     match mode {
         LogMode::Raw => run_raw(),
         LogMode::Pretty => run_pretty(),
-        LogMode::Stats => run_stats(),
+        LogMode::Stats => run_stats(latency_buckets_ms, detail),
     }
 }
 
@@ -52,7 +55,9 @@ fn run_pretty() -> Result<()> {
     Ok(())
 }
 
-fn run_stats() -> Result<()> {
+fn run_stats(latency_buckets_ms: &[u64], detail: StatsDetail) -> Result<()> {
+    validate_buckets(latency_buckets_ms)?;
+
     // Channel from reader thread -> stats loop.
     let (tx, rx) = mpsc::channel::<LogEvent>();
 
@@ -89,7 +94,7 @@ fn run_stats() -> Result<()> {
     // Hide cursor while dashboard runs.
     hide_cursor();
 
-    let mut agg = StatsAggregator::new(WINDOW);
+    let mut agg = StatsAggregator::new(WINDOW, latency_buckets_ms);
     let mut last_render = Instant::now();
 
     let shutdown = Arc::new(AtomicBool::new(false));
@@ -124,7 +129,7 @@ fn run_stats() -> Result<()> {
 
         if last_render.elapsed() >= RENDER_TICK {
             let snap = agg.snapshot();
-            redraw(&render_stats(&snap));
+            redraw(&render_stats(&snap, detail));
             last_render = Instant::now();
         }
 
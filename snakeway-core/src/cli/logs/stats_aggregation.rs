@@ -1,22 +1,48 @@
-use crate::cli::logs::constants::IN_FLIGHT_TTL;
+use crate::cli::logs::constants::{
+    ADAPTIVE_ERROR_RATE, ADAPTIVE_P99_MS, IN_FLIGHT_TTL, RECENT_ERRORS, TOP_ROUTES,
+};
 use crate::cli::logs::histogram::{Histogram, percentile_from_histogram};
 use crate::cli::logs::types::{IdentitySummary, LogEvent};
 use crate::ctx::RequestId;
+use crate::server::TLS_HANDSHAKE_FAILED;
 use std::collections::{HashMap, VecDeque};
 use std::time::{Duration, Instant, SystemTime};
 
-const LATENCY_BUCKETS_MS: &[u64] = &[1, 5, 10, 25, 50, 100, 250, 500, 1000];
-
 struct WindowEvent {
     inserted_at: Instant,    // for eviction
     latency_ms: Option<u64>, // computed from timestamps when available
     status: Option<i64>,
     identity: IdentitySummary,
+    /// Request path, without the query.
+    route: Option<String>,
+}
+
+/// One recent 5xx response, e.g. `502 GET /api/users`.
+struct ErrorSample {
+    inserted_at: Instant,
+    line: String,
+}
+
+/// Body bytes of one completed request.
+struct ThroughputSample {
+    inserted_at: Instant,
+    bytes_in: u64,
+    bytes_out: u64,
+}
+
+/// One failed TLS handshake.
+struct TlsFailureSample {
+    inserted_at: Instant,
+    reason: String,
 }
 
 pub struct StatsAggregator {
     window: Duration,
+    latency_buckets_ms: Vec<u64>,
     events: VecDeque<WindowEvent>,
+    throughput: VecDeque<ThroughputSample>,
+    tls_failures: VecDeque<TlsFailureSample>,
+    recent_errors: VecDeque<ErrorSample>,
     in_flight: HashMap<RequestId, InFlight>,
 }
 
@@ -25,20 +51,35 @@ struct InFlight {
     start_system: Option<SystemTime>, // for latency math
     status: Option<i64>,
     identity: IdentitySummary,
+    method: Option<String>,
+    uri: Option<String>,
 }
 
 impl StatsAggregator {
-    pub fn new(window: Duration) -> Self {
+    pub fn new(window: Duration, latency_buckets_ms: &[u64]) -> Self {
         Self {
             window,
+            latency_buckets_ms: latency_buckets_ms.to_vec(),
             events: VecDeque::new(),
+            throughput: VecDeque::new(),
+            tls_failures: VecDeque::new(),
+            recent_errors: VecDeque::new(),
             in_flight: HashMap::new(),
         }
     }
 
     pub(crate) fn push(&mut self, event: &LogEvent) {
-        let LogEvent::Snakeway(e) = event else {
-            return;
+        let e = match event {
+            LogEvent::Snakeway(e) => e,
+            LogEvent::Generic(e) => {
+                if e.name.as_deref() == Some(TLS_HANDSHAKE_FAILED) {
+                    self.tls_failures.push_back(TlsFailureSample {
+                        inserted_at: Instant::now(),
+                        reason: e.reason.clone().unwrap_or_else(|| "other".to_string()),
+                    });
+                }
+                return;
+            }
         };
         let Some(request_id) = &e.request_id else {
             return;
@@ -53,6 +94,8 @@ impl StatsAggregator {
                     start_system: e.ts,
                     status: None,
                     identity: e.identity.clone().unwrap_or_default(),
+                    method: e.method.clone(),
+                    uri: e.uri.clone(),
                 });
             }
             "after_proxy" => {
@@ -69,18 +112,45 @@ impl StatsAggregator {
                         _ => None,
                     };
 
+                    let status = e.status.or(f.status);
+                    if let Some(status @ 500..=599) = status {
+                        self.push_error(status, &f);
+                    }
+
                     self.events.push_back(WindowEvent {
                         inserted_at: Instant::now(),
                         latency_ms,
-                        status: e.status.or(f.status),
+                        status,
                         identity: f.identity,
+                        route: f.uri.as_deref().map(route_of),
                     });
                 }
             }
+            "complete" => {
+                self.throughput.push_back(ThroughputSample {
+                    inserted_at: Instant::now(),
+                    bytes_in: e.bytes_in.unwrap_or(0),
+                    bytes_out: e.bytes_out.unwrap_or(0),
+                });
+            }
             _ => {}
         }
     }
 
+    fn push_error(&mut self, status: i64, f: &InFlight) {
+        if self.recent_errors.len() == RECENT_ERRORS {
+            self.recent_errors.pop_front();
+        }
+        self.recent_errors.push_back(ErrorSample {
+            inserted_at: Instant::now(),
+            line: format!(
+                "{status} {} {}",
+                f.method.as_deref().unwrap_or("-"),
+                f.uri.as_deref().unwrap_or("-")
+            ),
+        });
+    }
+
     fn evict_window(&mut self, now: Instant) {
         while let Some(ev) = self.events.front() {
             if now.duration_since(ev.inserted_at) > self.window {
@@ -89,6 +159,30 @@ impl StatsAggregator {
                 break;
             }
         }
+
+        while let Some(sample) = self.throughput.front() {
+            if now.duration_since(sample.inserted_at) > self.window {
+                self.throughput.pop_front();
+            } else {
+                break;
+            }
+        }
+
+        while let Some(sample) = self.tls_failures.front() {
+            if now.duration_since(sample.inserted_at) > self.window {
+                self.tls_failures.pop_front();
+            } else {
+                break;
+            }
+        }
+
+        while let Some(sample) = self.recent_errors.front() {
+            if now.duration_since(sample.inserted_at) > self.window {
+                self.recent_errors.pop_front();
+            } else {
+                break;
+            }
+        }
     }
 
     fn evict_in_flight(&mut self, now: Instant) {
@@ -101,7 +195,7 @@ impl StatsAggregator {
         self.evict_window(now);
         self.evict_in_flight(now);
 
-        let mut latency = Histogram::new(LATENCY_BUCKETS_MS);
+        let mut latency = Histogram::new(&self.latency_buckets_ms);
         let mut status_2xx = 0;
         let mut status_4xx = 0;
         let mut status_5xx = 0;
@@ -111,6 +205,7 @@ impl StatsAggregator {
         let mut asn_counts: HashMap<usize, u64> = HashMap::new();
         let mut aso_counts: HashMap<String, u64> = HashMap::new();
         let mut country_counts: HashMap<String, u64> = HashMap::new();
+        let mut route_counts: HashMap<&str, RouteStats> = HashMap::new();
         let mut bot_count = 0;
         let mut human_count = 0;
         let mut unknown_identity_count = 0;
@@ -121,6 +216,20 @@ impl StatsAggregator {
                 latency.record(ms);
             }
 
+            if let Some(route) = &ev.route {
+                let stats = route_counts
+                    .entry(route.as_str())
+                    .or_insert_with(|| RouteStats {
+                        route: route.clone(),
+                        requests: 0,
+                        errors: 0,
+                    });
+                stats.requests += 1;
+                if matches!(ev.status, Some(500..=599)) {
+                    stats.errors += 1;
+                }
+            }
+
             if let Some(status) = ev.status {
                 match status {
                     200..=299 => status_2xx += 1,
@@ -176,9 +285,25 @@ impl StatsAggregator {
         let denom = span.as_secs_f64().clamp(0.1, self.window.as_secs_f64());
         let rps = self.events.len() as f64 / denom;
 
+        // Throughput uses the same span as RPS.
+        let bytes_in: u64 = self.throughput.iter().map(|s| s.bytes_in).sum();
+        let bytes_out: u64 = self.throughput.iter().map(|s| s.bytes_out).sum();
+
+        let mut tls_failure_counts: HashMap<String, u64> = HashMap::new();
+        for sample in &self.tls_failures {
+            *tls_failure_counts.entry(sample.reason.clone()).or_insert(0) += 1;
+        }
+
+        // Busiest routes first, then by path for a stable order.
+        let mut routes: Vec<RouteStats> = route_counts.into_values().collect();
+        routes.sort_by(|a, b| b.requests.cmp(&a.requests).then(a.route.cmp(&b.route)));
+        routes.truncate(TOP_ROUTES);
+
         StatsSnapshot {
             window_seconds: self.window.as_secs().max(1),
             rps,
+            bytes_in_per_sec: bytes_in as f64 / denom,
+            bytes_out_per_sec: bytes_out as f64 / denom,
             window_events: self.events.len() as u64,
             latency: latency.snapshot(),
             status: (status_2xx, status_4xx, status_5xx),
@@ -192,14 +317,26 @@ impl StatsAggregator {
             bot_count,
             human_count,
             unknown_identity_count,
+            tls_failure_counts,
+            routes,
+            recent_errors: self.recent_errors.iter().map(|e| e.line.clone()).collect(),
         }
     }
 }
 
+/// The request path of a URI, without its query.
+fn route_of(uri: &str) -> String {
+    uri.split_once('?')
+        .map_or(uri, |(path, _)| path)
+        .to_string()
+}
+
 pub struct StatsSnapshot {
     pub window_seconds: u64,
 
     pub rps: f64,
+    pub bytes_in_per_sec: f64,
+    pub bytes_out_per_sec: f64,
     pub window_events: u64,
     pub latency: Vec<(String, u64)>,
     pub status: (u64, u64, u64), // 2xx, 4xx, 5xx
@@ -215,4 +352,35 @@ pub struct StatsSnapshot {
     pub bot_count: u64,
     pub human_count: u64,
     pub unknown_identity_count: u64,
+
+    /// Failed TLS handshakes in the window, by reason.
+    pub tls_failure_counts: HashMap<String, u64>,
+
+    /// Busiest routes in the window, busiest first.
+    pub routes: Vec<RouteStats>,
+    /// Most recent 5xx responses in the window, oldest first.
+    pub recent_errors: Vec<String>,
+}
+
+impl StatsSnapshot {
+    /// Fraction of requests in the window answered with a 5xx.
+    pub fn error_rate(&self) -> f64 {
+        if self.window_events == 0 {
+            return 0.0;
+        }
+        self.status.2 as f64 / self.window_events as f64
+    }
+
+    /// Whether the window's error rate or p99 latency crossed the adaptive thresholds.
+    pub fn is_degraded(&self) -> bool {
+        self.error_rate() >= ADAPTIVE_ERROR_RATE || self.p99_ms > ADAPTIVE_P99_MS
+    }
+}
+
+/// Requests to one route in the window.
+pub struct RouteStats {
+    pub route: String,
+    pub requests: u64,
+    /// Requests answered with a 5xx.
+    pub errors: u64,
 }
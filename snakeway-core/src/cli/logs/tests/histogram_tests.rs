@@ -0,0 +1,90 @@
+use crate::cli::logs::histogram::{
+    DEFAULT_LATENCY_BUCKETS_MS, Histogram, percentile_from_histogram, validate_buckets,
+};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn configured_buckets_are_used() {
+    // Arrange
+    let mut histogram = Histogram::new(&[2, 4, 8]);
+
+    // Act
+    for value in [1, 2, 3, 8, 9] {
+        histogram.record(value);
+    }
+
+    // Assert
+    assert_eq!(
+        histogram.numeric_buckets(),
+        vec![(2, 2), (4, 1), (8, 1), (u64::MAX, 1)]
+    );
+    assert_eq!(
+        histogram.snapshot(),
+        vec![
+            ("0–2ms".to_string(), 2),
+            ("3–4ms".to_string(), 1),
+            ("5–8ms".to_string(), 1),
+            (">8ms".to_string(), 1),
+        ]
+    );
+}
+
+#[test]
+fn percentiles_follow_configured_buckets() {
+    // Arrange
+    let mut histogram = Histogram::new(&[100, 200, 300, 400]);
+    for value in 1..=100 {
+        histogram.record(value * 4);
+    }
+    let buckets = histogram.numeric_buckets();
+
+    // Act
+    let p50 = percentile_from_histogram(&buckets, 100, 0.50);
+    let p95 = percentile_from_histogram(&buckets, 100, 0.95);
+
+    // Assert
+    assert_eq!(p50, 200);
+    assert_eq!(p95, 400);
+}
+
+#[test]
+fn percentile_in_overflow_bucket_is_past_the_last_bound() {
+    // Arrange
+    let mut histogram = Histogram::new(&[10]);
+    histogram.record(5);
+    histogram.record(50);
+    let buckets = histogram.numeric_buckets();
+
+    // Act
+    let p99 = percentile_from_histogram(&buckets, 2, 0.99);
+
+    // Assert
+    assert_eq!(p99, 11);
+}
+
+#[test]
+fn default_buckets_are_valid() {
+    assert!(validate_buckets(DEFAULT_LATENCY_BUCKETS_MS).is_ok());
+}
+
+#[test]
+fn buckets_must_be_ascending() {
+    // Act
+    let err = validate_buckets(&[1, 10, 5]).unwrap_err();
+
+    // Assert
+    assert_eq!(
+        err.to_string(),
+        "histogram buckets must be ascending, but 10 is followed by 5"
+    );
+}
+
+#[test]
+fn duplicate_buckets_are_rejected() {
+    assert!(validate_buckets(&[1, 5, 5]).is_err());
+}
+
+#[test]
+fn empty_buckets_are_rejected() {
+    assert!(validate_buckets(&[]).is_err());
+}
@@ -0,0 +1,3 @@
+mod histogram_tests;
+mod render_tests;
+mod stats_aggregation_tests;
@@ -0,0 +1,123 @@
+use crate::cli::logs::render::{StatsDetail, render_stats};
+use crate::cli::logs::stats_aggregation::StatsAggregator;
+use crate::cli::logs::types::{LogEvent, SnakewayEvent};
+use pretty_assertions::assert_eq;
+use std::time::{Duration, SystemTime};
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+fn event(request_id: &str, name: &str, uri: &str, status: i64, ts: SystemTime) -> LogEvent {
+    LogEvent::Snakeway(SnakewayEvent {
+        request_id: Some(request_id.to_string()),
+        level: "INFO".to_string(),
+        name: name.to_string(),
+        method: Some("GET".to_string()),
+        uri: Some(uri.to_string()),
+        status: Some(status),
+        ts: Some(ts),
+        identity: None,
+        bytes_in: None,
+        bytes_out: None,
+    })
+}
+
+/// Push a 10ms request/response pair answered with `status`.
+fn push_request(agg: &mut StatsAggregator, request_id: &str, uri: &str, status: i64) {
+    let start = SystemTime::UNIX_EPOCH;
+    agg.push(&event(request_id, "request", uri, status, start));
+    agg.push(&event(
+        request_id,
+        "response",
+        uri,
+        status,
+        start + Duration::from_millis(10),
+    ));
+}
+
+fn aggregator() -> StatsAggregator {
+    StatsAggregator::new(Duration::from_secs(10), &[10, 100, 1000, 5000])
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn quiet_traffic_renders_the_summary_only() {
+    // Arrange
+    let mut agg = aggregator();
+    for i in 0..50 {
+        push_request(&mut agg, &i.to_string(), "/api/users?page=1", 200);
+    }
+    let snapshot = agg.snapshot();
+
+    // Act
+    let adaptive = render_stats(&snapshot, StatsDetail::Adaptive);
+    let summary = render_stats(&snapshot, StatsDetail::Summary);
+
+    // Assert
+    assert!(!snapshot.is_degraded());
+    assert_eq!(adaptive, summary);
+    assert!(!adaptive.contains("Routes (window):"));
+}
+
+#[test]
+fn error_heavy_traffic_renders_route_breakdown_and_recent_errors() {
+    // Arrange
+    let mut agg = aggregator();
+    for i in 0..8 {
+        push_request(&mut agg, &format!("ok-{i}"), "/api/users?page=1", 200);
+    }
+    push_request(&mut agg, "err-1", "/api/orders", 502);
+    push_request(&mut agg, "err-2", "/api/orders?id=7", 503);
+    let snapshot = agg.snapshot();
+
+    // Act
+    let output = render_stats(&snapshot, StatsDetail::Adaptive);
+
+    // Assert
+    assert!(snapshot.is_degraded());
+    assert!(output.contains("Error rate: 20.0%"), "{output}");
+    assert!(output.contains("Routes (window):"), "{output}");
+    assert!(output.contains("/api/users                       requests=8 5xx=0"));
+    assert!(output.contains("/api/orders                      requests=2 5xx=2"));
+    assert!(output.contains("Recent errors:\n  502 GET /api/orders\n  503 GET /api/orders?id=7\n"));
+}
+
+#[test]
+fn slow_traffic_renders_detail() {
+    // Arrange
+    let mut agg = aggregator();
+    let start = SystemTime::UNIX_EPOCH;
+    agg.push(&event("slow", "request", "/reports", 200, start));
+    agg.push(&event(
+        "slow",
+        "response",
+        "/reports",
+        200,
+        start + Duration::from_secs(3),
+    ));
+    let snapshot = agg.snapshot();
+
+    // Act
+    let output = render_stats(&snapshot, StatsDetail::Adaptive);
+
+    // Assert
+    assert_eq!(snapshot.p99_ms, 5000);
+    assert!(output.contains("Routes (window):"), "{output}");
+    assert!(!output.contains("Recent errors:"));
+}
+
+#[test]
+fn full_detail_renders_quiet_traffic_in_detail() {
+    // Arrange
+    let mut agg = aggregator();
+    push_request(&mut agg, "a", "/", 200);
+    let snapshot = agg.snapshot();
+
+    // Act
+    let output = render_stats(&snapshot, StatsDetail::Full);
+
+    // Assert
+    assert!(output.contains("Routes (window):"), "{output}");
+}
@@ -0,0 +1,103 @@
+use crate::cli::logs::stats_aggregation::StatsAggregator;
+use crate::cli::logs::types::{GenericEvent, LogEvent, SnakewayEvent};
+use pretty_assertions::assert_eq;
+use std::time::{Duration, SystemTime};
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+fn event(request_id: &str, name: &str, ts: SystemTime) -> LogEvent {
+    LogEvent::Snakeway(SnakewayEvent {
+        request_id: Some(request_id.to_string()),
+        level: "INFO".to_string(),
+        name: name.to_string(),
+        method: None,
+        uri: None,
+        status: Some(200),
+        ts: Some(ts),
+        identity: None,
+        bytes_in: None,
+        bytes_out: None,
+    })
+}
+
+fn tls_failure(reason: &str) -> LogEvent {
+    LogEvent::Generic(GenericEvent {
+        level: "WARN".to_string(),
+        message: "tls handshake failed".to_string(),
+        target: Some("snakeway_core::server::tls_handshake".to_string()),
+        name: Some("tls_handshake_failed".to_string()),
+        reason: Some(reason.to_string()),
+    })
+}
+
+/// Push a request/response pair that took `latency`.
+fn push_request(agg: &mut StatsAggregator, request_id: &str, latency: Duration) {
+    let start = SystemTime::UNIX_EPOCH;
+    agg.push(&event(request_id, "request", start));
+    agg.push(&event(request_id, "response", start + latency));
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn snapshot_uses_configured_latency_buckets() {
+    // Arrange
+    let mut agg = StatsAggregator::new(Duration::from_secs(10), &[1000, 5000, 30000]);
+    push_request(&mut agg, "a", Duration::from_millis(800));
+    push_request(&mut agg, "b", Duration::from_secs(4));
+    push_request(&mut agg, "c", Duration::from_secs(20));
+
+    // Act
+    let snapshot = agg.snapshot();
+
+    // Assert
+    assert_eq!(
+        snapshot.latency,
+        vec![
+            ("0–1000ms".to_string(), 1),
+            ("1001–5000ms".to_string(), 1),
+            ("5001–30000ms".to_string(), 1),
+            (">30000ms".to_string(), 0),
+        ]
+    );
+    assert_eq!(snapshot.p95_ms, 30000);
+}
+
+#[test]
+fn sub_millisecond_buckets_resolve_fast_percentiles() {
+    // Arrange
+    let mut agg = StatsAggregator::new(Duration::from_secs(10), &[1, 2, 3, 4, 5]);
+    for i in 0..20 {
+        let latency = if i < 19 { 2 } else { 4 };
+        push_request(&mut agg, &i.to_string(), Duration::from_millis(latency));
+    }
+
+    // Act
+    let snapshot = agg.snapshot();
+
+    // Assert
+    assert_eq!(snapshot.p95_ms, 2);
+    assert_eq!(snapshot.p99_ms, 4);
+}
+
+#[test]
+fn snapshot_counts_tls_handshake_failures_by_reason() {
+    // Arrange
+    let mut agg = StatsAggregator::new(Duration::from_secs(10), &[1000]);
+    agg.push(&tls_failure("not_tls"));
+    agg.push(&tls_failure("not_tls"));
+    agg.push(&tls_failure("certificate_expired"));
+
+    // Act
+    let snapshot = agg.snapshot();
+
+    // Assert
+    assert_eq!(snapshot.tls_failure_counts.get("not_tls"), Some(&2));
+    assert_eq!(
+        snapshot.tls_failure_counts.get("certificate_expired"),
+        Some(&1)
+    );
+    assert_eq!(snapshot.window_events, 0);
+}
@@ -17,6 +17,9 @@ pub struct SnakewayEvent {
     pub status: Option<i64>,
     pub ts: Option<SystemTime>,
     pub identity: Option<IdentitySummary>,
+    /// Body byte counts, present on `complete` events.
+    pub bytes_in: Option<u64>,
+    pub bytes_out: Option<u64>,
 }
 
 #[derive(Clone, Default)]
@@ -34,4 +37,8 @@ pub struct GenericEvent {
     pub level: String,
     pub message: String,
     pub target: Option<String>,
+    /// Event name, e.g. `tls_handshake_failed`.
+    pub name: Option<String>,
+    /// Classified failure reason, present on `tls_handshake_failed` events.
+    pub reason: Option<String>,
 }
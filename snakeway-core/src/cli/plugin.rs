@@ -1,5 +1,6 @@
 use crate::ctx::{NormalizedPath, RequestCtx};
 use crate::device::load_wasm_device;
+use crate::device::plugin::{HOST_CAPABILITIES, PluginBundle, PluginManifest};
 use anyhow::{Result, anyhow};
 use clap::{Args, Subcommand};
 use std::net::{IpAddr, Ipv4Addr};
@@ -9,6 +10,12 @@ use std::path::PathBuf;
 pub enum PluginCmd {
     /// Test a WASM plugin by invoking its exported hooks with a minimal ctx DTO.
     Test(PluginTestArgs),
+
+    /// Pack a WASM module and its plugin.hcl manifest into a plugin bundle.
+    Pack(PluginPackArgs),
+
+    /// Print a plugin bundle's manifest, and check that this host can run it.
+    Inspect(PluginInspectArgs),
 }
 
 #[derive(Args, Debug)]
@@ -25,12 +32,87 @@ pub struct PluginTestArgs {
     pub path: String,
 }
 
+#[derive(Args, Debug)]
+pub struct PluginPackArgs {
+    /// Path to the plugin manifest
+    #[arg(default_value = "plugin.hcl")]
+    pub manifest: PathBuf,
+
+    /// Directory to write the bundle to (default: <name>.plugin)
+    #[arg(long)]
+    pub out: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct PluginInspectArgs {
+    /// Path to the plugin bundle directory
+    pub bundle: PathBuf,
+}
+
 pub fn run(cmd: PluginCmd) -> Result<()> {
     match cmd {
         PluginCmd::Test(args) => run_test(args),
+        PluginCmd::Pack(args) => run_pack(args),
+        PluginCmd::Inspect(args) => run_inspect(args),
     }
 }
 
+fn run_pack(args: PluginPackArgs) -> Result<()> {
+    let out = match args.out {
+        Some(out) => out,
+        None => PathBuf::from(format!(
+            "{}.plugin",
+            PluginManifest::read(&args.manifest)?.name
+        )),
+    };
+
+    let bundle = PluginBundle::pack(&args.manifest, &out)?;
+    println!(
+        "Packed {} {} into {}",
+        bundle.manifest.name,
+        bundle.manifest.version,
+        bundle.dir.display()
+    );
+    Ok(())
+}
+
+fn run_inspect(args: PluginInspectArgs) -> Result<()> {
+    let bundle = PluginBundle::open(&args.bundle)?;
+    let manifest = &bundle.manifest;
+
+    println!("name:         {}", manifest.name);
+    println!("version:      {}", manifest.version);
+    println!("abi:          {}", manifest.abi);
+    println!("module:       {}", bundle.module_path().display());
+    if let Some(sha256) = &manifest.module_sha256 {
+        println!("sha256:       {sha256}");
+    }
+    let capabilities: Vec<String> = manifest
+        .capabilities
+        .iter()
+        .map(|c| {
+            if HOST_CAPABILITIES.contains(&c.as_str()) {
+                c.clone()
+            } else {
+                format!("{c} (unsupported)")
+            }
+        })
+        .collect();
+    println!("capabilities: {}", capabilities.join(", "));
+    if let Some(schema) = &manifest.config_schema {
+        println!("config schema:\n{}", serde_json::to_string_pretty(schema)?);
+    }
+    if let Some(defaults) = &manifest.default_config {
+        println!(
+            "default config:\n{}",
+            serde_json::to_string_pretty(defaults)?
+        );
+    }
+
+    manifest.check_host()?;
+    Ok(())
+}
+
 fn run_test(args: PluginTestArgs) -> Result<()> {
     tracing::info!(
         "Loading WASM device {} with hook {} against path {}",
@@ -1,3 +1,5 @@
+use crate::conf::load_config;
+use crate::runtime::RuntimeDiff;
 use anyhow::{Context, Result};
 use nix::sys::signal::{Signal, kill};
 use nix::unistd::Pid;
@@ -5,7 +7,18 @@ use std::fs;
 use std::path::Path;
 
 /// Send SIGHUP to a running Snakeway process via pid file.
-pub fn run<P: AsRef<Path>>(pid_file: P) -> Result<()> {
+///
+/// With `diff_from`, first print what changes between that config directory and `config`.
+pub fn run<P: AsRef<Path>>(pid_file: P, config: &Path, diff_from: Option<&Path>) -> Result<()> {
+    if let Some(old) = diff_from {
+        let old =
+            load_config(old).with_context(|| format!("failed to load config {}", old.display()))?;
+        let new = load_config(config)
+            .with_context(|| format!("failed to load config {}", config.display()))?;
+
+        print!("{}", RuntimeDiff::between(&old.config, &new.config));
+    }
+
     let pid_file = pid_file.as_ref();
 
     let contents = fs::read_to_string(pid_file)
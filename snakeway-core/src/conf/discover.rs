@@ -49,3 +49,44 @@ pub fn discover(root: &Path, glob_pattern: &str) -> Result<Vec<PathBuf>, ConfigE
 pub fn resolve_glob(root: &Path, pattern: &str) -> String {
     root.join(pattern).to_string_lossy().into_owned()
 }
+
+/// Directive that pulls another ingress file into the including one: `include "shared/admin.hcl"`.
+const INCLUDE_DIRECTIVE: &str = "include";
+
+/// Splits `include "path"` directives out of an ingress file.
+///
+/// A directive sits on a line of its own. Returns the source with each directive blanked out,
+/// so HCL errors keep their line numbers, and the included paths in declaration order.
+/// Relative paths are resolved against the including file's directory.
+pub fn discover_includes(path: &Path, source: &str) -> (String, Vec<PathBuf>) {
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let mut includes = Vec::new();
+
+    let stripped = source
+        .lines()
+        .map(|line| match parse_include(line) {
+            Some(include) => {
+                includes.push(dir.join(include));
+                ""
+            }
+            None => line,
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    (stripped, includes)
+}
+
+/// The quoted path of an `include "path"` line.
+fn parse_include(line: &str) -> Option<&str> {
+    let rest = line.trim().strip_prefix(INCLUDE_DIRECTIVE)?;
+    // `include = ...` and `includes` are ordinary attributes.
+    if !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+
+    rest.trim()
+        .strip_prefix('"')?
+        .strip_suffix('"')
+        .filter(|p| !p.is_empty() && !p.contains('"'))
+}
@@ -0,0 +1,187 @@
+use crate::conf::types::Origin;
+use crate::conf::validation::ConfigError;
+use std::path::Path;
+
+/// Opens an environment variable reference, e.g. `${env.PORT}` or `${env.PORT:-8080}`.
+const ENV_REFERENCE_PREFIX: &str = "${env.";
+
+/// Separates the variable name from its default value.
+const DEFAULT_SEPARATOR: &str = ":-";
+
+/// Where the scanner is in the HCL source.
+#[derive(Clone, Copy, PartialEq)]
+enum Scan {
+    Code,
+    String,
+    LineComment,
+    BlockComment,
+}
+
+/// Replace `${env.NAME}` and `${env.NAME:-default}` references with environment variable values.
+///
+/// Runs on the raw HCL text, so references work anywhere a value does: `port = ${env.PORT}`
+/// becomes `port = 8080`, and `"${env.HOST}"` is replaced inside the string, escaped as needed.
+/// References in comments are left alone, and `$${env.NAME}` stays a literal `${env.NAME}`.
+pub(crate) fn interpolate_env(path: &Path, source: &str) -> Result<String, ConfigError> {
+    interpolate_with(path, source, |name| std::env::var(name).ok())
+}
+
+/// [`interpolate_env`] with a custom variable lookup.
+pub(crate) fn interpolate_with(
+    path: &Path,
+    source: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<String, ConfigError> {
+    let mut out = String::with_capacity(source.len());
+    let mut scan = Scan::Code;
+    let mut depth = 0usize;
+    let mut block = String::new();
+    let mut line = 1;
+    let mut line_start = 0;
+    let mut i = 0;
+
+    while i < source.len() {
+        let rest = &source[i..];
+        let c = rest.chars().next().unwrap_or_default();
+
+        // `$${` is HCL's escape for a literal `${`.
+        if scan == Scan::Code || scan == Scan::String {
+            if rest.starts_with("$${") {
+                out.push_str("$${");
+                i += 3;
+                continue;
+            }
+
+            if rest.starts_with(ENV_REFERENCE_PREFIX)
+                && let Some(len) = rest.find('}')
+            {
+                let reference = &rest[ENV_REFERENCE_PREFIX.len()..len];
+                let (name, default) = match reference.split_once(DEFAULT_SEPARATOR) {
+                    Some((name, default)) => (name, Some(default)),
+                    None => (reference, None),
+                };
+
+                if is_variable_name(name) {
+                    let value = lookup(name)
+                        .or_else(|| default.map(str::to_string))
+                        .ok_or_else(|| ConfigError::MissingEnvVar {
+                            name: name.to_string(),
+                            field: field_name(&source[line_start..i]),
+                            line,
+                            origin: Origin::new(&path.to_path_buf(), &block, None),
+                        })?;
+
+                    match scan {
+                        Scan::String => push_escaped(&mut out, &value),
+                        _ => out.push_str(&value),
+                    }
+                    i += len + 1;
+                    continue;
+                }
+            }
+        }
+
+        match scan {
+            Scan::Code => match c {
+                '"' => scan = Scan::String,
+                '#' => scan = Scan::LineComment,
+                '/' if rest.starts_with("//") => scan = Scan::LineComment,
+                '/' if rest.starts_with("/*") => {
+                    scan = Scan::BlockComment;
+                    out.push_str("/*");
+                    i += 2;
+                    continue;
+                }
+                '{' | '[' => {
+                    if depth == 0 {
+                        block = block_name(&source[line_start..i]);
+                    }
+                    depth += 1;
+                }
+                '}' | ']' => depth = depth.saturating_sub(1),
+                _ => {}
+            },
+            Scan::String => match c {
+                '\\' => {
+                    // Copy the escaped character along with the backslash.
+                    let escaped = rest[1..].chars().next().map_or(0, char::len_utf8);
+                    out.push_str(&rest[..1 + escaped]);
+                    i += 1 + escaped;
+                    continue;
+                }
+                '"' | '\n' => scan = Scan::Code,
+                _ => {}
+            },
+            Scan::LineComment => {
+                if c == '\n' {
+                    scan = Scan::Code;
+                }
+            }
+            Scan::BlockComment => {
+                if rest.starts_with("*/") {
+                    scan = Scan::Code;
+                    out.push_str("*/");
+                    i += 2;
+                    continue;
+                }
+            }
+        }
+
+        if c == '\n' {
+            line += 1;
+            line_start = i + 1;
+        }
+        out.push(c);
+        i += c.len_utf8();
+    }
+
+    Ok(out)
+}
+
+fn is_variable_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// The name of a top-level block or attribute, from the text before its opening brace.
+fn block_name(line: &str) -> String {
+    line.split(|c: char| c.is_whitespace() || c == '=')
+        .find(|token| !token.is_empty())
+        .unwrap_or_default()
+        .trim_matches('"')
+        .to_string()
+}
+
+/// The attribute being assigned, from the text before a reference on its line.
+fn field_name(line: &str) -> String {
+    line.rsplit_once('=')
+        .and_then(|(key, _)| {
+            key.split(|c: char| c.is_whitespace() || c == '{' || c == ',')
+                .rfind(|token| !token.is_empty())
+        })
+        .map(|key| key.trim_matches('"'))
+        .unwrap_or("value")
+        .to_string()
+}
+
+/// Escape a value for use inside an HCL string literal.
+fn push_escaped(out: &mut String, value: &str) {
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '$' | '%' if chars.peek() == Some(&'{') => {
+                out.push(c);
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+}
@@ -1,6 +1,9 @@
 use crate::conf::discover::discover;
+use crate::conf::interpolation::interpolate_env;
 use crate::conf::lower::lower_configs;
+use crate::conf::merge::apply_service_defaults;
 use crate::conf::parse::{parse_devices, parse_ingress};
+use crate::conf::secrets::{SecretResolver, resolve_service_secrets, resolve_tls_secrets};
 use crate::conf::types::{
     DeviceSpec, EntrypointSpec, IngressSpec, Origin, RuntimeConfig, ServerSpec,
 };
@@ -11,15 +14,28 @@ use std::fs;
 use std::path::Path;
 
 pub fn load_config(root: &Path) -> Result<ValidatedConfig, ConfigError> {
+    load_config_with_secrets(root, &SecretResolver::new(root))
+}
+
+/// Load a config, resolving secret references with the given resolver.
+/// Use this to add secret providers beyond the built-in `env` and `file`.
+pub fn load_config_with_secrets(
+    root: &Path,
+    secrets: &SecretResolver,
+) -> Result<ValidatedConfig, ConfigError> {
     let (server_spec, device_specs, ingresses) = load_spec_config(root)?;
 
     // Semantic validation of DSL config (aggregate all semantic errors)
     let validation_report = validate_spec(&server_spec, &ingresses, &device_specs);
 
     // Convert spec to runtime config.
-    let (server, listeners, routes, services, devices) =
+    let (server, mut listeners, routes, mut services, devices) =
         lower_configs(server_spec, ingresses, device_specs)?;
 
+    // Resolve secret references into their values (hard fail).
+    resolve_tls_secrets(&mut listeners, secrets)?;
+    resolve_service_secrets(&mut services, secrets)?;
+
     //--------------------------------------------------------------------------
     // Build runtime config
     //--------------------------------------------------------------------------
@@ -47,6 +63,7 @@ pub fn load_spec_config(root: &Path) -> Result<Spec, ConfigError> {
         source: e,
     })?;
 
+    let entry = interpolate_env(&root_path, &entry)?;
     let mut entry: EntrypointSpec = hcl::from_str(&entry).map_err(|e| ConfigError::Parse {
         path: root_path.to_path_buf(),
         source: e,
@@ -60,6 +77,13 @@ pub fn load_spec_config(root: &Path) -> Result<Spec, ConfigError> {
     let device_files = discover(root, &entry.include.devices)?;
     let ingress_files = discover(root, &entry.include.ingress)?;
 
+    // Binds are only declared in ingress files, so without any there is nothing to listen on.
+    if ingress_files.is_empty() {
+        return Err(ConfigError::NoIngressFiles {
+            pattern: entry.include.ingress,
+        });
+    }
+
     //--------------------------------------------------------------------------
     // Parse devices (hard fail)
     //--------------------------------------------------------------------------
@@ -71,10 +95,15 @@ pub fn load_spec_config(root: &Path) -> Result<Spec, ConfigError> {
     //--------------------------------------------------------------------------
     // Parse ingress (hard fail)
     //--------------------------------------------------------------------------
-    let ingresses = ingress_files
+    let mut ingresses = ingress_files
         .iter()
         .map(|p| parse_ingress(p.as_path()))
         .collect::<Result<Vec<_>, _>>()?;
 
+    // Services inherit the server's defaults for settings they leave unset.
+    if let Some(defaults) = &entry.server.defaults {
+        apply_service_defaults(defaults, &mut ingresses);
+    }
+
     Ok((entry.server, parsed_devices, ingresses))
 }
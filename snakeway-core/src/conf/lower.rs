@@ -1,6 +1,7 @@
 use crate::conf::types::{
-    DeviceConfig, DeviceSpec, IngressSpec, ListenerConfig, RouteConfig, ServerConfig, ServerSpec,
-    ServiceConfig, ServiceRouteConfig, StaticRouteConfig, UpstreamTcpConfig, UpstreamUnixConfig,
+    DeviceConfig, DeviceSpec, GeoIpConfig, IngressSpec, ListenerConfig, RouteConfig, ServerConfig,
+    ServerSpec, ServiceConfig, ServiceRouteConfig, StaticRouteConfig, UpstreamConnectionConfig,
+    UpstreamSpec, UpstreamTcpConfig, UpstreamUnixConfig,
 };
 use crate::conf::validation::ConfigError;
 use std::collections::HashMap;
@@ -14,6 +15,10 @@ pub type IrConfig = (
     Vec<DeviceConfig>,
 );
 
+/// Default time to drain in-flight requests on shutdown.
+const DEFAULT_SHUTDOWN_TIMEOUT_SECONDS: u64 = 30;
+const DEFAULT_CONNECTION_LOG_SAMPLE_RATE: f64 = 1.0;
+
 /// Transform spec to the runtime configuration.
 ///
 /// Assumes all specs have already passed validation.
@@ -25,11 +30,39 @@ pub fn lower_configs(
     // ---------------------------------------------------------------------
     // Server
     // ---------------------------------------------------------------------
+    let strict_wasm_devices = server_spec.strict_wasm_devices();
     let server = ServerConfig {
         version: server_spec.version,
         threads: server_spec.threads,
         pid_file: server_spec.pid_file.unwrap_or_default(),
         ca_file: server_spec.ca_file.unwrap_or_default(),
+        shutdown_timeout_seconds: server_spec
+            .shutdown_timeout_seconds
+            .unwrap_or(DEFAULT_SHUTDOWN_TIMEOUT_SECONDS),
+        shutdown_readiness_grace_seconds: server_spec
+            .shutdown_readiness_grace_seconds
+            .unwrap_or_default(),
+        strict_host: server_spec.strict_host,
+        hide_as_404: server_spec.hide_as_404,
+        ws_max_connections: server_spec.ws_max_connections,
+        max_request_body_bytes: server_spec.max_request_body_bytes,
+        request_timeout_milliseconds: server_spec.request_timeout_milliseconds,
+        reuse_port: server_spec.reuse_port,
+        connection_log_sample_rate: server_spec
+            .connection_log_sample_rate
+            .unwrap_or(DEFAULT_CONNECTION_LOG_SAMPLE_RATE),
+        strict_wasm_devices,
+        trusted_proxies: server_spec.trusted_proxies,
+        access_log: server_spec.access_log,
+        access_log_format: server_spec.access_log_format,
+        geoip: server_spec
+            .geoip
+            .map(|geoip| GeoIpConfig {
+                city_db: geoip.city_db,
+                isp_db: geoip.isp_db,
+                connection_type_db: geoip.connection_type_db,
+            })
+            .unwrap_or_default(),
     };
 
     let mut listeners = Vec::new();
@@ -46,7 +79,11 @@ pub fn lower_configs(
         // Admin bind
         // -------------------------------------------------------------
         if let Some(bind_admin) = ingress.bind_admin {
-            listeners.push(ListenerConfig::from_bind_admin(&listener_name, bind_admin));
+            let admin_listener_name = format!("admin-listener-{}", idx);
+            listeners.push(ListenerConfig::from_bind_admin(
+                &admin_listener_name,
+                bind_admin,
+            ));
         }
 
         //--------------------------------------------------------------------
@@ -63,13 +100,27 @@ pub fn lower_configs(
             // Services
             //-----------------------------------------------------------------
             for service_spec in ingress.services {
+                // Upstream settings override the service's, which override built-in defaults.
+                let connection = |u: &UpstreamSpec| {
+                    UpstreamConnectionConfig::resolve(
+                        service_spec.connection.as_ref(),
+                        u.connection.as_ref(),
+                    )
+                };
+
                 let unix_upstreams = service_spec
                     .upstreams
                     .iter()
                     .filter_map(|u| {
-                        u.sock
-                            .as_ref()
-                            .map(|sock| UpstreamUnixConfig::new(sock.clone(), use_tls, u.weight))
+                        u.sock.as_ref().map(|sock| {
+                            UpstreamUnixConfig::new(
+                                sock.clone(),
+                                use_tls,
+                                u.weight,
+                                connection(u),
+                                u.group.clone(),
+                            )
+                        })
                     })
                     .collect::<Vec<_>>();
 
@@ -77,9 +128,15 @@ pub fn lower_configs(
                     .upstreams
                     .iter()
                     .filter_map(|u| {
-                        u.endpoint
-                            .as_ref()
-                            .map(|endpoint| UpstreamTcpConfig::new(use_tls, u.weight, endpoint))
+                        u.endpoint.as_ref().map(|endpoint| {
+                            UpstreamTcpConfig::new(
+                                use_tls,
+                                u.weight,
+                                connection(u),
+                                u.group.clone(),
+                                endpoint,
+                            )
+                        })
                     })
                     .collect::<Result<Vec<_>, _>>()
                     .expect("upstream.resolve() must not fail");
@@ -151,6 +208,24 @@ pub fn lower_configs(
             DeviceSpec::Identity(d) => Ok(DeviceConfig::Identity(d.into())),
             DeviceSpec::RequestFilter(d) => d.try_into().map(DeviceConfig::RequestFilter),
             DeviceSpec::StructuredLogging(d) => Ok(DeviceConfig::StructuredLogging(d.into())),
+            DeviceSpec::OpenApi(d) => Ok(DeviceConfig::OpenApi(d.into())),
+            DeviceSpec::JwtAuth(d) => Ok(DeviceConfig::JwtAuth(d.into())),
+            DeviceSpec::Quota(d) => Ok(DeviceConfig::Quota(d.into())),
+            DeviceSpec::BodyDigest(d) => Ok(DeviceConfig::BodyDigest(d.into())),
+            DeviceSpec::UpstreamGroup(d) => Ok(DeviceConfig::UpstreamGroup(d.into())),
+            DeviceSpec::RequiredHeaders(d) => Ok(DeviceConfig::RequiredHeaders(d.into())),
+            DeviceSpec::StatusRemap(d) => Ok(DeviceConfig::StatusRemap(d.into())),
+            DeviceSpec::ResponseHeaders(d) => Ok(DeviceConfig::ResponseHeaders(d.into())),
+            DeviceSpec::IpReputation(d) => Ok(DeviceConfig::IpReputation(d.into())),
+            DeviceSpec::RateLimit(d) => Ok(DeviceConfig::RateLimit(d.into())),
+            DeviceSpec::Experiment(d) => Ok(DeviceConfig::Experiment(d.into())),
+            DeviceSpec::HeaderRewrite(d) => Ok(DeviceConfig::HeaderRewrite(d.into())),
+            DeviceSpec::QueryRewrite(d) => Ok(DeviceConfig::QueryRewrite(d.into())),
+            DeviceSpec::ErrorPage(d) => Ok(DeviceConfig::ErrorPage(d.into())),
+            DeviceSpec::Cors(d) => Ok(DeviceConfig::Cors(d.into())),
+            DeviceSpec::Idempotency(d) => Ok(DeviceConfig::Idempotency(d.into())),
+            DeviceSpec::Deprecation(d) => Ok(DeviceConfig::Deprecation(d.into())),
+            DeviceSpec::Lua(d) => Ok(DeviceConfig::Lua(d.into())),
         })
         .collect::<Result<Vec<_>, _>>()?;
 
@@ -0,0 +1,71 @@
+use crate::conf::types::{IngressSpec, ServiceDefaultsSpec, UpstreamConnectionSpec};
+use crate::conf::validation::ConfigError;
+
+/// Merge an included ingress file into the file that includes it.
+///
+/// Services and static files are appended after the including file's own, and keep the origin
+/// of the file that declared them. `bind` and `bind_admin` can each be declared by only one of
+/// the two files.
+pub fn merge_ingress(base: &mut IngressSpec, included: IngressSpec) -> Result<(), ConfigError> {
+    match (&base.bind, included.bind) {
+        (Some(bind), Some(other)) => {
+            return Err(ConfigError::IncludeConflict {
+                field: "bind".to_string(),
+                origin: bind.origin.to_string(),
+                included: other.origin.to_string(),
+            });
+        }
+        (None, Some(other)) => base.bind = Some(other),
+        _ => {}
+    }
+
+    match (&base.bind_admin, included.bind_admin) {
+        (Some(bind_admin), Some(other)) => {
+            return Err(ConfigError::IncludeConflict {
+                field: "bind_admin".to_string(),
+                origin: bind_admin.origin.to_string(),
+                included: other.origin.to_string(),
+            });
+        }
+        (None, Some(other)) => base.bind_admin = Some(other),
+        _ => {}
+    }
+
+    base.services.extend(included.services);
+    base.static_files.extend(included.static_files);
+
+    Ok(())
+}
+
+/// Fill in the settings each service leaves unset from the server's `defaults`.
+///
+/// Connection settings are inherited field by field. Health check and circuit breaker
+/// blocks are inherited whole, by services that declare none.
+pub fn apply_service_defaults(defaults: &ServiceDefaultsSpec, ingresses: &mut [IngressSpec]) {
+    for service in ingresses.iter_mut().flat_map(|i| i.services.iter_mut()) {
+        if let Some(connection) = &defaults.connection {
+            let own = service.connection.get_or_insert_with(Default::default);
+            inherit_connection(own, connection);
+        }
+        if service.health_check.is_none() {
+            service.health_check = defaults.health_check.clone();
+        }
+        if service.circuit_breaker.is_none() {
+            service.circuit_breaker = defaults.circuit_breaker.clone();
+        }
+    }
+}
+
+fn inherit_connection(own: &mut UpstreamConnectionSpec, defaults: &UpstreamConnectionSpec) {
+    own.connect_timeout_milliseconds = own
+        .connect_timeout_milliseconds
+        .or(defaults.connect_timeout_milliseconds);
+    own.read_timeout_milliseconds = own
+        .read_timeout_milliseconds
+        .or(defaults.read_timeout_milliseconds);
+    own.idle_timeout_seconds = own.idle_timeout_seconds.or(defaults.idle_timeout_seconds);
+    own.response_idle_timeout_milliseconds = own
+        .response_idle_timeout_milliseconds
+        .or(defaults.response_idle_timeout_milliseconds);
+    own.max_connect_retries = own.max_connect_retries.or(defaults.max_connect_retries);
+}
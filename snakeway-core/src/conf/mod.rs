@@ -1,13 +1,21 @@
 mod discover;
+mod interpolation;
 mod loader;
 mod lower;
+mod merge;
 mod parse;
 mod resolution;
+mod schema;
+mod secrets;
 #[cfg(test)]
 mod tests;
 pub mod types;
 pub(crate) mod validation;
 
-pub use loader::{load_config, load_spec_config};
+pub use loader::{load_config, load_config_with_secrets, load_spec_config};
+pub use schema::{ConfigFileKind, config_schema};
+pub use secrets::{
+    EnvSecretProvider, FileSecretProvider, Secret, SecretError, SecretProvider, SecretResolver,
+};
 pub use types::RuntimeConfig;
 pub use validation::ValidatedConfig;
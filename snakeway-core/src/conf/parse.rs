@@ -1,39 +1,111 @@
+use crate::conf::discover::discover_includes;
+use crate::conf::interpolation::interpolate_env;
+use crate::conf::merge::merge_ingress;
 use crate::conf::types::{
-    BindAdminSpec, BindSpec, DeviceSpec, IdentityDeviceSpec, IngressSpec, Origin,
-    RequestFilterDeviceSpec, ServiceSpec, StaticFilesSpec, StructuredLoggingDeviceSpec,
-    WasmDeviceSpec,
+    BindAdminSpec, BindSpec, BodyDigestDeviceSpec, CorsDeviceSpec, DeprecationDeviceSpec,
+    DeviceSpec, ErrorPageDeviceSpec, ExperimentDeviceSpec, HeaderRewriteDeviceSpec,
+    IdempotencyDeviceSpec, IdentityDeviceSpec, IngressSpec, IpReputationDeviceSpec,
+    JwtAuthDeviceSpec, LuaDeviceSpec, OpenApiDeviceSpec, Origin, QueryRewriteDeviceSpec,
+    QuotaDeviceSpec, RateLimitDeviceSpec, RequestFilterDeviceSpec, RequiredHeadersDeviceSpec,
+    ResponseHeadersDeviceSpec, ServiceSpec, StaticFilesSpec, StatusRemapDeviceSpec,
+    StructuredLoggingDeviceSpec, UpstreamGroupDeviceSpec, WasmDeviceSpec,
 };
 use crate::conf::validation::ConfigError;
+use schemars::JsonSchema;
 use serde::Deserialize;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-#[derive(Debug, Deserialize, Default)]
-struct DevicesFile {
+/// A file matched by the entrypoint's `devices` pattern.
+#[derive(Debug, Deserialize, JsonSchema, Default)]
+pub(crate) struct DevicesFile {
     identity_device: Option<IdentityDeviceSpec>,
     structured_logging_device: Option<StructuredLoggingDeviceSpec>,
 
     #[serde(default)]
     request_filter_device: Option<RequestFilterDeviceSpec>,
 
+    #[serde(default)]
+    openapi_device: Option<OpenApiDeviceSpec>,
+
+    #[serde(default)]
+    jwt_auth_device: Option<JwtAuthDeviceSpec>,
+
+    #[serde(default)]
+    quota_device: Option<QuotaDeviceSpec>,
+
+    #[serde(default)]
+    body_digest_device: Option<BodyDigestDeviceSpec>,
+
+    #[serde(default)]
+    upstream_group_device: Option<UpstreamGroupDeviceSpec>,
+
+    #[serde(default)]
+    required_headers_device: Option<RequiredHeadersDeviceSpec>,
+
+    #[serde(default)]
+    status_remap_device: Option<StatusRemapDeviceSpec>,
+
+    #[serde(default)]
+    error_page_device: Option<ErrorPageDeviceSpec>,
+
+    #[serde(default)]
+    response_headers_device: Option<ResponseHeadersDeviceSpec>,
+
+    #[serde(default)]
+    deprecation_device: Option<DeprecationDeviceSpec>,
+
+    #[serde(default)]
+    ip_reputation_device: Option<IpReputationDeviceSpec>,
+
+    #[serde(default)]
+    rate_limit_device: Option<RateLimitDeviceSpec>,
+
+    #[serde(default)]
+    experiment_device: Option<ExperimentDeviceSpec>,
+
+    #[serde(default)]
+    header_rewrite_device: Option<HeaderRewriteDeviceSpec>,
+
+    #[serde(default)]
+    query_rewrite_device: Option<QueryRewriteDeviceSpec>,
+
+    #[serde(default)]
+    cors_device: Option<CorsDeviceSpec>,
+
+    #[serde(default)]
+    idempotency_device: Option<IdempotencyDeviceSpec>,
+
+    #[serde(default)]
+    lua_device: Option<LuaDeviceSpec>,
+
     #[serde(default)]
     wasm_devices: Vec<WasmDeviceSpec>,
 }
 
 pub fn parse_devices(path: &Path) -> Result<Vec<DeviceSpec>, ConfigError> {
     let s = fs::read_to_string(path).map_err(|e| ConfigError::read_file(path, e))?;
+    let s = interpolate_env(path, &s)?;
     let parsed: DevicesFile = hcl::from_str(&s).map_err(|e| ConfigError::parse(path, e))?;
 
     let mut device_config = Vec::new();
 
+    // Preflights never carry credentials, so CORS runs before devices that could reject them.
+    if let Some(mut cors) = parsed.cors_device {
+        cors.origin = Origin::new(&path.to_path_buf(), "cors_device", None);
+        device_config.push(DeviceSpec::Cors(cors));
+    }
+
     if let Some(mut identity) = parsed.identity_device {
         identity.origin = Origin::new(&path.to_path_buf(), "identity_device", None);
         device_config.push(DeviceSpec::Identity(identity));
     }
 
-    if let Some(mut logging) = parsed.structured_logging_device {
-        logging.origin = Origin::new(&path.to_path_buf(), "structured_logging_device", None);
-        device_config.push(DeviceSpec::StructuredLogging(logging));
+    // Header rewrites run right after identity, which reads the client's own headers, so a
+    // required header can be filled in and stateful devices read the rewritten request.
+    if let Some(mut header_rewrite) = parsed.header_rewrite_device {
+        header_rewrite.origin = Origin::new(&path.to_path_buf(), "header_rewrite_device", None);
+        device_config.push(DeviceSpec::HeaderRewrite(header_rewrite));
     }
 
     if let Some(mut request_filter) = parsed.request_filter_device {
@@ -41,16 +113,106 @@ pub fn parse_devices(path: &Path) -> Result<Vec<DeviceSpec>, ConfigError> {
         device_config.push(DeviceSpec::RequestFilter(request_filter));
     }
 
+    if let Some(mut openapi) = parsed.openapi_device {
+        openapi.origin = Origin::new(&path.to_path_buf(), "openapi_device", None);
+        device_config.push(DeviceSpec::OpenApi(openapi));
+    }
+
+    if let Some(mut jwt_auth) = parsed.jwt_auth_device {
+        jwt_auth.origin = Origin::new(&path.to_path_buf(), "jwt_auth_device", None);
+        device_config.push(DeviceSpec::JwtAuth(jwt_auth));
+    }
+
+    if let Some(mut quota) = parsed.quota_device {
+        quota.origin = Origin::new(&path.to_path_buf(), "quota_device", None);
+        device_config.push(DeviceSpec::Quota(quota));
+    }
+
+    if let Some(mut body_digest) = parsed.body_digest_device {
+        body_digest.origin = Origin::new(&path.to_path_buf(), "body_digest_device", None);
+        device_config.push(DeviceSpec::BodyDigest(body_digest));
+    }
+
+    if let Some(mut upstream_group) = parsed.upstream_group_device {
+        upstream_group.origin = Origin::new(&path.to_path_buf(), "upstream_group_device", None);
+        device_config.push(DeviceSpec::UpstreamGroup(upstream_group));
+    }
+
+    if let Some(mut required_headers) = parsed.required_headers_device {
+        required_headers.origin = Origin::new(&path.to_path_buf(), "required_headers_device", None);
+        device_config.push(DeviceSpec::RequiredHeaders(required_headers));
+    }
+
+    if let Some(mut status_remap) = parsed.status_remap_device {
+        status_remap.origin = Origin::new(&path.to_path_buf(), "status_remap_device", None);
+        device_config.push(DeviceSpec::StatusRemap(status_remap));
+    }
+
+    // Error pages replace upstream responses after their statuses are remapped.
+    if let Some(mut error_page) = parsed.error_page_device {
+        error_page.origin = Origin::new(&path.to_path_buf(), "error_page_device", None);
+        device_config.push(DeviceSpec::ErrorPage(error_page));
+    }
+
+    if let Some(mut response_headers) = parsed.response_headers_device {
+        response_headers.origin = Origin::new(&path.to_path_buf(), "response_headers_device", None);
+        device_config.push(DeviceSpec::ResponseHeaders(response_headers));
+    }
+
+    if let Some(mut deprecation) = parsed.deprecation_device {
+        deprecation.origin = Origin::new(&path.to_path_buf(), "deprecation_device", None);
+        device_config.push(DeviceSpec::Deprecation(deprecation));
+    }
+
+    if let Some(mut ip_reputation) = parsed.ip_reputation_device {
+        ip_reputation.origin = Origin::new(&path.to_path_buf(), "ip_reputation_device", None);
+        device_config.push(DeviceSpec::IpReputation(ip_reputation));
+    }
+
+    if let Some(mut rate_limit) = parsed.rate_limit_device {
+        rate_limit.origin = Origin::new(&path.to_path_buf(), "rate_limit_device", None);
+        device_config.push(DeviceSpec::RateLimit(rate_limit));
+    }
+
+    if let Some(mut experiment) = parsed.experiment_device {
+        experiment.origin = Origin::new(&path.to_path_buf(), "experiment_device", None);
+        device_config.push(DeviceSpec::Experiment(experiment));
+    }
+
+    if let Some(mut query_rewrite) = parsed.query_rewrite_device {
+        query_rewrite.origin = Origin::new(&path.to_path_buf(), "query_rewrite_device", None);
+        device_config.push(DeviceSpec::QueryRewrite(query_rewrite));
+    }
+
+    if let Some(mut idempotency) = parsed.idempotency_device {
+        idempotency.origin = Origin::new(&path.to_path_buf(), "idempotency_device", None);
+        device_config.push(DeviceSpec::Idempotency(idempotency));
+    }
+
+    // Scripted devices run after the builtin ones, Lua before WASM.
+    if let Some(mut lua) = parsed.lua_device {
+        lua.origin = Origin::new(&path.to_path_buf(), "lua_device", None);
+        device_config.push(DeviceSpec::Lua(lua));
+    }
+
     for (idx, mut device) in parsed.wasm_devices.into_iter().enumerate() {
         device.origin = Origin::new(&path.to_path_buf(), "wasm_device", idx.into());
         device_config.push(DeviceSpec::Wasm(device));
     }
 
+    // Logging runs last on the request, so it observes every other device's changes, and so
+    // first on the response, before other devices rewrite it.
+    if let Some(mut logging) = parsed.structured_logging_device {
+        logging.origin = Origin::new(&path.to_path_buf(), "structured_logging_device", None);
+        device_config.push(DeviceSpec::StructuredLogging(logging));
+    }
+
     Ok(device_config)
 }
 
-#[derive(Debug, Deserialize)]
-struct IngressFile {
+/// A file matched by the entrypoint's `ingress` pattern.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct IngressFile {
     bind: Option<BindSpec>,
 
     bind_admin: Option<BindAdminSpec>,
@@ -62,9 +224,40 @@ struct IngressFile {
     static_files: Vec<StaticFilesSpec>,
 }
 
+/// Parse an ingress file, merging in the files it includes.
 pub fn parse_ingress(path: &Path) -> Result<IngressSpec, ConfigError> {
+    parse_ingress_including(path, &mut Vec::new())
+}
+
+/// `chain` holds the files currently being parsed, from the top-level file down, to detect cycles.
+fn parse_ingress_including(
+    path: &Path,
+    chain: &mut Vec<PathBuf>,
+) -> Result<IngressSpec, ConfigError> {
+    let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    if let Some(start) = chain.iter().position(|p| *p == canonical) {
+        let mut cycle = chain[start..].to_vec();
+        cycle.push(canonical);
+        return Err(ConfigError::IncludeCycle { chain: cycle });
+    }
+
     let s = fs::read_to_string(path).map_err(|e| ConfigError::read_file(path, e))?;
-    let mut parsed: IngressFile = hcl::from_str(&s).map_err(|e| ConfigError::parse(path, e))?;
+    let s = interpolate_env(path, &s)?;
+    let (s, includes) = discover_includes(path, &s);
+    let mut spec = parse_ingress_source(path, &s)?;
+
+    chain.push(canonical);
+    for include in includes {
+        let included = parse_ingress_including(&include, chain)?;
+        merge_ingress(&mut spec, included)?;
+    }
+    chain.pop();
+
+    Ok(spec)
+}
+
+fn parse_ingress_source(path: &Path, s: &str) -> Result<IngressSpec, ConfigError> {
+    let mut parsed: IngressFile = hcl::from_str(s).map_err(|e| ConfigError::parse(path, e))?;
 
     //-------------------------------------------------------------------------
     // Inject origin metadata
@@ -0,0 +1,39 @@
+use crate::conf::parse::{DevicesFile, IngressFile};
+use crate::conf::types::EntrypointSpec;
+use schemars::{Schema, schema_for};
+use std::str::FromStr;
+
+/// The kinds of file a config directory is made of, each with its own schema.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigFileKind {
+    /// The top-level `snakeway.hcl`.
+    Entrypoint,
+    /// Files matched by the entrypoint's `ingress` pattern.
+    Ingress,
+    /// Files matched by the entrypoint's `devices` pattern.
+    Devices,
+}
+
+impl FromStr for ConfigFileKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "entrypoint" => Ok(Self::Entrypoint),
+            "ingress" => Ok(Self::Ingress),
+            "devices" => Ok(Self::Devices),
+            _ => Err(anyhow::anyhow!("invalid config file kind: {}", s)),
+        }
+    }
+}
+
+/// JSON Schema for one kind of config file, derived from the types the file is parsed into.
+///
+/// HCL maps onto JSON one to one, so a file converted to JSON can be checked against it.
+pub fn config_schema(kind: ConfigFileKind) -> Schema {
+    match kind {
+        ConfigFileKind::Entrypoint => schema_for!(EntrypointSpec),
+        ConfigFileKind::Ingress => schema_for!(IngressFile),
+        ConfigFileKind::Devices => schema_for!(DevicesFile),
+    }
+}
@@ -0,0 +1,222 @@
+use crate::conf::types::{ListenerConfig, ServiceConfig};
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Config values starting with this prefix are secret references, e.g. `secret://env/TLS_KEY`.
+pub const SECRET_REFERENCE_PREFIX: &str = "secret://";
+
+const REDACTED: &str = "<redacted>";
+
+/// A resolved secret value.
+///
+/// Debug and Serialize output are redacted, so secrets never end up in config dumps or logs.
+/// Use `expose` to read the value.
+#[derive(Clone, PartialEq, Eq, JsonSchema)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    /// Write the secret to a file only the current user can read, and return its path.
+    ///
+    /// For consumers that only accept file paths, such as TLS listeners.
+    pub fn materialize(&self, name: &str) -> std::io::Result<PathBuf> {
+        let dir = std::env::temp_dir().join(format!("snakeway-secrets-{}", std::process::id()));
+        fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(&dir)?;
+
+        let path = dir.join(name);
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(&path)?;
+        file.write_all(self.0.as_bytes())?;
+
+        Ok(path)
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl Serialize for Secret {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(REDACTED)
+    }
+}
+
+impl<'de> Deserialize<'de> for Secret {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Secret)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SecretError {
+    #[error("invalid secret reference '{0}': expected secret://<provider>/<name>")]
+    InvalidReference(String),
+
+    #[error("unknown secret provider '{provider}' in '{reference}'")]
+    UnknownProvider { provider: String, reference: String },
+
+    #[error("secret '{0}' not found")]
+    NotFound(String),
+
+    #[error("failed to read secret '{reference}': {source}")]
+    Read {
+        reference: String,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+/// Fetches secrets by name from one backend, e.g. the environment or a secret manager.
+///
+/// Providers are registered on a `SecretResolver` under the name used in references:
+/// `secret://<provider>/<name>`.
+pub trait SecretProvider: Send + Sync {
+    fn fetch(&self, name: &str) -> Result<Secret, SecretError>;
+}
+
+/// Reads secrets from environment variables: `secret://env/TLS_KEY`.
+pub struct EnvSecretProvider;
+
+impl SecretProvider for EnvSecretProvider {
+    fn fetch(&self, name: &str) -> Result<Secret, SecretError> {
+        std::env::var(name)
+            .map(Secret)
+            .map_err(|_| SecretError::NotFound(format!("{SECRET_REFERENCE_PREFIX}env/{name}")))
+    }
+}
+
+/// Reads secrets from files: `secret://file/keys/tls.key`.
+/// Relative paths are resolved against the config directory.
+pub struct FileSecretProvider {
+    root: PathBuf,
+}
+
+impl FileSecretProvider {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+impl SecretProvider for FileSecretProvider {
+    fn fetch(&self, name: &str) -> Result<Secret, SecretError> {
+        fs::read_to_string(self.root.join(name))
+            .map(Secret)
+            .map_err(|source| SecretError::Read {
+                reference: format!("{SECRET_REFERENCE_PREFIX}file/{name}"),
+                source,
+            })
+    }
+}
+
+/// Resolves secret references against the registered providers.
+pub struct SecretResolver {
+    providers: HashMap<String, Arc<dyn SecretProvider>>,
+}
+
+impl SecretResolver {
+    /// A resolver with the built-in `env` and `file` providers.
+    pub fn new(root: &Path) -> Self {
+        Self {
+            providers: HashMap::new(),
+        }
+        .with_provider("env", EnvSecretProvider)
+        .with_provider("file", FileSecretProvider::new(root))
+    }
+
+    /// Register a provider, replacing any provider with the same name.
+    pub fn with_provider(
+        mut self,
+        name: impl Into<String>,
+        provider: impl SecretProvider + 'static,
+    ) -> Self {
+        self.providers.insert(name.into(), Arc::new(provider));
+        self
+    }
+
+    pub fn is_reference(value: &str) -> bool {
+        value.starts_with(SECRET_REFERENCE_PREFIX)
+    }
+
+    /// Split a reference into its provider and secret name.
+    pub fn parse_reference(reference: &str) -> Result<(&str, &str), SecretError> {
+        reference
+            .strip_prefix(SECRET_REFERENCE_PREFIX)
+            .and_then(|rest| rest.split_once('/'))
+            .filter(|(provider, name)| !provider.is_empty() && !name.is_empty())
+            .ok_or_else(|| SecretError::InvalidReference(reference.to_string()))
+    }
+
+    pub fn resolve(&self, reference: &str) -> Result<Secret, SecretError> {
+        let (provider, name) = Self::parse_reference(reference)?;
+        let secret = self
+            .providers
+            .get(provider)
+            .ok_or_else(|| SecretError::UnknownProvider {
+                provider: provider.to_string(),
+                reference: reference.to_string(),
+            })?
+            .fetch(name)?;
+
+        tracing::debug!(reference, "resolved secret");
+        Ok(secret)
+    }
+}
+
+/// Resolve secret references in listener TLS settings.
+pub(crate) fn resolve_tls_secrets(
+    listeners: &mut [ListenerConfig],
+    resolver: &SecretResolver,
+) -> Result<(), SecretError> {
+    for tls in listeners.iter_mut().filter_map(|l| l.tls.as_mut()) {
+        if SecretResolver::is_reference(&tls.cert) {
+            tls.cert_pem = Some(resolver.resolve(&tls.cert)?);
+        }
+        if SecretResolver::is_reference(&tls.key) {
+            tls.key_pem = Some(resolver.resolve(&tls.key)?);
+        }
+    }
+    Ok(())
+}
+
+/// Resolve secret references in upstream request header values.
+///
+/// Trailing newlines are trimmed, since secret files usually end with one and header values cannot.
+pub(crate) fn resolve_service_secrets(
+    services: &mut HashMap<String, ServiceConfig>,
+    resolver: &SecretResolver,
+) -> Result<(), SecretError> {
+    for header in services
+        .values_mut()
+        .flat_map(|s| s.upstream_request_headers.iter_mut())
+    {
+        if let Some(reference) = &header.secret {
+            let secret = resolver.resolve(reference)?;
+            header.value = Secret::new(secret.expose().trim_end_matches(['\r', '\n']));
+        }
+    }
+    Ok(())
+}
@@ -1,8 +1,8 @@
-use crate::conf::discover::{discover, resolve_glob};
+use crate::conf::discover::{discover, discover_includes, resolve_glob};
 use crate::conf::validation::ConfigError;
 
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tempfile::tempdir;
 
 #[test]
@@ -138,3 +138,37 @@ fn resolve_glob_preserves_subdirectories() {
     // Assert
     assert_eq!(resolved, "/etc/snakeway/routes/**/*.hcl");
 }
+
+#[test]
+fn discover_includes_resolves_against_the_including_file() {
+    // Arrange
+    let path = Path::new("/etc/snakeway/ingress.d/api.hcl");
+    let source = "include \"shared/admin.hcl\"\nbind = {}\n  include \"../common.hcl\"\n";
+
+    // Act
+    let (stripped, includes) = discover_includes(path, source);
+
+    // Assert
+    assert_eq!(stripped, "\nbind = {}\n");
+    assert_eq!(
+        includes,
+        vec![
+            PathBuf::from("/etc/snakeway/ingress.d/shared/admin.hcl"),
+            PathBuf::from("/etc/snakeway/ingress.d/../common.hcl"),
+        ]
+    );
+}
+
+#[test]
+fn discover_includes_ignores_ordinary_attributes() {
+    // Arrange
+    let path = Path::new("/etc/snakeway/ingress.d/api.hcl");
+    let source = "include = \"a.hcl\"\nincludes \"b.hcl\"\ninclude {\n}";
+
+    // Act
+    let (stripped, includes) = discover_includes(path, source);
+
+    // Assert
+    assert_eq!(stripped, source);
+    assert!(includes.is_empty());
+}
@@ -0,0 +1,128 @@
+use crate::conf::interpolation::interpolate_with;
+use crate::conf::types::Origin;
+use crate::conf::validation::ConfigError;
+use pretty_assertions::assert_eq;
+use std::path::{Path, PathBuf};
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+fn interpolate(source: &str) -> Result<String, ConfigError> {
+    interpolate_with(Path::new("/test/api.hcl"), source, |name| match name {
+        "PORT" => Some("8080".to_string()),
+        "HOST" => Some("api.example.com".to_string()),
+        "QUOTED" => Some(r#"say "hi" \ ${x}"#.to_string()),
+        _ => None,
+    })
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn present_variable_is_substituted() {
+    // Act
+    let out = interpolate("bind = {\n  port = ${env.PORT}\n}\n").unwrap();
+
+    // Assert
+    assert_eq!(out, "bind = {\n  port = 8080\n}\n");
+}
+
+#[test]
+fn variable_inside_string_is_substituted() {
+    // Act
+    let out = interpolate(r#"host = "https://${env.HOST}/api""#).unwrap();
+
+    // Assert
+    assert_eq!(out, r#"host = "https://api.example.com/api""#);
+}
+
+#[test]
+fn value_inside_string_is_escaped() {
+    // Act
+    let out = interpolate(r#"greeting = "${env.QUOTED}""#).unwrap();
+
+    // Assert
+    assert_eq!(out, r#"greeting = "say \"hi\" \\ $${x}""#);
+}
+
+#[test]
+fn default_is_used_when_variable_is_unset() {
+    // Act
+    let out = interpolate("port = ${env.MISSING:-9090}\nhost = \"${env.MISSING:-}\"").unwrap();
+
+    // Assert
+    assert_eq!(out, "port = 9090\nhost = \"\"");
+}
+
+#[test]
+fn set_variable_wins_over_default() {
+    // Act
+    let out = interpolate("port = ${env.PORT:-9090}").unwrap();
+
+    // Assert
+    assert_eq!(out, "port = 8080");
+}
+
+#[test]
+fn missing_variable_without_default_is_an_error() {
+    // Act
+    let result =
+        interpolate("server {\n  version = 1\n}\n\nbind = {\n  port = ${env.MISSING}\n}\n");
+
+    // Assert
+    match result {
+        Err(ConfigError::MissingEnvVar {
+            name,
+            field,
+            line,
+            origin,
+        }) => {
+            assert_eq!(name, "MISSING");
+            assert_eq!(field, "port");
+            assert_eq!(line, 6);
+            assert_eq!(origin.file, PathBuf::from("/test/api.hcl"));
+            assert_eq!(origin.section, "bind");
+        }
+        other => panic!("expected MissingEnvVar, got {other:?}"),
+    }
+}
+
+#[test]
+fn missing_variable_error_names_the_origin() {
+    // Act
+    let err = interpolate("bind = { port = ${env.MISSING} }").unwrap_err();
+
+    // Assert
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "environment variable MISSING is not set for port (origin: {}, line 1)",
+            Origin::new(&PathBuf::from("/test/api.hcl"), "bind", None)
+        )
+    );
+}
+
+#[test]
+fn comments_and_escaped_references_are_left_alone() {
+    // Arrange
+    let source = "# port = ${env.MISSING}\n// ${env.MISSING}\n/* ${env.MISSING} */\nname = \"$${env.MISSING}\"\n";
+
+    // Act
+    let out = interpolate(source).unwrap();
+
+    // Assert
+    assert_eq!(out, source);
+}
+
+#[test]
+fn other_templates_are_left_alone() {
+    // Arrange
+    let source = r#"name = "${var.name}""#;
+
+    // Act
+    let out = interpolate(source).unwrap();
+
+    // Assert
+    assert_eq!(out, source);
+}
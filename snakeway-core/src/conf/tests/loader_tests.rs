@@ -1 +1,281 @@
+use crate::conf::validation::ConfigError;
+use crate::conf::{load_config, load_spec_config};
+use pretty_assertions::assert_eq;
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
 
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+const API_INGRESS: &str = r#"
+bind = {
+  interface = "127.0.0.1"
+  port      = 8080
+}
+
+services = [
+  {
+    routes = [
+      {
+        path = "/api"
+      }
+    ]
+
+    upstreams = [
+      {
+        endpoint = { host = "127.0.0.1", port = 9001 }
+      }
+    ]
+  }
+]
+"#;
+
+fn write_entrypoint(root: &Path) {
+    fs::create_dir_all(root.join("devices.d")).unwrap();
+    fs::create_dir_all(root.join("ingress.d")).unwrap();
+    fs::write(
+        root.join("snakeway.hcl"),
+        r#"
+server {
+  version = 1
+}
+
+include {
+  devices = "devices.d/*.hcl"
+  ingress = "ingress.d/*.hcl"
+}
+"#,
+    )
+    .unwrap();
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn entrypoint_includes_ingress_files() {
+    // Arrange
+    let dir = tempdir().unwrap();
+    write_entrypoint(dir.path());
+    fs::write(dir.path().join("ingress.d/api.hcl"), API_INGRESS).unwrap();
+
+    // Act
+    let (_, devices, ingresses) = load_spec_config(dir.path()).unwrap();
+
+    // Assert
+    assert!(devices.is_empty());
+    assert_eq!(ingresses.len(), 1);
+    assert!(ingresses[0].bind.is_some());
+    assert_eq!(ingresses[0].services.len(), 1);
+}
+
+#[test]
+fn entrypoint_without_ingress_files_is_rejected() {
+    // Arrange
+    let dir = tempdir().unwrap();
+    write_entrypoint(dir.path());
+
+    // Act
+    let result = load_spec_config(dir.path());
+
+    // Assert
+    match result {
+        Err(ConfigError::NoIngressFiles { pattern }) => assert_eq!(pattern, "ingress.d/*.hcl"),
+        other => panic!("expected NoIngressFiles, got {other:?}"),
+    }
+}
+
+#[test]
+fn ingress_file_without_bind_is_reported() {
+    // Arrange
+    let dir = tempdir().unwrap();
+    write_entrypoint(dir.path());
+    let unbound = API_INGRESS.replace(
+        r#"bind = {
+  interface = "127.0.0.1"
+  port      = 8080
+}"#,
+        "",
+    );
+    fs::write(dir.path().join("ingress.d/api.hcl"), unbound).unwrap();
+
+    // Act
+    let validated = load_config(dir.path()).unwrap();
+
+    // Assert
+    let messages: Vec<&str> = validated
+        .validation_report
+        .errors
+        .iter()
+        .map(|e| e.message.as_str())
+        .collect();
+    assert!(messages.contains(&"ingress config must have a bind or bind_admin declaration"));
+}
+
+#[test]
+fn environment_variables_are_interpolated() {
+    // Arrange
+    let dir = tempdir().unwrap();
+    write_entrypoint(dir.path());
+    unsafe { std::env::set_var("SNAKEWAY_TEST_INTERPOLATION_PORT", "8181") };
+    let ingress = API_INGRESS
+        .replace(
+            "port      = 8080",
+            "port      = ${env.SNAKEWAY_TEST_INTERPOLATION_PORT}",
+        )
+        .replace(
+            "port = 9001",
+            "port = ${env.SNAKEWAY_TEST_INTERPOLATION_UNSET:-9002}",
+        );
+    fs::write(dir.path().join("ingress.d/api.hcl"), ingress).unwrap();
+
+    // Act
+    let (_, _, ingresses) = load_spec_config(dir.path()).unwrap();
+
+    // Assert
+    assert_eq!(ingresses[0].bind.as_ref().unwrap().port, 8181);
+    let validated = load_config(dir.path()).unwrap();
+    assert!(validated.validation_report.errors.is_empty());
+}
+
+#[test]
+fn missing_environment_variable_fails_loading() {
+    // Arrange
+    let dir = tempdir().unwrap();
+    write_entrypoint(dir.path());
+    let ingress = API_INGRESS.replace(
+        "port      = 8080",
+        "port      = ${env.SNAKEWAY_TEST_INTERPOLATION_MISSING}",
+    );
+    fs::write(dir.path().join("ingress.d/api.hcl"), ingress).unwrap();
+
+    // Act
+    let result = load_spec_config(dir.path());
+
+    // Assert
+    match result {
+        Err(ConfigError::MissingEnvVar {
+            name,
+            field,
+            origin,
+            ..
+        }) => {
+            assert_eq!(name, "SNAKEWAY_TEST_INTERPOLATION_MISSING");
+            assert_eq!(field, "port");
+            assert_eq!(origin.file, dir.path().join("ingress.d/api.hcl"));
+            assert_eq!(origin.section, "bind");
+        }
+        other => panic!("expected MissingEnvVar, got {other:?}"),
+    }
+}
+
+#[test]
+fn included_bind_admin_is_merged_into_runtime_config() {
+    // Arrange
+    let dir = tempdir().unwrap();
+    write_entrypoint(dir.path());
+    fs::create_dir_all(dir.path().join("ingress.d/shared")).unwrap();
+    fs::write(
+        dir.path().join("ingress.d/shared/admin.hcl"),
+        r#"
+bind_admin = {
+  interface = "127.0.0.1"
+  port      = 8440
+  tls = {
+    cert = "cert.pem"
+    key  = "key.pem"
+  }
+}
+"#,
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("ingress.d/api.hcl"),
+        format!("include \"shared/admin.hcl\"\n{API_INGRESS}"),
+    )
+    .unwrap();
+
+    // Act
+    let validated = load_config(dir.path()).unwrap();
+
+    // Assert
+    let listeners: Vec<(&str, bool)> = validated
+        .config
+        .listeners
+        .iter()
+        .map(|l| (l.addr.as_str(), l.enable_admin))
+        .collect();
+    assert_eq!(
+        listeners,
+        vec![("127.0.0.1:8080", false), ("127.0.0.1:8440", true)]
+    );
+    assert_eq!(validated.config.services.len(), 1);
+}
+
+#[test]
+fn services_inherit_server_defaults_unless_they_override_them() {
+    // Arrange
+    let dir = tempdir().unwrap();
+    write_entrypoint(dir.path());
+    fs::write(
+        dir.path().join("snakeway.hcl"),
+        r#"
+server {
+  version = 1
+
+  defaults {
+    connection = {
+      connect_timeout_milliseconds = 1500
+      max_connect_retries          = 2
+    }
+    health_check = {
+      enable = true
+    }
+  }
+}
+
+include {
+  devices = "devices.d/*.hcl"
+  ingress = "ingress.d/*.hcl"
+}
+"#,
+    )
+    .unwrap();
+    fs::write(dir.path().join("ingress.d/api.hcl"), API_INGRESS).unwrap();
+    let tenants = API_INGRESS.replace("8080", "8081").replace(
+        "    routes = [",
+        r#"    connection = {
+      connect_timeout_milliseconds = 250
+    }
+
+    routes = ["#,
+    );
+    fs::write(dir.path().join("ingress.d/tenants.hcl"), tenants).unwrap();
+
+    // Act
+    let validated = load_config(dir.path()).unwrap();
+
+    // Assert
+    let services = &validated.config.services;
+    let inherited = &services["127.0.0.1:8080-service"];
+    let overriding = &services["127.0.0.1:8081-service"];
+    assert_eq!(
+        inherited.tcp_upstreams[0]
+            .connection
+            .connect_timeout_milliseconds,
+        1500
+    );
+    assert_eq!(
+        overriding.tcp_upstreams[0]
+            .connection
+            .connect_timeout_milliseconds,
+        250
+    );
+    assert_eq!(
+        overriding.tcp_upstreams[0].connection.max_connect_retries,
+        2
+    );
+    assert!(inherited.health_check.enable);
+    assert!(overriding.health_check.enable);
+}
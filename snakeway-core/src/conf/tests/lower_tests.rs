@@ -1 +1,119 @@
+use crate::conf::lower::lower_configs;
+use crate::conf::types::{
+    BindInterfaceInput, BindSpec, EndpointSpec, HostSpec, IngressSpec, ServerSpec, ServiceSpec,
+    UpstreamConnectionConfig, UpstreamConnectionSpec, UpstreamSpec,
+};
+use pretty_assertions::assert_eq;
+use std::net::IpAddr;
+use std::str::FromStr;
 
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+fn upstream(port: u16, connection: Option<UpstreamConnectionSpec>) -> UpstreamSpec {
+    UpstreamSpec {
+        endpoint: Some(EndpointSpec {
+            host: HostSpec::Ip(IpAddr::from_str("127.0.0.1").unwrap()),
+            port,
+        }),
+        weight: 1,
+        connection,
+        ..Default::default()
+    }
+}
+
+fn ingress(service: ServiceSpec) -> IngressSpec {
+    IngressSpec {
+        bind: Some(BindSpec {
+            interface: BindInterfaceInput::Keyword("loopback".to_string()),
+            port: 8080,
+            ..Default::default()
+        }),
+        services: vec![service],
+        ..Default::default()
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Upstream connection settings
+//-----------------------------------------------------------------------------
+#[test]
+fn upstream_inherits_service_connection_settings_and_defaults() {
+    // Arrange
+    let service = ServiceSpec {
+        connection: Some(UpstreamConnectionSpec {
+            connect_timeout_milliseconds: Some(250),
+            max_connect_retries: Some(2),
+            ..Default::default()
+        }),
+        upstreams: vec![upstream(9001, None)],
+        ..Default::default()
+    };
+
+    // Act
+    let (_, _, _, services, _) =
+        lower_configs(ServerSpec::default(), vec![ingress(service)], vec![]).unwrap();
+
+    // Assert
+    let service = services.values().next().unwrap();
+    assert_eq!(
+        service.tcp_upstreams[0].connection,
+        UpstreamConnectionConfig {
+            connect_timeout_milliseconds: 250,
+            read_timeout_milliseconds: 60_000,
+            idle_timeout_seconds: 60,
+            response_idle_timeout_milliseconds: 0,
+            max_connect_retries: 2,
+        }
+    );
+}
+
+#[test]
+fn upstream_connection_settings_override_service() {
+    // Arrange
+    let service = ServiceSpec {
+        connection: Some(UpstreamConnectionSpec {
+            connect_timeout_milliseconds: Some(250),
+            idle_timeout_seconds: Some(30),
+            ..Default::default()
+        }),
+        upstreams: vec![upstream(
+            9001,
+            Some(UpstreamConnectionSpec {
+                idle_timeout_seconds: Some(5),
+                ..Default::default()
+            }),
+        )],
+        ..Default::default()
+    };
+
+    // Act
+    let (_, _, _, services, _) =
+        lower_configs(ServerSpec::default(), vec![ingress(service)], vec![]).unwrap();
+
+    // Assert
+    let connection = services.values().next().unwrap().tcp_upstreams[0].connection;
+    assert_eq!(connection.connect_timeout_milliseconds, 250);
+    assert_eq!(connection.idle_timeout_seconds, 5);
+}
+
+#[test]
+fn runtime_dump_shows_effective_upstream_connection_settings() {
+    // Arrange
+    let service = ServiceSpec {
+        upstreams: vec![upstream(9001, None)],
+        ..Default::default()
+    };
+    let (_, _, _, services, _) =
+        lower_configs(ServerSpec::default(), vec![ingress(service)], vec![]).unwrap();
+
+    // Act
+    let dumped = serde_json::to_value(&services).unwrap();
+
+    // Assert
+    let connection = &dumped["127.0.0.1:8080-service"]["tcp_upstreams"][0]["connection"];
+    assert_eq!(connection["connect_timeout_milliseconds"], 5_000);
+    assert_eq!(connection["read_timeout_milliseconds"], 60_000);
+    assert_eq!(connection["idle_timeout_seconds"], 60);
+    assert_eq!(connection["max_connect_retries"], 0);
+}
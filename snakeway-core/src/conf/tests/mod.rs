@@ -1,4 +1,6 @@
 mod discover_tests;
+mod interpolation_tests;
 mod loader_tests;
 mod lower_tests;
 mod parse_tests;
+mod secrets_tests;
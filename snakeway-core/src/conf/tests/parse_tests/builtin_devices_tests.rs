@@ -60,3 +60,47 @@ structured_logging_device = {
     assert_eq!(devices.len(), 1);
     assert!(matches!(devices[0], DeviceSpec::StructuredLogging(_)));
 }
+
+#[test]
+fn parse_devices_puts_structured_logging_last() {
+    // Arrange
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("devices.hcl");
+
+    fs::write(
+        &path,
+        r#"
+structured_logging_device = {
+  enable = true
+  include_headers = false
+  allowed_headers = []
+  redacted_headers = []
+  level = "info"
+  include_identity = false
+  identity_fields = []
+}
+
+identity_device = {
+  enable = true
+  trusted_proxies = []
+  enable_geoip = false
+  enable_user_agent = false
+  ua_engine = "woothee"
+}
+
+wasm_devices = [
+  { enable = true, path = "./a.wasm", config = {} }
+]
+"#,
+    )
+    .unwrap();
+
+    // Act
+    let devices = parse_devices(&path).unwrap();
+
+    // Assert
+    assert_eq!(devices.len(), 3);
+    assert!(matches!(devices[0], DeviceSpec::Identity(_)));
+    assert!(matches!(devices[1], DeviceSpec::Wasm(_)));
+    assert!(matches!(devices[2], DeviceSpec::StructuredLogging(_)));
+}
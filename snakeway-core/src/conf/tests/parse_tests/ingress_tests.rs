@@ -116,3 +116,100 @@ fn parse_ingress_invalid_hcl_returns_error() {
     // Assert
     assert!(matches!(err, ConfigError::Parse { .. }));
 }
+
+#[test]
+fn parse_ingress_merges_included_files() {
+    // Arrange
+    let dir = tempdir().unwrap();
+    fs::create_dir(dir.path().join("shared")).unwrap();
+    let path = dir.path().join("api.hcl");
+    let shared = dir.path().join("shared/admin.hcl");
+
+    fs::write(
+        &path,
+        r#"
+include "shared/admin.hcl"
+
+services = [
+  {
+    routes    = [{ path = "/api" }]
+    upstreams = [{ endpoint = { host = "127.0.0.1", port = 9001 } }]
+  }
+]
+"#,
+    )
+    .unwrap();
+    fs::write(
+        &shared,
+        r#"
+bind_admin = {
+  interface = "127.0.0.1"
+  port      = 8440
+  tls = {
+    cert = "cert.pem"
+    key  = "key.pem"
+  }
+}
+"#,
+    )
+    .unwrap();
+
+    // Act
+    let ingress = parse_ingress(&path).unwrap();
+
+    // Assert
+    let bind_admin = ingress.bind_admin.unwrap();
+    assert_eq!(bind_admin.port, 8440);
+    assert_eq!(bind_admin.origin.file, shared);
+    assert_eq!(ingress.services.len(), 1);
+    assert_eq!(ingress.services[0].origin.file, path);
+}
+
+#[test]
+fn parse_ingress_rejects_cyclic_includes() {
+    // Arrange
+    let dir = tempdir().unwrap();
+    let a = dir.path().join("a.hcl");
+    let b = dir.path().join("b.hcl");
+    fs::write(&a, "include \"b.hcl\"\n").unwrap();
+    fs::write(&b, "include \"a.hcl\"\n").unwrap();
+
+    // Act
+    let result = parse_ingress(&a);
+
+    // Assert
+    match result {
+        Err(ConfigError::IncludeCycle { chain }) => {
+            let names: Vec<_> = chain
+                .iter()
+                .map(|p| p.file_name().unwrap().to_str().unwrap())
+                .collect();
+            assert_eq!(names, vec!["a.hcl", "b.hcl", "a.hcl"]);
+        }
+        other => panic!("expected IncludeCycle, got {other:?}"),
+    }
+}
+
+#[test]
+fn parse_ingress_rejects_bind_declared_twice() {
+    // Arrange
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("api.hcl");
+    let bind = r#"
+bind = {
+  interface = "127.0.0.1"
+  port = 8080
+}
+"#;
+    fs::write(&path, format!("include \"other.hcl\"\n{bind}")).unwrap();
+    fs::write(dir.path().join("other.hcl"), bind).unwrap();
+
+    // Act
+    let result = parse_ingress(&path);
+
+    // Assert
+    assert!(matches!(
+        result,
+        Err(ConfigError::IncludeConflict { field, .. }) if field == "bind"
+    ));
+}
@@ -3,5 +3,9 @@ mod shared;
 mod specification;
 
 pub use runtime::*;
-pub use shared::{CircuitBreakerConfig, HealthCheckConfig, ServerConfig, TlsConfig};
+pub use shared::{
+    ActiveHealthCheckConfig, CircuitBreakerBackoffConfig, CircuitBreakerConfig,
+    CircuitBreakerIsolation, GeoIpConfig, HealthCheckConfig, KetamaConfig, OutlierDetectionConfig,
+    RetryPolicyConfig, ServerConfig, TlsConfig,
+};
 pub use specification::*;
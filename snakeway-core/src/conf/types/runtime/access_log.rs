@@ -0,0 +1,148 @@
+use thiserror::Error;
+
+/// A field of the access log, by the name it is logged under.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessLogField {
+    RequestId,
+    Method,
+    Path,
+    Status,
+    LatencyMs,
+    Service,
+    Upstream,
+    ClientIp,
+    UaDeviceType,
+    BytesIn,
+    BytesOut,
+    Device,
+    Error,
+}
+
+impl AccessLogField {
+    pub const ALL: [AccessLogField; 13] = [
+        AccessLogField::RequestId,
+        AccessLogField::Method,
+        AccessLogField::Path,
+        AccessLogField::Status,
+        AccessLogField::LatencyMs,
+        AccessLogField::Service,
+        AccessLogField::Upstream,
+        AccessLogField::ClientIp,
+        AccessLogField::UaDeviceType,
+        AccessLogField::BytesIn,
+        AccessLogField::BytesOut,
+        AccessLogField::Device,
+        AccessLogField::Error,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AccessLogField::RequestId => "request_id",
+            AccessLogField::Method => "method",
+            AccessLogField::Path => "path",
+            AccessLogField::Status => "status",
+            AccessLogField::LatencyMs => "latency_ms",
+            AccessLogField::Service => "service",
+            AccessLogField::Upstream => "upstream",
+            AccessLogField::ClientIp => "client_ip",
+            AccessLogField::UaDeviceType => "ua_device_type",
+            AccessLogField::BytesIn => "bytes_in",
+            AccessLogField::BytesOut => "bytes_out",
+            AccessLogField::Device => "device",
+            AccessLogField::Error => "error",
+        }
+    }
+
+    /// Look up a field by its name, or by the nginx variable it corresponds to,
+    /// e.g. `remote_addr` for `client_ip`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let field = match name {
+            "request_id" => AccessLogField::RequestId,
+            "method" | "request_method" => AccessLogField::Method,
+            "path" | "uri" => AccessLogField::Path,
+            "status" => AccessLogField::Status,
+            "latency_ms" | "duration" | "request_time" => AccessLogField::LatencyMs,
+            "service" => AccessLogField::Service,
+            "upstream" | "upstream_addr" => AccessLogField::Upstream,
+            "client_ip" | "remote_addr" => AccessLogField::ClientIp,
+            "ua_device_type" => AccessLogField::UaDeviceType,
+            "bytes_in" | "request_length" => AccessLogField::BytesIn,
+            "bytes_out" | "body_bytes_sent" => AccessLogField::BytesOut,
+            "device" => AccessLogField::Device,
+            "error" => AccessLogField::Error,
+            _ => return None,
+        };
+        Some(field)
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+#[error("unknown access log field: ${0}")]
+pub struct UnknownAccessLogField(pub String);
+
+/// Part of an access log template.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AccessLogPart {
+    Literal(String),
+    Field(AccessLogField),
+}
+
+/// An access log template, e.g. `$remote_addr "$method $path" $status $request_time`.
+///
+/// `$` followed by a field name is replaced with the field's value, and everything else is
+/// copied as is. Only the fields a template names are logged.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessLogFormat {
+    parts: Vec<AccessLogPart>,
+}
+
+impl AccessLogFormat {
+    /// Parse a template, failing on the first variable that names no field.
+    pub fn parse(template: &str) -> Result<Self, UnknownAccessLogField> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut rest = template;
+
+        while let Some(start) = rest.find('$') {
+            literal.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+            let len = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(after.len());
+
+            // A `$` that starts no name is kept as is.
+            if len == 0 {
+                literal.push('$');
+                rest = after;
+                continue;
+            }
+
+            let name = &after[..len];
+            let field = AccessLogField::from_name(name)
+                .ok_or_else(|| UnknownAccessLogField(name.to_string()))?;
+            if !literal.is_empty() {
+                parts.push(AccessLogPart::Literal(std::mem::take(&mut literal)));
+            }
+            parts.push(AccessLogPart::Field(field));
+            rest = &after[len..];
+        }
+
+        literal.push_str(rest);
+        if !literal.is_empty() {
+            parts.push(AccessLogPart::Literal(literal));
+        }
+
+        Ok(Self { parts })
+    }
+
+    pub fn parts(&self) -> &[AccessLogPart] {
+        &self.parts
+    }
+
+    /// Whether the template names `field`.
+    pub fn includes(&self, field: AccessLogField) -> bool {
+        self.parts
+            .iter()
+            .any(|part| *part == AccessLogPart::Field(field))
+    }
+}
@@ -0,0 +1,26 @@
+use crate::conf::types::BodyDigestDeviceSpec;
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct BodyDigestDeviceConfig {
+    pub enable: bool,
+
+    pub priority: i32,
+
+    pub routes: Vec<String>,
+
+    /// Maximum request body size that will be buffered for verification.
+    pub max_body_bytes: usize,
+}
+
+impl From<BodyDigestDeviceSpec> for BodyDigestDeviceConfig {
+    fn from(spec: BodyDigestDeviceSpec) -> Self {
+        Self {
+            enable: spec.enable,
+            priority: spec.priority,
+            routes: spec.routes,
+            max_body_bytes: spec.max_body_bytes,
+        }
+    }
+}
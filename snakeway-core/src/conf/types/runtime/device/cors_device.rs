@@ -0,0 +1,40 @@
+use crate::conf::types::CorsDeviceSpec;
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CorsDeviceConfig {
+    pub enable: bool,
+
+    pub priority: i32,
+
+    pub routes: Vec<String>,
+
+    pub allow_origins: Vec<String>,
+
+    pub allow_methods: Vec<String>,
+
+    pub allow_headers: Vec<String>,
+
+    pub expose_headers: Vec<String>,
+
+    pub allow_credentials: bool,
+
+    pub max_age_seconds: Option<u32>,
+}
+
+impl From<CorsDeviceSpec> for CorsDeviceConfig {
+    fn from(spec: CorsDeviceSpec) -> Self {
+        Self {
+            enable: spec.enable,
+            priority: spec.priority,
+            routes: spec.routes,
+            allow_origins: spec.allow_origins,
+            allow_methods: spec.allow_methods,
+            allow_headers: spec.allow_headers,
+            expose_headers: spec.expose_headers,
+            allow_credentials: spec.allow_credentials,
+            max_age_seconds: spec.max_age_seconds,
+        }
+    }
+}
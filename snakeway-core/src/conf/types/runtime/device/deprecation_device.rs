@@ -0,0 +1,51 @@
+use crate::conf::types::{DeprecationDeviceSpec, DeprecationSpec};
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeprecationDeviceConfig {
+    pub enable: bool,
+
+    pub priority: i32,
+
+    pub routes: Vec<String>,
+
+    /// Deprecated routes, and the headers announced on their responses.
+    pub deprecations: Vec<DeprecationConfig>,
+}
+
+impl From<DeprecationDeviceSpec> for DeprecationDeviceConfig {
+    fn from(spec: DeprecationDeviceSpec) -> Self {
+        Self {
+            enable: spec.enable,
+            priority: spec.priority,
+            routes: spec.routes,
+            deprecations: spec.deprecations.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeprecationConfig {
+    pub path: String,
+
+    /// RFC 3339 timestamp.
+    pub deprecated_at: String,
+
+    /// RFC 3339 timestamp.
+    pub sunset_at: Option<String>,
+
+    pub successor: Option<String>,
+}
+
+impl From<DeprecationSpec> for DeprecationConfig {
+    fn from(spec: DeprecationSpec) -> Self {
+        Self {
+            path: spec.path,
+            deprecated_at: spec.deprecated_at,
+            sunset_at: spec.sunset_at,
+            successor: spec.successor,
+        }
+    }
+}
@@ -1,5 +1,10 @@
 use crate::conf::types::{
-    IdentityDeviceConfig, RequestFilterDeviceConfig, StructuredLoggingDeviceConfig,
+    BodyDigestDeviceConfig, CorsDeviceConfig, DeprecationDeviceConfig, ErrorPageDeviceConfig,
+    ExperimentDeviceConfig, HeaderRewriteDeviceConfig, IdempotencyDeviceConfig,
+    IdentityDeviceConfig, IpReputationDeviceConfig, JwtAuthDeviceConfig, LuaDeviceConfig,
+    OpenApiDeviceConfig, QueryRewriteDeviceConfig, QuotaDeviceConfig, RateLimitDeviceConfig,
+    RequestFilterDeviceConfig, RequiredHeadersDeviceConfig, ResponseHeadersDeviceConfig,
+    StatusRemapDeviceConfig, StructuredLoggingDeviceConfig, UpstreamGroupDeviceConfig,
     WasmDeviceConfig,
 };
 use serde::Serialize;
@@ -10,7 +15,25 @@ pub enum DeviceConfig {
     Wasm(WasmDeviceConfig),
     Identity(IdentityDeviceConfig),
     RequestFilter(RequestFilterDeviceConfig),
+    OpenApi(OpenApiDeviceConfig),
     StructuredLogging(StructuredLoggingDeviceConfig),
+    JwtAuth(JwtAuthDeviceConfig),
+    Quota(QuotaDeviceConfig),
+    BodyDigest(BodyDigestDeviceConfig),
+    UpstreamGroup(UpstreamGroupDeviceConfig),
+    RequiredHeaders(RequiredHeadersDeviceConfig),
+    StatusRemap(StatusRemapDeviceConfig),
+    ErrorPage(ErrorPageDeviceConfig),
+    ResponseHeaders(ResponseHeadersDeviceConfig),
+    IpReputation(IpReputationDeviceConfig),
+    RateLimit(RateLimitDeviceConfig),
+    Experiment(ExperimentDeviceConfig),
+    HeaderRewrite(HeaderRewriteDeviceConfig),
+    QueryRewrite(QueryRewriteDeviceConfig),
+    Cors(CorsDeviceConfig),
+    Idempotency(IdempotencyDeviceConfig),
+    Deprecation(DeprecationDeviceConfig),
+    Lua(LuaDeviceConfig),
 }
 
 impl DeviceConfig {
@@ -18,8 +41,110 @@ impl DeviceConfig {
         match self {
             DeviceConfig::Identity(i) => i.enable,
             DeviceConfig::RequestFilter(r) => r.enable,
+            DeviceConfig::OpenApi(o) => o.enable,
             DeviceConfig::StructuredLogging(s) => s.enable,
+            DeviceConfig::JwtAuth(j) => j.enable,
+            DeviceConfig::Quota(q) => q.enable,
+            DeviceConfig::BodyDigest(b) => b.enable,
+            DeviceConfig::UpstreamGroup(u) => u.enable,
+            DeviceConfig::RequiredHeaders(r) => r.enable,
+            DeviceConfig::StatusRemap(s) => s.enable,
+            DeviceConfig::ErrorPage(e) => e.enable,
+            DeviceConfig::ResponseHeaders(r) => r.enable,
+            DeviceConfig::IpReputation(i) => i.enable,
+            DeviceConfig::RateLimit(r) => r.enable,
+            DeviceConfig::Experiment(e) => e.enable,
+            DeviceConfig::HeaderRewrite(h) => h.enable,
+            DeviceConfig::QueryRewrite(q) => q.enable,
+            DeviceConfig::Cors(c) => c.enable,
+            DeviceConfig::Idempotency(i) => i.enable,
+            DeviceConfig::Deprecation(d) => d.enable,
+            DeviceConfig::Lua(l) => l.enable,
             DeviceConfig::Wasm(w) => w.enable,
         }
     }
+
+    /// Where the device runs in the pipeline, lower first.
+    pub fn priority(&self) -> i32 {
+        match self {
+            DeviceConfig::Identity(i) => i.priority,
+            DeviceConfig::RequestFilter(r) => r.priority,
+            DeviceConfig::OpenApi(o) => o.priority,
+            DeviceConfig::StructuredLogging(s) => s.priority,
+            DeviceConfig::JwtAuth(j) => j.priority,
+            DeviceConfig::Quota(q) => q.priority,
+            DeviceConfig::BodyDigest(b) => b.priority,
+            DeviceConfig::UpstreamGroup(u) => u.priority,
+            DeviceConfig::RequiredHeaders(r) => r.priority,
+            DeviceConfig::StatusRemap(s) => s.priority,
+            DeviceConfig::ErrorPage(e) => e.priority,
+            DeviceConfig::ResponseHeaders(r) => r.priority,
+            DeviceConfig::IpReputation(i) => i.priority,
+            DeviceConfig::RateLimit(r) => r.priority,
+            DeviceConfig::Experiment(e) => e.priority,
+            DeviceConfig::HeaderRewrite(h) => h.priority,
+            DeviceConfig::QueryRewrite(q) => q.priority,
+            DeviceConfig::Cors(c) => c.priority,
+            DeviceConfig::Idempotency(i) => i.priority,
+            DeviceConfig::Deprecation(d) => d.priority,
+            DeviceConfig::Lua(l) => l.priority,
+            DeviceConfig::Wasm(w) => w.priority,
+        }
+    }
+
+    /// Request path prefixes the device runs on. Empty runs on every path.
+    pub fn routes(&self) -> &[String] {
+        match self {
+            DeviceConfig::Identity(i) => &i.routes,
+            DeviceConfig::RequestFilter(r) => &r.routes,
+            DeviceConfig::OpenApi(o) => &o.routes,
+            DeviceConfig::StructuredLogging(s) => &s.routes,
+            DeviceConfig::JwtAuth(j) => &j.routes,
+            DeviceConfig::Quota(q) => &q.routes,
+            DeviceConfig::BodyDigest(b) => &b.routes,
+            DeviceConfig::UpstreamGroup(u) => &u.routes,
+            DeviceConfig::RequiredHeaders(r) => &r.routes,
+            DeviceConfig::StatusRemap(s) => &s.routes,
+            DeviceConfig::ErrorPage(e) => &e.routes,
+            DeviceConfig::ResponseHeaders(r) => &r.routes,
+            DeviceConfig::IpReputation(i) => &i.routes,
+            DeviceConfig::RateLimit(r) => &r.routes,
+            DeviceConfig::Experiment(e) => &e.routes,
+            DeviceConfig::HeaderRewrite(h) => &h.routes,
+            DeviceConfig::QueryRewrite(q) => &q.routes,
+            DeviceConfig::Cors(c) => &c.routes,
+            DeviceConfig::Idempotency(i) => &i.routes,
+            DeviceConfig::Deprecation(d) => &d.routes,
+            DeviceConfig::Lua(l) => &l.routes,
+            DeviceConfig::Wasm(w) => &w.routes,
+        }
+    }
+
+    /// The name used to attach a device to listeners.
+    pub fn name(&self) -> &str {
+        match self {
+            DeviceConfig::Identity(_) => "identity",
+            DeviceConfig::RequestFilter(_) => "request_filter",
+            DeviceConfig::OpenApi(_) => "openapi",
+            DeviceConfig::StructuredLogging(_) => "structured_logging",
+            DeviceConfig::JwtAuth(_) => "jwt_auth",
+            DeviceConfig::Quota(_) => "quota",
+            DeviceConfig::BodyDigest(_) => "body_digest",
+            DeviceConfig::UpstreamGroup(_) => "upstream_group",
+            DeviceConfig::RequiredHeaders(_) => "required_headers",
+            DeviceConfig::StatusRemap(_) => "status_remap",
+            DeviceConfig::ErrorPage(_) => "error_page",
+            DeviceConfig::ResponseHeaders(_) => "response_headers",
+            DeviceConfig::IpReputation(_) => "ip_reputation",
+            DeviceConfig::RateLimit(_) => "rate_limit",
+            DeviceConfig::Experiment(_) => "experiment",
+            DeviceConfig::HeaderRewrite(_) => "header_rewrite",
+            DeviceConfig::QueryRewrite(_) => "query_rewrite",
+            DeviceConfig::Cors(_) => "cors",
+            DeviceConfig::Idempotency(_) => "idempotency",
+            DeviceConfig::Deprecation(_) => "deprecation",
+            DeviceConfig::Lua(_) => "lua",
+            DeviceConfig::Wasm(w) => &w.name,
+        }
+    }
 }
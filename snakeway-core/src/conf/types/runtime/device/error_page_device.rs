@@ -0,0 +1,44 @@
+use crate::conf::types::ErrorPageDeviceSpec;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ErrorPageDeviceConfig {
+    pub enable: bool,
+
+    pub priority: i32,
+
+    pub routes: Vec<String>,
+
+    /// Statuses, or status classes such as `5xx`, whose upstream responses are replaced.
+    pub statuses: Vec<String>,
+
+    pub body: Option<String>,
+
+    pub body_file: Option<PathBuf>,
+
+    pub content_type: String,
+
+    /// Content type patterns of upstream error responses that pass through unchanged.
+    pub pass_through_content_types: Vec<String>,
+
+    /// Upstream headers kept on a replaced response.
+    pub keep_headers: Vec<String>,
+}
+
+impl From<ErrorPageDeviceSpec> for ErrorPageDeviceConfig {
+    fn from(spec: ErrorPageDeviceSpec) -> Self {
+        Self {
+            enable: spec.enable,
+            priority: spec.priority,
+            routes: spec.routes,
+            statuses: spec.statuses,
+            body: spec.body,
+            body_file: spec.body_file,
+            content_type: spec.content_type,
+            pass_through_content_types: spec.pass_through_content_types,
+            keep_headers: spec.keep_headers,
+        }
+    }
+}
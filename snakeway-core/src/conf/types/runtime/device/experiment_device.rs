@@ -0,0 +1,49 @@
+use crate::conf::types::{ExperimentDeviceSpec, ExperimentVariantSpec};
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExperimentDeviceConfig {
+    pub enable: bool,
+
+    pub priority: i32,
+
+    pub routes: Vec<String>,
+
+    pub experiment: String,
+
+    /// Cookie whose value identifies the user.
+    pub cookie: String,
+
+    pub variants: Vec<ExperimentVariantConfig>,
+}
+
+impl From<ExperimentDeviceSpec> for ExperimentDeviceConfig {
+    fn from(spec: ExperimentDeviceSpec) -> Self {
+        Self {
+            enable: spec.enable,
+            priority: spec.priority,
+            routes: spec.routes,
+            experiment: spec.experiment,
+            cookie: spec.cookie,
+            variants: spec.variants.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExperimentVariantConfig {
+    pub name: String,
+
+    pub weight: u32,
+}
+
+impl From<ExperimentVariantSpec> for ExperimentVariantConfig {
+    fn from(spec: ExperimentVariantSpec) -> Self {
+        Self {
+            name: spec.name,
+            weight: spec.weight,
+        }
+    }
+}
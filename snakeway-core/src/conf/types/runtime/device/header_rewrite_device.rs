@@ -0,0 +1,51 @@
+use crate::conf::types::{HeaderRewriteDeviceSpec, HeaderSetSpec};
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct HeaderRewriteDeviceConfig {
+    pub enable: bool,
+
+    pub priority: i32,
+
+    pub routes: Vec<String>,
+
+    pub request_set: Vec<HeaderSetConfig>,
+
+    pub request_remove: Vec<String>,
+
+    pub response_set: Vec<HeaderSetConfig>,
+
+    pub response_remove: Vec<String>,
+}
+
+impl From<HeaderRewriteDeviceSpec> for HeaderRewriteDeviceConfig {
+    fn from(spec: HeaderRewriteDeviceSpec) -> Self {
+        Self {
+            enable: spec.enable,
+            priority: spec.priority,
+            routes: spec.routes,
+            request_set: spec.request_set.into_iter().map(Into::into).collect(),
+            request_remove: spec.request_remove,
+            response_set: spec.response_set.into_iter().map(Into::into).collect(),
+            response_remove: spec.response_remove,
+        }
+    }
+}
+
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct HeaderSetConfig {
+    pub name: String,
+
+    pub value: String,
+}
+
+impl From<HeaderSetSpec> for HeaderSetConfig {
+    fn from(spec: HeaderSetSpec) -> Self {
+        Self {
+            name: spec.name,
+            value: spec.value,
+        }
+    }
+}
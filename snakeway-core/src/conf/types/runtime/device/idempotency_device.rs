@@ -0,0 +1,42 @@
+use crate::conf::types::IdempotencyDeviceSpec;
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct IdempotencyDeviceConfig {
+    pub enable: bool,
+
+    pub priority: i32,
+
+    pub routes: Vec<String>,
+
+    /// Request header carrying the idempotency key.
+    pub key_header: String,
+
+    pub methods: Vec<String>,
+
+    /// Path prefixes. Empty means every path.
+    pub paths: Vec<String>,
+
+    pub ttl_seconds: u64,
+
+    pub wait_timeout_milliseconds: u64,
+
+    pub max_body_bytes: usize,
+}
+
+impl From<IdempotencyDeviceSpec> for IdempotencyDeviceConfig {
+    fn from(spec: IdempotencyDeviceSpec) -> Self {
+        Self {
+            enable: spec.enable,
+            priority: spec.priority,
+            routes: spec.routes,
+            key_header: spec.key_header,
+            methods: spec.methods,
+            paths: spec.paths,
+            ttl_seconds: spec.ttl_seconds,
+            wait_timeout_milliseconds: spec.wait_timeout_milliseconds,
+            max_body_bytes: spec.max_body_bytes,
+        }
+    }
+}
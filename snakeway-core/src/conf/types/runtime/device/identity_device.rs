@@ -1,4 +1,4 @@
-use crate::conf::types::{IdentityDeviceSpec, UaEngineSpec};
+use crate::conf::types::{ForwardingHeaderSpec, IdentityDeviceSpec, UaEngineSpec};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -7,9 +7,19 @@ use std::path::PathBuf;
 pub struct IdentityDeviceConfig {
     pub enable: bool,
 
+    pub priority: i32,
+
+    pub routes: Vec<String>,
+
     /// CIDR strings
     pub trusted_proxies: Vec<String>,
 
+    /// Header carrying the real client IP, preferred over X-Forwarded-For.
+    pub client_ip_header: Option<String>,
+
+    /// Walked when a request carries both forwarding headers.
+    pub preferred_forwarding_header: ForwardingHeaderKind,
+
     pub enable_geoip: bool,
 
     pub geoip_city_db: Option<PathBuf>,
@@ -19,19 +29,27 @@ pub struct IdentityDeviceConfig {
     pub enable_user_agent: bool,
 
     pub ua_engine: UaEngineKind,
+
+    /// `0` disables the cache.
+    pub ua_cache_capacity: usize,
 }
 
 impl From<IdentityDeviceSpec> for IdentityDeviceConfig {
     fn from(spec: IdentityDeviceSpec) -> Self {
         Self {
             enable: spec.enable,
+            priority: spec.priority,
+            routes: spec.routes,
             trusted_proxies: spec.trusted_proxies,
+            client_ip_header: spec.client_ip_header,
+            preferred_forwarding_header: spec.preferred_forwarding_header.into(),
             enable_geoip: spec.enable_geoip,
             geoip_city_db: spec.geoip_city_db,
             geoip_isp_db: spec.geoip_isp_db,
             geoip_connection_type_db: spec.geoip_connection_type_db,
             enable_user_agent: spec.enable_user_agent,
             ua_engine: spec.ua_engine.into(),
+            ua_cache_capacity: spec.ua_cache_capacity,
         }
     }
 }
@@ -52,3 +70,20 @@ impl From<UaEngineSpec> for UaEngineKind {
         }
     }
 }
+
+#[derive(Default, Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ForwardingHeaderKind {
+    #[default]
+    XForwardedFor,
+    Forwarded,
+}
+
+impl From<ForwardingHeaderSpec> for ForwardingHeaderKind {
+    fn from(header: ForwardingHeaderSpec) -> Self {
+        match header {
+            ForwardingHeaderSpec::XForwardedFor => ForwardingHeaderKind::XForwardedFor,
+            ForwardingHeaderSpec::Forwarded => ForwardingHeaderKind::Forwarded,
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use crate::conf::types::{IpReputationActionSpec, IpReputationDeviceSpec};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct IpReputationDeviceConfig {
+    pub enable: bool,
+
+    pub priority: i32,
+
+    pub routes: Vec<String>,
+
+    /// File listing bad IPs and CIDRs.
+    pub list_file: Option<PathBuf>,
+
+    pub list_reload_interval_seconds: u64,
+
+    /// URL of the external reputation service.
+    pub reputation_url: Option<String>,
+
+    pub timeout_milliseconds: u64,
+
+    pub cache_ttl_milliseconds: u64,
+
+    pub fail_open: bool,
+
+    pub action: IpReputationAction,
+
+    pub block_status: u16,
+
+    pub tag_header: String,
+}
+
+impl From<IpReputationDeviceSpec> for IpReputationDeviceConfig {
+    fn from(spec: IpReputationDeviceSpec) -> Self {
+        Self {
+            enable: spec.enable,
+            priority: spec.priority,
+            routes: spec.routes,
+            list_file: spec.list_file,
+            list_reload_interval_seconds: spec.list_reload_interval_seconds,
+            reputation_url: spec.reputation_url,
+            timeout_milliseconds: spec.timeout_milliseconds,
+            cache_ttl_milliseconds: spec.cache_ttl_milliseconds,
+            fail_open: spec.fail_open,
+            action: spec.action.into(),
+            block_status: spec.block_status,
+            tag_header: spec.tag_header,
+        }
+    }
+}
+
+#[derive(Default, Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum IpReputationAction {
+    #[default]
+    Block,
+    Tag,
+}
+
+impl From<IpReputationActionSpec> for IpReputationAction {
+    fn from(action: IpReputationActionSpec) -> Self {
+        match action {
+            IpReputationActionSpec::Block => IpReputationAction::Block,
+            IpReputationActionSpec::Tag => IpReputationAction::Tag,
+        }
+    }
+}
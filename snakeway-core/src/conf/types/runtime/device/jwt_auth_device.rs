@@ -0,0 +1,48 @@
+use crate::conf::types::JwtAuthDeviceSpec;
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct JwtAuthDeviceConfig {
+    pub enable: bool,
+
+    pub priority: i32,
+
+    pub routes: Vec<String>,
+
+    /// Shared secret for HMAC-signed tokens.
+    pub hmac_secret: Option<String>,
+
+    /// URL of the JWKS document with the token signing keys.
+    pub jwks_url: Option<String>,
+
+    pub algorithms: Vec<String>,
+
+    pub issuer: Option<String>,
+
+    pub audience: Option<String>,
+
+    pub leeway_seconds: u64,
+
+    pub jwks_refresh_seconds: u64,
+
+    pub timeout_milliseconds: u64,
+}
+
+impl From<JwtAuthDeviceSpec> for JwtAuthDeviceConfig {
+    fn from(spec: JwtAuthDeviceSpec) -> Self {
+        Self {
+            enable: spec.enable,
+            priority: spec.priority,
+            routes: spec.routes,
+            hmac_secret: spec.hmac_secret,
+            jwks_url: spec.jwks_url,
+            algorithms: spec.algorithms,
+            issuer: spec.issuer,
+            audience: spec.audience,
+            leeway_seconds: spec.leeway_seconds,
+            jwks_refresh_seconds: spec.jwks_refresh_seconds,
+            timeout_milliseconds: spec.timeout_milliseconds,
+        }
+    }
+}
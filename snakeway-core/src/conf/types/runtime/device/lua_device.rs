@@ -0,0 +1,43 @@
+use crate::conf::types::LuaDeviceSpec;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct LuaDeviceConfig {
+    pub enable: bool,
+
+    pub priority: i32,
+
+    pub routes: Vec<String>,
+
+    /// The Lua script.
+    pub path: PathBuf,
+
+    /// Lua VM instructions each hook call may run.
+    pub instruction_limit: u64,
+
+    /// Memory the script may allocate, in bytes.
+    pub memory_limit_bytes: usize,
+
+    /// Largest body the script may respond with, in bytes.
+    pub max_body_bytes: usize,
+
+    /// Run hook calls on the blocking thread pool.
+    pub offload: bool,
+}
+
+impl From<LuaDeviceSpec> for LuaDeviceConfig {
+    fn from(spec: LuaDeviceSpec) -> Self {
+        Self {
+            enable: spec.enable,
+            priority: spec.priority,
+            routes: spec.routes,
+            path: spec.path,
+            instruction_limit: spec.instruction_limit,
+            memory_limit_bytes: spec.memory_limit_bytes,
+            max_body_bytes: spec.max_body_bytes,
+            offload: spec.offload,
+        }
+    }
+}
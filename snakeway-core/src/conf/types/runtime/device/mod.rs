@@ -1,11 +1,47 @@
+mod body_digest_device;
+mod cors_device;
+mod deprecation_device;
 mod device_config;
+mod error_page_device;
+mod experiment_device;
+mod header_rewrite_device;
+mod idempotency_device;
 mod identity_device;
+mod ip_reputation_device;
+mod jwt_auth_device;
+mod lua_device;
+mod openapi_device;
+mod query_rewrite_device;
+mod quota_device;
+mod rate_limit_device;
 mod request_filter_device;
+mod required_headers_device;
+mod response_headers_device;
+mod status_remap_device;
 mod structured_logging_device;
+mod upstream_group_device;
 mod wasm_device;
 
+pub use body_digest_device::*;
+pub use cors_device::*;
+pub use deprecation_device::*;
 pub use device_config::*;
+pub use error_page_device::*;
+pub use experiment_device::*;
+pub use header_rewrite_device::*;
+pub use idempotency_device::*;
 pub use identity_device::*;
+pub use ip_reputation_device::*;
+pub use jwt_auth_device::*;
+pub use lua_device::*;
+pub use openapi_device::*;
+pub use query_rewrite_device::*;
+pub use quota_device::*;
+pub use rate_limit_device::*;
 pub use request_filter_device::*;
+pub use required_headers_device::*;
+pub use response_headers_device::*;
+pub use status_remap_device::*;
 pub use structured_logging_device::*;
+pub use upstream_group_device::*;
 pub use wasm_device::*;
@@ -0,0 +1,33 @@
+use crate::conf::types::OpenApiDeviceSpec;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct OpenApiDeviceConfig {
+    pub enable: bool,
+
+    pub priority: i32,
+
+    pub routes: Vec<String>,
+
+    /// Path to the OpenAPI 3 document requests are validated against.
+    pub spec_path: PathBuf,
+
+    pub max_body_bytes: usize,
+
+    pub reject_unknown_operations: bool,
+}
+
+impl From<OpenApiDeviceSpec> for OpenApiDeviceConfig {
+    fn from(spec: OpenApiDeviceSpec) -> Self {
+        Self {
+            enable: spec.enable,
+            priority: spec.priority,
+            routes: spec.routes,
+            spec_path: spec.spec_path,
+            max_body_bytes: spec.max_body_bytes,
+            reject_unknown_operations: spec.reject_unknown_operations,
+        }
+    }
+}
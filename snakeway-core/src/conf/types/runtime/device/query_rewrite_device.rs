@@ -0,0 +1,57 @@
+use crate::conf::types::{QueryOperationKindSpec, QueryOperationSpec, QueryRewriteDeviceSpec};
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct QueryRewriteDeviceConfig {
+    pub enable: bool,
+
+    pub priority: i32,
+
+    pub routes: Vec<String>,
+
+    pub operations: Vec<QueryOperation>,
+}
+
+impl From<QueryRewriteDeviceSpec> for QueryRewriteDeviceConfig {
+    fn from(spec: QueryRewriteDeviceSpec) -> Self {
+        Self {
+            enable: spec.enable,
+            priority: spec.priority,
+            routes: spec.routes,
+            operations: spec.operations.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// One step of a query rewrite. Names and values are unencoded, as written in config.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum QueryOperation {
+    AddIfMissing { name: String, value: String },
+    Set { name: String, value: String },
+    Remove { name: String },
+    Rename { name: String, to: String },
+}
+
+impl From<QueryOperationSpec> for QueryOperation {
+    fn from(spec: QueryOperationSpec) -> Self {
+        // Validation guarantees `value` and `to` are present for the operations that need them.
+        let value = spec.value.unwrap_or_default();
+        match spec.op {
+            QueryOperationKindSpec::AddIfMissing => Self::AddIfMissing {
+                name: spec.name,
+                value,
+            },
+            QueryOperationKindSpec::Set => Self::Set {
+                name: spec.name,
+                value,
+            },
+            QueryOperationKindSpec::Remove => Self::Remove { name: spec.name },
+            QueryOperationKindSpec::Rename => Self::Rename {
+                name: spec.name,
+                to: spec.to.unwrap_or_default(),
+            },
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use crate::conf::types::QuotaDeviceSpec;
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct QuotaDeviceConfig {
+    pub enable: bool,
+
+    pub priority: i32,
+
+    pub routes: Vec<String>,
+
+    /// URL of the external quota service.
+    pub quota_url: String,
+
+    /// Request header carrying the API key.
+    pub api_key_header: String,
+
+    pub timeout_milliseconds: u64,
+
+    pub cache_ttl_milliseconds: u64,
+
+    pub fail_open: bool,
+}
+
+impl From<QuotaDeviceSpec> for QuotaDeviceConfig {
+    fn from(spec: QuotaDeviceSpec) -> Self {
+        Self {
+            enable: spec.enable,
+            priority: spec.priority,
+            routes: spec.routes,
+            quota_url: spec.quota_url,
+            api_key_header: spec.api_key_header,
+            timeout_milliseconds: spec.timeout_milliseconds,
+            cache_ttl_milliseconds: spec.cache_ttl_milliseconds,
+            fail_open: spec.fail_open,
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use crate::conf::types::{RateLimitDeviceSpec, RateLimitKeySpec};
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimitDeviceConfig {
+    pub enable: bool,
+
+    pub priority: i32,
+
+    pub routes: Vec<String>,
+
+    pub requests_per_second: u32,
+
+    pub burst: u32,
+
+    pub key: RateLimitKey,
+}
+
+impl From<RateLimitDeviceSpec> for RateLimitDeviceConfig {
+    fn from(spec: RateLimitDeviceSpec) -> Self {
+        let key = match spec.key {
+            RateLimitKeySpec::ClientIp => RateLimitKey::ClientIp,
+            RateLimitKeySpec::Header => RateLimitKey::Header(spec.key_header.unwrap_or_default()),
+            RateLimitKeySpec::Path => RateLimitKey::Path,
+        };
+
+        Self {
+            enable: spec.enable,
+            priority: spec.priority,
+            routes: spec.routes,
+            requests_per_second: spec.requests_per_second,
+            burst: spec.burst.unwrap_or(spec.requests_per_second),
+            key,
+        }
+    }
+}
+
+#[derive(Default, Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitKey {
+    #[default]
+    ClientIp,
+    Header(String),
+    Path,
+}
@@ -7,6 +7,8 @@ use serde::{Deserialize, Serialize};
 #[serde(deny_unknown_fields)]
 pub struct RequestFilterDeviceConfig {
     pub enable: bool,
+    pub priority: i32,
+    pub routes: Vec<String>,
     #[serde(with = "serde_method_vec")]
     pub allow_methods: Vec<Method>,
     #[serde(with = "serde_method_vec")]
@@ -86,6 +88,8 @@ impl TryFrom<RequestFilterDeviceSpec> for RequestFilterDeviceConfig {
 
         Ok(Self {
             enable: spec.enable,
+            priority: spec.priority,
+            routes: spec.routes,
             allow_methods,
             deny_methods,
             deny_headers,
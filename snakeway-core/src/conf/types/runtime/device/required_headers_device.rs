@@ -0,0 +1,64 @@
+use crate::conf::types::{MissingHeaderActionSpec, RequiredHeaderSpec, RequiredHeadersDeviceSpec};
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RequiredHeadersDeviceConfig {
+    pub enable: bool,
+
+    pub priority: i32,
+
+    pub routes: Vec<String>,
+
+    /// Headers every request must carry.
+    pub headers: Vec<RequiredHeaderConfig>,
+}
+
+impl From<RequiredHeadersDeviceSpec> for RequiredHeadersDeviceConfig {
+    fn from(spec: RequiredHeadersDeviceSpec) -> Self {
+        Self {
+            enable: spec.enable,
+            priority: spec.priority,
+            routes: spec.routes,
+            headers: spec.headers.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RequiredHeaderConfig {
+    pub name: String,
+
+    pub on_missing: MissingHeaderAction,
+
+    /// Value template for `populate` mode.
+    pub value: Option<String>,
+}
+
+impl From<RequiredHeaderSpec> for RequiredHeaderConfig {
+    fn from(spec: RequiredHeaderSpec) -> Self {
+        Self {
+            name: spec.name,
+            on_missing: spec.on_missing.into(),
+            value: spec.value,
+        }
+    }
+}
+
+#[derive(Default, Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MissingHeaderAction {
+    #[default]
+    Reject,
+    Populate,
+}
+
+impl From<MissingHeaderActionSpec> for MissingHeaderAction {
+    fn from(action: MissingHeaderActionSpec) -> Self {
+        match action {
+            MissingHeaderActionSpec::Reject => MissingHeaderAction::Reject,
+            MissingHeaderActionSpec::Populate => MissingHeaderAction::Populate,
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use crate::conf::types::{ResponseHeaderSpec, ResponseHeadersDeviceSpec};
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ResponseHeadersDeviceConfig {
+    pub enable: bool,
+
+    pub priority: i32,
+
+    pub routes: Vec<String>,
+
+    /// Headers added to every proxied response.
+    pub headers: Vec<ResponseHeaderConfig>,
+}
+
+impl From<ResponseHeadersDeviceSpec> for ResponseHeadersDeviceConfig {
+    fn from(spec: ResponseHeadersDeviceSpec) -> Self {
+        Self {
+            enable: spec.enable,
+            priority: spec.priority,
+            routes: spec.routes,
+            headers: spec.headers.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ResponseHeaderConfig {
+    pub name: String,
+
+    /// Value template.
+    pub value: String,
+}
+
+impl From<ResponseHeaderSpec> for ResponseHeaderConfig {
+    fn from(spec: ResponseHeaderSpec) -> Self {
+        Self {
+            name: spec.name,
+            value: spec.value,
+        }
+    }
+}
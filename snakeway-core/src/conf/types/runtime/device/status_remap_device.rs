@@ -0,0 +1,47 @@
+use crate::conf::types::{StatusRemapDeviceSpec, StatusRemapSpec};
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct StatusRemapDeviceConfig {
+    pub enable: bool,
+
+    pub priority: i32,
+
+    pub routes: Vec<String>,
+
+    /// Upstream statuses to rewrite.
+    pub remaps: Vec<StatusRemapConfig>,
+}
+
+impl From<StatusRemapDeviceSpec> for StatusRemapDeviceConfig {
+    fn from(spec: StatusRemapDeviceSpec) -> Self {
+        Self {
+            enable: spec.enable,
+            priority: spec.priority,
+            routes: spec.routes,
+            remaps: spec.remaps.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct StatusRemapConfig {
+    pub from: u16,
+
+    pub to: u16,
+
+    /// Replacement response body, if any.
+    pub body: Option<String>,
+}
+
+impl From<StatusRemapSpec> for StatusRemapConfig {
+    fn from(spec: StatusRemapSpec) -> Self {
+        Self {
+            from: spec.from,
+            to: spec.to,
+            body: spec.body,
+        }
+    }
+}
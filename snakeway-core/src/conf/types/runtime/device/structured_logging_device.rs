@@ -1,5 +1,7 @@
 use crate::conf::types::StructuredLoggingDeviceSpec;
-use crate::device::builtin::structured_logging::{IdentityField, LogEvent, LogLevel, LogPhase};
+use crate::device::builtin::structured_logging::{
+    IdentityField, LogEvent, LogLevel, LogPhase, RouteHeaderCapture,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Default, Clone, Debug, Deserialize, Serialize)]
@@ -7,6 +9,10 @@ use serde::{Deserialize, Serialize};
 pub struct StructuredLoggingDeviceConfig {
     pub enable: bool,
 
+    pub priority: i32,
+
+    pub routes: Vec<String>,
+
     pub level: LogLevel,
 
     /// Headers are excluded by default.
@@ -25,6 +31,10 @@ pub struct StructuredLoggingDeviceConfig {
     /// Identity fields to include in the request context (and possibly log).
     pub identity_fields: Vec<IdentityField>,
 
+    /// Headers to capture on specific routes only (subject to redaction).
+    #[serde(default)]
+    pub route_headers: Vec<RouteHeaderCapture>,
+
     pub events: Option<Vec<LogEvent>>,
 
     pub phases: Option<Vec<LogPhase>>,
@@ -34,12 +44,15 @@ impl From<StructuredLoggingDeviceSpec> for StructuredLoggingDeviceConfig {
     fn from(spec: StructuredLoggingDeviceSpec) -> Self {
         Self {
             enable: spec.enable,
+            priority: spec.priority,
+            routes: spec.routes,
             level: spec.level,
             include_headers: spec.include_headers,
             allowed_headers: spec.allowed_headers,
             redacted_headers: spec.redacted_headers,
             include_identity: spec.include_identity,
             identity_fields: spec.identity_fields,
+            route_headers: spec.route_headers,
             events: spec.events,
             phases: spec.phases,
         }
@@ -0,0 +1,31 @@
+use crate::conf::types::UpstreamGroupDeviceSpec;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct UpstreamGroupDeviceConfig {
+    pub enable: bool,
+
+    pub priority: i32,
+
+    pub routes: Vec<String>,
+
+    /// Request header that selects the upstream group.
+    pub header: String,
+
+    /// Header value to upstream group.
+    pub groups: HashMap<String, String>,
+}
+
+impl From<UpstreamGroupDeviceSpec> for UpstreamGroupDeviceConfig {
+    fn from(spec: UpstreamGroupDeviceSpec) -> Self {
+        Self {
+            enable: spec.enable,
+            priority: spec.priority,
+            routes: spec.routes,
+            header: spec.header,
+            groups: spec.groups,
+        }
+    }
+}
@@ -6,9 +6,22 @@ use std::path::PathBuf;
 pub struct WasmDeviceConfig {
     pub enable: bool,
 
+    pub priority: i32,
+
+    pub routes: Vec<String>,
+
+    /// Name used to attach the device to listeners.
+    pub name: String,
+
     /// The location of the WASM module.
     pub path: PathBuf,
 
+    /// Fuel each hook call may consume.
+    pub fuel: u64,
+
+    /// Run hook calls on the blocking thread pool.
+    pub offload: bool,
+
     /// Device-specific configuration blob
     pub config: Option<hcl::Value>,
 }
@@ -17,7 +30,12 @@ impl From<WasmDeviceSpec> for WasmDeviceConfig {
     fn from(spec: WasmDeviceSpec) -> Self {
         Self {
             enable: spec.enable,
+            priority: spec.priority,
+            routes: spec.routes,
+            name: spec.name(),
             path: spec.path,
+            fuel: spec.fuel,
+            offload: spec.offload,
             config: spec.config,
         }
     }
@@ -21,6 +21,11 @@ pub struct ListenerConfig {
 
     /// Optional redirect config.
     pub redirect: Option<RedirectConfig>,
+
+    /// Names of devices attached to this listener only.
+    ///
+    /// Public listeners run global devices plus these; admin listeners run only these.
+    pub devices: Vec<String>,
 }
 
 impl ListenerConfig {
@@ -37,10 +42,8 @@ impl ListenerConfig {
             tls: None,
             enable_http2: false,
             enable_admin: false,
-            redirect: Some(RedirectConfig::new(
-                addr.to_string(),
-                redirect_response_code,
-            )),
+            redirect: Some(RedirectConfig::new(addr.port(), redirect_response_code)),
+            devices: Vec::new(),
         }
     }
 
@@ -55,6 +58,7 @@ impl ListenerConfig {
             enable_http2: spec.enable_http2,
             enable_admin: false,
             redirect: None,
+            devices: spec.devices,
         }
     }
 
@@ -69,20 +73,23 @@ impl ListenerConfig {
             enable_http2: false,
             enable_admin: true,
             redirect: None,
+            devices: spec.devices,
         }
     }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct RedirectConfig {
-    pub destination: String,
+    /// Port of the HTTPS listener requests are redirected to.
+    /// The host is taken from each request, since the listener may bind an internal address.
+    pub https_port: u16,
     pub response_code: u16,
 }
 
 impl RedirectConfig {
-    pub fn new(destination: String, response_code: u16) -> Self {
+    pub fn new(https_port: u16, response_code: u16) -> Self {
         Self {
-            destination,
+            https_port,
             response_code,
         }
     }
@@ -1,3 +1,4 @@
+pub mod access_log;
 pub mod device;
 pub mod listener;
 pub mod route;
@@ -6,6 +7,7 @@ pub mod service;
 pub mod tls;
 
 use crate::conf::types::ServerConfig;
+pub use access_log::*;
 pub use device::*;
 pub use listener::*;
 pub use route::*;
@@ -22,3 +24,34 @@ pub struct RuntimeConfig {
     pub services: HashMap<String, ServiceConfig>,
     pub devices: Vec<DeviceConfig>,
 }
+
+impl RuntimeConfig {
+    /// Whether a device runs on a listener.
+    ///
+    /// Devices attached to listeners run only on those listeners. Every other device is global
+    /// and runs on every public listener; admin listeners never run global devices.
+    pub fn device_runs_on(&self, device: &str, listener: &ListenerConfig) -> bool {
+        let is_attached = self
+            .listeners
+            .iter()
+            .any(|l| l.devices.iter().any(|d| d == device));
+
+        listener.devices.iter().any(|d| d == device) || (!listener.enable_admin && !is_attached)
+    }
+
+    /// Every device in pipeline order: ascending priority, then the order they are parsed in.
+    pub fn ordered_devices(&self) -> Vec<&DeviceConfig> {
+        let mut devices: Vec<&DeviceConfig> = self.devices.iter().collect();
+        // The sort is stable, so devices with equal priorities keep the order they are parsed in.
+        devices.sort_by_key(|d| d.priority());
+        devices
+    }
+
+    /// The enabled devices that run on a listener, in pipeline order.
+    pub fn device_pipeline(&self, listener: &ListenerConfig) -> Vec<&DeviceConfig> {
+        self.ordered_devices()
+            .into_iter()
+            .filter(|d| d.is_enabled() && self.device_runs_on(d.name(), listener))
+            .collect()
+    }
+}
@@ -1,5 +1,9 @@
-use crate::conf::types::ServiceRouteSpec;
+use crate::conf::types::{
+    RequireTlsAction, RequireTlsSpec, ResponseRateLimitSpec, ServiceRouteSpec,
+};
+use http::StatusCode;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ServiceRouteConfig {
@@ -11,6 +15,22 @@ pub struct ServiceRouteConfig {
     pub allow_websocket: bool,
     pub ws_max_connections: Option<usize>,
 
+    pub response_rate_limit: Option<ResponseRateLimitConfig>,
+
+    /// Overrides the server's `hide_as_404`.
+    pub hide_as_404: Option<bool>,
+
+    /// Overrides the server's `max_request_body_bytes`.
+    pub max_request_body_bytes: Option<u64>,
+
+    /// Overrides the server's `request_timeout_milliseconds`.
+    pub request_timeout: Option<Duration>,
+
+    pub require_tls: Option<RequireTlsConfig>,
+
+    /// How long sticky strategies honor a client's upstream affinity.
+    pub affinity_ttl: Option<Duration>,
+
     pub listener: String,
 }
 
@@ -22,6 +42,54 @@ impl ServiceRouteConfig {
             path: spec.path,
             allow_websocket: spec.enable_websocket,
             ws_max_connections: spec.ws_max_connections,
+            response_rate_limit: spec.response_rate_limit.map(Into::into),
+            hide_as_404: spec.hide_as_404,
+            max_request_body_bytes: spec.max_request_body_bytes,
+            request_timeout: spec.request_timeout_milliseconds.map(Duration::from_millis),
+            require_tls: spec.require_tls.map(Into::into),
+            affinity_ttl: spec.affinity_ttl_seconds.map(Duration::from_secs),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ResponseRateLimitConfig {
+    pub bytes_per_second: u64,
+    pub burst_bytes: u64,
+}
+
+impl From<ResponseRateLimitSpec> for ResponseRateLimitConfig {
+    fn from(spec: ResponseRateLimitSpec) -> Self {
+        Self {
+            bytes_per_second: spec.bytes_per_second,
+            burst_bytes: spec.burst_bytes.unwrap_or(spec.bytes_per_second),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RequireTlsConfig {
+    pub action: RequireTlsAction,
+    pub reject_status: u16,
+}
+
+impl From<RequireTlsSpec> for RequireTlsConfig {
+    fn from(spec: RequireTlsSpec) -> Self {
+        Self {
+            action: spec.action,
+            reject_status: spec.reject_status.unwrap_or(StatusCode::FORBIDDEN.as_u16()),
+        }
+    }
+}
+
+impl RequireTlsConfig {
+    /// Status requests arriving over plaintext HTTP are answered with.
+    pub fn status(&self) -> StatusCode {
+        match self.action {
+            RequireTlsAction::Redirect => StatusCode::PERMANENT_REDIRECT,
+            RequireTlsAction::Reject => {
+                StatusCode::from_u16(self.reject_status).unwrap_or(StatusCode::FORBIDDEN)
+            }
         }
     }
 }
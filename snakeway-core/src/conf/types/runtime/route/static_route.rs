@@ -1,4 +1,6 @@
-use crate::conf::types::{CachePolicySpec, CompressionOptsSpec, StaticRouteSpec};
+use crate::conf::types::{
+    CachePolicySpec, CompressionOptsSpec, RequireTlsConfig, SpaFallbackSpec, StaticRouteSpec,
+};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -19,6 +21,13 @@ pub struct StaticRouteConfig {
 
     pub static_config: CompressionOptions,
     pub cache_policy: CachePolicy,
+
+    /// Overrides the server's `hide_as_404`.
+    pub hide_as_404: Option<bool>,
+
+    pub require_tls: Option<RequireTlsConfig>,
+
+    pub spa_fallback: Option<SpaFallbackConfig>,
 }
 
 impl StaticRouteConfig {
@@ -32,6 +41,25 @@ impl StaticRouteConfig {
             max_file_size: spec.max_file_size,
             static_config: spec.compression.into(),
             cache_policy: spec.cache_policy.into(),
+            hide_as_404: spec.hide_as_404,
+            require_tls: spec.require_tls.map(Into::into),
+            spa_fallback: spec.spa_fallback.map(Into::into),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SpaFallbackConfig {
+    /// Fallback document, relative to the route's `file_dir`.
+    pub document: String,
+    pub missing_assets_404: bool,
+}
+
+impl From<SpaFallbackSpec> for SpaFallbackConfig {
+    fn from(spec: SpaFallbackSpec) -> Self {
+        Self {
+            document: spec.document.unwrap_or_else(|| "index.html".to_string()),
+            missing_assets_404: spec.missing_assets_404.unwrap_or(true),
         }
     }
 }
@@ -43,6 +71,8 @@ pub struct CompressionOptions {
     pub min_brotli_size: u64,
     pub enable_gzip: bool,
     pub enable_brotli: bool,
+    /// Content type patterns (`type/subtype` or `type/*`) that are served uncompressed.
+    pub skip_content_types: Vec<String>,
 }
 
 impl From<CompressionOptsSpec> for CompressionOptions {
@@ -53,6 +83,7 @@ impl From<CompressionOptsSpec> for CompressionOptions {
             min_brotli_size: spec.min_brotli_size,
             enable_gzip: spec.enable_gzip,
             enable_brotli: spec.enable_brotli,
+            skip_content_types: spec.skip_content_types,
         }
     }
 }
@@ -16,4 +16,61 @@ pub struct ServerConfig {
     /// CA file path.
     /// If set/not empty, Pingora will use this file to verify upstream certificates.
     pub ca_file: String,
+
+    /// Time in seconds to drain in-flight requests on shutdown.
+    /// Requests still in flight after this are dropped.
+    pub shutdown_timeout_seconds: u64,
+
+    /// Time in seconds listeners keep accepting on shutdown after readiness flips.
+    /// If zero, listeners stop accepting as soon as readiness flips.
+    pub shutdown_readiness_grace_seconds: u64,
+
+    /// Reject requests whose Host is not declared in any service's `hosts` on the listener.
+    pub strict_host: bool,
+
+    /// Answer requests denied by a device with `404` instead of `403`, unless the route overrides it.
+    pub hide_as_404: bool,
+
+    /// Maximum number of concurrent WebSocket connections across all routes.
+    /// If `None`, only the per-route limits apply.
+    pub ws_max_connections: Option<usize>,
+
+    /// Largest request body accepted, unless the route overrides it.
+    /// If `None`, request bodies are not limited.
+    pub max_request_body_bytes: Option<u64>,
+
+    /// Time a proxied request may take, unless the route overrides it.
+    /// If `None`, only the upstream connection timeouts apply.
+    pub request_timeout_milliseconds: Option<u64>,
+
+    /// Bind one SO_REUSEPORT socket per worker thread on public listeners.
+    pub reuse_port: bool,
+
+    /// Fraction of connections whose lifecycle events are logged.
+    /// Failure events bypass sampling.
+    pub connection_log_sample_rate: f64,
+
+    /// Fail startup when a WASM device fails to load.
+    /// If false, such devices are disabled with a warning.
+    pub strict_wasm_devices: bool,
+
+    /// Proxies whose `X-Forwarded-Host` is trusted as the host of redirect URLs.
+    pub trusted_proxies: Vec<String>,
+
+    /// Log one `access` event per request.
+    pub access_log: bool,
+
+    /// Template of the access log line. Every field is logged when unset.
+    pub access_log_format: Option<String>,
+
+    /// MaxMind databases the identity device looks up client locations in.
+    pub geoip: GeoIpConfig,
+}
+
+/// MaxMind `.mmdb` databases. Lookups skip any that are unset.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct GeoIpConfig {
+    pub city_db: Option<PathBuf>,
+    pub isp_db: Option<PathBuf>,
+    pub connection_type_db: Option<PathBuf>,
 }
@@ -1,8 +1,10 @@
 use crate::conf::types::runtime::service::upstream::UpstreamTcpConfig;
 use crate::conf::types::{
-    CircuitBreakerConfig, HealthCheckConfig, LoadBalancingStrategySpec, ServiceSpec,
+    CircuitBreakerConfig, HealthCheckConfig, KetamaConfig, LoadBalancingStrategySpec,
+    OutlierDetectionConfig, RetryPolicyConfig, ServiceSpec, UpstreamRequestHeaderSpec,
     UpstreamUnixConfig,
 };
+use crate::conf::{Secret, SecretResolver};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -22,6 +24,35 @@ pub struct ServiceConfig {
     pub circuit_breaker: CircuitBreakerConfig,
 
     pub health_check: HealthCheckConfig,
+
+    pub ketama: KetamaConfig,
+
+    pub outlier_detection: OutlierDetectionConfig,
+
+    pub retry_policy: RetryPolicyConfig,
+
+    /// Lowercased request hosts this service answers for.
+    pub hosts: Vec<String>,
+
+    /// Header names rewritten to this exact casing toward HTTP/1.1 upstreams.
+    pub upstream_header_case: Vec<String>,
+
+    /// SNI template for TLS upstreams; see `ServiceSpec::upstream_sni`.
+    pub upstream_sni: Option<String>,
+
+    /// `Accept-Encoding` policy toward upstreams; see `ServiceSpec::upstream_accept_encoding`.
+    pub upstream_accept_encoding: Option<String>,
+
+    pub enable_response_compression: bool,
+
+    /// Headers injected into every upstream request; see `ServiceSpec::upstream_request_headers`.
+    pub upstream_request_headers: Vec<UpstreamRequestHeaderConfig>,
+
+    /// Content type patterns that are proxied uncompressed.
+    pub response_compression_skip_content_types: Vec<String>,
+
+    /// Smallest `Content-Length` that is compressed.
+    pub response_compression_min_size: u64,
 }
 
 impl ServiceConfig {
@@ -40,6 +71,56 @@ impl ServiceConfig {
             unix_upstreams,
             circuit_breaker: spec.circuit_breaker.clone().unwrap_or_default(),
             health_check: spec.health_check.clone().unwrap_or_default(),
+            ketama: spec.ketama.clone().unwrap_or_default(),
+            outlier_detection: spec.outlier_detection.clone().unwrap_or_default(),
+            retry_policy: spec.retry_policy.clone().unwrap_or_default(),
+            hosts: spec.hosts.iter().map(|h| h.to_string()).collect(),
+            upstream_header_case: spec.upstream_header_case.clone(),
+            upstream_sni: spec.upstream_sni.clone(),
+            upstream_accept_encoding: spec.upstream_accept_encoding.clone(),
+            enable_response_compression: spec.enable_response_compression,
+            upstream_request_headers: spec
+                .upstream_request_headers
+                .iter()
+                .cloned()
+                .map(Into::into)
+                .collect(),
+            response_compression_skip_content_types: spec
+                .response_compression_skip_content_types
+                .clone(),
+            response_compression_min_size: spec.response_compression_min_size,
+        }
+    }
+}
+
+/// A static upstream request header.
+///
+/// A secret reference is kept in `secret`, and its value is resolved into `value`
+/// while loading the config. `value` is redacted in dumps and logs either way.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct UpstreamRequestHeaderConfig {
+    pub name: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+
+    pub value: Secret,
+}
+
+impl From<UpstreamRequestHeaderSpec> for UpstreamRequestHeaderConfig {
+    fn from(spec: UpstreamRequestHeaderSpec) -> Self {
+        if SecretResolver::is_reference(spec.value.expose()) {
+            Self {
+                name: spec.name,
+                secret: Some(spec.value.expose().to_string()),
+                value: Secret::new(""),
+            }
+        } else {
+            Self {
+                name: spec.name,
+                secret: None,
+                value: spec.value,
+            }
         }
     }
 }
@@ -51,6 +132,8 @@ pub enum LoadBalancingStrategy {
     RequestPressure,
     StickyHash,
     Random,
+    Ewma,
+    Ketama,
 }
 
 impl From<LoadBalancingStrategySpec> for LoadBalancingStrategy {
@@ -61,6 +144,8 @@ impl From<LoadBalancingStrategySpec> for LoadBalancingStrategy {
             LoadBalancingStrategySpec::RequestPressure => Self::RequestPressure,
             LoadBalancingStrategySpec::StickyHash => Self::StickyHash,
             LoadBalancingStrategySpec::Random => Self::Random,
+            LoadBalancingStrategySpec::Ewma => Self::Ewma,
+            LoadBalancingStrategySpec::Ketama => Self::Ketama,
         }
     }
 }
@@ -1,5 +1,5 @@
 use crate::conf::resolution::ResolveError;
-use crate::conf::types::EndpointSpec;
+use crate::conf::types::{EndpointSpec, HostSpec, UpstreamConnectionSpec};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -7,16 +7,40 @@ pub struct UpstreamTcpConfig {
     /// e.g. "http://10.0.0.1:8080"
     pub url: String,
 
+    /// The configured hostname, if the endpoint was not an IP.
+    /// It is re-resolved at connection time, `url` holds the address it resolved to at load.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+
     pub weight: u32,
+
+    pub connection: UpstreamConnectionConfig,
+
+    /// Upstream group, `None` for the default pool.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
 }
 
 impl UpstreamTcpConfig {
-    pub fn new(use_tls: bool, weight: u32, spec: &EndpointSpec) -> Result<Self, ResolveError> {
+    pub fn new(
+        use_tls: bool,
+        weight: u32,
+        connection: UpstreamConnectionConfig,
+        group: Option<String>,
+        spec: &EndpointSpec,
+    ) -> Result<Self, ResolveError> {
         let protocol = if use_tls { "https" } else { "http" };
         let addr = spec.resolve()?;
+        let hostname = match &spec.host {
+            HostSpec::Ip(_) => None,
+            HostSpec::Hostname(_) => Some(spec.host.to_string()),
+        };
         Ok(Self {
             weight,
             url: format!("{protocol}://{addr}"),
+            hostname,
+            connection,
+            group,
         })
     }
 }
@@ -31,15 +55,115 @@ pub struct UpstreamUnixConfig {
     pub sni: String,
 
     pub weight: u32,
+
+    pub connection: UpstreamConnectionConfig,
+
+    /// Upstream group, `None` for the default pool.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
 }
 
 impl UpstreamUnixConfig {
-    pub fn new(sock: String, use_tls: bool, weight: u32) -> Self {
+    pub fn new(
+        sock: String,
+        use_tls: bool,
+        weight: u32,
+        connection: UpstreamConnectionConfig,
+        group: Option<String>,
+    ) -> Self {
         Self {
             sock,
             use_tls,
             sni: "localhost".to_string(),
             weight,
+            connection,
+            group,
+        }
+    }
+}
+
+/// Effective connection settings for a single upstream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct UpstreamConnectionConfig {
+    pub connect_timeout_milliseconds: u64,
+
+    pub read_timeout_milliseconds: u64,
+
+    /// How long an idle pooled connection is kept before it is closed.
+    pub idle_timeout_seconds: u64,
+
+    /// How long a response body may go without bytes from the upstream before it is aborted.
+    /// `0` disables the check, leaving only `read_timeout_milliseconds`.
+    pub response_idle_timeout_milliseconds: u64,
+
+    /// How many times a failed connection attempt is retried.
+    pub max_connect_retries: u32,
+}
+
+impl Default for UpstreamConnectionConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout_milliseconds: 5_000,
+            read_timeout_milliseconds: 60_000,
+            idle_timeout_seconds: 60,
+            response_idle_timeout_milliseconds: 0,
+            max_connect_retries: 0,
+        }
+    }
+}
+
+impl UpstreamConnectionConfig {
+    /// Resolve the effective settings for an upstream.
+    ///
+    /// Precedence per field: upstream, then service, then the built-in default.
+    pub fn resolve(
+        service: Option<&UpstreamConnectionSpec>,
+        upstream: Option<&UpstreamConnectionSpec>,
+    ) -> Self {
+        fn pick<T>(
+            service: Option<&UpstreamConnectionSpec>,
+            upstream: Option<&UpstreamConnectionSpec>,
+            field: impl Fn(&UpstreamConnectionSpec) -> Option<T>,
+            default: T,
+        ) -> T {
+            upstream
+                .and_then(&field)
+                .or_else(|| service.and_then(&field))
+                .unwrap_or(default)
+        }
+
+        let defaults = Self::default();
+        Self {
+            connect_timeout_milliseconds: pick(
+                service,
+                upstream,
+                |c| c.connect_timeout_milliseconds,
+                defaults.connect_timeout_milliseconds,
+            ),
+            read_timeout_milliseconds: pick(
+                service,
+                upstream,
+                |c| c.read_timeout_milliseconds,
+                defaults.read_timeout_milliseconds,
+            ),
+            idle_timeout_seconds: pick(
+                service,
+                upstream,
+                |c| c.idle_timeout_seconds,
+                defaults.idle_timeout_seconds,
+            ),
+            response_idle_timeout_milliseconds: pick(
+                service,
+                upstream,
+                |c| c.response_idle_timeout_milliseconds,
+                defaults.response_idle_timeout_milliseconds,
+            ),
+            max_connect_retries: pick(
+                service,
+                upstream,
+                |c| c.max_connect_retries,
+                defaults.max_connect_retries,
+            ),
         }
     }
 }
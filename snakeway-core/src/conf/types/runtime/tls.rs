@@ -1,12 +1,45 @@
+use crate::conf::secrets::Secret;
 use crate::conf::types::TlsSpec;
 use serde::{Deserialize, Serialize};
 
 /// Paths are validated and resolved during config validation.
 /// Runtime code assumes these values are valid.
+///
+/// `cert` and `key` may instead be secret references, whose PEM contents are resolved
+/// into `cert_pem` and `key_pem` while loading the config.
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
 pub struct TlsConfig {
     pub cert: String,
     pub key: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cert_pem: Option<Secret>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_pem: Option<Secret>,
+}
+
+impl TlsConfig {
+    /// Certificate and key file paths for the TLS listener.
+    /// Secrets are written to private files first, since the listener only accepts paths.
+    pub fn paths(&self) -> std::io::Result<(String, String)> {
+        Ok((
+            material_path(&self.cert, self.cert_pem.as_ref())?,
+            material_path(&self.key, self.key_pem.as_ref())?,
+        ))
+    }
+}
+
+fn material_path(value: &str, pem: Option<&Secret>) -> std::io::Result<String> {
+    let Some(pem) = pem else {
+        return Ok(value.to_string());
+    };
+
+    let name = value
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    Ok(pem.materialize(&name)?.to_string_lossy().into_owned())
 }
 
 impl From<TlsSpec> for TlsConfig {
@@ -14,6 +47,8 @@ impl From<TlsSpec> for TlsConfig {
         Self {
             cert: spec.cert,
             key: spec.key,
+            cert_pem: None,
+            key_pem: None,
         }
     }
 }
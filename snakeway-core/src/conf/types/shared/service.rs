@@ -1,12 +1,15 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, Default)]
 pub struct HealthCheckConfig {
     pub enable: bool,
     #[serde(default = "hc_default_threshold")]
     pub failure_threshold: u32,
     #[serde(default = "hc_default_unhealthy_cooldown_seconds")]
     pub unhealthy_cooldown_seconds: u64,
+    /// Probe upstreams in the background, on top of watching the requests they serve.
+    pub active: Option<ActiveHealthCheckConfig>,
 }
 
 fn hc_default_threshold() -> u32 {
@@ -17,7 +20,71 @@ fn hc_default_unhealthy_cooldown_seconds() -> u64 {
     10
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize)]
+pub struct ActiveHealthCheckConfig {
+    /// Path of the `GET` request sent to each upstream.
+    #[serde(default = "ahc_default_path")]
+    pub path: String,
+
+    /// Time between probes of an upstream.
+    #[serde(default = "ahc_default_interval_milliseconds")]
+    pub interval_milliseconds: u64,
+
+    /// Probes not answered within this time fail.
+    #[serde(default = "ahc_default_timeout_milliseconds")]
+    pub timeout_milliseconds: u64,
+
+    /// Status a healthy upstream answers with. Any other status fails the probe.
+    #[serde(default = "ahc_default_expected_status")]
+    pub expected_status: u16,
+
+    /// Consecutive failed probes that take an upstream out of rotation.
+    #[serde(default = "ahc_default_failure_threshold")]
+    pub failure_threshold: u32,
+
+    /// Consecutive successful probes that put it back.
+    #[serde(default = "ahc_default_success_threshold")]
+    pub success_threshold: u32,
+}
+
+fn ahc_default_path() -> String {
+    "/".to_string()
+}
+
+fn ahc_default_interval_milliseconds() -> u64 {
+    5_000
+}
+
+fn ahc_default_timeout_milliseconds() -> u64 {
+    1_000
+}
+
+fn ahc_default_expected_status() -> u16 {
+    200
+}
+
+fn ahc_default_failure_threshold() -> u32 {
+    3
+}
+
+fn ahc_default_success_threshold() -> u32 {
+    2
+}
+
+impl Default for ActiveHealthCheckConfig {
+    fn default() -> Self {
+        Self {
+            path: ahc_default_path(),
+            interval_milliseconds: ahc_default_interval_milliseconds(),
+            timeout_milliseconds: ahc_default_timeout_milliseconds(),
+            expected_status: ahc_default_expected_status(),
+            failure_threshold: ahc_default_failure_threshold(),
+            success_threshold: ahc_default_success_threshold(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, Default)]
 pub struct CircuitBreakerConfig {
     /// Enable circuit breaking auto recovery for this service.
     #[serde(default)]
@@ -36,13 +103,201 @@ pub struct CircuitBreakerConfig {
     #[serde(default = "cb_default_half_open_max_requests")]
     pub half_open_max_requests: u32,
 
-    /// How many successful probes close the circuit again.
+    /// How many successful probes close the circuit again. Half-open admits exactly this many
+    /// probes, and any of them failing re-opens the circuit.
     #[serde(default = "cb_default_success_threshold")]
     pub success_threshold: u32,
 
+    /// Keep the circuit open longer each time its probes fail, see
+    /// `CircuitBreakerBackoffConfig`. Without it, every open lasts the open duration.
+    #[serde(default)]
+    pub open_backoff: Option<CircuitBreakerBackoffConfig>,
+
     /// Whether HTTP 5xx responses count as failures for the circuit.
     #[serde(default = "cb_default_count_http_5xx_as_failure")]
     pub count_http_5xx_as_failure: bool,
+
+    /// Whether routes to this service share circuit state, or each route has its own.
+    #[serde(default)]
+    pub isolation: CircuitBreakerIsolation,
+}
+
+/// Backoff of the open duration of a circuit whose half-open probes keep failing.
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize)]
+pub struct CircuitBreakerBackoffConfig {
+    /// Factor the open duration grows by each time a probe fails and re-opens the circuit.
+    #[serde(default = "cbb_default_multiplier")]
+    pub multiplier: u32,
+
+    /// Cap on the open duration. The circuit starts over from the open duration once it closes.
+    #[serde(default = "cbb_default_max_open_duration_milliseconds")]
+    pub max_open_duration_milliseconds: u64,
+}
+
+impl Default for CircuitBreakerBackoffConfig {
+    fn default() -> Self {
+        Self {
+            multiplier: cbb_default_multiplier(),
+            max_open_duration_milliseconds: cbb_default_max_open_duration_milliseconds(),
+        }
+    }
+}
+
+fn cbb_default_multiplier() -> u32 {
+    2
+}
+
+fn cbb_default_max_open_duration_milliseconds() -> u64 {
+    300_000
+}
+
+/// Hash ring settings for the `ketama` load balancing strategy.
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize)]
+pub struct KetamaConfig {
+    /// Points each upstream gets on the hash ring, times its weight.
+    /// More points spread keys more evenly, at the cost of a larger ring.
+    #[serde(default = "ketama_default_replicas")]
+    pub replicas: u32,
+
+    /// Request header whose value is hashed to pick an upstream, e.g. `x-cache-key`.
+    /// Requests without it are keyed by client IP.
+    pub key_header: Option<String>,
+}
+
+impl Default for KetamaConfig {
+    fn default() -> Self {
+        Self {
+            replicas: ketama_default_replicas(),
+            key_header: None,
+        }
+    }
+}
+
+fn ketama_default_replicas() -> u32 {
+    160
+}
+
+/// Passive outlier detection: upstreams answering too many requests in a row with a 5xx or a
+/// connection error are ejected from rotation for a while.
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize)]
+pub struct OutlierDetectionConfig {
+    #[serde(default)]
+    pub enable: bool,
+
+    /// Consecutive 5xx responses or connection errors that eject an upstream.
+    #[serde(default = "od_default_consecutive_5xx")]
+    pub consecutive_5xx: u32,
+
+    /// How long the first ejection lasts. Each repeated ejection lasts twice as long.
+    #[serde(default = "od_default_base_ejection_time_milliseconds")]
+    pub base_ejection_time_milliseconds: u64,
+
+    /// Cap on the ejection time. An upstream that stays in rotation this long after its last
+    /// ejection starts over from the base time.
+    #[serde(default = "od_default_max_ejection_time_milliseconds")]
+    pub max_ejection_time_milliseconds: u64,
+}
+
+impl Default for OutlierDetectionConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            consecutive_5xx: od_default_consecutive_5xx(),
+            base_ejection_time_milliseconds: od_default_base_ejection_time_milliseconds(),
+            max_ejection_time_milliseconds: od_default_max_ejection_time_milliseconds(),
+        }
+    }
+}
+
+fn od_default_consecutive_5xx() -> u32 {
+    5
+}
+
+fn od_default_base_ejection_time_milliseconds() -> u64 {
+    30_000
+}
+
+fn od_default_max_ejection_time_milliseconds() -> u64 {
+    300_000
+}
+
+/// Retries of requests that failed on an upstream, each sent to another upstream when there
+/// is one. Retries are bounded by a budget, so a failing service is not flooded with them.
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize)]
+pub struct RetryPolicyConfig {
+    #[serde(default)]
+    pub enable: bool,
+
+    /// Retries of a single request, on top of its first attempt.
+    #[serde(default = "rp_default_max_retries")]
+    pub max_retries: u32,
+
+    /// Upstream response statuses that are retried.
+    #[serde(default = "rp_default_retry_on_status")]
+    pub retry_on_status: Vec<u16>,
+
+    /// Retry requests whose upstream connection failed before a response arrived,
+    /// e.g. because it was reset.
+    #[serde(default = "rp_default_retry_on_connection_error")]
+    pub retry_on_connection_error: bool,
+
+    /// Retry requests with non-idempotent methods, e.g. `POST` and `PATCH`, too.
+    #[serde(default)]
+    pub retry_non_idempotent: bool,
+
+    /// Retries in flight, as a percentage of the service's requests in flight.
+    #[serde(default = "rp_default_budget_percent")]
+    pub budget_percent: u32,
+
+    /// Retries in flight allowed whatever the budget percentage, so services with little
+    /// traffic can still retry.
+    #[serde(default = "rp_default_min_retry_concurrency")]
+    pub min_retry_concurrency: u32,
+}
+
+impl Default for RetryPolicyConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            max_retries: rp_default_max_retries(),
+            retry_on_status: rp_default_retry_on_status(),
+            retry_on_connection_error: rp_default_retry_on_connection_error(),
+            retry_non_idempotent: false,
+            budget_percent: rp_default_budget_percent(),
+            min_retry_concurrency: rp_default_min_retry_concurrency(),
+        }
+    }
+}
+
+fn rp_default_max_retries() -> u32 {
+    2
+}
+
+fn rp_default_retry_on_status() -> Vec<u16> {
+    vec![502, 503, 504]
+}
+
+fn rp_default_retry_on_connection_error() -> bool {
+    true
+}
+
+fn rp_default_budget_percent() -> u32 {
+    20
+}
+
+fn rp_default_min_retry_concurrency() -> u32 {
+    3
+}
+
+/// What circuit breaker state is kept per, besides the upstream.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitBreakerIsolation {
+    /// Every route to the service trips the same circuit.
+    #[default]
+    Service,
+    /// Each route trips its own circuit, so one route's failures do not block another.
+    Route,
 }
 
 fn cb_default_failure_threshold() -> u32 {
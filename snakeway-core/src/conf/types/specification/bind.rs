@@ -1,10 +1,11 @@
 use crate::conf::resolution::ResolveError;
 use crate::conf::types::specification::bind_interface::{BindInterfaceInput, BindInterfaceSpec};
 use crate::conf::types::{Origin, TlsSpec};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
 
-#[derive(Debug, Deserialize, Default, Serialize, Clone)]
+#[derive(Debug, Deserialize, JsonSchema, Default, Serialize, Clone)]
 pub struct BindSpec {
     #[serde(skip)]
     pub origin: Origin,
@@ -13,6 +14,9 @@ pub struct BindSpec {
     pub tls: Option<TlsSpec>,
     pub enable_http2: bool,
     pub redirect_http_to_https: Option<RedirectSpec>,
+    /// Names of devices that only run on this listener.
+    #[serde(default)]
+    pub devices: Vec<String>,
 }
 
 impl BindSpec {
@@ -32,7 +36,7 @@ impl BindSpec {
     }
 }
 
-#[derive(Debug, Deserialize, Default, Serialize, Clone)]
+#[derive(Debug, Deserialize, JsonSchema, Default, Serialize, Clone)]
 pub struct RedirectSpec {
     pub port: u16,
     pub status: u16,
@@ -1,16 +1,20 @@
 use crate::conf::resolution::ResolveError;
 use crate::conf::types::specification::bind_interface::{BindInterfaceInput, BindInterfaceSpec};
 use crate::conf::types::{Origin, TlsSpec};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
 
-#[derive(Debug, Deserialize, Default, Serialize)]
+#[derive(Debug, Deserialize, JsonSchema, Default, Serialize)]
 pub struct BindAdminSpec {
     #[serde(skip)]
     pub origin: Origin,
     pub interface: BindInterfaceInput,
     pub port: u16,
     pub tls: TlsSpec,
+    /// Names of devices that only run on this listener.
+    #[serde(default)]
+    pub devices: Vec<String>,
 }
 
 impl BindAdminSpec {
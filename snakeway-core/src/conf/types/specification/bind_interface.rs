@@ -1,4 +1,5 @@
 use crate::conf::validation::ConfigError;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::net::IpAddr;
@@ -26,7 +27,7 @@ impl BindInterfaceSpec {
     }
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize)]
 #[serde(untagged)]
 pub enum BindInterfaceInput {
     Keyword(String),
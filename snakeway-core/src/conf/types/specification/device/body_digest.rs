@@ -0,0 +1,30 @@
+use crate::conf::types::Origin;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct BodyDigestDeviceSpec {
+    #[serde(skip)]
+    pub origin: Origin,
+
+    /// Whether this body digest device is enabled.
+    pub enable: bool,
+
+    /// Where this body digest device runs in the pipeline, lower first. Ties keep declaration order.
+    #[serde(default)]
+    pub priority: i32,
+
+    /// Request path prefixes this body digest device runs on, e.g. `/api`. Empty runs on every path.
+    #[serde(default)]
+    pub routes: Vec<String>,
+
+    /// Maximum request body size that will be buffered for verification.
+    /// Larger bodies carrying a digest are rejected with 413.
+    #[serde(default = "default_max_body_bytes")]
+    pub max_body_bytes: usize,
+}
+
+fn default_max_body_bytes() -> usize {
+    10 * 1024 * 1024
+}
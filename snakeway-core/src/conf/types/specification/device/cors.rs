@@ -0,0 +1,63 @@
+use crate::conf::types::Origin;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Cross-origin resource sharing (CORS) for browser clients.
+///
+/// ```hcl
+/// cors_device {
+///   enable            = true
+///   allow_origins     = ["https://app.example.com"]
+///   allow_methods     = ["GET", "POST"]
+///   allow_headers     = ["content-type", "authorization"]
+///   expose_headers    = ["x-request-id"]
+///   allow_credentials = true
+///   max_age_seconds   = 600
+/// }
+/// ```
+#[derive(Default, Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CorsDeviceSpec {
+    #[serde(skip)]
+    pub origin: Origin,
+
+    /// Whether this CORS device is enabled.
+    pub enable: bool,
+
+    /// Where this CORS device runs in the pipeline, lower first. Ties keep declaration order.
+    #[serde(default)]
+    pub priority: i32,
+
+    /// Request path prefixes this CORS device runs on, e.g. `/api`. Empty runs on every path.
+    #[serde(default)]
+    pub routes: Vec<String>,
+
+    /// Origins allowed to make cross-origin requests, e.g. `https://app.example.com`.
+    /// `*` allows any origin. The request's `Origin` is always reflected back, never `*`.
+    #[serde(default)]
+    pub allow_origins: Vec<String>,
+
+    /// Methods allowed in preflight requests.
+    #[serde(default = "default_allow_methods")]
+    pub allow_methods: Vec<String>,
+
+    /// Request headers allowed in preflight requests. `*` allows any header.
+    #[serde(default)]
+    pub allow_headers: Vec<String>,
+
+    /// Response headers browsers may expose to scripts, beyond the CORS-safelisted ones.
+    #[serde(default)]
+    pub expose_headers: Vec<String>,
+
+    /// Whether browsers may send cookies and credentials with cross-origin requests.
+    #[serde(default)]
+    pub allow_credentials: bool,
+
+    /// How long browsers may cache a preflight response.
+    #[serde(default)]
+    pub max_age_seconds: Option<u32>,
+}
+
+fn default_allow_methods() -> Vec<String> {
+    vec!["GET".to_string(), "HEAD".to_string(), "POST".to_string()]
+}
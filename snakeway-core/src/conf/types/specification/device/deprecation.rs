@@ -0,0 +1,42 @@
+use crate::conf::types::Origin;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeprecationDeviceSpec {
+    #[serde(skip)]
+    pub origin: Origin,
+
+    /// Whether this deprecation device is enabled.
+    pub enable: bool,
+
+    /// Where this deprecation device runs in the pipeline, lower first. Ties keep declaration order.
+    #[serde(default)]
+    pub priority: i32,
+
+    /// Request path prefixes this deprecation device runs on, e.g. `/api`. Empty runs on every path.
+    #[serde(default)]
+    pub routes: Vec<String>,
+
+    /// Deprecated routes, and the headers announced on their responses.
+    #[serde(default)]
+    pub deprecations: Vec<DeprecationSpec>,
+}
+
+#[derive(Default, Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeprecationSpec {
+    /// Path of the deprecated route, as the route declares it, e.g. `/api/v1`.
+    pub path: String,
+
+    /// When the route was deprecated, as an RFC 3339 timestamp, e.g. `2026-01-01T00:00:00Z`.
+    /// Sent in the `Deprecation` header.
+    pub deprecated_at: String,
+
+    /// When the route stops responding, as an RFC 3339 timestamp. Sent in the `Sunset` header.
+    pub sunset_at: Option<String>,
+
+    /// URL of the route that replaces this one, sent as a `successor-version` link.
+    pub successor: Option<String>,
+}
@@ -1,6 +1,10 @@
 use crate::conf::types::{
-    IdentityDeviceSpec, Origin, RequestFilterDeviceSpec, StructuredLoggingDeviceSpec,
-    WasmDeviceSpec,
+    BodyDigestDeviceSpec, CorsDeviceSpec, DeprecationDeviceSpec, ErrorPageDeviceSpec,
+    ExperimentDeviceSpec, HeaderRewriteDeviceSpec, IdempotencyDeviceSpec, IdentityDeviceSpec,
+    IpReputationDeviceSpec, JwtAuthDeviceSpec, LuaDeviceSpec, OpenApiDeviceSpec, Origin,
+    QueryRewriteDeviceSpec, QuotaDeviceSpec, RateLimitDeviceSpec, RequestFilterDeviceSpec,
+    RequiredHeadersDeviceSpec, ResponseHeadersDeviceSpec, StatusRemapDeviceSpec,
+    StructuredLoggingDeviceSpec, UpstreamGroupDeviceSpec, WasmDeviceSpec,
 };
 use serde::Serialize;
 
@@ -11,6 +15,24 @@ pub enum DeviceSpec {
     Identity(IdentityDeviceSpec),
     StructuredLogging(StructuredLoggingDeviceSpec),
     RequestFilter(RequestFilterDeviceSpec),
+    OpenApi(OpenApiDeviceSpec),
+    JwtAuth(JwtAuthDeviceSpec),
+    Quota(QuotaDeviceSpec),
+    BodyDigest(BodyDigestDeviceSpec),
+    UpstreamGroup(UpstreamGroupDeviceSpec),
+    RequiredHeaders(RequiredHeadersDeviceSpec),
+    StatusRemap(StatusRemapDeviceSpec),
+    ErrorPage(ErrorPageDeviceSpec),
+    ResponseHeaders(ResponseHeadersDeviceSpec),
+    IpReputation(IpReputationDeviceSpec),
+    RateLimit(RateLimitDeviceSpec),
+    Experiment(ExperimentDeviceSpec),
+    HeaderRewrite(HeaderRewriteDeviceSpec),
+    QueryRewrite(QueryRewriteDeviceSpec),
+    Cors(CorsDeviceSpec),
+    Idempotency(IdempotencyDeviceSpec),
+    Deprecation(DeprecationDeviceSpec),
+    Lua(LuaDeviceSpec),
 }
 
 impl DeviceSpec {
@@ -18,8 +40,82 @@ impl DeviceSpec {
         match self {
             DeviceSpec::Identity(i) => &i.origin,
             DeviceSpec::RequestFilter(r) => &r.origin,
+            DeviceSpec::OpenApi(o) => &o.origin,
             DeviceSpec::StructuredLogging(s) => &s.origin,
+            DeviceSpec::JwtAuth(j) => &j.origin,
+            DeviceSpec::Quota(q) => &q.origin,
+            DeviceSpec::BodyDigest(b) => &b.origin,
+            DeviceSpec::UpstreamGroup(u) => &u.origin,
+            DeviceSpec::RequiredHeaders(r) => &r.origin,
+            DeviceSpec::StatusRemap(s) => &s.origin,
+            DeviceSpec::ErrorPage(e) => &e.origin,
+            DeviceSpec::ResponseHeaders(r) => &r.origin,
+            DeviceSpec::IpReputation(i) => &i.origin,
+            DeviceSpec::RateLimit(r) => &r.origin,
+            DeviceSpec::Experiment(e) => &e.origin,
+            DeviceSpec::HeaderRewrite(h) => &h.origin,
+            DeviceSpec::QueryRewrite(q) => &q.origin,
+            DeviceSpec::Cors(c) => &c.origin,
+            DeviceSpec::Idempotency(i) => &i.origin,
+            DeviceSpec::Deprecation(d) => &d.origin,
+            DeviceSpec::Lua(l) => &l.origin,
             DeviceSpec::Wasm(w) => &w.origin,
         }
     }
+
+    /// Request path prefixes the device runs on. Empty runs on every path.
+    pub fn routes(&self) -> &[String] {
+        match self {
+            DeviceSpec::Identity(i) => &i.routes,
+            DeviceSpec::RequestFilter(r) => &r.routes,
+            DeviceSpec::OpenApi(o) => &o.routes,
+            DeviceSpec::StructuredLogging(s) => &s.routes,
+            DeviceSpec::JwtAuth(j) => &j.routes,
+            DeviceSpec::Quota(q) => &q.routes,
+            DeviceSpec::BodyDigest(b) => &b.routes,
+            DeviceSpec::UpstreamGroup(u) => &u.routes,
+            DeviceSpec::RequiredHeaders(r) => &r.routes,
+            DeviceSpec::StatusRemap(s) => &s.routes,
+            DeviceSpec::ErrorPage(e) => &e.routes,
+            DeviceSpec::ResponseHeaders(r) => &r.routes,
+            DeviceSpec::IpReputation(i) => &i.routes,
+            DeviceSpec::RateLimit(r) => &r.routes,
+            DeviceSpec::Experiment(e) => &e.routes,
+            DeviceSpec::HeaderRewrite(h) => &h.routes,
+            DeviceSpec::QueryRewrite(q) => &q.routes,
+            DeviceSpec::Cors(c) => &c.routes,
+            DeviceSpec::Idempotency(i) => &i.routes,
+            DeviceSpec::Deprecation(d) => &d.routes,
+            DeviceSpec::Lua(l) => &l.routes,
+            DeviceSpec::Wasm(w) => &w.routes,
+        }
+    }
+
+    /// The name used to attach a device to listeners.
+    pub fn name(&self) -> String {
+        match self {
+            DeviceSpec::Identity(_) => "identity".to_string(),
+            DeviceSpec::RequestFilter(_) => "request_filter".to_string(),
+            DeviceSpec::OpenApi(_) => "openapi".to_string(),
+            DeviceSpec::StructuredLogging(_) => "structured_logging".to_string(),
+            DeviceSpec::JwtAuth(_) => "jwt_auth".to_string(),
+            DeviceSpec::Quota(_) => "quota".to_string(),
+            DeviceSpec::BodyDigest(_) => "body_digest".to_string(),
+            DeviceSpec::UpstreamGroup(_) => "upstream_group".to_string(),
+            DeviceSpec::RequiredHeaders(_) => "required_headers".to_string(),
+            DeviceSpec::StatusRemap(_) => "status_remap".to_string(),
+            DeviceSpec::ErrorPage(_) => "error_page".to_string(),
+            DeviceSpec::ResponseHeaders(_) => "response_headers".to_string(),
+            DeviceSpec::IpReputation(_) => "ip_reputation".to_string(),
+            DeviceSpec::RateLimit(_) => "rate_limit".to_string(),
+            DeviceSpec::Experiment(_) => "experiment".to_string(),
+            DeviceSpec::HeaderRewrite(_) => "header_rewrite".to_string(),
+            DeviceSpec::QueryRewrite(_) => "query_rewrite".to_string(),
+            DeviceSpec::Cors(_) => "cors".to_string(),
+            DeviceSpec::Idempotency(_) => "idempotency".to_string(),
+            DeviceSpec::Deprecation(_) => "deprecation".to_string(),
+            DeviceSpec::Lua(_) => "lua".to_string(),
+            DeviceSpec::Wasm(w) => w.name(),
+        }
+    }
 }
@@ -0,0 +1,94 @@
+use crate::conf::types::Origin;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ErrorPageDeviceSpec {
+    #[serde(skip)]
+    pub origin: Origin,
+
+    /// Whether this error page device is enabled.
+    pub enable: bool,
+
+    /// Where this error page device runs in the pipeline, lower first. Ties keep declaration order.
+    #[serde(default)]
+    pub priority: i32,
+
+    /// Request path prefixes this error page device runs on, e.g. `/api`. Empty runs on every path.
+    #[serde(default)]
+    pub routes: Vec<String>,
+
+    /// Upstream statuses whose responses are replaced, e.g. `5xx` or `404`.
+    #[serde(default = "default_statuses")]
+    pub statuses: Vec<String>,
+
+    /// The error page sent instead of the upstream body.
+    pub body: Option<String>,
+
+    /// A file holding the error page, read when the config is loaded. Use instead of `body`.
+    pub body_file: Option<PathBuf>,
+
+    /// Content type of the error page.
+    #[serde(default = "default_content_type")]
+    pub content_type: String,
+
+    /// Upstream responses with these content types are intentional and pass through, e.g.
+    /// `application/problem+json`. Patterns are `type/subtype` or `type/*`.
+    #[serde(default = "default_pass_through_content_types")]
+    pub pass_through_content_types: Vec<String>,
+
+    /// Upstream headers kept on a replaced response. Every other upstream header is dropped.
+    #[serde(default = "default_keep_headers")]
+    pub keep_headers: Vec<String>,
+}
+
+impl Default for ErrorPageDeviceSpec {
+    fn default() -> Self {
+        Self {
+            origin: Origin::default(),
+            enable: false,
+            priority: 0,
+            routes: Vec::new(),
+            statuses: default_statuses(),
+            body: None,
+            body_file: None,
+            content_type: default_content_type(),
+            pass_through_content_types: default_pass_through_content_types(),
+            keep_headers: default_keep_headers(),
+        }
+    }
+}
+
+fn default_statuses() -> Vec<String> {
+    vec!["5xx".to_string()]
+}
+
+fn default_content_type() -> String {
+    "text/html; charset=utf-8".to_string()
+}
+
+fn default_pass_through_content_types() -> Vec<String> {
+    vec!["application/problem+json".to_string()]
+}
+
+fn default_keep_headers() -> Vec<String> {
+    vec!["retry-after".to_string()]
+}
+
+/// The statuses an error page `statuses` entry covers: a single status such as `502`,
+/// or a class such as `5xx`. `None` unless every status is from 400 to 599.
+pub fn error_page_statuses(entry: &str) -> Option<RangeInclusive<u16>> {
+    let range = match entry.to_ascii_lowercase().as_str() {
+        "4xx" => 400..=499,
+        "5xx" => 500..=599,
+        status => {
+            let status: u16 = status.parse().ok()?;
+            status..=status
+        }
+    };
+
+    (*range.start() >= 400 && *range.end() <= 599).then_some(range)
+}
@@ -0,0 +1,42 @@
+use crate::conf::types::Origin;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExperimentDeviceSpec {
+    #[serde(skip)]
+    pub origin: Origin,
+
+    /// Whether this experiment device is enabled.
+    pub enable: bool,
+
+    /// Where this experiment device runs in the pipeline, lower first. Ties keep declaration order.
+    #[serde(default)]
+    pub priority: i32,
+
+    /// Request path prefixes this experiment device runs on, e.g. `/api`. Empty runs on every path.
+    #[serde(default)]
+    pub routes: Vec<String>,
+
+    /// Experiment name, e.g. `checkout-redesign`. Hashed with the key, so each experiment
+    /// assigns users independently.
+    pub experiment: String,
+
+    /// Cookie whose value identifies the user, e.g. `uid`. Requests without it are not tagged.
+    pub cookie: String,
+
+    /// Variants users are assigned to, in proportion to their weights.
+    #[serde(default)]
+    pub variants: Vec<ExperimentVariantSpec>,
+}
+
+#[derive(Default, Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExperimentVariantSpec {
+    /// Variant name, sent in `X-Experiment-Variant`.
+    pub name: String,
+
+    /// Relative share of users assigned to this variant.
+    pub weight: u32,
+}
@@ -0,0 +1,47 @@
+use crate::conf::types::Origin;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct HeaderRewriteDeviceSpec {
+    #[serde(skip)]
+    pub origin: Origin,
+
+    /// Whether this header rewrite device is enabled.
+    pub enable: bool,
+
+    /// Where this header rewrite device runs in the pipeline, lower first. Ties keep declaration order.
+    #[serde(default)]
+    pub priority: i32,
+
+    /// Request path prefixes this header rewrite device runs on, e.g. `/api`. Empty runs on every path.
+    #[serde(default)]
+    pub routes: Vec<String>,
+
+    /// Headers set on the request sent upstream, replacing any values the client sent.
+    #[serde(default)]
+    pub request_set: Vec<HeaderSetSpec>,
+
+    /// Headers removed from the request sent upstream.
+    #[serde(default)]
+    pub request_remove: Vec<String>,
+
+    /// Headers set on the response sent to the client, replacing any values the upstream sent.
+    #[serde(default)]
+    pub response_set: Vec<HeaderSetSpec>,
+
+    /// Headers removed from the response sent to the client.
+    #[serde(default)]
+    pub response_remove: Vec<String>,
+}
+
+#[derive(Default, Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct HeaderSetSpec {
+    /// Header name, e.g. `X-Environment`.
+    pub name: String,
+
+    /// Header value.
+    pub value: String,
+}
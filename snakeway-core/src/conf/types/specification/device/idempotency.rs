@@ -0,0 +1,75 @@
+use crate::conf::types::Origin;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Replays stored responses for requests that repeat an idempotency key.
+///
+/// ```hcl
+/// idempotency_device {
+///   enable      = true
+///   methods     = ["POST"]
+///   paths       = ["/payments"]
+///   ttl_seconds = 86400
+/// }
+/// ```
+#[derive(Default, Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct IdempotencyDeviceSpec {
+    #[serde(skip)]
+    pub origin: Origin,
+
+    /// Whether this idempotency device is enabled.
+    pub enable: bool,
+
+    /// Where this idempotency device runs in the pipeline, lower first. Ties keep declaration order.
+    #[serde(default)]
+    pub priority: i32,
+
+    /// Request path prefixes this idempotency device runs on, e.g. `/api`. Empty runs on every path.
+    #[serde(default)]
+    pub routes: Vec<String>,
+
+    /// Request header carrying the idempotency key.
+    #[serde(default = "default_key_header")]
+    pub key_header: String,
+
+    /// Methods that must carry a key. Other methods pass through untouched.
+    #[serde(default = "default_methods")]
+    pub methods: Vec<String>,
+
+    /// Path prefixes that must carry a key, e.g. `/payments`. Empty means every path.
+    #[serde(default)]
+    pub paths: Vec<String>,
+
+    /// How long a stored response is replayed for its key.
+    #[serde(default = "default_ttl_seconds")]
+    pub ttl_seconds: u64,
+
+    /// How long a duplicate waits for the request holding its key before getting `409`.
+    #[serde(default = "default_wait_timeout_milliseconds")]
+    pub wait_timeout_milliseconds: u64,
+
+    /// Largest response body that is stored. Larger responses are sent but not replayed.
+    #[serde(default = "default_max_body_bytes")]
+    pub max_body_bytes: usize,
+}
+
+fn default_key_header() -> String {
+    "Idempotency-Key".to_string()
+}
+
+fn default_methods() -> Vec<String> {
+    vec!["POST".to_string(), "PATCH".to_string()]
+}
+
+fn default_ttl_seconds() -> u64 {
+    24 * 60 * 60
+}
+
+fn default_wait_timeout_milliseconds() -> u64 {
+    10_000
+}
+
+fn default_max_body_bytes() -> usize {
+    1024 * 1024
+}
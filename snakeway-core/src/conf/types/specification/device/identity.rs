@@ -1,8 +1,9 @@
 use crate::conf::types::Origin;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
-#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+#[derive(Default, Clone, Debug, Deserialize, JsonSchema, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct IdentityDeviceSpec {
     #[serde(skip)]
@@ -10,9 +11,24 @@ pub struct IdentityDeviceSpec {
 
     pub enable: bool,
 
+    #[serde(default)]
+    pub priority: i32,
+
+    #[serde(default)]
+    pub routes: Vec<String>,
+
     /// CIDR strings
     pub trusted_proxies: Vec<String>,
 
+    /// Header carrying the real client IP (e.g. `CF-Connecting-IP`, `True-Client-IP`).
+    /// Only honored when the immediate peer is a trusted proxy.
+    pub client_ip_header: Option<String>,
+
+    /// Which forwarding header is walked when a request carries both `X-Forwarded-For` and
+    /// the standard `Forwarded` header.
+    #[serde(default)]
+    pub preferred_forwarding_header: ForwardingHeaderSpec,
+
     pub enable_geoip: bool,
 
     pub geoip_city_db: Option<PathBuf>,
@@ -22,12 +38,28 @@ pub struct IdentityDeviceSpec {
     pub enable_user_agent: bool,
 
     pub ua_engine: UaEngineSpec,
+
+    /// Number of distinct user agents whose parse results are cached. `0` disables the cache.
+    #[serde(default = "default_ua_cache_capacity")]
+    pub ua_cache_capacity: usize,
 }
 
-#[derive(Default, Debug, Deserialize, Serialize, Clone, Copy)]
+fn default_ua_cache_capacity() -> usize {
+    1024
+}
+
+#[derive(Default, Debug, Deserialize, JsonSchema, Serialize, Clone, Copy)]
 #[serde(rename_all = "lowercase")]
 pub enum UaEngineSpec {
     UaParser,
     #[default]
     Woothee,
 }
+
+#[derive(Default, Debug, Deserialize, JsonSchema, Serialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum ForwardingHeaderSpec {
+    #[default]
+    XForwardedFor,
+    Forwarded,
+}
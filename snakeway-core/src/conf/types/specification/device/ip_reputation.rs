@@ -0,0 +1,89 @@
+use crate::conf::types::Origin;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Default, Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct IpReputationDeviceSpec {
+    #[serde(skip)]
+    pub origin: Origin,
+
+    /// Whether this IP reputation device is enabled.
+    pub enable: bool,
+
+    /// Where this IP reputation device runs in the pipeline, lower first. Ties keep declaration order.
+    #[serde(default)]
+    pub priority: i32,
+
+    /// Request path prefixes this IP reputation device runs on, e.g. `/api`. Empty runs on every path.
+    #[serde(default)]
+    pub routes: Vec<String>,
+
+    /// File listing bad IPs and CIDRs, one per line. Blank lines and `#` comments are ignored.
+    pub list_file: Option<PathBuf>,
+
+    /// How often the list file is checked for changes. Set to 0 to never reload it.
+    #[serde(default = "default_list_reload_interval_seconds")]
+    pub list_reload_interval_seconds: u64,
+
+    /// URL of an external reputation service, e.g. "http://127.0.0.1:9200/reputation".
+    /// The client IP is sent in the `X-Client-IP` header.
+    pub reputation_url: Option<String>,
+
+    /// How long to wait for the reputation service before giving up.
+    #[serde(default = "default_timeout_milliseconds")]
+    pub timeout_milliseconds: u64,
+
+    /// How long a reputation service verdict is reused for the same IP.
+    #[serde(default = "default_cache_ttl_milliseconds")]
+    pub cache_ttl_milliseconds: u64,
+
+    /// Treat IPs as clean when the reputation service fails or times out.
+    #[serde(default = "default_fail_open")]
+    pub fail_open: bool,
+
+    /// What to do with a bad IP.
+    #[serde(default)]
+    pub action: IpReputationActionSpec,
+
+    /// Status sent to bad IPs with `action = "block"`.
+    #[serde(default = "default_block_status")]
+    pub block_status: u16,
+
+    /// Header sent upstream with the verdict with `action = "tag"`.
+    #[serde(default = "default_tag_header")]
+    pub tag_header: String,
+}
+
+#[derive(Default, Debug, Deserialize, JsonSchema, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum IpReputationActionSpec {
+    #[default]
+    Block,
+    Tag,
+}
+
+fn default_list_reload_interval_seconds() -> u64 {
+    30
+}
+
+fn default_timeout_milliseconds() -> u64 {
+    250
+}
+
+fn default_cache_ttl_milliseconds() -> u64 {
+    60_000
+}
+
+fn default_fail_open() -> bool {
+    true
+}
+
+fn default_block_status() -> u16 {
+    403
+}
+
+fn default_tag_header() -> String {
+    "X-IP-Reputation".to_string()
+}
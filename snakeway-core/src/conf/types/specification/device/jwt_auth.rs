@@ -0,0 +1,76 @@
+use crate::conf::types::Origin;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Algorithms accepted with `hmac_secret`.
+pub const JWT_HMAC_ALGORITHMS: &[&str] = &["HS256", "HS384", "HS512"];
+
+/// Algorithms accepted with `jwks_url`.
+pub const JWT_JWKS_ALGORITHMS: &[&str] = &[
+    "RS256", "RS384", "RS512", "PS256", "PS384", "PS512", "ES256", "ES384", "EdDSA",
+];
+
+#[derive(Default, Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct JwtAuthDeviceSpec {
+    #[serde(skip)]
+    pub origin: Origin,
+
+    /// Whether this JWT auth device is enabled.
+    pub enable: bool,
+
+    /// Where this JWT auth device runs in the pipeline, lower first. Ties keep declaration order.
+    #[serde(default)]
+    pub priority: i32,
+
+    /// Request path prefixes this JWT auth device runs on, e.g. `/api`. Empty runs on every path.
+    #[serde(default)]
+    pub routes: Vec<String>,
+
+    /// Shared secret for HMAC-signed tokens.
+    /// Exactly one of `hmac_secret` and `jwks_url` must be set.
+    #[serde(default)]
+    pub hmac_secret: Option<String>,
+
+    /// URL of a JWKS document with the public keys tokens are signed with,
+    /// e.g. "https://auth.example.com/.well-known/jwks.json".
+    #[serde(default)]
+    pub jwks_url: Option<String>,
+
+    /// Accepted signing algorithms, e.g. ["RS256"].
+    /// Defaults to HS256 with `hmac_secret` and RS256 with `jwks_url`.
+    #[serde(default)]
+    pub algorithms: Vec<String>,
+
+    /// Required `iss` claim, if any.
+    #[serde(default)]
+    pub issuer: Option<String>,
+
+    /// Required `aud` claim, if any.
+    #[serde(default)]
+    pub audience: Option<String>,
+
+    /// Clock skew tolerated when checking `exp` and `nbf`.
+    #[serde(default = "default_leeway_seconds")]
+    pub leeway_seconds: u64,
+
+    /// How long fetched JWKS keys are used before they are fetched again.
+    #[serde(default = "default_jwks_refresh_seconds")]
+    pub jwks_refresh_seconds: u64,
+
+    /// How long to wait for the JWKS endpoint before giving up.
+    #[serde(default = "default_timeout_milliseconds")]
+    pub timeout_milliseconds: u64,
+}
+
+fn default_leeway_seconds() -> u64 {
+    60
+}
+
+fn default_jwks_refresh_seconds() -> u64 {
+    300
+}
+
+fn default_timeout_milliseconds() -> u64 {
+    1000
+}
@@ -0,0 +1,73 @@
+use crate::conf::types::Origin;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Lua VM instructions a hook call may run when `instruction_limit` is not set.
+pub const DEFAULT_LUA_INSTRUCTION_LIMIT: u64 = 1_000_000;
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct LuaDeviceSpec {
+    #[serde(skip)]
+    pub origin: Origin,
+
+    /// Whether this Lua device is enabled.
+    pub enable: bool,
+
+    /// Where this Lua device runs in the pipeline, lower first. Ties keep declaration order.
+    #[serde(default)]
+    pub priority: i32,
+
+    /// Request path prefixes this Lua device runs on, e.g. `/api`. Empty runs on every path.
+    #[serde(default)]
+    pub routes: Vec<String>,
+
+    /// The Lua script, defining an `on_request` function, an `on_response` function, or both.
+    pub path: PathBuf,
+
+    /// Lua VM instructions each hook call may run. A call that runs out is aborted,
+    /// and the device lets the request continue.
+    #[serde(default = "default_instruction_limit")]
+    pub instruction_limit: u64,
+
+    /// Memory the script may allocate, in bytes.
+    #[serde(default = "default_memory_limit_bytes")]
+    pub memory_limit_bytes: usize,
+
+    /// Largest body, in bytes, the script may respond with or replace a response body with.
+    #[serde(default = "default_max_body_bytes")]
+    pub max_body_bytes: usize,
+
+    /// Run hook calls on the blocking thread pool, for scripts doing heavy work.
+    #[serde(default)]
+    pub offload: bool,
+}
+
+impl Default for LuaDeviceSpec {
+    fn default() -> Self {
+        Self {
+            origin: Origin::default(),
+            enable: false,
+            priority: 0,
+            routes: Vec::new(),
+            path: PathBuf::new(),
+            instruction_limit: default_instruction_limit(),
+            memory_limit_bytes: default_memory_limit_bytes(),
+            max_body_bytes: default_max_body_bytes(),
+            offload: false,
+        }
+    }
+}
+
+fn default_instruction_limit() -> u64 {
+    DEFAULT_LUA_INSTRUCTION_LIMIT
+}
+
+fn default_memory_limit_bytes() -> usize {
+    8 * 1024 * 1024
+}
+
+fn default_max_body_bytes() -> usize {
+    64 * 1024
+}
@@ -1,11 +1,47 @@
+mod body_digest;
+mod cors;
+mod deprecation;
 mod device_spec;
+mod error_page;
+mod experiment;
+mod header_rewrite;
+mod idempotency;
 mod identity;
+mod ip_reputation;
+mod jwt_auth;
+mod lua;
+mod openapi;
+mod query_rewrite;
+mod quota;
+mod rate_limit;
 mod request_filter;
+mod required_headers;
+mod response_headers;
+mod status_remap;
 mod structured_logging;
+mod upstream_group;
 mod wasm;
 
+pub use body_digest::*;
+pub use cors::*;
+pub use deprecation::*;
 pub use device_spec::*;
+pub use error_page::*;
+pub use experiment::*;
+pub use header_rewrite::*;
+pub use idempotency::*;
 pub use identity::*;
+pub use ip_reputation::*;
+pub use jwt_auth::*;
+pub use lua::*;
+pub use openapi::*;
+pub use query_rewrite::*;
+pub use quota::*;
+pub use rate_limit::*;
 pub use request_filter::*;
+pub use required_headers::*;
+pub use response_headers::*;
+pub use status_remap::*;
 pub use structured_logging::*;
+pub use upstream_group::*;
 pub use wasm::*;
@@ -0,0 +1,38 @@
+use crate::conf::types::Origin;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Default, Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct OpenApiDeviceSpec {
+    #[serde(skip)]
+    pub origin: Origin,
+
+    /// Whether this OpenAPI device is enabled.
+    pub enable: bool,
+
+    /// Where this OpenAPI device runs in the pipeline, lower first. Ties keep declaration order.
+    #[serde(default)]
+    pub priority: i32,
+
+    /// Request path prefixes this OpenAPI device runs on, e.g. `/api`. Empty runs on every path.
+    #[serde(default)]
+    pub routes: Vec<String>,
+
+    /// Path to the OpenAPI 3 document requests are validated against, in JSON or YAML.
+    pub spec_path: PathBuf,
+
+    /// Maximum JSON request body size that will be buffered for validation.
+    /// Larger bodies are rejected with 413.
+    #[serde(default = "default_max_body_bytes")]
+    pub max_body_bytes: usize,
+
+    /// Reject requests that match no operation in the document, instead of passing them through.
+    #[serde(default)]
+    pub reject_unknown_operations: bool,
+}
+
+fn default_max_body_bytes() -> usize {
+    1024 * 1024
+}
@@ -0,0 +1,58 @@
+use crate::conf::types::Origin;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct QueryRewriteDeviceSpec {
+    #[serde(skip)]
+    pub origin: Origin,
+
+    /// Whether this query rewrite device is enabled.
+    pub enable: bool,
+
+    /// Where this query rewrite device runs in the pipeline, lower first. Ties keep declaration order.
+    #[serde(default)]
+    pub priority: i32,
+
+    /// Request path prefixes this query rewrite device runs on, e.g. `/api`. Empty runs on every path.
+    #[serde(default)]
+    pub routes: Vec<String>,
+
+    /// Operations applied in order to the query string sent upstream.
+    #[serde(default)]
+    pub operations: Vec<QueryOperationSpec>,
+}
+
+#[derive(Default, Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct QueryOperationSpec {
+    /// What the operation does.
+    pub op: QueryOperationKindSpec,
+
+    /// Query parameter the operation applies to, e.g. `utm_source`.
+    pub name: String,
+
+    /// Value written by `add_if_missing` and `set`.
+    pub value: Option<String>,
+
+    /// New parameter name for `rename`.
+    pub to: Option<String>,
+}
+
+#[derive(Default, Clone, Copy, Debug, Deserialize, JsonSchema, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryOperationKindSpec {
+    /// Add the parameter with `value`, unless the query already has it.
+    #[default]
+    AddIfMissing,
+
+    /// Replace every value of the parameter with `value`, adding it if absent.
+    Set,
+
+    /// Remove every value of the parameter.
+    Remove,
+
+    /// Rename the parameter to `to`, keeping its values.
+    Rename,
+}
@@ -0,0 +1,54 @@
+use crate::conf::types::Origin;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct QuotaDeviceSpec {
+    #[serde(skip)]
+    pub origin: Origin,
+
+    /// Whether this quota device is enabled.
+    pub enable: bool,
+
+    /// Where this quota device runs in the pipeline, lower first. Ties keep declaration order.
+    #[serde(default)]
+    pub priority: i32,
+
+    /// Request path prefixes this quota device runs on, e.g. `/api`. Empty runs on every path.
+    #[serde(default)]
+    pub routes: Vec<String>,
+
+    /// URL of the external quota service, e.g. "http://127.0.0.1:9100/quota".
+    pub quota_url: String,
+
+    /// Request header carrying the API key.
+    /// The key is sent to the quota service under the same header name.
+    #[serde(default = "default_api_key_header")]
+    pub api_key_header: String,
+
+    /// How long to wait for the quota service before giving up.
+    #[serde(default = "default_timeout_milliseconds")]
+    pub timeout_milliseconds: u64,
+
+    /// How long a quota decision is reused for the same API key.
+    /// Set to 0 to call the quota service on every request.
+    #[serde(default = "default_cache_ttl_milliseconds")]
+    pub cache_ttl_milliseconds: u64,
+
+    /// Let requests through when the quota service fails or times out.
+    #[serde(default)]
+    pub fail_open: bool,
+}
+
+fn default_api_key_header() -> String {
+    "X-API-Key".to_string()
+}
+
+fn default_timeout_milliseconds() -> u64 {
+    250
+}
+
+fn default_cache_ttl_milliseconds() -> u64 {
+    1000
+}
@@ -0,0 +1,44 @@
+use crate::conf::types::Origin;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimitDeviceSpec {
+    #[serde(skip)]
+    pub origin: Origin,
+
+    /// Whether this rate limit device is enabled.
+    pub enable: bool,
+
+    /// Where this rate limit device runs in the pipeline, lower first. Ties keep declaration order.
+    #[serde(default)]
+    pub priority: i32,
+
+    /// Request path prefixes this rate limit device runs on, e.g. `/api`. Empty runs on every path.
+    #[serde(default)]
+    pub routes: Vec<String>,
+
+    /// Requests each key may make per second, on average.
+    pub requests_per_second: u32,
+
+    /// Requests a key may make at once after being idle. Defaults to `requests_per_second`.
+    pub burst: Option<u32>,
+
+    /// What requests are counted by.
+    #[serde(default)]
+    pub key: RateLimitKeySpec,
+
+    /// Header counted by with `key = "header"`. Requests without it are not limited.
+    pub key_header: Option<String>,
+}
+
+#[derive(Default, Debug, Deserialize, JsonSchema, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitKeySpec {
+    /// The client IP resolved by the identity device, or the peer IP without it.
+    #[default]
+    ClientIp,
+    Header,
+    Path,
+}
@@ -1,7 +1,8 @@
 use crate::conf::types::Origin;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+#[derive(Default, Clone, Debug, Deserialize, JsonSchema, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct RequestFilterDeviceSpec {
     #[serde(skip)]
@@ -10,6 +11,14 @@ pub struct RequestFilterDeviceSpec {
     /// Whether this request filter device is enabled.
     pub enable: bool,
 
+    /// Where this request filter device runs in the pipeline, lower first. Ties keep declaration order.
+    #[serde(default)]
+    pub priority: i32,
+
+    /// Request path prefixes this request filter device runs on, e.g. `/api`. Empty runs on every path.
+    #[serde(default)]
+    pub routes: Vec<String>,
+
     //-------------------------------------------------------------------------
     // Method policy
     //-------------------------------------------------------------------------
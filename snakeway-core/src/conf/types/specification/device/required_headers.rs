@@ -0,0 +1,47 @@
+use crate::conf::types::Origin;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RequiredHeadersDeviceSpec {
+    #[serde(skip)]
+    pub origin: Origin,
+
+    /// Whether this required headers device is enabled.
+    pub enable: bool,
+
+    /// Where this required headers device runs in the pipeline, lower first. Ties keep declaration order.
+    #[serde(default)]
+    pub priority: i32,
+
+    /// Request path prefixes this required headers device runs on, e.g. `/api`. Empty runs on every path.
+    #[serde(default)]
+    pub routes: Vec<String>,
+
+    /// Headers every request must carry.
+    #[serde(default)]
+    pub headers: Vec<RequiredHeaderSpec>,
+}
+
+#[derive(Default, Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RequiredHeaderSpec {
+    /// Header name, e.g. `X-Correlation-Id`.
+    pub name: String,
+
+    /// What to do when a request does not carry the header.
+    #[serde(default)]
+    pub on_missing: MissingHeaderActionSpec,
+
+    /// Value set in `populate` mode. `{request_id}` is replaced with the request id.
+    pub value: Option<String>,
+}
+
+#[derive(Default, Debug, Deserialize, JsonSchema, Serialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum MissingHeaderActionSpec {
+    #[default]
+    Reject,
+    Populate,
+}
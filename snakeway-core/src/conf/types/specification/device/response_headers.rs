@@ -0,0 +1,39 @@
+use crate::conf::types::Origin;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Placeholders a response header value may use.
+pub const RESPONSE_HEADER_PLACEHOLDERS: [&str; 3] = ["{request_id}", "{upstream}", "{route}"];
+
+#[derive(Default, Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ResponseHeadersDeviceSpec {
+    #[serde(skip)]
+    pub origin: Origin,
+
+    /// Whether this response headers device is enabled.
+    pub enable: bool,
+
+    /// Where this response headers device runs in the pipeline, lower first. Ties keep declaration order.
+    #[serde(default)]
+    pub priority: i32,
+
+    /// Request path prefixes this response headers device runs on, e.g. `/api`. Empty runs on every path.
+    #[serde(default)]
+    pub routes: Vec<String>,
+
+    /// Headers added to every proxied response.
+    #[serde(default)]
+    pub headers: Vec<ResponseHeaderSpec>,
+}
+
+#[derive(Default, Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ResponseHeaderSpec {
+    /// Header name, e.g. `X-Served-By`.
+    pub name: String,
+
+    /// Value template. `{request_id}`, `{upstream}`, and `{route}` are replaced with
+    /// values captured from the request.
+    pub value: String,
+}
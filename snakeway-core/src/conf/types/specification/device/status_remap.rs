@@ -0,0 +1,38 @@
+use crate::conf::types::Origin;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct StatusRemapDeviceSpec {
+    #[serde(skip)]
+    pub origin: Origin,
+
+    /// Whether this status remap device is enabled.
+    pub enable: bool,
+
+    /// Where this status remap device runs in the pipeline, lower first. Ties keep declaration order.
+    #[serde(default)]
+    pub priority: i32,
+
+    /// Request path prefixes this status remap device runs on, e.g. `/api`. Empty runs on every path.
+    #[serde(default)]
+    pub routes: Vec<String>,
+
+    /// Upstream statuses to rewrite. Statuses not listed pass through unchanged.
+    #[serde(default)]
+    pub remaps: Vec<StatusRemapSpec>,
+}
+
+#[derive(Default, Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct StatusRemapSpec {
+    /// Status returned by the upstream, e.g. `418`.
+    pub from: u16,
+
+    /// Status sent to the client instead, e.g. `429`.
+    pub to: u16,
+
+    /// Replaces the upstream response body, sent as `text/plain`.
+    pub body: Option<String>,
+}
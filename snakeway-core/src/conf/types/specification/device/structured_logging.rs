@@ -1,8 +1,11 @@
 use crate::conf::types::Origin;
-use crate::device::builtin::structured_logging::{IdentityField, LogEvent, LogLevel, LogPhase};
+use crate::device::builtin::structured_logging::{
+    IdentityField, LogEvent, LogLevel, LogPhase, RouteHeaderCapture,
+};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+#[derive(Default, Clone, Debug, Deserialize, JsonSchema, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct StructuredLoggingDeviceSpec {
     #[serde(skip)]
@@ -10,6 +13,12 @@ pub struct StructuredLoggingDeviceSpec {
 
     pub enable: bool,
 
+    #[serde(default)]
+    pub priority: i32,
+
+    #[serde(default)]
+    pub routes: Vec<String>,
+
     pub level: LogLevel,
 
     /// Headers are excluded by default.
@@ -28,6 +37,10 @@ pub struct StructuredLoggingDeviceSpec {
     /// Identity fields to include in the request context (and possibly log).
     pub identity_fields: Vec<IdentityField>,
 
+    /// Headers to capture on specific routes only (subject to redaction).
+    #[serde(default)]
+    pub route_headers: Vec<RouteHeaderCapture>,
+
     pub events: Option<Vec<LogEvent>>,
 
     pub phases: Option<Vec<LogPhase>>,
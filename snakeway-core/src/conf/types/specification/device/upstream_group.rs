@@ -0,0 +1,30 @@
+use crate::conf::types::Origin;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Default, Clone, Debug, Deserialize, JsonSchema, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct UpstreamGroupDeviceSpec {
+    #[serde(skip)]
+    pub origin: Origin,
+
+    /// Whether this upstream group device is enabled.
+    pub enable: bool,
+
+    /// Where this upstream group device runs in the pipeline, lower first. Ties keep declaration order.
+    #[serde(default)]
+    pub priority: i32,
+
+    /// Request path prefixes this upstream group device runs on, e.g. `/api`. Empty runs on every path.
+    #[serde(default)]
+    pub routes: Vec<String>,
+
+    /// Request header that selects the upstream group, e.g. `X-Tenant`.
+    pub header: String,
+
+    /// Header value to upstream group, e.g. `{ premium = "premium" }`.
+    /// Requests with any other value, or without the header, use the default pool.
+    #[serde(default)]
+    pub groups: HashMap<String, String>,
+}
@@ -1,17 +1,72 @@
 use crate::conf::types::Origin;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
-#[derive(Default, Debug, Clone, Deserialize, Serialize)]
+/// Fuel a WASM device hook call may consume when `fuel` is not set.
+pub const DEFAULT_WASM_DEVICE_FUEL: u64 = 1_000_000_000;
+
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize)]
 pub struct WasmDeviceSpec {
     #[serde(skip)]
     pub origin: Origin,
 
     pub enable: bool,
 
+    #[serde(default)]
+    pub priority: i32,
+
+    #[serde(default)]
+    pub routes: Vec<String>,
+
+    /// Name used to attach the device to listeners.
+    /// Defaults to the file stem of the module path.
+    pub name: Option<String>,
+
     /// The location of the WASM module.
     pub path: PathBuf,
 
+    /// Fuel each hook call may consume, roughly one unit per WASM instruction.
+    /// A call that runs out is aborted, and the device lets the request continue.
+    #[serde(default = "default_fuel")]
+    pub fuel: u64,
+
+    /// Run hook calls on the blocking thread pool, for devices doing heavy synchronous work.
+    #[serde(default)]
+    pub offload: bool,
+
     /// Device-specific configuration blob
+    #[schemars(with = "Option<serde_json::Value>")]
     pub config: Option<hcl::Value>,
 }
+
+impl Default for WasmDeviceSpec {
+    fn default() -> Self {
+        Self {
+            origin: Origin::default(),
+            enable: false,
+            priority: 0,
+            routes: Vec::new(),
+            name: None,
+            path: PathBuf::new(),
+            fuel: default_fuel(),
+            offload: false,
+            config: None,
+        }
+    }
+}
+
+fn default_fuel() -> u64 {
+    DEFAULT_WASM_DEVICE_FUEL
+}
+
+impl WasmDeviceSpec {
+    pub fn name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| {
+            self.path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default()
+        })
+    }
+}
@@ -1,16 +1,24 @@
 use crate::conf::types::ServerSpec;
+use schemars::JsonSchema;
 use serde::Deserialize;
 
-/// Represents the top-level configuration file.
-#[derive(Debug, Deserialize)]
+/// Represents the top-level configuration file, `snakeway.hcl`.
+///
+/// It holds the server settings and the glob patterns of the files to include. Binds,
+/// services and static files are declared together in each ingress file, so they never
+/// reference one another across files.
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct EntrypointSpec {
     pub server: ServerSpec,
     pub include: IncludeSpec,
 }
 
 /// Represents the include section of the top-level config file.
-/// The members are directory paths where sub-configuration files are located.
-#[derive(Debug, Deserialize)]
+/// The members are glob patterns, relative to the config directory, of sub-configuration files.
+///
+/// The device pattern may match nothing. The ingress pattern must match at least one file,
+/// since the ingress files declare every bind.
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct IncludeSpec {
     pub devices: String,
     pub ingress: String,
@@ -13,22 +13,36 @@ pub use bind::{BindSpec, RedirectSpec};
 pub use bind_admin::BindAdminSpec;
 pub use bind_interface::{BindInterfaceInput, BindInterfaceSpec};
 pub use device::{
-    DeviceSpec, IdentityDeviceSpec, RequestFilterDeviceSpec, StructuredLoggingDeviceSpec,
-    UaEngineSpec, WasmDeviceSpec,
+    BodyDigestDeviceSpec, CorsDeviceSpec, DEFAULT_WASM_DEVICE_FUEL, DeprecationDeviceSpec,
+    DeprecationSpec, DeviceSpec, ErrorPageDeviceSpec, ExperimentDeviceSpec, ExperimentVariantSpec,
+    ForwardingHeaderSpec, HeaderRewriteDeviceSpec, HeaderSetSpec, IdempotencyDeviceSpec,
+    IdentityDeviceSpec, IpReputationActionSpec, IpReputationDeviceSpec, JWT_HMAC_ALGORITHMS,
+    JWT_JWKS_ALGORITHMS, JwtAuthDeviceSpec, LuaDeviceSpec, MissingHeaderActionSpec,
+    OpenApiDeviceSpec, QueryOperationKindSpec, QueryOperationSpec, QueryRewriteDeviceSpec,
+    QuotaDeviceSpec, RESPONSE_HEADER_PLACEHOLDERS, RateLimitDeviceSpec, RateLimitKeySpec,
+    RequestFilterDeviceSpec, RequiredHeaderSpec, RequiredHeadersDeviceSpec, ResponseHeaderSpec,
+    ResponseHeadersDeviceSpec, StatusRemapDeviceSpec, StatusRemapSpec, StructuredLoggingDeviceSpec,
+    UaEngineSpec, UpstreamGroupDeviceSpec, WasmDeviceSpec, error_page_statuses,
 };
 pub use entrypoint::EntrypointSpec;
 pub use origin::Origin;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-pub use server::ServerSpec;
+pub use server::{GeoIpSpec, ServerSpec, ServiceDefaultsSpec};
 pub use service::{
-    EndpointSpec, HostSpec, LoadBalancingStrategySpec, ServiceRouteSpec, ServiceSpec, UpstreamSpec,
+    EndpointSpec, HostSpec, LoadBalancingStrategySpec, RequireTlsAction, RequireTlsSpec,
+    ResponseRateLimitSpec, ServiceRouteSpec, ServiceSpec, UPSTREAM_ACCEPT_ENCODING_STRIP,
+    UPSTREAM_SNI_HOST_PLACEHOLDER, UpstreamConnectionSpec, UpstreamRequestHeaderSpec, UpstreamSpec,
+};
+pub use static_files::{
+    CachePolicySpec, CompressionOptsSpec, DEFAULT_COMPRESSION_SKIP_CONTENT_TYPES, SpaFallbackSpec,
+    StaticFilesSpec, StaticRouteSpec, default_skip_content_types,
 };
-pub use static_files::{CachePolicySpec, CompressionOptsSpec, StaticFilesSpec, StaticRouteSpec};
 pub use tls::TlsSpec;
 
 /// The operator DSL for the config subsystem.
 /// This defines the configuration file format of files in ./config/ingress.d/*.hcl
-#[derive(Debug, Deserialize, Serialize, Default)]
+#[derive(Debug, Deserialize, JsonSchema, Serialize, Default)]
 #[serde(rename_all = "snake_case")]
 pub struct IngressSpec {
     #[serde(skip)]
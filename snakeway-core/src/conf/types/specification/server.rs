@@ -1,8 +1,9 @@
-use crate::conf::types::Origin;
+use crate::conf::types::{CircuitBreakerConfig, HealthCheckConfig, Origin, UpstreamConnectionSpec};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
-#[derive(Debug, Deserialize, Serialize, Default)]
+#[derive(Debug, Deserialize, JsonSchema, Serialize, Default)]
 pub struct ServerSpec {
     #[serde(skip)]
     pub origin: Origin,
@@ -19,4 +20,100 @@ pub struct ServerSpec {
 
     /// Optional CA file path. If set, Pingora will use this file to verify upstream certificates.
     pub ca_file: Option<String>,
+
+    /// Optional time in seconds to drain in-flight requests on shutdown.
+    pub shutdown_timeout_seconds: Option<u64>,
+
+    /// Optional time in seconds to keep accepting connections on shutdown after readiness flips,
+    /// so load balancers see `/admin/ready` fail before listeners close.
+    pub shutdown_readiness_grace_seconds: Option<u64>,
+
+    /// Reject requests whose Host is not declared in any service's `hosts` on the listener.
+    #[serde(default)]
+    pub strict_host: bool,
+
+    /// Answer requests denied by a device with `404` instead of `403`,
+    /// so blocked routes look like they do not exist. Routes can override this.
+    #[serde(default)]
+    pub hide_as_404: bool,
+
+    /// Optional maximum number of concurrent WebSocket connections across all routes.
+    pub ws_max_connections: Option<usize>,
+
+    /// Optional largest request body accepted, in bytes. Larger requests are answered with `413`.
+    /// Routes can override this.
+    pub max_request_body_bytes: Option<u64>,
+
+    /// Optional time a proxied request may take, from arriving to its response being sent.
+    /// Slower requests are answered with `504`. Routes can override this.
+    pub request_timeout_milliseconds: Option<u64>,
+
+    /// Bind one SO_REUSEPORT socket per worker thread on public listeners,
+    /// so the kernel spreads accepted connections across them.
+    #[serde(default)]
+    pub reuse_port: bool,
+
+    /// Optional fraction of connections whose lifecycle events are logged, from 0.0 to 1.0.
+    /// Failure events are always logged.
+    pub connection_log_sample_rate: Option<f64>,
+
+    /// Fail startup when a WASM device's module is missing or fails to load (default: true).
+    /// If false, such devices are disabled with a warning instead.
+    pub strict_wasm_devices: Option<bool>,
+
+    /// Proxies, as IPs or CIDR ranges, whose `X-Forwarded-Host` is used as the host of
+    /// redirect URLs Snakeway builds, e.g. for `require_tls`.
+    #[serde(default)]
+    pub trusted_proxies: Vec<String>,
+
+    /// Log one `access` event per request, with its method, path, status, and latency.
+    #[serde(default)]
+    pub access_log: bool,
+
+    /// Optional template of the access log line, e.g. `$client_ip $method $path $status`.
+    /// Only the fields it names are logged. Every field is logged when unset.
+    pub access_log_format: Option<String>,
+
+    /// Optional MaxMind databases the identity device looks up client locations in.
+    /// They are reopened on reload, so replaced `.mmdb` files take effect on `SIGHUP`.
+    pub geoip: Option<GeoIpSpec>,
+
+    /// Settings every service inherits unless it sets its own.
+    pub defaults: Option<ServiceDefaultsSpec>,
+}
+
+impl ServerSpec {
+    /// Whether WASM devices that fail to load fail startup, rather than being disabled.
+    pub fn strict_wasm_devices(&self) -> bool {
+        self.strict_wasm_devices.unwrap_or(true)
+    }
+}
+
+/// Server-wide service settings, applied to services that leave them unset.
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, Default)]
+pub struct ServiceDefaultsSpec {
+    /// Connection settings, inherited field by field.
+    pub connection: Option<UpstreamConnectionSpec>,
+    /// Inherited by services without a `health_check` block.
+    pub health_check: Option<HealthCheckConfig>,
+    /// Inherited by services without a `circuit_breaker` block.
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
+}
+
+/// MaxMind `.mmdb` databases, any of which may be left out.
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, Default)]
+pub struct GeoIpSpec {
+    /// City or Country database, e.g. GeoLite2-City, for the country and region.
+    pub city_db: Option<PathBuf>,
+    /// ASN or ISP database, e.g. GeoLite2-ASN, for the autonomous system.
+    pub isp_db: Option<PathBuf>,
+    /// Connection-Type database, e.g. GeoIP2-Connection-Type, for the kind of network.
+    pub connection_type_db: Option<PathBuf>,
+}
+
+impl GeoIpSpec {
+    /// Whether no database is set.
+    pub fn is_empty(&self) -> bool {
+        self.city_db.is_none() && self.isp_db.is_none() && self.connection_type_db.is_none()
+    }
 }
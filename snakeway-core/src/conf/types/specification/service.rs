@@ -1,10 +1,15 @@
 use crate::conf::resolution::ResolveError;
-use crate::conf::types::{CircuitBreakerConfig, HealthCheckConfig, Origin};
+use crate::conf::secrets::Secret;
+use crate::conf::types::{
+    CircuitBreakerConfig, HealthCheckConfig, KetamaConfig, Origin, OutlierDetectionConfig,
+    RetryPolicyConfig, default_skip_content_types,
+};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::net::{SocketAddr, ToSocketAddrs};
 
-#[derive(Debug, Deserialize, Default, Serialize)]
+#[derive(Debug, Deserialize, JsonSchema, Default, Serialize)]
 pub struct ServiceSpec {
     #[serde(skip)]
     pub origin: Origin,
@@ -14,9 +19,62 @@ pub struct ServiceSpec {
     pub upstreams: Vec<UpstreamSpec>,
     pub health_check: Option<HealthCheckConfig>,
     pub circuit_breaker: Option<CircuitBreakerConfig>,
+    /// Ejects upstreams that keep failing requests, see `OutlierDetectionConfig`.
+    pub outlier_detection: Option<OutlierDetectionConfig>,
+    /// Retries requests that failed on an upstream, see `RetryPolicyConfig`.
+    pub retry_policy: Option<RetryPolicyConfig>,
+    /// Hash ring settings, used by the `ketama` strategy.
+    pub ketama: Option<KetamaConfig>,
+    /// Connection defaults for every upstream of this service.
+    pub connection: Option<UpstreamConnectionSpec>,
+    /// Request hosts this service answers for. Only enforced when `strict_host` is enabled.
+    #[serde(default)]
+    pub hosts: Vec<HostSpec>,
+    /// Header names to send to HTTP/1.1 upstreams with exactly this casing,
+    /// e.g. `WWW-Authenticate`, for backends that are case-sensitive about header names.
+    #[serde(default)]
+    pub upstream_header_case: Vec<String>,
+    /// SNI sent to TLS upstreams, e.g. `{host}` or `{host}.storage.example.com`.
+    /// `{host}` is replaced with the request's Host. Defaults to the upstream hostname.
+    pub upstream_sni: Option<String>,
+    /// `Accept-Encoding` sent to upstreams, e.g. `identity` so Snakeway compresses once.
+    /// `strip` removes the header instead.
+    pub upstream_accept_encoding: Option<String>,
+    /// Compress proxied responses for clients that accept it.
+    #[serde(default)]
+    pub enable_response_compression: bool,
+    /// Headers added to every request sent to this service's upstreams, e.g. an API key.
+    /// Values may be secret references. Client-sent headers with the same name are replaced.
+    #[serde(default)]
+    pub upstream_request_headers: Vec<UpstreamRequestHeaderSpec>,
+    /// Response content types that are never compressed, e.g. `image/*` or `application/zip`.
+    #[serde(default = "default_skip_content_types")]
+    pub response_compression_skip_content_types: Vec<String>,
+    /// Responses with a smaller `Content-Length` are not compressed.
+    #[serde(default = "default_response_compression_min_size")]
+    pub response_compression_min_size: u64,
+}
+
+fn default_response_compression_min_size() -> u64 {
+    1024 // 1 KiB
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+/// A static header injected into upstream requests.
+#[derive(Debug, Deserialize, JsonSchema, Serialize, Clone)]
+pub struct UpstreamRequestHeaderSpec {
+    pub name: String,
+    /// Header value, or a secret reference such as `secret://env/BACKEND_API_KEY`.
+    /// Redacted when the spec is dumped.
+    pub value: Secret,
+}
+
+/// Placeholder in `upstream_sni` that is replaced with the request's Host (without port).
+pub const UPSTREAM_SNI_HOST_PLACEHOLDER: &str = "{host}";
+
+/// `upstream_accept_encoding` value that removes the header instead of rewriting it.
+pub const UPSTREAM_ACCEPT_ENCODING_STRIP: &str = "strip";
+
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum LoadBalancingStrategySpec {
     #[default]
@@ -25,9 +83,11 @@ pub enum LoadBalancingStrategySpec {
     RequestPressure,
     StickyHash,
     Random,
+    Ewma,
+    Ketama,
 }
 
-#[derive(Debug, Deserialize, Serialize, Default)]
+#[derive(Debug, Deserialize, JsonSchema, Serialize, Default)]
 pub struct ServiceRouteSpec {
     #[serde(skip)]
     pub origin: Origin,
@@ -35,9 +95,49 @@ pub struct ServiceRouteSpec {
     #[serde(default)]
     pub enable_websocket: bool,
     pub ws_max_connections: Option<usize>,
+    /// Paces responses on this route to a maximum rate, per connection.
+    pub response_rate_limit: Option<ResponseRateLimitSpec>,
+    /// Overrides the server's `hide_as_404` for this route.
+    pub hide_as_404: Option<bool>,
+    /// Overrides the server's `max_request_body_bytes` for this route.
+    pub max_request_body_bytes: Option<u64>,
+    /// Overrides the server's `request_timeout_milliseconds` for this route.
+    pub request_timeout_milliseconds: Option<u64>,
+    /// Keeps this route off plaintext HTTP listeners.
+    pub require_tls: Option<RequireTlsSpec>,
+    /// How long the sticky hash strategy keeps a client on the same upstream, before
+    /// selecting afresh. Affinity never expires by default.
+    pub affinity_ttl_seconds: Option<u64>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Default)]
+/// What a route requiring TLS does with requests arriving over plaintext HTTP.
+#[derive(Debug, Deserialize, JsonSchema, Serialize, Default, Clone, PartialEq, Eq)]
+pub struct RequireTlsSpec {
+    #[serde(default)]
+    pub action: RequireTlsAction,
+    /// Status rejected requests are answered with, `403` or `400`. Defaults to `403`.
+    pub reject_status: Option<u16>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema, Serialize, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RequireTlsAction {
+    /// Redirect to the same URL over HTTPS, with a `308`.
+    #[default]
+    Redirect,
+    /// Answer with the `reject_status`.
+    Reject,
+}
+
+/// Token bucket settings for response rate limiting.
+#[derive(Debug, Deserialize, JsonSchema, Serialize, Default, Clone, PartialEq, Eq)]
+pub struct ResponseRateLimitSpec {
+    pub bytes_per_second: u64,
+    /// Bytes that may be sent at full speed before pacing starts. Defaults to one second's worth.
+    pub burst_bytes: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema, Serialize, Default)]
 pub struct UpstreamSpec {
     #[serde(skip)]
     pub origin: Origin,
@@ -45,12 +145,32 @@ pub struct UpstreamSpec {
     pub sock: Option<String>,
     #[serde(default = "default_weight")]
     pub weight: u32,
+    /// Connection settings for this upstream, overriding the service's.
+    pub connection: Option<UpstreamConnectionSpec>,
+    /// Upstream group a device may send requests to, e.g. `premium`.
+    /// Ungrouped upstreams form the service's default pool.
+    pub group: Option<String>,
 }
 fn default_weight() -> u32 {
     1
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+/// Upstream connection pooling, timeout, and retry settings.
+///
+/// Unset fields fall back to the service's settings, then to built-in defaults.
+#[derive(Debug, Deserialize, JsonSchema, Serialize, Default, Clone, PartialEq, Eq)]
+pub struct UpstreamConnectionSpec {
+    pub connect_timeout_milliseconds: Option<u64>,
+    pub read_timeout_milliseconds: Option<u64>,
+    /// How long an idle pooled connection is kept before it is closed.
+    pub idle_timeout_seconds: Option<u64>,
+    /// How long a response body may go without bytes from the upstream before it is aborted.
+    pub response_idle_timeout_milliseconds: Option<u64>,
+    /// How many times a failed connection attempt is retried.
+    pub max_connect_retries: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema, Serialize, Clone, PartialEq, Eq)]
 #[serde(untagged)]
 pub enum HostSpec {
     Ip(std::net::IpAddr),
@@ -66,7 +186,7 @@ impl fmt::Display for HostSpec {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[derive(Debug, Deserialize, JsonSchema, Serialize, Clone, PartialEq, Eq)]
 pub struct EndpointSpec {
     pub host: HostSpec,
     pub port: u16,
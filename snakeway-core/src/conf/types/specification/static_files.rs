@@ -1,15 +1,16 @@
-use crate::conf::types::Origin;
+use crate::conf::types::{Origin, RequireTlsSpec};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
-#[derive(Debug, Deserialize, Default, Serialize)]
+#[derive(Debug, Deserialize, JsonSchema, Default, Serialize)]
 pub struct StaticFilesSpec {
     #[serde(skip)]
     pub origin: Origin,
     pub routes: Vec<StaticRouteSpec>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Default)]
+#[derive(Debug, Deserialize, JsonSchema, Serialize, Default)]
 pub struct StaticRouteSpec {
     #[serde(skip)]
     pub origin: Origin,
@@ -20,15 +21,59 @@ pub struct StaticRouteSpec {
     pub max_file_size: u64,
     pub compression: CompressionOptsSpec,
     pub cache_policy: CachePolicySpec,
+    /// Overrides the server's `hide_as_404` for this route.
+    pub hide_as_404: Option<bool>,
+    /// Keeps this route off plaintext HTTP listeners.
+    pub require_tls: Option<RequireTlsSpec>,
+    /// Serves a fallback document for paths matching no file, for single-page apps.
+    pub spa_fallback: Option<SpaFallbackSpec>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+/// Fallback for paths matching no file, so client-side routing of single-page apps works.
+#[derive(Debug, Deserialize, JsonSchema, Serialize, Default, Clone, PartialEq, Eq)]
+pub struct SpaFallbackSpec {
+    /// Document served in place of missing files, relative to `file_dir`.
+    /// Defaults to `index.html`.
+    pub document: Option<String>,
+    /// Whether missing paths with a file extension, e.g. `/app.js`, still get a 404 rather than
+    /// the fallback document, so broken asset references are not masked. Defaults to `true`.
+    pub missing_assets_404: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize)]
 pub struct CompressionOptsSpec {
     pub small_file_threshold: u64,
     pub min_gzip_size: u64,
     pub min_brotli_size: u64,
     pub enable_gzip: bool,
     pub enable_brotli: bool,
+    /// Content types that are never compressed, e.g. `image/*` or `application/zip`.
+    #[serde(default = "default_skip_content_types")]
+    pub skip_content_types: Vec<String>,
+}
+
+/// Content types that are already compressed, so compressing them again only costs CPU.
+pub const DEFAULT_COMPRESSION_SKIP_CONTENT_TYPES: &[&str] = &[
+    "image/*",
+    "video/*",
+    "audio/*",
+    "font/woff",
+    "font/woff2",
+    "application/zip",
+    "application/gzip",
+    "application/x-gzip",
+    "application/x-bzip2",
+    "application/x-7z-compressed",
+    "application/x-rar-compressed",
+    "application/zstd",
+    "application/pdf",
+];
+
+pub fn default_skip_content_types() -> Vec<String> {
+    DEFAULT_COMPRESSION_SKIP_CONTENT_TYPES
+        .iter()
+        .map(|t| t.to_string())
+        .collect()
 }
 
 impl Default for CompressionOptsSpec {
@@ -39,11 +84,12 @@ impl Default for CompressionOptsSpec {
             min_brotli_size: 4 * 1024,        // 4 KiB
             enable_gzip: true,
             enable_brotli: true,
+            skip_content_types: default_skip_content_types(),
         }
     }
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize)]
 pub struct CachePolicySpec {
     pub max_age_seconds: u32,
     pub public: bool,
@@ -1,6 +1,7 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[derive(Debug, Clone, Deserialize, JsonSchema, Serialize, Default)]
 pub struct TlsSpec {
     pub cert: String,
     pub key: String,
@@ -1,3 +1,5 @@
+use crate::conf::secrets::SecretError;
+use crate::conf::types::Origin;
 use std::path::PathBuf;
 use thiserror::Error;
 
@@ -20,6 +22,19 @@ pub enum ConfigError {
         source: glob::PatternError,
     },
 
+    #[error("no ingress files match the include pattern: {pattern}")]
+    NoIngressFiles { pattern: String },
+
+    #[error("cyclic ingress include: {}", display_chain(.chain))]
+    IncludeCycle { chain: Vec<PathBuf> },
+
+    #[error("{field} is declared in both {origin} and the included {included}")]
+    IncludeConflict {
+        field: String,
+        origin: String,
+        included: String,
+    },
+
     #[error("message")]
     Custom { message: String },
 
@@ -33,6 +48,17 @@ pub enum ConfigError {
         source: hcl::Error,
     },
 
+    //-------------------------------------------------------------------------
+    // Interpolation
+    //-------------------------------------------------------------------------
+    #[error("environment variable {name} is not set for {field} (origin: {origin}, line {line})")]
+    MissingEnvVar {
+        name: String,
+        field: String,
+        line: usize,
+        origin: Origin,
+    },
+
     //-------------------------------------------------------------------------
     // Validation during transformation
     //-------------------------------------------------------------------------
@@ -44,6 +70,12 @@ pub enum ConfigError {
 
     #[error("invalid header name: {value} (origin: {origin})")]
     InvalidHeaderName { value: String, origin: String },
+
+    //-------------------------------------------------------------------------
+    // Secret resolution
+    //-------------------------------------------------------------------------
+    #[error(transparent)]
+    Secret(#[from] SecretError),
 }
 
 impl ConfigError {
@@ -61,3 +93,12 @@ impl ConfigError {
         }
     }
 }
+
+/// Render an include chain as `a.hcl -> b.hcl -> a.hcl`.
+fn display_chain(chain: &[PathBuf]) -> String {
+    chain
+        .iter()
+        .map(|p| p.display().to_string())
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
@@ -0,0 +1,119 @@
+use crate::conf::types::{
+    BindInterfaceSpec, BindSpec, DeviceSpec, IngressSpec, ResponseHeadersDeviceSpec, ServiceSpec,
+    UpstreamConnectionSpec,
+};
+use crate::conf::validation::report::ValidationReport;
+
+/// Best-practice checks for a config that already passed validation.
+///
+/// Every finding is a warning: the config is valid, but likely not what an operator wants in production.
+pub fn lint_spec(ingresses: &[IngressSpec], devices: &[DeviceSpec]) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    let attached: Vec<&String> = ingresses
+        .iter()
+        .flat_map(|i| {
+            i.bind
+                .iter()
+                .flat_map(|b| &b.devices)
+                .chain(i.bind_admin.iter().flat_map(|b| &b.devices))
+        })
+        .collect();
+
+    let response_headers = devices.iter().find_map(|d| match d {
+        DeviceSpec::ResponseHeaders(cfg) if cfg.enable => Some(cfg),
+        _ => None,
+    });
+
+    for ingress in ingresses {
+        if let Some(bind) = &ingress.bind {
+            lint_hsts(bind, response_headers, &attached, &mut report);
+        }
+
+        if let Some(admin) = &ingress.bind_admin {
+            let exposed = match BindInterfaceSpec::try_from(admin.interface.clone()) {
+                Ok(BindInterfaceSpec::All) => true,
+                Ok(BindInterfaceSpec::Ip(ip)) => ip.is_unspecified(),
+                _ => false,
+            };
+            if exposed {
+                report.lint_admin_bound_to_all_interfaces(&admin.origin);
+            }
+        }
+
+        for service in &ingress.services {
+            lint_service(service, &mut report);
+        }
+    }
+
+    if let Some(cfg) = response_headers {
+        lint_cors(cfg, &mut report);
+    }
+
+    report
+}
+
+fn lint_service(service: &ServiceSpec, report: &mut ValidationReport) {
+    let service_conn = service.connection.as_ref();
+    let missing_timeout = service.upstreams.iter().any(|u| {
+        let upstream_conn = u.connection.as_ref();
+        let set = |f: fn(&UpstreamConnectionSpec) -> Option<u64>| {
+            upstream_conn
+                .and_then(f)
+                .or(service_conn.and_then(f))
+                .is_some()
+        };
+        !set(|c| c.connect_timeout_milliseconds) || !set(|c| c.read_timeout_milliseconds)
+    });
+    if missing_timeout {
+        report.lint_service_missing_timeouts(&service.origin);
+    }
+
+    let health_checked = service.health_check.as_ref().is_some_and(|h| h.enable);
+    if service.upstreams.len() > 1 && !health_checked {
+        report.lint_service_without_health_check(service.upstreams.len(), &service.origin);
+    }
+}
+
+fn lint_hsts(
+    bind: &BindSpec,
+    response_headers: Option<&ResponseHeadersDeviceSpec>,
+    attached: &[&String],
+    report: &mut ValidationReport,
+) {
+    if bind.tls.is_none() {
+        return;
+    }
+
+    // Mirrors the runtime rule: an attached device only runs on its listeners, others run everywhere.
+    let name = "response_headers";
+    let runs_here =
+        bind.devices.iter().any(|d| d == name) || !attached.iter().any(|d| d.as_str() == name);
+
+    let sets_hsts = response_headers.is_some_and(|cfg| {
+        runs_here
+            && cfg
+                .headers
+                .iter()
+                .any(|h| h.name.eq_ignore_ascii_case("strict-transport-security"))
+    });
+    if !sets_hsts {
+        report.lint_tls_without_hsts(&bind.origin);
+    }
+}
+
+fn lint_cors(cfg: &ResponseHeadersDeviceSpec, report: &mut ValidationReport) {
+    let header = |name: &str| {
+        cfg.headers
+            .iter()
+            .find(|h| h.name.eq_ignore_ascii_case(name))
+            .map(|h| h.value.trim())
+    };
+
+    let any_origin = header("access-control-allow-origin") == Some("*");
+    let credentials =
+        header("access-control-allow-credentials").is_some_and(|v| v.eq_ignore_ascii_case("true"));
+    if any_origin && credentials {
+        report.lint_cors_any_origin_with_credentials(&cfg.origin);
+    }
+}
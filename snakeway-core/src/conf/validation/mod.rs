@@ -1,11 +1,15 @@
 mod error;
+mod lint;
 mod report;
 mod single_file;
+#[cfg(test)]
+mod tests;
 mod validate;
 mod validated_config;
 pub mod validator;
 
 pub use error::ConfigError;
+pub use lint::lint_spec;
 pub use report::*;
 #[cfg(test)]
 pub use single_file::*;
@@ -1,5 +1,6 @@
 use crate::conf::resolution::ResolveError;
-use crate::conf::types::Origin;
+use crate::conf::types::{AccessLogField, Origin};
+use crate::device::plugin::PluginError;
 use owo_colors::OwoColorize;
 use serde::Serialize;
 use std::fmt::Debug;
@@ -106,13 +107,18 @@ impl ValidationReport {
     }
 
     pub fn render_pretty(&self) {
+        self.render_pretty_as("configuration validation failed");
+    }
+
+    /// Pretty output under a custom summary line, e.g. for lint findings.
+    pub fn render_pretty_as(&self, summary: &str) {
         if !self.has_violations() {
             return;
         }
 
         // Establish that there are some errors and/or warnings.
         println!(
-            "configuration validation failed ({} errors, {} warnings)\n",
+            "{summary} ({} errors, {} warnings)\n",
             self.errors.len(),
             self.warnings.len()
         );
@@ -195,6 +201,14 @@ impl ValidationReport {
         self.error(format!("missing key file: {}", key_file), origin, None);
     }
 
+    pub fn invalid_secret_reference(&mut self, reference: &str, origin: &Origin) {
+        self.error(
+            format!("invalid secret reference: {}", reference),
+            origin,
+            Some("Use secret://<provider>/<name>, e.g. secret://env/TLS_KEY.".to_string()),
+        );
+    }
+
     pub fn http2_requires_tls(&mut self, addr: &str, origin: &Origin) {
         self.error(
             format!("HTTP/2 requires TLS: {}", addr),
@@ -219,6 +233,14 @@ impl ValidationReport {
         );
     }
 
+    pub fn invalid_require_tls_reject_status(&mut self, status_code: u16, origin: &Origin) {
+        self.error(
+            format!("invalid require_tls reject_status {status_code}"),
+            origin,
+            Some("Use 403 or 400.".to_string()),
+        );
+    }
+
     pub fn invalid_http_status_code(&mut self, status_code: u16, origin: &Origin) {
         self.error(
             format!("invalid HTTP status code {}", status_code),
@@ -227,6 +249,23 @@ impl ValidationReport {
         );
     }
 
+    /// Reported against the first origin in the loop; the message names all of them.
+    pub fn redirect_loop(&mut self, chain: &[String], origins: &[&Origin]) {
+        let involved = origins
+            .iter()
+            .map(|o| o.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.error(
+            format!("redirect loop: {} ({})", chain.join(" -> "), involved),
+            origins[0],
+            Some(
+                "redirect_http_to_https must point at a port that does not redirect back."
+                    .to_string(),
+            ),
+        );
+    }
+
     pub fn duplicate_redirect_http_to_https_port(&mut self, port: u16, origin: &Origin) {
         self.error(
             format!("duplicate redirect_http_to_https port: {}", port),
@@ -264,6 +303,14 @@ impl ValidationReport {
             None,
         );
     }
+
+    pub fn invalid_spa_fallback_document(&mut self, document: &str, origin: &Origin) {
+        self.error(
+            format!("invalid spa_fallback document: {document}"),
+            origin,
+            Some("Use a path relative to file_dir, e.g. index.html.".to_string()),
+        );
+    }
 }
 
 /// Service Spec Validation
@@ -276,6 +323,21 @@ impl ValidationReport {
         self.error(format!("invalid upstream weight: {}", weight), origin, None)
     }
 
+    pub fn service_has_no_default_upstreams(&mut self, origin: &Origin) {
+        self.error(
+            "service has no ungrouped upstreams".to_string(),
+            origin,
+            Some(
+                "Requests without an upstream group need at least one upstream without a group."
+                    .to_string(),
+            ),
+        )
+    }
+
+    pub fn upstream_group_is_empty(&mut self, origin: &Origin) {
+        self.error("upstream group is empty".to_string(), origin, None)
+    }
+
     pub fn upstream_cannot_have_both_sock_and_endpoint(
         &mut self,
         sock: &str,
@@ -327,6 +389,108 @@ impl ValidationReport {
             None,
         )
     }
+
+    pub fn invalid_service_host(&mut self, host: &str, origin: &Origin) {
+        self.error(format!("invalid service host: {}", host), origin, None)
+    }
+
+    pub fn strict_host_listener_declares_no_hosts(&mut self, origin: &Origin) {
+        self.warning(
+            "strict_host is enabled but no service on this listener declares hosts".to_string(),
+            origin,
+            Some("Every request to this listener will be rejected with 421".to_string()),
+        )
+    }
+
+    pub fn invalid_upstream_sni(&mut self, sni: &str, origin: &Origin) {
+        self.error(
+            format!("invalid upstream SNI: {}", sni),
+            origin,
+            Some("Use a hostname, optionally containing {host}.".to_string()),
+        )
+    }
+
+    pub fn invalid_upstream_accept_encoding(&mut self, value: &str, origin: &Origin) {
+        self.error(
+            format!("invalid upstream Accept-Encoding: {}", value),
+            origin,
+            Some("Use a header value such as identity, or strip to remove the header.".to_string()),
+        )
+    }
+
+    pub fn upstream_request_header_duplicated(&mut self, name: &str, origin: &Origin) {
+        self.error(
+            format!("upstream request header '{name}' is listed more than once"),
+            origin,
+            None,
+        )
+    }
+
+    pub fn invalid_upstream_request_header_value(&mut self, name: &str, origin: &Origin) {
+        // The value itself may be a credential, so it is never echoed.
+        self.error(
+            format!("upstream request header '{name}' has an invalid value"),
+            origin,
+            None,
+        )
+    }
+
+    pub fn active_health_check_invalid_path(&mut self, path: &str, origin: &Origin) {
+        self.error(
+            format!("active health check path '{path}' must start with '/'"),
+            origin,
+            None,
+        )
+    }
+
+    pub fn active_health_check_timeout_exceeds_interval(
+        &mut self,
+        timeout_ms: u64,
+        interval_ms: u64,
+        origin: &Origin,
+    ) {
+        self.warning(
+            format!(
+                "active health check timeout {timeout_ms}ms exceeds its interval {interval_ms}ms"
+            ),
+            origin,
+            Some("Probes of a slow upstream overlap. Use a shorter timeout.".to_string()),
+        )
+    }
+
+    pub fn outlier_base_ejection_time_exceeds_max(
+        &mut self,
+        base_ms: u64,
+        max_ms: u64,
+        origin: &Origin,
+    ) {
+        self.warning(
+            format!("outlier base ejection time {base_ms}ms exceeds its max {max_ms}ms"),
+            origin,
+            Some("Every ejection lasts the max ejection time.".to_string()),
+        )
+    }
+
+    pub fn circuit_open_duration_exceeds_max(
+        &mut self,
+        open_ms: u64,
+        max_ms: u64,
+        origin: &Origin,
+    ) {
+        self.warning(
+            format!("circuit open duration {open_ms}ms exceeds its max {max_ms}ms"),
+            origin,
+            Some("Every open lasts the max open duration.".to_string()),
+        )
+    }
+
+    pub fn affinity_ttl_without_sticky_strategy(&mut self, path: &str, origin: &Origin) {
+        self.warning(
+            format!("route {path} sets affinity_ttl_seconds, but its service is not sticky"),
+            origin,
+            Some("The TTL only applies to the sticky_hash load balancing strategy.".to_string()),
+        )
+    }
 }
 
 /// Server Spec Validation
@@ -366,122 +530,937 @@ impl ValidationReport {
             None,
         )
     }
+
+    pub fn reuse_port_with_single_thread(&mut self, origin: &Origin) {
+        self.warning(
+            "reuse_port is enabled but the server runs a single worker thread".to_string(),
+            origin,
+            Some("Set threads to bind one socket per worker thread".to_string()),
+        )
+    }
+
+    pub fn access_log_format_unknown_field(&mut self, field: &str, origin: &Origin) {
+        let fields = AccessLogField::ALL
+            .iter()
+            .map(|f| format!("${}", f.name()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.error(
+            format!("access_log_format uses unknown field ${field}"),
+            origin,
+            Some(format!("Use {fields}")),
+        )
+    }
+
+    pub fn access_log_format_without_access_log(&mut self, origin: &Origin) {
+        self.warning(
+            "access_log_format is set but access_log is disabled".to_string(),
+            origin,
+            Some("Set access_log = true to log requests".to_string()),
+        )
+    }
 }
 
-/// Wasm Device Spec Validation
+/// Device Attachment Validation
 impl ValidationReport {
-    pub fn wasm_device_path_is_empty(&mut self, path: Display, origin: &Origin) {
-        self.error(format!("wasm device path is empty: {}", path), origin, None)
+    pub fn unknown_device_attached_to_listener(&mut self, name: &str, origin: &Origin) {
+        self.error(
+            format!("unknown device attached to listener: {}", name),
+            origin,
+            Some("Attached devices must be defined in devices.d".to_string()),
+        )
     }
-    pub fn wasm_device_path_does_not_exist(&mut self, path: Display, origin: &Origin) {
+
+    pub fn duplicate_device_name(&mut self, name: &str, origin: &Origin) {
         self.error(
-            format!("wasm device path does not exist: {}", path),
+            format!("duplicate device name: {}", name),
             origin,
-            None,
+            Some("Set a unique `name` on each wasm device".to_string()),
         )
     }
-    pub fn wasm_device_path_is_not_a_file(&mut self, path: Display, origin: &Origin) {
+
+    pub fn invalid_device_route(&mut self, name: &str, route: &str, origin: &Origin) {
         self.error(
-            format!("wasm device path is not a file: {}", path),
+            format!("invalid route on device {}: {}", name, route),
+            origin,
+            Some(
+                "Device routes are path prefixes starting with '/', e.g. `/api` also matches `/api/users`"
+                    .to_string(),
+            ),
+        )
+    }
+
+    pub fn duplicate_device_route(&mut self, name: &str, route: &str, origin: &Origin) {
+        self.error(
+            format!("duplicate route on device {}: {}", name, route),
             origin,
             None,
         )
     }
 }
 
-/// Builtin Identity Device Spec Validation
+/// Wasm Device Spec Validation
 impl ValidationReport {
-    pub fn geoip_enabled_with_no_dbs_specified(&mut self, origin: &Origin) {
-        self.warning(
-            "geoip enabled with no dbs specified".to_string(),
+    pub fn wasm_device_path_is_empty(&mut self, path: Display, strict: bool, origin: &Origin) {
+        self.wasm_device_path_issue(
+            format!("wasm device path is empty: {}", path),
+            strict,
             origin,
-            Some("At least one geoip db must be specified".to_string()),
         )
     }
-
-    pub fn geoip_db_path_is_empty(&mut self, path: Display, origin: &Origin) {
-        self.error(format!("geoip db path is empty: {}", path), origin, None)
+    pub fn wasm_device_path_does_not_exist(
+        &mut self,
+        path: Display,
+        strict: bool,
+        origin: &Origin,
+    ) {
+        self.wasm_device_path_issue(
+            format!("wasm device path does not exist: {}", path),
+            strict,
+            origin,
+        )
     }
-    pub fn geoip_db_path_does_not_exist(&mut self, path: Display, origin: &Origin) {
+    pub fn wasm_device_path_is_not_a_file(&mut self, path: Display, strict: bool, origin: &Origin) {
+        self.wasm_device_path_issue(
+            format!("wasm device path is not a file: {}", path),
+            strict,
+            origin,
+        )
+    }
+    /// Without `strict_wasm_devices`, the device is disabled at startup rather than failing it.
+    fn wasm_device_path_issue(&mut self, message: String, strict: bool, origin: &Origin) {
+        if strict {
+            self.error(message, origin, None)
+        } else {
+            self.warning(
+                message,
+                origin,
+                Some("The device will be disabled, since strict_wasm_devices is false".to_string()),
+            )
+        }
+    }
+    pub fn wasm_plugin_is_invalid(&mut self, path: Display, error: &PluginError, origin: &Origin) {
         self.error(
-            format!("geoip db path does not exist: {}", path),
+            format!("wasm plugin {} is invalid: {}", path, error),
             origin,
             None,
         )
     }
-    pub fn geoip_db_is_not_a_file(&mut self, path: Display, origin: &Origin) {
+    pub fn wasm_plugin_config_is_invalid(
+        &mut self,
+        plugin: &str,
+        violation: &str,
+        origin: &Origin,
+    ) {
         self.error(
-            format!("geoip db path is not a file: {}", path),
+            format!(
+                "wasm device config does not match the schema of plugin '{plugin}': {violation}"
+            ),
             origin,
-            None,
+            Some(
+                "Run `snakeway plugin inspect` on the bundle to see its config schema".to_string(),
+            ),
         )
     }
+}
 
-    pub fn invalid_trusted_proxy(&mut self, proxy: &str, origin: &Origin) {
-        self.error(format!("invalid trusted proxy: {}", proxy), origin, None)
+/// Builtin OpenAPI Device Spec Validation
+impl ValidationReport {
+    pub fn openapi_device_already_defined(&mut self, origin: &Origin) {
+        self.error("openapi device already defined".to_string(), origin, None)
     }
 
-    pub fn trusted_proxies_cannot_trust_all_networks(&mut self, origin: &Origin) {
+    pub fn openapi_spec_path_is_not_a_file(&mut self, path: Display, origin: &Origin) {
         self.error(
-            "trusted_proxies must not contain a catch-all network (0.0.0.0/0 or ::/0)".to_string(),
+            format!("openapi spec_path is not a file: {}", path),
             origin,
-            None,
+            Some("Point spec_path at an OpenAPI 3 document in JSON or YAML".to_string()),
         )
     }
+}
 
-    pub fn trusted_proxies_contains_a_public_ip_range_warning(
+/// Builtin JWT Auth Device Spec Validation
+impl ValidationReport {
+    pub fn jwt_auth_device_already_defined(&mut self, origin: &Origin) {
+        self.error("jwt auth device already defined".to_string(), origin, None)
+    }
+
+    pub fn jwt_auth_key_source_required(&mut self, origin: &Origin) {
+        self.error(
+            "jwt auth device needs exactly one of hmac_secret and jwks_url".to_string(),
+            origin,
+            Some(
+                "Use hmac_secret for HS256 tokens, or jwks_url for RS256/ES256 tokens".to_string(),
+            ),
+        )
+    }
+
+    pub fn empty_jwt_auth_hmac_secret(&mut self, origin: &Origin) {
+        self.error("jwt auth hmac_secret is empty".to_string(), origin, None)
+    }
+
+    pub fn invalid_jwt_auth_jwks_url(&mut self, url: &str, origin: &Origin) {
+        self.error(
+            format!("invalid jwt auth jwks url: {}", url),
+            origin,
+            Some("Use an absolute http:// or https:// URL".to_string()),
+        )
+    }
+
+    pub fn invalid_jwt_auth_algorithm(
         &mut self,
-        network: ipnet::IpNet,
+        algorithm: &str,
+        allowed: &[&str],
         origin: &Origin,
     ) {
-        self.warning(
-            format!("trusted_proxies should NOT contain a public IP range: {network}"),
+        self.error(
+            format!(
+                "invalid jwt auth algorithm for this key source: {}",
+                algorithm
+            ),
+            origin,
+            Some(format!("Use one of: {}", allowed.join(", "))),
+        )
+    }
+}
+
+/// Builtin Quota Device Spec Validation
+impl ValidationReport {
+    pub fn invalid_quota_url(&mut self, url: &str, origin: &Origin) {
+        self.error(
+            format!("invalid quota url: {}", url),
+            origin,
+            Some("Use an absolute http:// or https:// URL".to_string()),
+        )
+    }
+
+    pub fn quota_device_already_defined(&mut self, origin: &Origin) {
+        self.error("quota device already defined".to_string(), origin, None)
+    }
+}
+
+/// Builtin IP Reputation Device Spec Validation
+impl ValidationReport {
+    pub fn ip_reputation_device_already_defined(&mut self, origin: &Origin) {
+        self.error(
+            "ip reputation device already defined".to_string(),
             origin,
             None,
         )
     }
 
-    pub fn ua_engine_is_empty(&mut self, origin: &Origin) {
-        self.error("ua_engine is empty".to_string(), origin, None)
+    pub fn ip_reputation_device_has_no_source(&mut self, origin: &Origin) {
+        self.error(
+            "ip reputation device has no list_file or reputation_url".to_string(),
+            origin,
+            Some("Set list_file, reputation_url, or both".to_string()),
+        )
     }
 
-    pub fn identity_device_already_defined(&mut self, origin: &Origin) {
-        self.error("identity device already defined".to_string(), origin, None)
+    pub fn ip_reputation_list_file_is_not_a_file(&mut self, path: Display, origin: &Origin) {
+        self.error(
+            format!("ip reputation list_file is not a file: {}", path),
+            origin,
+            None,
+        )
     }
 
-    pub fn request_filter_device_already_defined(&mut self, origin: &Origin) {
+    pub fn invalid_reputation_url(&mut self, url: &str, origin: &Origin) {
         self.error(
-            "request filter device already defined".to_string(),
+            format!("invalid reputation url: {}", url),
+            origin,
+            Some("Use an absolute http:// or https:// URL".to_string()),
+        )
+    }
+}
+
+/// Builtin Body Digest Device Spec Validation
+impl ValidationReport {
+    pub fn body_digest_device_already_defined(&mut self, origin: &Origin) {
+        self.error(
+            "body digest device already defined".to_string(),
             origin,
             None,
         )
     }
+}
 
-    pub fn structured_logging_device_already_defined(&mut self, origin: &Origin) {
+/// Builtin Upstream Group Device Spec Validation
+impl ValidationReport {
+    pub fn upstream_group_device_already_defined(&mut self, origin: &Origin) {
         self.error(
-            "structured logging device already defined".to_string(),
+            "upstream group device already defined".to_string(),
             origin,
             None,
         )
     }
 
-    pub fn invalid_http_method(&mut self, method: &str, origin: &Origin) {
-        self.error(format!("invalid HTTP method: {}", method), origin, None)
+    pub fn upstream_group_device_has_no_groups(&mut self, origin: &Origin) {
+        self.warning(
+            "upstream group device has no groups".to_string(),
+            origin,
+            Some("Every request will use the default upstream pool".to_string()),
+        )
     }
 
-    pub fn invalid_http_header_name(&mut self, header: &str, origin: &Origin) {
+    pub fn upstream_group_device_group_is_empty(&mut self, value: &str, origin: &Origin) {
         self.error(
-            format!("invalid HTTP header name: {}", header),
+            format!("upstream group for header value '{value}' is empty"),
             origin,
             None,
         )
     }
+}
 
-    pub fn warn_max_suspicious_bytes_large_than_max_body_bytes(&mut self, origin: &Origin) {
+/// Builtin Required Headers Device Spec Validation
+impl ValidationReport {
+    pub fn required_headers_device_already_defined(&mut self, origin: &Origin) {
+        self.error(
+            "required headers device already defined".to_string(),
+            origin,
+            None,
+        )
+    }
+
+    pub fn required_headers_device_has_no_headers(&mut self, origin: &Origin) {
         self.warning(
-            "max_suspicious_body_bytes should not be larger than max_body_bytes".to_string(),
+            "required headers device has no headers".to_string(),
             origin,
-            Some("max_suspicious_body_bytes applies to functions that can technically have a body, but should be treated suspiciously (and thus have a lower max size than a regular body)".to_string()),
+            Some("The device will not check any request".to_string()),
+        )
+    }
+
+    pub fn required_header_duplicated(&mut self, name: &str, origin: &Origin) {
+        self.error(
+            format!("required header '{name}' is listed more than once"),
+            origin,
+            None,
+        )
+    }
+
+    pub fn required_header_populate_without_value(&mut self, name: &str, origin: &Origin) {
+        self.error(
+            format!("required header '{name}' uses populate but has no value"),
+            origin,
+            Some("Set a value, e.g. value = \"{request_id}\"".to_string()),
+        )
+    }
+
+    pub fn required_header_invalid_value(&mut self, name: &str, origin: &Origin) {
+        self.error(
+            format!("required header '{name}' has an invalid value"),
+            origin,
+            None,
+        )
+    }
+
+    pub fn required_header_value_unused(&mut self, name: &str, origin: &Origin) {
+        self.warning(
+            format!("required header '{name}' has a value but rejects when missing"),
+            origin,
+            Some("The value is only used with on_missing = \"populate\"".to_string()),
+        )
+    }
+}
+
+/// Builtin Rate Limit Device Spec Validation
+impl ValidationReport {
+    pub fn rate_limit_device_already_defined(&mut self, origin: &Origin) {
+        self.error(
+            "rate limit device already defined".to_string(),
+            origin,
+            None,
+        )
+    }
+
+    pub fn rate_limit_key_header_missing(&mut self, origin: &Origin) {
+        self.error(
+            "rate limit key is header, but key_header is not set".to_string(),
+            origin,
+            Some("Set key_header to the header requests are counted by".to_string()),
+        )
+    }
+
+    pub fn rate_limit_key_header_unused(&mut self, origin: &Origin) {
+        self.warning(
+            "rate limit key_header is set, but key is not header".to_string(),
+            origin,
+            Some("Set key = \"header\" to count requests by key_header".to_string()),
+        )
+    }
+}
+
+/// Builtin Experiment Device Spec Validation
+impl ValidationReport {
+    pub fn experiment_device_already_defined(&mut self, origin: &Origin) {
+        self.error(
+            "experiment device already defined".to_string(),
+            origin,
+            None,
+        )
+    }
+
+    pub fn experiment_name_is_empty(&mut self, origin: &Origin) {
+        self.error("experiment name is empty".to_string(), origin, None)
+    }
+
+    pub fn experiment_cookie_is_empty(&mut self, origin: &Origin) {
+        self.error(
+            "experiment cookie is empty".to_string(),
+            origin,
+            Some("Set cookie to the name of the cookie identifying the user".to_string()),
+        )
+    }
+
+    pub fn experiment_variant_invalid_name(&mut self, name: &str, origin: &Origin) {
+        self.error(
+            format!("experiment variant name '{name}' is not a valid header value"),
+            origin,
+            None,
+        )
+    }
+
+    pub fn experiment_variant_duplicated(&mut self, name: &str, origin: &Origin) {
+        self.error(
+            format!("experiment variant '{name}' is listed more than once"),
+            origin,
+            None,
+        )
+    }
+
+    pub fn experiment_has_no_weighted_variants(&mut self, origin: &Origin) {
+        self.error(
+            "experiment has no variant with a weight above 0".to_string(),
+            origin,
+            Some("Add a variant, or give an existing one a weight".to_string()),
+        )
+    }
+}
+
+/// Builtin Header Rewrite Device Spec Validation
+impl ValidationReport {
+    pub fn header_rewrite_device_already_defined(&mut self, origin: &Origin) {
+        self.error(
+            "header rewrite device already defined".to_string(),
+            origin,
+            None,
+        )
+    }
+
+    pub fn header_rewrite_device_has_no_operations(&mut self, origin: &Origin) {
+        self.warning(
+            "header rewrite device has no operations".to_string(),
+            origin,
+            Some("The device will not change any header".to_string()),
+        )
+    }
+
+    pub fn header_rewrite_set_duplicated(&mut self, phase: &str, name: &str, origin: &Origin) {
+        self.error(
+            format!("{phase}_set header '{name}' is listed more than once"),
+            origin,
+            None,
+        )
+    }
+
+    pub fn header_rewrite_invalid_value(&mut self, name: &str, origin: &Origin) {
+        self.error(
+            format!("header rewrite value for '{name}' is invalid"),
+            origin,
+            None,
+        )
+    }
+
+    pub fn header_rewrite_set_and_removed(&mut self, phase: &str, name: &str, origin: &Origin) {
+        self.error(
+            format!("header '{name}' is in both {phase}_set and {phase}_remove"),
+            origin,
+            Some("Setting a header already replaces its values".to_string()),
+        )
+    }
+}
+
+/// Builtin Query Rewrite Device Spec Validation
+impl ValidationReport {
+    pub fn query_rewrite_device_already_defined(&mut self, origin: &Origin) {
+        self.error(
+            "query rewrite device already defined".to_string(),
+            origin,
+            None,
+        )
+    }
+
+    pub fn query_rewrite_device_has_no_operations(&mut self, origin: &Origin) {
+        self.warning(
+            "query rewrite device has no operations".to_string(),
+            origin,
+            Some("The device will not change any query".to_string()),
+        )
+    }
+
+    pub fn query_rewrite_operation_name_is_empty(&mut self, idx: usize, origin: &Origin) {
+        self.error(
+            format!("query rewrite operations[{idx}] has an empty name"),
+            origin,
+            None,
+        )
+    }
+
+    pub fn query_rewrite_operation_field_missing(
+        &mut self,
+        idx: usize,
+        field: &str,
+        origin: &Origin,
+    ) {
+        self.error(
+            format!("query rewrite operations[{idx}] requires '{field}'"),
+            origin,
+            None,
+        )
+    }
+
+    pub fn query_rewrite_operation_field_ignored(
+        &mut self,
+        idx: usize,
+        field: &str,
+        origin: &Origin,
+    ) {
+        self.warning(
+            format!("query rewrite operations[{idx}] does not use '{field}'"),
+            origin,
+            Some(format!("Remove '{field}', it is ignored by this operation")),
+        )
+    }
+}
+
+/// Builtin CORS Device Spec Validation
+impl ValidationReport {
+    pub fn cors_device_already_defined(&mut self, origin: &Origin) {
+        self.error("cors device already defined".to_string(), origin, None)
+    }
+
+    pub fn cors_allow_origins_is_empty(&mut self, origin: &Origin) {
+        self.error(
+            "cors device allows no origins".to_string(),
+            origin,
+            Some("List the allowed origins, or use \"*\" to allow any origin".to_string()),
+        )
+    }
+
+    pub fn cors_invalid_origin(&mut self, value: &str, origin: &Origin) {
+        self.error(
+            format!("cors origin '{value}' is not a valid origin"),
+            origin,
+            Some(
+                "Use scheme://host[:port] without a path, e.g. https://app.example.com".to_string(),
+            ),
+        )
+    }
+
+    pub fn cors_any_origin_with_credentials(&mut self, origin: &Origin) {
+        self.warning(
+            "cors device allows credentials from any origin".to_string(),
+            origin,
+            Some("Any website could make requests with your users' cookies; list the allowed origins".to_string()),
+        )
+    }
+}
+
+/// Builtin Idempotency Device Spec Validation
+impl ValidationReport {
+    pub fn idempotency_device_already_defined(&mut self, origin: &Origin) {
+        self.error(
+            "idempotency device already defined".to_string(),
+            origin,
+            None,
+        )
+    }
+
+    pub fn idempotency_methods_is_empty(&mut self, origin: &Origin) {
+        self.error(
+            "idempotency device requires a key on no methods".to_string(),
+            origin,
+            Some("List the methods that must carry a key, e.g. [\"POST\"]".to_string()),
+        )
+    }
+
+    pub fn idempotency_invalid_path(&mut self, path: &str, origin: &Origin) {
+        self.error(
+            format!("idempotency path '{path}' must start with '/'"),
+            origin,
+            None,
+        )
+    }
+}
+
+/// Builtin Response Headers Device Spec Validation
+impl ValidationReport {
+    pub fn response_headers_device_already_defined(&mut self, origin: &Origin) {
+        self.error(
+            "response headers device already defined".to_string(),
+            origin,
+            None,
+        )
+    }
+
+    pub fn response_headers_device_has_no_headers(&mut self, origin: &Origin) {
+        self.warning(
+            "response headers device has no headers".to_string(),
+            origin,
+            Some("The device will not add any header".to_string()),
+        )
+    }
+
+    pub fn response_header_duplicated(&mut self, name: &str, origin: &Origin) {
+        self.error(
+            format!("response header '{name}' is listed more than once"),
+            origin,
+            None,
+        )
+    }
+
+    pub fn response_header_invalid_value(&mut self, name: &str, origin: &Origin) {
+        self.error(
+            format!("response header '{name}' has an invalid value"),
+            origin,
+            None,
+        )
+    }
+
+    pub fn response_header_unknown_placeholder(
+        &mut self,
+        name: &str,
+        placeholder: &str,
+        origin: &Origin,
+    ) {
+        self.error(
+            format!("response header '{name}' uses unknown placeholder {placeholder}"),
+            origin,
+            Some("Use {request_id}, {upstream}, or {route}".to_string()),
+        )
+    }
+}
+
+/// Builtin Status Remap Device Spec Validation
+impl ValidationReport {
+    pub fn status_remap_device_already_defined(&mut self, origin: &Origin) {
+        self.error(
+            "status remap device already defined".to_string(),
+            origin,
+            None,
+        )
+    }
+
+    pub fn status_remap_device_has_no_remaps(&mut self, origin: &Origin) {
+        self.warning(
+            "status remap device has no remaps".to_string(),
+            origin,
+            Some("The device will not change any response".to_string()),
+        )
+    }
+
+    pub fn status_remap_duplicated(&mut self, from: u16, origin: &Origin) {
+        self.error(
+            format!("status {from} is remapped more than once"),
+            origin,
+            None,
+        )
+    }
+
+    pub fn status_remap_is_noop(&mut self, from: u16, origin: &Origin) {
+        self.warning(
+            format!("status {from} is remapped to itself"),
+            origin,
+            Some("Remove the remap, or set a body to only replace the response body".to_string()),
+        )
+    }
+}
+
+/// Builtin Error Page Device Spec Validation
+impl ValidationReport {
+    pub fn error_page_device_already_defined(&mut self, origin: &Origin) {
+        self.error(
+            "error page device already defined".to_string(),
+            origin,
+            None,
+        )
+    }
+
+    pub fn error_page_device_has_no_body(&mut self, origin: &Origin) {
+        self.error(
+            "error page device has no body or body_file".to_string(),
+            origin,
+            Some("Set the error page inline with body, or from a file with body_file".to_string()),
+        )
+    }
+
+    pub fn error_page_device_has_two_bodies(&mut self, origin: &Origin) {
+        self.error(
+            "error page device sets both body and body_file".to_string(),
+            origin,
+            Some("Keep only one of them".to_string()),
+        )
+    }
+
+    pub fn error_page_body_file_is_not_a_file(&mut self, path: Display, origin: &Origin) {
+        self.error(
+            format!("error page body_file is not a file: {}", path),
+            origin,
+            None,
+        )
+    }
+
+    pub fn error_page_device_has_no_statuses(&mut self, origin: &Origin) {
+        self.warning(
+            "error page device has no statuses".to_string(),
+            origin,
+            Some("The device will not replace any response".to_string()),
+        )
+    }
+
+    pub fn error_page_status_is_invalid(&mut self, status: &str, origin: &Origin) {
+        self.error(
+            format!("error page status '{status}' is not an error status"),
+            origin,
+            Some("Use a status from 400 to 599, or a class such as 5xx".to_string()),
+        )
+    }
+
+    pub fn error_page_content_type_is_invalid(&mut self, content_type: &str, origin: &Origin) {
+        self.error(
+            format!("error page content_type '{content_type}' is not a valid header value"),
+            origin,
+            None,
+        )
+    }
+}
+
+/// Builtin Deprecation Device Spec Validation
+impl ValidationReport {
+    pub fn deprecation_device_already_defined(&mut self, origin: &Origin) {
+        self.error(
+            "deprecation device already defined".to_string(),
+            origin,
+            None,
+        )
+    }
+
+    pub fn deprecation_device_has_no_deprecations(&mut self, origin: &Origin) {
+        self.warning(
+            "deprecation device has no deprecations".to_string(),
+            origin,
+            Some("The device will not mark any route as deprecated".to_string()),
+        )
+    }
+
+    pub fn deprecation_invalid_path(&mut self, path: &str, origin: &Origin) {
+        self.error(
+            format!("deprecation path '{path}' must start with '/'"),
+            origin,
+            None,
+        )
+    }
+
+    pub fn deprecation_path_duplicated(&mut self, path: &str, origin: &Origin) {
+        self.error(
+            format!("deprecation path '{path}' is listed more than once"),
+            origin,
+            None,
+        )
+    }
+
+    pub fn deprecation_invalid_timestamp(&mut self, path: &str, timestamp: &str, origin: &Origin) {
+        self.error(
+            format!("deprecation of '{path}' has an invalid timestamp: {timestamp}"),
+            origin,
+            Some("Use an RFC 3339 timestamp, e.g. 2026-01-01T00:00:00Z".to_string()),
+        )
+    }
+
+    pub fn deprecation_sunset_precedes_deprecation(&mut self, path: &str, origin: &Origin) {
+        self.error(
+            format!("deprecation of '{path}' has a sunset_at before its deprecated_at"),
+            origin,
+            None,
+        )
+    }
+
+    pub fn deprecation_invalid_successor(&mut self, path: &str, successor: &str, origin: &Origin) {
+        self.error(
+            format!("deprecation of '{path}' has an invalid successor URL: {successor}"),
+            origin,
+            None,
+        )
+    }
+}
+
+/// Builtin Lua Device Spec Validation
+impl ValidationReport {
+    pub fn lua_device_already_defined(&mut self, origin: &Origin) {
+        self.error("lua device already defined".to_string(), origin, None)
+    }
+
+    pub fn lua_script_is_not_a_file(&mut self, path: Display, origin: &Origin) {
+        self.error(
+            format!("lua device script is not a file: {}", path),
+            origin,
+            None,
+        )
+    }
+}
+
+/// Builtin Identity Device Spec Validation
+impl ValidationReport {
+    pub fn geoip_enabled_with_no_dbs_specified(&mut self, origin: &Origin) {
+        self.warning(
+            "geoip enabled with no dbs specified".to_string(),
+            origin,
+            Some("Set the server's geoip databases, or the device's own".to_string()),
+        )
+    }
+
+    pub fn geoip_db_path_is_empty(&mut self, path: Display, origin: &Origin) {
+        self.error(format!("geoip db path is empty: {}", path), origin, None)
+    }
+    pub fn geoip_db_path_does_not_exist(&mut self, path: Display, origin: &Origin) {
+        self.error(
+            format!("geoip db path does not exist: {}", path),
+            origin,
+            None,
+        )
+    }
+    pub fn geoip_db_is_not_a_file(&mut self, path: Display, origin: &Origin) {
+        self.error(
+            format!("geoip db path is not a file: {}", path),
+            origin,
+            None,
+        )
+    }
+
+    pub fn invalid_trusted_proxy(&mut self, proxy: &str, origin: &Origin) {
+        self.error(format!("invalid trusted proxy: {}", proxy), origin, None)
+    }
+
+    pub fn trusted_proxies_cannot_trust_all_networks(&mut self, origin: &Origin) {
+        self.error(
+            "trusted_proxies must not contain a catch-all network (0.0.0.0/0 or ::/0)".to_string(),
+            origin,
+            None,
+        )
+    }
+
+    pub fn trusted_proxies_contains_a_public_ip_range_warning(
+        &mut self,
+        network: ipnet::IpNet,
+        origin: &Origin,
+    ) {
+        self.warning(
+            format!("trusted_proxies should NOT contain a public IP range: {network}"),
+            origin,
+            None,
+        )
+    }
+
+    pub fn client_ip_header_without_trusted_proxies(&mut self, origin: &Origin) {
+        self.warning(
+            "client_ip_header is set but trusted_proxies is empty".to_string(),
+            origin,
+            Some("client_ip_header is only honored when the peer is a trusted proxy".to_string()),
+        )
+    }
+
+    pub fn ua_engine_is_empty(&mut self, origin: &Origin) {
+        self.error("ua_engine is empty".to_string(), origin, None)
+    }
+
+    pub fn identity_device_already_defined(&mut self, origin: &Origin) {
+        self.error("identity device already defined".to_string(), origin, None)
+    }
+
+    pub fn request_filter_device_already_defined(&mut self, origin: &Origin) {
+        self.error(
+            "request filter device already defined".to_string(),
+            origin,
+            None,
+        )
+    }
+
+    pub fn structured_logging_device_already_defined(&mut self, origin: &Origin) {
+        self.error(
+            "structured logging device already defined".to_string(),
+            origin,
+            None,
+        )
+    }
+
+    pub fn invalid_http_method(&mut self, method: &str, origin: &Origin) {
+        self.error(format!("invalid HTTP method: {}", method), origin, None)
+    }
+
+    pub fn invalid_http_header_name(&mut self, header: &str, origin: &Origin) {
+        self.error(
+            format!("invalid HTTP header name: {}", header),
+            origin,
+            None,
+        )
+    }
+
+    pub fn invalid_content_type_pattern(&mut self, pattern: &str, origin: &Origin) {
+        self.error(
+            format!("invalid content type pattern: {}", pattern),
+            origin,
+            Some("Use type/subtype or type/*, e.g. application/zip or image/*.".to_string()),
+        )
+    }
+
+    pub fn warn_max_suspicious_bytes_large_than_max_body_bytes(&mut self, origin: &Origin) {
+        self.warning(
+            "max_suspicious_body_bytes should not be larger than max_body_bytes".to_string(),
+            origin,
+            Some("max_suspicious_body_bytes applies to functions that can technically have a body, but should be treated suspiciously (and thus have a lower max size than a regular body)".to_string()),
+        )
+    }
+}
+
+/// Lints
+impl ValidationReport {
+    pub fn lint_service_missing_timeouts(&mut self, origin: &Origin) {
+        self.warning(
+            "service relies on default upstream timeouts".to_string(),
+            origin,
+            Some("Set connection.connect_timeout_milliseconds and connection.read_timeout_milliseconds to bound how long a slow upstream can hold a request".to_string()),
+        )
+    }
+
+    pub fn lint_service_without_health_check(&mut self, upstreams: usize, origin: &Origin) {
+        self.warning(
+            format!("service has {upstreams} upstreams but no health check"),
+            origin,
+            Some("Enable health_check so traffic stops going to a failing upstream".to_string()),
+        )
+    }
+
+    pub fn lint_tls_without_hsts(&mut self, origin: &Origin) {
+        self.warning(
+            "tls listener does not send Strict-Transport-Security".to_string(),
+            origin,
+            Some("Add a Strict-Transport-Security header with the response_headers device, e.g. max-age=31536000".to_string()),
+        )
+    }
+
+    pub fn lint_cors_any_origin_with_credentials(&mut self, origin: &Origin) {
+        self.warning(
+            "response headers allow any CORS origin with credentials".to_string(),
+            origin,
+            Some("Browsers reject Access-Control-Allow-Origin: * with credentials; list the allowed origin explicitly".to_string()),
+        )
+    }
+
+    pub fn lint_admin_bound_to_all_interfaces(&mut self, origin: &Origin) {
+        self.warning(
+            "admin listener is bound to all interfaces".to_string(),
+            origin,
+            Some("Bind the admin API to loopback or a private address".to_string()),
         )
     }
 }
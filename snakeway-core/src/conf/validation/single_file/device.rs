@@ -1,35 +1,99 @@
-use crate::conf::types::{DeviceSpec, Origin};
+use crate::conf::types::{
+    DeviceSpec, IngressSpec, JWT_HMAC_ALGORITHMS, JWT_JWKS_ALGORITHMS, MissingHeaderActionSpec,
+    Origin, QueryOperationKindSpec, RESPONSE_HEADER_PLACEHOLDERS, RateLimitKeySpec, ServerSpec,
+    WasmDeviceSpec, error_page_statuses,
+};
 use crate::conf::validation::ValidationReport;
 use crate::conf::validation::validator::{
-    REQUEST_FILTER_DENY_STATUS, validate_http_header_name, validate_http_method, validate_range,
+    BODY_DIGEST_MAX_BODY_BYTES, CORS_MAX_AGE_SECONDS, EXPERIMENT_VARIANT_WEIGHT,
+    IDEMPOTENCY_MAX_BODY_BYTES, IDEMPOTENCY_TTL_SECONDS, IDEMPOTENCY_WAIT_TIMEOUT_MS,
+    IDENTITY_UA_CACHE_CAPACITY, IP_REPUTATION_BLOCK_STATUS, IP_REPUTATION_CACHE_TTL_MS,
+    IP_REPUTATION_TIMEOUT_MS, JWT_AUTH_JWKS_REFRESH_SECONDS, JWT_AUTH_LEEWAY_SECONDS,
+    JWT_AUTH_TIMEOUT_MS, LUA_INSTRUCTION_LIMIT, LUA_MAX_BODY_BYTES, LUA_MEMORY_LIMIT_BYTES,
+    OPENAPI_MAX_BODY_BYTES, QUOTA_CACHE_TTL_MS, QUOTA_TIMEOUT_MS, RATE_LIMIT_BURST,
+    RATE_LIMIT_REQUESTS_PER_SECOND, REQUEST_FILTER_DENY_STATUS, STATUS_REMAP_FROM, STATUS_REMAP_TO,
+    WASM_DEVICE_FUEL, validate_content_type_pattern, validate_http_header_name,
+    validate_http_method, validate_range,
 };
+use crate::device::plugin::{PluginBundle, is_plugin_bundle};
+use chrono::DateTime;
+use http::uri::Authority;
+use http::{HeaderName, HeaderValue, Uri};
 use ipnet::IpNet;
 use nix::NixPath;
+use std::collections::HashSet;
 use std::net::IpAddr;
 use std::path::Path;
 
+/// Validate that devices attached to listeners exist and are uniquely named,
+/// and that the routes devices are scoped to are path prefixes.
+pub fn validate_device_attachments(
+    ingresses: &[IngressSpec],
+    devices: &[DeviceSpec],
+    report: &mut ValidationReport,
+) {
+    let mut names = HashSet::new();
+    for device in devices {
+        let name = device.name();
+        // Builtin devices are already checked for duplicates in `validate_devices`.
+        if !names.insert(name.clone()) && matches!(device, DeviceSpec::Wasm(_)) {
+            report.duplicate_device_name(&name, device.origin());
+        }
+
+        let mut routes = HashSet::new();
+        for route in device.routes() {
+            if !route.starts_with('/') || route.contains('*') {
+                report.invalid_device_route(&name, route, device.origin());
+            } else if !routes.insert(route) {
+                report.duplicate_device_route(&name, route, device.origin());
+            }
+        }
+    }
+
+    for ingress in ingresses {
+        let attachments = ingress
+            .bind
+            .iter()
+            .map(|b| (&b.devices, &b.origin))
+            .chain(ingress.bind_admin.iter().map(|b| (&b.devices, &b.origin)));
+
+        for (attached, origin) in attachments {
+            for name in attached {
+                if !names.contains(name) {
+                    report.unknown_device_attached_to_listener(name, origin);
+                }
+            }
+        }
+    }
+}
+
 pub fn validate_devices(devices: &[DeviceSpec], report: &mut ValidationReport) {
     let mut identity_seen = false;
     let mut request_filter_seen = false;
     let mut structured_logging_seen = false;
+    let mut openapi_seen = false;
+    let mut jwt_auth_seen = false;
+    let mut quota_seen = false;
+    let mut body_digest_seen = false;
+    let mut upstream_group_seen = false;
+    let mut required_headers_seen = false;
+    let mut status_remap_seen = false;
+    let mut response_headers_seen = false;
+    let mut ip_reputation_seen = false;
+    let mut rate_limit_seen = false;
+    let mut experiment_seen = false;
+    let mut header_rewrite_seen = false;
+    let mut query_rewrite_seen = false;
+    let mut error_page_seen = false;
+    let mut cors_seen = false;
+    let mut idempotency_seen = false;
+    let mut deprecation_seen = false;
+    let mut lua_seen = false;
 
     for device in devices {
         match device {
-            DeviceSpec::Wasm(cfg) => {
-                if !cfg.enable {
-                    return;
-                }
-
-                if cfg.path.is_empty() {
-                    report.wasm_device_path_is_empty(cfg.path.display(), device.origin());
-                }
-                if !cfg.path.exists() {
-                    report.wasm_device_path_does_not_exist(cfg.path.display(), device.origin());
-                }
-                if !cfg.path.is_file() {
-                    report.wasm_device_path_is_not_a_file(cfg.path.display(), device.origin());
-                }
-            }
+            // Checked by `validate_wasm_devices`, which knows whether they are strict.
+            DeviceSpec::Wasm(_) => {}
             DeviceSpec::Identity(cfg) => {
                 if identity_seen {
                     report.identity_device_already_defined(device.origin());
@@ -42,14 +106,23 @@ pub fn validate_devices(devices: &[DeviceSpec], report: &mut ValidationReport) {
 
                 validate_trusted_proxies(&cfg.trusted_proxies, report, device.origin());
 
-                if cfg.enable_geoip {
-                    if cfg.geoip_city_db.is_none()
-                        && cfg.geoip_isp_db.is_none()
-                        && cfg.geoip_connection_type_db.is_none()
-                    {
-                        report.geoip_enabled_with_no_dbs_specified(device.origin());
+                if let Some(header) = &cfg.client_ip_header {
+                    if HeaderName::from_bytes(header.as_bytes()).is_err() {
+                        report.invalid_http_header_name(header, device.origin());
+                    }
+                    if cfg.trusted_proxies.is_empty() {
+                        report.client_ip_header_without_trusted_proxies(device.origin());
                     }
+                }
+
+                validate_range(
+                    cfg.ua_cache_capacity,
+                    &IDENTITY_UA_CACHE_CAPACITY,
+                    report,
+                    device.origin(),
+                );
 
+                if cfg.enable_geoip {
                     if let Some(path) = cfg.geoip_city_db.as_ref() {
                         validate_geoip_db_file(path, report, device.origin());
                     }
@@ -115,12 +188,832 @@ pub fn validate_devices(devices: &[DeviceSpec], report: &mut ValidationReport) {
                 if !cfg.enable {
                     return;
                 }
+
+                for capture in &cfg.route_headers {
+                    for header in capture
+                        .request_headers
+                        .iter()
+                        .chain(&capture.response_headers)
+                    {
+                        if HeaderName::from_bytes(header.as_bytes()).is_err() {
+                            report.invalid_http_header_name(header, device.origin());
+                        }
+                    }
+                }
+            }
+            DeviceSpec::OpenApi(cfg) => {
+                if openapi_seen {
+                    report.openapi_device_already_defined(device.origin());
+                }
+                openapi_seen = true;
+
+                if !cfg.enable {
+                    continue;
+                }
+
+                if !cfg.spec_path.is_file() {
+                    report
+                        .openapi_spec_path_is_not_a_file(cfg.spec_path.display(), device.origin());
+                }
+
+                validate_range(
+                    cfg.max_body_bytes,
+                    &OPENAPI_MAX_BODY_BYTES,
+                    report,
+                    device.origin(),
+                );
+            }
+            DeviceSpec::JwtAuth(cfg) => {
+                if jwt_auth_seen {
+                    report.jwt_auth_device_already_defined(device.origin());
+                }
+                jwt_auth_seen = true;
+
+                if !cfg.enable {
+                    continue;
+                }
+
+                let allowed = match (&cfg.hmac_secret, &cfg.jwks_url) {
+                    (Some(secret), None) => {
+                        if secret.is_empty() {
+                            report.empty_jwt_auth_hmac_secret(device.origin());
+                        }
+                        JWT_HMAC_ALGORITHMS
+                    }
+                    (None, Some(url)) => {
+                        let is_valid_url = url.parse::<Uri>().is_ok_and(|uri| {
+                            matches!(uri.scheme_str(), Some("http") | Some("https"))
+                                && uri.host().is_some()
+                        });
+                        if !is_valid_url {
+                            report.invalid_jwt_auth_jwks_url(url, device.origin());
+                        }
+                        JWT_JWKS_ALGORITHMS
+                    }
+                    _ => {
+                        report.jwt_auth_key_source_required(device.origin());
+                        continue;
+                    }
+                };
+
+                for algorithm in &cfg.algorithms {
+                    if !allowed.contains(&algorithm.as_str()) {
+                        report.invalid_jwt_auth_algorithm(algorithm, allowed, device.origin());
+                    }
+                }
+
+                validate_range(
+                    cfg.leeway_seconds,
+                    &JWT_AUTH_LEEWAY_SECONDS,
+                    report,
+                    device.origin(),
+                );
+                validate_range(
+                    cfg.jwks_refresh_seconds,
+                    &JWT_AUTH_JWKS_REFRESH_SECONDS,
+                    report,
+                    device.origin(),
+                );
+                validate_range(
+                    cfg.timeout_milliseconds,
+                    &JWT_AUTH_TIMEOUT_MS,
+                    report,
+                    device.origin(),
+                );
+            }
+            DeviceSpec::Quota(cfg) => {
+                if quota_seen {
+                    report.quota_device_already_defined(device.origin());
+                }
+                quota_seen = true;
+
+                if !cfg.enable {
+                    continue;
+                }
+
+                let is_valid_url = cfg.quota_url.parse::<Uri>().is_ok_and(|uri| {
+                    matches!(uri.scheme_str(), Some("http") | Some("https")) && uri.host().is_some()
+                });
+                if !is_valid_url {
+                    report.invalid_quota_url(&cfg.quota_url, device.origin());
+                }
+
+                if HeaderName::from_bytes(cfg.api_key_header.as_bytes()).is_err() {
+                    report.invalid_http_header_name(&cfg.api_key_header, device.origin());
+                }
+
+                validate_range(
+                    cfg.timeout_milliseconds,
+                    &QUOTA_TIMEOUT_MS,
+                    report,
+                    device.origin(),
+                );
+                validate_range(
+                    cfg.cache_ttl_milliseconds,
+                    &QUOTA_CACHE_TTL_MS,
+                    report,
+                    device.origin(),
+                );
+            }
+            DeviceSpec::BodyDigest(cfg) => {
+                if body_digest_seen {
+                    report.body_digest_device_already_defined(device.origin());
+                }
+                body_digest_seen = true;
+
+                if !cfg.enable {
+                    continue;
+                }
+
+                validate_range(
+                    cfg.max_body_bytes,
+                    &BODY_DIGEST_MAX_BODY_BYTES,
+                    report,
+                    device.origin(),
+                );
+            }
+            DeviceSpec::UpstreamGroup(cfg) => {
+                if upstream_group_seen {
+                    report.upstream_group_device_already_defined(device.origin());
+                }
+                upstream_group_seen = true;
+
+                if !cfg.enable {
+                    continue;
+                }
+
+                if HeaderName::from_bytes(cfg.header.as_bytes()).is_err() {
+                    report.invalid_http_header_name(&cfg.header, device.origin());
+                }
+
+                if cfg.groups.is_empty() {
+                    report.upstream_group_device_has_no_groups(device.origin());
+                }
+
+                for (value, group) in &cfg.groups {
+                    if group.trim().is_empty() {
+                        report.upstream_group_device_group_is_empty(value, device.origin());
+                    }
+                }
+            }
+            DeviceSpec::RequiredHeaders(cfg) => {
+                if required_headers_seen {
+                    report.required_headers_device_already_defined(device.origin());
+                }
+                required_headers_seen = true;
+
+                if !cfg.enable {
+                    continue;
+                }
+
+                if cfg.headers.is_empty() {
+                    report.required_headers_device_has_no_headers(device.origin());
+                }
+
+                let mut seen_names = HashSet::new();
+                for header in &cfg.headers {
+                    if HeaderName::from_bytes(header.name.as_bytes()).is_err() {
+                        report.invalid_http_header_name(&header.name, device.origin());
+                    } else if !seen_names.insert(header.name.to_ascii_lowercase()) {
+                        report.required_header_duplicated(&header.name, device.origin());
+                    }
+
+                    match (header.on_missing, &header.value) {
+                        (MissingHeaderActionSpec::Populate, None) => {
+                            report.required_header_populate_without_value(
+                                &header.name,
+                                device.origin(),
+                            );
+                        }
+                        (MissingHeaderActionSpec::Populate, Some(value)) => {
+                            if HeaderValue::from_str(value).is_err() {
+                                report.required_header_invalid_value(&header.name, device.origin());
+                            }
+                        }
+                        (MissingHeaderActionSpec::Reject, Some(_)) => {
+                            report.required_header_value_unused(&header.name, device.origin());
+                        }
+                        (MissingHeaderActionSpec::Reject, None) => {}
+                    }
+                }
+            }
+            DeviceSpec::StatusRemap(cfg) => {
+                if status_remap_seen {
+                    report.status_remap_device_already_defined(device.origin());
+                }
+                status_remap_seen = true;
+
+                if !cfg.enable {
+                    continue;
+                }
+
+                if cfg.remaps.is_empty() {
+                    report.status_remap_device_has_no_remaps(device.origin());
+                }
+
+                let mut seen_from = HashSet::new();
+                for remap in &cfg.remaps {
+                    validate_range(remap.from, &STATUS_REMAP_FROM, report, device.origin());
+                    validate_range(remap.to, &STATUS_REMAP_TO, report, device.origin());
+
+                    if !seen_from.insert(remap.from) {
+                        report.status_remap_duplicated(remap.from, device.origin());
+                    }
+                    if remap.from == remap.to && remap.body.is_none() {
+                        report.status_remap_is_noop(remap.from, device.origin());
+                    }
+                }
+            }
+            DeviceSpec::ErrorPage(cfg) => {
+                if error_page_seen {
+                    report.error_page_device_already_defined(device.origin());
+                }
+                error_page_seen = true;
+
+                if !cfg.enable {
+                    continue;
+                }
+
+                match (&cfg.body, &cfg.body_file) {
+                    (None, None) => report.error_page_device_has_no_body(device.origin()),
+                    (Some(_), Some(_)) => report.error_page_device_has_two_bodies(device.origin()),
+                    (None, Some(body_file)) if !body_file.is_file() => report
+                        .error_page_body_file_is_not_a_file(body_file.display(), device.origin()),
+                    _ => {}
+                }
+
+                if cfg.statuses.is_empty() {
+                    report.error_page_device_has_no_statuses(device.origin());
+                }
+                for status in &cfg.statuses {
+                    if error_page_statuses(status).is_none() {
+                        report.error_page_status_is_invalid(status, device.origin());
+                    }
+                }
+
+                if HeaderValue::from_str(&cfg.content_type).is_err() {
+                    report.error_page_content_type_is_invalid(&cfg.content_type, device.origin());
+                }
+                for pattern in &cfg.pass_through_content_types {
+                    validate_content_type_pattern(pattern, report, device.origin());
+                }
+                for name in &cfg.keep_headers {
+                    if HeaderName::from_bytes(name.as_bytes()).is_err() {
+                        report.invalid_http_header_name(name, device.origin());
+                    }
+                }
+            }
+            DeviceSpec::IpReputation(cfg) => {
+                if ip_reputation_seen {
+                    report.ip_reputation_device_already_defined(device.origin());
+                }
+                ip_reputation_seen = true;
+
+                if !cfg.enable {
+                    continue;
+                }
+
+                if cfg.list_file.is_none() && cfg.reputation_url.is_none() {
+                    report.ip_reputation_device_has_no_source(device.origin());
+                }
+
+                if let Some(list_file) = &cfg.list_file
+                    && !list_file.is_file()
+                {
+                    report.ip_reputation_list_file_is_not_a_file(
+                        list_file.display(),
+                        device.origin(),
+                    );
+                }
+
+                if let Some(url) = &cfg.reputation_url {
+                    let is_valid_url = url.parse::<Uri>().is_ok_and(|uri| {
+                        matches!(uri.scheme_str(), Some("http") | Some("https"))
+                            && uri.host().is_some()
+                    });
+                    if !is_valid_url {
+                        report.invalid_reputation_url(url, device.origin());
+                    }
+                }
+
+                if HeaderName::from_bytes(cfg.tag_header.as_bytes()).is_err() {
+                    report.invalid_http_header_name(&cfg.tag_header, device.origin());
+                }
+
+                validate_range(
+                    cfg.timeout_milliseconds,
+                    &IP_REPUTATION_TIMEOUT_MS,
+                    report,
+                    device.origin(),
+                );
+                validate_range(
+                    cfg.cache_ttl_milliseconds,
+                    &IP_REPUTATION_CACHE_TTL_MS,
+                    report,
+                    device.origin(),
+                );
+                validate_range(
+                    cfg.block_status,
+                    &IP_REPUTATION_BLOCK_STATUS,
+                    report,
+                    device.origin(),
+                );
+            }
+            DeviceSpec::RateLimit(cfg) => {
+                if rate_limit_seen {
+                    report.rate_limit_device_already_defined(device.origin());
+                }
+                rate_limit_seen = true;
+
+                if !cfg.enable {
+                    continue;
+                }
+
+                match (&cfg.key, &cfg.key_header) {
+                    (RateLimitKeySpec::Header, None) => {
+                        report.rate_limit_key_header_missing(device.origin());
+                    }
+                    (RateLimitKeySpec::Header, Some(name)) => {
+                        if HeaderName::from_bytes(name.as_bytes()).is_err() {
+                            report.invalid_http_header_name(name, device.origin());
+                        }
+                    }
+                    (_, Some(_)) => report.rate_limit_key_header_unused(device.origin()),
+                    (_, None) => {}
+                }
+
+                validate_range(
+                    cfg.requests_per_second,
+                    &RATE_LIMIT_REQUESTS_PER_SECOND,
+                    report,
+                    device.origin(),
+                );
+                if let Some(burst) = cfg.burst {
+                    validate_range(burst, &RATE_LIMIT_BURST, report, device.origin());
+                }
+            }
+            DeviceSpec::Experiment(cfg) => {
+                if experiment_seen {
+                    report.experiment_device_already_defined(device.origin());
+                }
+                experiment_seen = true;
+
+                if !cfg.enable {
+                    continue;
+                }
+
+                if cfg.experiment.trim().is_empty() {
+                    report.experiment_name_is_empty(device.origin());
+                }
+                if cfg.cookie.trim().is_empty() {
+                    report.experiment_cookie_is_empty(device.origin());
+                }
+
+                let mut seen_names = HashSet::new();
+                for variant in &cfg.variants {
+                    if variant.name.is_empty() || HeaderValue::from_str(&variant.name).is_err() {
+                        report.experiment_variant_invalid_name(&variant.name, device.origin());
+                    } else if !seen_names.insert(variant.name.as_str()) {
+                        report.experiment_variant_duplicated(&variant.name, device.origin());
+                    }
+                    validate_range(
+                        variant.weight,
+                        &EXPERIMENT_VARIANT_WEIGHT,
+                        report,
+                        device.origin(),
+                    );
+                }
+
+                if cfg.variants.iter().all(|v| v.weight == 0) {
+                    report.experiment_has_no_weighted_variants(device.origin());
+                }
+            }
+            DeviceSpec::HeaderRewrite(cfg) => {
+                if header_rewrite_seen {
+                    report.header_rewrite_device_already_defined(device.origin());
+                }
+                header_rewrite_seen = true;
+
+                if !cfg.enable {
+                    continue;
+                }
+
+                if cfg.request_set.is_empty()
+                    && cfg.request_remove.is_empty()
+                    && cfg.response_set.is_empty()
+                    && cfg.response_remove.is_empty()
+                {
+                    report.header_rewrite_device_has_no_operations(device.origin());
+                }
+
+                let phases = [
+                    ("request", &cfg.request_set, &cfg.request_remove),
+                    ("response", &cfg.response_set, &cfg.response_remove),
+                ];
+                for (phase, set, remove) in phases {
+                    let mut set_names = HashSet::new();
+                    for header in set {
+                        if HeaderName::from_bytes(header.name.as_bytes()).is_err() {
+                            report.invalid_http_header_name(&header.name, device.origin());
+                        } else if !set_names.insert(header.name.to_ascii_lowercase()) {
+                            report.header_rewrite_set_duplicated(
+                                phase,
+                                &header.name,
+                                device.origin(),
+                            );
+                        }
+                        if HeaderValue::from_str(&header.value).is_err() {
+                            report.header_rewrite_invalid_value(&header.name, device.origin());
+                        }
+                    }
+
+                    for name in remove {
+                        if HeaderName::from_bytes(name.as_bytes()).is_err() {
+                            report.invalid_http_header_name(name, device.origin());
+                        } else if set_names.contains(&name.to_ascii_lowercase()) {
+                            report.header_rewrite_set_and_removed(phase, name, device.origin());
+                        }
+                    }
+                }
+            }
+            DeviceSpec::QueryRewrite(cfg) => {
+                if query_rewrite_seen {
+                    report.query_rewrite_device_already_defined(device.origin());
+                }
+                query_rewrite_seen = true;
+
+                if !cfg.enable {
+                    continue;
+                }
+
+                if cfg.operations.is_empty() {
+                    report.query_rewrite_device_has_no_operations(device.origin());
+                }
+
+                for (idx, operation) in cfg.operations.iter().enumerate() {
+                    if operation.name.is_empty() {
+                        report.query_rewrite_operation_name_is_empty(idx, device.origin());
+                    }
+
+                    let (needs_value, needs_to) = match operation.op {
+                        QueryOperationKindSpec::AddIfMissing | QueryOperationKindSpec::Set => {
+                            (true, false)
+                        }
+                        QueryOperationKindSpec::Remove => (false, false),
+                        QueryOperationKindSpec::Rename => (false, true),
+                    };
+
+                    match (&operation.value, needs_value) {
+                        (None, true) => report.query_rewrite_operation_field_missing(
+                            idx,
+                            "value",
+                            device.origin(),
+                        ),
+                        (Some(_), false) => report.query_rewrite_operation_field_ignored(
+                            idx,
+                            "value",
+                            device.origin(),
+                        ),
+                        _ => {}
+                    }
+
+                    match (operation.to.as_deref(), needs_to) {
+                        (None | Some(""), true) => {
+                            report.query_rewrite_operation_field_missing(idx, "to", device.origin())
+                        }
+                        (Some(_), false) => {
+                            report.query_rewrite_operation_field_ignored(idx, "to", device.origin())
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            DeviceSpec::Cors(cfg) => {
+                if cors_seen {
+                    report.cors_device_already_defined(device.origin());
+                }
+                cors_seen = true;
+
+                if !cfg.enable {
+                    continue;
+                }
+
+                if cfg.allow_origins.is_empty() {
+                    report.cors_allow_origins_is_empty(device.origin());
+                }
+                for value in &cfg.allow_origins {
+                    if value != "*" && !is_cors_origin(value) {
+                        report.cors_invalid_origin(value, device.origin());
+                    }
+                }
+                if cfg.allow_credentials && cfg.allow_origins.iter().any(|o| o == "*") {
+                    report.cors_any_origin_with_credentials(device.origin());
+                }
+
+                for method in &cfg.allow_methods {
+                    validate_http_method(method, report, device.origin());
+                }
+                let headers = cfg
+                    .allow_headers
+                    .iter()
+                    .filter(|h| h.as_str() != "*")
+                    .chain(&cfg.expose_headers);
+                for header in headers {
+                    if HeaderName::from_bytes(header.as_bytes()).is_err() {
+                        report.invalid_http_header_name(header, device.origin());
+                    }
+                }
+
+                if let Some(max_age) = cfg.max_age_seconds {
+                    validate_range(max_age, &CORS_MAX_AGE_SECONDS, report, device.origin());
+                }
+            }
+            DeviceSpec::Idempotency(cfg) => {
+                if idempotency_seen {
+                    report.idempotency_device_already_defined(device.origin());
+                }
+                idempotency_seen = true;
+
+                if !cfg.enable {
+                    continue;
+                }
+
+                if HeaderName::from_bytes(cfg.key_header.as_bytes()).is_err() {
+                    report.invalid_http_header_name(&cfg.key_header, device.origin());
+                }
+
+                if cfg.methods.is_empty() {
+                    report.idempotency_methods_is_empty(device.origin());
+                }
+                for method in &cfg.methods {
+                    validate_http_method(method, report, device.origin());
+                }
+
+                for path in &cfg.paths {
+                    if !path.starts_with('/') {
+                        report.idempotency_invalid_path(path, device.origin());
+                    }
+                }
+
+                validate_range(
+                    cfg.ttl_seconds,
+                    &IDEMPOTENCY_TTL_SECONDS,
+                    report,
+                    device.origin(),
+                );
+                validate_range(
+                    cfg.wait_timeout_milliseconds,
+                    &IDEMPOTENCY_WAIT_TIMEOUT_MS,
+                    report,
+                    device.origin(),
+                );
+                validate_range(
+                    cfg.max_body_bytes,
+                    &IDEMPOTENCY_MAX_BODY_BYTES,
+                    report,
+                    device.origin(),
+                );
+            }
+            DeviceSpec::ResponseHeaders(cfg) => {
+                if response_headers_seen {
+                    report.response_headers_device_already_defined(device.origin());
+                }
+                response_headers_seen = true;
+
+                if !cfg.enable {
+                    continue;
+                }
+
+                if cfg.headers.is_empty() {
+                    report.response_headers_device_has_no_headers(device.origin());
+                }
+
+                let mut seen_names = HashSet::new();
+                for header in &cfg.headers {
+                    if HeaderName::from_bytes(header.name.as_bytes()).is_err() {
+                        report.invalid_http_header_name(&header.name, device.origin());
+                    } else if !seen_names.insert(header.name.to_ascii_lowercase()) {
+                        report.response_header_duplicated(&header.name, device.origin());
+                    }
+
+                    if HeaderValue::from_str(&header.value).is_err() {
+                        report.response_header_invalid_value(&header.name, device.origin());
+                    }
+                    for placeholder in unknown_placeholders(&header.value) {
+                        report.response_header_unknown_placeholder(
+                            &header.name,
+                            placeholder,
+                            device.origin(),
+                        );
+                    }
+                }
+            }
+            DeviceSpec::Deprecation(cfg) => {
+                if deprecation_seen {
+                    report.deprecation_device_already_defined(device.origin());
+                }
+                deprecation_seen = true;
+
+                if !cfg.enable {
+                    continue;
+                }
+
+                if cfg.deprecations.is_empty() {
+                    report.deprecation_device_has_no_deprecations(device.origin());
+                }
+
+                let mut seen_paths = HashSet::new();
+                for deprecation in &cfg.deprecations {
+                    let path = &deprecation.path;
+                    if !path.starts_with('/') {
+                        report.deprecation_invalid_path(path, device.origin());
+                    } else if !seen_paths.insert(path.trim_end_matches('/')) {
+                        report.deprecation_path_duplicated(path, device.origin());
+                    }
+
+                    let deprecated_at = DateTime::parse_from_rfc3339(&deprecation.deprecated_at);
+                    if deprecated_at.is_err() {
+                        report.deprecation_invalid_timestamp(
+                            path,
+                            &deprecation.deprecated_at,
+                            device.origin(),
+                        );
+                    }
+
+                    if let Some(sunset_at) = &deprecation.sunset_at {
+                        match DateTime::parse_from_rfc3339(sunset_at) {
+                            Ok(sunset) => {
+                                if deprecated_at.is_ok_and(|deprecated| sunset < deprecated) {
+                                    report.deprecation_sunset_precedes_deprecation(
+                                        path,
+                                        device.origin(),
+                                    );
+                                }
+                            }
+                            Err(_) => {
+                                report.deprecation_invalid_timestamp(
+                                    path,
+                                    sunset_at,
+                                    device.origin(),
+                                );
+                            }
+                        }
+                    }
+
+                    if let Some(successor) = &deprecation.successor
+                        && (successor.parse::<Uri>().is_err()
+                            || HeaderValue::from_str(successor).is_err())
+                    {
+                        report.deprecation_invalid_successor(path, successor, device.origin());
+                    }
+                }
             }
+            DeviceSpec::Lua(cfg) => {
+                if lua_seen {
+                    report.lua_device_already_defined(device.origin());
+                }
+                lua_seen = true;
+
+                if !cfg.enable {
+                    continue;
+                }
+
+                if !cfg.path.is_file() {
+                    report.lua_script_is_not_a_file(cfg.path.display(), device.origin());
+                }
+
+                validate_range(
+                    cfg.instruction_limit,
+                    &LUA_INSTRUCTION_LIMIT,
+                    report,
+                    device.origin(),
+                );
+                validate_range(
+                    cfg.memory_limit_bytes,
+                    &LUA_MEMORY_LIMIT_BYTES,
+                    report,
+                    device.origin(),
+                );
+                validate_range(
+                    cfg.max_body_bytes,
+                    &LUA_MAX_BODY_BYTES,
+                    report,
+                    device.origin(),
+                );
+            }
+        };
+    }
+}
+
+/// Validate WASM devices. Missing modules are errors when `strict`, and warnings otherwise,
+/// since the device is then disabled at startup instead.
+pub fn validate_wasm_devices(devices: &[DeviceSpec], strict: bool, report: &mut ValidationReport) {
+    for device in devices {
+        let DeviceSpec::Wasm(cfg) = device else {
+            continue;
         };
+        if !cfg.enable {
+            continue;
+        }
+
+        if cfg.path.is_empty() {
+            report.wasm_device_path_is_empty(cfg.path.display(), strict, device.origin());
+        }
+        if !cfg.path.exists() {
+            report.wasm_device_path_does_not_exist(cfg.path.display(), strict, device.origin());
+        }
+        if is_plugin_bundle(&cfg.path) {
+            validate_plugin_bundle(cfg, report, device.origin());
+        } else if !cfg.path.is_file() {
+            report.wasm_device_path_is_not_a_file(cfg.path.display(), strict, device.origin());
+        }
+
+        validate_range(cfg.fuel, &WASM_DEVICE_FUEL, report, device.origin());
+    }
+}
+
+/// Check a plugin bundle against this host, and the device's config against the plugin's schema.
+fn validate_plugin_bundle(cfg: &WasmDeviceSpec, report: &mut ValidationReport, origin: &Origin) {
+    let bundle = match PluginBundle::open(&cfg.path) {
+        Ok(bundle) => bundle,
+        Err(e) => {
+            report.wasm_plugin_is_invalid(cfg.path.display(), &e, origin);
+            return;
+        }
+    };
+    let manifest = &bundle.manifest;
+
+    if let Err(e) = manifest.check_host() {
+        report.wasm_plugin_is_invalid(cfg.path.display(), &e, origin);
+    }
+
+    let config = manifest.config_with_defaults(cfg.config.as_ref());
+    match manifest.config_violations(config.as_ref()) {
+        Ok(violations) => {
+            for violation in violations {
+                report.wasm_plugin_config_is_invalid(&manifest.name, &violation, origin);
+            }
+        }
+        Err(e) => report.wasm_plugin_is_invalid(cfg.path.display(), &e, origin),
+    }
+}
+
+/// `{...}` placeholders in a response header value that the device does not fill in.
+fn unknown_placeholders(value: &str) -> Vec<&str> {
+    let mut unknown = Vec::new();
+    let mut rest = value;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let placeholder = &rest[start..=start + len];
+        if !RESPONSE_HEADER_PLACEHOLDERS.contains(&placeholder) {
+            unknown.push(placeholder);
+        }
+        rest = &rest[start + len + 1..];
+    }
+    unknown
+}
+
+/// An origin as browsers send it: `scheme://host[:port]`, with no path, userinfo, or trailing slash.
+fn is_cors_origin(value: &str) -> bool {
+    let Some((scheme, authority)) = value.split_once("://") else {
+        return false;
+    };
+    matches!(scheme, "http" | "https")
+        && !authority.is_empty()
+        && !authority.contains(['/', '?', '#', '@'])
+        && authority.parse::<Authority>().is_ok()
+}
+
+/// Warn about identity devices that enable GeoIP with no database to look clients up in,
+/// neither their own nor the server's.
+pub fn validate_geoip_databases(
+    server: &ServerSpec,
+    devices: &[DeviceSpec],
+    report: &mut ValidationReport,
+) {
+    if server.geoip.as_ref().is_some_and(|geoip| !geoip.is_empty()) {
+        return;
+    }
+    for device in devices {
+        if let DeviceSpec::Identity(cfg) = device
+            && cfg.enable
+            && cfg.enable_geoip
+            && cfg.geoip_city_db.is_none()
+            && cfg.geoip_isp_db.is_none()
+            && cfg.geoip_connection_type_db.is_none()
+        {
+            report.geoip_enabled_with_no_dbs_specified(device.origin());
+        }
     }
 }
 
-fn validate_geoip_db_file(geoip_db: &Path, report: &mut ValidationReport, origin: &Origin) -> bool {
+pub fn validate_geoip_db_file(
+    geoip_db: &Path,
+    report: &mut ValidationReport,
+    origin: &Origin,
+) -> bool {
     let mut has_error = false;
     if !geoip_db.is_file() {
         if NixPath::is_empty(geoip_db) {
@@ -139,7 +1032,12 @@ fn validate_geoip_db_file(geoip_db: &Path, report: &mut ValidationReport, origin
     !has_error
 }
 
-fn validate_trusted_proxies(proxies: &[String], report: &mut ValidationReport, origin: &Origin) {
+/// Check that trusted proxies are IPs or CIDR ranges, and that none of them trusts everyone.
+pub fn validate_trusted_proxies(
+    proxies: &[String],
+    report: &mut ValidationReport,
+    origin: &Origin,
+) {
     let mut networks = Vec::new();
     for proxy in proxies {
         if let Ok(net) = proxy.parse::<IpNet>() {
@@ -1,14 +1,25 @@
+use crate::conf::secrets::SecretResolver;
 use crate::conf::types::{
-    BindInterfaceSpec, BindSpec, HostSpec, IngressSpec, Origin, RedirectSpec, ServiceSpec,
-    StaticFilesSpec,
+    BindInterfaceSpec, BindSpec, HostSpec, IngressSpec, LoadBalancingStrategySpec, Origin,
+    RedirectSpec, RequireTlsSpec, ServerSpec, ServiceSpec, StaticFilesSpec,
+    UPSTREAM_SNI_HOST_PLACEHOLDER, UpstreamConnectionSpec,
 };
 use crate::conf::validation::ValidationReport;
 use crate::conf::validation::validator::{
-    CB_FAILURE_THRESHOLD, CB_HALF_OPEN_MAX_REQUESTS, CB_OPEN_DURATION_MS, CB_SUCCESS_THRESHOLD,
-    REDIRECT_RESPONSE_CODE, is_valid_hostname, is_valid_port, validate_range,
+    ACTIVE_HC_FAILURE_THRESHOLD, ACTIVE_HC_INTERVAL_MS, ACTIVE_HC_SUCCESS_THRESHOLD,
+    ACTIVE_HC_TIMEOUT_MS, CB_FAILURE_THRESHOLD, CB_HALF_OPEN_MAX_REQUESTS, CB_MAX_OPEN_DURATION_MS,
+    CB_OPEN_BACKOFF_MULTIPLIER, CB_OPEN_DURATION_MS, CB_SUCCESS_THRESHOLD, KETAMA_REPLICAS,
+    OD_BASE_EJECTION_TIME_MS, OD_CONSECUTIVE_5XX, OD_MAX_EJECTION_TIME_MS, REDIRECT_RESPONSE_CODE,
+    RESPONSE_RATE_LIMIT_BURST_BYTES, RESPONSE_RATE_LIMIT_BYTES_PER_SECOND, RETRY_BUDGET_PERCENT,
+    RETRY_MAX_RETRIES, RETRY_MIN_RETRY_CONCURRENCY, RETRY_ON_STATUS, ROUTE_AFFINITY_TTL_SECONDS,
+    ROUTE_MAX_REQUEST_BODY_BYTES, ROUTE_REQUEST_TIMEOUT_MS, UPSTREAM_CONNECT_TIMEOUT_MS,
+    UPSTREAM_IDLE_TIMEOUT_SECONDS, UPSTREAM_MAX_CONNECT_RETRIES, UPSTREAM_READ_TIMEOUT_MS,
+    UPSTREAM_RESPONSE_IDLE_TIMEOUT_MS, is_valid_hostname, is_valid_port,
+    validate_content_type_pattern, validate_range,
 };
-use std::collections::{HashMap, HashSet};
-use std::path::Path;
+use http::{HeaderName, HeaderValue};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Component, Path};
 
 /// Validate listener definitions.
 ///
@@ -44,10 +55,14 @@ pub fn validate_ingresses(ingresses: &[IngressSpec], report: &mut ValidationRepo
             }
 
             if let Some(tls) = &bind.tls {
-                if !Path::new(&tls.cert).is_file() {
+                if SecretResolver::is_reference(&tls.cert) {
+                    validate_secret_reference(&tls.cert, &bind.origin, report);
+                } else if !Path::new(&tls.cert).is_file() {
                     report.missing_cert_file(&tls.cert, &bind.origin);
                 }
-                if !Path::new(&tls.key).is_file() {
+                if SecretResolver::is_reference(&tls.key) {
+                    validate_secret_reference(&tls.key, &bind.origin, report);
+                } else if !Path::new(&tls.key).is_file() {
                     report.missing_key_file(&tls.key, &bind.origin);
                 }
             }
@@ -148,6 +163,97 @@ fn validate_static_files(static_file_specs: &[StaticFilesSpec], report: &mut Val
             if route.file_dir.is_relative() {
                 report.invalid_static_dir_must_be_absolute(&route.file_dir, &route.origin);
             }
+            for pattern in &route.compression.skip_content_types {
+                validate_content_type_pattern(pattern, report, &route.origin);
+            }
+            if let Some(require_tls) = &route.require_tls {
+                validate_require_tls(require_tls, report, &route.origin);
+            }
+
+            if let Some(document) = route
+                .spa_fallback
+                .as_ref()
+                .and_then(|fallback| fallback.document.as_deref())
+            {
+                let path = Path::new(document);
+                let is_plain_relative = path.components().count() > 0
+                    && path.components().all(|c| matches!(c, Component::Normal(_)));
+                if !is_plain_relative {
+                    report.invalid_spa_fallback_document(document, &route.origin);
+                }
+            }
+        }
+    }
+}
+
+/// Validate strict host checking against the hosts declared by each listener's services.
+///
+/// A listener with no declared hosts would reject every request under `strict_host`.
+pub fn validate_strict_host(
+    server: &ServerSpec,
+    ingresses: &[IngressSpec],
+    report: &mut ValidationReport,
+) {
+    if !server.strict_host {
+        return;
+    }
+
+    for ingress in ingresses {
+        if let Some(bind) = &ingress.bind
+            && ingress.services.iter().all(|s| s.hosts.is_empty())
+        {
+            report.strict_host_listener_declares_no_hosts(&bind.origin);
+        }
+    }
+}
+
+/// Detect redirect_http_to_https listeners that send clients around in a loop.
+///
+/// Each redirect listener sends clients from its own address to its bind's address. A loop
+/// forms when a redirect points at itself, or at another redirect listener that leads back.
+pub fn validate_redirect_cycles(ingresses: &[IngressSpec], report: &mut ValidationReport) {
+    let mut redirects: BTreeMap<String, (String, &Origin)> = BTreeMap::new();
+
+    for ingress in ingresses {
+        let Some(bind) = &ingress.bind else {
+            continue;
+        };
+        let Some(redirect) = &bind.redirect_http_to_https else {
+            continue;
+        };
+        let Ok(interface) = BindInterfaceSpec::try_from(bind.interface.clone()) else {
+            continue;
+        };
+
+        let ip = interface.as_ip();
+        redirects.insert(
+            format!("{}:{}", ip, redirect.port),
+            (format!("{}:{}", ip, bind.port), &bind.origin),
+        );
+    }
+
+    let mut seen_loops = BTreeSet::new();
+
+    for start in redirects.keys() {
+        let mut chain = vec![start.clone()];
+
+        while let Some((next, _)) = redirects.get(chain.last().expect("chain is never empty")) {
+            let Some(loop_start) = chain.iter().position(|addr| addr == next) else {
+                chain.push(next.clone());
+                continue;
+            };
+
+            let mut cycle = chain.split_off(loop_start);
+
+            // The same loop is found from each of its members, so report it once.
+            let mut members = cycle.clone();
+            members.sort();
+            if seen_loops.insert(members) {
+                let origins: Vec<&Origin> = cycle.iter().map(|addr| redirects[addr].1).collect();
+                cycle.push(next.clone());
+                report.redirect_loop(&cycle, &origins);
+            }
+            break;
         }
     }
 }
@@ -162,6 +268,12 @@ pub fn validate_redirect(spec: &RedirectSpec, origin: &Origin, report: &mut Vali
 }
 
 /// Validate service definitions.
+fn validate_secret_reference(reference: &str, origin: &Origin, report: &mut ValidationReport) {
+    if SecretResolver::parse_reference(reference).is_err() {
+        report.invalid_secret_reference(reference, origin);
+    }
+}
+
 pub fn validate_services(
     maybe_bind: &Option<BindSpec>,
     services: &[ServiceSpec],
@@ -174,6 +286,13 @@ pub fn validate_services(
             report.service_has_no_upstreams(&service.origin);
         }
 
+        // Upstream groups: requests without a group go to the ungrouped default pool.
+        if service.upstreams.iter().any(|u| u.group.is_some())
+            && service.upstreams.iter().all(|u| u.group.is_some())
+        {
+            report.service_has_no_default_upstreams(&service.origin);
+        }
+
         let mut seen_sock_values = HashMap::new();
 
         // Routes
@@ -181,6 +300,102 @@ pub fn validate_services(
             if bind_uses_http2 && route.enable_websocket {
                 report.websocket_route_cannot_be_used_with_http2(&route.path, &route.origin);
             }
+
+            if let Some(limit) = &route.response_rate_limit {
+                validate_range(
+                    limit.bytes_per_second,
+                    &RESPONSE_RATE_LIMIT_BYTES_PER_SECOND,
+                    report,
+                    &route.origin,
+                );
+                if let Some(burst) = limit.burst_bytes {
+                    validate_range(
+                        burst,
+                        &RESPONSE_RATE_LIMIT_BURST_BYTES,
+                        report,
+                        &route.origin,
+                    );
+                }
+            }
+
+            if let Some(require_tls) = &route.require_tls {
+                validate_require_tls(require_tls, report, &route.origin);
+            }
+
+            if let Some(max) = route.max_request_body_bytes {
+                validate_range(max, &ROUTE_MAX_REQUEST_BODY_BYTES, report, &route.origin);
+            }
+
+            if let Some(timeout) = route.request_timeout_milliseconds {
+                validate_range(timeout, &ROUTE_REQUEST_TIMEOUT_MS, report, &route.origin);
+            }
+
+            if let Some(ttl) = route.affinity_ttl_seconds {
+                validate_range(ttl, &ROUTE_AFFINITY_TTL_SECONDS, report, &route.origin);
+                if !matches!(
+                    service.load_balancing_strategy,
+                    LoadBalancingStrategySpec::StickyHash
+                ) {
+                    report.affinity_ttl_without_sticky_strategy(&route.path, &route.origin);
+                }
+            }
+        }
+
+        // Upstream header casing
+        for header in &service.upstream_header_case {
+            if HeaderName::from_bytes(header.as_bytes()).is_err() {
+                report.invalid_http_header_name(header, &service.origin);
+            }
+        }
+
+        // Hosts
+        for host in &service.hosts {
+            if let HostSpec::Hostname(name) = host
+                && !is_valid_hostname(name)
+            {
+                report.invalid_service_host(name, &service.origin);
+            }
+        }
+
+        // Upstream SNI - the template must render to a legal hostname for any legal Host.
+        if let Some(template) = &service.upstream_sni
+            && !is_valid_hostname(&template.replace(UPSTREAM_SNI_HOST_PLACEHOLDER, "host"))
+        {
+            report.invalid_upstream_sni(template, &service.origin);
+        }
+
+        // Upstream Accept-Encoding
+        if let Some(value) = &service.upstream_accept_encoding
+            && HeaderValue::from_str(value).is_err()
+        {
+            report.invalid_upstream_accept_encoding(value, &service.origin);
+        }
+
+        // Upstream request headers
+        let mut seen_request_headers = HashSet::new();
+        for header in &service.upstream_request_headers {
+            if HeaderName::from_bytes(header.name.as_bytes()).is_err() {
+                report.invalid_http_header_name(&header.name, &service.origin);
+            } else if !seen_request_headers.insert(header.name.to_ascii_lowercase()) {
+                report.upstream_request_header_duplicated(&header.name, &service.origin);
+            }
+
+            let value = header.value.expose();
+            if SecretResolver::is_reference(value) {
+                validate_secret_reference(value, &service.origin, report);
+            } else if HeaderValue::from_str(value).is_err() {
+                report.invalid_upstream_request_header_value(&header.name, &service.origin);
+            }
+        }
+
+        // Response compression opt-outs
+        for pattern in &service.response_compression_skip_content_types {
+            validate_content_type_pattern(pattern, report, &service.origin);
+        }
+
+        // Connection defaults
+        if let Some(connection) = &service.connection {
+            validate_upstream_connection(connection, report, &service.origin);
         }
 
         // Upstreams
@@ -189,6 +404,16 @@ pub fn validate_services(
                 report.invalid_upstream_weight(&upstream.weight, &service.origin);
             }
 
+            if let Some(connection) = &upstream.connection {
+                validate_upstream_connection(connection, report, &service.origin);
+            }
+
+            if let Some(group) = &upstream.group
+                && group.trim().is_empty()
+            {
+                report.upstream_group_is_empty(&service.origin);
+            }
+
             if let (Some(sock), Some(endpoint)) = (&upstream.sock, &upstream.endpoint) {
                 report.upstream_cannot_have_both_sock_and_endpoint(
                     sock,
@@ -255,6 +480,172 @@ pub fn validate_services(
                 report,
                 &service.origin,
             );
+            if let Some(backoff) = &cb.open_backoff {
+                validate_range(
+                    backoff.multiplier,
+                    &CB_OPEN_BACKOFF_MULTIPLIER,
+                    report,
+                    &service.origin,
+                );
+                validate_range(
+                    backoff.max_open_duration_milliseconds,
+                    &CB_MAX_OPEN_DURATION_MS,
+                    report,
+                    &service.origin,
+                );
+                if cb.open_duration_milliseconds > backoff.max_open_duration_milliseconds {
+                    report.circuit_open_duration_exceeds_max(
+                        cb.open_duration_milliseconds,
+                        backoff.max_open_duration_milliseconds,
+                        &service.origin,
+                    );
+                }
+            }
         }
+
+        // Active health checks
+        if let Some(hc) = &service.health_check
+            && hc.enable
+            && let Some(active) = &hc.active
+        {
+            if !active.path.starts_with('/') {
+                report.active_health_check_invalid_path(&active.path, &service.origin);
+            }
+            if !(100..=599).contains(&active.expected_status) {
+                report.invalid_http_status_code(active.expected_status, &service.origin);
+            }
+            validate_range(
+                active.interval_milliseconds,
+                &ACTIVE_HC_INTERVAL_MS,
+                report,
+                &service.origin,
+            );
+            validate_range(
+                active.timeout_milliseconds,
+                &ACTIVE_HC_TIMEOUT_MS,
+                report,
+                &service.origin,
+            );
+            validate_range(
+                active.failure_threshold,
+                &ACTIVE_HC_FAILURE_THRESHOLD,
+                report,
+                &service.origin,
+            );
+            validate_range(
+                active.success_threshold,
+                &ACTIVE_HC_SUCCESS_THRESHOLD,
+                report,
+                &service.origin,
+            );
+            if active.timeout_milliseconds > active.interval_milliseconds {
+                report.active_health_check_timeout_exceeds_interval(
+                    active.timeout_milliseconds,
+                    active.interval_milliseconds,
+                    &service.origin,
+                );
+            }
+        }
+
+        // Outlier detection
+        if let Some(od) = &service.outlier_detection
+            && od.enable
+        {
+            validate_range(
+                od.consecutive_5xx,
+                &OD_CONSECUTIVE_5XX,
+                report,
+                &service.origin,
+            );
+            validate_range(
+                od.base_ejection_time_milliseconds,
+                &OD_BASE_EJECTION_TIME_MS,
+                report,
+                &service.origin,
+            );
+            validate_range(
+                od.max_ejection_time_milliseconds,
+                &OD_MAX_EJECTION_TIME_MS,
+                report,
+                &service.origin,
+            );
+            if od.base_ejection_time_milliseconds > od.max_ejection_time_milliseconds {
+                report.outlier_base_ejection_time_exceeds_max(
+                    od.base_ejection_time_milliseconds,
+                    od.max_ejection_time_milliseconds,
+                    &service.origin,
+                );
+            }
+        }
+
+        // Retry policy
+        if let Some(retry) = &service.retry_policy
+            && retry.enable
+        {
+            validate_range(
+                retry.max_retries,
+                &RETRY_MAX_RETRIES,
+                report,
+                &service.origin,
+            );
+            validate_range(
+                retry.budget_percent,
+                &RETRY_BUDGET_PERCENT,
+                report,
+                &service.origin,
+            );
+            validate_range(
+                retry.min_retry_concurrency,
+                &RETRY_MIN_RETRY_CONCURRENCY,
+                report,
+                &service.origin,
+            );
+            for status in &retry.retry_on_status {
+                validate_range(*status, &RETRY_ON_STATUS, report, &service.origin);
+            }
+        }
+
+        // Consistent hashing
+        if let Some(ketama) = &service.ketama {
+            validate_range(ketama.replicas, &KETAMA_REPLICAS, report, &service.origin);
+            if let Some(name) = &ketama.key_header
+                && HeaderName::from_bytes(name.as_bytes()).is_err()
+            {
+                report.invalid_http_header_name(name, &service.origin);
+            }
+        }
+    }
+}
+
+/// Plaintext requests are rejected as forbidden, or as bad requests.
+fn validate_require_tls(spec: &RequireTlsSpec, report: &mut ValidationReport, origin: &Origin) {
+    if let Some(status) = spec.reject_status
+        && status != 400
+        && status != 403
+    {
+        report.invalid_require_tls_reject_status(status, origin);
+    }
+}
+
+/// Validate upstream connection settings, at either the service or the upstream level.
+fn validate_upstream_connection(
+    connection: &UpstreamConnectionSpec,
+    report: &mut ValidationReport,
+    origin: &Origin,
+) {
+    if let Some(v) = connection.connect_timeout_milliseconds {
+        validate_range(v, &UPSTREAM_CONNECT_TIMEOUT_MS, report, origin);
+    }
+    if let Some(v) = connection.read_timeout_milliseconds {
+        validate_range(v, &UPSTREAM_READ_TIMEOUT_MS, report, origin);
+    }
+    if let Some(v) = connection.idle_timeout_seconds {
+        validate_range(v, &UPSTREAM_IDLE_TIMEOUT_SECONDS, report, origin);
+    }
+    if let Some(v) = connection.response_idle_timeout_milliseconds {
+        validate_range(v, &UPSTREAM_RESPONSE_IDLE_TIMEOUT_MS, report, origin);
+    }
+    if let Some(v) = connection.max_connect_retries {
+        validate_range(v, &UPSTREAM_MAX_CONNECT_RETRIES, report, origin);
     }
 }
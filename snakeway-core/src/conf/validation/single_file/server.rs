@@ -1,6 +1,11 @@
-use crate::conf::types::ServerSpec;
+use super::{validate_geoip_db_file, validate_trusted_proxies};
+use crate::conf::types::{AccessLogFormat, ServerSpec, UnknownAccessLogField};
 use crate::conf::validation::report::ValidationReport;
-use crate::conf::validation::validator::{SERVER_THREADS, validate_range};
+use crate::conf::validation::validator::{
+    SERVER_CONNECTION_LOG_SAMPLE_RATE, SERVER_MAX_REQUEST_BODY_BYTES, SERVER_REQUEST_TIMEOUT_MS,
+    SERVER_SHUTDOWN_READINESS_GRACE_SECONDS, SERVER_SHUTDOWN_TIMEOUT_SECONDS, SERVER_THREADS,
+    SERVER_WS_MAX_CONNECTIONS, validate_range,
+};
 
 /// Validate top-level config version.
 ///
@@ -43,4 +48,68 @@ pub fn validate_server(cfg: &ServerSpec, report: &mut ValidationReport) {
     {
         validate_range(t, &SERVER_THREADS, report, &cfg.origin);
     }
+
+    if let Some(timeout) = cfg.shutdown_timeout_seconds {
+        validate_range(
+            timeout,
+            &SERVER_SHUTDOWN_TIMEOUT_SECONDS,
+            report,
+            &cfg.origin,
+        );
+    }
+
+    if let Some(grace) = cfg.shutdown_readiness_grace_seconds {
+        validate_range(
+            grace,
+            &SERVER_SHUTDOWN_READINESS_GRACE_SECONDS,
+            report,
+            &cfg.origin,
+        );
+    }
+
+    if let Some(max) = cfg.ws_max_connections {
+        validate_range(max, &SERVER_WS_MAX_CONNECTIONS, report, &cfg.origin);
+    }
+
+    if let Some(max) = cfg.max_request_body_bytes {
+        validate_range(max, &SERVER_MAX_REQUEST_BODY_BYTES, report, &cfg.origin);
+    }
+
+    if let Some(timeout) = cfg.request_timeout_milliseconds {
+        validate_range(timeout, &SERVER_REQUEST_TIMEOUT_MS, report, &cfg.origin);
+    }
+
+    if let Some(geoip) = &cfg.geoip {
+        for path in [&geoip.city_db, &geoip.isp_db, &geoip.connection_type_db]
+            .into_iter()
+            .flatten()
+        {
+            validate_geoip_db_file(path, report, &cfg.origin);
+        }
+    }
+
+    if let Some(rate) = cfg.connection_log_sample_rate {
+        validate_range(
+            rate,
+            &SERVER_CONNECTION_LOG_SAMPLE_RATE,
+            report,
+            &cfg.origin,
+        );
+    }
+
+    validate_trusted_proxies(&cfg.trusted_proxies, report, &cfg.origin);
+
+    if let Some(format) = &cfg.access_log_format {
+        if let Err(UnknownAccessLogField(field)) = AccessLogFormat::parse(format) {
+            report.access_log_format_unknown_field(&field, &cfg.origin);
+        }
+        if !cfg.access_log {
+            report.access_log_format_without_access_log(&cfg.origin);
+        }
+    }
+
+    // Pingora runs a single worker thread unless told otherwise, leaving one socket per listener.
+    if cfg.reuse_port && cfg.threads.unwrap_or(1) == 1 {
+        report.reuse_port_with_single_thread(&cfg.origin);
+    }
 }
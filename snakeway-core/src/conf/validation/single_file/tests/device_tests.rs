@@ -1,5 +1,16 @@
-use crate::conf::types::{DeviceSpec, IdentityDeviceSpec, WasmDeviceSpec};
-use crate::conf::validation::{ValidationReport, validate_devices};
+use crate::conf::types::{
+    BindAdminSpec, BindSpec, BodyDigestDeviceSpec, CorsDeviceSpec, DeprecationDeviceSpec,
+    DeprecationSpec, DeviceSpec, ErrorPageDeviceSpec, ExperimentDeviceSpec, ExperimentVariantSpec,
+    HeaderRewriteDeviceSpec, HeaderSetSpec, IdempotencyDeviceSpec, IdentityDeviceSpec, IngressSpec,
+    IpReputationDeviceSpec, JwtAuthDeviceSpec, LuaDeviceSpec, MissingHeaderActionSpec,
+    QueryOperationKindSpec, QueryOperationSpec, QueryRewriteDeviceSpec, QuotaDeviceSpec,
+    RateLimitDeviceSpec, RateLimitKeySpec, RequiredHeaderSpec, RequiredHeadersDeviceSpec,
+    ResponseHeaderSpec, ResponseHeadersDeviceSpec, StatusRemapDeviceSpec, StatusRemapSpec,
+    WasmDeviceSpec,
+};
+use crate::conf::validation::{
+    ValidationReport, validate_device_attachments, validate_devices, validate_wasm_devices,
+};
 use std::path::PathBuf;
 
 #[test]
@@ -18,7 +29,7 @@ fn validate_wasm_device_valid() {
     });
 
     // Act
-    validate_devices(&[device], &mut report);
+    validate_wasm_devices(&[device], true, &mut report);
 
     // Assert
     assert!(!report.has_violations());
@@ -36,7 +47,7 @@ fn validate_wasm_device_disabled_skips_validation() {
     let devices = vec![device];
 
     // Act
-    validate_devices(&devices, &mut report);
+    validate_wasm_devices(&devices, true, &mut report);
 
     // Assert
     assert!(!report.has_violations());
@@ -54,7 +65,7 @@ fn validate_wasm_device_path_empty() {
     let devices = vec![device];
 
     // Act
-    validate_devices(&devices, &mut report);
+    validate_wasm_devices(&devices, true, &mut report);
 
     // Assert
     assert!(report.has_violations());
@@ -83,7 +94,7 @@ fn validate_wasm_device_path_does_not_exist() {
     let devices = vec![device];
 
     // Act
-    validate_devices(&devices, &mut report);
+    validate_wasm_devices(&devices, true, &mut report);
 
     // Assert
     assert!(report.has_violations());
@@ -95,6 +106,29 @@ fn validate_wasm_device_path_does_not_exist() {
     );
 }
 
+#[test]
+fn validate_wasm_device_missing_path_is_a_warning_when_not_strict() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let device = DeviceSpec::Wasm(WasmDeviceSpec {
+        enable: true,
+        path: PathBuf::from("/non/existent/path/to/wasm"),
+        ..Default::default()
+    });
+
+    // Act
+    validate_wasm_devices(&[device], false, &mut report);
+
+    // Assert
+    assert!(report.errors.is_empty());
+    assert!(
+        report
+            .warnings
+            .iter()
+            .any(|w| w.message.contains("wasm device path does not exist"))
+    );
+}
+
 #[test]
 fn validate_wasm_device_path_is_not_a_file() {
     let mut report = ValidationReport::default();
@@ -106,7 +140,7 @@ fn validate_wasm_device_path_is_not_a_file() {
         ..Default::default()
     });
 
-    validate_devices(&[device], &mut report);
+    validate_wasm_devices(&[device], true, &mut report);
 
     assert!(
         report
@@ -116,6 +150,48 @@ fn validate_wasm_device_path_is_not_a_file() {
     );
 }
 
+#[test]
+fn validate_wasm_plugin_bundle_config_against_its_schema() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("path_blocker.wasm"), "dummy wasm").unwrap();
+    std::fs::write(
+        dir.path().join("plugin.hcl"),
+        r#"
+name         = "path_blocker"
+version      = "1.0.0"
+abi          = "0.2.0"
+module       = "path_blocker.wasm"
+capabilities = ["policy", "config"]
+
+config_schema = {
+  type       = "object"
+  properties = { block_paths = { type = "array" } }
+}
+"#,
+    )
+    .unwrap();
+
+    let device = DeviceSpec::Wasm(WasmDeviceSpec {
+        enable: true,
+        path: dir.path().to_path_buf(),
+        config: Some(hcl::from_str(r#"block_paths = "/admin""#).unwrap()),
+        ..Default::default()
+    });
+
+    // Act
+    validate_wasm_devices(&[device], true, &mut report);
+
+    // Assert
+    let messages: Vec<_> = report.errors.iter().map(|e| e.message.as_str()).collect();
+    assert_eq!(messages.len(), 1, "{messages:?}");
+    assert!(
+        messages[0]
+            .starts_with("wasm device config does not match the schema of plugin 'path_blocker'")
+    );
+}
+
 #[test]
 fn validate_identity_device_valid() {
     let mut report = ValidationReport::default();
@@ -231,6 +307,66 @@ fn validate_identity_device_trusted_proxy_public_ip_warning() {
     }))
 }
 
+#[test]
+fn validate_identity_device_client_ip_header_valid() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let device = DeviceSpec::Identity(IdentityDeviceSpec {
+        enable: true,
+        trusted_proxies: vec!["10.0.0.0/8".to_string()],
+        client_ip_header: Some("CF-Connecting-IP".to_string()),
+        ..Default::default()
+    });
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    assert!(!report.has_violations());
+}
+
+#[test]
+fn validate_identity_device_client_ip_header_invalid_name() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let device = DeviceSpec::Identity(IdentityDeviceSpec {
+        enable: true,
+        trusted_proxies: vec!["10.0.0.0/8".to_string()],
+        client_ip_header: Some("cf connecting ip".to_string()),
+        ..Default::default()
+    });
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    assert!(report.errors.iter().any(|e| {
+        e.message
+            .contains("invalid HTTP header name: cf connecting ip")
+    }));
+}
+
+#[test]
+fn validate_identity_device_client_ip_header_without_trusted_proxies_warning() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let device = DeviceSpec::Identity(IdentityDeviceSpec {
+        enable: true,
+        client_ip_header: Some("True-Client-IP".to_string()),
+        ..Default::default()
+    });
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    assert!(report.errors.is_empty());
+    assert!(report.warnings.iter().any(|w| {
+        w.message
+            .contains("client_ip_header is set but trusted_proxies is empty")
+    }));
+}
+
 #[test]
 fn validate_identity_device_geoip_db_empty() {
     // Arrange
@@ -302,3 +438,924 @@ fn validate_identity_device_geoip_db_is_not_a_file() {
             .any(|e| e.message.contains("geoip db path is not a file"))
     );
 }
+
+#[test]
+fn validate_device_attachments_known_devices() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let devices = vec![
+        DeviceSpec::Identity(IdentityDeviceSpec {
+            enable: true,
+            ..Default::default()
+        }),
+        DeviceSpec::Wasm(WasmDeviceSpec {
+            enable: true,
+            path: PathBuf::from("/devices/rate_limit.wasm"),
+            ..Default::default()
+        }),
+    ];
+    let ingress = IngressSpec {
+        bind: Some(BindSpec {
+            devices: vec!["rate_limit".to_string()],
+            ..Default::default()
+        }),
+        bind_admin: Some(BindAdminSpec {
+            devices: vec!["identity".to_string()],
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    // Act
+    validate_device_attachments(&[ingress], &devices, &mut report);
+
+    // Assert
+    assert!(!report.has_violations());
+}
+
+#[test]
+fn validate_device_attachments_unknown_device() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let ingress = IngressSpec {
+        bind: Some(BindSpec {
+            devices: vec!["missing".to_string()],
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    // Act
+    validate_device_attachments(&[ingress], &[], &mut report);
+
+    // Assert
+    assert_eq!(report.errors.len(), 1);
+    assert!(report.errors[0].message.contains("unknown device"));
+}
+
+#[test]
+fn validate_device_attachments_duplicate_wasm_name() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let devices = vec![
+        DeviceSpec::Wasm(WasmDeviceSpec {
+            enable: true,
+            path: PathBuf::from("/a/plugin.wasm"),
+            ..Default::default()
+        }),
+        DeviceSpec::Wasm(WasmDeviceSpec {
+            enable: true,
+            path: PathBuf::from("/b/plugin.wasm"),
+            ..Default::default()
+        }),
+    ];
+
+    // Act
+    validate_device_attachments(&[], &devices, &mut report);
+
+    // Assert
+    assert_eq!(report.errors.len(), 1);
+    assert!(report.errors[0].message.contains("duplicate device name"));
+}
+
+#[test]
+fn validate_device_attachments_scoped_routes() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let devices = vec![DeviceSpec::Identity(IdentityDeviceSpec {
+        enable: true,
+        routes: vec!["/api".to_string(), "/admin/users".to_string()],
+        ..Default::default()
+    })];
+
+    // Act
+    validate_device_attachments(&[], &devices, &mut report);
+
+    // Assert
+    assert!(!report.has_violations());
+}
+
+#[test]
+fn validate_device_attachments_invalid_routes() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let devices = vec![DeviceSpec::Identity(IdentityDeviceSpec {
+        enable: true,
+        routes: vec![
+            "api".to_string(),
+            "/api/*".to_string(),
+            "/public".to_string(),
+            "/public".to_string(),
+        ],
+        ..Default::default()
+    })];
+
+    // Act
+    validate_device_attachments(&[], &devices, &mut report);
+
+    // Assert
+    let messages: Vec<&str> = report.errors.iter().map(|e| e.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "invalid route on device identity: api",
+            "invalid route on device identity: /api/*",
+            "duplicate route on device identity: /public",
+        ]
+    );
+}
+
+#[test]
+fn validate_openapi_device_valid() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let dir = tempfile::tempdir().unwrap();
+    let spec_path = dir.path().join("openapi.yaml");
+    std::fs::write(&spec_path, "openapi: 3.0.3").unwrap();
+
+    let device = DeviceSpec::OpenApi(OpenApiDeviceSpec {
+        enable: true,
+        spec_path,
+        max_body_bytes: 1024 * 1024,
+        ..Default::default()
+    });
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    assert!(!report.has_violations());
+}
+
+#[test]
+fn validate_openapi_device_missing_spec() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let device = DeviceSpec::OpenApi(OpenApiDeviceSpec {
+        enable: true,
+        spec_path: PathBuf::from("/non/existent/openapi.yaml"),
+        max_body_bytes: 1024 * 1024,
+        ..Default::default()
+    });
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    assert_eq!(report.errors.len(), 1);
+    assert!(
+        report.errors[0]
+            .message
+            .contains("openapi spec_path is not a file")
+    );
+}
+
+fn jwt_auth_device() -> JwtAuthDeviceSpec {
+    JwtAuthDeviceSpec {
+        enable: true,
+        hmac_secret: Some("secret".to_string()),
+        leeway_seconds: 60,
+        jwks_refresh_seconds: 300,
+        timeout_milliseconds: 1000,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn validate_jwt_auth_device_valid() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let device = DeviceSpec::JwtAuth(jwt_auth_device());
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    assert!(!report.has_violations());
+}
+
+#[test]
+fn validate_jwt_auth_device_needs_one_key_source() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let device = DeviceSpec::JwtAuth(JwtAuthDeviceSpec {
+        jwks_url: Some("https://auth.example.com/.well-known/jwks.json".to_string()),
+        ..jwt_auth_device()
+    });
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    assert_eq!(report.errors.len(), 1);
+    assert!(report.errors[0].message.contains("exactly one of"));
+}
+
+#[test]
+fn validate_jwt_auth_device_invalid_jwks_url() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let device = DeviceSpec::JwtAuth(JwtAuthDeviceSpec {
+        hmac_secret: None,
+        jwks_url: Some("/jwks.json".to_string()),
+        ..jwt_auth_device()
+    });
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    assert_eq!(report.errors.len(), 1);
+    assert!(
+        report.errors[0]
+            .message
+            .contains("invalid jwt auth jwks url")
+    );
+}
+
+#[test]
+fn validate_jwt_auth_device_algorithm_must_match_key_source() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let device = DeviceSpec::JwtAuth(JwtAuthDeviceSpec {
+        algorithms: vec!["HS256".to_string(), "RS256".to_string()],
+        ..jwt_auth_device()
+    });
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    assert_eq!(report.errors.len(), 1);
+    assert!(report.errors[0].message.contains("RS256"));
+}
+
+#[test]
+fn validate_quota_device_valid() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let device = DeviceSpec::Quota(QuotaDeviceSpec {
+        enable: true,
+        quota_url: "http://127.0.0.1:9100/quota".to_string(),
+        api_key_header: "X-API-Key".to_string(),
+        timeout_milliseconds: 250,
+        cache_ttl_milliseconds: 1000,
+        ..Default::default()
+    });
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    assert!(!report.has_violations());
+}
+
+#[test]
+fn validate_quota_device_invalid_url() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let device = DeviceSpec::Quota(QuotaDeviceSpec {
+        enable: true,
+        quota_url: "/quota".to_string(),
+        api_key_header: "X-API-Key".to_string(),
+        timeout_milliseconds: 250,
+        cache_ttl_milliseconds: 1000,
+        ..Default::default()
+    });
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    assert_eq!(report.errors.len(), 1);
+    assert!(report.errors[0].message.contains("invalid quota url"));
+}
+
+#[test]
+fn validate_rate_limit_device_valid() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let device = DeviceSpec::RateLimit(RateLimitDeviceSpec {
+        enable: true,
+        requests_per_second: 10,
+        burst: Some(20),
+        key: RateLimitKeySpec::Header,
+        key_header: Some("X-API-Key".to_string()),
+        ..Default::default()
+    });
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    assert!(!report.has_violations());
+}
+
+#[test]
+fn validate_rate_limit_device_header_key_without_key_header() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let device = DeviceSpec::RateLimit(RateLimitDeviceSpec {
+        enable: true,
+        requests_per_second: 10,
+        key: RateLimitKeySpec::Header,
+        ..Default::default()
+    });
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    assert_eq!(report.errors.len(), 1);
+    assert!(report.errors[0].message.contains("key_header is not set"));
+}
+
+#[test]
+fn validate_rate_limit_device_zero_requests_per_second() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let device = DeviceSpec::RateLimit(RateLimitDeviceSpec {
+        enable: true,
+        requests_per_second: 0,
+        ..Default::default()
+    });
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    assert_eq!(report.errors.len(), 1);
+    assert!(
+        report.errors[0]
+            .message
+            .contains("rate_limit_device.requests_per_second")
+    );
+}
+
+#[test]
+fn validate_experiment_device_valid() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let device = DeviceSpec::Experiment(ExperimentDeviceSpec {
+        enable: true,
+        experiment: "checkout".to_string(),
+        cookie: "uid".to_string(),
+        variants: vec![
+            ExperimentVariantSpec {
+                name: "control".to_string(),
+                weight: 50,
+            },
+            ExperimentVariantSpec {
+                name: "treatment".to_string(),
+                weight: 50,
+            },
+        ],
+        ..Default::default()
+    });
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    assert!(!report.has_violations());
+}
+
+#[test]
+fn validate_experiment_device_without_weighted_variants() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let device = DeviceSpec::Experiment(ExperimentDeviceSpec {
+        enable: true,
+        experiment: "checkout".to_string(),
+        cookie: "uid".to_string(),
+        variants: vec![ExperimentVariantSpec {
+            name: "control".to_string(),
+            weight: 0,
+        }],
+        ..Default::default()
+    });
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    assert_eq!(report.errors.len(), 1);
+    assert!(
+        report.errors[0]
+            .message
+            .contains("no variant with a weight above 0")
+    );
+}
+
+#[test]
+fn validate_header_rewrite_device_valid() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let device = DeviceSpec::HeaderRewrite(HeaderRewriteDeviceSpec {
+        enable: true,
+        request_set: vec![HeaderSetSpec {
+            name: "X-Env".to_string(),
+            value: "prod".to_string(),
+        }],
+        response_remove: vec!["Server".to_string()],
+        ..Default::default()
+    });
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    assert!(!report.has_violations());
+}
+
+#[test]
+fn validate_header_rewrite_device_set_and_removed() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let device = DeviceSpec::HeaderRewrite(HeaderRewriteDeviceSpec {
+        enable: true,
+        response_set: vec![HeaderSetSpec {
+            name: "Server".to_string(),
+            value: "snakeway".to_string(),
+        }],
+        response_remove: vec!["server".to_string()],
+        ..Default::default()
+    });
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(
+        report.errors[0].message,
+        "header 'server' is in both response_set and response_remove"
+    );
+}
+
+#[test]
+fn validate_query_rewrite_device_valid() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let device = DeviceSpec::QueryRewrite(QueryRewriteDeviceSpec {
+        enable: true,
+        operations: vec![
+            QueryOperationSpec {
+                op: QueryOperationKindSpec::AddIfMissing,
+                name: "format".to_string(),
+                value: Some("json".to_string()),
+                to: None,
+            },
+            QueryOperationSpec {
+                op: QueryOperationKindSpec::Rename,
+                name: "q".to_string(),
+                value: None,
+                to: Some("query".to_string()),
+            },
+        ],
+        ..Default::default()
+    });
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    assert!(!report.has_violations());
+}
+
+#[test]
+fn validate_query_rewrite_device_missing_fields() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let device = DeviceSpec::QueryRewrite(QueryRewriteDeviceSpec {
+        enable: true,
+        operations: vec![
+            QueryOperationSpec {
+                op: QueryOperationKindSpec::Set,
+                name: "page".to_string(),
+                value: None,
+                to: None,
+            },
+            QueryOperationSpec {
+                op: QueryOperationKindSpec::Rename,
+                name: String::new(),
+                value: None,
+                to: None,
+            },
+        ],
+        ..Default::default()
+    });
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    let messages: Vec<&str> = report.errors.iter().map(|e| e.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "query rewrite operations[0] requires 'value'",
+            "query rewrite operations[1] has an empty name",
+            "query rewrite operations[1] requires 'to'",
+        ]
+    );
+}
+
+#[test]
+fn validate_query_rewrite_device_ignored_field_warns() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let device = DeviceSpec::QueryRewrite(QueryRewriteDeviceSpec {
+        enable: true,
+        operations: vec![QueryOperationSpec {
+            op: QueryOperationKindSpec::Remove,
+            name: "utm_source".to_string(),
+            value: Some("ad".to_string()),
+            to: None,
+        }],
+        ..Default::default()
+    });
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    assert!(report.errors.is_empty());
+    assert_eq!(
+        report.warnings[0].message,
+        "query rewrite operations[0] does not use 'value'"
+    );
+}
+
+#[test]
+fn validate_cors_device_valid() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let device = DeviceSpec::Cors(CorsDeviceSpec {
+        enable: true,
+        allow_origins: vec![
+            "https://app.example.com".to_string(),
+            "http://localhost:3000".to_string(),
+        ],
+        allow_methods: vec!["GET".to_string(), "POST".to_string()],
+        allow_headers: vec!["content-type".to_string()],
+        max_age_seconds: Some(600),
+        ..Default::default()
+    });
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    assert!(!report.has_violations());
+}
+
+#[test]
+fn validate_cors_device_invalid_origins() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let device = DeviceSpec::Cors(CorsDeviceSpec {
+        enable: true,
+        allow_origins: vec![
+            "app.example.com".to_string(),
+            "https://app.example.com/".to_string(),
+            "ftp://files.example.com".to_string(),
+        ],
+        ..Default::default()
+    });
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    assert_eq!(report.errors.len(), 3);
+    assert_eq!(
+        report.errors[0].message,
+        "cors origin 'app.example.com' is not a valid origin"
+    );
+}
+
+#[test]
+fn validate_cors_device_any_origin_with_credentials_warns() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let device = DeviceSpec::Cors(CorsDeviceSpec {
+        enable: true,
+        allow_origins: vec!["*".to_string()],
+        allow_credentials: true,
+        ..Default::default()
+    });
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    assert!(report.errors.is_empty());
+    assert_eq!(report.warnings.len(), 1);
+    assert_eq!(
+        report.warnings[0].message,
+        "cors device allows credentials from any origin"
+    );
+}
+
+fn idempotency_device() -> IdempotencyDeviceSpec {
+    IdempotencyDeviceSpec {
+        enable: true,
+        key_header: "Idempotency-Key".to_string(),
+        methods: vec!["POST".to_string()],
+        paths: vec!["/payments".to_string()],
+        ttl_seconds: 86400,
+        wait_timeout_milliseconds: 10_000,
+        max_body_bytes: 1024 * 1024,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn validate_idempotency_device_valid() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let device = DeviceSpec::Idempotency(idempotency_device());
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    assert!(!report.has_violations());
+}
+
+#[test]
+fn validate_idempotency_device_invalid() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let device = DeviceSpec::Idempotency(IdempotencyDeviceSpec {
+        methods: vec![],
+        paths: vec!["payments".to_string()],
+        ttl_seconds: 0,
+        ..idempotency_device()
+    });
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    let messages: Vec<&str> = report.errors.iter().map(|e| e.message.as_str()).collect();
+    assert_eq!(messages.len(), 3);
+    assert_eq!(
+        messages[0],
+        "idempotency device requires a key on no methods"
+    );
+    assert_eq!(
+        messages[1],
+        "idempotency path 'payments' must start with '/'"
+    );
+    assert!(messages[2].starts_with("invalid idempotency_device.ttl_seconds"));
+}
+
+#[test]
+fn validate_body_digest_device_max_body_bytes_out_of_range() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let device = DeviceSpec::BodyDigest(BodyDigestDeviceSpec {
+        enable: true,
+        max_body_bytes: 0,
+        ..Default::default()
+    });
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    assert_eq!(report.errors.len(), 1);
+    assert!(
+        report.errors[0]
+            .message
+            .contains("body_digest_device.max_body_bytes")
+    );
+}
+
+#[test]
+fn validate_required_headers_device_populate_without_value() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let device = DeviceSpec::RequiredHeaders(RequiredHeadersDeviceSpec {
+        enable: true,
+        headers: vec![RequiredHeaderSpec {
+            name: "X-Correlation-Id".to_string(),
+            on_missing: MissingHeaderActionSpec::Populate,
+            value: None,
+        }],
+        ..Default::default()
+    });
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(
+        report.errors[0].message,
+        "required header 'X-Correlation-Id' uses populate but has no value"
+    );
+}
+
+#[test]
+fn validate_status_remap_device_duplicate_and_out_of_range() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let remap = |from, to| StatusRemapSpec {
+        from,
+        to,
+        body: None,
+    };
+    let device = DeviceSpec::StatusRemap(StatusRemapDeviceSpec {
+        enable: true,
+        remaps: vec![remap(418, 429), remap(418, 503), remap(520, 700)],
+        ..Default::default()
+    });
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    let messages: Vec<_> = report.errors.iter().map(|e| e.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "status 418 is remapped more than once",
+            "invalid status_remap_device.remaps.to: 700 (must be between 200 and 599)",
+        ]
+    );
+}
+
+#[test]
+fn validate_error_page_device_without_body_and_with_invalid_statuses() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let device = DeviceSpec::ErrorPage(ErrorPageDeviceSpec {
+        enable: true,
+        statuses: vec!["5xx".to_string(), "302".to_string(), "6xx".to_string()],
+        ..Default::default()
+    });
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    let messages: Vec<_> = report.errors.iter().map(|e| e.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "error page device has no body or body_file",
+            "error page status '302' is not an error status",
+            "error page status '6xx' is not an error status",
+        ]
+    );
+}
+
+#[test]
+fn validate_response_headers_device_unknown_placeholder_and_duplicate() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let header = |name: &str, value: &str| ResponseHeaderSpec {
+        name: name.to_string(),
+        value: value.to_string(),
+    };
+    let device = DeviceSpec::ResponseHeaders(ResponseHeadersDeviceSpec {
+        enable: true,
+        headers: vec![
+            header("X-Request-Id", "{request_id}"),
+            header("x-request-id", "{route}"),
+            header("X-Served-By", "{upstream}-{host}"),
+        ],
+        ..Default::default()
+    });
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    let messages: Vec<_> = report.errors.iter().map(|e| e.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "response header 'x-request-id' is listed more than once",
+            "response header 'X-Served-By' uses unknown placeholder {host}",
+        ]
+    );
+}
+
+#[test]
+fn validate_ip_reputation_device_without_source() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let device = DeviceSpec::IpReputation(IpReputationDeviceSpec {
+        enable: true,
+        timeout_milliseconds: 250,
+        block_status: 200,
+        tag_header: "X-IP-Reputation".to_string(),
+        ..Default::default()
+    });
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    let messages: Vec<_> = report.errors.iter().map(|e| e.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "ip reputation device has no list_file or reputation_url",
+            "invalid ip_reputation_device.block_status: 200 (must be between 400 and 599)",
+        ]
+    );
+}
+
+#[test]
+fn validate_wasm_device_fuel_out_of_range() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let dir = tempfile::tempdir().unwrap();
+    let wasm_file = dir.path().join("plugin.wasm");
+    std::fs::write(&wasm_file, "dummy wasm").unwrap();
+
+    let device = DeviceSpec::Wasm(WasmDeviceSpec {
+        enable: true,
+        path: wasm_file,
+        fuel: 10,
+        ..Default::default()
+    });
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(
+        report.errors[0].message,
+        "invalid wasm_device.fuel: 10 (must be between 1000 and 1000000000000)"
+    );
+}
+
+#[test]
+fn validate_deprecation_device_timestamps_and_duplicates() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let deprecation = |path: &str, deprecated_at: &str, sunset_at: &str| DeprecationSpec {
+        path: path.to_string(),
+        deprecated_at: deprecated_at.to_string(),
+        sunset_at: Some(sunset_at.to_string()),
+        successor: None,
+    };
+    let device = DeviceSpec::Deprecation(DeprecationDeviceSpec {
+        enable: true,
+        deprecations: vec![
+            deprecation("/api/v1", "2026-01-01T00:00:00Z", "2026-08-01T00:00:00Z"),
+            deprecation("/api/v1/", "2026-01-01T00:00:00Z", "2026-08-01T00:00:00Z"),
+            deprecation("/legacy", "2026-01-01", "2026-08-01T00:00:00Z"),
+            deprecation("/old", "2026-08-01T00:00:00Z", "2026-01-01T00:00:00Z"),
+        ],
+        ..Default::default()
+    });
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    let messages: Vec<_> = report.errors.iter().map(|e| e.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "deprecation path '/api/v1/' is listed more than once",
+            "deprecation of '/legacy' has an invalid timestamp: 2026-01-01",
+            "deprecation of '/old' has a sunset_at before its deprecated_at",
+        ]
+    );
+}
+
+#[test]
+fn validate_lua_device_script_and_limits() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let device = DeviceSpec::Lua(LuaDeviceSpec {
+        enable: true,
+        path: PathBuf::from("/nonexistent/script.lua"),
+        instruction_limit: 10,
+        max_body_bytes: 0,
+        ..Default::default()
+    });
+
+    // Act
+    validate_devices(&[device], &mut report);
+
+    // Assert
+    let messages: Vec<_> = report.errors.iter().map(|e| e.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "lua device script is not a file: /nonexistent/script.lua",
+            "invalid lua_device.instruction_limit: 10 (must be between 1000 and 1000000000)",
+            "invalid lua_device.max_body_bytes: 0 (must be between 1 and 16777216)",
+        ]
+    );
+}
@@ -1,5 +1,7 @@
 use crate::conf::types::*;
-use crate::conf::validation::{ValidationReport, validate_ingresses, validate_redirect};
+use crate::conf::validation::{
+    ValidationReport, validate_ingresses, validate_redirect, validate_redirect_cycles,
+};
 use pretty_assertions::assert_eq;
 use std::net::IpAddr;
 use std::path::PathBuf;
@@ -108,6 +110,32 @@ fn validate_ingress_tls_missing_cert_and_key() {
     assert_eq!(report.errors[1].message, expected_key_error);
 }
 
+#[test]
+fn validate_ingress_tls_secret_references_skip_file_checks() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let mut bind = minimal_bind();
+    bind.tls = Some(TlsSpec {
+        cert: "secret://env/TLS_CERT".to_string(),
+        key: "secret://env".to_string(),
+    });
+    let ingress = IngressSpec {
+        bind: Some(bind),
+        services: vec![minimal_service()],
+        ..Default::default()
+    };
+
+    // Act
+    validate_ingresses(&[ingress], &mut report);
+
+    // Assert
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(
+        report.errors[0].message,
+        "invalid secret reference: secret://env"
+    );
+}
+
 #[test]
 fn validate_ingress_http2_requires_tls() {
     // Arrange
@@ -305,3 +333,69 @@ fn redirect_should_not_exist_without_tls() {
     assert_eq!(report.errors[0].message, expected_error);
     assert_eq!(report.errors[0].help, expected_help);
 }
+
+fn redirecting_ingress(port: u16, redirect_port: u16, file: &str) -> IngressSpec {
+    let mut bind = minimal_bind();
+    bind.port = port;
+    bind.origin = Origin::new(&PathBuf::from(file), "bind", None);
+    bind.redirect_http_to_https = Some(RedirectSpec {
+        port: redirect_port,
+        status: 308,
+    });
+    IngressSpec {
+        bind: Some(bind),
+        services: vec![minimal_service()],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn redirect_to_own_bind_port_is_a_loop() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let ingress = redirecting_ingress(8443, 8443, "/etc/snakeway/ingress.d/a.hcl");
+
+    // Act
+    validate_redirect_cycles(&[ingress], &mut report);
+
+    // Assert
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(
+        report.errors[0].message,
+        "redirect loop: 127.0.0.1:8443 -> 127.0.0.1:8443 (/etc/snakeway/ingress.d/a.hcl: bind block)"
+    );
+}
+
+#[test]
+fn redirects_pointing_at_each_other_are_a_loop() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let ingresses = [
+        redirecting_ingress(8443, 8080, "/etc/snakeway/ingress.d/a.hcl"),
+        redirecting_ingress(8080, 8443, "/etc/snakeway/ingress.d/b.hcl"),
+    ];
+
+    // Act
+    validate_redirect_cycles(&ingresses, &mut report);
+
+    // Assert
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(
+        report.errors[0].message,
+        "redirect loop: 127.0.0.1:8080 -> 127.0.0.1:8443 -> 127.0.0.1:8080 \
+         (/etc/snakeway/ingress.d/a.hcl: bind block, /etc/snakeway/ingress.d/b.hcl: bind block)"
+    );
+}
+
+#[test]
+fn redirect_to_another_port_is_not_a_loop() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let ingress = redirecting_ingress(8443, 8080, "/etc/snakeway/ingress.d/a.hcl");
+
+    // Act
+    validate_redirect_cycles(&[ingress], &mut report);
+
+    // Assert
+    assert!(!report.has_violations());
+}
@@ -1,8 +1,12 @@
 use crate::conf::types::{
-    BindInterfaceInput, BindSpec, CircuitBreakerConfig, EndpointSpec, HostSpec, IngressSpec,
-    Origin, ServiceRouteSpec, ServiceSpec, UpstreamSpec,
+    ActiveHealthCheckConfig, BindInterfaceInput, BindSpec, CircuitBreakerBackoffConfig,
+    CircuitBreakerConfig, EndpointSpec, HealthCheckConfig, HostSpec, IngressSpec,
+    LoadBalancingStrategySpec, Origin, ResponseRateLimitSpec, ServerSpec, ServiceRouteSpec,
+    ServiceSpec, UpstreamSpec,
+};
+use crate::conf::validation::{
+    ValidationReport, validate_ingresses, validate_services, validate_strict_host,
 };
-use crate::conf::validation::{ValidationReport, validate_ingresses, validate_services};
 use pretty_assertions::assert_eq;
 use std::net::IpAddr;
 use std::str::FromStr;
@@ -271,6 +275,69 @@ fn validate_service_circuit_breaker_valid() {
     assert!(!report.has_violations());
 }
 
+#[test]
+fn validate_service_active_health_check_invalid_path_and_status() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let services = vec![ServiceSpec {
+        upstreams: vec![minimal_upstream()],
+        health_check: Some(HealthCheckConfig {
+            enable: true,
+            active: Some(ActiveHealthCheckConfig {
+                path: "healthz".to_string(),
+                expected_status: 1000,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }];
+    let maybe_bind = minimal_maybe_bind_addr();
+
+    // Act
+    validate_services(&maybe_bind, &services, &mut report);
+
+    // Assert
+    let messages: Vec<_> = report.errors.iter().map(|e| e.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "active health check path 'healthz' must start with '/'",
+            "invalid HTTP status code 1000",
+        ]
+    );
+}
+
+#[test]
+fn validate_service_active_health_check_success_threshold_out_of_range() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let services = vec![ServiceSpec {
+        upstreams: vec![minimal_upstream()],
+        health_check: Some(HealthCheckConfig {
+            enable: true,
+            active: Some(ActiveHealthCheckConfig {
+                success_threshold: 0, // Min is 1
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }];
+    let maybe_bind = minimal_maybe_bind_addr();
+
+    // Act
+    validate_services(&maybe_bind, &services, &mut report);
+
+    // Assert
+    let error = report.errors.first().expect("expected at least one error");
+    assert!(
+        error
+            .message
+            .contains("health_check.active.success_threshold")
+    );
+}
+
 #[test]
 fn validate_service_circuit_breaker_failure_threshold_out_of_range() {
     // Arrange
@@ -383,6 +450,149 @@ fn validate_service_circuit_breaker_success_threshold_out_of_range() {
     assert!(error.message.contains("circuit_breaker.success_threshold"));
 }
 
+#[test]
+fn validate_service_circuit_breaker_open_backoff_multiplier_out_of_range() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let services = vec![ServiceSpec {
+        upstreams: vec![minimal_upstream()],
+        circuit_breaker: Some(CircuitBreakerConfig {
+            enable_auto_recovery: true,
+            failure_threshold: 5,
+            open_duration_milliseconds: 1000,
+            half_open_max_requests: 1,
+            success_threshold: 2,
+            open_backoff: Some(CircuitBreakerBackoffConfig {
+                multiplier: 0, // Min is 1
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }];
+    let maybe_bind = minimal_maybe_bind_addr();
+
+    // Act
+    validate_services(&maybe_bind, &services, &mut report);
+
+    // Assert
+    let error = report.errors.first().expect("expected at least one error");
+    assert!(
+        error
+            .message
+            .contains("circuit_breaker.open_backoff.multiplier")
+    );
+}
+
+#[test]
+fn validate_service_circuit_breaker_open_duration_exceeds_max_warns() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let services = vec![ServiceSpec {
+        upstreams: vec![minimal_upstream()],
+        circuit_breaker: Some(CircuitBreakerConfig {
+            enable_auto_recovery: true,
+            failure_threshold: 5,
+            open_duration_milliseconds: 10_000,
+            half_open_max_requests: 1,
+            success_threshold: 2,
+            open_backoff: Some(CircuitBreakerBackoffConfig {
+                multiplier: 2,
+                max_open_duration_milliseconds: 5_000,
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }];
+    let maybe_bind = minimal_maybe_bind_addr();
+
+    // Act
+    validate_services(&maybe_bind, &services, &mut report);
+
+    // Assert
+    assert!(report.errors.is_empty());
+    assert!(report.warnings.iter().any(|w| {
+        w.message
+            .contains("circuit open duration 10000ms exceeds its max 5000ms")
+    }));
+}
+
+#[test]
+fn validate_service_route_response_rate_limit_out_of_range() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let mut service = minimal_service();
+    service.routes.push(ServiceRouteSpec {
+        path: "/downloads".to_string(),
+        response_rate_limit: Some(ResponseRateLimitSpec {
+            bytes_per_second: 0, // Min is 1
+            burst_bytes: None,
+        }),
+        ..Default::default()
+    });
+    let services = vec![service];
+    let maybe_bind = minimal_maybe_bind_addr();
+
+    // Act
+    validate_services(&maybe_bind, &services, &mut report);
+
+    // Assert
+    let error = report.errors.first().expect("expected at least one error");
+    assert!(
+        error
+            .message
+            .contains("response_rate_limit.bytes_per_second")
+    );
+}
+
+#[test]
+fn validate_service_route_affinity_ttl_out_of_range() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let mut service = minimal_service();
+    service.load_balancing_strategy = LoadBalancingStrategySpec::StickyHash;
+    service.routes.push(ServiceRouteSpec {
+        path: "/sessions".to_string(),
+        affinity_ttl_seconds: Some(0), // Min is 1
+        ..Default::default()
+    });
+    let services = vec![service];
+    let maybe_bind = minimal_maybe_bind_addr();
+
+    // Act
+    validate_services(&maybe_bind, &services, &mut report);
+
+    // Assert
+    let error = report.errors.first().expect("expected at least one error");
+    assert!(error.message.contains("affinity_ttl_seconds"));
+    assert!(report.warnings.is_empty());
+}
+
+#[test]
+fn validate_service_route_affinity_ttl_without_sticky_strategy_warns() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let mut service = minimal_service();
+    service.load_balancing_strategy = LoadBalancingStrategySpec::RoundRobin;
+    service.routes.push(ServiceRouteSpec {
+        path: "/sessions".to_string(),
+        affinity_ttl_seconds: Some(300),
+        ..Default::default()
+    });
+    let services = vec![service];
+    let maybe_bind = minimal_maybe_bind_addr();
+
+    // Act
+    validate_services(&maybe_bind, &services, &mut report);
+
+    // Assert
+    assert!(report.errors.is_empty());
+    assert!(report.warnings.iter().any(|w| {
+        w.message
+            .contains("route /sessions sets affinity_ttl_seconds, but its service is not sticky")
+    }));
+}
+
 #[test]
 fn validate_sock_file_not_reused_across_services() {
     // Arrange
@@ -420,3 +630,173 @@ fn validate_sock_file_not_reused_across_services() {
     // Assert
     assert_eq!(report.errors[0].message, expected_error);
 }
+
+#[test]
+fn validate_service_upstream_header_case_valid() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let services = vec![ServiceSpec {
+        upstreams: vec![minimal_upstream()],
+        upstream_header_case: vec!["WWW-Authenticate".to_string(), "X-Legacy-Token".to_string()],
+        ..Default::default()
+    }];
+    let maybe_bind = minimal_maybe_bind_addr();
+
+    // Act
+    validate_services(&maybe_bind, &services, &mut report);
+
+    // Assert
+    assert!(!report.has_violations());
+}
+
+#[test]
+fn validate_service_upstream_header_case_invalid_header_name() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let services = vec![ServiceSpec {
+        upstreams: vec![minimal_upstream()],
+        upstream_header_case: vec!["X Legacy Token".to_string()],
+        ..Default::default()
+    }];
+    let maybe_bind = minimal_maybe_bind_addr();
+
+    // Act
+    validate_services(&maybe_bind, &services, &mut report);
+
+    // Assert
+    let error = report.errors.first().expect("expected at least one error");
+    assert!(
+        error
+            .message
+            .contains("invalid HTTP header name: X Legacy Token")
+    );
+}
+
+#[test]
+fn validate_service_upstream_sni_host_template_valid() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let services = vec![ServiceSpec {
+        upstreams: vec![minimal_upstream()],
+        upstream_sni: Some("{host}.storage.example.com".to_string()),
+        ..Default::default()
+    }];
+    let maybe_bind = minimal_maybe_bind_addr();
+
+    // Act
+    validate_services(&maybe_bind, &services, &mut report);
+
+    // Assert
+    assert!(!report.has_violations());
+}
+
+#[test]
+fn validate_service_upstream_sni_invalid_hostname() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let services = vec![ServiceSpec {
+        upstreams: vec![minimal_upstream()],
+        upstream_sni: Some("{host}_storage".to_string()),
+        ..Default::default()
+    }];
+    let maybe_bind = minimal_maybe_bind_addr();
+
+    // Act
+    validate_services(&maybe_bind, &services, &mut report);
+
+    // Assert
+    let error = report.errors.first().expect("expected at least one error");
+    assert_eq!(error.message, "invalid upstream SNI: {host}_storage");
+}
+
+#[test]
+fn validate_service_hosts_invalid_hostname() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let services = vec![ServiceSpec {
+        upstreams: vec![minimal_upstream()],
+        hosts: vec![HostSpec::Hostname("api example.com".to_string())],
+        ..Default::default()
+    }];
+    let maybe_bind = minimal_maybe_bind_addr();
+
+    // Act
+    validate_services(&maybe_bind, &services, &mut report);
+
+    // Assert
+    let error = report.errors.first().expect("expected at least one error");
+    assert_eq!(error.message, "invalid service host: api example.com");
+}
+
+#[test]
+fn validate_strict_host_warns_when_listener_declares_no_hosts() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let server = ServerSpec {
+        strict_host: true,
+        ..Default::default()
+    };
+    let ingresses = vec![IngressSpec {
+        bind: minimal_maybe_bind_addr(),
+        services: vec![minimal_service()],
+        ..Default::default()
+    }];
+
+    // Act
+    validate_strict_host(&server, &ingresses, &mut report);
+
+    // Assert
+    assert!(report.errors.is_empty());
+    assert_eq!(
+        report.warnings[0].message,
+        "strict_host is enabled but no service on this listener declares hosts"
+    );
+}
+
+#[test]
+fn validate_strict_host_accepts_listener_with_declared_hosts() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let server = ServerSpec {
+        strict_host: true,
+        ..Default::default()
+    };
+    let ingresses = vec![IngressSpec {
+        bind: minimal_maybe_bind_addr(),
+        services: vec![ServiceSpec {
+            hosts: vec![HostSpec::Hostname("api.example.com".to_string())],
+            ..minimal_service()
+        }],
+        ..Default::default()
+    }];
+
+    // Act
+    validate_strict_host(&server, &ingresses, &mut report);
+
+    // Assert
+    assert!(!report.has_violations());
+}
+
+#[test]
+fn validate_service_with_only_grouped_upstreams() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let services = vec![ServiceSpec {
+        upstreams: vec![UpstreamSpec {
+            group: Some("premium".to_string()),
+            ..minimal_upstream()
+        }],
+        ..minimal_service()
+    }];
+    let maybe_bind = minimal_maybe_bind_addr();
+
+    // Act
+    validate_services(&maybe_bind, &services, &mut report);
+
+    // Assert
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(
+        report.errors[0].message,
+        "service has no ungrouped upstreams"
+    );
+}
@@ -1,5 +1,5 @@
 use crate::conf::types::{
-    BindInterfaceInput, BindSpec, IngressSpec, StaticFilesSpec, StaticRouteSpec,
+    BindInterfaceInput, BindSpec, IngressSpec, SpaFallbackSpec, StaticFilesSpec, StaticRouteSpec,
 };
 use crate::conf::validation::{ValidationReport, validate_ingresses};
 use pretty_assertions::assert_eq;
@@ -57,3 +57,47 @@ fn validate_static_file_dir_is_not_relative() {
     assert_eq!(report.errors[0].message, expected_error0);
     assert_eq!(report.errors[1].message, expected_error1);
 }
+
+#[test]
+fn validate_static_compression_skip_content_type_pattern() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let mut ingress = minimal_static_files_ingress("/");
+    ingress.static_files[0].routes[0]
+        .compression
+        .skip_content_types = vec!["image/*".to_string(), "zip".to_string(), "*/*".to_string()];
+
+    // Act
+    validate_ingresses(&[ingress], &mut report);
+
+    // Assert
+    let messages: Vec<_> = report.errors.iter().map(|e| e.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "invalid content type pattern: zip",
+            "invalid content type pattern: */*",
+        ]
+    );
+}
+
+#[test]
+fn validate_static_spa_fallback_document_must_stay_in_file_dir() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let mut ingress = minimal_static_files_ingress("/");
+    ingress.static_files[0].routes[0].spa_fallback = Some(SpaFallbackSpec {
+        document: Some("../index.html".to_string()),
+        ..Default::default()
+    });
+
+    // Act
+    validate_ingresses(&[ingress], &mut report);
+
+    // Assert
+    let messages: Vec<_> = report.errors.iter().map(|e| e.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec!["invalid spa_fallback document: ../index.html"]
+    );
+}
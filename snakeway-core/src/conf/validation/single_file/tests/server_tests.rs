@@ -142,6 +142,69 @@ fn validate_server_threads_too_high() {
     );
 }
 
+#[test]
+fn validate_server_ws_max_connections_zero() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let server = ServerSpec {
+        ws_max_connections: Some(0),
+        ..Default::default()
+    };
+
+    // Act
+    validate_server(&server, &mut report);
+
+    // Assert
+    assert!(report.has_violations());
+    assert!(
+        report.errors[0]
+            .message
+            .contains("invalid server.ws_max_connections: 0")
+    );
+}
+
+#[test]
+fn validate_server_max_request_body_bytes_zero() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let server = ServerSpec {
+        max_request_body_bytes: Some(0),
+        ..Default::default()
+    };
+
+    // Act
+    validate_server(&server, &mut report);
+
+    // Assert
+    assert!(report.has_violations());
+    assert!(
+        report.errors[0]
+            .message
+            .contains("invalid server.max_request_body_bytes: 0")
+    );
+}
+
+#[test]
+fn validate_server_request_timeout_milliseconds_zero() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let server = ServerSpec {
+        request_timeout_milliseconds: Some(0),
+        ..Default::default()
+    };
+
+    // Act
+    validate_server(&server, &mut report);
+
+    // Assert
+    assert!(report.has_violations());
+    assert!(
+        report.errors[0]
+            .message
+            .contains("invalid server.request_timeout_milliseconds: 0ms")
+    );
+}
+
 #[test]
 fn validate_server_pid_file_parent_is_not_a_dir() {
     // Arrange
@@ -218,3 +281,184 @@ fn validate_server_valid_pid_and_ca_files() {
     // Assert
     assert!(!report.has_violations());
 }
+
+#[test]
+fn validate_server_shutdown_timeout_out_of_range() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let server = ServerSpec {
+        shutdown_timeout_seconds: Some(0),
+        ..Default::default()
+    };
+
+    // Act
+    validate_server(&server, &mut report);
+
+    // Assert
+    assert!(report.errors.iter().any(|e| {
+        e.message
+            .contains("invalid server.shutdown_timeout_seconds")
+    }));
+}
+
+#[test]
+fn validate_server_shutdown_readiness_grace_out_of_range() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let server = ServerSpec {
+        shutdown_readiness_grace_seconds: Some(3600),
+        ..Default::default()
+    };
+
+    // Act
+    validate_server(&server, &mut report);
+
+    // Assert
+    assert!(report.errors.iter().any(|e| {
+        e.message
+            .contains("invalid server.shutdown_readiness_grace_seconds")
+    }));
+}
+
+#[test]
+fn validate_server_reuse_port_with_threads() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let server = ServerSpec {
+        reuse_port: true,
+        threads: Some(4),
+        ..Default::default()
+    };
+
+    // Act
+    validate_server(&server, &mut report);
+
+    // Assert
+    assert!(!report.has_violations());
+}
+
+#[test]
+fn validate_server_reuse_port_with_single_thread_warns() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let server = ServerSpec {
+        reuse_port: true,
+        ..Default::default()
+    };
+
+    // Act
+    validate_server(&server, &mut report);
+
+    // Assert
+    assert!(report.errors.is_empty());
+    assert_eq!(
+        report.warnings[0].message,
+        "reuse_port is enabled but the server runs a single worker thread"
+    );
+}
+
+#[test]
+fn validate_server_connection_log_sample_rate_out_of_range() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let server = ServerSpec {
+        connection_log_sample_rate: Some(1.5),
+        ..Default::default()
+    };
+
+    // Act
+    validate_server(&server, &mut report);
+
+    // Assert
+    assert!(report.errors.iter().any(|e| {
+        e.message
+            .contains("invalid server.connection_log_sample_rate: 1.5")
+    }));
+}
+
+#[test]
+fn validate_server_connection_log_sample_rate_in_range() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let server = ServerSpec {
+        connection_log_sample_rate: Some(0.01),
+        ..Default::default()
+    };
+
+    // Act
+    validate_server(&server, &mut report);
+
+    // Assert
+    assert!(!report.has_violations());
+}
+
+#[test]
+fn validate_server_trusted_proxies_rejects_invalid_and_catch_all_networks() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let server = ServerSpec {
+        trusted_proxies: vec![
+            "10.0.0.0/8".to_string(),
+            "proxy".to_string(),
+            "::/0".to_string(),
+        ],
+        ..Default::default()
+    };
+
+    // Act
+    validate_server(&server, &mut report);
+
+    // Assert
+    assert_eq!(report.errors.len(), 2);
+    assert_eq!(report.errors[0].message, "invalid trusted proxy: proxy");
+    assert!(
+        report.errors[1]
+            .message
+            .starts_with("trusted_proxies must not contain a catch-all network")
+    );
+}
+
+#[test]
+fn validate_server_access_log_format_accepts_known_fields() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let server = ServerSpec {
+        access_log: true,
+        access_log_format: Some("$remote_addr \"$method $path\" $status $request_time".to_string()),
+        ..Default::default()
+    };
+
+    // Act
+    validate_server(&server, &mut report);
+
+    // Assert
+    assert!(!report.has_violations());
+}
+
+#[test]
+fn validate_server_access_log_format_rejects_unknown_field() {
+    // Arrange
+    let mut report = ValidationReport::default();
+    let server = ServerSpec {
+        access_log: true,
+        access_log_format: Some("$client_ip $referer $status".to_string()),
+        ..Default::default()
+    };
+
+    // Act
+    validate_server(&server, &mut report);
+
+    // Assert
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(
+        report.errors[0].message,
+        "access_log_format uses unknown field $referer"
+    );
+    assert!(
+        report.errors[0]
+            .help
+            .as_deref()
+            .unwrap()
+            .contains("$client_ip")
+    );
+}
@@ -0,0 +1,310 @@
+use crate::conf::types::{
+    BindAdminSpec, BindInterfaceInput, BindSpec, DeviceSpec, HealthCheckConfig, IngressSpec,
+    ResponseHeaderSpec, ResponseHeadersDeviceSpec, ServiceSpec, TlsSpec, UpstreamConnectionSpec,
+    UpstreamSpec,
+};
+use crate::conf::validation::lint_spec;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+
+fn upstream() -> UpstreamSpec {
+    UpstreamSpec {
+        sock: Some("/tmp/upstream.sock".to_string()),
+        ..Default::default()
+    }
+}
+
+fn timeouts() -> Option<UpstreamConnectionSpec> {
+    Some(UpstreamConnectionSpec {
+        connect_timeout_milliseconds: Some(1_000),
+        read_timeout_milliseconds: Some(10_000),
+        ..Default::default()
+    })
+}
+
+fn service(upstreams: usize) -> ServiceSpec {
+    ServiceSpec {
+        upstreams: (0..upstreams).map(|_| upstream()).collect(),
+        connection: timeouts(),
+        ..Default::default()
+    }
+}
+
+fn ingress_with_service(service: ServiceSpec) -> IngressSpec {
+    IngressSpec {
+        services: vec![service],
+        ..Default::default()
+    }
+}
+
+fn tls_ingress(devices: Vec<String>) -> IngressSpec {
+    IngressSpec {
+        bind: Some(BindSpec {
+            port: 8443,
+            tls: Some(TlsSpec::default()),
+            devices,
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+fn admin_ingress(interface: &str) -> IngressSpec {
+    IngressSpec {
+        bind_admin: Some(BindAdminSpec {
+            interface: BindInterfaceInput::Keyword(interface.to_string()),
+            port: 8440,
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+fn response_headers(headers: &[(&str, &str)]) -> DeviceSpec {
+    DeviceSpec::ResponseHeaders(ResponseHeadersDeviceSpec {
+        enable: true,
+        headers: headers
+            .iter()
+            .map(|(name, value)| ResponseHeaderSpec {
+                name: name.to_string(),
+                value: value.to_string(),
+            })
+            .collect(),
+        ..Default::default()
+    })
+}
+
+fn messages(ingresses: &[IngressSpec], devices: &[DeviceSpec]) -> Vec<String> {
+    let report = lint_spec(ingresses, devices);
+    assert!(report.errors.is_empty());
+    report.warnings.into_iter().map(|w| w.message).collect()
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+
+#[test]
+fn lint_service_missing_timeouts_fires() {
+    // Arrange
+    let ingress = ingress_with_service(ServiceSpec {
+        connection: None,
+        ..service(1)
+    });
+
+    // Act
+    let messages = messages(&[ingress], &[]);
+
+    // Assert
+    assert_eq!(
+        messages,
+        vec!["service relies on default upstream timeouts"]
+    );
+}
+
+#[test]
+fn lint_service_timeouts_set_per_upstream_is_quiet() {
+    // Arrange
+    let ingress = ingress_with_service(ServiceSpec {
+        connection: None,
+        upstreams: vec![UpstreamSpec {
+            connection: timeouts(),
+            ..upstream()
+        }],
+        ..Default::default()
+    });
+
+    // Act
+    let messages = messages(&[ingress], &[]);
+
+    // Assert
+    assert!(messages.is_empty());
+}
+
+#[test]
+fn lint_multi_upstream_service_without_health_check_fires() {
+    // Arrange
+    let ingress = ingress_with_service(service(2));
+
+    // Act
+    let messages = messages(&[ingress], &[]);
+
+    // Assert
+    assert_eq!(
+        messages,
+        vec!["service has 2 upstreams but no health check"]
+    );
+}
+
+#[test]
+fn lint_multi_upstream_service_with_disabled_health_check_fires() {
+    // Arrange
+    let ingress = ingress_with_service(ServiceSpec {
+        health_check: Some(HealthCheckConfig {
+            enable: false,
+            ..Default::default()
+        }),
+        ..service(2)
+    });
+
+    // Act
+    let messages = messages(&[ingress], &[]);
+
+    // Assert
+    assert_eq!(
+        messages,
+        vec!["service has 2 upstreams but no health check"]
+    );
+}
+
+#[test]
+fn lint_health_checked_or_single_upstream_service_is_quiet() {
+    // Arrange
+    let checked = ingress_with_service(ServiceSpec {
+        health_check: Some(HealthCheckConfig {
+            enable: true,
+            ..Default::default()
+        }),
+        ..service(2)
+    });
+    let single = ingress_with_service(service(1));
+
+    // Act
+    let messages = messages(&[checked, single], &[]);
+
+    // Assert
+    assert!(messages.is_empty());
+}
+
+#[test]
+fn lint_tls_without_hsts_fires() {
+    // Arrange
+    let devices = vec![response_headers(&[("X-Served-By", "snakeway")])];
+
+    // Act
+    let messages = messages(&[tls_ingress(vec![])], &devices);
+
+    // Assert
+    assert_eq!(
+        messages,
+        vec!["tls listener does not send Strict-Transport-Security"]
+    );
+}
+
+#[test]
+fn lint_tls_with_global_hsts_is_quiet() {
+    // Arrange
+    let devices = vec![response_headers(&[(
+        "strict-transport-security",
+        "max-age=31536000",
+    )])];
+
+    // Act
+    let messages = messages(&[tls_ingress(vec![])], &devices);
+
+    // Assert
+    assert!(messages.is_empty());
+}
+
+#[test]
+fn lint_tls_with_hsts_attached_elsewhere_fires() {
+    // Arrange
+    let devices = vec![response_headers(&[(
+        "Strict-Transport-Security",
+        "max-age=31536000",
+    )])];
+    let attached = tls_ingress(vec!["response_headers".to_string()]);
+    let unattached = tls_ingress(vec![]);
+
+    // Act
+    let messages = messages(&[attached, unattached], &devices);
+
+    // Assert
+    assert_eq!(
+        messages,
+        vec!["tls listener does not send Strict-Transport-Security"]
+    );
+}
+
+#[test]
+fn lint_plain_listener_without_hsts_is_quiet() {
+    // Arrange
+    let ingress = IngressSpec {
+        bind: Some(BindSpec {
+            port: 8080,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    // Act
+    let messages = messages(&[ingress], &[]);
+
+    // Assert
+    assert!(messages.is_empty());
+}
+
+#[test]
+fn lint_cors_any_origin_with_credentials_fires() {
+    // Arrange
+    let devices = vec![response_headers(&[
+        ("Access-Control-Allow-Origin", "*"),
+        ("Access-Control-Allow-Credentials", "true"),
+    ])];
+
+    // Act
+    let messages = messages(&[], &devices);
+
+    // Assert
+    assert_eq!(
+        messages,
+        vec!["response headers allow any CORS origin with credentials"]
+    );
+}
+
+#[test]
+fn lint_cors_explicit_origin_with_credentials_is_quiet() {
+    // Arrange
+    let devices = vec![response_headers(&[
+        ("Access-Control-Allow-Origin", "https://app.example.com"),
+        ("Access-Control-Allow-Credentials", "true"),
+    ])];
+
+    // Act
+    let messages = messages(&[], &devices);
+
+    // Assert
+    assert!(messages.is_empty());
+}
+
+#[test]
+fn lint_admin_bound_to_all_interfaces_fires() {
+    // Arrange
+    let ingresses = [admin_ingress("all"), admin_ingress("::")];
+
+    // Act
+    let messages = messages(&ingresses, &[]);
+
+    // Assert
+    assert_eq!(
+        messages,
+        vec![
+            "admin listener is bound to all interfaces",
+            "admin listener is bound to all interfaces"
+        ]
+    );
+}
+
+#[test]
+fn lint_admin_bound_to_loopback_is_quiet() {
+    // Arrange
+    let ingresses = [admin_ingress("loopback"), admin_ingress("10.0.0.5")];
+
+    // Act
+    let messages = messages(&ingresses, &[]);
+
+    // Assert
+    assert!(messages.is_empty());
+}
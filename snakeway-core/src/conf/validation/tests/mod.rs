@@ -0,0 +1 @@
+mod lint_tests;
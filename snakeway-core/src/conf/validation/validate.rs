@@ -15,7 +15,12 @@ pub fn validate_spec(
     if single_file::validate_version(server, &mut report) {
         single_file::validate_server(server, &mut report);
         single_file::validate_ingresses(ingresses, &mut report);
+        single_file::validate_strict_host(server, ingresses, &mut report);
+        single_file::validate_redirect_cycles(ingresses, &mut report);
         single_file::validate_devices(devices, &mut report);
+        single_file::validate_geoip_databases(server, devices, &mut report);
+        single_file::validate_wasm_devices(devices, server.strict_wasm_devices(), &mut report);
+        single_file::validate_device_attachments(ingresses, devices, &mut report);
     }
     report
 }
@@ -12,6 +12,27 @@ pub fn validate_http_header_name(header: &str, report: &mut ValidationReport, or
     }
 }
 
+/// Content type patterns are `type/subtype` or `type/*`, without parameters.
+pub fn validate_content_type_pattern(
+    pattern: &str,
+    report: &mut ValidationReport,
+    origin: &Origin,
+) {
+    let valid = pattern.split_once('/').is_some_and(|(type_, subtype)| {
+        is_mime_token(type_) && (subtype == "*" || is_mime_token(subtype))
+    });
+
+    if !valid {
+        report.invalid_content_type_pattern(pattern, origin);
+    }
+}
+
+fn is_mime_token(s: &str) -> bool {
+    !s.is_empty()
+        && s.bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$&-^_.+".contains(&b))
+}
+
 pub fn validate_http_method(method: &str, report: &mut ValidationReport, origin: &Origin) {
     if Method::from_bytes(method.as_bytes()).is_err() {
         report.invalid_http_method(method, origin);
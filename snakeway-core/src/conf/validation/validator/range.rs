@@ -37,6 +37,104 @@ pub const CB_SUCCESS_THRESHOLD: RangeConstraint<u32> = RangeConstraint {
     units: None,
 };
 
+pub const CB_OPEN_BACKOFF_MULTIPLIER: RangeConstraint<u32> = RangeConstraint {
+    min: 1,
+    max: 10,
+    label: "circuit_breaker.open_backoff.multiplier",
+    units: None,
+};
+
+pub const CB_MAX_OPEN_DURATION_MS: RangeConstraint<u64> = RangeConstraint {
+    min: 1,
+    max: 24 * 60 * 60 * 1000,
+    label: "circuit_breaker.open_backoff.max_open_duration_milliseconds",
+    units: Some("ms"),
+};
+
+pub const ACTIVE_HC_INTERVAL_MS: RangeConstraint<u64> = RangeConstraint {
+    min: 100,
+    max: 60 * 60 * 1000,
+    label: "health_check.active.interval_milliseconds",
+    units: Some("ms"),
+};
+
+pub const ACTIVE_HC_TIMEOUT_MS: RangeConstraint<u64> = RangeConstraint {
+    min: 1,
+    max: 60 * 1000,
+    label: "health_check.active.timeout_milliseconds",
+    units: Some("ms"),
+};
+
+pub const ACTIVE_HC_FAILURE_THRESHOLD: RangeConstraint<u32> = RangeConstraint {
+    min: 1,
+    max: 10_000,
+    label: "health_check.active.failure_threshold",
+    units: None,
+};
+
+pub const ACTIVE_HC_SUCCESS_THRESHOLD: RangeConstraint<u32> = RangeConstraint {
+    min: 1,
+    max: 10_000,
+    label: "health_check.active.success_threshold",
+    units: None,
+};
+
+pub const OD_CONSECUTIVE_5XX: RangeConstraint<u32> = RangeConstraint {
+    min: 1,
+    max: 10_000,
+    label: "outlier_detection.consecutive_5xx",
+    units: None,
+};
+
+pub const OD_BASE_EJECTION_TIME_MS: RangeConstraint<u64> = RangeConstraint {
+    min: 1,
+    max: 60 * 60 * 1000,
+    label: "outlier_detection.base_ejection_time_milliseconds",
+    units: Some("ms"),
+};
+
+pub const OD_MAX_EJECTION_TIME_MS: RangeConstraint<u64> = RangeConstraint {
+    min: 1,
+    max: 24 * 60 * 60 * 1000,
+    label: "outlier_detection.max_ejection_time_milliseconds",
+    units: Some("ms"),
+};
+
+pub const RETRY_MAX_RETRIES: RangeConstraint<u32> = RangeConstraint {
+    min: 1,
+    max: 10,
+    label: "retry_policy.max_retries",
+    units: None,
+};
+
+pub const RETRY_BUDGET_PERCENT: RangeConstraint<u32> = RangeConstraint {
+    min: 1,
+    max: 100,
+    label: "retry_policy.budget_percent",
+    units: Some("%"),
+};
+
+pub const RETRY_MIN_RETRY_CONCURRENCY: RangeConstraint<u32> = RangeConstraint {
+    min: 0,
+    max: 10_000,
+    label: "retry_policy.min_retry_concurrency",
+    units: None,
+};
+
+pub const RETRY_ON_STATUS: RangeConstraint<u16> = RangeConstraint {
+    min: 400,
+    max: 599,
+    label: "retry_policy.retry_on_status",
+    units: None,
+};
+
+pub const KETAMA_REPLICAS: RangeConstraint<u32> = RangeConstraint {
+    min: 1,
+    max: 10_000,
+    label: "ketama.replicas",
+    units: None,
+};
+
 pub const SERVER_THREADS: RangeConstraint<usize> = RangeConstraint {
     min: 1,
     max: 1024,
@@ -44,6 +142,48 @@ pub const SERVER_THREADS: RangeConstraint<usize> = RangeConstraint {
     units: None,
 };
 
+pub const SERVER_WS_MAX_CONNECTIONS: RangeConstraint<usize> = RangeConstraint {
+    min: 1,
+    max: 1_000_000,
+    label: "server.ws_max_connections",
+    units: None,
+};
+
+pub const SERVER_MAX_REQUEST_BODY_BYTES: RangeConstraint<u64> = RangeConstraint {
+    min: 1,
+    max: 64 * 1024 * 1024 * 1024,
+    label: "server.max_request_body_bytes",
+    units: None,
+};
+
+pub const SERVER_REQUEST_TIMEOUT_MS: RangeConstraint<u64> = RangeConstraint {
+    min: 1,
+    max: 24 * 60 * 60 * 1000,
+    label: "server.request_timeout_milliseconds",
+    units: Some("ms"),
+};
+
+pub const SERVER_CONNECTION_LOG_SAMPLE_RATE: RangeConstraint<f64> = RangeConstraint {
+    min: 0.0,
+    max: 1.0,
+    label: "server.connection_log_sample_rate",
+    units: None,
+};
+
+pub const SERVER_SHUTDOWN_TIMEOUT_SECONDS: RangeConstraint<u64> = RangeConstraint {
+    min: 1,
+    max: 60 * 60,
+    label: "server.shutdown_timeout_seconds",
+    units: Some("s"),
+};
+
+pub const SERVER_SHUTDOWN_READINESS_GRACE_SECONDS: RangeConstraint<u64> = RangeConstraint {
+    min: 0,
+    max: 5 * 60,
+    label: "server.shutdown_readiness_grace_seconds",
+    units: Some("s"),
+};
+
 pub const REDIRECT_RESPONSE_CODE: RangeConstraint<u16> = RangeConstraint {
     min: 300,
     max: 399,
@@ -51,6 +191,209 @@ pub const REDIRECT_RESPONSE_CODE: RangeConstraint<u16> = RangeConstraint {
     units: None,
 };
 
+pub const UPSTREAM_CONNECT_TIMEOUT_MS: RangeConstraint<u64> = RangeConstraint {
+    min: 1,
+    max: 60 * 1000,
+    label: "connection.connect_timeout_milliseconds",
+    units: Some("ms"),
+};
+
+pub const UPSTREAM_READ_TIMEOUT_MS: RangeConstraint<u64> = RangeConstraint {
+    min: 1,
+    max: 60 * 60 * 1000,
+    label: "connection.read_timeout_milliseconds",
+    units: Some("ms"),
+};
+
+pub const UPSTREAM_IDLE_TIMEOUT_SECONDS: RangeConstraint<u64> = RangeConstraint {
+    min: 0,
+    max: 60 * 60,
+    label: "connection.idle_timeout_seconds",
+    units: Some("s"),
+};
+
+pub const UPSTREAM_RESPONSE_IDLE_TIMEOUT_MS: RangeConstraint<u64> = RangeConstraint {
+    min: 0,
+    max: 60 * 60 * 1000,
+    label: "connection.response_idle_timeout_milliseconds",
+    units: Some("ms"),
+};
+
+pub const UPSTREAM_MAX_CONNECT_RETRIES: RangeConstraint<u32> = RangeConstraint {
+    min: 0,
+    max: 10,
+    label: "connection.max_connect_retries",
+    units: None,
+};
+
+pub const RESPONSE_RATE_LIMIT_BYTES_PER_SECOND: RangeConstraint<u64> = RangeConstraint {
+    min: 1,
+    max: 10 * 1024 * 1024 * 1024,
+    label: "response_rate_limit.bytes_per_second",
+    units: None,
+};
+
+pub const RESPONSE_RATE_LIMIT_BURST_BYTES: RangeConstraint<u64> = RangeConstraint {
+    min: 1,
+    max: 10 * 1024 * 1024 * 1024,
+    label: "response_rate_limit.burst_bytes",
+    units: None,
+};
+
+pub const ROUTE_MAX_REQUEST_BODY_BYTES: RangeConstraint<u64> = RangeConstraint {
+    min: 1,
+    max: 64 * 1024 * 1024 * 1024,
+    label: "route.max_request_body_bytes",
+    units: None,
+};
+
+pub const ROUTE_REQUEST_TIMEOUT_MS: RangeConstraint<u64> = RangeConstraint {
+    min: 1,
+    max: 24 * 60 * 60 * 1000,
+    label: "route.request_timeout_milliseconds",
+    units: Some("ms"),
+};
+
+pub const ROUTE_AFFINITY_TTL_SECONDS: RangeConstraint<u64> = RangeConstraint {
+    min: 1,
+    max: 30 * 24 * 60 * 60,
+    label: "affinity_ttl_seconds",
+    units: Some("s"),
+};
+
+pub const WASM_DEVICE_FUEL: RangeConstraint<u64> = RangeConstraint {
+    min: 1_000,
+    max: 1_000_000_000_000,
+    label: "wasm_device.fuel",
+    units: None,
+};
+
+pub const IDENTITY_UA_CACHE_CAPACITY: RangeConstraint<usize> = RangeConstraint {
+    min: 0,
+    max: 1_000_000,
+    label: "identity_device.ua_cache_capacity",
+    units: None,
+};
+
+pub const QUOTA_TIMEOUT_MS: RangeConstraint<u64> = RangeConstraint {
+    min: 1,
+    max: 60 * 1000,
+    label: "quota_device.timeout_milliseconds",
+    units: Some("ms"),
+};
+
+pub const QUOTA_CACHE_TTL_MS: RangeConstraint<u64> = RangeConstraint {
+    min: 0,
+    max: 60 * 60 * 1000,
+    label: "quota_device.cache_ttl_milliseconds",
+    units: Some("ms"),
+};
+
+pub const OPENAPI_MAX_BODY_BYTES: RangeConstraint<usize> = RangeConstraint {
+    min: 1,
+    max: 100 * 1024 * 1024,
+    label: "openapi_device.max_body_bytes",
+    units: None,
+};
+
+pub const JWT_AUTH_LEEWAY_SECONDS: RangeConstraint<u64> = RangeConstraint {
+    min: 0,
+    max: 60 * 60,
+    label: "jwt_auth_device.leeway_seconds",
+    units: Some("s"),
+};
+
+pub const JWT_AUTH_JWKS_REFRESH_SECONDS: RangeConstraint<u64> = RangeConstraint {
+    min: 1,
+    max: 24 * 60 * 60,
+    label: "jwt_auth_device.jwks_refresh_seconds",
+    units: Some("s"),
+};
+
+pub const JWT_AUTH_TIMEOUT_MS: RangeConstraint<u64> = RangeConstraint {
+    min: 1,
+    max: 60 * 1000,
+    label: "jwt_auth_device.timeout_milliseconds",
+    units: Some("ms"),
+};
+
+pub const IP_REPUTATION_TIMEOUT_MS: RangeConstraint<u64> = RangeConstraint {
+    min: 1,
+    max: 60 * 1000,
+    label: "ip_reputation_device.timeout_milliseconds",
+    units: Some("ms"),
+};
+
+pub const IP_REPUTATION_CACHE_TTL_MS: RangeConstraint<u64> = RangeConstraint {
+    min: 0,
+    max: 24 * 60 * 60 * 1000,
+    label: "ip_reputation_device.cache_ttl_milliseconds",
+    units: Some("ms"),
+};
+
+pub const IP_REPUTATION_BLOCK_STATUS: RangeConstraint<u16> = RangeConstraint {
+    min: 400,
+    max: 599,
+    label: "ip_reputation_device.block_status",
+    units: None,
+};
+
+pub const RATE_LIMIT_REQUESTS_PER_SECOND: RangeConstraint<u32> = RangeConstraint {
+    min: 1,
+    max: 1_000_000,
+    label: "rate_limit_device.requests_per_second",
+    units: None,
+};
+
+pub const RATE_LIMIT_BURST: RangeConstraint<u32> = RangeConstraint {
+    min: 1,
+    max: 1_000_000,
+    label: "rate_limit_device.burst",
+    units: None,
+};
+
+pub const EXPERIMENT_VARIANT_WEIGHT: RangeConstraint<u32> = RangeConstraint {
+    min: 0,
+    max: 10_000,
+    label: "experiment_device.variants.weight",
+    units: None,
+};
+
+pub const CORS_MAX_AGE_SECONDS: RangeConstraint<u32> = RangeConstraint {
+    min: 0,
+    max: 24 * 60 * 60,
+    label: "cors_device.max_age_seconds",
+    units: Some("s"),
+};
+
+pub const IDEMPOTENCY_TTL_SECONDS: RangeConstraint<u64> = RangeConstraint {
+    min: 1,
+    max: 7 * 24 * 60 * 60,
+    label: "idempotency_device.ttl_seconds",
+    units: Some("s"),
+};
+
+pub const IDEMPOTENCY_WAIT_TIMEOUT_MS: RangeConstraint<u64> = RangeConstraint {
+    min: 1,
+    max: 5 * 60 * 1000,
+    label: "idempotency_device.wait_timeout_milliseconds",
+    units: Some("ms"),
+};
+
+pub const IDEMPOTENCY_MAX_BODY_BYTES: RangeConstraint<usize> = RangeConstraint {
+    min: 1,
+    max: 64 * 1024 * 1024,
+    label: "idempotency_device.max_body_bytes",
+    units: None,
+};
+
+pub const BODY_DIGEST_MAX_BODY_BYTES: RangeConstraint<usize> = RangeConstraint {
+    min: 1,
+    max: 1024 * 1024 * 1024,
+    label: "body_digest_device.max_body_bytes",
+    units: None,
+};
+
 pub const REQUEST_FILTER_DENY_STATUS: RangeConstraint<u16> = RangeConstraint {
     min: 400,
     max: 599,
@@ -58,6 +401,41 @@ pub const REQUEST_FILTER_DENY_STATUS: RangeConstraint<u16> = RangeConstraint {
     units: None,
 };
 
+pub const STATUS_REMAP_FROM: RangeConstraint<u16> = RangeConstraint {
+    min: 200,
+    max: 599,
+    label: "status_remap_device.remaps.from",
+    units: None,
+};
+
+pub const STATUS_REMAP_TO: RangeConstraint<u16> = RangeConstraint {
+    min: 200,
+    max: 599,
+    label: "status_remap_device.remaps.to",
+    units: None,
+};
+
+pub const LUA_INSTRUCTION_LIMIT: RangeConstraint<u64> = RangeConstraint {
+    min: 1_000,
+    max: 1_000_000_000,
+    label: "lua_device.instruction_limit",
+    units: None,
+};
+
+pub const LUA_MEMORY_LIMIT_BYTES: RangeConstraint<usize> = RangeConstraint {
+    min: 1024 * 1024,
+    max: 1024 * 1024 * 1024,
+    label: "lua_device.memory_limit_bytes",
+    units: None,
+};
+
+pub const LUA_MAX_BODY_BYTES: RangeConstraint<usize> = RangeConstraint {
+    min: 1,
+    max: 16 * 1024 * 1024,
+    label: "lua_device.max_body_bytes",
+    units: None,
+};
+
 pub fn validate_range<T>(
     value: T,
     constraint: &RangeConstraint<T>,
@@ -3,7 +3,10 @@ pub mod response_ctx;
 mod ws_close_ctx;
 mod ws_ctx;
 
-pub use request::{NormalizedPath, NormalizedRequest, RequestCtx, RequestId, RequestRejectError};
+pub use request::{
+    ListenerInfo, NormalizedPath, NormalizedRequest, RequestCtx, RequestId, RequestRejectError,
+    encode_query_component, join_query, parse_query,
+};
 pub use response_ctx::ResponseCtx;
 pub use ws_close_ctx::*;
 pub use ws_ctx::*;
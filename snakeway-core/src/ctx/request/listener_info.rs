@@ -0,0 +1,27 @@
+use crate::conf::types::ListenerConfig;
+use std::sync::Arc;
+
+/// The listener a request arrived on.
+///
+/// Devices can branch on it, e.g. to apply stricter checks on the admin listener.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListenerInfo {
+    /// Name of the listener, unique among listeners.
+    pub name: Arc<str>,
+
+    /// Whether the listener serves admin endpoints.
+    pub admin: bool,
+
+    /// Whether the listener terminates TLS.
+    pub tls: bool,
+}
+
+impl From<&ListenerConfig> for ListenerInfo {
+    fn from(cfg: &ListenerConfig) -> Self {
+        Self {
+            name: Arc::from(cfg.name.as_str()),
+            admin: cfg.enable_admin,
+            tls: cfg.tls.is_some(),
+        }
+    }
+}
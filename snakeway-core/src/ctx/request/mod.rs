@@ -1,4 +1,5 @@
 mod error;
+mod listener_info;
 mod normalization;
 mod normalized_request;
 mod request_ctx;
@@ -7,6 +8,8 @@ mod request_id;
 mod tests;
 
 pub use error::*;
+pub use listener_info::ListenerInfo;
+pub use normalization::{encode_query_component, join_query, parse_query};
 pub use normalized_request::*;
 pub use request_ctx::*;
 pub use request_id::*;
@@ -38,28 +38,10 @@ pub fn normalize_query(query: &str) -> NormalizationOutcome<CanonicalQuery> {
         };
     }
 
-    let mut decoded_rewrite = false;
-    let mut pairs = Vec::new();
-
-    for part in query.split('&') {
-        let (raw_key, raw_val) = match part.split_once('=') {
-            Some((k, v)) => (k, v),
-            None => (part, ""),
-        };
-
-        let (key, key_rewritten) = match percent_decode_unreserved_with_outcome(raw_key) {
-            Ok(v) => v,
-            Err(r) => return NormalizationOutcome::Reject { reason: r },
-        };
-
-        let (val, val_rewritten) = match percent_decode_unreserved_with_outcome(raw_val) {
-            Ok(v) => v,
-            Err(r) => return NormalizationOutcome::Reject { reason: r },
-        };
-
-        decoded_rewrite |= key_rewritten || val_rewritten;
-        pairs.push((key, val));
-    }
+    let (pairs, decoded_rewrite) = match split_query(query) {
+        Ok(v) => v,
+        Err(reason) => return NormalizationOutcome::Reject { reason },
+    };
 
     // Canonical ordering (Phase 3A)
     let mut sorted = pairs.clone();
@@ -84,6 +66,74 @@ pub fn normalize_query(query: &str) -> NormalizationOutcome<CanonicalQuery> {
     }
 }
 
+/// Splits a query string into key/value pairs in request order, decoding unreserved characters.
+///
+/// Unlike [`normalize_query`], pairs are not sorted, so the result can be rewritten and joined
+/// back into a query with [`join_query`] without reordering repeated keys.
+pub fn parse_query(query: &str) -> Result<Vec<(String, String)>, RejectReason> {
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    if query.as_bytes().contains(&0) {
+        return Err(RejectReason::InvalidQueryEncoding);
+    }
+
+    split_query(query).map(|(pairs, _)| pairs)
+}
+
+/// Joins key/value pairs into a query string, without the leading `?`.
+///
+/// Keys and values are written as they are, so they must already be encoded.
+/// A key with an empty value is written without `=`.
+pub fn join_query(pairs: &[(String, String)]) -> String {
+    pairs
+        .iter()
+        .map(|(key, value)| {
+            if value.is_empty() {
+                key.clone()
+            } else {
+                format!("{key}={value}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Percent-encodes everything but unreserved characters, for query keys and values that did
+/// not come from a request (e.g. config). Uses uppercase hexadecimal per RFC 3986 Section 2.1.
+pub fn encode_query_component(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    out
+}
+
+/// Split a query into pairs in request order, and report whether any component was decoded.
+fn split_query(query: &str) -> Result<(Vec<(String, String)>, bool), RejectReason> {
+    let mut decoded_rewrite = false;
+    let mut pairs = Vec::new();
+
+    for part in query.split('&') {
+        let (raw_key, raw_val) = match part.split_once('=') {
+            Some((k, v)) => (k, v),
+            None => (part, ""),
+        };
+
+        let (key, key_rewritten) = percent_decode_unreserved_with_outcome(raw_key)?;
+        let (val, val_rewritten) = percent_decode_unreserved_with_outcome(raw_val)?;
+
+        decoded_rewrite |= key_rewritten || val_rewritten;
+        pairs.push((key, val));
+    }
+
+    Ok((pairs, decoded_rewrite))
+}
+
 fn percent_decode_unreserved_with_outcome(input: &str) -> Result<(String, bool), RejectReason> {
     let decoded =
         percent_decode_unreserved(input).map_err(|_| RejectReason::InvalidPercentEncoding)?;
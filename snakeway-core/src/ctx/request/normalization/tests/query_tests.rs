@@ -1,4 +1,6 @@
-use crate::ctx::request::normalization::query::normalize_query;
+use crate::ctx::request::normalization::query::{
+    encode_query_component, join_query, normalize_query, parse_query,
+};
 use crate::ctx::request::normalization::{NormalizationOutcome, RejectReason, RewriteReason};
 use pretty_assertions::assert_eq;
 
@@ -160,3 +162,55 @@ fn reject_truncated_percent_encoding() {
 fn reject_nul_in_query() {
     assert_reject_query("a=1\0", RejectReason::InvalidQueryEncoding);
 }
+
+//-----------------------------------------------------------------------------
+// Parse, join, and encode
+//-----------------------------------------------------------------------------
+#[test]
+fn parse_query_keeps_request_order() {
+    // Act
+    let pairs = parse_query("b=2&a=%41&b=1&flag").unwrap();
+
+    // Assert
+    assert_eq!(
+        pairs,
+        vec![
+            ("b".to_string(), "2".to_string()),
+            ("a".to_string(), "A".to_string()),
+            ("b".to_string(), "1".to_string()),
+            ("flag".to_string(), String::new()),
+        ]
+    );
+}
+
+#[test]
+fn parse_query_rejects_invalid_encoding() {
+    assert_eq!(
+        parse_query("a=%ZZ"),
+        Err(RejectReason::InvalidPercentEncoding)
+    );
+    assert_eq!(
+        parse_query("a=1\0"),
+        Err(RejectReason::InvalidQueryEncoding)
+    );
+}
+
+#[test]
+fn join_query_round_trips_parsed_pairs() {
+    // Arrange
+    let pairs = parse_query("b=2&q=a%20b&flag").unwrap();
+
+    // Act
+    let query = join_query(&pairs);
+
+    // Assert
+    assert_eq!(query, "b=2&q=a%20b&flag");
+}
+
+#[test]
+fn encode_query_component_escapes_reserved_characters() {
+    assert_eq!(
+        encode_query_component("a b&c=d/é~"),
+        "a%20b%26c%3Dd%2F%C3%A9~"
+    );
+}
@@ -4,15 +4,22 @@ use crate::ctx::request::normalization::{
     NormalizationOutcome, ProtocolNormalizationMode, normalize_headers, normalize_path,
     normalize_query,
 };
-use crate::ctx::request::{NormalizedHeaders, NormalizedRequest};
+use crate::ctx::request::{ListenerInfo, NormalizedHeaders, NormalizedRequest};
 use crate::route::types::RouteId;
 use crate::runtime::UpstreamId;
+use crate::server::InFlightGuard;
+use crate::traffic_management::retry::RetryBudgetGuard;
 use crate::traffic_management::{AdmissionGuard, ServiceId, UpstreamOutcome};
 use crate::ws_connection_management::WsConnectionGuard;
-use http::{Extensions, HeaderMap, HeaderName, HeaderValue, Method, Uri, Version};
+use http::uri::Authority;
+use http::{Extensions, HeaderMap, HeaderName, HeaderValue, Method, Uri, Version, header};
+use ipnet::IpNet;
 use pingora::prelude::Session;
 use pingora::protocols::l4::socket::SocketAddr as PingoraSocketAddr;
 use std::net::{IpAddr, Ipv4Addr};
+use std::time::{Duration, Instant};
+
+const X_FORWARDED_HOST: &str = "x-forwarded-host";
 
 /// Canonical request context passed through the Snakeway pipeline
 #[derive(Debug)]
@@ -23,6 +30,18 @@ pub struct RequestCtx {
     /// It is necessary to guard requests to ensure proper circuit breaker state updates.
     pub admission_guard: Option<AdmissionGuard>,
 
+    /// Counts the request as in-flight so shutdown can drain it.
+    pub in_flight_guard: Option<InFlightGuard>,
+
+    /// Headers devices add to the downstream response.
+    pub response_headers: HeaderMap,
+
+    /// Headers devices add to the upstream request.
+    pub upstream_headers: HeaderMap,
+
+    /// Headers devices remove from the upstream request, before `upstream_headers` are added.
+    pub upstream_remove_headers: Vec<HeaderName>,
+
     /// Lifecycle flag to determine if the context has already been hydrated from a session.
     pub hydrated: bool,
 
@@ -32,9 +51,20 @@ pub struct RequestCtx {
     /// Optional override for the upstream request path
     pub upstream_path: Option<String>,
 
+    /// Optional override for the upstream query string, without the leading `?`.
+    /// When unset, the query the client sent is forwarded.
+    pub upstream_query: Option<String>,
+
+    /// Upstream group chosen by a device, e.g. `premium`.
+    /// The traffic director falls back to the default pool when unset or unknown.
+    pub upstream_group: Option<String>,
+
     /// Remote IP of the TCP connection (authoritative)
     pub peer_ip: IpAddr,
 
+    /// Listener the request arrived on. Unset for synthetic requests.
+    pub listener: Option<ListenerInfo>,
+
     /// Was a websocket connection opened?
     pub ws_opened: bool,
 
@@ -50,6 +80,9 @@ pub struct RequestCtx {
     /// Route ID for routing decisions.
     pub route_id: Option<RouteId>,
 
+    /// How long sticky strategies honor the client's upstream affinity, when the route limits it.
+    pub affinity_ttl: Option<Duration>,
+
     /// Selected upstream and outcome
     pub selected_upstream: Option<(ServiceId, UpstreamId)>,
 
@@ -58,6 +91,34 @@ pub struct RequestCtx {
 
     /// Circuit breaker started?
     pub cb_started: bool,
+
+    /// Connection attempts retried so far.
+    pub connect_retries: u32,
+
+    /// Retry limit of the currently selected upstream.
+    pub max_connect_retries: u32,
+
+    /// Counts the request against its service's retry budget, when the service retries requests.
+    pub retry_budget: Option<RetryBudgetGuard>,
+
+    /// Upstreams the request was retried away from. Retries go to other upstreams while
+    /// there are any.
+    pub retried_upstreams: Vec<UpstreamId>,
+
+    /// Request body bytes read from the client.
+    pub bytes_in: u64,
+
+    /// Largest request body accepted on the matched route. `None` if bodies are not limited.
+    pub max_request_body_bytes: Option<u64>,
+
+    /// Response body bytes sent to the client.
+    pub bytes_out: u64,
+
+    /// When the request context was created, i.e. when the request arrived.
+    pub started_at: Instant,
+
+    /// The device that answered the request instead of it being proxied, if any.
+    pub responded_by: Option<String>,
 }
 
 impl Default for RequestCtx {
@@ -71,16 +132,23 @@ impl RequestCtx {
     pub fn empty() -> Self {
         Self {
             route_id: None,
+            affinity_ttl: None,
 
             // Request lifecycle-related.
             hydrated: false,
             admission_guard: None,
+            in_flight_guard: None,
+            response_headers: HeaderMap::new(),
+            upstream_headers: HeaderMap::new(),
+            upstream_remove_headers: Vec::new(),
             ws_guard: None,
 
             // Upstream/routing related.
             service: None,
             selected_upstream: None,
             upstream_path: None,
+            upstream_query: None,
+            upstream_group: None,
 
             // Protocol flag(s) that help figure out what to do with the request.
             ws_opened: false,
@@ -92,8 +160,26 @@ impl RequestCtx {
             cb_started: false,
             upstream_outcome: None,
 
+            // Connection retries.
+            connect_retries: 0,
+            max_connect_retries: 0,
+
+            // Retries of failed responses.
+            retry_budget: None,
+            retried_upstreams: Vec::new(),
+
+            // Body byte counts, measured while streaming.
+            bytes_in: 0,
+            bytes_out: 0,
+            max_request_body_bytes: None,
+
+            // Access logging.
+            started_at: Instant::now(),
+            responded_by: None,
+
             // Peer info - filled out during hydration
             peer_ip: Ipv4Addr::UNSPECIFIED.into(),
+            listener: None,
 
             // Device related data.
             extensions: Extensions::new(),
@@ -103,6 +189,14 @@ impl RequestCtx {
         }
     }
 
+    /// An empty context for a request arriving on the given listener.
+    pub fn for_listener(listener: ListenerInfo) -> Self {
+        Self {
+            listener: Some(listener),
+            ..Self::empty()
+        }
+    }
+
     /// Create a boundary to decouple session from logic.
     /// This makes testing the hydration/normalization code easier.
     pub fn hydrate_from_session(&mut self, session: &Session) -> Result<(), RequestRejectError> {
@@ -241,6 +335,24 @@ impl RequestCtx {
     }
 }
 
+/// Listener API
+impl RequestCtx {
+    /// Name of the listener the request arrived on.
+    pub fn listener_name(&self) -> Option<&str> {
+        self.listener.as_ref().map(|l| l.name.as_ref())
+    }
+
+    /// Whether the request arrived on an admin listener.
+    pub fn is_admin_listener(&self) -> bool {
+        self.listener.as_ref().is_some_and(|l| l.admin)
+    }
+
+    /// Whether the request arrived on a listener that terminates TLS.
+    pub fn is_tls(&self) -> bool {
+        self.listener.as_ref().is_some_and(|l| l.tls)
+    }
+}
+
 /// HTTP/2 API
 impl RequestCtx {
     /// Returns the upstream authority (host:port) to use for HTTP/2 requests.
@@ -283,6 +395,81 @@ impl RequestCtx {
     }
 }
 
+/// Request Host API
+impl RequestCtx {
+    /// Host the request is addressed to, without the port.
+    ///
+    /// Taken from the `Host` header, falling back to the URI authority (e.g. HTTP/2 `:authority`).
+    pub fn host(&self) -> Option<&str> {
+        debug_assert!(self.hydrated);
+        let authority = self
+            .headers()
+            .get(header::HOST)
+            .and_then(|v| v.to_str().ok())
+            .or_else(|| {
+                self.normalized_request
+                    .original_uri()
+                    .authority()
+                    .map(|a| a.as_str())
+            })?;
+
+        strip_port(authority)
+    }
+
+    /// Host to build absolute redirect URLs for, without the port.
+    ///
+    /// Requests from a trusted proxy use its `X-Forwarded-Host`, so redirects point at the host
+    /// the client asked for rather than the internal one the proxy connected to. Otherwise, or
+    /// if that header is missing or malformed, this is the same as [`RequestCtx::host`].
+    pub fn effective_host(&self, trusted_proxies: &[IpNet]) -> Option<&str> {
+        debug_assert!(self.hydrated);
+        if !trusted_proxies
+            .iter()
+            .any(|net| net.contains(&self.peer_ip))
+        {
+            return self.host();
+        }
+
+        self.headers()
+            .get(X_FORWARDED_HOST)
+            .and_then(|v| v.to_str().ok())
+            // Proxies append to the list, so the last entry is the one the trusted proxy saw.
+            .and_then(|v| v.rsplit(',').next())
+            .map(str::trim)
+            .filter(|authority| !authority.contains('@') && authority.parse::<Authority>().is_ok())
+            .and_then(strip_port)
+            .or_else(|| self.host())
+    }
+}
+
+/// Strip a numeric port from an authority, e.g. `example.com:8443`.
+/// Returns `None` if no host is left.
+fn strip_port(authority: &str) -> Option<&str> {
+    let host = authority
+        .rsplit_once(':')
+        .filter(|(_, port)| port.parse::<u16>().is_ok())
+        .map_or(authority, |(host, _)| host);
+
+    (!host.is_empty()).then_some(host)
+}
+
+/// Request Query API
+impl RequestCtx {
+    /// Query string key/value pairs in canonical (sorted) order, with unreserved characters decoded.
+    pub fn query_pairs(&self) -> &[(String, String)] {
+        debug_assert!(self.hydrated);
+        self.normalized_request.query().pairs()
+    }
+
+    /// Query string used when proxying upstream, without the leading `?`.
+    pub fn upstream_query(&self) -> &str {
+        debug_assert!(self.hydrated);
+        self.upstream_query
+            .as_deref()
+            .unwrap_or(self.normalized_request.query().raw())
+    }
+}
+
 /// Request Path API
 impl RequestCtx {
     /// Path used when proxying upstream
@@ -292,6 +479,19 @@ impl RequestCtx {
             .unwrap_or(self.canonical_path())
     }
 
+    /// Path and query used when proxying upstream.
+    ///
+    /// An `upstream_path` override that already carries a query is used as it is.
+    pub fn upstream_uri(&self) -> String {
+        let path = self.upstream_path();
+        let query = self.upstream_query();
+        if query.is_empty() || path.contains('?') {
+            path.to_string()
+        } else {
+            format!("{path}?{query}")
+        }
+    }
+
     /// Will return the full original URI as received the proxy.
     /// This may include the scheme, host, and port.
     /// Or, just the path with an optional query string.
@@ -1,5 +1,6 @@
 use crate::ctx::{RequestCtx, RequestRejectError};
 use http::{HeaderMap, HeaderValue, Method, Uri, Version};
+use ipnet::IpNet;
 use pingora::prelude::Session;
 use pretty_assertions::assert_eq;
 use tokio::io::{AsyncWriteExt, duplex};
@@ -271,6 +272,41 @@ async fn upstream_path_returns_canonical_path_when_not_set() {
     assert_eq!(result, expected_path);
 }
 
+#[tokio::test]
+async fn upstream_uri_forwards_the_original_query() {
+    // Arrange
+    let request = RawHttpRequest::new("GET", "/books?b=2&a=1")
+        .header("Host", "example.test")
+        .build();
+    let session = make_h1_session(&request).await;
+    let mut ctx = RequestCtx::empty();
+    let _ = ctx.hydrate_from_session(&session);
+
+    // Act
+    let result = ctx.upstream_uri();
+
+    // Assert
+    assert_eq!(result, "/books?b=2&a=1");
+}
+
+#[tokio::test]
+async fn upstream_uri_uses_query_override_when_set() {
+    // Arrange
+    let request = RawHttpRequest::new("GET", "/books?b=2&a=1")
+        .header("Host", "example.test")
+        .build();
+    let session = make_h1_session(&request).await;
+    let mut ctx = RequestCtx::empty();
+    let _ = ctx.hydrate_from_session(&session);
+    ctx.upstream_query = Some("page=3".to_string());
+
+    // Act
+    let result = ctx.upstream_uri();
+
+    // Assert
+    assert_eq!(result, "/books?page=3");
+}
+
 #[tokio::test]
 async fn upstream_authority_return_none_when_not_set() {
     // Arrange
@@ -364,3 +400,124 @@ async fn original_uri_path_is_intact() {
     // Assert
     assert_eq!(result, expected_path);
 }
+
+#[tokio::test]
+async fn host_strips_port_from_host_header() {
+    // Arrange
+    let request = RawHttpRequest::new("GET", "/")
+        .header("Host", "bucket.example.test:8443")
+        .build();
+    let session = make_h1_session(&request).await;
+    let mut ctx = RequestCtx::empty();
+    let _ = ctx.hydrate_from_session(&session);
+
+    // Act
+    let result = ctx.host();
+
+    // Assert
+    assert_eq!(result, Some("bucket.example.test"));
+}
+
+#[tokio::test]
+async fn host_returns_none_without_host() {
+    // Arrange
+    let request = RawHttpRequest::new("GET", "/").build();
+    let session = make_h1_session(&request).await;
+    let mut ctx = RequestCtx::empty();
+    let _ = ctx.hydrate_from_session(&session);
+
+    // Act
+    let result = ctx.host();
+
+    // Assert
+    assert_eq!(result, None);
+}
+
+fn hydrated_from(peer_ip: &str, headers: &[(&'static str, &'static str)]) -> RequestCtx {
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+        map.append(*name, HeaderValue::from_static(*value));
+    }
+    let mut ctx = RequestCtx::empty();
+    ctx.hydrate(
+        &Uri::from_static("/login"),
+        &Method::GET,
+        &map,
+        &Version::HTTP_11,
+        false,
+        peer_ip.parse().unwrap(),
+    )
+    .unwrap();
+    ctx
+}
+
+#[test]
+fn effective_host_uses_forwarded_host_from_trusted_proxy() {
+    // Arrange
+    let trusted: Vec<IpNet> = vec!["10.0.0.0/8".parse().unwrap()];
+    let ctx = hydrated_from(
+        "10.0.0.7",
+        &[
+            ("host", "app.internal:8080"),
+            ("x-forwarded-host", "spoofed.test, www.example.com:443"),
+        ],
+    );
+
+    // Act
+    let result = ctx.effective_host(&trusted);
+
+    // Assert
+    assert_eq!(result, Some("www.example.com"));
+}
+
+#[test]
+fn effective_host_ignores_forwarded_host_from_untrusted_peer() {
+    // Arrange
+    let trusted: Vec<IpNet> = vec!["10.0.0.0/8".parse().unwrap()];
+    let ctx = hydrated_from(
+        "203.0.113.9",
+        &[
+            ("host", "app.internal:8080"),
+            ("x-forwarded-host", "www.example.com"),
+        ],
+    );
+
+    // Act
+    let result = ctx.effective_host(&trusted);
+
+    // Assert
+    assert_eq!(result, Some("app.internal"));
+}
+
+#[test]
+fn effective_host_falls_back_to_host_on_malformed_forwarded_host() {
+    // Arrange
+    let trusted: Vec<IpNet> = vec!["10.0.0.0/8".parse().unwrap()];
+    let ctx = hydrated_from(
+        "10.0.0.7",
+        &[
+            ("host", "app.internal"),
+            ("x-forwarded-host", "user@evil.test"),
+        ],
+    );
+
+    // Act
+    let result = ctx.effective_host(&trusted);
+
+    // Assert
+    assert_eq!(result, Some("app.internal"));
+}
+
+#[test]
+fn synthetic_request_has_no_listener() {
+    // Arrange
+    let ctx = RequestCtx::empty();
+
+    // Act
+    let name = ctx.listener_name();
+
+    // Assert
+    assert_eq!(name, None);
+    assert!(!ctx.is_admin_listener());
+    assert!(!ctx.is_tls());
+}
@@ -1,12 +1,17 @@
+use crate::route::RouteId;
 use http::{HeaderMap, StatusCode};
 
 #[derive(Debug)]
 pub struct ResponseCtx {
     pub request_id: Option<String>,
+    /// The route that handled the request, if one matched.
+    pub route_id: Option<RouteId>,
     pub status: StatusCode,
     pub headers: HeaderMap,
-    #[allow(dead_code)]
+    /// Response body. Set by an `after_proxy` device, it replaces the upstream body.
     pub body: Vec<u8>,
+    /// The device that short-circuited the chain with this response, if one did.
+    pub responded_by: Option<String>,
 }
 
 impl ResponseCtx {
@@ -18,9 +23,11 @@ impl ResponseCtx {
     ) -> Self {
         Self {
             request_id,
+            route_id: None,
             status,
             headers,
             body,
+            responded_by: None,
         }
     }
 }
@@ -1,2 +1,21 @@
-#[derive(Debug, Default)]
-pub struct WsCloseCtx {}
+use serde::Serialize;
+
+/// Read-only context provided when a WebSocket connection is closed.
+///
+/// Frames are proxied without being parsed, so the close code and reason are not known.
+#[derive(Debug, Default, Serialize)]
+pub struct WsCloseCtx {
+    pub request_id: Option<String>,
+
+    /// Path of the upgrade request.
+    pub path: String,
+
+    /// Bytes received from the client over the connection.
+    pub bytes_in: u64,
+
+    /// Bytes sent to the client over the connection.
+    pub bytes_out: u64,
+
+    /// The error that ended the connection, if it did not close cleanly.
+    pub error: Option<String>,
+}
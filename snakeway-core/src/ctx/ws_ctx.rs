@@ -1,4 +1,11 @@
+use serde::Serialize;
+
 /// Read-only context provided when a WebSocket connection is established.
 /// Intentionally does not expose the underlying stream.
-#[derive(Debug, Default)]
-pub struct WsCtx {}
+#[derive(Debug, Default, Serialize)]
+pub struct WsCtx {
+    pub request_id: Option<String>,
+
+    /// Path of the upgrade request.
+    pub path: String,
+}
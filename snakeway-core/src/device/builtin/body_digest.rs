@@ -0,0 +1,175 @@
+use crate::conf::types::BodyDigestDeviceConfig;
+use crate::ctx::{RequestCtx, ResponseCtx};
+use crate::device::core::{Device, DeviceResult};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use bytes::{Bytes, BytesMut};
+use http::{HeaderMap, StatusCode};
+use md5::Md5;
+use sha2::{Digest, Sha256};
+
+/// RFC 3230 instance digest header, e.g. `Digest: SHA-256=<base64>`.
+const DIGEST_HEADER: &str = "digest";
+
+/// RFC 1864 header, e.g. `Content-MD5: <base64>`.
+const CONTENT_MD5_HEADER: &str = "content-md5";
+
+/// A body digest the client expects the request body to match.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExpectedDigest {
+    Md5(Vec<u8>),
+    Sha256(Vec<u8>),
+}
+
+impl ExpectedDigest {
+    /// Read the expected digest from `Digest` or `Content-MD5`.
+    ///
+    /// `Digest` takes precedence, and SHA-256 is preferred over MD5 when both are listed.
+    /// Returns `Ok(None)` when the client sent no digest, and an error when the
+    /// digest is malformed or uses no supported algorithm.
+    pub fn from_headers(headers: &HeaderMap) -> Result<Option<Self>, &'static str> {
+        if let Some(value) = headers.get(DIGEST_HEADER) {
+            let value = value.to_str().map_err(|_| "Malformed Digest header")?;
+            return Self::from_digest_header(value).map(Some);
+        }
+
+        if let Some(value) = headers.get(CONTENT_MD5_HEADER) {
+            let digest = BASE64
+                .decode(value.as_bytes())
+                .map_err(|_| "Malformed Content-MD5 header")?;
+            return Ok(Some(Self::Md5(digest)));
+        }
+
+        Ok(None)
+    }
+
+    fn from_digest_header(value: &str) -> Result<Self, &'static str> {
+        let mut md5 = None;
+
+        for instance in value.split(',') {
+            let (algorithm, encoded) = instance
+                .trim()
+                .split_once('=')
+                .ok_or("Malformed Digest header")?;
+
+            let decode = || {
+                BASE64
+                    .decode(encoded.trim())
+                    .map_err(|_| "Malformed Digest header")
+            };
+
+            if algorithm.eq_ignore_ascii_case("sha-256") {
+                return Ok(Self::Sha256(decode()?));
+            }
+            if algorithm.eq_ignore_ascii_case("md5") {
+                md5 = Some(Self::Md5(decode()?));
+            }
+        }
+
+        md5.ok_or("Unsupported Digest algorithm")
+    }
+
+    /// Returns true if the body hashes to the expected digest.
+    pub fn matches(&self, body: &[u8]) -> bool {
+        match self {
+            ExpectedDigest::Md5(expected) => Md5::digest(body).as_slice() == expected.as_slice(),
+            ExpectedDigest::Sha256(expected) => {
+                Sha256::digest(body).as_slice() == expected.as_slice()
+            }
+        }
+    }
+}
+
+/// Verifies a client-provided `Content-MD5` or `Digest` header against the request body.
+///
+/// A request carrying a digest has its body buffered, up to `max_body_bytes`, and only
+/// forwarded once the digest matches. Mismatching bodies are rejected with `400`.
+/// Requests without a digest are streamed through untouched.
+pub struct BodyDigestDevice {
+    max_body_bytes: usize,
+}
+
+impl BodyDigestDevice {
+    pub fn from_config(cfg: BodyDigestDeviceConfig) -> anyhow::Result<Self> {
+        Ok(Self {
+            max_body_bytes: cfg.max_body_bytes,
+        })
+    }
+
+    fn deny(&self, ctx: &RequestCtx, status: StatusCode, reason: &'static str) -> DeviceResult {
+        DeviceResult::Respond(ResponseCtx::new(
+            ctx.request_id(),
+            status,
+            Default::default(),
+            reason.as_bytes().to_vec(),
+        ))
+    }
+}
+
+impl Device for BodyDigestDevice {
+    fn name(&self) -> &str {
+        "Body Digest"
+    }
+
+    fn on_request(&self, ctx: &mut RequestCtx) -> DeviceResult {
+        match ExpectedDigest::from_headers(ctx.headers()) {
+            Ok(Some(expected)) => {
+                ctx.extensions.insert(PendingBodyDigest::new(expected));
+                DeviceResult::Continue
+            }
+            Ok(None) => DeviceResult::Continue,
+            Err(reason) => self.deny(ctx, StatusCode::BAD_REQUEST, reason),
+        }
+    }
+
+    /// Buffer the body, then release it in one piece once the digest has been verified.
+    fn on_stream_request_body(
+        &self,
+        ctx: &mut RequestCtx,
+        maybe_chunk: &mut Option<Bytes>,
+        end_of_stream: bool,
+    ) -> DeviceResult {
+        let Some(pending) = ctx.extensions.get_mut::<PendingBodyDigest>() else {
+            return DeviceResult::Continue;
+        };
+
+        if let Some(chunk) = maybe_chunk.take() {
+            if pending.body.len() + chunk.len() > self.max_body_bytes {
+                ctx.extensions.remove::<PendingBodyDigest>();
+                return self.deny(ctx, StatusCode::PAYLOAD_TOO_LARGE, "Request body too large");
+            }
+            pending.body.extend_from_slice(&chunk);
+        }
+
+        if !end_of_stream {
+            return DeviceResult::Continue;
+        }
+
+        let Some(pending) = ctx.extensions.remove::<PendingBodyDigest>() else {
+            return DeviceResult::Continue;
+        };
+
+        if !pending.expected.matches(&pending.body) {
+            return self.deny(ctx, StatusCode::BAD_REQUEST, "Request body digest mismatch");
+        }
+
+        *maybe_chunk = Some(pending.body.freeze());
+        DeviceResult::Continue
+    }
+}
+
+/// Request body held back until its digest is verified.
+#[derive(Debug, Clone)]
+pub(crate) struct PendingBodyDigest {
+    expected: ExpectedDigest,
+    body: BytesMut,
+}
+
+impl PendingBodyDigest {
+    pub(crate) fn new(expected: ExpectedDigest) -> Self {
+        Self {
+            expected,
+            body: BytesMut::new(),
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use crate::conf::types::CorsDeviceConfig;
+use crate::ctx::{RequestCtx, ResponseCtx};
+use crate::device::core::{Device, DeviceResult};
+use http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode, header};
+
+/// Answers CORS preflight requests and adds `Access-Control-*` headers for allowed origins.
+///
+/// Preflights are short-circuited with `204 No Content`; they never reach the upstream.
+/// A preflight that is not allowed still gets a `204`, just without the headers, so the
+/// browser blocks the actual request. Other requests are always proxied; the browser decides
+/// whether scripts may read the response.
+pub struct CorsDevice {
+    any_origin: bool,
+    /// Allowed origins, lowercased.
+    origins: Vec<String>,
+    methods: Vec<Method>,
+    methods_value: HeaderValue,
+    any_header: bool,
+    headers: Vec<HeaderName>,
+    expose_headers: Option<HeaderValue>,
+    allow_credentials: bool,
+    max_age: Option<HeaderValue>,
+}
+
+impl CorsDevice {
+    pub fn from_config(cfg: CorsDeviceConfig) -> anyhow::Result<Self> {
+        let methods = cfg
+            .allow_methods
+            .iter()
+            .map(|m| Method::from_bytes(m.as_bytes()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let methods_value = HeaderValue::from_str(&cfg.allow_methods.join(", "))?;
+
+        let headers = cfg
+            .allow_headers
+            .iter()
+            .filter(|h| h.as_str() != "*")
+            .map(|h| HeaderName::from_bytes(h.as_bytes()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let expose_headers = if cfg.expose_headers.is_empty() {
+            None
+        } else {
+            Some(HeaderValue::from_str(&cfg.expose_headers.join(", "))?)
+        };
+
+        Ok(Self {
+            any_origin: cfg.allow_origins.iter().any(|o| o == "*"),
+            origins: cfg
+                .allow_origins
+                .iter()
+                .map(|o| o.to_ascii_lowercase())
+                .collect(),
+            methods,
+            methods_value,
+            any_header: cfg.allow_headers.iter().any(|h| h == "*"),
+            headers,
+            expose_headers,
+            allow_credentials: cfg.allow_credentials,
+            max_age: cfg.max_age_seconds.map(HeaderValue::from),
+        })
+    }
+
+    fn allows_origin(&self, origin: &HeaderValue) -> bool {
+        // Sandboxed documents and file:// pages send the opaque origin `null`; it is never allowed.
+        if origin.as_bytes().eq_ignore_ascii_case(b"null") {
+            return false;
+        }
+        self.any_origin
+            || origin.to_str().is_ok_and(|o| {
+                self.origins
+                    .iter()
+                    .any(|allowed| allowed.eq_ignore_ascii_case(o))
+            })
+    }
+
+    /// Whether the method and headers a preflight asks for are all allowed.
+    fn allows_preflight(&self, headers: &HeaderMap) -> bool {
+        let method_allowed = headers
+            .get(header::ACCESS_CONTROL_REQUEST_METHOD)
+            .and_then(|m| Method::from_bytes(m.as_bytes()).ok())
+            .is_some_and(|m| self.methods.contains(&m));
+
+        let headers_allowed = self.any_header
+            || headers
+                .get_all(header::ACCESS_CONTROL_REQUEST_HEADERS)
+                .iter()
+                .all(|v| v.to_str().is_ok_and(|v| self.allows_headers(v)));
+
+        method_allowed && headers_allowed
+    }
+
+    /// Whether every header in a comma-separated `Access-Control-Request-Headers` value is allowed.
+    fn allows_headers(&self, requested: &str) -> bool {
+        requested
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .all(|name| {
+                HeaderName::from_bytes(name.as_bytes())
+                    .is_ok_and(|name| self.headers.contains(&name))
+            })
+    }
+
+    fn preflight(&self, ctx: &RequestCtx, origin: &HeaderValue) -> ResponseCtx {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::VARY,
+            HeaderValue::from_static(
+                "Origin, Access-Control-Request-Method, Access-Control-Request-Headers",
+            ),
+        );
+
+        if self.allows_origin(origin) && self.allows_preflight(ctx.headers()) {
+            headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin.clone());
+            headers.insert(
+                header::ACCESS_CONTROL_ALLOW_METHODS,
+                self.methods_value.clone(),
+            );
+            if let Some(requested) = ctx.headers().get(header::ACCESS_CONTROL_REQUEST_HEADERS) {
+                headers.insert(header::ACCESS_CONTROL_ALLOW_HEADERS, requested.clone());
+            }
+            if self.allow_credentials {
+                headers.insert(
+                    header::ACCESS_CONTROL_ALLOW_CREDENTIALS,
+                    HeaderValue::from_static("true"),
+                );
+            }
+            if let Some(max_age) = &self.max_age {
+                headers.insert(header::ACCESS_CONTROL_MAX_AGE, max_age.clone());
+            }
+        }
+
+        ResponseCtx::new(
+            ctx.request_id(),
+            StatusCode::NO_CONTENT,
+            headers,
+            Vec::new(),
+        )
+    }
+}
+
+impl Device for CorsDevice {
+    fn name(&self) -> &str {
+        "CORS"
+    }
+
+    fn on_request(&self, ctx: &mut RequestCtx) -> DeviceResult {
+        let Some(origin) = ctx.headers().get(header::ORIGIN).cloned() else {
+            return DeviceResult::Continue;
+        };
+
+        if ctx.method() == Method::OPTIONS
+            && ctx
+                .headers()
+                .contains_key(header::ACCESS_CONTROL_REQUEST_METHOD)
+        {
+            return DeviceResult::Respond(self.preflight(ctx, &origin));
+        }
+
+        // The response does not carry the request's Origin, so the headers are attached to the
+        // request here and added to the response by the gateway.
+        if self.allows_origin(&origin) {
+            ctx.response_headers
+                .insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+            if self.allow_credentials {
+                ctx.response_headers.insert(
+                    header::ACCESS_CONTROL_ALLOW_CREDENTIALS,
+                    HeaderValue::from_static("true"),
+                );
+            }
+            if let Some(expose) = &self.expose_headers {
+                ctx.response_headers
+                    .insert(header::ACCESS_CONTROL_EXPOSE_HEADERS, expose.clone());
+            }
+        }
+
+        DeviceResult::Continue
+    }
+
+    /// The response depends on the request's Origin, so caches must not share it across origins.
+    fn on_response(&self, ctx: &mut ResponseCtx) -> DeviceResult {
+        let varies = ctx
+            .headers
+            .get_all(header::VARY)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .map(str::trim)
+            .any(|v| v == "*" || v.eq_ignore_ascii_case("origin"));
+
+        if !varies {
+            ctx.headers
+                .append(header::VARY, HeaderValue::from_static("Origin"));
+        }
+
+        DeviceResult::Continue
+    }
+}
@@ -0,0 +1,93 @@
+use crate::conf::types::DeprecationDeviceConfig;
+use crate::ctx::ResponseCtx;
+use crate::device::core::{Device, DeviceResult};
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use http::{HeaderValue, header};
+use std::collections::HashMap;
+
+/// Announces that routes are deprecated, with the standard `Deprecation` (RFC 9745) and
+/// `Sunset` (RFC 8594) headers, and a `Link` to the successor route when one is configured.
+///
+/// Routes are matched by the path they are declared with, so a deprecated `/api/v1` route
+/// does not mark responses from a separately declared `/api/v1/users` route.
+pub struct DeprecationDevice {
+    /// Headers by route path, without a trailing slash.
+    deprecations: HashMap<String, DeprecationHeaders>,
+}
+
+struct DeprecationHeaders {
+    deprecation: HeaderValue,
+    sunset: Option<HeaderValue>,
+    link: Option<HeaderValue>,
+}
+
+impl DeprecationDevice {
+    pub fn from_config(cfg: DeprecationDeviceConfig) -> anyhow::Result<Self> {
+        let mut deprecations = HashMap::new();
+        for d in cfg.deprecations {
+            let deprecated_at = parse_timestamp(&d.deprecated_at)?;
+            let sunset = d
+                .sunset_at
+                .as_deref()
+                .map(|s| -> anyhow::Result<_> {
+                    let sunset_at = parse_timestamp(s)?;
+                    Ok(HeaderValue::from_str(&http_date(sunset_at))?)
+                })
+                .transpose()?;
+            let link = d
+                .successor
+                .map(|url| HeaderValue::from_str(&format!("<{url}>; rel=\"successor-version\"")))
+                .transpose()
+                .with_context(|| format!("invalid successor URL for {}", d.path))?;
+
+            let headers = DeprecationHeaders {
+                // A structured field date: `@` and seconds since the epoch.
+                deprecation: HeaderValue::from_str(&format!("@{}", deprecated_at.timestamp()))?,
+                sunset,
+                link,
+            };
+            deprecations.insert(d.path.trim_end_matches('/').to_string(), headers);
+        }
+
+        Ok(Self { deprecations })
+    }
+}
+
+fn parse_timestamp(timestamp: &str) -> anyhow::Result<DateTime<Utc>> {
+    let parsed = DateTime::parse_from_rfc3339(timestamp)
+        .with_context(|| format!("invalid RFC 3339 timestamp: {timestamp}"))?;
+    Ok(parsed.with_timezone(&Utc))
+}
+
+/// IMF-fixdate, e.g. `Sat, 01 Aug 2026 00:00:00 GMT`.
+fn http_date(at: DateTime<Utc>) -> String {
+    at.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+impl Device for DeprecationDevice {
+    fn name(&self) -> &str {
+        "Deprecation"
+    }
+
+    fn on_response(&self, ctx: &mut ResponseCtx) -> DeviceResult {
+        let Some(route) = &ctx.route_id else {
+            return DeviceResult::Continue;
+        };
+        let Some(headers) = self.deprecations.get(route.path()) else {
+            return DeviceResult::Continue;
+        };
+
+        ctx.headers
+            .insert("deprecation", headers.deprecation.clone());
+        if let Some(sunset) = &headers.sunset {
+            ctx.headers.insert("sunset", sunset.clone());
+        }
+        // The upstream may send links of its own, so the successor is added alongside them.
+        if let Some(link) = &headers.link {
+            ctx.headers.append(header::LINK, link.clone());
+        }
+
+        DeviceResult::Continue
+    }
+}
@@ -0,0 +1,87 @@
+use crate::conf::types::{ErrorPageDeviceConfig, error_page_statuses};
+use crate::ctx::ResponseCtx;
+use crate::device::core::{Device, DeviceResult};
+use crate::static_files::render::compression::is_skipped_content_type;
+use anyhow::{Context, anyhow};
+use http::{HeaderMap, HeaderName, HeaderValue, header};
+use std::fs;
+use std::ops::RangeInclusive;
+
+/// Replaces upstream error responses with a safe error page, so stack traces and internal
+/// headers never reach the client.
+///
+/// The status is kept. Only the headers listed in `keep_headers` survive from the upstream
+/// response. Error responses with a pass-through content type, e.g. `application/problem+json`,
+/// are meant for the client and are sent unchanged.
+pub struct ErrorPageDevice {
+    statuses: Vec<RangeInclusive<u16>>,
+    body: Vec<u8>,
+    content_type: HeaderValue,
+    pass_through_content_types: Vec<String>,
+    keep_headers: Vec<HeaderName>,
+}
+
+impl ErrorPageDevice {
+    pub fn from_config(cfg: ErrorPageDeviceConfig) -> anyhow::Result<Self> {
+        let statuses = cfg
+            .statuses
+            .iter()
+            .map(|s| error_page_statuses(s).ok_or_else(|| anyhow!("invalid error status: {s}")))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let body = match (cfg.body, cfg.body_file) {
+            (Some(body), _) => body.into_bytes(),
+            (None, Some(path)) => fs::read(&path)
+                .with_context(|| format!("failed to read error page {}", path.display()))?,
+            (None, None) => return Err(anyhow!("error page device has no body or body_file")),
+        };
+
+        let keep_headers = cfg
+            .keep_headers
+            .iter()
+            .map(|h| HeaderName::from_bytes(h.as_bytes()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            statuses,
+            body,
+            content_type: HeaderValue::from_str(&cfg.content_type)?,
+            pass_through_content_types: cfg.pass_through_content_types,
+            keep_headers,
+        })
+    }
+
+    fn is_intentional(&self, headers: &HeaderMap) -> bool {
+        headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|ct| is_skipped_content_type(ct, &self.pass_through_content_types))
+    }
+}
+
+impl Device for ErrorPageDevice {
+    fn name(&self) -> &str {
+        "Error Page"
+    }
+
+    fn after_proxy(&self, ctx: &mut ResponseCtx) -> DeviceResult {
+        let status = ctx.status.as_u16();
+        if !self.statuses.iter().any(|s| s.contains(&status)) || self.is_intentional(&ctx.headers) {
+            return DeviceResult::Continue;
+        }
+
+        let mut headers = HeaderMap::new();
+        for name in &self.keep_headers {
+            for value in ctx.headers.get_all(name) {
+                headers.append(name.clone(), value.clone());
+            }
+        }
+        headers.insert(header::CONTENT_TYPE, self.content_type.clone());
+        headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+
+        ctx.headers = headers;
+        ctx.body = self.body.clone();
+
+        DeviceResult::Continue
+    }
+}
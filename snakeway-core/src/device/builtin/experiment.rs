@@ -0,0 +1,93 @@
+use crate::conf::types::ExperimentDeviceConfig;
+use crate::ctx::RequestCtx;
+use crate::device::core::{Device, DeviceResult};
+use http::{HeaderMap, HeaderName, HeaderValue, header};
+use sha2::{Digest, Sha256};
+
+/// Header carrying the assigned variant, both upstream and to the client.
+pub const EXPERIMENT_VARIANT_HEADER: HeaderName = HeaderName::from_static("x-experiment-variant");
+
+/// Assigns users to experiment variants and tags requests and responses with the variant.
+///
+/// The variant is picked by hashing the experiment name with the value of the configured
+/// cookie, so a user always lands in the same variant, on every instance, across restarts.
+/// Unlike the upstream group device, this does not change where the request is routed.
+pub struct ExperimentDevice {
+    experiment: String,
+    cookie: String,
+    /// Variant names with their cumulative weights, for a single pass lookup.
+    variants: Vec<(HeaderValue, u64)>,
+    total_weight: u64,
+}
+
+impl ExperimentDevice {
+    pub fn from_config(cfg: ExperimentDeviceConfig) -> anyhow::Result<Self> {
+        let mut total_weight = 0;
+        let mut variants = Vec::with_capacity(cfg.variants.len());
+        for variant in cfg.variants {
+            total_weight += u64::from(variant.weight);
+            variants.push((HeaderValue::from_str(&variant.name)?, total_weight));
+        }
+
+        if total_weight == 0 {
+            anyhow::bail!("experiment '{}' has no weighted variants", cfg.experiment);
+        }
+
+        Ok(Self {
+            experiment: cfg.experiment,
+            cookie: cfg.cookie,
+            variants,
+            total_weight,
+        })
+    }
+
+    /// The variant for a user key. The same key always gets the same variant.
+    pub fn assign(&self, key: &str) -> &HeaderValue {
+        let digest = Sha256::new()
+            .chain_update(self.experiment.as_bytes())
+            .chain_update(b":")
+            .chain_update(key.as_bytes())
+            .finalize();
+        let hash = u64::from_be_bytes(digest[..8].try_into().expect("digest is 32 bytes"));
+        let bucket = hash % self.total_weight;
+
+        self.variants
+            .iter()
+            .find(|(_, cumulative)| bucket < *cumulative)
+            .map(|(name, _)| name)
+            .expect("bucket is below the total weight")
+    }
+
+    /// The value of the configured cookie, looking through every `Cookie` header.
+    fn key<'a>(&self, headers: &'a HeaderMap) -> Option<&'a str> {
+        headers
+            .get_all(header::COOKIE)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(';'))
+            .filter_map(|pair| pair.trim().split_once('='))
+            .find(|(name, _)| *name == self.cookie)
+            .map(|(_, value)| value.trim_matches('"'))
+            .filter(|value| !value.is_empty())
+    }
+}
+
+impl Device for ExperimentDevice {
+    fn name(&self) -> &str {
+        "Experiment"
+    }
+
+    fn on_request(&self, ctx: &mut RequestCtx) -> DeviceResult {
+        let Some(key) = self.key(ctx.headers()) else {
+            return DeviceResult::Continue;
+        };
+
+        let variant = self.assign(key).clone();
+        ctx.upstream_headers
+            .insert(EXPERIMENT_VARIANT_HEADER, variant.clone());
+        ctx.response_headers
+            .insert(EXPERIMENT_VARIANT_HEADER, variant);
+
+        DeviceResult::Continue
+    }
+}
@@ -0,0 +1,79 @@
+use crate::conf::types::{HeaderRewriteDeviceConfig, HeaderSetConfig};
+use crate::ctx::{RequestCtx, ResponseCtx};
+use crate::device::core::{Device, DeviceResult};
+use http::{HeaderName, HeaderValue};
+
+/// Sets and removes request and response headers.
+///
+/// Setting a header replaces every value it had, and removing a header the message does not
+/// carry is a no-op. Header names are case-insensitive, as everywhere else in the pipeline.
+pub struct HeaderRewriteDevice {
+    request_set: Vec<(HeaderName, HeaderValue)>,
+    request_remove: Vec<HeaderName>,
+    response_set: Vec<(HeaderName, HeaderValue)>,
+    response_remove: Vec<HeaderName>,
+}
+
+impl HeaderRewriteDevice {
+    pub fn from_config(cfg: HeaderRewriteDeviceConfig) -> anyhow::Result<Self> {
+        Ok(Self {
+            request_set: parse_set(cfg.request_set)?,
+            request_remove: parse_names(cfg.request_remove)?,
+            response_set: parse_set(cfg.response_set)?,
+            response_remove: parse_names(cfg.response_remove)?,
+        })
+    }
+}
+
+fn parse_set(headers: Vec<HeaderSetConfig>) -> anyhow::Result<Vec<(HeaderName, HeaderValue)>> {
+    headers
+        .into_iter()
+        .map(|h| {
+            Ok((
+                HeaderName::from_bytes(h.name.as_bytes())?,
+                HeaderValue::from_str(&h.value)?,
+            ))
+        })
+        .collect()
+}
+
+fn parse_names(names: Vec<String>) -> anyhow::Result<Vec<HeaderName>> {
+    names
+        .into_iter()
+        .map(|name| Ok(HeaderName::from_bytes(name.as_bytes())?))
+        .collect()
+}
+
+impl Device for HeaderRewriteDevice {
+    fn name(&self) -> &str {
+        "Header Rewrite"
+    }
+
+    fn on_request(&self, ctx: &mut RequestCtx) -> DeviceResult {
+        // Later devices see the rewritten request, and the upstream request gets the same changes.
+        for name in &self.request_remove {
+            ctx.remove_header(name.as_str());
+            ctx.upstream_headers.remove(name);
+            ctx.upstream_remove_headers.push(name.clone());
+        }
+
+        for (name, value) in &self.request_set {
+            ctx.insert_header(name.clone(), value.clone());
+            ctx.upstream_headers.insert(name.clone(), value.clone());
+        }
+
+        DeviceResult::Continue
+    }
+
+    fn on_response(&self, ctx: &mut ResponseCtx) -> DeviceResult {
+        for name in &self.response_remove {
+            ctx.headers.remove(name);
+        }
+
+        for (name, value) in &self.response_set {
+            ctx.headers.insert(name.clone(), value.clone());
+        }
+
+        DeviceResult::Continue
+    }
+}
@@ -0,0 +1,326 @@
+use crate::conf::types::IdempotencyDeviceConfig;
+use crate::ctx::{RequestCtx, ResponseCtx};
+use crate::device::core::{CachePurge, Device, DeviceResult};
+use async_trait::async_trait;
+use bytes::{Bytes, BytesMut};
+use dashmap::DashMap;
+use dashmap::mapref::entry::Entry;
+use http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode, header};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+/// Set on replayed responses, so clients can tell a replay from a fresh response.
+pub const IDEMPOTENT_REPLAYED_HEADER: HeaderName = HeaderName::from_static("idempotent-replayed");
+
+/// Expired responses are purged once this many keys are tracked.
+const MAX_TRACKED_KEYS: usize = 10_000;
+
+/// Longest idempotency key accepted.
+const MAX_KEY_LENGTH: usize = 255;
+
+/// Headers that describe the original connection rather than the response, so are not replayed.
+const UNSTORED_HEADERS: [HeaderName; 4] = [
+    header::CONNECTION,
+    header::CONTENT_LENGTH,
+    header::TRANSFER_ENCODING,
+    header::UPGRADE,
+];
+
+/// A response stored for replay.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+enum Slot {
+    /// A request holding the key is being proxied. Waiters are woken when its sender drops.
+    InFlight(watch::Receiver<()>),
+    Stored {
+        response: StoredResponse,
+        expires_at: Instant,
+    },
+}
+
+enum Claim {
+    /// The key is now held by this request. Dropping the sender wakes waiting duplicates.
+    Acquired(watch::Sender<()>),
+    Replay(StoredResponse),
+    Wait(watch::Receiver<()>),
+}
+
+/// The response of a request holding a key, captured as it is sent to the client.
+struct Capture {
+    key: String,
+    status: Option<StatusCode>,
+    headers: HeaderMap,
+    body: BytesMut,
+    too_large: bool,
+    _done: watch::Sender<()>,
+}
+
+/// Requires an idempotency key on mutating requests, and replays the stored response for
+/// repeated keys instead of proxying them again.
+///
+/// Keys are scoped to the method and path. While the first request with a key is in flight,
+/// duplicates wait for it, up to `wait_timeout_milliseconds`, then get `409`.
+/// Only complete responses are stored. 5xx responses, failed requests, and bodies over
+/// `max_body_bytes` release the key, so the client can retry.
+///
+/// Responses are stored in memory on this instance, and are lost on restart or reload.
+pub struct IdempotencyDevice {
+    key_header: HeaderName,
+    methods: Vec<Method>,
+    paths: Vec<String>,
+    ttl: Duration,
+    wait_timeout: Duration,
+    max_body_bytes: usize,
+    slots: DashMap<String, Slot>,
+    /// Responses being captured, by request id.
+    captures: DashMap<String, Capture>,
+}
+
+impl IdempotencyDevice {
+    pub fn from_config(cfg: IdempotencyDeviceConfig) -> anyhow::Result<Self> {
+        let methods = cfg
+            .methods
+            .iter()
+            .map(|m| Method::from_bytes(m.as_bytes()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            key_header: HeaderName::from_bytes(cfg.key_header.as_bytes())?,
+            methods,
+            paths: cfg.paths,
+            ttl: Duration::from_secs(cfg.ttl_seconds),
+            wait_timeout: Duration::from_millis(cfg.wait_timeout_milliseconds),
+            max_body_bytes: cfg.max_body_bytes,
+            slots: DashMap::new(),
+            captures: DashMap::new(),
+        })
+    }
+
+    fn applies_to(&self, ctx: &RequestCtx) -> bool {
+        let path = ctx.canonical_path();
+        self.methods.contains(ctx.method())
+            && (self.paths.is_empty() || self.paths.iter().any(|p| has_prefix(path, p)))
+    }
+
+    /// Take the key, or find out what the request holding it left behind.
+    fn claim(&self, key: &str, now: Instant) -> Claim {
+        if self.slots.len() >= MAX_TRACKED_KEYS {
+            self.slots.retain(|_, slot| match slot {
+                Slot::Stored { expires_at, .. } => *expires_at > now,
+                Slot::InFlight(_) => true,
+            });
+        }
+
+        let entry = self.slots.entry(key.to_string());
+        if let Entry::Occupied(occupied) = &entry {
+            match occupied.get() {
+                Slot::InFlight(waiters) => return Claim::Wait(waiters.clone()),
+                Slot::Stored {
+                    response,
+                    expires_at,
+                } if *expires_at > now => return Claim::Replay(response.clone()),
+                Slot::Stored { .. } => {}
+            }
+        }
+
+        let (done, waiters) = watch::channel(());
+        entry.insert(Slot::InFlight(waiters));
+        Claim::Acquired(done)
+    }
+
+    /// Store a captured response, or release its key if it cannot be replayed.
+    ///
+    /// The slot is updated before the capture, and its sender, is dropped,
+    /// so woken duplicates find the stored response.
+    fn finish(&self, capture: Capture, now: Instant) {
+        match capture.status {
+            Some(status) if !capture.too_large && !status.is_server_error() => {
+                let response = StoredResponse {
+                    status,
+                    headers: capture.headers,
+                    body: capture.body.freeze(),
+                };
+                self.slots.insert(
+                    capture.key,
+                    Slot::Stored {
+                        response,
+                        expires_at: now + self.ttl,
+                    },
+                );
+            }
+            _ => self.release(&capture.key),
+        }
+    }
+
+    fn release(&self, key: &str) {
+        self.slots
+            .remove_if(key, |_, slot| matches!(slot, Slot::InFlight(_)));
+    }
+}
+
+#[async_trait]
+impl Device for IdempotencyDevice {
+    fn name(&self) -> &str {
+        "Idempotency"
+    }
+
+    async fn on_request_async(&self, ctx: &mut RequestCtx) -> DeviceResult {
+        if !self.applies_to(ctx) {
+            return DeviceResult::Continue;
+        }
+        let Some(request_id) = ctx.request_id() else {
+            return DeviceResult::Continue;
+        };
+
+        let key = match ctx.headers().get(&self.key_header).map(|v| v.to_str()) {
+            None => {
+                let message = format!("Missing {} header", self.key_header);
+                return reject(ctx, StatusCode::BAD_REQUEST, message);
+            }
+            Some(Ok(key)) if !key.is_empty() && key.len() <= MAX_KEY_LENGTH => key,
+            Some(_) => {
+                let message = format!("Invalid {} header", self.key_header);
+                return reject(ctx, StatusCode::BAD_REQUEST, message);
+            }
+        };
+        let key = format!("{} {} {}", ctx.method(), ctx.canonical_path(), key);
+
+        let deadline = Instant::now() + self.wait_timeout;
+        loop {
+            match self.claim(&key, Instant::now()) {
+                Claim::Acquired(done) => {
+                    self.captures.insert(
+                        request_id,
+                        Capture {
+                            key,
+                            status: None,
+                            headers: HeaderMap::new(),
+                            body: BytesMut::new(),
+                            too_large: false,
+                            _done: done,
+                        },
+                    );
+                    return DeviceResult::Continue;
+                }
+                Claim::Replay(response) => return replay(ctx, response),
+                Claim::Wait(mut waiters) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    // The sender only ever closes, so either outcome means the holder is done.
+                    if tokio::time::timeout(remaining, waiters.changed())
+                        .await
+                        .is_err()
+                    {
+                        let message = "A request with this idempotency key is still in progress";
+                        return reject(ctx, StatusCode::CONFLICT, message.to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    fn on_response(&self, ctx: &mut ResponseCtx) -> DeviceResult {
+        if let Some(request_id) = &ctx.request_id
+            && let Some(mut capture) = self.captures.get_mut(request_id)
+        {
+            let mut headers = ctx.headers.clone();
+            for name in &UNSTORED_HEADERS {
+                headers.remove(name);
+            }
+            capture.status = Some(ctx.status);
+            capture.headers = headers;
+        }
+        DeviceResult::Continue
+    }
+
+    fn on_stream_response_body(
+        &self,
+        ctx: &RequestCtx,
+        chunk: Option<&Bytes>,
+        end_of_stream: bool,
+    ) {
+        let Some(request_id) = ctx.request_id() else {
+            return;
+        };
+
+        if let Some(chunk) = chunk
+            && let Some(mut capture) = self.captures.get_mut(&request_id)
+            && !capture.too_large
+        {
+            if capture.body.len() + chunk.len() > self.max_body_bytes {
+                capture.too_large = true;
+                capture.body = BytesMut::new();
+            } else {
+                capture.body.extend_from_slice(chunk);
+            }
+        }
+
+        if end_of_stream && let Some((_, capture)) = self.captures.remove(&request_id) {
+            self.finish(capture, Instant::now());
+        }
+    }
+
+    /// A request that never finished its response, e.g. it failed or was short-circuited,
+    /// releases its key here.
+    fn on_complete(&self, ctx: &RequestCtx) {
+        if let Some(request_id) = ctx.request_id()
+            && let Some((_, capture)) = self.captures.remove(&request_id)
+        {
+            self.release(&capture.key);
+        }
+    }
+
+    /// Stored responses are cached as `<method> <path> <key>`, e.g. `POST /payments abc`.
+    /// Keys held by requests in flight are kept, so their duplicates still wait for them.
+    fn purge_cache(&self, purge: &CachePurge) -> usize {
+        let mut purged = 0;
+        self.slots.retain(|key, slot| {
+            let matches = matches!(slot, Slot::Stored { .. }) && purge.matches(key);
+            purged += usize::from(matches);
+            !matches
+        });
+        purged
+    }
+
+    fn reset(&self) -> bool {
+        self.purge_cache(&CachePurge::All);
+        true
+    }
+}
+
+/// Whether `path` is `prefix`, or below it.
+fn has_prefix(path: &str, prefix: &str) -> bool {
+    match path.strip_prefix(prefix) {
+        Some(rest) => rest.is_empty() || rest.starts_with('/') || prefix.ends_with('/'),
+        None => false,
+    }
+}
+
+fn replay(ctx: &RequestCtx, response: StoredResponse) -> DeviceResult {
+    let mut headers = response.headers;
+    headers.insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+    DeviceResult::Respond(ResponseCtx::new(
+        ctx.request_id(),
+        response.status,
+        headers,
+        response.body.to_vec(),
+    ))
+}
+
+fn reject(ctx: &RequestCtx, status: StatusCode, message: String) -> DeviceResult {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("text/plain; charset=utf-8"),
+    );
+    DeviceResult::Respond(ResponseCtx::new(
+        ctx.request_id(),
+        status,
+        headers,
+        message.into_bytes(),
+    ))
+}
@@ -1,47 +1,48 @@
-use crate::conf::types::IdentityDeviceConfig;
+use crate::conf::types::{ForwardingHeaderKind, IdentityDeviceConfig};
 use crate::ctx::{RequestCtx, ResponseCtx};
 use crate::device::core::errors::DeviceError;
 use crate::device::core::{Device, DeviceResult};
-use crate::enrichment::user_agent::{ClientIdentity, GeoInfo, UaEngine, build_ua_engine};
-use http::HeaderMap;
+use crate::enrichment::geoip::{GeoIp, GeoIpDatabases};
+use crate::enrichment::user_agent::{ClientIdentity, UaEngine, UaParseCache, build_ua_engine};
+use http::{HeaderMap, HeaderName, header};
 use ipnet::IpNet;
-use maxminddb::PathElement;
-use std::net::IpAddr;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::num::NonZeroUsize;
+use std::str::FromStr;
+use std::sync::Arc;
 
 const MAX_USER_AGENT_LENGTH: usize = 2048;
-const MAX_X_FORWARDED_FOR_LENGTH: usize = 1024;
+const MAX_FORWARDING_HEADER_LENGTH: usize = 1024;
+const X_FORWARDED_FOR: &str = "x-forwarded-for";
 
 pub struct IdentityDevice {
     // GeoIP
     pub enable_geoip: bool,
     trusted_proxies: Vec<IpNet>,
-    city_reader: Option<maxminddb::Reader<maxminddb::Mmap>>,
-    isp_reader: Option<maxminddb::Reader<maxminddb::Mmap>>,
-    connection_type_reader: Option<maxminddb::Reader<maxminddb::Mmap>>,
+    client_ip_header: Option<HeaderName>,
+    preferred_forwarding_header: ForwardingHeaderKind,
+    geoip: Option<Arc<GeoIp>>,
 
     // User-agent
     pub enable_user_agent: bool,
     ua_engine: Option<UaEngine>,
+    ua_cache: Option<UaParseCache>,
 }
 
 impl IdentityDevice {
-    pub fn from_config(cfg: IdentityDeviceConfig) -> anyhow::Result<Self> {
-        // Safety note on these memory-mapped GeoIP files...
-        // - File is opened read-only
-        // - Lifetime is bound to IdentityDevice
-        // - Snakeway does not mutate the mmdb file
-        let geoip_city_db = match (cfg.enable_geoip, &cfg.geoip_city_db) {
-            (true, Some(path)) => Some(unsafe { maxminddb::Reader::open_mmap(path)? }),
-            _ => None,
-        };
-
-        let geoip_isp_db = match (cfg.enable_geoip, &cfg.geoip_isp_db) {
-            (true, Some(path)) => Some(unsafe { maxminddb::Reader::open_mmap(path)? }),
-            _ => None,
-        };
-        let geoip_connection_type_db = match (cfg.enable_geoip, &cfg.geoip_connection_type_db) {
-            (true, Some(path)) => Some(unsafe { maxminddb::Reader::open_mmap(path)? }),
-            _ => None,
+    /// `geoip` is the server's databases, used unless the device names databases of its own.
+    pub fn from_config(cfg: IdentityDeviceConfig, geoip: Arc<GeoIp>) -> anyhow::Result<Self> {
+        let own_databases = cfg.geoip_city_db.is_some()
+            || cfg.geoip_isp_db.is_some()
+            || cfg.geoip_connection_type_db.is_some();
+        let geoip = match (cfg.enable_geoip, own_databases) {
+            (false, _) => None,
+            (true, false) => Some(geoip),
+            (true, true) => Some(Arc::new(GeoIp::from(GeoIpDatabases::open(
+                cfg.geoip_city_db.as_deref(),
+                cfg.geoip_isp_db.as_deref(),
+                cfg.geoip_connection_type_db.as_deref(),
+            )?))),
         };
 
         let ua_engine = if cfg.enable_user_agent {
@@ -50,22 +51,33 @@ impl IdentityDevice {
             None
         };
 
+        let ua_cache = NonZeroUsize::new(cfg.ua_cache_capacity)
+            .filter(|_| cfg.enable_user_agent)
+            .map(UaParseCache::new);
+
         let trusted_proxies = cfg
             .trusted_proxies
             .iter()
             .map(|s| s.parse::<IpNet>())
             .collect::<Result<Vec<_>, _>>()?;
 
+        let client_ip_header = cfg
+            .client_ip_header
+            .as_deref()
+            .map(HeaderName::from_str)
+            .transpose()?;
+
         Ok(Self {
             // GeoIP
             enable_geoip: cfg.enable_geoip,
-            city_reader: geoip_city_db,
-            isp_reader: geoip_isp_db,
-            connection_type_reader: geoip_connection_type_db,
+            geoip,
             trusted_proxies,
+            client_ip_header,
+            preferred_forwarding_header: cfg.preferred_forwarding_header,
             // User-agent
             enable_user_agent: cfg.enable_user_agent,
             ua_engine,
+            ua_cache,
         })
     }
 }
@@ -76,87 +88,24 @@ impl Device for IdentityDevice {
     }
 
     fn on_request(&self, ctx: &mut RequestCtx) -> DeviceResult {
-        let (client_ip, proxy_chain) =
-            resolve_client_ip(ctx.headers(), ctx.peer_ip, &self.trusted_proxies);
+        let (client_ip, proxy_chain) = resolve_client_ip(
+            ctx.headers(),
+            ctx.peer_ip,
+            &self.trusted_proxies,
+            self.client_ip_header.as_ref(),
+            self.preferred_forwarding_header,
+        );
 
         let mut identity = ClientIdentity {
             ip: client_ip,
             proxy_chain,
-            geo: None,
+            geo: self
+                .geoip
+                .as_ref()
+                .and_then(|geoip| geoip.lookup(client_ip)),
             ua: None,
         };
 
-        if self.enable_geoip {
-            let mut geo = GeoInfo::default();
-
-            //-----------------------------------------------------------------
-            // Country and Region
-            //-----------------------------------------------------------------
-            let lookup = self
-                .city_reader
-                .as_ref()
-                .and_then(|reader| reader.lookup(client_ip).ok());
-
-            if let Some(lookup) = lookup {
-                geo.country_code = lookup
-                    .decode_path::<String>(&[
-                        PathElement::Key("country"),
-                        PathElement::Key("iso_code"),
-                    ])
-                    .ok()
-                    .flatten();
-
-                geo.region = lookup
-                    .decode_path::<String>(&[
-                        PathElement::Key("subdivisions"),
-                        PathElement::Index(0),
-                        PathElement::Key("iso_code"),
-                    ])
-                    .ok()
-                    .flatten();
-            }
-
-            //-----------------------------------------------------------------
-            // ASN
-            //-----------------------------------------------------------------
-            let lookup = self
-                .isp_reader
-                .as_ref()
-                .and_then(|reader| reader.lookup(client_ip).ok());
-
-            if let Some(lookup) = lookup {
-                geo.asn = lookup
-                    .decode_path::<u32>(&[PathElement::Key("autonomous_system_number")])
-                    .ok()
-                    .flatten();
-
-                geo.aso = lookup
-                    .decode_path::<String>(&[PathElement::Key("autonomous_system_organization")])
-                    .ok()
-                    .flatten();
-            }
-
-            //-----------------------------------------------------------------
-            // Connection-type
-            //-----------------------------------------------------------------
-            let lookup = self
-                .connection_type_reader
-                .as_ref()
-                .and_then(|reader| reader.lookup(client_ip).ok());
-
-            if let Some(lookup) = lookup {
-                geo.connection_type = lookup
-                    .decode_path::<String>(&[PathElement::Key("connection_type")])
-                    .ok()
-                    .flatten();
-            }
-
-            // Put it together...
-            if geo.has_some_info() {
-                identity.geo = Some(geo);
-            }
-        }
-
         if self.enable_user_agent {
             // User-Agent parsing
             if let Some((engine, ua)) = self.ua_engine.as_ref().zip(
@@ -165,7 +114,10 @@ impl Device for IdentityDevice {
                     .and_then(|v| v.to_str().ok())
                     .filter(|ua| ua.len() <= MAX_USER_AGENT_LENGTH),
             ) {
-                identity.ua = Some(engine.parse(ua));
+                identity.ua = Some(match &self.ua_cache {
+                    Some(cache) => cache.parse(engine, ua),
+                    None => engine.parse(ua),
+                });
             }
         }
 
@@ -190,20 +142,26 @@ impl Device for IdentityDevice {
     fn on_error(&self, _: &DeviceError) {}
 }
 
-/// Resolve the true client IP using X-Forwarded-For and a trusted proxy list.
+/// Resolve the true client IP using X-Forwarded-For or Forwarded, and a trusted proxy list.
 ///
 /// Returns:
 /// - client_ip: the resolved client IP
 /// - proxy_chain: ordered list of proxy IPs (closest first)
 ///
 /// Rules:
-/// - Walk XFF from right → left
+/// - If a client IP header is configured (e.g. CF-Connecting-IP) and holds a valid IP, use it
+/// - Otherwise, walk XFF or the `for=` elements of Forwarded from right → left
 /// - Stop at first IP not in trusted_proxies
 /// - If no untrusted IP found, fall back to peer_ip
+///
+/// If a request carries both XFF and Forwarded, only `preferred_header` is walked.
+/// No header is consulted unless the immediate peer is a trusted proxy.
 pub fn resolve_client_ip(
     headers: &HeaderMap,
     peer_ip: IpAddr,
     trusted_proxies: &[IpNet],
+    client_ip_header: Option<&HeaderName>,
+    preferred_header: ForwardingHeaderKind,
 ) -> (IpAddr, Vec<IpAddr>) {
     // If there are no trusted proxies, we can't trust XFF, so just return the peer IP.
     if trusted_proxies.is_empty() {
@@ -215,21 +173,19 @@ pub fn resolve_client_ip(
         return (peer_ip, Vec::new());
     }
 
-    let xff = match headers.get("x-forwarded-for").and_then(|h| h.to_str().ok()) {
-        Some(v) => v,
-        None => return (peer_ip, Vec::new()),
-    };
-
-    // Guard against overly long XFF headers to prevent potential abuse.
-    if xff.len() > MAX_X_FORWARDED_FOR_LENGTH {
-        return (peer_ip, Vec::new());
+    // A single-IP header set by the CDN/edge takes precedence over XFF.
+    if let Some(ip) = client_ip_header
+        .and_then(|name| headers.get(name))
+        .and_then(|h| h.to_str().ok())
+        .and_then(|v| v.trim().parse::<IpAddr>().ok())
+    {
+        return (ip, Vec::new());
     }
 
-    let ips: Vec<IpAddr> = xff
-        .split(',')
-        .map(|s| s.trim())
-        .filter_map(|s| s.parse::<IpAddr>().ok())
-        .collect();
+    let ips = match forwarded_ips(headers, preferred_header) {
+        Some(ips) => ips,
+        None => return (peer_ip, Vec::new()),
+    };
 
     let mut proxy_chain = Vec::with_capacity(ips.len());
 
@@ -244,3 +200,81 @@ pub fn resolve_client_ip(
 
     (peer_ip, proxy_chain)
 }
+
+/// The IPs listed by the forwarding header to walk, client first.
+fn forwarded_ips(
+    headers: &HeaderMap,
+    preferred_header: ForwardingHeaderKind,
+) -> Option<Vec<IpAddr>> {
+    let xff = headers.get(X_FORWARDED_FOR).and_then(|h| h.to_str().ok());
+    let forwarded = headers.get(header::FORWARDED).and_then(|h| h.to_str().ok());
+
+    let (value, parse): (&str, fn(&str) -> Vec<IpAddr>) = match (preferred_header, xff, forwarded) {
+        (ForwardingHeaderKind::Forwarded, _, Some(forwarded))
+        | (ForwardingHeaderKind::XForwardedFor, None, Some(forwarded)) => {
+            (forwarded, parse_forwarded_for)
+        }
+        (_, Some(xff), _) => (xff, parse_x_forwarded_for),
+        (_, None, None) => return None,
+    };
+
+    // Guard against overly long headers to prevent potential abuse.
+    if value.len() > MAX_FORWARDING_HEADER_LENGTH {
+        return None;
+    }
+
+    Some(parse(value))
+}
+
+/// The IPs in an X-Forwarded-For value. Entries that are not IPs are skipped.
+fn parse_x_forwarded_for(value: &str) -> Vec<IpAddr> {
+    value
+        .split(',')
+        .map(|s| s.trim())
+        .filter_map(|s| s.parse::<IpAddr>().ok())
+        .collect()
+}
+
+/// The `for=` IPs in an RFC 7239 Forwarded value, e.g.
+/// `for=192.0.2.60;proto=https, for="[2001:db8:cafe::17]:4711"`.
+///
+/// Ports are dropped. Elements without a `for=` IP, such as `for=unknown` or an obfuscated
+/// `for=_hidden`, are skipped, like X-Forwarded-For entries that are not IPs.
+pub fn parse_forwarded_for(value: &str) -> Vec<IpAddr> {
+    value
+        .split(',')
+        .filter_map(|element| {
+            element.split(';').find_map(|pair| {
+                let (name, node) = pair.split_once('=')?;
+                if !name.trim().eq_ignore_ascii_case("for") {
+                    return None;
+                }
+                Some(parse_forwarded_node(node.trim()))
+            })?
+        })
+        .collect()
+}
+
+/// The IP of a Forwarded node, e.g. `192.0.2.60`, `"192.0.2.60:443"` or `"[2001:db8::1]:443"`.
+fn parse_forwarded_node(node: &str) -> Option<IpAddr> {
+    let node = node
+        .strip_prefix('"')
+        .and_then(|n| n.strip_suffix('"'))
+        .unwrap_or(node);
+
+    if let Some(bracketed) = node.strip_prefix('[') {
+        let (ip, port) = bracketed.split_once(']')?;
+        if !(port.is_empty() || port.starts_with(':')) {
+            return None;
+        }
+        return ip.parse::<Ipv6Addr>().ok().map(IpAddr::V6);
+    }
+
+    // RFC 7239 requires brackets around IPv6, but bare IPv6 is accepted, as in X-Forwarded-For.
+    if let Ok(ip) = node.parse::<IpAddr>() {
+        return Some(ip);
+    }
+
+    let (ip, _port) = node.split_once(':')?;
+    ip.parse::<Ipv4Addr>().ok().map(IpAddr::V4)
+}
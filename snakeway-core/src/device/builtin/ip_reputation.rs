@@ -0,0 +1,357 @@
+use crate::conf::types::{IpReputationAction, IpReputationDeviceConfig};
+use crate::ctx::{RequestCtx, ResponseCtx};
+use crate::device::core::errors::DeviceError;
+use crate::device::core::{CachePurge, Device, DeviceResult};
+use crate::enrichment::user_agent::ClientIdentity;
+use anyhow::{Context, anyhow};
+use arc_swap::ArcSwap;
+use async_trait::async_trait;
+use dashmap::DashMap;
+use http::{HeaderName, HeaderValue, Method, StatusCode, Uri, header};
+use ipnet::IpNet;
+use pingora::connectors::http::Connector;
+use pingora::http::RequestHeader;
+use pingora::upstreams::peer::HttpPeer;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Header carrying the client IP in requests to the reputation service.
+const CLIENT_IP_HEADER: &str = "x-client-ip";
+
+/// Expired cache entries are purged once the cache grows past this many IPs.
+const MAX_CACHED_VERDICTS: usize = 100_000;
+
+/// How long an idle connection to the reputation service is kept in the pool.
+const REPUTATION_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Parse a reputation list: one IP or CIDR per line, blank lines and `#` comments ignored.
+pub fn parse_reputation_list(contents: &str) -> anyhow::Result<Vec<IpNet>> {
+    contents
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line.split('#').next().unwrap_or_default().trim()))
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(line_no, line)| {
+            line.parse::<IpNet>()
+                .or_else(|_| line.parse::<IpAddr>().map(IpNet::from))
+                .map_err(|_| anyhow!("line {line_no}: invalid IP or CIDR: {line}"))
+        })
+        .collect()
+}
+
+/// Bad IPs and CIDRs loaded from a file.
+///
+/// The file is checked for changes at most once per reload interval, on the request path,
+/// so an updated threat feed is picked up without reloading the configuration.
+/// A file that fails to parse is logged and the previous list is kept.
+pub struct ReputationList {
+    path: PathBuf,
+    reload_interval: Option<Duration>,
+    networks: ArcSwap<Vec<IpNet>>,
+    state: Mutex<ListState>,
+}
+
+struct ListState {
+    checked_at: Instant,
+    fingerprint: Option<(SystemTime, u64)>,
+}
+
+impl ReputationList {
+    /// Load the list, failing if the file cannot be read or parsed.
+    /// `reload_interval` of `None` never reloads it.
+    pub fn load(path: &Path, reload_interval: Option<Duration>) -> anyhow::Result<Self> {
+        let fingerprint = fingerprint(path);
+        let networks = read_list(path)?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            reload_interval,
+            networks: ArcSwap::from_pointee(networks),
+            state: Mutex::new(ListState {
+                checked_at: Instant::now(),
+                fingerprint,
+            }),
+        })
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        self.reload_if_changed();
+        self.networks.load().iter().any(|net| net.contains(&ip))
+    }
+
+    fn reload_if_changed(&self) {
+        let Some(interval) = self.reload_interval else {
+            return;
+        };
+        // Another request is already checking the file.
+        let Ok(mut state) = self.state.try_lock() else {
+            return;
+        };
+        if state.checked_at.elapsed() < interval {
+            return;
+        }
+        state.checked_at = Instant::now();
+
+        let fingerprint = fingerprint(&self.path);
+        if fingerprint == state.fingerprint {
+            return;
+        }
+        state.fingerprint = fingerprint;
+
+        match read_list(&self.path) {
+            Ok(networks) => {
+                tracing::info!(
+                    path = %self.path.display(),
+                    entries = networks.len(),
+                    "reloaded ip reputation list"
+                );
+                self.networks.store(Arc::new(networks));
+            }
+            Err(e) => {
+                tracing::warn!(
+                    path = %self.path.display(),
+                    error = %e,
+                    "failed to reload ip reputation list, keeping the previous list"
+                );
+            }
+        }
+    }
+}
+
+/// Modification time and size, used to notice the list file changed.
+fn fingerprint(path: &Path) -> Option<(SystemTime, u64)> {
+    let meta = std::fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
+
+fn read_list(path: &Path) -> anyhow::Result<Vec<IpNet>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read ip reputation list {}", path.display()))?;
+    parse_reputation_list(&contents)
+        .with_context(|| format!("invalid ip reputation list {}", path.display()))
+}
+
+/// Client of the external reputation service.
+///
+/// The client IP is sent in the `X-Client-IP` header. `403` means the IP is bad,
+/// any other `2xx` means it is clean. Verdicts are cached per IP for the cache TTL.
+struct ReputationService {
+    connector: Connector,
+    peer: HttpPeer,
+    authority: String,
+    path: String,
+    timeout: Duration,
+    cache_ttl: Duration,
+    cache: DashMap<IpAddr, (Instant, bool)>,
+}
+
+impl ReputationService {
+    fn new(url: &str, timeout: Duration, cache_ttl: Duration) -> anyhow::Result<Self> {
+        let uri: Uri = url.parse()?;
+        let tls = uri.scheme_str() == Some("https");
+        let host = uri
+            .host()
+            .ok_or_else(|| anyhow!("reputation_url has no host: {url}"))?
+            .to_string();
+        let port = uri.port_u16().unwrap_or(if tls { 443 } else { 80 });
+        let path = uri
+            .path_and_query()
+            .map(|p| p.as_str().to_string())
+            .unwrap_or_else(|| "/".to_string());
+
+        Ok(Self {
+            connector: Connector::new(None),
+            peer: HttpPeer::new((host.as_str(), port), tls, host.clone()),
+            authority: format!("{}:{}", host, port),
+            path,
+            timeout,
+            cache_ttl,
+            cache: DashMap::new(),
+        })
+    }
+
+    /// Whether the service considers the IP bad, from the cache when possible.
+    async fn is_bad(&self, ip: IpAddr) -> anyhow::Result<bool> {
+        if let Some(entry) = self.cache.get(&ip) {
+            let (cached_at, bad) = *entry.value();
+            if cached_at.elapsed() < self.cache_ttl {
+                return Ok(bad);
+            }
+        }
+
+        let bad = tokio::time::timeout(self.timeout, self.call_reputation_service(ip))
+            .await
+            .map_err(|_| anyhow!("reputation service timed out after {:?}", self.timeout))??;
+
+        if !self.cache_ttl.is_zero() {
+            if self.cache.len() >= MAX_CACHED_VERDICTS {
+                self.cache
+                    .retain(|_, (cached_at, _)| cached_at.elapsed() < self.cache_ttl);
+            }
+            self.cache.insert(ip, (Instant::now(), bad));
+        }
+
+        Ok(bad)
+    }
+
+    /// Drop the cached verdicts for IPs `purge` matches, returning how many.
+    fn purge(&self, purge: &CachePurge) -> usize {
+        let mut purged = 0;
+        self.cache.retain(|ip, _| {
+            let matches = purge.matches(&ip.to_string());
+            purged += usize::from(matches);
+            !matches
+        });
+        purged
+    }
+
+    async fn call_reputation_service(&self, ip: IpAddr) -> anyhow::Result<bool> {
+        let mut req = RequestHeader::build(Method::GET, self.path.as_bytes(), None)?;
+        req.insert_header(header::HOST, &self.authority)?;
+        req.insert_header(CLIENT_IP_HEADER, ip.to_string())?;
+
+        let (mut session, _reused) = self.connector.get_http_session(&self.peer).await?;
+        session.write_request_header(Box::new(req)).await?;
+        session.finish_request_body().await?;
+        session.read_response_header().await?;
+
+        let status = session
+            .response_header()
+            .ok_or_else(|| anyhow!("reputation service sent no response header"))?
+            .status;
+        let bad = match status {
+            StatusCode::FORBIDDEN => true,
+            s if s.is_success() => false,
+            s => return Err(anyhow!("unexpected reputation service status: {s}")),
+        };
+
+        // Drain the body so the connection can be reused.
+        while session.read_response_body().await?.is_some() {}
+        self.connector
+            .release_http_session(session, &self.peer, Some(REPUTATION_IDLE_TIMEOUT))
+            .await;
+
+        Ok(bad)
+    }
+}
+
+/// Checks the client IP against a local reputation list and, optionally, an external
+/// reputation service.
+///
+/// Bad IPs are either blocked, or tagged with a header sent to the upstream so it can decide,
+/// e.g. by challenging the client. The client IP is the one the identity device resolved,
+/// falling back to the connection's peer IP.
+pub struct IpReputationDevice {
+    list: Option<ReputationList>,
+    service: Option<ReputationService>,
+    fail_open: bool,
+    action: IpReputationAction,
+    block_status: StatusCode,
+    tag_header: HeaderName,
+}
+
+impl IpReputationDevice {
+    pub fn from_config(cfg: IpReputationDeviceConfig) -> anyhow::Result<Self> {
+        let reload_interval = (cfg.list_reload_interval_seconds > 0)
+            .then(|| Duration::from_secs(cfg.list_reload_interval_seconds));
+        let list = cfg
+            .list_file
+            .as_deref()
+            .map(|path| ReputationList::load(path, reload_interval))
+            .transpose()?;
+
+        let service = cfg
+            .reputation_url
+            .as_deref()
+            .map(|url| {
+                ReputationService::new(
+                    url,
+                    Duration::from_millis(cfg.timeout_milliseconds),
+                    Duration::from_millis(cfg.cache_ttl_milliseconds),
+                )
+            })
+            .transpose()?;
+
+        Ok(Self {
+            list,
+            service,
+            fail_open: cfg.fail_open,
+            action: cfg.action,
+            block_status: StatusCode::from_u16(cfg.block_status)?,
+            tag_header: HeaderName::from_bytes(cfg.tag_header.as_bytes())?,
+        })
+    }
+
+    async fn is_bad(&self, ip: IpAddr) -> Result<bool, DeviceError> {
+        if self.list.as_ref().is_some_and(|list| list.contains(ip)) {
+            return Ok(true);
+        }
+
+        let Some(service) = &self.service else {
+            return Ok(false);
+        };
+        match service.is_bad(ip).await {
+            Ok(bad) => Ok(bad),
+            Err(e) if self.fail_open => {
+                tracing::warn!(error = %e, "reputation service unavailable, failing open");
+                Ok(false)
+            }
+            Err(e) => Err(DeviceError {
+                message: format!("reputation service unavailable: {e}"),
+                fatal: true,
+            }),
+        }
+    }
+}
+
+#[async_trait]
+impl Device for IpReputationDevice {
+    fn name(&self) -> &str {
+        "IP Reputation"
+    }
+
+    async fn on_request_async(&self, ctx: &mut RequestCtx) -> DeviceResult {
+        let ip = ctx
+            .extensions
+            .get::<ClientIdentity>()
+            .map(|identity| identity.ip)
+            .unwrap_or(ctx.peer_ip);
+
+        let bad = match self.is_bad(ip).await {
+            Ok(bad) => bad,
+            Err(e) => return DeviceResult::Error(e),
+        };
+
+        match self.action {
+            IpReputationAction::Block if bad => DeviceResult::Respond(ResponseCtx::new(
+                ctx.request_id(),
+                self.block_status,
+                Default::default(),
+                Vec::new(),
+            )),
+            IpReputationAction::Block => DeviceResult::Continue,
+            IpReputationAction::Tag => {
+                // Always set, so a client cannot send its own verdict upstream.
+                let verdict = if bad { "bad" } else { "clean" };
+                ctx.upstream_headers
+                    .insert(self.tag_header.clone(), HeaderValue::from_static(verdict));
+                DeviceResult::Continue
+            }
+        }
+    }
+
+    /// Verdicts of the reputation service are cached by IP. The list is not a cache; it is
+    /// only reloaded from its file.
+    fn purge_cache(&self, purge: &CachePurge) -> usize {
+        self.service
+            .as_ref()
+            .map_or(0, |service| service.purge(purge))
+    }
+
+    fn reset(&self) -> bool {
+        self.purge_cache(&CachePurge::All);
+        self.service.is_some()
+    }
+}
@@ -0,0 +1,372 @@
+use crate::conf::types::JwtAuthDeviceConfig;
+use crate::ctx::{RequestCtx, ResponseCtx};
+use crate::device::core::{Device, DeviceResult};
+use anyhow::{anyhow, bail};
+use arc_swap::ArcSwapOption;
+use async_trait::async_trait;
+use http::{HeaderMap, HeaderValue, Method, StatusCode, Uri, header};
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use pingora::connectors::http::Connector;
+use pingora::http::RequestHeader;
+use pingora::upstreams::peer::HttpPeer;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// JWKS documents larger than this are rejected.
+const MAX_JWKS_BYTES: usize = 1024 * 1024;
+
+/// How long an idle connection to the JWKS endpoint is kept in the pool.
+const JWKS_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Claims of a validated token, attached to the request context.
+///
+/// Downstream devices read them with `ctx.extensions.get::<JwtClaims>()`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JwtClaims(pub Map<String, Value>);
+
+impl JwtClaims {
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.0.get(name)
+    }
+
+    /// The `sub` claim, if it is a string.
+    pub fn subject(&self) -> Option<&str> {
+        self.get("sub").and_then(Value::as_str)
+    }
+}
+
+/// Where token signing keys come from.
+enum KeySource {
+    Hmac(DecodingKey),
+    Jwks(JwksClient),
+}
+
+/// Keys from one JWKS fetch.
+struct CachedJwks {
+    /// When the keys were fetched, or last failed to be fetched again.
+    refreshed_at: Instant,
+
+    /// Keys with a `kid`, matched against the token header.
+    by_kid: HashMap<String, DecodingKey>,
+
+    /// Keys without a `kid`, tried when the token header has none.
+    anonymous: Vec<DecodingKey>,
+}
+
+impl CachedJwks {
+    fn from_jwks(jwks: &JwkSet) -> Self {
+        let mut by_kid = HashMap::new();
+        let mut anonymous = Vec::new();
+
+        for jwk in &jwks.keys {
+            // Keys this build can't use (e.g. unsupported curves) are skipped, not fatal.
+            let Ok(key) = DecodingKey::from_jwk(jwk) else {
+                continue;
+            };
+            match &jwk.common.key_id {
+                Some(kid) => {
+                    by_kid.insert(kid.clone(), key);
+                }
+                None => anonymous.push(key),
+            }
+        }
+
+        Self {
+            refreshed_at: Instant::now(),
+            by_kid,
+            anonymous,
+        }
+    }
+
+    /// The same keys, kept for another refresh interval after a failed refresh.
+    fn renewed(&self) -> Self {
+        Self {
+            refreshed_at: Instant::now(),
+            by_kid: self.by_kid.clone(),
+            anonymous: self.anonymous.clone(),
+        }
+    }
+
+    fn keys_for(&self, kid: Option<&str>) -> Vec<&DecodingKey> {
+        match kid {
+            Some(kid) => self.by_kid.get(kid).into_iter().collect(),
+            None => self.anonymous.iter().collect(),
+        }
+    }
+}
+
+/// Fetches and caches the keys of a JWKS endpoint.
+struct JwksClient {
+    connector: Connector,
+    peer: HttpPeer,
+    authority: String,
+    path: String,
+    timeout: Duration,
+    refresh: Duration,
+    cache: ArcSwapOption<CachedJwks>,
+
+    /// Held while the keys are fetched, so concurrent requests share one fetch.
+    refreshing: tokio::sync::Mutex<()>,
+}
+
+impl JwksClient {
+    fn new(url: &str, timeout: Duration, refresh: Duration) -> anyhow::Result<Self> {
+        let uri: Uri = url.parse()?;
+        let tls = uri.scheme_str() == Some("https");
+        let host = uri
+            .host()
+            .ok_or_else(|| anyhow!("jwks_url has no host: {}", url))?
+            .to_string();
+        let port = uri.port_u16().unwrap_or(if tls { 443 } else { 80 });
+        let path = uri
+            .path_and_query()
+            .map(|p| p.as_str().to_string())
+            .unwrap_or_else(|| "/".to_string());
+
+        Ok(Self {
+            connector: Connector::new(None),
+            peer: HttpPeer::new((host.as_str(), port), tls, host.clone()),
+            authority: format!("{}:{}", host, port),
+            path,
+            timeout,
+            refresh,
+            cache: ArcSwapOption::empty(),
+            refreshing: tokio::sync::Mutex::new(()),
+        })
+    }
+
+    /// The cached keys, fetched again once they are older than the refresh interval.
+    ///
+    /// One request fetches the keys at a time. While it does, other requests use the stale keys,
+    /// or wait for it if there are none yet. If a refresh fails, the stale keys are kept for
+    /// another refresh interval, so an unavailable endpoint does not delay every request.
+    async fn keys(&self) -> anyhow::Result<Arc<CachedJwks>> {
+        if let Some(fresh) = self.fresh_keys() {
+            return Ok(fresh);
+        }
+
+        let _refreshing = match self.cache.load_full() {
+            Some(stale) => match self.refreshing.try_lock() {
+                Ok(guard) => guard,
+                Err(_) => return Ok(stale),
+            },
+            None => self.refreshing.lock().await,
+        };
+
+        // Another request may have refreshed the keys while this one waited.
+        if let Some(fresh) = self.fresh_keys() {
+            return Ok(fresh);
+        }
+
+        match self.fetch().await {
+            Ok(jwks) => {
+                let fresh = Arc::new(CachedJwks::from_jwks(&jwks));
+                self.cache.store(Some(fresh.clone()));
+                Ok(fresh)
+            }
+            Err(e) => match self.cache.load_full() {
+                Some(stale) => {
+                    tracing::warn!(
+                        error = %e,
+                        retry_in = ?self.refresh,
+                        "JWKS refresh failed, using stale keys"
+                    );
+                    let renewed = Arc::new(stale.renewed());
+                    self.cache.store(Some(renewed.clone()));
+                    Ok(renewed)
+                }
+                None => Err(e),
+            },
+        }
+    }
+
+    /// The cached keys, if they are younger than the refresh interval.
+    fn fresh_keys(&self) -> Option<Arc<CachedJwks>> {
+        self.cache
+            .load_full()
+            .filter(|cached| cached.refreshed_at.elapsed() < self.refresh)
+    }
+
+    async fn fetch(&self) -> anyhow::Result<JwkSet> {
+        tokio::time::timeout(self.timeout, self.call_jwks_endpoint())
+            .await
+            .map_err(|_| anyhow!("JWKS endpoint timed out after {:?}", self.timeout))?
+    }
+
+    async fn call_jwks_endpoint(&self) -> anyhow::Result<JwkSet> {
+        let mut req = RequestHeader::build(Method::GET, self.path.as_bytes(), None)?;
+        req.insert_header(header::HOST, &self.authority)?;
+        req.insert_header(header::ACCEPT, "application/json")?;
+
+        let (mut session, _reused) = self.connector.get_http_session(&self.peer).await?;
+        session.write_request_header(Box::new(req)).await?;
+        session.finish_request_body().await?;
+        session.read_response_header().await?;
+
+        let status = session
+            .response_header()
+            .ok_or_else(|| anyhow!("JWKS endpoint sent no response header"))?
+            .status;
+        if !status.is_success() {
+            bail!("unexpected JWKS endpoint status: {}", status);
+        }
+
+        let mut body = Vec::new();
+        while let Some(chunk) = session.read_response_body().await? {
+            if body.len() + chunk.len() > MAX_JWKS_BYTES {
+                bail!("JWKS document exceeds {} bytes", MAX_JWKS_BYTES);
+            }
+            body.extend_from_slice(&chunk);
+        }
+        self.connector
+            .release_http_session(session, &self.peer, Some(JWKS_IDLE_TIMEOUT))
+            .await;
+
+        Ok(serde_json::from_slice(&body)?)
+    }
+}
+
+/// Why a request was not authenticated.
+#[derive(Debug, PartialEq)]
+pub enum JwtRejection {
+    /// No bearer token in the `Authorization` header.
+    MissingToken,
+
+    /// The token is malformed, badly signed, expired, or fails a claim check.
+    InvalidToken(String),
+}
+
+/// Validates bearer tokens against an HMAC secret or the keys of a JWKS endpoint.
+///
+/// Requests without a valid token are short-circuited with `401`. The claims of a valid token
+/// are attached to the request context as [`JwtClaims`] for downstream devices.
+///
+/// JWKS keys are cached for `jwks_refresh_seconds`, so rotated keys are picked up within one
+/// refresh interval.
+pub struct JwtAuthDevice {
+    keys: KeySource,
+    validation: Validation,
+}
+
+impl JwtAuthDevice {
+    pub fn from_config(cfg: JwtAuthDeviceConfig) -> anyhow::Result<Self> {
+        let (keys, default_algorithm) = match (&cfg.hmac_secret, &cfg.jwks_url) {
+            (Some(secret), None) => (
+                KeySource::Hmac(DecodingKey::from_secret(secret.as_bytes())),
+                Algorithm::HS256,
+            ),
+            (None, Some(url)) => (
+                KeySource::Jwks(JwksClient::new(
+                    url,
+                    Duration::from_millis(cfg.timeout_milliseconds),
+                    Duration::from_secs(cfg.jwks_refresh_seconds),
+                )?),
+                Algorithm::RS256,
+            ),
+            _ => bail!("jwt_auth device needs exactly one of hmac_secret and jwks_url"),
+        };
+
+        let mut validation = Validation::new(default_algorithm);
+        if !cfg.algorithms.is_empty() {
+            validation.algorithms = cfg
+                .algorithms
+                .iter()
+                .map(|a| Algorithm::from_str(a).map_err(|_| anyhow!("unknown JWT algorithm: {a}")))
+                .collect::<anyhow::Result<_>>()?;
+        }
+        validation.leeway = cfg.leeway_seconds;
+        validation.validate_nbf = true;
+        if let Some(issuer) = &cfg.issuer {
+            validation.set_issuer(&[issuer]);
+        }
+        match &cfg.audience {
+            Some(audience) => validation.set_audience(&[audience]),
+            None => validation.validate_aud = false,
+        }
+
+        Ok(Self { keys, validation })
+    }
+
+    /// Validate the bearer token in `headers` and return its claims.
+    pub async fn authenticate(&self, headers: &HeaderMap) -> Result<JwtClaims, JwtRejection> {
+        let token = bearer_token(headers).ok_or(JwtRejection::MissingToken)?;
+        let invalid = |e: jsonwebtoken::errors::Error| JwtRejection::InvalidToken(e.to_string());
+
+        match &self.keys {
+            KeySource::Hmac(key) => self.decode(token, key).map_err(invalid),
+            KeySource::Jwks(client) => {
+                let kid = jsonwebtoken::decode_header(token).map_err(invalid)?.kid;
+                let jwks = client.keys().await.map_err(|e| {
+                    tracing::warn!(error = %e, "JWKS unavailable");
+                    JwtRejection::InvalidToken("signing keys unavailable".to_string())
+                })?;
+
+                let mut last_error = JwtRejection::InvalidToken("unknown signing key".to_string());
+                for key in jwks.keys_for(kid.as_deref()) {
+                    match self.decode(token, key) {
+                        Ok(claims) => return Ok(claims),
+                        Err(e) => last_error = invalid(e),
+                    }
+                }
+                Err(last_error)
+            }
+        }
+    }
+
+    fn decode(&self, token: &str, key: &DecodingKey) -> jsonwebtoken::errors::Result<JwtClaims> {
+        jsonwebtoken::decode::<Map<String, Value>>(token, key, &self.validation)
+            .map(|data| JwtClaims(data.claims))
+    }
+}
+
+/// The token from an `Authorization: Bearer <token>` header.
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    let value = headers.get(header::AUTHORIZATION)?.to_str().ok()?;
+    let (scheme, token) = value.split_once(' ')?;
+    let token = token.trim();
+    (scheme.eq_ignore_ascii_case("bearer") && !token.is_empty()).then_some(token)
+}
+
+/// The `WWW-Authenticate` challenge for a rejection, per RFC 6750.
+fn challenge(rejection: &JwtRejection) -> HeaderValue {
+    match rejection {
+        JwtRejection::MissingToken => HeaderValue::from_static("Bearer"),
+        JwtRejection::InvalidToken(_) => {
+            HeaderValue::from_static(r#"Bearer error="invalid_token""#)
+        }
+    }
+}
+
+#[async_trait]
+impl Device for JwtAuthDevice {
+    fn name(&self) -> &str {
+        "JWT Auth"
+    }
+
+    async fn on_request_async(&self, ctx: &mut RequestCtx) -> DeviceResult {
+        match self.authenticate(ctx.headers()).await {
+            Ok(claims) => {
+                ctx.extensions.insert(claims);
+                DeviceResult::Continue
+            }
+            Err(rejection) => {
+                if let JwtRejection::InvalidToken(reason) = &rejection {
+                    tracing::debug!(reason = %reason, "rejected JWT");
+                }
+
+                let mut headers = HeaderMap::new();
+                headers.insert(header::WWW_AUTHENTICATE, challenge(&rejection));
+                DeviceResult::Respond(ResponseCtx::new(
+                    ctx.request_id(),
+                    StatusCode::UNAUTHORIZED,
+                    headers,
+                    Vec::new(),
+                ))
+            }
+        }
+    }
+}
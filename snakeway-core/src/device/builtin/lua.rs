@@ -0,0 +1,434 @@
+use crate::conf::types::LuaDeviceConfig;
+use crate::ctx::{RequestCtx, ResponseCtx};
+use crate::device::core::errors::DeviceError;
+use crate::device::core::{Device, DeviceResult};
+use anyhow::{Context, bail};
+use http::{HeaderMap, HeaderName, HeaderValue, StatusCode, header};
+use mlua::{ChunkMode, Function, HookTriggers, Lua, LuaOptions, StdLib, Table};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// Instructions run between two checks of the instruction budget.
+const INSTRUCTION_CHECK_INTERVAL: u32 = 1_000;
+
+/// Base library globals removed from the sandbox.
+/// They load code, or catch the error that stops a script over its instruction budget.
+const REMOVED_GLOBALS: &[&str] = &[
+    "collectgarbage",
+    "dofile",
+    "load",
+    "loadfile",
+    "pcall",
+    "print",
+    "require",
+    "xpcall",
+];
+
+/// Content type of a short-circuit response with a body and no `content-type` header.
+const DEFAULT_CONTENT_TYPE: &str = "text/plain; charset=utf-8";
+
+/// Runs the `on_request` and `on_response` functions of a sandboxed Lua script.
+///
+/// `on_request(req)` may change `req.headers`, which are then sent upstream, or return a
+/// `{ status, headers, body }` table to respond without proxying. `on_response(res)` may change
+/// `res.status`, `res.headers` and `res.body`. Both run on a budget of Lua VM instructions, and
+/// a script that fails is skipped, so the request continues as if the device was not there.
+pub struct LuaDevice {
+    source: String,
+    /// Chunk name in Lua error messages.
+    chunk_name: String,
+    instruction_limit: u64,
+    memory_limit_bytes: usize,
+    max_body_bytes: usize,
+    offload: bool,
+    has_on_request: bool,
+    has_on_response: bool,
+    /// Idle Lua states with the script loaded, reused across hook calls.
+    states: Mutex<Vec<Sandbox>>,
+}
+
+/// A Lua state with the script loaded and an instruction budget.
+struct Sandbox {
+    lua: Lua,
+    /// Instructions left for the current call.
+    budget: Arc<AtomicU64>,
+}
+
+/// Raised when a script runs out of instructions, e.g. stuck in a loop.
+#[derive(Debug, thiserror::Error)]
+#[error("Lua script ran out of instructions")]
+struct OutOfInstructions;
+
+impl LuaDevice {
+    /// Load the script, failing if it does not compile, fails when run, or defines neither hook.
+    pub fn from_config(cfg: LuaDeviceConfig) -> anyhow::Result<Self> {
+        let source = std::fs::read_to_string(&cfg.path)
+            .with_context(|| format!("failed to read Lua script {}", cfg.path.display()))?;
+
+        let mut device = Self {
+            source,
+            chunk_name: format!("@{}", cfg.path.display()),
+            instruction_limit: cfg.instruction_limit,
+            memory_limit_bytes: cfg.memory_limit_bytes,
+            max_body_bytes: cfg.max_body_bytes,
+            offload: cfg.offload,
+            has_on_request: false,
+            has_on_response: false,
+            states: Mutex::new(Vec::new()),
+        };
+
+        let sandbox = device.sandbox()?;
+        let globals = sandbox.lua.globals();
+        device.has_on_request = globals.get::<_, Option<Function>>("on_request")?.is_some();
+        device.has_on_response = globals.get::<_, Option<Function>>("on_response")?.is_some();
+        drop(globals);
+
+        if !device.has_on_request && !device.has_on_response {
+            bail!(
+                "Lua script {} defines neither on_request nor on_response",
+                cfg.path.display()
+            );
+        }
+
+        device.idle().push(sandbox);
+        Ok(device)
+    }
+
+    /// A new Lua state running the script, with the standard library reduced to string, table,
+    /// math and utf8 functions.
+    fn sandbox(&self) -> anyhow::Result<Sandbox> {
+        let lua = Lua::new_with(
+            StdLib::STRING | StdLib::TABLE | StdLib::MATH | StdLib::UTF8,
+            LuaOptions::default(),
+        )?;
+        lua.set_memory_limit(self.memory_limit_bytes)?;
+
+        let globals = lua.globals();
+        for name in REMOVED_GLOBALS {
+            globals.set(*name, mlua::Value::Nil)?;
+        }
+        drop(globals);
+
+        let budget = Arc::new(AtomicU64::new(self.instruction_limit));
+        let left = budget.clone();
+        lua.set_hook(
+            HookTriggers::new().every_nth_instruction(INSTRUCTION_CHECK_INTERVAL),
+            move |_, _| {
+                let interval = u64::from(INSTRUCTION_CHECK_INTERVAL);
+                match left.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| {
+                    n.checked_sub(interval)
+                }) {
+                    Ok(_) => Ok(()),
+                    Err(_) => Err(mlua::Error::runtime(OutOfInstructions)),
+                }
+            },
+        );
+
+        let loaded = lua
+            .load(self.source.as_str())
+            .set_name(self.chunk_name.as_str())
+            .set_mode(ChunkMode::Text)
+            .exec();
+        let sandbox = Sandbox { lua, budget };
+        sandbox.check_budget(loaded)?;
+
+        Ok(sandbox)
+    }
+
+    fn idle(&self) -> MutexGuard<'_, Vec<Sandbox>> {
+        // The pool holds no invariants a panic could break.
+        self.states.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Call a hook in an idle Lua state with a fresh instruction budget.
+    ///
+    /// A state whose call failed is dropped, since the script may have stopped half way
+    /// through changing its globals.
+    fn call<R>(
+        &self,
+        hook: &str,
+        f: impl for<'lua> FnOnce(&'lua Lua, Function<'lua>) -> mlua::Result<R>,
+    ) -> anyhow::Result<R> {
+        let sandbox = match self.idle().pop() {
+            Some(sandbox) => sandbox,
+            None => self.sandbox()?,
+        };
+        sandbox
+            .budget
+            .store(self.instruction_limit, Ordering::Relaxed);
+
+        let result = sandbox
+            .lua
+            .globals()
+            .get::<_, Function>(hook)
+            .and_then(|func| f(&sandbox.lua, func));
+        let result = sandbox.check_budget(result)?;
+
+        self.idle().push(sandbox);
+        Ok(result)
+    }
+
+    /// Fail open when a hook call fails.
+    /// A script that runs out of instructions is reported to `on_error` handlers, since it is
+    /// likely stuck in a loop.
+    fn call_failed(&self, hook: &str, e: anyhow::Error) -> DeviceResult {
+        if e.downcast_ref::<OutOfInstructions>().is_some() {
+            tracing::warn!(
+                "Lua {hook} ran out of instructions ({}), continuing",
+                self.instruction_limit
+            );
+            return DeviceResult::Error(DeviceError {
+                message: format!("Lua device ran out of instructions in {hook}"),
+                fatal: false,
+            });
+        }
+
+        tracing::error!("Lua {hook} failed: {e:#}");
+        DeviceResult::Continue
+    }
+
+    fn check_body(&self, body: &[u8]) -> anyhow::Result<()> {
+        if body.len() > self.max_body_bytes {
+            bail!(
+                "Lua body of {} bytes is larger than max_body_bytes ({})",
+                body.len(),
+                self.max_body_bytes
+            );
+        }
+        Ok(())
+    }
+
+    /// The response an `on_request` table short-circuits with.
+    fn short_circuit(
+        &self,
+        ctx: &RequestCtx,
+        response: ScriptResponse,
+    ) -> anyhow::Result<ResponseCtx> {
+        let status = StatusCode::from_u16(response.status)?;
+        let body = response.body.unwrap_or_default();
+        self.check_body(&body)?;
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in response.headers {
+            let (name, value) = header_pair(&name, &value)?;
+            headers.insert(name, value);
+        }
+        // Responses without headers are sent without their body.
+        if !body.is_empty() && !headers.contains_key(header::CONTENT_TYPE) {
+            headers.insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static(DEFAULT_CONTENT_TYPE),
+            );
+        }
+
+        Ok(ResponseCtx::new(ctx.request_id(), status, headers, body))
+    }
+}
+
+impl Sandbox {
+    /// Report a call stopped by the instruction hook as `OutOfInstructions`.
+    fn check_budget<R>(&self, result: mlua::Result<R>) -> anyhow::Result<R> {
+        let exhausted = self.budget.load(Ordering::Relaxed) < u64::from(INSTRUCTION_CHECK_INTERVAL);
+        match result {
+            Err(_) if exhausted => Err(OutOfInstructions.into()),
+            result => Ok(result?),
+        }
+    }
+}
+
+/// The table `on_request` returned to respond without proxying.
+struct ScriptResponse {
+    status: u16,
+    headers: BTreeMap<String, String>,
+    body: Option<Vec<u8>>,
+}
+
+impl ScriptResponse {
+    fn read(table: Table) -> mlua::Result<Self> {
+        let headers = table
+            .get::<_, Option<Table>>("headers")?
+            .map(read_headers)
+            .transpose()?
+            .unwrap_or_default();
+        let body = table
+            .get::<_, Option<mlua::String>>("body")?
+            .map(|body| body.as_bytes().to_vec());
+
+        Ok(Self {
+            status: table.get("status")?,
+            headers,
+            body,
+        })
+    }
+}
+
+impl Device for LuaDevice {
+    fn name(&self) -> &str {
+        "Lua"
+    }
+
+    fn offload(&self) -> bool {
+        self.offload
+    }
+
+    fn on_request(&self, ctx: &mut RequestCtx) -> DeviceResult {
+        if !self.has_on_request {
+            return DeviceResult::Continue;
+        }
+
+        let before = header_strings(ctx.headers());
+        let called = self.call("on_request", |lua, on_request| {
+            let req = lua.create_table()?;
+            req.set("method", ctx.method_str())?;
+            req.set("path", ctx.canonical_path())?;
+            req.set("original_path", ctx.original_uri_path())?;
+            req.set("query", ctx.upstream_query())?;
+            req.set("client_ip", ctx.peer_ip.to_string())?;
+            req.set("headers", headers_table(lua, &before)?)?;
+
+            let response = on_request
+                .call::<_, Option<Table>>(req.clone())?
+                .map(ScriptResponse::read)
+                .transpose()?;
+            let after = read_headers(req.get("headers")?)?;
+            Ok((after, response))
+        });
+
+        let result = called.and_then(|(after, response)| match response {
+            Some(response) => self.short_circuit(ctx, response).map(Some),
+            None => {
+                let changes = HeaderChanges::between(&before, &after)?;
+                for (name, value) in changes.set {
+                    ctx.insert_header(name, value);
+                }
+                for name in changes.removed {
+                    ctx.remove_header(name.as_str());
+                }
+                Ok(None)
+            }
+        });
+
+        match result {
+            Ok(Some(response)) => DeviceResult::Respond(response),
+            Ok(None) => DeviceResult::Continue,
+            Err(e) => self.call_failed("on_request", e),
+        }
+    }
+
+    fn after_proxy(&self, ctx: &mut ResponseCtx) -> DeviceResult {
+        if !self.has_on_response {
+            return DeviceResult::Continue;
+        }
+
+        let before = header_strings(&ctx.headers);
+        let called = self.call("on_response", |lua, on_response| {
+            let res = lua.create_table()?;
+            res.set("status", ctx.status.as_u16())?;
+            res.set("headers", headers_table(lua, &before)?)?;
+            res.set("body", lua.create_string(&ctx.body)?)?;
+
+            on_response.call::<_, ()>(res.clone())?;
+
+            let status: u16 = res.get("status")?;
+            let headers = read_headers(res.get("headers")?)?;
+            let body = res.get::<_, mlua::String>("body")?.as_bytes().to_vec();
+            Ok((status, headers, body))
+        });
+
+        let result = called.and_then(|(status, after, body)| {
+            let status = StatusCode::from_u16(status)?;
+            let changes = HeaderChanges::between(&before, &after)?;
+            self.check_body(&body)?;
+
+            ctx.status = status;
+            for (name, value) in changes.set {
+                ctx.headers.insert(name, value);
+            }
+            for name in changes.removed {
+                ctx.headers.remove(name);
+            }
+            // The body starts empty, so a script that sets it replaces the upstream body.
+            ctx.body = body;
+            Ok(())
+        });
+
+        match result {
+            Ok(()) => DeviceResult::Continue,
+            Err(e) => self.call_failed("on_response", e),
+        }
+    }
+}
+
+/// Header changes a script made, by comparing its headers table before and after the call.
+/// Headers the script did not touch are left alone, so repeated headers keep every value.
+struct HeaderChanges {
+    set: Vec<(HeaderName, HeaderValue)>,
+    removed: Vec<HeaderName>,
+}
+
+impl HeaderChanges {
+    fn between(
+        before: &BTreeMap<String, String>,
+        after: &BTreeMap<String, String>,
+    ) -> anyhow::Result<Self> {
+        let set = after
+            .iter()
+            .filter(|(name, value)| before.get(*name) != Some(*value))
+            .map(|(name, value)| header_pair(name, value))
+            .collect::<anyhow::Result<_>>()?;
+        let removed = before
+            .keys()
+            .filter(|name| !after.contains_key(*name))
+            .map(|name| HeaderName::from_bytes(name.as_bytes()))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { set, removed })
+    }
+}
+
+/// Headers by lowercase name, with repeated headers joined by commas.
+/// Headers that are not valid UTF-8 are left out.
+fn header_strings(headers: &HeaderMap) -> BTreeMap<String, String> {
+    let mut strings = BTreeMap::<String, String>::new();
+    for (name, value) in headers {
+        let Ok(value) = value.to_str() else {
+            continue;
+        };
+        strings
+            .entry(name.as_str().to_string())
+            .and_modify(|joined| {
+                joined.push_str(", ");
+                joined.push_str(value);
+            })
+            .or_insert_with(|| value.to_string());
+    }
+    strings
+}
+
+fn headers_table<'lua>(
+    lua: &'lua Lua,
+    headers: &BTreeMap<String, String>,
+) -> mlua::Result<Table<'lua>> {
+    lua.create_table_from(
+        headers
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str())),
+    )
+}
+
+/// A headers table, with names lowercased so `X-Foo` and `x-foo` are the same header.
+fn read_headers(table: Table) -> mlua::Result<BTreeMap<String, String>> {
+    table
+        .pairs::<String, String>()
+        .map(|pair| pair.map(|(name, value)| (name.to_ascii_lowercase(), value)))
+        .collect()
+}
+
+fn header_pair(name: &str, value: &str) -> anyhow::Result<(HeaderName, HeaderValue)> {
+    let name = HeaderName::from_bytes(name.as_bytes())
+        .with_context(|| format!("invalid header name from Lua: {name}"))?;
+    let value = HeaderValue::from_str(value)
+        .with_context(|| format!("invalid value from Lua for header {name}"))?;
+    Ok((name, value))
+}
@@ -1,3 +1,24 @@
+pub mod body_digest;
+pub mod cors;
+pub mod deprecation;
+pub mod error_page;
+pub mod experiment;
+pub mod header_rewrite;
+pub mod idempotency;
 pub mod identity;
+pub mod ip_reputation;
+pub mod jwt_auth;
+#[cfg(feature = "lua")]
+pub mod lua;
+pub mod openapi;
+pub mod query_rewrite;
+pub mod quota;
+pub mod rate_limit;
 pub mod request_filter;
+pub mod required_headers;
+pub mod response_headers;
+pub mod status_remap;
 pub mod structured_logging;
+#[cfg(test)]
+mod tests;
+pub mod upstream_group;
@@ -0,0 +1,823 @@
+use crate::conf::types::OpenApiDeviceConfig;
+use crate::ctx::{RequestCtx, ResponseCtx};
+use crate::device::core::{Device, DeviceResult};
+use anyhow::{Context, anyhow, bail};
+use bytes::{Bytes, BytesMut};
+use http::{HeaderMap, HeaderValue, Method, StatusCode, header};
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::path::Path;
+
+/// `$ref` chains deeper than this are reported as unresolvable, so recursive schemas terminate.
+const MAX_REF_DEPTH: usize = 32;
+
+/// Operation keys of an OpenAPI path item.
+const OPERATION_METHODS: [(&str, Method); 8] = [
+    ("get", Method::GET),
+    ("put", Method::PUT),
+    ("post", Method::POST),
+    ("delete", Method::DELETE),
+    ("options", Method::OPTIONS),
+    ("head", Method::HEAD),
+    ("patch", Method::PATCH),
+    ("trace", Method::TRACE),
+];
+
+/// One way a request does not match the API schema.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SchemaViolation {
+    /// Where the violation is, e.g. `query.limit` or `body.items[0].name`.
+    pub location: String,
+    pub message: String,
+}
+
+impl SchemaViolation {
+    fn new(location: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            location: location.into(),
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ParameterLocation {
+    Path,
+    Query,
+    Header,
+}
+
+impl ParameterLocation {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ParameterLocation::Path => "path",
+            ParameterLocation::Query => "query",
+            ParameterLocation::Header => "header",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Parameter {
+    name: String,
+    location: ParameterLocation,
+    required: bool,
+    schema: Value,
+}
+
+#[derive(Debug)]
+struct RequestBody {
+    required: bool,
+
+    /// Declared media types, e.g. `application/json`.
+    media_types: Vec<String>,
+
+    /// Schema of the JSON media type, if one is declared.
+    json_schema: Option<Value>,
+}
+
+#[derive(Debug)]
+enum Segment {
+    Literal(String),
+    Param(String),
+}
+
+#[derive(Debug)]
+struct Operation {
+    method: Method,
+    segments: Vec<Segment>,
+    parameters: Vec<Parameter>,
+    body: Option<RequestBody>,
+}
+
+impl Operation {
+    /// Path parameter values if `path` matches this operation's template.
+    fn match_path<'a>(&self, path: &[&'a str]) -> Option<Vec<(&str, &'a str)>> {
+        if path.len() != self.segments.len() {
+            return None;
+        }
+
+        let mut params = Vec::new();
+        for (segment, value) in self.segments.iter().zip(path) {
+            match segment {
+                Segment::Literal(literal) if literal.as_str() == *value => {}
+                Segment::Literal(_) => return None,
+                Segment::Param(name) => params.push((name.as_str(), *value)),
+            }
+        }
+        Some(params)
+    }
+
+    fn literal_segments(&self) -> usize {
+        self.segments
+            .iter()
+            .filter(|s| matches!(s, Segment::Literal(_)))
+            .count()
+    }
+}
+
+/// How a request path and method matched the document.
+pub enum OperationMatch<'a> {
+    Found {
+        index: usize,
+        path_params: Vec<(&'a str, &'a str)>,
+    },
+    MethodNotAllowed,
+    NotFound,
+}
+
+/// The operations of an OpenAPI 3 document, ready to validate requests against.
+pub struct OpenApiDocument {
+    root: Value,
+    operations: Vec<Operation>,
+}
+
+impl OpenApiDocument {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read OpenAPI document {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("invalid OpenAPI document {}", path.display()))
+    }
+
+    /// Parse a JSON or YAML OpenAPI 3 document.
+    pub fn parse(text: &str) -> anyhow::Result<Self> {
+        // JSON documents are valid YAML, so one parser handles both.
+        let root: Value = serde_yaml::from_str(text)?;
+
+        let version = root.get("openapi").and_then(Value::as_str).unwrap_or("");
+        if !version.starts_with("3.") {
+            bail!("only OpenAPI 3 documents are supported");
+        }
+
+        let mut doc = Self {
+            root,
+            operations: Vec::new(),
+        };
+        doc.operations = doc.read_operations()?;
+        Ok(doc)
+    }
+
+    fn read_operations(&self) -> anyhow::Result<Vec<Operation>> {
+        let Some(paths) = self.root.get("paths").and_then(Value::as_object) else {
+            bail!("document has no paths");
+        };
+
+        let mut operations = Vec::new();
+        for (template, item) in paths {
+            let item = self.resolve(item)?;
+            let shared = item.get("parameters");
+
+            for (key, method) in &OPERATION_METHODS {
+                let Some(op) = item.get(*key) else {
+                    continue;
+                };
+
+                operations.push(Operation {
+                    method: method.clone(),
+                    segments: parse_template(template),
+                    parameters: self.read_parameters(shared, op.get("parameters"))?,
+                    body: op
+                        .get("requestBody")
+                        .map(|b| self.read_request_body(b))
+                        .transpose()?,
+                });
+            }
+        }
+        Ok(operations)
+    }
+
+    /// Path item parameters, overridden by operation parameters with the same name and location.
+    fn read_parameters(
+        &self,
+        shared: Option<&Value>,
+        own: Option<&Value>,
+    ) -> anyhow::Result<Vec<Parameter>> {
+        let mut parameters: Vec<Parameter> = Vec::new();
+
+        let declared = [shared, own]
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_array)
+            .flatten();
+
+        for param in declared {
+            let param = self.resolve(param)?;
+            let name = param
+                .get("name")
+                .and_then(Value::as_str)
+                .ok_or_else(|| anyhow!("parameter has no name"))?;
+            let location = match param.get("in").and_then(Value::as_str) {
+                Some("path") => ParameterLocation::Path,
+                Some("query") => ParameterLocation::Query,
+                Some("header") => ParameterLocation::Header,
+                // Cookie parameters are not validated.
+                Some("cookie") => continue,
+                other => bail!("parameter {name} has an invalid location: {other:?}"),
+            };
+
+            let parameter = Parameter {
+                name: name.to_string(),
+                location,
+                required: location == ParameterLocation::Path
+                    || param.get("required").and_then(Value::as_bool) == Some(true),
+                schema: param.get("schema").cloned().unwrap_or_else(|| json!({})),
+            };
+
+            parameters.retain(|p| p.name != parameter.name || p.location != parameter.location);
+            parameters.push(parameter);
+        }
+        Ok(parameters)
+    }
+
+    fn read_request_body(&self, body: &Value) -> anyhow::Result<RequestBody> {
+        let body = self.resolve(body)?;
+        let content = body.get("content").and_then(Value::as_object);
+
+        let media_types = content
+            .map(|c| c.keys().map(|k| k.to_ascii_lowercase()).collect())
+            .unwrap_or_default();
+        let json_schema = content.and_then(|c| {
+            c.iter()
+                .find(|(media_type, _)| is_json(media_type))
+                .map(|(_, media)| media.get("schema").cloned().unwrap_or_else(|| json!({})))
+        });
+
+        Ok(RequestBody {
+            required: body.get("required").and_then(Value::as_bool) == Some(true),
+            media_types,
+            json_schema,
+        })
+    }
+
+    /// Follow `$ref`s to the value they point at.
+    fn resolve<'a>(&'a self, mut value: &'a Value) -> anyhow::Result<&'a Value> {
+        for _ in 0..MAX_REF_DEPTH {
+            let Some(reference) = value.get("$ref").and_then(Value::as_str) else {
+                return Ok(value);
+            };
+            value = reference
+                .strip_prefix('#')
+                .and_then(|pointer| self.root.pointer(pointer))
+                .ok_or_else(|| anyhow!("unresolvable $ref: {reference}"))?;
+        }
+        bail!("$ref chain is too deep")
+    }
+
+    /// Find the operation for a request, preferring templates with more literal segments.
+    pub fn match_operation<'a>(&'a self, method: &Method, path: &'a str) -> OperationMatch<'a> {
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+        let mut path_matched = false;
+        let mut best: Option<(usize, Vec<(&str, &str)>)> = None;
+
+        for (index, op) in self.operations.iter().enumerate() {
+            let Some(params) = op.match_path(&segments) else {
+                continue;
+            };
+            path_matched = true;
+
+            if op.method != *method {
+                continue;
+            }
+            let better = best.as_ref().is_none_or(|(current, _)| {
+                op.literal_segments() > self.operations[*current].literal_segments()
+            });
+            if better {
+                best = Some((index, params));
+            }
+        }
+
+        match best {
+            Some((index, path_params)) => OperationMatch::Found { index, path_params },
+            None if path_matched => OperationMatch::MethodNotAllowed,
+            None => OperationMatch::NotFound,
+        }
+    }
+
+    /// Validate path, query and header parameters of a matched operation.
+    pub fn validate_parameters(
+        &self,
+        index: usize,
+        path_params: &[(&str, &str)],
+        query: &[(String, String)],
+        headers: &HeaderMap,
+    ) -> Vec<SchemaViolation> {
+        let mut violations = Vec::new();
+
+        for param in &self.operations[index].parameters {
+            let location = format!("{}.{}", param.location.as_str(), param.name);
+
+            let values: Vec<&str> = match param.location {
+                ParameterLocation::Path => path_params
+                    .iter()
+                    .filter(|(name, _)| *name == param.name)
+                    .map(|(_, value)| *value)
+                    .collect(),
+                ParameterLocation::Query => query
+                    .iter()
+                    .filter(|(name, _)| *name == param.name)
+                    .map(|(_, value)| value.as_str())
+                    .collect(),
+                ParameterLocation::Header => headers
+                    .get_all(param.name.as_str())
+                    .iter()
+                    .filter_map(|v| v.to_str().ok())
+                    .collect(),
+            };
+
+            if values.is_empty() {
+                if param.required {
+                    violations.push(SchemaViolation::new(location, "is required"));
+                }
+                continue;
+            }
+
+            let value = self.coerce_parameter(&param.schema, param.location, &values);
+            self.validate_value(&param.schema, &value, &location, 0, &mut violations);
+        }
+
+        violations
+    }
+
+    /// Validate a JSON request body against the operation's JSON schema.
+    pub fn validate_body(&self, index: usize, body: &[u8]) -> Vec<SchemaViolation> {
+        let Some(schema) = self.operations[index]
+            .body
+            .as_ref()
+            .and_then(|b| b.json_schema.as_ref())
+        else {
+            return Vec::new();
+        };
+
+        let value: Value = match serde_json::from_slice(body) {
+            Ok(value) => value,
+            Err(e) => {
+                return vec![SchemaViolation::new(
+                    "body",
+                    format!("is not valid JSON: {e}"),
+                )];
+            }
+        };
+
+        let mut violations = Vec::new();
+        self.validate_value(schema, &value, "body", 0, &mut violations);
+        violations
+    }
+
+    /// Turn raw parameter strings into the JSON value the schema describes.
+    ///
+    /// Values that don't parse as the declared type are kept as strings,
+    /// so the schema check reports the type mismatch.
+    fn coerce_parameter(
+        &self,
+        schema: &Value,
+        location: ParameterLocation,
+        values: &[&str],
+    ) -> Value {
+        let schema = self.resolve(schema).unwrap_or(schema);
+
+        if schema_type(schema) == Some("array") {
+            let items = schema.get("items").unwrap_or(&Value::Null);
+            let items = self.resolve(items).unwrap_or(items);
+
+            // Repeated query keys form the array; path and header values are comma-separated.
+            let parts: Vec<&str> = match location {
+                ParameterLocation::Query => values.to_vec(),
+                _ => values[0].split(',').map(str::trim).collect(),
+            };
+            return Value::Array(parts.iter().map(|p| coerce_scalar(items, p)).collect());
+        }
+
+        coerce_scalar(schema, values[0])
+    }
+
+    fn validate_value(
+        &self,
+        schema: &Value,
+        value: &Value,
+        location: &str,
+        depth: usize,
+        out: &mut Vec<SchemaViolation>,
+    ) {
+        if depth > MAX_REF_DEPTH {
+            out.push(SchemaViolation::new(
+                location,
+                "schema is nested too deeply",
+            ));
+            return;
+        }
+
+        let schema = match self.resolve(schema) {
+            Ok(schema) => schema,
+            Err(e) => {
+                out.push(SchemaViolation::new(location, e.to_string()));
+                return;
+            }
+        };
+
+        for sub in schema
+            .get("allOf")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            self.validate_value(sub, value, location, depth + 1, out);
+        }
+        if let Some(subs) = schema.get("anyOf").and_then(Value::as_array)
+            && !subs
+                .iter()
+                .any(|s| self.is_valid(s, value, location, depth))
+        {
+            out.push(SchemaViolation::new(
+                location,
+                "does not match any allowed schema",
+            ));
+        }
+        if let Some(subs) = schema.get("oneOf").and_then(Value::as_array) {
+            let matching = subs
+                .iter()
+                .filter(|s| self.is_valid(s, value, location, depth))
+                .count();
+            if matching != 1 {
+                out.push(SchemaViolation::new(
+                    location,
+                    "must match exactly one schema",
+                ));
+            }
+        }
+
+        if value.is_null() && schema.get("nullable").and_then(Value::as_bool) == Some(true) {
+            return;
+        }
+
+        if let Some(allowed) = schema.get("enum").and_then(Value::as_array)
+            && !allowed.contains(value)
+        {
+            out.push(SchemaViolation::new(
+                location,
+                format!("must be one of {}", Value::Array(allowed.clone())),
+            ));
+        }
+
+        if let Some(expected) = schema.get("type") {
+            let types: Vec<&str> = match expected {
+                Value::String(t) => vec![t.as_str()],
+                Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+                _ => Vec::new(),
+            };
+            if !types.is_empty() && !types.iter().any(|t| has_type(value, t)) {
+                out.push(SchemaViolation::new(
+                    location,
+                    format!("expected {}", types.join(" or ")),
+                ));
+                return;
+            }
+        }
+
+        match value {
+            Value::String(s) => {
+                let len = s.chars().count() as u64;
+                if let Some(min) = schema.get("minLength").and_then(Value::as_u64)
+                    && len < min
+                {
+                    out.push(SchemaViolation::new(
+                        location,
+                        format!("must be at least {min} characters"),
+                    ));
+                }
+                if let Some(max) = schema.get("maxLength").and_then(Value::as_u64)
+                    && len > max
+                {
+                    out.push(SchemaViolation::new(
+                        location,
+                        format!("must be at most {max} characters"),
+                    ));
+                }
+            }
+            Value::Number(n) => {
+                let n = n.as_f64().unwrap_or_default();
+                if let Some(min) = schema.get("minimum").and_then(Value::as_f64)
+                    && n < min
+                {
+                    out.push(SchemaViolation::new(
+                        location,
+                        format!("must be at least {min}"),
+                    ));
+                }
+                if let Some(max) = schema.get("maximum").and_then(Value::as_f64)
+                    && n > max
+                {
+                    out.push(SchemaViolation::new(
+                        location,
+                        format!("must be at most {max}"),
+                    ));
+                }
+            }
+            Value::Array(items) => {
+                let len = items.len() as u64;
+                if let Some(min) = schema.get("minItems").and_then(Value::as_u64)
+                    && len < min
+                {
+                    out.push(SchemaViolation::new(
+                        location,
+                        format!("must have at least {min} items"),
+                    ));
+                }
+                if let Some(max) = schema.get("maxItems").and_then(Value::as_u64)
+                    && len > max
+                {
+                    out.push(SchemaViolation::new(
+                        location,
+                        format!("must have at most {max} items"),
+                    ));
+                }
+                if let Some(item_schema) = schema.get("items") {
+                    for (i, item) in items.iter().enumerate() {
+                        let item_location = format!("{location}[{i}]");
+                        self.validate_value(item_schema, item, &item_location, depth + 1, out);
+                    }
+                }
+            }
+            Value::Object(fields) => {
+                let required = schema.get("required").and_then(Value::as_array);
+                for name in required.into_iter().flatten().filter_map(Value::as_str) {
+                    if !fields.contains_key(name) {
+                        out.push(SchemaViolation::new(
+                            format!("{location}.{name}"),
+                            "is required",
+                        ));
+                    }
+                }
+
+                let properties = schema.get("properties").and_then(Value::as_object);
+                for (name, field) in fields {
+                    let field_location = format!("{location}.{name}");
+                    match properties.and_then(|p| p.get(name)) {
+                        Some(field_schema) => self.validate_value(
+                            field_schema,
+                            field,
+                            &field_location,
+                            depth + 1,
+                            out,
+                        ),
+                        None => match schema.get("additionalProperties") {
+                            Some(Value::Bool(false)) => {
+                                out.push(SchemaViolation::new(field_location, "is not allowed"))
+                            }
+                            Some(extra @ Value::Object(_)) => {
+                                self.validate_value(extra, field, &field_location, depth + 1, out)
+                            }
+                            _ => {}
+                        },
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn is_valid(&self, schema: &Value, value: &Value, location: &str, depth: usize) -> bool {
+        let mut violations = Vec::new();
+        self.validate_value(schema, value, location, depth + 1, &mut violations);
+        violations.is_empty()
+    }
+}
+
+fn parse_template(template: &str) -> Vec<Segment> {
+    template
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .map(
+            |s| match s.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                Some(name) => Segment::Param(name.to_string()),
+                None => Segment::Literal(s.to_string()),
+            },
+        )
+        .collect()
+}
+
+fn is_json(media_type: &str) -> bool {
+    let essence = media_type.split(';').next().unwrap_or("").trim();
+    essence.eq_ignore_ascii_case("application/json") || essence.ends_with("+json")
+}
+
+fn schema_type(schema: &Value) -> Option<&str> {
+    schema.get("type").and_then(Value::as_str)
+}
+
+fn coerce_scalar(schema: &Value, raw: &str) -> Value {
+    let parsed = match schema_type(schema) {
+        Some("integer") => raw.parse::<i64>().ok().map(Value::from),
+        Some("number") => raw.parse::<f64>().ok().map(Value::from),
+        Some("boolean") => raw.parse::<bool>().ok().map(Value::from),
+        _ => None,
+    };
+    parsed.unwrap_or_else(|| Value::String(raw.to_string()))
+}
+
+fn has_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "integer" => value.as_f64().is_some_and(|n| n.fract() == 0.0),
+        "number" => value.is_number(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+/// Validates requests against an OpenAPI 3 document.
+///
+/// Requests are matched to an operation by method and path template. Path, query and header
+/// parameters are checked in `on_request`; JSON bodies are buffered up to `max_body_bytes` and
+/// checked once complete. Non-conforming requests are rejected with `400` and a JSON list of
+/// violations, before anything is forwarded upstream.
+///
+/// Requests matching no operation pass through, unless `reject_unknown_operations` is set.
+pub struct OpenApiDevice {
+    document: OpenApiDocument,
+    max_body_bytes: usize,
+    reject_unknown_operations: bool,
+}
+
+impl OpenApiDevice {
+    pub fn from_config(cfg: OpenApiDeviceConfig) -> anyhow::Result<Self> {
+        Ok(Self {
+            document: OpenApiDocument::load(&cfg.spec_path)?,
+            max_body_bytes: cfg.max_body_bytes,
+            reject_unknown_operations: cfg.reject_unknown_operations,
+        })
+    }
+
+    fn reject(
+        &self,
+        ctx: &RequestCtx,
+        status: StatusCode,
+        violations: Vec<SchemaViolation>,
+    ) -> DeviceResult {
+        let body = json!({
+            "error": "request does not match the API schema",
+            "violations": violations,
+        });
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/json"),
+        );
+        DeviceResult::Respond(ResponseCtx::new(
+            ctx.request_id(),
+            status,
+            headers,
+            body.to_string().into_bytes(),
+        ))
+    }
+}
+
+impl Device for OpenApiDevice {
+    fn name(&self) -> &str {
+        "OpenAPI"
+    }
+
+    fn on_request(&self, ctx: &mut RequestCtx) -> DeviceResult {
+        let (index, violations) = match self
+            .document
+            .match_operation(ctx.method(), ctx.canonical_path())
+        {
+            OperationMatch::Found { index, path_params } => (
+                index,
+                self.document.validate_parameters(
+                    index,
+                    &path_params,
+                    ctx.query_pairs(),
+                    ctx.headers(),
+                ),
+            ),
+            _ if !self.reject_unknown_operations => return DeviceResult::Continue,
+            OperationMatch::MethodNotAllowed => {
+                let violation = SchemaViolation::new("method", "is not allowed for this path");
+                return self.reject(ctx, StatusCode::METHOD_NOT_ALLOWED, vec![violation]);
+            }
+            OperationMatch::NotFound => {
+                let violation = SchemaViolation::new("path", "matches no operation");
+                return self.reject(ctx, StatusCode::NOT_FOUND, vec![violation]);
+            }
+        };
+
+        if !violations.is_empty() {
+            return self.reject(ctx, StatusCode::BAD_REQUEST, violations);
+        }
+
+        let Some(body) = &self.document.operations[index].body else {
+            return DeviceResult::Continue;
+        };
+
+        let headers = ctx.headers();
+        let content_length = headers
+            .get(header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<usize>().ok());
+        let has_body = headers.contains_key(header::TRANSFER_ENCODING)
+            || content_length.is_some_and(|n| n > 0);
+
+        if !has_body {
+            if body.required {
+                let violation = SchemaViolation::new("body", "is required");
+                return self.reject(ctx, StatusCode::BAD_REQUEST, vec![violation]);
+            }
+            return DeviceResult::Continue;
+        }
+
+        let content_type = headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| {
+                v.split(';')
+                    .next()
+                    .unwrap_or("")
+                    .trim()
+                    .to_ascii_lowercase()
+            })
+            .unwrap_or_default();
+        if !body.media_types.is_empty()
+            && !body
+                .media_types
+                .iter()
+                .any(|m| m == &content_type || m == "*/*")
+        {
+            let violation = SchemaViolation::new(
+                "header.content-type",
+                format!("must be one of {}", body.media_types.join(", ")),
+            );
+            return self.reject(ctx, StatusCode::UNSUPPORTED_MEDIA_TYPE, vec![violation]);
+        }
+
+        if body.json_schema.is_none() || !is_json(&content_type) {
+            return DeviceResult::Continue;
+        }
+        if content_length.is_some_and(|n| n > self.max_body_bytes) {
+            let violation = SchemaViolation::new("body", "is too large to validate");
+            return self.reject(ctx, StatusCode::PAYLOAD_TOO_LARGE, vec![violation]);
+        }
+
+        ctx.extensions.insert(PendingBodyValidation::new(index));
+        DeviceResult::Continue
+    }
+
+    /// Buffer a JSON body, then release it in one piece once it has been validated.
+    fn on_stream_request_body(
+        &self,
+        ctx: &mut RequestCtx,
+        maybe_chunk: &mut Option<Bytes>,
+        end_of_stream: bool,
+    ) -> DeviceResult {
+        let Some(pending) = ctx.extensions.get_mut::<PendingBodyValidation>() else {
+            return DeviceResult::Continue;
+        };
+
+        if let Some(chunk) = maybe_chunk.take() {
+            if pending.body.len() + chunk.len() > self.max_body_bytes {
+                ctx.extensions.remove::<PendingBodyValidation>();
+                let violation = SchemaViolation::new("body", "is too large to validate");
+                return self.reject(ctx, StatusCode::PAYLOAD_TOO_LARGE, vec![violation]);
+            }
+            pending.body.extend_from_slice(&chunk);
+        }
+
+        if !end_of_stream {
+            return DeviceResult::Continue;
+        }
+
+        let Some(pending) = ctx.extensions.remove::<PendingBodyValidation>() else {
+            return DeviceResult::Continue;
+        };
+
+        let violations = self
+            .document
+            .validate_body(pending.operation, &pending.body);
+        if !violations.is_empty() {
+            return self.reject(ctx, StatusCode::BAD_REQUEST, violations);
+        }
+
+        *maybe_chunk = Some(pending.body.freeze());
+        DeviceResult::Continue
+    }
+}
+
+/// Request body held back until it has been validated against its operation's schema.
+#[derive(Debug, Clone)]
+pub(crate) struct PendingBodyValidation {
+    operation: usize,
+    body: BytesMut,
+}
+
+impl PendingBodyValidation {
+    pub(crate) fn new(operation: usize) -> Self {
+        Self {
+            operation,
+            body: BytesMut::new(),
+        }
+    }
+}
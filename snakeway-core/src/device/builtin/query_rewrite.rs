@@ -0,0 +1,102 @@
+use crate::conf::types::{QueryOperation, QueryRewriteDeviceConfig};
+use crate::ctx::{RequestCtx, encode_query_component, join_query, parse_query};
+use crate::device::core::{Device, DeviceResult};
+
+/// Adds, sets, removes, and renames query parameters on the request sent upstream.
+///
+/// Operations run in order on the query as the client sent it, or as an earlier device rewrote
+/// it. Parameter order is kept, so repeated parameters reach the upstream in request order.
+/// Names match after unreserved characters are decoded, the same as everywhere else in the
+/// pipeline. Devices see the client's query; only the upstream request is rewritten.
+pub struct QueryRewriteDevice {
+    /// Operations with names and values encoded for the query string.
+    operations: Vec<QueryOperation>,
+}
+
+impl QueryRewriteDevice {
+    pub fn from_config(cfg: QueryRewriteDeviceConfig) -> anyhow::Result<Self> {
+        let encode = encode_query_component;
+        let operations = cfg
+            .operations
+            .into_iter()
+            .map(|op| match op {
+                QueryOperation::AddIfMissing { name, value } => QueryOperation::AddIfMissing {
+                    name: encode(&name),
+                    value: encode(&value),
+                },
+                QueryOperation::Set { name, value } => QueryOperation::Set {
+                    name: encode(&name),
+                    value: encode(&value),
+                },
+                QueryOperation::Remove { name } => QueryOperation::Remove {
+                    name: encode(&name),
+                },
+                QueryOperation::Rename { name, to } => QueryOperation::Rename {
+                    name: encode(&name),
+                    to: encode(&to),
+                },
+            })
+            .collect();
+
+        Ok(Self { operations })
+    }
+
+    /// Apply the operations to a query string, without the leading `?`.
+    ///
+    /// Returns `None` if the query is not validly encoded, or the operations change nothing.
+    pub fn rewrite(&self, query: &str) -> Option<String> {
+        let mut pairs = parse_query(query).ok()?;
+        let before = pairs.clone();
+
+        for operation in &self.operations {
+            apply(&mut pairs, operation);
+        }
+
+        (pairs != before).then(|| join_query(&pairs))
+    }
+}
+
+fn apply(pairs: &mut Vec<(String, String)>, operation: &QueryOperation) {
+    match operation {
+        QueryOperation::AddIfMissing { name, value } => {
+            if !pairs.iter().any(|(key, _)| key == name) {
+                pairs.push((name.clone(), value.clone()));
+            }
+        }
+        QueryOperation::Set { name, value } => {
+            // The first occurrence keeps its position, the rest are dropped.
+            match pairs.iter().position(|(key, _)| key == name) {
+                Some(first) => {
+                    pairs[first].1 = value.clone();
+                    let mut idx = 0;
+                    pairs.retain(|(key, _)| {
+                        let keep = key != name || idx == first;
+                        idx += 1;
+                        keep
+                    });
+                }
+                None => pairs.push((name.clone(), value.clone())),
+            }
+        }
+        QueryOperation::Remove { name } => pairs.retain(|(key, _)| key != name),
+        QueryOperation::Rename { name, to } => {
+            for (key, _) in pairs.iter_mut().filter(|(key, _)| key == name) {
+                key.clone_from(to);
+            }
+        }
+    }
+}
+
+impl Device for QueryRewriteDevice {
+    fn name(&self) -> &str {
+        "Query Rewrite"
+    }
+
+    fn before_proxy(&self, ctx: &mut RequestCtx) -> DeviceResult {
+        if let Some(query) = self.rewrite(ctx.upstream_query()) {
+            ctx.upstream_query = Some(query);
+        }
+
+        DeviceResult::Continue
+    }
+}
@@ -0,0 +1,217 @@
+use crate::conf::types::QuotaDeviceConfig;
+use crate::ctx::{RequestCtx, ResponseCtx};
+use crate::device::core::errors::DeviceError;
+use crate::device::core::{CachePurge, Device, DeviceResult};
+use anyhow::anyhow;
+use async_trait::async_trait;
+use dashmap::DashMap;
+use http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode, Uri, header};
+use pingora::connectors::http::Connector;
+use pingora::http::RequestHeader;
+use pingora::upstreams::peer::HttpPeer;
+use std::time::{Duration, Instant};
+
+/// Quota service response headers with this prefix are copied onto the client response.
+const RATE_LIMIT_HEADER_PREFIX: &str = "x-ratelimit-";
+
+/// Expired cache entries are purged once the cache grows past this many API keys.
+const MAX_CACHED_DECISIONS: usize = 10_000;
+
+/// How long an idle connection to the quota service is kept in the pool.
+const QUOTA_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The quota service's answer for an API key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuotaDecision {
+    pub allowed: bool,
+
+    /// `X-RateLimit-*` headers to inject into the client response.
+    pub headers: HeaderMap,
+}
+
+impl QuotaDecision {
+    /// Interpret a quota service response.
+    ///
+    /// `429` means the key is over quota, any other `2xx` means it is allowed.
+    /// Anything else is not a decision and returns `None`.
+    pub fn from_response(status: StatusCode, headers: &HeaderMap) -> Option<Self> {
+        let allowed = match status {
+            StatusCode::TOO_MANY_REQUESTS => false,
+            s if s.is_success() => true,
+            _ => return None,
+        };
+
+        let headers = headers
+            .iter()
+            .filter(|(name, _)| name.as_str().starts_with(RATE_LIMIT_HEADER_PREFIX))
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+
+        Some(Self { allowed, headers })
+    }
+}
+
+/// Enforces per-API-key quotas by asking an external quota service.
+///
+/// On every request carrying an API key, the quota service is called with the key in the same
+/// header. Over-quota keys are short-circuited with `429`, and the service's `X-RateLimit-*`
+/// headers are injected into the client response either way.
+///
+/// Decisions are cached per key for `cache_ttl_milliseconds` to reduce quota service load.
+/// While cached, requests are not reported to the quota service, so counts lag by up to one TTL.
+pub struct QuotaDevice {
+    connector: Connector,
+    peer: HttpPeer,
+    authority: String,
+    path: String,
+    api_key_header: HeaderName,
+    timeout: Duration,
+    cache_ttl: Duration,
+    fail_open: bool,
+    cache: DashMap<String, (Instant, QuotaDecision)>,
+}
+
+impl QuotaDevice {
+    pub fn from_config(cfg: QuotaDeviceConfig) -> anyhow::Result<Self> {
+        let uri: Uri = cfg.quota_url.parse()?;
+        let tls = uri.scheme_str() == Some("https");
+        let host = uri
+            .host()
+            .ok_or_else(|| anyhow!("quota_url has no host: {}", cfg.quota_url))?
+            .to_string();
+        let port = uri.port_u16().unwrap_or(if tls { 443 } else { 80 });
+        let path = uri
+            .path_and_query()
+            .map(|p| p.as_str().to_string())
+            .unwrap_or_else(|| "/".to_string());
+
+        Ok(Self {
+            connector: Connector::new(None),
+            peer: HttpPeer::new((host.as_str(), port), tls, host.clone()),
+            authority: format!("{}:{}", host, port),
+            path,
+            api_key_header: HeaderName::from_bytes(cfg.api_key_header.as_bytes())?,
+            timeout: Duration::from_millis(cfg.timeout_milliseconds),
+            cache_ttl: Duration::from_millis(cfg.cache_ttl_milliseconds),
+            fail_open: cfg.fail_open,
+            cache: DashMap::new(),
+        })
+    }
+
+    fn cached_decision(&self, api_key: &str) -> Option<QuotaDecision> {
+        let entry = self.cache.get(api_key)?;
+        let (cached_at, decision) = entry.value();
+        (cached_at.elapsed() < self.cache_ttl).then(|| decision.clone())
+    }
+
+    fn cache_decision(&self, api_key: &str, decision: &QuotaDecision) {
+        if self.cache_ttl.is_zero() {
+            return;
+        }
+
+        if self.cache.len() >= MAX_CACHED_DECISIONS {
+            self.cache
+                .retain(|_, (cached_at, _)| cached_at.elapsed() < self.cache_ttl);
+        }
+
+        self.cache
+            .insert(api_key.to_string(), (Instant::now(), decision.clone()));
+    }
+
+    /// Ask the quota service for a decision, bounded by the configured timeout.
+    async fn check(&self, api_key: &HeaderValue) -> anyhow::Result<QuotaDecision> {
+        tokio::time::timeout(self.timeout, self.call_quota_service(api_key))
+            .await
+            .map_err(|_| anyhow!("quota service timed out after {:?}", self.timeout))?
+    }
+
+    async fn call_quota_service(&self, api_key: &HeaderValue) -> anyhow::Result<QuotaDecision> {
+        let mut req = RequestHeader::build(Method::GET, self.path.as_bytes(), None)?;
+        req.insert_header(header::HOST, &self.authority)?;
+        req.insert_header(self.api_key_header.clone(), api_key.clone())?;
+
+        let (mut session, _reused) = self.connector.get_http_session(&self.peer).await?;
+        session.write_request_header(Box::new(req)).await?;
+        session.finish_request_body().await?;
+        session.read_response_header().await?;
+
+        let resp = session
+            .response_header()
+            .ok_or_else(|| anyhow!("quota service sent no response header"))?;
+        let decision = QuotaDecision::from_response(resp.status, &resp.headers)
+            .ok_or_else(|| anyhow!("unexpected quota service status: {}", resp.status))?;
+
+        // Drain the body so the connection can be reused.
+        while session.read_response_body().await?.is_some() {}
+        self.connector
+            .release_http_session(session, &self.peer, Some(QUOTA_IDLE_TIMEOUT))
+            .await;
+
+        Ok(decision)
+    }
+}
+
+#[async_trait]
+impl Device for QuotaDevice {
+    fn name(&self) -> &str {
+        "Quota"
+    }
+
+    async fn on_request_async(&self, ctx: &mut RequestCtx) -> DeviceResult {
+        // Requests without an API key are not subject to quotas.
+        let Some(api_key) = ctx.headers().get(&self.api_key_header).cloned() else {
+            return DeviceResult::Continue;
+        };
+        let Ok(api_key_str) = api_key.to_str() else {
+            return DeviceResult::Continue;
+        };
+
+        let decision = match self.cached_decision(api_key_str) {
+            Some(decision) => decision,
+            None => match self.check(&api_key).await {
+                Ok(decision) => {
+                    self.cache_decision(api_key_str, &decision);
+                    decision
+                }
+                Err(e) if self.fail_open => {
+                    tracing::warn!(error = %e, "quota service unavailable, failing open");
+                    return DeviceResult::Continue;
+                }
+                Err(e) => {
+                    return DeviceResult::Error(DeviceError {
+                        message: format!("quota service unavailable: {e}"),
+                        fatal: true,
+                    });
+                }
+            },
+        };
+
+        if !decision.allowed {
+            return DeviceResult::Respond(ResponseCtx::new(
+                ctx.request_id(),
+                StatusCode::TOO_MANY_REQUESTS,
+                decision.headers,
+                Vec::new(),
+            ));
+        }
+
+        ctx.response_headers.extend(decision.headers);
+        DeviceResult::Continue
+    }
+
+    /// Decisions are cached by API key.
+    fn purge_cache(&self, purge: &CachePurge) -> usize {
+        let mut purged = 0;
+        self.cache.retain(|api_key, _| {
+            let matches = purge.matches(api_key);
+            purged += usize::from(matches);
+            !matches
+        });
+        purged
+    }
+
+    fn reset(&self) -> bool {
+        self.cache.clear();
+        true
+    }
+}
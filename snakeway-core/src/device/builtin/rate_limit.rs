@@ -0,0 +1,157 @@
+use crate::conf::types::{RateLimitDeviceConfig, RateLimitKey};
+use crate::ctx::{RequestCtx, ResponseCtx};
+use crate::device::core::{Device, DeviceResult};
+use crate::enrichment::user_agent::ClientIdentity;
+use dashmap::DashMap;
+use http::{HeaderMap, HeaderName, HeaderValue, StatusCode, header};
+use std::time::Instant;
+
+/// Full buckets are purged once this many keys are tracked.
+const MAX_TRACKED_KEYS: usize = 100_000;
+
+/// A token bucket holding up to `burst` tokens, refilled at `rate` tokens per second.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenBucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl TokenBucket {
+    /// A full bucket, so a new key may send a whole burst at once.
+    pub fn full(burst: f64, now: Instant) -> Self {
+        Self {
+            tokens: burst,
+            updated_at: now,
+        }
+    }
+
+    /// Refill for the time elapsed since the last call, then take one token if there is one.
+    pub fn try_take(&mut self, rate: f64, burst: f64, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate).min(burst);
+        self.updated_at = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Whole seconds until the next token is available.
+    pub fn retry_after_seconds(&self, rate: f64) -> u64 {
+        ((1.0 - self.tokens) / rate).ceil().max(1.0) as u64
+    }
+
+    /// Whether the bucket has refilled completely, and so is no different from a new one.
+    fn is_full(&self, rate: f64, burst: f64, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        self.tokens + elapsed * rate >= burst
+    }
+}
+
+/// Limits requests per key with a token bucket, in memory on this instance.
+///
+/// Requests over the limit are short-circuited with `429` and a `Retry-After` header.
+/// Requests without a key, e.g. missing the configured header, are not limited.
+pub struct RateLimitDevice {
+    rate: f64,
+    burst: f64,
+    key: Key,
+    buckets: DashMap<String, TokenBucket>,
+}
+
+enum Key {
+    ClientIp,
+    Header(HeaderName),
+    Path,
+}
+
+impl RateLimitDevice {
+    pub fn from_config(cfg: RateLimitDeviceConfig) -> anyhow::Result<Self> {
+        let key = match cfg.key {
+            RateLimitKey::ClientIp => Key::ClientIp,
+            RateLimitKey::Header(name) => Key::Header(HeaderName::from_bytes(name.as_bytes())?),
+            RateLimitKey::Path => Key::Path,
+        };
+
+        Ok(Self {
+            rate: cfg.requests_per_second as f64,
+            burst: cfg.burst as f64,
+            key,
+            buckets: DashMap::new(),
+        })
+    }
+
+    fn key(&self, ctx: &RequestCtx) -> Option<String> {
+        match &self.key {
+            // Prefer the client IP the identity device resolved through trusted proxies.
+            Key::ClientIp => {
+                let ip = ctx
+                    .extensions
+                    .get::<ClientIdentity>()
+                    .map(|identity| identity.ip)
+                    .unwrap_or(ctx.peer_ip);
+                Some(ip.to_string())
+            }
+            Key::Header(name) => ctx
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string),
+            Key::Path => Some(ctx.canonical_path().to_string()),
+        }
+    }
+
+    /// Take a token for the key, returning the seconds to wait if there is none.
+    fn take(&self, key: String, now: Instant) -> Result<(), u64> {
+        if self.buckets.len() >= MAX_TRACKED_KEYS {
+            self.buckets
+                .retain(|_, bucket| !bucket.is_full(self.rate, self.burst, now));
+        }
+
+        let mut bucket = self
+            .buckets
+            .entry(key)
+            .or_insert_with(|| TokenBucket::full(self.burst, now));
+
+        if bucket.try_take(self.rate, self.burst, now) {
+            Ok(())
+        } else {
+            Err(bucket.retry_after_seconds(self.rate))
+        }
+    }
+}
+
+impl Device for RateLimitDevice {
+    fn name(&self) -> &str {
+        "Rate Limit"
+    }
+
+    fn on_request(&self, ctx: &mut RequestCtx) -> DeviceResult {
+        let Some(key) = self.key(ctx) else {
+            return DeviceResult::Continue;
+        };
+
+        match self.take(key, Instant::now()) {
+            Ok(()) => DeviceResult::Continue,
+            Err(retry_after) => {
+                let mut headers = HeaderMap::new();
+                headers.insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+                DeviceResult::Respond(ResponseCtx::new(
+                    ctx.request_id(),
+                    StatusCode::TOO_MANY_REQUESTS,
+                    headers,
+                    Vec::new(),
+                ))
+            }
+        }
+    }
+
+    /// Every key gets a full bucket again.
+    fn reset(&self) -> bool {
+        self.buckets.clear();
+        true
+    }
+}
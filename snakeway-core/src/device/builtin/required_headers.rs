@@ -0,0 +1,88 @@
+use crate::conf::types::{MissingHeaderAction, RequiredHeadersDeviceConfig};
+use crate::ctx::{RequestCtx, ResponseCtx};
+use crate::device::core::errors::DeviceError;
+use crate::device::core::{Device, DeviceResult};
+use http::{HeaderName, HeaderValue, StatusCode};
+
+/// Placeholder in a `populate` value that is replaced with the request id.
+const REQUEST_ID_PLACEHOLDER: &str = "{request_id}";
+
+/// Enforces headers that every request must carry.
+///
+/// A missing header either rejects the request with `400 Bad Request`, or is populated
+/// from its configured value before the request is proxied. Populated headers are visible
+/// to later devices and are forwarded to the upstream.
+pub struct RequiredHeadersDevice {
+    headers: Vec<RequiredHeader>,
+}
+
+struct RequiredHeader {
+    name: HeaderName,
+    on_missing: MissingHeaderAction,
+    value: Option<String>,
+}
+
+impl RequiredHeadersDevice {
+    pub fn from_config(cfg: RequiredHeadersDeviceConfig) -> anyhow::Result<Self> {
+        let headers = cfg
+            .headers
+            .into_iter()
+            .map(|h| {
+                Ok(RequiredHeader {
+                    name: HeaderName::from_bytes(h.name.as_bytes())?,
+                    on_missing: h.on_missing,
+                    value: h.value,
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(Self { headers })
+    }
+
+    fn reject(&self, ctx: &RequestCtx, name: &HeaderName) -> DeviceResult {
+        DeviceResult::Respond(ResponseCtx::new(
+            ctx.request_id(),
+            StatusCode::BAD_REQUEST,
+            Default::default(),
+            format!("Required header missing: {name}").into_bytes(),
+        ))
+    }
+}
+
+impl Device for RequiredHeadersDevice {
+    fn name(&self) -> &str {
+        "Required Headers"
+    }
+
+    fn on_request(&self, ctx: &mut RequestCtx) -> DeviceResult {
+        for header in &self.headers {
+            if ctx.headers().contains_key(&header.name) {
+                continue;
+            }
+
+            let template = match (header.on_missing, &header.value) {
+                (MissingHeaderAction::Populate, Some(template)) => template,
+                _ => return self.reject(ctx, &header.name),
+            };
+
+            let value = template.replace(
+                REQUEST_ID_PLACEHOLDER,
+                &ctx.request_id().unwrap_or_default(),
+            );
+            let value = match HeaderValue::from_str(&value) {
+                Ok(value) => value,
+                Err(e) => {
+                    return DeviceResult::Error(DeviceError {
+                        message: format!("invalid value for required header {}: {e}", header.name),
+                        fatal: true,
+                    });
+                }
+            };
+
+            ctx.insert_header(header.name.clone(), value.clone());
+            ctx.upstream_headers.insert(header.name.clone(), value);
+        }
+
+        DeviceResult::Continue
+    }
+}
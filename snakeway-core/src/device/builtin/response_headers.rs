@@ -0,0 +1,70 @@
+use crate::conf::types::ResponseHeadersDeviceConfig;
+use crate::ctx::RequestCtx;
+use crate::device::core::{Device, DeviceResult};
+use http::{HeaderName, HeaderValue};
+
+/// Adds headers computed from the request to every proxied response,
+/// e.g. `X-Request-Id: {request_id}` or `X-Served-By: {upstream}`.
+///
+/// Values are rendered once the request is routed, and stored on the request context
+/// alongside other response headers, so they are applied when the response is sent.
+pub struct ResponseHeadersDevice {
+    headers: Vec<ResponseHeader>,
+}
+
+struct ResponseHeader {
+    name: HeaderName,
+    template: String,
+}
+
+impl ResponseHeadersDevice {
+    pub fn from_config(cfg: ResponseHeadersDeviceConfig) -> anyhow::Result<Self> {
+        let headers = cfg
+            .headers
+            .into_iter()
+            .map(|h| {
+                Ok(ResponseHeader {
+                    name: HeaderName::from_bytes(h.name.as_bytes())?,
+                    template: h.value,
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(Self { headers })
+    }
+}
+
+impl Device for ResponseHeadersDevice {
+    fn name(&self) -> &str {
+        "Response Headers"
+    }
+
+    fn before_proxy(&self, ctx: &mut RequestCtx) -> DeviceResult {
+        let request_id = ctx.request_id().unwrap_or_default();
+        let upstream = ctx.service.clone().unwrap_or_default();
+        let route = ctx
+            .route_id
+            .as_ref()
+            .map(|id| id.path().to_string())
+            .unwrap_or_default();
+
+        for header in &self.headers {
+            let value = header
+                .template
+                .replace("{request_id}", &request_id)
+                .replace("{upstream}", &upstream)
+                .replace("{route}", &route);
+            // A header is not worth failing the request over, so a bad render is skipped.
+            match HeaderValue::from_str(&value) {
+                Ok(value) => {
+                    ctx.response_headers.insert(header.name.clone(), value);
+                }
+                Err(e) => {
+                    tracing::warn!("skipping response header {}: {e}", header.name);
+                }
+            }
+        }
+
+        DeviceResult::Continue
+    }
+}
@@ -0,0 +1,59 @@
+use crate::conf::types::StatusRemapDeviceConfig;
+use crate::ctx::ResponseCtx;
+use crate::device::core::{Device, DeviceResult};
+use http::{HeaderValue, StatusCode, header};
+use std::collections::HashMap;
+
+/// Rewrites quirky upstream statuses, e.g. a backend's `418` that means "rate limited" to `429`.
+///
+/// Only configured statuses are rewritten; every other response passes through unchanged.
+/// A remap with a body also replaces the upstream response body.
+pub struct StatusRemapDevice {
+    remaps: HashMap<StatusCode, StatusRemap>,
+}
+
+struct StatusRemap {
+    to: StatusCode,
+    body: Option<Vec<u8>>,
+}
+
+impl StatusRemapDevice {
+    pub fn from_config(cfg: StatusRemapDeviceConfig) -> anyhow::Result<Self> {
+        let remaps = cfg
+            .remaps
+            .into_iter()
+            .map(|r| {
+                let remap = StatusRemap {
+                    to: StatusCode::from_u16(r.to)?,
+                    body: r.body.map(String::into_bytes),
+                };
+                Ok((StatusCode::from_u16(r.from)?, remap))
+            })
+            .collect::<anyhow::Result<HashMap<_, _>>>()?;
+
+        Ok(Self { remaps })
+    }
+}
+
+impl Device for StatusRemapDevice {
+    fn name(&self) -> &str {
+        "Status Remap"
+    }
+
+    fn after_proxy(&self, ctx: &mut ResponseCtx) -> DeviceResult {
+        let Some(remap) = self.remaps.get(&ctx.status) else {
+            return DeviceResult::Continue;
+        };
+
+        ctx.status = remap.to;
+        if let Some(body) = &remap.body {
+            ctx.body = body.clone();
+            ctx.headers.insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("text/plain; charset=utf-8"),
+            );
+        }
+
+        DeviceResult::Continue
+    }
+}
@@ -1,20 +1,23 @@
 use crate::conf::types::StructuredLoggingDeviceConfig;
 use crate::ctx::{RequestCtx, RequestId, ResponseCtx};
+use crate::device::builtin::jwt_auth::JwtClaims;
 use crate::device::core::errors::DeviceError;
 use crate::device::core::{Device, result::DeviceResult};
 use crate::enrichment::user_agent::ClientIdentity;
 use crate::http_event::HttpEvent;
+use crate::route::RouteId;
 use anyhow::Result;
-use http::HeaderMap;
+use http::{HeaderMap, HeaderValue};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use tracing::{debug, error, info, trace, warn};
 
 // ----------------------------------------------------------------------------
 // Logging level & config enums
 // ----------------------------------------------------------------------------
 
-#[derive(Default, Debug, Deserialize, Serialize, Clone, Copy)]
+#[derive(Default, Debug, Deserialize, JsonSchema, Serialize, Clone, Copy)]
 #[serde(rename_all = "lowercase")]
 pub enum LogLevel {
     Trace,
@@ -25,23 +28,24 @@ pub enum LogLevel {
     Error,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Deserialize, JsonSchema, Serialize, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum LogEvent {
     Request,
     BeforeProxy,
     AfterProxy,
     Response,
+    Complete,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Deserialize, JsonSchema, Serialize, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum LogPhase {
     Request,
     Response,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Deserialize, JsonSchema, Serialize, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum IdentityField {
     Asn,
@@ -53,6 +57,22 @@ pub enum IdentityField {
     Device,
 }
 
+/// Headers captured into the log record for a single route only.
+#[derive(Default, Debug, Deserialize, JsonSchema, Serialize, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct RouteHeaderCapture {
+    /// Route path, as configured on the route.
+    pub path: String,
+
+    /// Inbound request headers to capture.
+    #[serde(default)]
+    pub request_headers: Vec<String>,
+
+    /// Outbound response headers to capture.
+    #[serde(default)]
+    pub response_headers: Vec<String>,
+}
+
 // ----------------------------------------------------------------------------
 // Emit macro ...to DRY-out logging calls.
 // ----------------------------------------------------------------------------
@@ -83,6 +103,9 @@ pub struct StructuredLoggingDevice {
     include_identity: bool,
     identity_fields: Vec<IdentityField>,
 
+    /// Per-route header capture, keyed by route path.
+    route_headers: HashMap<String, RouteHeaderCapture>,
+
     events: Option<Vec<LogEvent>>,
     phases: Option<Vec<LogPhase>>,
 }
@@ -107,6 +130,23 @@ impl StructuredLoggingDevice {
             include_identity: cfg.include_identity,
             identity_fields: cfg.identity_fields,
 
+            route_headers: cfg
+                .route_headers
+                .into_iter()
+                .map(|capture| {
+                    let lowercase =
+                        |names: Vec<String>| names.into_iter().map(|h| h.to_lowercase()).collect();
+                    (
+                        capture.path.trim_end_matches('/').to_string(),
+                        RouteHeaderCapture {
+                            request_headers: lowercase(capture.request_headers),
+                            response_headers: lowercase(capture.response_headers),
+                            ..capture
+                        },
+                    )
+                })
+                .collect(),
+
             events: cfg.events,
             phases: cfg.phases,
         })
@@ -148,21 +188,52 @@ impl StructuredLoggingDevice {
                 continue;
             }
 
-            let val = if self.redact_headers.contains(&name_lc) {
-                "<redacted>".to_string()
-            } else {
-                value
-                    .to_str()
-                    .map(str::to_string)
-                    .unwrap_or("<binary>".into())
-            };
-
+            let val = self.header_value(&name_lc, value);
             out.insert(name_lc, val);
         }
 
         out
     }
 
+    /// Headers the matched route asks to capture, regardless of `include_headers`.
+    /// Returns `None` outside of configured routes.
+    pub(crate) fn route_headers_json(
+        &self,
+        route_id: Option<&RouteId>,
+        phase: LogPhase,
+        headers: &HeaderMap,
+    ) -> Option<String> {
+        let capture = self.route_headers.get(route_id?.path())?;
+        let names = match phase {
+            LogPhase::Request => &capture.request_headers,
+            LogPhase::Response => &capture.response_headers,
+        };
+        if names.is_empty() {
+            return None;
+        }
+
+        let out: BTreeMap<&str, String> = names
+            .iter()
+            .filter_map(|name| {
+                let value = headers.get(name.as_str())?;
+                Some((name.as_str(), self.header_value(name, value)))
+            })
+            .collect();
+
+        serde_json::to_string(&out).ok()
+    }
+
+    fn header_value(&self, name_lc: &str, value: &HeaderValue) -> String {
+        if self.redact_headers.contains(name_lc) {
+            "<redacted>".to_string()
+        } else {
+            value
+                .to_str()
+                .map(str::to_string)
+                .unwrap_or("<binary>".into())
+        }
+    }
+
     // ------------------------------------------------------------------------
     // Identity handling
     // ------------------------------------------------------------------------
@@ -233,6 +304,8 @@ impl StructuredLoggingDevice {
         status: Option<&str>,
     ) {
         let headers = self.headers_json(ctx.headers());
+        let route_headers =
+            self.route_headers_json(ctx.route_id.as_ref(), LogPhase::Request, ctx.headers());
         let identity = ctx
             .extensions
             .get::<ClientIdentity>()
@@ -247,20 +320,45 @@ impl StructuredLoggingDevice {
             method = method,
             uri = uri,
             status = status,
+            subject = self.subject(ctx),
             headers = headers,
+            route_headers = route_headers,
             identity = identity,
         );
     }
 
     fn emit_http_response(&self, ctx: &ResponseCtx, event: HttpEvent) {
+        let route_headers =
+            self.route_headers_json(ctx.route_id.as_ref(), LogPhase::Response, &ctx.headers);
+
         emit!(
             self.level,
             event = %event.as_str(),
             request_id = ctx.request_id.as_deref(),
             status = Some(ctx.status.as_str()),
+            route_headers = route_headers,
+        );
+    }
+
+    fn emit_http_complete(&self, ctx: &RequestCtx) {
+        emit!(
+            self.level,
+            event = %HttpEvent::Complete.as_str(),
+            request_id = self.request_id(ctx),
+            subject = self.subject(ctx),
+            bytes_in = ctx.bytes_in,
+            bytes_out = ctx.bytes_out,
+            bytes_total = ctx.bytes_in + ctx.bytes_out,
         );
     }
 
+    /// The `sub` claim of the token the JWT auth device validated, if any.
+    fn subject<'a>(&self, ctx: &'a RequestCtx) -> Option<&'a str> {
+        ctx.extensions
+            .get::<JwtClaims>()
+            .and_then(|claims| claims.subject())
+    }
+
     fn request_id<'a>(&self, ctx: &'a RequestCtx) -> Option<&'a str> {
         ctx.extensions
             .get::<RequestId>()
@@ -316,6 +414,12 @@ impl Device for StructuredLoggingDevice {
         DeviceResult::Continue
     }
 
+    fn on_complete(&self, ctx: &RequestCtx) {
+        if self.phase_enabled(LogPhase::Response) && self.event_enabled(LogEvent::Complete) {
+            self.emit_http_complete(ctx);
+        }
+    }
+
     fn on_error(&self, err: &DeviceError) {
         emit!(
             self.level,
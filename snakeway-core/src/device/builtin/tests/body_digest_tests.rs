@@ -0,0 +1,173 @@
+use crate::conf::types::BodyDigestDeviceConfig;
+use crate::ctx::RequestCtx;
+use crate::device::builtin::body_digest::{BodyDigestDevice, ExpectedDigest, PendingBodyDigest};
+use crate::device::core::{Device, DeviceResult};
+use bytes::Bytes;
+use http::{HeaderMap, HeaderValue, StatusCode};
+use pretty_assertions::assert_eq;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+const BODY: &[u8] = b"hello world";
+
+/// base64(md5("hello world"))
+const BODY_MD5: &str = "XrY7u+Ae7tCTyyK7j1rNww==";
+
+/// base64(sha256("hello world"))
+const BODY_SHA256: &str = "uU0nuZNNPgilLlLX2n2r+sSE7+N6U4DukIj3rOLvzek=";
+
+fn headers(name: &'static str, value: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(name, HeaderValue::from_str(value).unwrap());
+    headers
+}
+
+fn device(max_body_bytes: usize) -> BodyDigestDevice {
+    BodyDigestDevice::from_config(BodyDigestDeviceConfig {
+        enable: true,
+        priority: 0,
+        routes: vec![],
+        max_body_bytes,
+    })
+    .unwrap()
+}
+
+fn ctx_expecting(expected: ExpectedDigest) -> RequestCtx {
+    let mut ctx = RequestCtx::empty();
+    ctx.extensions.insert(PendingBodyDigest::new(expected));
+    ctx
+}
+
+fn stream(
+    device: &BodyDigestDevice,
+    ctx: &mut RequestCtx,
+    chunks: &[&'static [u8]],
+) -> (DeviceResult, Option<Bytes>) {
+    let mut forwarded = None;
+    for (idx, chunk) in chunks.iter().enumerate() {
+        let mut maybe_chunk = Some(Bytes::from_static(chunk));
+        let result = device.on_stream_request_body(ctx, &mut maybe_chunk, idx == chunks.len() - 1);
+        if !matches!(result, DeviceResult::Continue) {
+            return (result, None);
+        }
+        forwarded = maybe_chunk;
+    }
+    (DeviceResult::Continue, forwarded)
+}
+
+fn sha256_of_body() -> ExpectedDigest {
+    ExpectedDigest::from_headers(&headers("digest", &format!("SHA-256={BODY_SHA256}")))
+        .unwrap()
+        .unwrap()
+}
+
+//-----------------------------------------------------------------------------
+// Header parsing
+//-----------------------------------------------------------------------------
+#[test]
+fn no_digest_headers_means_no_verification() {
+    // Act
+    let expected = ExpectedDigest::from_headers(&HeaderMap::new());
+
+    // Assert
+    assert_eq!(expected, Ok(None));
+}
+
+#[test]
+fn sha256_is_preferred_over_md5_in_digest_header() {
+    // Arrange
+    let headers = headers("digest", &format!("MD5={BODY_MD5}, SHA-256={BODY_SHA256}"));
+
+    // Act
+    let expected = ExpectedDigest::from_headers(&headers).unwrap().unwrap();
+
+    // Assert
+    assert!(matches!(expected, ExpectedDigest::Sha256(_)));
+}
+
+#[test]
+fn unsupported_digest_algorithm_is_rejected() {
+    // Arrange
+    let headers = headers("digest", "SHA-512=abc=");
+
+    // Act
+    let expected = ExpectedDigest::from_headers(&headers);
+
+    // Assert
+    assert_eq!(expected, Err("Unsupported Digest algorithm"));
+}
+
+//-----------------------------------------------------------------------------
+// Digest matching
+//-----------------------------------------------------------------------------
+#[test]
+fn content_md5_matches_body() {
+    // Arrange
+    let expected = ExpectedDigest::from_headers(&headers("content-md5", BODY_MD5))
+        .unwrap()
+        .unwrap();
+
+    // Act / Assert
+    assert!(expected.matches(BODY));
+    assert!(!expected.matches(b"hello world!"));
+}
+
+#[test]
+fn sha256_digest_matches_body() {
+    // Arrange
+    let expected = sha256_of_body();
+
+    // Act / Assert
+    assert!(expected.matches(BODY));
+    assert!(!expected.matches(b"hello worle"));
+}
+
+//-----------------------------------------------------------------------------
+// Body buffering
+//-----------------------------------------------------------------------------
+#[test]
+fn matching_body_is_forwarded_whole_at_end_of_stream() {
+    // Arrange
+    let device = device(1024);
+    let mut ctx = ctx_expecting(sha256_of_body());
+
+    // Act
+    let (result, forwarded) = stream(&device, &mut ctx, &[b"hello ", b"world"]);
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+    assert_eq!(forwarded, Some(Bytes::from_static(BODY)));
+}
+
+#[test]
+fn mismatching_body_is_rejected_with_400() {
+    // Arrange
+    let device = device(1024);
+    let mut ctx = ctx_expecting(sha256_of_body());
+
+    // Act
+    let (result, _) = stream(&device, &mut ctx, &[b"hello ", b"there"]);
+
+    // Assert
+    let DeviceResult::Respond(resp) = result else {
+        panic!("expected the request to be rejected");
+    };
+    assert_eq!(resp.status, StatusCode::BAD_REQUEST);
+}
+
+#[test]
+fn body_over_max_body_bytes_is_rejected_with_413() {
+    // Arrange
+    let device = device(8);
+    let mut ctx = ctx_expecting(sha256_of_body());
+
+    // Act
+    let (result, _) = stream(&device, &mut ctx, &[b"hello ", b"world"]);
+
+    // Assert
+    let DeviceResult::Respond(resp) = result else {
+        panic!("expected the request to be rejected");
+    };
+    assert_eq!(resp.status, StatusCode::PAYLOAD_TOO_LARGE);
+}
@@ -0,0 +1,265 @@
+use crate::conf::types::CorsDeviceConfig;
+use crate::ctx::{RequestCtx, ResponseCtx};
+use crate::device::builtin::cors::CorsDevice;
+use crate::device::core::{Device, DeviceResult};
+use http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode, Version, header};
+use pretty_assertions::assert_eq;
+use std::net::Ipv4Addr;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+fn device(origins: &[&str]) -> CorsDevice {
+    CorsDevice::from_config(CorsDeviceConfig {
+        enable: true,
+        priority: 0,
+        routes: vec![],
+        allow_origins: origins.iter().map(|o| o.to_string()).collect(),
+        allow_methods: vec!["GET".to_string(), "POST".to_string()],
+        allow_headers: vec!["content-type".to_string()],
+        expose_headers: vec!["x-request-id".to_string()],
+        allow_credentials: true,
+        max_age_seconds: Some(600),
+    })
+    .unwrap()
+}
+
+fn ctx(method: Method, headers: &[(&str, &'static str)]) -> RequestCtx {
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+        map.append(
+            HeaderName::from_bytes(name.as_bytes()).unwrap(),
+            HeaderValue::from_static(value),
+        );
+    }
+
+    let mut ctx = RequestCtx::empty();
+    ctx.hydrate(
+        &"/api".parse().unwrap(),
+        &method,
+        &map,
+        &Version::HTTP_11,
+        false,
+        Ipv4Addr::LOCALHOST.into(),
+    )
+    .unwrap();
+    ctx
+}
+
+fn preflight(device: &CorsDevice, headers: &[(&str, &'static str)]) -> ResponseCtx {
+    let mut ctx = ctx(Method::OPTIONS, headers);
+    match device.on_request(&mut ctx) {
+        DeviceResult::Respond(resp) => resp,
+        _ => panic!("expected the preflight to be answered"),
+    }
+}
+
+fn value<'a>(headers: &'a HeaderMap, name: HeaderName) -> Option<&'a str> {
+    headers.get(name).map(|v| v.to_str().unwrap())
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn allowed_origin_is_reflected() {
+    // Arrange
+    let device = device(&["https://app.example.com"]);
+    let mut ctx = ctx(Method::GET, &[("origin", "https://app.example.com")]);
+
+    // Act
+    let result = device.on_request(&mut ctx);
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+    let headers = &ctx.response_headers;
+    assert_eq!(
+        value(headers, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+        Some("https://app.example.com")
+    );
+    assert_eq!(
+        value(headers, header::ACCESS_CONTROL_ALLOW_CREDENTIALS),
+        Some("true")
+    );
+    assert_eq!(
+        value(headers, header::ACCESS_CONTROL_EXPOSE_HEADERS),
+        Some("x-request-id")
+    );
+}
+
+#[test]
+fn wildcard_reflects_any_origin() {
+    // Arrange
+    let device = device(&["*"]);
+    let mut ctx = ctx(Method::GET, &[("origin", "https://other.example")]);
+
+    // Act
+    device.on_request(&mut ctx);
+
+    // Assert
+    assert_eq!(
+        value(&ctx.response_headers, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+        Some("https://other.example")
+    );
+}
+
+#[test]
+fn disallowed_origin_gets_no_headers() {
+    // Arrange
+    let device = device(&["https://app.example.com"]);
+    let mut ctx = ctx(Method::GET, &[("origin", "https://evil.example")]);
+
+    // Act
+    let result = device.on_request(&mut ctx);
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+    assert!(ctx.response_headers.is_empty());
+}
+
+#[test]
+fn null_origin_is_never_allowed() {
+    // Arrange
+    let device = device(&["*"]);
+    let mut ctx = ctx(Method::GET, &[("origin", "null")]);
+
+    // Act
+    device.on_request(&mut ctx);
+
+    // Assert
+    assert!(ctx.response_headers.is_empty());
+}
+
+#[test]
+fn preflight_is_answered_with_no_content() {
+    // Arrange
+    let device = device(&["https://app.example.com"]);
+
+    // Act
+    let resp = preflight(
+        &device,
+        &[
+            ("origin", "https://app.example.com"),
+            ("access-control-request-method", "POST"),
+            ("access-control-request-headers", "Content-Type"),
+        ],
+    );
+
+    // Assert
+    assert_eq!(resp.status, StatusCode::NO_CONTENT);
+    assert_eq!(
+        value(&resp.headers, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+        Some("https://app.example.com")
+    );
+    assert_eq!(
+        value(&resp.headers, header::ACCESS_CONTROL_ALLOW_METHODS),
+        Some("GET, POST")
+    );
+    assert_eq!(
+        value(&resp.headers, header::ACCESS_CONTROL_ALLOW_HEADERS),
+        Some("Content-Type")
+    );
+    assert_eq!(
+        value(&resp.headers, header::ACCESS_CONTROL_MAX_AGE),
+        Some("600")
+    );
+}
+
+#[test]
+fn preflight_from_disallowed_origin_gets_no_headers() {
+    // Arrange
+    let device = device(&["https://app.example.com"]);
+
+    // Act
+    let resp = preflight(
+        &device,
+        &[
+            ("origin", "https://evil.example"),
+            ("access-control-request-method", "GET"),
+        ],
+    );
+
+    // Assert
+    assert_eq!(resp.status, StatusCode::NO_CONTENT);
+    assert_eq!(
+        value(&resp.headers, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+        None
+    );
+}
+
+#[test]
+fn preflight_for_disallowed_method_gets_no_headers() {
+    // Arrange
+    let device = device(&["https://app.example.com"]);
+
+    // Act
+    let resp = preflight(
+        &device,
+        &[
+            ("origin", "https://app.example.com"),
+            ("access-control-request-method", "DELETE"),
+        ],
+    );
+
+    // Assert
+    assert_eq!(
+        value(&resp.headers, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+        None
+    );
+}
+
+#[test]
+fn preflight_for_disallowed_header_gets_no_headers() {
+    // Arrange
+    let device = device(&["https://app.example.com"]);
+
+    // Act
+    let resp = preflight(
+        &device,
+        &[
+            ("origin", "https://app.example.com"),
+            ("access-control-request-method", "GET"),
+            ("access-control-request-headers", "content-type, x-secret"),
+        ],
+    );
+
+    // Assert
+    assert_eq!(
+        value(&resp.headers, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+        None
+    );
+}
+
+#[test]
+fn options_without_request_method_is_not_a_preflight() {
+    // Arrange
+    let device = device(&["https://app.example.com"]);
+    let mut ctx = ctx(Method::OPTIONS, &[("origin", "https://app.example.com")]);
+
+    // Act
+    let result = device.on_request(&mut ctx);
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+}
+
+#[test]
+fn response_varies_on_origin() {
+    // Arrange
+    let device = device(&["*"]);
+    let mut headers = HeaderMap::new();
+    headers.insert(header::VARY, HeaderValue::from_static("Accept-Encoding"));
+    let mut resp = ResponseCtx::new(None, StatusCode::OK, headers, Vec::new());
+
+    // Act
+    device.on_response(&mut resp);
+
+    // Assert
+    let vary: Vec<&str> = resp
+        .headers
+        .get_all(header::VARY)
+        .iter()
+        .map(|v| v.to_str().unwrap())
+        .collect();
+    assert_eq!(vary, vec!["Accept-Encoding", "Origin"]);
+}
@@ -0,0 +1,138 @@
+use crate::conf::types::{DeprecationConfig, DeprecationDeviceConfig};
+use crate::ctx::ResponseCtx;
+use crate::device::builtin::deprecation::DeprecationDevice;
+use crate::device::core::{Device, DeviceResult};
+use crate::route::RouteId;
+use http::{HeaderMap, HeaderValue, StatusCode, header};
+use pretty_assertions::assert_eq;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+fn device() -> DeprecationDevice {
+    DeprecationDevice::from_config(DeprecationDeviceConfig {
+        enable: true,
+        priority: 0,
+        routes: vec![],
+        deprecations: vec![
+            DeprecationConfig {
+                path: "/api/v1/".to_string(),
+                deprecated_at: "2026-01-01T02:00:00+02:00".to_string(),
+                sunset_at: Some("2026-08-01T00:00:00Z".to_string()),
+                successor: Some("https://api.example.com/api/v2".to_string()),
+            },
+            DeprecationConfig {
+                path: "/legacy".to_string(),
+                deprecated_at: "2026-01-01T00:00:00Z".to_string(),
+                sunset_at: None,
+                successor: None,
+            },
+        ],
+    })
+    .unwrap()
+}
+
+fn response(route: Option<RouteId>) -> ResponseCtx {
+    let mut ctx = ResponseCtx::new(None, StatusCode::OK, HeaderMap::new(), Vec::new());
+    ctx.route_id = route;
+    ctx
+}
+
+fn header_value<'a>(ctx: &'a ResponseCtx, name: &str) -> Option<&'a str> {
+    ctx.headers.get(name).map(|v| v.to_str().unwrap())
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn deprecated_route_gets_deprecation_sunset_and_link_headers() {
+    // Arrange
+    let device = device();
+    let mut ctx = response(Some(RouteId::service("/api/v1", "api")));
+
+    // Act
+    let result = device.on_response(&mut ctx);
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+    assert_eq!(header_value(&ctx, "deprecation"), Some("@1767225600"));
+    assert_eq!(
+        header_value(&ctx, "sunset"),
+        Some("Sat, 01 Aug 2026 00:00:00 GMT")
+    );
+    assert_eq!(
+        header_value(&ctx, "link"),
+        Some("<https://api.example.com/api/v2>; rel=\"successor-version\"")
+    );
+}
+
+#[test]
+fn other_routes_are_left_unmarked() {
+    // Arrange
+    let device = device();
+    let mut current = response(Some(RouteId::service("/api/v2", "api")));
+    let mut nested = response(Some(RouteId::service("/api/v1/users", "users")));
+    let mut unrouted = response(None);
+
+    // Act
+    device.on_response(&mut current);
+    device.on_response(&mut nested);
+    device.on_response(&mut unrouted);
+
+    // Assert
+    for ctx in [current, nested, unrouted] {
+        assert!(ctx.headers.is_empty(), "{:?}", ctx.headers);
+    }
+}
+
+#[test]
+fn sunset_and_link_are_optional() {
+    // Arrange
+    let device = device();
+    let mut ctx = response(Some(RouteId::static_route("/legacy/", "/var/www")));
+
+    // Act
+    device.on_response(&mut ctx);
+
+    // Assert
+    assert_eq!(header_value(&ctx, "deprecation"), Some("@1767225600"));
+    assert_eq!(header_value(&ctx, "sunset"), None);
+    assert_eq!(header_value(&ctx, "link"), None);
+}
+
+#[test]
+fn successor_link_is_added_alongside_upstream_links() {
+    // Arrange
+    let device = device();
+    let mut ctx = response(Some(RouteId::service("/api/v1", "api")));
+    ctx.headers.insert(
+        header::LINK,
+        HeaderValue::from_static("</api/v1/users?page=2>; rel=\"next\""),
+    );
+
+    // Act
+    device.on_response(&mut ctx);
+
+    // Assert
+    let links: Vec<&HeaderValue> = ctx.headers.get_all(header::LINK).iter().collect();
+    assert_eq!(links.len(), 2);
+    assert_eq!(links[0], "</api/v1/users?page=2>; rel=\"next\"");
+}
+
+#[test]
+fn invalid_timestamp_fails_to_load() {
+    // Act
+    let result = DeprecationDevice::from_config(DeprecationDeviceConfig {
+        enable: true,
+        deprecations: vec![DeprecationConfig {
+            path: "/api/v1".to_string(),
+            deprecated_at: "last tuesday".to_string(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    });
+
+    // Assert
+    assert!(result.is_err());
+}
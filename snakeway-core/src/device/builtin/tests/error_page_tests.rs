@@ -0,0 +1,118 @@
+use crate::conf::types::{ErrorPageDeviceConfig, ErrorPageDeviceSpec};
+use crate::ctx::ResponseCtx;
+use crate::device::builtin::error_page::ErrorPageDevice;
+use crate::device::core::{Device, DeviceResult};
+use http::{HeaderMap, HeaderValue, StatusCode, header};
+use pretty_assertions::assert_eq;
+use std::fs;
+use tempfile::tempdir;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+const ERROR_PAGE: &str = "<h1>Something went wrong</h1>";
+
+fn config() -> ErrorPageDeviceConfig {
+    ErrorPageDeviceSpec {
+        enable: true,
+        body: Some(ERROR_PAGE.to_string()),
+        ..Default::default()
+    }
+    .into()
+}
+
+fn upstream_response(status: StatusCode, content_type: &'static str, body: &str) -> ResponseCtx {
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+    headers.insert("x-powered-by", HeaderValue::from_static("Express 4.17.1"));
+    headers.insert(
+        "x-internal-host",
+        HeaderValue::from_static("api-7.prod.internal"),
+    );
+    headers.insert(header::RETRY_AFTER, HeaderValue::from_static("30"));
+    ResponseCtx::new(None, status, headers, body.as_bytes().to_vec())
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn upstream_500_with_stack_trace_is_replaced_by_error_page() {
+    // Arrange
+    let device = ErrorPageDevice::from_config(config()).unwrap();
+    let mut ctx = upstream_response(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "text/plain",
+        "TypeError: Cannot read properties of undefined\n    at /srv/app/routes/users.js:42:17",
+    );
+
+    // Act
+    let result = device.after_proxy(&mut ctx);
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+    assert_eq!(ctx.status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(ctx.body, ERROR_PAGE.as_bytes().to_vec());
+    let mut names: Vec<&str> = ctx.headers.keys().map(|n| n.as_str()).collect();
+    names.sort();
+    assert_eq!(names, vec!["cache-control", "content-type", "retry-after"]);
+    assert_eq!(
+        ctx.headers.get(header::CONTENT_TYPE).unwrap(),
+        "text/html; charset=utf-8"
+    );
+}
+
+#[test]
+fn intentional_problem_json_error_passes_through() {
+    // Arrange
+    let device = ErrorPageDevice::from_config(config()).unwrap();
+    let body = r#"{"title":"Upstream quota exhausted"}"#;
+    let mut ctx = upstream_response(
+        StatusCode::SERVICE_UNAVAILABLE,
+        "application/problem+json",
+        body,
+    );
+
+    // Act
+    device.after_proxy(&mut ctx);
+
+    // Assert
+    assert_eq!(ctx.body, body.as_bytes().to_vec());
+    assert!(ctx.headers.contains_key("x-powered-by"));
+}
+
+#[test]
+fn status_outside_configured_set_passes_through() {
+    // Arrange
+    let device = ErrorPageDevice::from_config(config()).unwrap();
+    let mut ctx = upstream_response(StatusCode::NOT_FOUND, "text/plain", "no such user");
+
+    // Act
+    device.after_proxy(&mut ctx);
+
+    // Assert
+    assert_eq!(ctx.body, b"no such user".to_vec());
+    assert!(ctx.headers.contains_key("x-internal-host"));
+}
+
+#[test]
+fn exact_status_and_body_file_are_supported() {
+    // Arrange
+    let dir = tempdir().unwrap();
+    let page = dir.path().join("404.html");
+    fs::write(&page, "<h1>Not found</h1>").unwrap();
+    let device = ErrorPageDevice::from_config(ErrorPageDeviceConfig {
+        statuses: vec!["404".to_string()],
+        body: None,
+        body_file: Some(page),
+        ..config()
+    })
+    .unwrap();
+    let mut ctx = upstream_response(StatusCode::NOT_FOUND, "text/plain", "no such user");
+
+    // Act
+    device.after_proxy(&mut ctx);
+
+    // Assert
+    assert_eq!(ctx.body, b"<h1>Not found</h1>".to_vec());
+}
@@ -0,0 +1,179 @@
+use crate::conf::types::{ExperimentDeviceConfig, ExperimentVariantConfig};
+use crate::ctx::RequestCtx;
+use crate::device::builtin::experiment::{EXPERIMENT_VARIANT_HEADER, ExperimentDevice};
+use crate::device::core::{Device, DeviceResult};
+use http::{HeaderMap, HeaderValue, Method, Version, header};
+use pretty_assertions::assert_eq;
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+fn device(experiment: &str, weights: &[(&str, u32)]) -> ExperimentDevice {
+    ExperimentDevice::from_config(ExperimentDeviceConfig {
+        enable: true,
+        priority: 0,
+        routes: vec![],
+        experiment: experiment.to_string(),
+        cookie: "uid".to_string(),
+        variants: weights
+            .iter()
+            .map(|(name, weight)| ExperimentVariantConfig {
+                name: name.to_string(),
+                weight: *weight,
+            })
+            .collect(),
+    })
+    .unwrap()
+}
+
+fn ctx_with_cookies(cookies: &[&'static str]) -> RequestCtx {
+    let mut headers = HeaderMap::new();
+    for cookie in cookies {
+        headers.append(header::COOKIE, HeaderValue::from_static(cookie));
+    }
+
+    let mut ctx = RequestCtx::empty();
+    ctx.hydrate(
+        &"/".parse().unwrap(),
+        &Method::GET,
+        &headers,
+        &Version::HTTP_11,
+        false,
+        Ipv4Addr::LOCALHOST.into(),
+    )
+    .unwrap();
+    ctx
+}
+
+fn proportions(device: &ExperimentDevice, users: usize) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for user in 0..users {
+        let variant = device.assign(&format!("user-{user}"));
+        *counts
+            .entry(variant.to_str().unwrap().to_string())
+            .or_insert(0) += 1;
+    }
+    counts
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn assignment_is_deterministic() {
+    // Arrange
+    let first = device("checkout", &[("control", 50), ("treatment", 50)]);
+    let second = device("checkout", &[("control", 50), ("treatment", 50)]);
+
+    // Act
+    let assigned: Vec<bool> = (0..100)
+        .map(|user| {
+            let key = format!("user-{user}");
+            first.assign(&key) == second.assign(&key) && first.assign(&key) == first.assign(&key)
+        })
+        .collect();
+
+    // Assert
+    assert!(assigned.iter().all(|same| *same));
+}
+
+#[test]
+fn experiments_assign_independently() {
+    // Arrange
+    let checkout = device("checkout", &[("a", 50), ("b", 50)]);
+    let search = device("search", &[("a", 50), ("b", 50)]);
+
+    // Act
+    let differs = (0..100)
+        .map(|user| format!("user-{user}"))
+        .filter(|key| checkout.assign(key) != search.assign(key))
+        .count();
+
+    // Assert
+    assert!(differs > 0);
+}
+
+#[test]
+fn variants_are_assigned_in_proportion_to_weights() {
+    // Arrange
+    let device = device("checkout", &[("control", 80), ("treatment", 20)]);
+
+    // Act
+    let counts = proportions(&device, 20_000);
+
+    // Assert
+    let control = counts["control"] as f64 / 20_000.0;
+    let treatment = counts["treatment"] as f64 / 20_000.0;
+    assert!((control - 0.8).abs() < 0.02, "control share was {control}");
+    assert!(
+        (treatment - 0.2).abs() < 0.02,
+        "treatment share was {treatment}"
+    );
+}
+
+#[test]
+fn zero_weight_variant_is_never_assigned() {
+    // Arrange
+    let device = device("checkout", &[("control", 1), ("paused", 0)]);
+
+    // Act
+    let counts = proportions(&device, 1_000);
+
+    // Assert
+    assert_eq!(counts.get("paused"), None);
+    assert_eq!(counts["control"], 1_000);
+}
+
+#[test]
+fn variant_is_sent_upstream_and_to_client() {
+    // Arrange
+    let device = device("checkout", &[("control", 50), ("treatment", 50)]);
+    let mut ctx = ctx_with_cookies(&["theme=dark; uid=user-42"]);
+    let expected = device.assign("user-42").clone();
+
+    // Act
+    let result = device.on_request(&mut ctx);
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+    assert_eq!(
+        ctx.upstream_headers.get(EXPERIMENT_VARIANT_HEADER),
+        Some(&expected)
+    );
+    assert_eq!(
+        ctx.response_headers.get(EXPERIMENT_VARIANT_HEADER),
+        Some(&expected)
+    );
+}
+
+#[test]
+fn cookie_is_found_across_cookie_headers() {
+    // Arrange
+    let device = device("checkout", &[("control", 50), ("treatment", 50)]);
+    let mut ctx = ctx_with_cookies(&["theme=dark", "uid=user-42"]);
+
+    // Act
+    device.on_request(&mut ctx);
+
+    // Assert
+    assert_eq!(
+        ctx.upstream_headers.get(EXPERIMENT_VARIANT_HEADER),
+        Some(device.assign("user-42"))
+    );
+}
+
+#[test]
+fn request_without_cookie_is_not_tagged() {
+    // Arrange
+    let device = device("checkout", &[("control", 50), ("treatment", 50)]);
+    let mut ctx = ctx_with_cookies(&["theme=dark; uid_old=user-42"]);
+
+    // Act
+    device.on_request(&mut ctx);
+
+    // Assert
+    assert!(ctx.upstream_headers.is_empty());
+    assert!(ctx.response_headers.is_empty());
+}
@@ -0,0 +1,175 @@
+use crate::conf::types::{HeaderRewriteDeviceConfig, HeaderSetConfig};
+use crate::ctx::{RequestCtx, ResponseCtx};
+use crate::device::builtin::header_rewrite::HeaderRewriteDevice;
+use crate::device::core::{Device, DeviceResult};
+use http::{HeaderMap, HeaderValue, Method, StatusCode, Version};
+use pretty_assertions::assert_eq;
+use std::net::Ipv4Addr;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+fn set(name: &str, value: &str) -> HeaderSetConfig {
+    HeaderSetConfig {
+        name: name.to_string(),
+        value: value.to_string(),
+    }
+}
+
+fn header_map(headers: &[(&'static str, &'static str)]) -> HeaderMap {
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+        map.append(*name, HeaderValue::from_static(value));
+    }
+    map
+}
+
+fn ctx_with(headers: &[(&'static str, &'static str)]) -> RequestCtx {
+    let mut ctx = RequestCtx::empty();
+    ctx.hydrate(
+        &"/".parse().unwrap(),
+        &Method::GET,
+        &header_map(headers),
+        &Version::HTTP_11,
+        false,
+        Ipv4Addr::LOCALHOST.into(),
+    )
+    .unwrap();
+    ctx
+}
+
+fn response_with(headers: &[(&'static str, &'static str)]) -> ResponseCtx {
+    ResponseCtx::new(None, StatusCode::OK, header_map(headers), Vec::new())
+}
+
+fn values(headers: &HeaderMap, name: &str) -> Vec<String> {
+    headers
+        .get_all(name)
+        .iter()
+        .map(|v| v.to_str().unwrap().to_string())
+        .collect()
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn request_set_replaces_every_value() {
+    // Arrange
+    let device = HeaderRewriteDevice::from_config(HeaderRewriteDeviceConfig {
+        enable: true,
+        request_set: vec![set("X-Env", "prod")],
+        ..Default::default()
+    })
+    .unwrap();
+    let mut ctx = ctx_with(&[("x-env", "dev"), ("x-env", "staging")]);
+
+    // Act
+    let result = device.on_request(&mut ctx);
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+    assert_eq!(values(ctx.headers(), "x-env"), vec!["prod"]);
+    assert_eq!(values(&ctx.upstream_headers, "x-env"), vec!["prod"]);
+}
+
+#[test]
+fn request_remove_is_case_insensitive_and_removes_every_value() {
+    // Arrange
+    let device = HeaderRewriteDevice::from_config(HeaderRewriteDeviceConfig {
+        enable: true,
+        request_remove: vec!["X-DEBUG".to_string()],
+        ..Default::default()
+    })
+    .unwrap();
+    let mut ctx = ctx_with(&[("x-debug", "1"), ("x-debug", "2"), ("accept", "*/*")]);
+
+    // Act
+    device.on_request(&mut ctx);
+
+    // Assert
+    assert!(ctx.headers().get("x-debug").is_none());
+    assert_eq!(values(ctx.headers(), "accept"), vec!["*/*"]);
+    assert_eq!(ctx.upstream_remove_headers, vec!["x-debug"]);
+}
+
+#[test]
+fn request_remove_drops_headers_earlier_devices_added() {
+    // Arrange
+    let device = HeaderRewriteDevice::from_config(HeaderRewriteDeviceConfig {
+        enable: true,
+        request_remove: vec!["X-Internal".to_string()],
+        ..Default::default()
+    })
+    .unwrap();
+    let mut ctx = ctx_with(&[]);
+    ctx.upstream_headers
+        .insert("x-internal", HeaderValue::from_static("1"));
+
+    // Act
+    device.on_request(&mut ctx);
+
+    // Assert
+    assert!(ctx.upstream_headers.is_empty());
+}
+
+#[test]
+fn removing_missing_header_is_a_no_op() {
+    // Arrange
+    let device = HeaderRewriteDevice::from_config(HeaderRewriteDeviceConfig {
+        enable: true,
+        request_remove: vec!["X-Missing".to_string()],
+        response_remove: vec!["X-Missing".to_string()],
+        ..Default::default()
+    })
+    .unwrap();
+    let mut ctx = ctx_with(&[("accept", "*/*")]);
+    let mut resp = response_with(&[("content-type", "text/plain")]);
+
+    // Act
+    device.on_request(&mut ctx);
+    let result = device.on_response(&mut resp);
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+    assert_eq!(ctx.headers().len(), 1);
+    assert_eq!(resp.headers, header_map(&[("content-type", "text/plain")]));
+}
+
+#[test]
+fn response_set_and_remove_apply_to_multi_value_headers() {
+    // Arrange
+    let device = HeaderRewriteDevice::from_config(HeaderRewriteDeviceConfig {
+        enable: true,
+        response_set: vec![set("Cache-Control", "no-store")],
+        response_remove: vec!["set-COOKIE".to_string()],
+        ..Default::default()
+    })
+    .unwrap();
+    let mut resp = response_with(&[
+        ("cache-control", "max-age=60"),
+        ("cache-control", "public"),
+        ("set-cookie", "a=1"),
+        ("set-cookie", "b=2"),
+    ]);
+
+    // Act
+    device.on_response(&mut resp);
+
+    // Assert
+    assert_eq!(values(&resp.headers, "cache-control"), vec!["no-store"]);
+    assert!(resp.headers.get("set-cookie").is_none());
+}
+
+#[test]
+fn invalid_header_name_fails_to_load() {
+    // Act
+    let result = HeaderRewriteDevice::from_config(HeaderRewriteDeviceConfig {
+        enable: true,
+        request_set: vec![set("bad header", "1")],
+        ..Default::default()
+    });
+
+    // Assert
+    assert!(result.is_err());
+}
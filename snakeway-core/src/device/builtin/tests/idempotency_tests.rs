@@ -0,0 +1,269 @@
+use crate::conf::types::IdempotencyDeviceConfig;
+use crate::ctx::{RequestCtx, ResponseCtx};
+use crate::device::builtin::idempotency::{IDEMPOTENT_REPLAYED_HEADER, IdempotencyDevice};
+use crate::device::core::{CachePurge, Device, DeviceResult};
+use bytes::Bytes;
+use http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode, Version, header};
+use pretty_assertions::assert_eq;
+use std::net::Ipv4Addr;
+use std::sync::Arc;
+use std::time::Duration;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+fn device(wait_timeout_milliseconds: u64) -> IdempotencyDevice {
+    IdempotencyDevice::from_config(IdempotencyDeviceConfig {
+        enable: true,
+        priority: 0,
+        routes: vec![],
+        key_header: "Idempotency-Key".to_string(),
+        methods: vec!["POST".to_string()],
+        paths: vec!["/payments".to_string()],
+        ttl_seconds: 60,
+        wait_timeout_milliseconds,
+        max_body_bytes: 1024,
+    })
+    .unwrap()
+}
+
+fn ctx(method: Method, path: &str, key: Option<&'static str>) -> RequestCtx {
+    let mut headers = HeaderMap::new();
+    if let Some(key) = key {
+        headers.insert(
+            HeaderName::from_static("idempotency-key"),
+            HeaderValue::from_static(key),
+        );
+    }
+
+    let mut ctx = RequestCtx::empty();
+    ctx.hydrate(
+        &path.parse().unwrap(),
+        &method,
+        &headers,
+        &Version::HTTP_11,
+        false,
+        Ipv4Addr::LOCALHOST.into(),
+    )
+    .unwrap();
+    ctx
+}
+
+/// Run the response hooks the gateway runs for a proxied response.
+fn respond(device: &IdempotencyDevice, ctx: &RequestCtx, status: StatusCode, body: &'static str) {
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("text/plain"));
+    headers.insert(header::CONTENT_LENGTH, HeaderValue::from(body.len()));
+    let mut resp = ResponseCtx::new(ctx.request_id(), status, headers, Vec::new());
+
+    device.on_response(&mut resp);
+    device.on_stream_response_body(ctx, Some(&Bytes::from_static(body.as_bytes())), false);
+    device.on_stream_response_body(ctx, None, true);
+    device.on_complete(ctx);
+}
+
+fn replayed(result: DeviceResult) -> ResponseCtx {
+    match result {
+        DeviceResult::Respond(resp) => resp,
+        _ => panic!("expected a response"),
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[tokio::test]
+async fn first_request_is_proxied() {
+    // Arrange
+    let device = device(1000);
+    let mut ctx = ctx(Method::POST, "/payments", Some("abc"));
+
+    // Act
+    let result = device.on_request_async(&mut ctx).await;
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+}
+
+#[tokio::test]
+async fn duplicate_is_replayed() {
+    // Arrange
+    let device = device(1000);
+    let mut first = ctx(Method::POST, "/payments", Some("abc"));
+    device.on_request_async(&mut first).await;
+    respond(&device, &first, StatusCode::CREATED, "payment 1");
+    let mut second = ctx(Method::POST, "/payments", Some("abc"));
+
+    // Act
+    let resp = replayed(device.on_request_async(&mut second).await);
+
+    // Assert
+    assert_eq!(resp.status, StatusCode::CREATED);
+    assert_eq!(resp.body, b"payment 1");
+    assert_eq!(
+        resp.headers.get(IDEMPOTENT_REPLAYED_HEADER).unwrap(),
+        "true"
+    );
+    assert_eq!(
+        resp.headers.get(header::CONTENT_TYPE).unwrap(),
+        "text/plain"
+    );
+    assert_eq!(resp.headers.get(header::CONTENT_LENGTH), None);
+}
+
+#[tokio::test]
+async fn concurrent_duplicate_waits_for_the_first() {
+    // Arrange
+    let device = Arc::new(device(5000));
+    let mut first = ctx(Method::POST, "/payments", Some("abc"));
+    device.on_request_async(&mut first).await;
+
+    let waiting = device.clone();
+    let second = tokio::spawn(async move {
+        let mut ctx = ctx(Method::POST, "/payments", Some("abc"));
+        waiting.on_request_async(&mut ctx).await
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(!second.is_finished());
+
+    // Act
+    respond(&device, &first, StatusCode::CREATED, "payment 1");
+    let resp = replayed(second.await.unwrap());
+
+    // Assert
+    assert_eq!(resp.status, StatusCode::CREATED);
+    assert_eq!(resp.body, b"payment 1");
+}
+
+#[tokio::test]
+async fn concurrent_duplicate_gets_conflict_after_waiting() {
+    // Arrange
+    let device = device(20);
+    let mut first = ctx(Method::POST, "/payments", Some("abc"));
+    device.on_request_async(&mut first).await;
+    let mut second = ctx(Method::POST, "/payments", Some("abc"));
+
+    // Act
+    let resp = replayed(device.on_request_async(&mut second).await);
+
+    // Assert
+    assert_eq!(resp.status, StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn failed_request_releases_the_key() {
+    // Arrange
+    let device = device(1000);
+    let mut first = ctx(Method::POST, "/payments", Some("abc"));
+    device.on_request_async(&mut first).await;
+    device.on_complete(&first);
+    let mut second = ctx(Method::POST, "/payments", Some("abc"));
+
+    // Act
+    let result = device.on_request_async(&mut second).await;
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+}
+
+#[tokio::test]
+async fn server_error_is_not_replayed() {
+    // Arrange
+    let device = device(1000);
+    let mut first = ctx(Method::POST, "/payments", Some("abc"));
+    device.on_request_async(&mut first).await;
+    respond(&device, &first, StatusCode::BAD_GATEWAY, "try again");
+    let mut second = ctx(Method::POST, "/payments", Some("abc"));
+
+    // Act
+    let result = device.on_request_async(&mut second).await;
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+}
+
+#[tokio::test]
+async fn keys_are_scoped_to_the_path() {
+    // Arrange
+    let device = device(1000);
+    let mut first = ctx(Method::POST, "/payments", Some("abc"));
+    device.on_request_async(&mut first).await;
+    respond(&device, &first, StatusCode::CREATED, "payment 1");
+    let mut other = ctx(Method::POST, "/payments/refunds", Some("abc"));
+
+    // Act
+    let result = device.on_request_async(&mut other).await;
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+}
+
+#[tokio::test]
+async fn missing_key_is_rejected() {
+    // Arrange
+    let device = device(1000);
+    let mut ctx = ctx(Method::POST, "/payments", None);
+
+    // Act
+    let resp = replayed(device.on_request_async(&mut ctx).await);
+
+    // Assert
+    assert_eq!(resp.status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn other_methods_and_paths_need_no_key() {
+    // Arrange
+    let device = device(1000);
+    let mut get = ctx(Method::GET, "/payments", None);
+    let mut other_path = ctx(Method::POST, "/paymentsx", None);
+
+    // Act
+    let get_result = device.on_request_async(&mut get).await;
+    let other_path_result = device.on_request_async(&mut other_path).await;
+
+    // Assert
+    assert!(matches!(get_result, DeviceResult::Continue));
+    assert!(matches!(other_path_result, DeviceResult::Continue));
+}
+
+#[tokio::test]
+async fn purged_response_is_proxied_again() {
+    // Arrange
+    let device = device(1000);
+    let mut first = ctx(Method::POST, "/payments", Some("abc"));
+    device.on_request_async(&mut first).await;
+    respond(&device, &first, StatusCode::CREATED, "payment 1");
+    let mut other = ctx(Method::POST, "/payments", Some("def"));
+    device.on_request_async(&mut other).await;
+    respond(&device, &other, StatusCode::CREATED, "payment 2");
+
+    // Act
+    let purged = device.purge_cache(&CachePurge::Key("POST /payments abc".to_string()));
+    let mut second = ctx(Method::POST, "/payments", Some("abc"));
+    let result = device.on_request_async(&mut second).await;
+    let mut other_again = ctx(Method::POST, "/payments", Some("def"));
+    let other_result = device.on_request_async(&mut other_again).await;
+
+    // Assert
+    assert_eq!(purged, 1);
+    assert!(matches!(result, DeviceResult::Continue));
+    assert_eq!(replayed(other_result).body, b"payment 2");
+}
+
+#[tokio::test]
+async fn reset_keeps_keys_in_flight() {
+    // Arrange
+    let device = device(0);
+    let mut first = ctx(Method::POST, "/payments", Some("abc"));
+    device.on_request_async(&mut first).await;
+
+    // Act
+    let reset = device.reset();
+    let mut duplicate = ctx(Method::POST, "/payments", Some("abc"));
+    let resp = replayed(device.on_request_async(&mut duplicate).await);
+
+    // Assert
+    assert!(reset);
+    assert_eq!(resp.status, StatusCode::CONFLICT);
+}
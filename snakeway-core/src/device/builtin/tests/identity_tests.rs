@@ -0,0 +1,314 @@
+use crate::conf::types::{ForwardingHeaderKind, GeoIpConfig, IdentityDeviceConfig};
+use crate::ctx::RequestCtx;
+use crate::device::builtin::identity::{IdentityDevice, parse_forwarded_for, resolve_client_ip};
+use crate::device::core::{Device, DeviceResult};
+use crate::enrichment::geoip::{GeoInfo, GeoIp};
+use crate::enrichment::user_agent::ClientIdentity;
+use http::{HeaderMap, HeaderName, HeaderValue};
+use ipnet::IpNet;
+use pretty_assertions::assert_eq;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+fn ip(s: &str) -> IpAddr {
+    s.parse().unwrap()
+}
+
+fn trusted(nets: &[&str]) -> Vec<IpNet> {
+    nets.iter().map(|n| n.parse().unwrap()).collect()
+}
+
+fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    for (name, value) in pairs {
+        headers.insert(*name, HeaderValue::from_static(value));
+    }
+    headers
+}
+
+const XFF: ForwardingHeaderKind = ForwardingHeaderKind::XForwardedFor;
+const FORWARDED: ForwardingHeaderKind = ForwardingHeaderKind::Forwarded;
+
+fn cf_connecting_ip() -> HeaderName {
+    HeaderName::from_static("cf-connecting-ip")
+}
+
+/// A DB-IP country database, which has the same layout as GeoLite2-Country.
+fn country_db() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../integration-tests/fixtures/geoip/dbip-country-lite-2025-12.mmdb")
+}
+
+fn geoip_device() -> IdentityDevice {
+    IdentityDevice::from_config(
+        IdentityDeviceConfig {
+            enable: true,
+            enable_geoip: true,
+            geoip_city_db: Some(country_db()),
+            ..Default::default()
+        },
+        Arc::default(),
+    )
+    .unwrap()
+}
+
+/// A device looking clients up in the server's databases.
+fn server_geoip_device(geoip: Arc<GeoIp>) -> IdentityDevice {
+    IdentityDevice::from_config(
+        IdentityDeviceConfig {
+            enable: true,
+            enable_geoip: true,
+            ..Default::default()
+        },
+        geoip,
+    )
+    .unwrap()
+}
+
+fn geo_of(device: &IdentityDevice, peer_ip: &str) -> Option<GeoInfo> {
+    let mut ctx = RequestCtx::empty();
+    ctx.peer_ip = ip(peer_ip);
+    device.on_request(&mut ctx);
+    ctx.extensions.get::<ClientIdentity>().unwrap().geo.clone()
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn client_ip_header_from_trusted_proxy_is_used() {
+    // Arrange
+    let headers = headers(&[("cf-connecting-ip", "203.0.113.7")]);
+    let proxies = trusted(&["10.0.0.0/8"]);
+    let header_name = cf_connecting_ip();
+
+    // Act
+    let (client_ip, proxy_chain) =
+        resolve_client_ip(&headers, ip("10.1.2.3"), &proxies, Some(&header_name), XFF);
+
+    // Assert
+    assert_eq!(client_ip, ip("203.0.113.7"));
+    assert!(proxy_chain.is_empty());
+}
+
+#[test]
+fn client_ip_header_from_untrusted_peer_is_ignored() {
+    // Arrange
+    let headers = headers(&[("cf-connecting-ip", "203.0.113.7")]);
+    let proxies = trusted(&["10.0.0.0/8"]);
+    let header_name = cf_connecting_ip();
+
+    // Act
+    let (client_ip, _) = resolve_client_ip(
+        &headers,
+        ip("198.51.100.1"),
+        &proxies,
+        Some(&header_name),
+        XFF,
+    );
+
+    // Assert
+    assert_eq!(client_ip, ip("198.51.100.1"));
+}
+
+#[test]
+fn client_ip_header_takes_precedence_over_xff() {
+    // Arrange
+    let headers = headers(&[
+        ("cf-connecting-ip", "203.0.113.7"),
+        ("x-forwarded-for", "192.0.2.44"),
+    ]);
+    let proxies = trusted(&["10.0.0.0/8"]);
+    let header_name = cf_connecting_ip();
+
+    // Act
+    let (client_ip, _) =
+        resolve_client_ip(&headers, ip("10.1.2.3"), &proxies, Some(&header_name), XFF);
+
+    // Assert
+    assert_eq!(client_ip, ip("203.0.113.7"));
+}
+
+#[test]
+fn invalid_client_ip_header_falls_back_to_xff() {
+    // Arrange
+    let headers = headers(&[
+        ("cf-connecting-ip", "not-an-ip"),
+        ("x-forwarded-for", "192.0.2.44, 10.0.0.9"),
+    ]);
+    let proxies = trusted(&["10.0.0.0/8"]);
+    let header_name = cf_connecting_ip();
+
+    // Act
+    let (client_ip, proxy_chain) =
+        resolve_client_ip(&headers, ip("10.1.2.3"), &proxies, Some(&header_name), XFF);
+
+    // Assert
+    assert_eq!(client_ip, ip("192.0.2.44"));
+    assert_eq!(proxy_chain, vec![ip("10.0.0.9")]);
+}
+
+#[test]
+fn unconfigured_client_ip_header_is_ignored() {
+    // Arrange
+    let headers = headers(&[("cf-connecting-ip", "203.0.113.7")]);
+    let proxies = trusted(&["10.0.0.0/8"]);
+
+    // Act
+    let (client_ip, _) = resolve_client_ip(&headers, ip("10.1.2.3"), &proxies, None, XFF);
+
+    // Assert
+    assert_eq!(client_ip, ip("10.1.2.3"));
+}
+
+#[test]
+fn forwarded_ipv6_with_port_is_resolved() {
+    // Arrange
+    let headers = headers(&[("forwarded", r#"for="[2001:db8:cafe::17]:4711";proto=https"#)]);
+    let proxies = trusted(&["10.0.0.0/8"]);
+
+    // Act
+    let (client_ip, proxy_chain) = resolve_client_ip(&headers, ip("10.1.2.3"), &proxies, None, XFF);
+
+    // Assert
+    assert_eq!(client_ip, ip("2001:db8:cafe::17"));
+    assert!(proxy_chain.is_empty());
+}
+
+#[test]
+fn forwarded_chain_is_walked_past_trusted_proxies() {
+    // Arrange
+    let headers = headers(&[(
+        "forwarded",
+        r#"for=198.51.100.9, for="192.0.2.60:8080";by=10.0.0.1, for=10.0.0.7, For="[fd00::2]""#,
+    )]);
+    let proxies = trusted(&["10.0.0.0/8", "fd00::/8"]);
+
+    // Act
+    let (client_ip, proxy_chain) = resolve_client_ip(&headers, ip("10.1.2.3"), &proxies, None, XFF);
+
+    // Assert
+    assert_eq!(client_ip, ip("192.0.2.60"));
+    assert_eq!(proxy_chain, vec![ip("fd00::2"), ip("10.0.0.7")]);
+}
+
+#[test]
+fn forwarded_from_untrusted_peer_is_ignored() {
+    // Arrange
+    let headers = headers(&[("forwarded", "for=192.0.2.60")]);
+    let proxies = trusted(&["10.0.0.0/8"]);
+
+    // Act
+    let (client_ip, _) = resolve_client_ip(&headers, ip("198.51.100.1"), &proxies, None, FORWARDED);
+
+    // Assert
+    assert_eq!(client_ip, ip("198.51.100.1"));
+}
+
+#[test]
+fn malformed_forwarded_elements_are_skipped() {
+    // Act
+    let ips = parse_forwarded_for(concat!(
+        r#"for=unknown, for=_hidden, proto=https, for="[2001:db8::1"#,
+        r#", for=[2001:db8::2]x, for=not-an-ip, for=192.0.2.60:443"#,
+        r#", for="[2001:db8::3]:443", host=example.com;for=203.0.113.5"#,
+    ));
+
+    // Assert
+    assert_eq!(
+        ips,
+        vec![ip("192.0.2.60"), ip("2001:db8::3"), ip("203.0.113.5")]
+    );
+}
+
+#[test]
+fn preferred_forwarding_header_decides_when_both_are_present() {
+    // Arrange
+    let headers = headers(&[
+        ("x-forwarded-for", "192.0.2.44"),
+        ("forwarded", "for=203.0.113.7"),
+    ]);
+    let proxies = trusted(&["10.0.0.0/8"]);
+
+    // Act
+    let (via_xff, _) = resolve_client_ip(&headers, ip("10.1.2.3"), &proxies, None, XFF);
+    let (via_forwarded, _) = resolve_client_ip(&headers, ip("10.1.2.3"), &proxies, None, FORWARDED);
+
+    // Assert
+    assert_eq!(via_xff, ip("192.0.2.44"));
+    assert_eq!(via_forwarded, ip("203.0.113.7"));
+}
+
+#[test]
+fn preferred_forwarding_header_falls_back_to_the_other_one() {
+    // Arrange
+    let xff_only = headers(&[("x-forwarded-for", "192.0.2.44")]);
+    let forwarded_only = headers(&[("forwarded", "for=203.0.113.7")]);
+    let proxies = trusted(&["10.0.0.0/8"]);
+
+    // Act
+    let (from_xff, _) = resolve_client_ip(&xff_only, ip("10.1.2.3"), &proxies, None, FORWARDED);
+    let (from_forwarded, _) =
+        resolve_client_ip(&forwarded_only, ip("10.1.2.3"), &proxies, None, XFF);
+
+    // Assert
+    assert_eq!(from_xff, ip("192.0.2.44"));
+    assert_eq!(from_forwarded, ip("203.0.113.7"));
+}
+
+#[test]
+fn client_identity_carries_geoip_country() {
+    // Arrange
+    let device = geoip_device();
+    let mut ctx = RequestCtx::empty();
+    ctx.peer_ip = ip("81.2.69.142");
+
+    // Act
+    let result = device.on_request(&mut ctx);
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+    let identity = ctx.extensions.get::<ClientIdentity>().unwrap();
+    let geo = identity.geo.as_ref().unwrap();
+    assert_eq!(geo.country_code.as_deref(), Some("GB"));
+}
+
+#[test]
+fn client_identity_has_no_geo_for_unknown_ips() {
+    // Arrange
+    let device = geoip_device();
+    let mut ctx = RequestCtx::empty();
+    ctx.peer_ip = ip("10.1.2.3");
+
+    // Act
+    device.on_request(&mut ctx);
+
+    // Assert
+    let identity = ctx.extensions.get::<ClientIdentity>().unwrap();
+    assert!(identity.geo.is_none());
+}
+
+#[test]
+fn server_geoip_reload_takes_effect_for_the_device() {
+    // Arrange
+    let geoip = Arc::new(GeoIp::open(&GeoIpConfig::default()).unwrap());
+    let device = server_geoip_device(geoip.clone());
+    let before = geo_of(&device, "81.2.69.142");
+
+    // Act
+    geoip
+        .reload(&GeoIpConfig {
+            city_db: Some(country_db()),
+            ..Default::default()
+        })
+        .unwrap();
+
+    // Assert
+    assert!(before.is_none());
+    let after = geo_of(&device, "81.2.69.142").unwrap();
+    assert_eq!(after.country_code.as_deref(), Some("GB"));
+}
@@ -0,0 +1,164 @@
+use crate::conf::types::{IpReputationAction, IpReputationDeviceConfig};
+use crate::ctx::RequestCtx;
+use crate::device::builtin::ip_reputation::{
+    IpReputationDevice, ReputationList, parse_reputation_list,
+};
+use crate::device::core::{Device, DeviceResult};
+use http::StatusCode;
+use pretty_assertions::assert_eq;
+use std::net::IpAddr;
+use std::path::Path;
+use std::time::Duration;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+fn device(list_file: &Path, action: IpReputationAction) -> IpReputationDevice {
+    IpReputationDevice::from_config(IpReputationDeviceConfig {
+        enable: true,
+        priority: 0,
+        routes: vec![],
+        list_file: Some(list_file.to_path_buf()),
+        list_reload_interval_seconds: 30,
+        reputation_url: None,
+        timeout_milliseconds: 250,
+        cache_ttl_milliseconds: 60_000,
+        fail_open: true,
+        action,
+        block_status: 403,
+        tag_header: "X-IP-Reputation".to_string(),
+    })
+    .unwrap()
+}
+
+fn ctx_from(ip: &str) -> RequestCtx {
+    let mut ctx = RequestCtx::empty();
+    ctx.peer_ip = ip.parse().unwrap();
+    ctx
+}
+
+fn ip(s: &str) -> IpAddr {
+    s.parse().unwrap()
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn list_accepts_ips_cidrs_and_comments() {
+    // Arrange
+    let contents = "# threat feed\n203.0.113.7\n\n198.51.100.0/24 # scanner range\n2001:db8::/32\n";
+
+    // Act
+    let networks = parse_reputation_list(contents).unwrap();
+
+    // Assert
+    let networks: Vec<_> = networks.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        networks,
+        vec!["203.0.113.7/32", "198.51.100.0/24", "2001:db8::/32"]
+    );
+}
+
+#[test]
+fn list_rejects_invalid_entry() {
+    // Arrange
+    let contents = "203.0.113.7\nnot-an-ip\n";
+
+    // Act
+    let result = parse_reputation_list(contents);
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "line 2: invalid IP or CIDR: not-an-ip"
+    );
+}
+
+#[tokio::test]
+async fn listed_ip_is_blocked() {
+    // Arrange
+    let dir = tempfile::tempdir().unwrap();
+    let list_file = dir.path().join("bad_ips.txt");
+    std::fs::write(&list_file, "198.51.100.0/24\n").unwrap();
+    let device = device(&list_file, IpReputationAction::Block);
+    let mut ctx = ctx_from("198.51.100.23");
+
+    // Act
+    let result = device.on_request_async(&mut ctx).await;
+
+    // Assert
+    let DeviceResult::Respond(resp) = result else {
+        panic!("expected the request to be blocked, got {result:?}");
+    };
+    assert_eq!(resp.status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn unlisted_ip_continues() {
+    // Arrange
+    let dir = tempfile::tempdir().unwrap();
+    let list_file = dir.path().join("bad_ips.txt");
+    std::fs::write(&list_file, "198.51.100.0/24\n").unwrap();
+    let device = device(&list_file, IpReputationAction::Block);
+    let mut ctx = ctx_from("192.0.2.1");
+
+    // Act
+    let result = device.on_request_async(&mut ctx).await;
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+}
+
+#[tokio::test]
+async fn tag_action_sends_verdict_upstream() {
+    // Arrange
+    let dir = tempfile::tempdir().unwrap();
+    let list_file = dir.path().join("bad_ips.txt");
+    std::fs::write(&list_file, "203.0.113.7\n").unwrap();
+    let device = device(&list_file, IpReputationAction::Tag);
+    let mut bad = ctx_from("203.0.113.7");
+    let mut clean = ctx_from("192.0.2.1");
+
+    // Act
+    let bad_result = device.on_request_async(&mut bad).await;
+    let clean_result = device.on_request_async(&mut clean).await;
+
+    // Assert
+    assert!(matches!(bad_result, DeviceResult::Continue));
+    assert!(matches!(clean_result, DeviceResult::Continue));
+    assert_eq!(bad.upstream_headers["x-ip-reputation"], "bad");
+    assert_eq!(clean.upstream_headers["x-ip-reputation"], "clean");
+}
+
+#[test]
+fn list_reload_picks_up_changes() {
+    // Arrange
+    let dir = tempfile::tempdir().unwrap();
+    let list_file = dir.path().join("bad_ips.txt");
+    std::fs::write(&list_file, "203.0.113.7\n").unwrap();
+    let list = ReputationList::load(&list_file, Some(Duration::ZERO)).unwrap();
+    assert!(!list.contains(ip("198.51.100.23")));
+
+    // Act
+    std::fs::write(&list_file, "203.0.113.7\n198.51.100.0/24\n").unwrap();
+
+    // Assert
+    assert!(list.contains(ip("198.51.100.23")));
+    assert!(list.contains(ip("203.0.113.7")));
+}
+
+#[test]
+fn invalid_reload_keeps_previous_list() {
+    // Arrange
+    let dir = tempfile::tempdir().unwrap();
+    let list_file = dir.path().join("bad_ips.txt");
+    std::fs::write(&list_file, "203.0.113.7\n").unwrap();
+    let list = ReputationList::load(&list_file, Some(Duration::ZERO)).unwrap();
+
+    // Act
+    std::fs::write(&list_file, "203.0.113.7\nnot-an-ip\n").unwrap();
+
+    // Assert
+    assert!(list.contains(ip("203.0.113.7")));
+}
@@ -0,0 +1,261 @@
+use crate::conf::types::JwtAuthDeviceConfig;
+use crate::ctx::RequestCtx;
+use crate::device::builtin::jwt_auth::{JwtAuthDevice, JwtClaims};
+use crate::device::core::{Device, DeviceResult};
+use http::{HeaderMap, HeaderValue, Method, StatusCode, Version, header};
+use jsonwebtoken::{EncodingKey, Header};
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use std::net::Ipv4Addr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+const SECRET: &str = "test-secret";
+
+/// `SECRET` as a JWKS key without a `kid`, so it matches tokens from `token`.
+const JWKS: &str = r#"{"keys":[{"kty":"oct","k":"dGVzdC1zZWNyZXQ","alg":"HS256"}]}"#;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+fn device() -> JwtAuthDevice {
+    JwtAuthDevice::from_config(JwtAuthDeviceConfig {
+        enable: true,
+        hmac_secret: Some(SECRET.to_string()),
+        issuer: Some("https://auth.example.com".to_string()),
+        leeway_seconds: 0,
+        jwks_refresh_seconds: 300,
+        timeout_milliseconds: 1000,
+        ..Default::default()
+    })
+    .unwrap()
+}
+
+fn jwks_device(jwks_url: &str) -> JwtAuthDevice {
+    JwtAuthDevice::from_config(JwtAuthDeviceConfig {
+        enable: true,
+        jwks_url: Some(jwks_url.to_string()),
+        algorithms: vec!["HS256".to_string()],
+        issuer: Some("https://auth.example.com".to_string()),
+        leeway_seconds: 0,
+        jwks_refresh_seconds: 1,
+        timeout_milliseconds: 1000,
+        ..Default::default()
+    })
+    .unwrap()
+}
+
+/// A JWKS endpoint that serves `JWKS` once and fails every later request, and the number of
+/// requests it got.
+async fn flaky_jwks_endpoint() -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/jwks.json", listener.local_addr().unwrap());
+    let requests = Arc::new(AtomicUsize::new(0));
+
+    let counted = requests.clone();
+    tokio::spawn(async move {
+        while let Ok((mut conn, _)) = listener.accept().await {
+            let counted = counted.clone();
+            tokio::spawn(async move {
+                let mut head = Vec::new();
+                let mut buf = [0u8; 1024];
+                loop {
+                    // JWKS requests have no body, so each one ends with its head.
+                    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+                        match conn.read(&mut buf).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => head.extend_from_slice(&buf[..n]),
+                        }
+                    }
+                    head.clear();
+
+                    let response = if counted.fetch_add(1, Ordering::SeqCst) == 0 {
+                        format!(
+                            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{JWKS}",
+                            JWKS.len()
+                        )
+                    } else {
+                        "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\n\r\n".to_string()
+                    };
+                    if conn.write_all(response.as_bytes()).await.is_err() {
+                        return;
+                    }
+                }
+            });
+        }
+    });
+
+    (url, requests)
+}
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+fn token(secret: &str, exp: i64) -> String {
+    let claims = json!({
+        "sub": "user-42",
+        "iss": "https://auth.example.com",
+        "exp": exp,
+    });
+    jsonwebtoken::encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(secret.as_bytes()),
+    )
+    .unwrap()
+}
+
+fn ctx(authorization: Option<String>) -> RequestCtx {
+    let mut headers = HeaderMap::new();
+    if let Some(authorization) = authorization {
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(&authorization).unwrap(),
+        );
+    }
+
+    let mut ctx = RequestCtx::empty();
+    ctx.hydrate(
+        &"/api".parse().unwrap(),
+        &Method::GET,
+        &headers,
+        &Version::HTTP_11,
+        false,
+        Ipv4Addr::LOCALHOST.into(),
+    )
+    .unwrap();
+    ctx
+}
+
+fn assert_unauthorized(result: DeviceResult, challenge: &str) {
+    match result {
+        DeviceResult::Respond(resp) => {
+            assert_eq!(resp.status, StatusCode::UNAUTHORIZED);
+            assert_eq!(resp.headers[header::WWW_AUTHENTICATE], challenge);
+        }
+        _ => panic!("expected a 401 response"),
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[tokio::test]
+async fn valid_token_attaches_claims() {
+    // Arrange
+    let device = device();
+    let mut ctx = ctx(Some(format!("Bearer {}", token(SECRET, now() + 3600))));
+
+    // Act
+    let result = device.on_request_async(&mut ctx).await;
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+    let claims = ctx.extensions.get::<JwtClaims>().unwrap();
+    assert_eq!(claims.subject(), Some("user-42"));
+}
+
+#[tokio::test]
+async fn expired_token_is_rejected() {
+    // Arrange
+    let device = device();
+    let mut ctx = ctx(Some(format!("Bearer {}", token(SECRET, now() - 3600))));
+
+    // Act
+    let result = device.on_request_async(&mut ctx).await;
+
+    // Assert
+    assert_unauthorized(result, r#"Bearer error="invalid_token""#);
+    assert!(ctx.extensions.get::<JwtClaims>().is_none());
+}
+
+#[tokio::test]
+async fn wrong_signature_is_rejected() {
+    // Arrange
+    let device = device();
+    let mut ctx = ctx(Some(format!(
+        "Bearer {}",
+        token("other-secret", now() + 3600)
+    )));
+
+    // Act
+    let result = device.on_request_async(&mut ctx).await;
+
+    // Assert
+    assert_unauthorized(result, r#"Bearer error="invalid_token""#);
+}
+
+#[tokio::test]
+async fn missing_authorization_header_is_rejected() {
+    // Arrange
+    let device = device();
+    let mut ctx = ctx(None);
+
+    // Act
+    let result = device.on_request_async(&mut ctx).await;
+
+    // Assert
+    assert_unauthorized(result, "Bearer");
+}
+
+#[tokio::test]
+async fn non_bearer_authorization_is_rejected() {
+    // Arrange
+    let device = device();
+    let mut ctx = ctx(Some("Basic dXNlcjpwYXNz".to_string()));
+
+    // Act
+    let result = device.on_request_async(&mut ctx).await;
+
+    // Assert
+    assert_unauthorized(result, "Bearer");
+}
+
+#[tokio::test]
+async fn wrong_issuer_is_rejected() {
+    // Arrange
+    let device = JwtAuthDevice::from_config(JwtAuthDeviceConfig {
+        enable: true,
+        hmac_secret: Some(SECRET.to_string()),
+        issuer: Some("https://other.example.com".to_string()),
+        jwks_refresh_seconds: 300,
+        timeout_milliseconds: 1000,
+        ..Default::default()
+    })
+    .unwrap();
+    let mut ctx = ctx(Some(format!("Bearer {}", token(SECRET, now() + 3600))));
+
+    // Act
+    let result = device.on_request_async(&mut ctx).await;
+
+    // Assert
+    assert_unauthorized(result, r#"Bearer error="invalid_token""#);
+}
+
+#[tokio::test]
+async fn failed_jwks_refresh_is_not_retried_on_the_next_request() {
+    // Arrange
+    let (url, requests) = flaky_jwks_endpoint().await;
+    let device = jwks_device(&url);
+    let authorization = Some(format!("Bearer {}", token(SECRET, now() + 3600)));
+    let first = device
+        .on_request_async(&mut ctx(authorization.clone()))
+        .await;
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+
+    // Act
+    let failed_refresh = device
+        .on_request_async(&mut ctx(authorization.clone()))
+        .await;
+    let next = device.on_request_async(&mut ctx(authorization)).await;
+
+    // Assert
+    assert!(matches!(first, DeviceResult::Continue));
+    assert!(matches!(failed_refresh, DeviceResult::Continue));
+    assert!(matches!(next, DeviceResult::Continue));
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,140 @@
+use crate::conf::types::{LuaDeviceConfig, LuaDeviceSpec};
+use crate::ctx::{RequestCtx, ResponseCtx};
+use crate::device::builtin::lua::LuaDevice;
+use crate::device::core::{Device, DeviceResult};
+use http::{HeaderMap, Method, StatusCode, Version};
+use pretty_assertions::assert_eq;
+use std::io::Write;
+use std::net::Ipv4Addr;
+use tempfile::NamedTempFile;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+/// A device running `script`, with the default limits.
+fn device(script: &str) -> LuaDevice {
+    let mut file = NamedTempFile::new().unwrap();
+    file.write_all(script.as_bytes()).unwrap();
+
+    LuaDevice::from_config(LuaDeviceConfig::from(LuaDeviceSpec {
+        enable: true,
+        path: file.path().to_path_buf(),
+        ..Default::default()
+    }))
+    .unwrap()
+}
+
+fn ctx_for(path: &str) -> RequestCtx {
+    let mut ctx = RequestCtx::empty();
+    ctx.hydrate(
+        &path.parse().unwrap(),
+        &Method::GET,
+        &HeaderMap::new(),
+        &Version::HTTP_11,
+        false,
+        Ipv4Addr::LOCALHOST.into(),
+    )
+    .unwrap();
+    ctx
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn script_rejects_a_path() {
+    // Arrange
+    let device = device(
+        r#"
+        function on_request(req)
+          if req.path:find("^/admin") then
+            return { status = 403, body = "forbidden" }
+          end
+        end
+        "#,
+    );
+
+    // Act
+    let rejected = device.on_request(&mut ctx_for("/admin/users"));
+    let allowed = device.on_request(&mut ctx_for("/users"));
+
+    // Assert
+    let DeviceResult::Respond(resp) = rejected else {
+        panic!("expected a response");
+    };
+    assert_eq!(resp.status, StatusCode::FORBIDDEN);
+    assert_eq!(resp.body, b"forbidden");
+    assert_eq!(
+        resp.headers.get("content-type").unwrap(),
+        "text/plain; charset=utf-8"
+    );
+    assert!(matches!(allowed, DeviceResult::Continue));
+}
+
+#[test]
+fn script_adds_headers() {
+    // Arrange
+    let device = device(
+        r#"
+        function on_request(req)
+          req.headers["x-scripted"] = req.method .. " " .. req.path
+        end
+
+        function on_response(res)
+          res.headers["X-Served-By"] = "lua"
+        end
+        "#,
+    );
+    let mut ctx = ctx_for("/users");
+    let mut resp = ResponseCtx::new(None, StatusCode::OK, HeaderMap::new(), Vec::new());
+
+    // Act
+    let request_result = device.on_request(&mut ctx);
+    let response_result = device.after_proxy(&mut resp);
+
+    // Assert
+    assert!(matches!(request_result, DeviceResult::Continue));
+    assert!(matches!(response_result, DeviceResult::Continue));
+    assert_eq!(ctx.headers().get("x-scripted").unwrap(), "GET /users");
+    assert_eq!(resp.headers.get("x-served-by").unwrap(), "lua");
+    assert_eq!(resp.status, StatusCode::OK);
+}
+
+#[test]
+fn script_over_its_instruction_budget_is_reported() {
+    // Arrange
+    let device = device(
+        r#"
+        function on_request(req)
+          while true do end
+        end
+        "#,
+    );
+
+    // Act
+    let result = device.on_request(&mut ctx_for("/"));
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Error(e) if !e.fatal));
+}
+
+#[test]
+fn sandbox_has_no_io_or_os_libraries() {
+    // Arrange
+    let device = device(
+        r#"
+        function on_request(req)
+          return { status = 200, body = type(io) .. " " .. type(os) .. " " .. type(load) }
+        end
+        "#,
+    );
+
+    // Act
+    let result = device.on_request(&mut ctx_for("/"));
+
+    // Assert
+    let DeviceResult::Respond(resp) = result else {
+        panic!("expected a response");
+    };
+    assert_eq!(resp.body, b"nil nil nil");
+}
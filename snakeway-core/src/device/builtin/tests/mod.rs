@@ -0,0 +1,21 @@
+mod body_digest_tests;
+mod cors_tests;
+mod deprecation_tests;
+mod error_page_tests;
+mod experiment_tests;
+mod header_rewrite_tests;
+mod idempotency_tests;
+mod identity_tests;
+mod ip_reputation_tests;
+mod jwt_auth_tests;
+#[cfg(feature = "lua")]
+mod lua_tests;
+mod openapi_tests;
+mod query_rewrite_tests;
+mod quota_tests;
+mod rate_limit_tests;
+mod required_headers_tests;
+mod response_headers_tests;
+mod status_remap_tests;
+mod structured_logging_tests;
+mod upstream_group_tests;
@@ -0,0 +1,379 @@
+use crate::conf::types::OpenApiDeviceConfig;
+use crate::ctx::RequestCtx;
+use crate::device::builtin::openapi::OpenApiDevice;
+use crate::device::core::{Device, DeviceResult};
+use bytes::Bytes;
+use http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode, Version, header};
+use pretty_assertions::assert_eq;
+use serde_json::{Value, json};
+use std::net::Ipv4Addr;
+
+const SPEC: &str = r##"
+openapi: 3.0.3
+info:
+  title: Pets
+  version: "1"
+paths:
+  /pets:
+    get:
+      parameters:
+        - name: limit
+          in: query
+          schema:
+            type: integer
+            minimum: 1
+            maximum: 100
+    post:
+      requestBody:
+        required: true
+        content:
+          application/json:
+            schema:
+              $ref: "#/components/schemas/NewPet"
+  /pets/{id}:
+    parameters:
+      - name: id
+        in: path
+        schema:
+          type: integer
+    get:
+      parameters:
+        - name: X-Tenant
+          in: header
+          required: true
+          schema:
+            type: string
+  /pets/mine:
+    get: {}
+components:
+  schemas:
+    NewPet:
+      type: object
+      required: [name]
+      additionalProperties: false
+      properties:
+        name:
+          type: string
+          minLength: 1
+        tags:
+          type: array
+          items:
+            type: string
+"##;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+fn device(reject_unknown_operations: bool) -> OpenApiDevice {
+    let dir = tempfile::tempdir().unwrap();
+    let spec_path = dir.path().join("openapi.yaml");
+    std::fs::write(&spec_path, SPEC).unwrap();
+
+    OpenApiDevice::from_config(OpenApiDeviceConfig {
+        enable: true,
+        priority: 0,
+        routes: vec![],
+        spec_path,
+        max_body_bytes: 1024,
+        reject_unknown_operations,
+    })
+    .unwrap()
+}
+
+fn ctx(method: Method, uri: &str, headers: &[(&str, &str)]) -> RequestCtx {
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+        map.insert(
+            HeaderName::from_bytes(name.as_bytes()).unwrap(),
+            HeaderValue::from_str(value).unwrap(),
+        );
+    }
+
+    let mut ctx = RequestCtx::empty();
+    ctx.hydrate(
+        &uri.parse().unwrap(),
+        &method,
+        &map,
+        &Version::HTTP_11,
+        false,
+        Ipv4Addr::LOCALHOST.into(),
+    )
+    .unwrap();
+    ctx
+}
+
+fn json_ctx(body: &str) -> RequestCtx {
+    let content_length = body.len().to_string();
+    ctx(
+        Method::POST,
+        "/pets",
+        &[
+            ("content-type", "application/json"),
+            ("content-length", &content_length),
+        ],
+    )
+}
+
+/// Run the request hooks the gateway runs for a request with a body.
+fn send(device: &OpenApiDevice, ctx: &mut RequestCtx, body: &'static str) -> DeviceResult {
+    let result = device.on_request(ctx);
+    if !matches!(result, DeviceResult::Continue) {
+        return result;
+    }
+
+    let mut chunk = Some(Bytes::from_static(body.as_bytes()));
+    device.on_stream_request_body(ctx, &mut chunk, true)
+}
+
+/// The status and sorted violation locations of a rejected request.
+fn rejection(result: DeviceResult) -> (StatusCode, Vec<String>) {
+    let DeviceResult::Respond(resp) = result else {
+        panic!("expected a response");
+    };
+    let body: Value = serde_json::from_slice(&resp.body).unwrap();
+    let mut locations: Vec<String> = body["violations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| v["location"].as_str().unwrap().to_string())
+        .collect();
+    locations.sort();
+    (resp.status, locations)
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn conforming_query_is_allowed() {
+    // Arrange
+    let device = device(false);
+    let mut ctx = ctx(Method::GET, "/pets?limit=10", &[]);
+
+    // Act
+    let result = device.on_request(&mut ctx);
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+}
+
+#[test]
+fn query_of_wrong_type_is_rejected() {
+    // Arrange
+    let device = device(false);
+    let mut ctx = ctx(Method::GET, "/pets?limit=ten", &[]);
+
+    // Act
+    let result = device.on_request(&mut ctx);
+
+    // Assert
+    assert_eq!(
+        rejection(result),
+        (StatusCode::BAD_REQUEST, vec!["query.limit".to_string()])
+    );
+}
+
+#[test]
+fn query_out_of_range_is_rejected() {
+    // Arrange
+    let device = device(false);
+    let mut ctx = ctx(Method::GET, "/pets?limit=500", &[]);
+
+    // Act
+    let result = device.on_request(&mut ctx);
+
+    // Assert
+    assert_eq!(
+        rejection(result),
+        (StatusCode::BAD_REQUEST, vec!["query.limit".to_string()])
+    );
+}
+
+#[test]
+fn path_and_header_parameters_are_validated() {
+    // Arrange
+    let device = device(false);
+    let mut ctx = ctx(Method::GET, "/pets/abc", &[]);
+
+    // Act
+    let result = device.on_request(&mut ctx);
+
+    // Assert
+    assert_eq!(
+        rejection(result),
+        (
+            StatusCode::BAD_REQUEST,
+            vec!["header.X-Tenant".to_string(), "path.id".to_string()]
+        )
+    );
+}
+
+#[test]
+fn conforming_path_and_header_are_allowed() {
+    // Arrange
+    let device = device(false);
+    let mut ctx = ctx(Method::GET, "/pets/42", &[("x-tenant", "acme")]);
+
+    // Act
+    let result = device.on_request(&mut ctx);
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+}
+
+#[test]
+fn literal_path_is_preferred_over_template() {
+    // Arrange
+    let device = device(false);
+    let mut ctx = ctx(Method::GET, "/pets/mine", &[]);
+
+    // Act
+    let result = device.on_request(&mut ctx);
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+}
+
+#[test]
+fn conforming_body_is_forwarded() {
+    // Arrange
+    let device = device(false);
+    let body = r#"{"name": "Rex", "tags": ["good"]}"#;
+    let mut ctx = json_ctx(body);
+
+    // Act
+    let result = device.on_request(&mut ctx);
+    let mut chunk = Some(Bytes::from_static(body.as_bytes()));
+    let body_result = device.on_stream_request_body(&mut ctx, &mut chunk, true);
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+    assert!(matches!(body_result, DeviceResult::Continue));
+    assert_eq!(chunk, Some(Bytes::from_static(body.as_bytes())));
+}
+
+#[test]
+fn non_conforming_body_is_rejected_with_every_violation() {
+    // Arrange
+    let device = device(false);
+    let body = r#"{"tags": [1], "owner": "me"}"#;
+    let mut ctx = json_ctx(body);
+
+    // Act
+    let result = send(&device, &mut ctx, body);
+
+    // Assert
+    assert_eq!(
+        rejection(result),
+        (
+            StatusCode::BAD_REQUEST,
+            vec![
+                "body.name".to_string(),
+                "body.owner".to_string(),
+                "body.tags[0]".to_string(),
+            ]
+        )
+    );
+}
+
+#[test]
+fn invalid_json_body_is_rejected() {
+    // Arrange
+    let device = device(false);
+    let body = "{not json";
+    let mut ctx = json_ctx(body);
+
+    // Act
+    let result = send(&device, &mut ctx, body);
+
+    // Assert
+    assert_eq!(
+        rejection(result),
+        (StatusCode::BAD_REQUEST, vec!["body".to_string()])
+    );
+}
+
+#[test]
+fn missing_required_body_is_rejected() {
+    // Arrange
+    let device = device(false);
+    let mut ctx = ctx(Method::POST, "/pets", &[]);
+
+    // Act
+    let result = device.on_request(&mut ctx);
+
+    // Assert
+    assert_eq!(
+        rejection(result),
+        (StatusCode::BAD_REQUEST, vec!["body".to_string()])
+    );
+}
+
+#[test]
+fn body_over_the_size_cap_is_rejected() {
+    // Arrange
+    let device = device(false);
+    let body = format!(r#"{{"name": "{}"}}"#, "x".repeat(2048));
+    let mut ctx = json_ctx(&body);
+
+    // Act
+    let result = device.on_request(&mut ctx);
+
+    // Assert
+    assert_eq!(
+        rejection(result),
+        (StatusCode::PAYLOAD_TOO_LARGE, vec!["body".to_string()])
+    );
+}
+
+#[test]
+fn unknown_operation_passes_through_by_default() {
+    // Arrange
+    let device = device(false);
+    let mut ctx = ctx(Method::GET, "/owners", &[]);
+
+    // Act
+    let result = device.on_request(&mut ctx);
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+}
+
+#[test]
+fn unknown_operation_is_rejected_when_configured() {
+    // Arrange
+    let device = device(true);
+    let mut not_found = ctx(Method::GET, "/owners", &[]);
+    let mut not_allowed = ctx(Method::DELETE, "/pets", &[]);
+
+    // Act
+    let not_found = device.on_request(&mut not_found);
+    let not_allowed = device.on_request(&mut not_allowed);
+
+    // Assert
+    assert_eq!(rejection(not_found).0, StatusCode::NOT_FOUND);
+    assert_eq!(rejection(not_allowed).0, StatusCode::METHOD_NOT_ALLOWED);
+}
+
+#[test]
+fn violations_are_returned_as_json() {
+    // Arrange
+    let device = device(false);
+    let mut ctx = ctx(Method::GET, "/pets?limit=0", &[]);
+
+    // Act
+    let DeviceResult::Respond(resp) = device.on_request(&mut ctx) else {
+        panic!("expected a response");
+    };
+
+    // Assert
+    assert_eq!(resp.headers[header::CONTENT_TYPE], "application/json");
+    let body: Value = serde_json::from_slice(&resp.body).unwrap();
+    assert_eq!(
+        body,
+        json!({
+            "error": "request does not match the API schema",
+            "violations": [{"location": "query.limit", "message": "must be at least 1"}],
+        })
+    );
+}
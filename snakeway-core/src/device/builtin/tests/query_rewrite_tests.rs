@@ -0,0 +1,217 @@
+use crate::conf::types::{QueryOperation, QueryRewriteDeviceConfig};
+use crate::ctx::RequestCtx;
+use crate::device::builtin::query_rewrite::QueryRewriteDevice;
+use crate::device::core::{Device, DeviceResult};
+use http::{HeaderMap, Method, Version};
+use pretty_assertions::assert_eq;
+use std::net::Ipv4Addr;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+fn device(operations: Vec<QueryOperation>) -> QueryRewriteDevice {
+    QueryRewriteDevice::from_config(QueryRewriteDeviceConfig {
+        enable: true,
+        operations,
+        ..Default::default()
+    })
+    .unwrap()
+}
+
+fn add_if_missing(name: &str, value: &str) -> QueryOperation {
+    QueryOperation::AddIfMissing {
+        name: name.to_string(),
+        value: value.to_string(),
+    }
+}
+
+fn set(name: &str, value: &str) -> QueryOperation {
+    QueryOperation::Set {
+        name: name.to_string(),
+        value: value.to_string(),
+    }
+}
+
+fn remove(name: &str) -> QueryOperation {
+    QueryOperation::Remove {
+        name: name.to_string(),
+    }
+}
+
+fn rename(name: &str, to: &str) -> QueryOperation {
+    QueryOperation::Rename {
+        name: name.to_string(),
+        to: to.to_string(),
+    }
+}
+
+fn ctx(uri: &str) -> RequestCtx {
+    let mut ctx = RequestCtx::empty();
+    ctx.hydrate(
+        &uri.parse().unwrap(),
+        &Method::GET,
+        &HeaderMap::new(),
+        &Version::HTTP_11,
+        false,
+        Ipv4Addr::LOCALHOST.into(),
+    )
+    .unwrap();
+    ctx
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn add_if_missing_adds_absent_parameter() {
+    // Arrange
+    let device = device(vec![add_if_missing("format", "json")]);
+
+    // Act
+    let query = device.rewrite("q=rust");
+
+    // Assert
+    assert_eq!(query.as_deref(), Some("q=rust&format=json"));
+}
+
+#[test]
+fn add_if_missing_keeps_present_parameter() {
+    // Arrange
+    let device = device(vec![add_if_missing("format", "json")]);
+
+    // Act
+    let query = device.rewrite("format=xml&q=rust");
+
+    // Assert
+    assert_eq!(query, None);
+}
+
+#[test]
+fn set_replaces_every_value_in_place() {
+    // Arrange
+    let device = device(vec![set("page", "1")]);
+
+    // Act
+    let query = device.rewrite("page=3&q=rust&page=4");
+
+    // Assert
+    assert_eq!(query.as_deref(), Some("page=1&q=rust"));
+}
+
+#[test]
+fn set_adds_absent_parameter() {
+    // Arrange
+    let device = device(vec![set("page", "1")]);
+
+    // Act
+    let query = device.rewrite("q=rust");
+
+    // Assert
+    assert_eq!(query.as_deref(), Some("q=rust&page=1"));
+}
+
+#[test]
+fn remove_drops_every_value() {
+    // Arrange
+    let device = device(vec![remove("utm_source"), remove("utm_medium")]);
+
+    // Act
+    let query = device.rewrite("utm_source=a&q=rust&utm_source=b&utm_medium=email");
+
+    // Assert
+    assert_eq!(query.as_deref(), Some("q=rust"));
+}
+
+#[test]
+fn rename_keeps_values_and_order() {
+    // Arrange
+    let device = device(vec![rename("q", "query")]);
+
+    // Act
+    let query = device.rewrite("q=a&page=2&q=b");
+
+    // Assert
+    assert_eq!(query.as_deref(), Some("query=a&page=2&query=b"));
+}
+
+#[test]
+fn operations_run_in_order() {
+    // Arrange
+    let device = device(vec![
+        rename("q", "query"),
+        add_if_missing("q", "default"),
+        remove("query"),
+    ]);
+
+    // Act
+    let query = device.rewrite("q=rust");
+
+    // Assert
+    assert_eq!(query.as_deref(), Some("q=default"));
+}
+
+#[test]
+fn config_values_are_encoded() {
+    // Arrange
+    let device = device(vec![set("filter", "a b&c")]);
+
+    // Act
+    let query = device.rewrite("");
+
+    // Assert
+    assert_eq!(query.as_deref(), Some("filter=a%20b%26c"));
+}
+
+#[test]
+fn encoded_names_match_decoded_config_names() {
+    // Arrange
+    let device = device(vec![remove("utm_source")]);
+
+    // Act
+    let query = device.rewrite("utm%5Fsource=a&q=rust");
+
+    // Assert
+    assert_eq!(query.as_deref(), Some("q=rust"));
+}
+
+#[test]
+fn before_proxy_rewrites_the_upstream_query() {
+    // Arrange
+    let device = device(vec![remove("utm_source"), add_if_missing("format", "json")]);
+    let mut ctx = ctx("/api?utm_source=ad&q=rust");
+
+    // Act
+    let result = device.before_proxy(&mut ctx);
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+    assert_eq!(ctx.upstream_uri(), "/api?q=rust&format=json");
+}
+
+#[test]
+fn before_proxy_builds_on_an_earlier_rewrite() {
+    // Arrange
+    let device = device(vec![rename("q", "query")]);
+    let mut ctx = ctx("/api?q=rust");
+    ctx.upstream_query = Some("q=go".to_string());
+
+    // Act
+    device.before_proxy(&mut ctx);
+
+    // Assert
+    assert_eq!(ctx.upstream_query(), "query=go");
+}
+
+#[test]
+fn before_proxy_leaves_unchanged_query_alone() {
+    // Arrange
+    let device = device(vec![remove("utm_source")]);
+    let mut ctx = ctx("/api?q=rust");
+
+    // Act
+    device.before_proxy(&mut ctx);
+
+    // Assert
+    assert_eq!(ctx.upstream_query, None);
+    assert_eq!(ctx.upstream_uri(), "/api?q=rust");
+}
@@ -0,0 +1,67 @@
+use crate::device::builtin::quota::QuotaDecision;
+use http::{HeaderMap, HeaderValue, StatusCode};
+use pretty_assertions::assert_eq;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+fn quota_headers() -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert("x-ratelimit-limit", HeaderValue::from_static("100"));
+    headers.insert("x-ratelimit-remaining", HeaderValue::from_static("42"));
+    headers.insert("content-type", HeaderValue::from_static("text/plain"));
+    headers
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn success_status_allows_request() {
+    // Arrange
+    let headers = quota_headers();
+
+    // Act
+    let decision = QuotaDecision::from_response(StatusCode::OK, &headers).unwrap();
+
+    // Assert
+    assert!(decision.allowed);
+}
+
+#[test]
+fn too_many_requests_status_denies_request() {
+    // Arrange
+    let headers = quota_headers();
+
+    // Act
+    let decision = QuotaDecision::from_response(StatusCode::TOO_MANY_REQUESTS, &headers).unwrap();
+
+    // Assert
+    assert!(!decision.allowed);
+}
+
+#[test]
+fn only_rate_limit_headers_are_kept() {
+    // Arrange
+    let headers = quota_headers();
+
+    // Act
+    let decision = QuotaDecision::from_response(StatusCode::OK, &headers).unwrap();
+
+    // Assert
+    assert_eq!(decision.headers.len(), 2);
+    assert_eq!(decision.headers["x-ratelimit-remaining"], "42");
+    assert!(!decision.headers.contains_key("content-type"));
+}
+
+#[test]
+fn unexpected_status_is_not_a_decision() {
+    // Arrange
+    let headers = quota_headers();
+
+    // Act
+    let decision = QuotaDecision::from_response(StatusCode::BAD_GATEWAY, &headers);
+
+    // Assert
+    assert_eq!(decision, None);
+}
@@ -0,0 +1,201 @@
+use crate::conf::types::{RateLimitDeviceConfig, RateLimitKey};
+use crate::ctx::RequestCtx;
+use crate::device::builtin::rate_limit::{RateLimitDevice, TokenBucket};
+use crate::device::core::{Device, DeviceResult};
+use crate::enrichment::user_agent::ClientIdentity;
+use http::{HeaderMap, HeaderValue, Method, StatusCode, Version, header};
+use pretty_assertions::assert_eq;
+use std::net::Ipv4Addr;
+use std::time::{Duration, Instant};
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+fn device(requests_per_second: u32, burst: u32, key: RateLimitKey) -> RateLimitDevice {
+    RateLimitDevice::from_config(RateLimitDeviceConfig {
+        enable: true,
+        priority: 0,
+        routes: vec![],
+        requests_per_second,
+        burst,
+        key,
+    })
+    .unwrap()
+}
+
+fn ctx(path: &str, api_key: Option<&'static str>) -> RequestCtx {
+    let mut headers = HeaderMap::new();
+    if let Some(api_key) = api_key {
+        headers.insert("x-api-key", HeaderValue::from_static(api_key));
+    }
+
+    let mut ctx = RequestCtx::empty();
+    ctx.hydrate(
+        &path.parse().unwrap(),
+        &Method::GET,
+        &headers,
+        &Version::HTTP_11,
+        false,
+        Ipv4Addr::LOCALHOST.into(),
+    )
+    .unwrap();
+    ctx
+}
+
+fn is_limited(result: &DeviceResult) -> bool {
+    matches!(result, DeviceResult::Respond(resp) if resp.status == StatusCode::TOO_MANY_REQUESTS)
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn bucket_allows_burst_then_refuses() {
+    // Arrange
+    let now = Instant::now();
+    let mut bucket = TokenBucket::full(3.0, now);
+
+    // Act
+    let taken: Vec<bool> = (0..4).map(|_| bucket.try_take(1.0, 3.0, now)).collect();
+
+    // Assert
+    assert_eq!(taken, vec![true, true, true, false]);
+}
+
+#[test]
+fn bucket_refills_at_rate() {
+    // Arrange
+    let now = Instant::now();
+    let mut bucket = TokenBucket::full(1.0, now);
+    assert!(bucket.try_take(2.0, 1.0, now));
+
+    // Act
+    let too_soon = bucket.try_take(2.0, 1.0, now + Duration::from_millis(400));
+    let refilled = bucket.try_take(2.0, 1.0, now + Duration::from_millis(500));
+
+    // Assert
+    assert!(!too_soon);
+    assert!(refilled);
+}
+
+#[test]
+fn bucket_refill_is_capped_at_burst() {
+    // Arrange
+    let now = Instant::now();
+    let mut bucket = TokenBucket::full(2.0, now);
+    let later = now + Duration::from_secs(60);
+
+    // Act
+    let taken: Vec<bool> = (0..3).map(|_| bucket.try_take(10.0, 2.0, later)).collect();
+
+    // Assert
+    assert_eq!(taken, vec![true, true, false]);
+}
+
+#[test]
+fn retry_after_is_time_until_next_token() {
+    // Arrange
+    let now = Instant::now();
+    let mut bucket = TokenBucket::full(1.0, now);
+    bucket.try_take(0.25, 1.0, now);
+
+    // Act
+    let retry_after = bucket.retry_after_seconds(0.25);
+
+    // Assert
+    assert_eq!(retry_after, 4);
+}
+
+#[test]
+fn request_over_limit_is_rejected_with_retry_after() {
+    // Arrange
+    let device = device(1, 2, RateLimitKey::ClientIp);
+
+    // Act
+    let results: Vec<DeviceResult> = (0..3)
+        .map(|_| device.on_request(&mut ctx("/", None)))
+        .collect();
+
+    // Assert
+    assert!(matches!(results[0], DeviceResult::Continue));
+    assert!(matches!(results[1], DeviceResult::Continue));
+    let DeviceResult::Respond(resp) = &results[2] else {
+        panic!("expected a 429 response");
+    };
+    assert_eq!(resp.status, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(resp.headers.get(header::RETRY_AFTER).unwrap(), "1");
+}
+
+#[test]
+fn client_ip_key_uses_resolved_identity() {
+    // Arrange
+    let device = device(1, 1, RateLimitKey::ClientIp);
+    let mut first = ctx("/", None);
+    let mut second = ctx("/", None);
+    second.extensions.insert(ClientIdentity {
+        ip: "203.0.113.7".parse().unwrap(),
+        proxy_chain: vec![],
+        geo: None,
+        ua: None,
+    });
+
+    // Act
+    let first = device.on_request(&mut first);
+    let second = device.on_request(&mut second);
+
+    // Assert
+    assert!(!is_limited(&first));
+    assert!(!is_limited(&second));
+}
+
+#[test]
+fn header_key_limits_per_value_and_skips_requests_without_it() {
+    // Arrange
+    let device = device(1, 1, RateLimitKey::Header("X-Api-Key".to_string()));
+
+    // Act
+    let a1 = device.on_request(&mut ctx("/", Some("a")));
+    let a2 = device.on_request(&mut ctx("/", Some("a")));
+    let b1 = device.on_request(&mut ctx("/", Some("b")));
+    let anonymous: Vec<DeviceResult> = (0..3)
+        .map(|_| device.on_request(&mut ctx("/", None)))
+        .collect();
+
+    // Assert
+    assert!(!is_limited(&a1));
+    assert!(is_limited(&a2));
+    assert!(!is_limited(&b1));
+    assert!(!anonymous.iter().any(is_limited));
+}
+
+#[test]
+fn path_key_limits_per_path() {
+    // Arrange
+    let device = device(1, 1, RateLimitKey::Path);
+
+    // Act
+    let login1 = device.on_request(&mut ctx("/login", None));
+    let login2 = device.on_request(&mut ctx("/login", None));
+    let search = device.on_request(&mut ctx("/search", None));
+
+    // Assert
+    assert!(!is_limited(&login1));
+    assert!(is_limited(&login2));
+    assert!(!is_limited(&search));
+}
+
+#[test]
+fn reset_refills_every_bucket() {
+    // Arrange
+    let device = device(1, 1, RateLimitKey::Path);
+    device.on_request(&mut ctx("/a", None));
+    assert!(is_limited(&device.on_request(&mut ctx("/a", None))));
+
+    // Act
+    let reset = device.reset();
+    let result = device.on_request(&mut ctx("/a", None));
+
+    // Assert
+    assert!(reset);
+    assert!(!is_limited(&result));
+}
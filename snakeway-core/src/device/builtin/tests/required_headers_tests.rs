@@ -0,0 +1,114 @@
+use crate::conf::types::{MissingHeaderAction, RequiredHeaderConfig, RequiredHeadersDeviceConfig};
+use crate::ctx::RequestCtx;
+use crate::device::builtin::required_headers::RequiredHeadersDevice;
+use crate::device::core::{Device, DeviceResult};
+use http::{HeaderMap, HeaderValue, Method, StatusCode, Version};
+use pretty_assertions::assert_eq;
+use std::net::Ipv4Addr;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+fn device(on_missing: MissingHeaderAction, value: Option<&str>) -> RequiredHeadersDevice {
+    RequiredHeadersDevice::from_config(RequiredHeadersDeviceConfig {
+        enable: true,
+        priority: 0,
+        routes: vec![],
+        headers: vec![RequiredHeaderConfig {
+            name: "X-Correlation-Id".to_string(),
+            on_missing,
+            value: value.map(str::to_string),
+        }],
+    })
+    .unwrap()
+}
+
+fn ctx_with_correlation_id(id: Option<&'static str>) -> RequestCtx {
+    let mut headers = HeaderMap::new();
+    if let Some(id) = id {
+        headers.insert("x-correlation-id", HeaderValue::from_static(id));
+    }
+
+    let mut ctx = RequestCtx::empty();
+    ctx.hydrate(
+        &"/".parse().unwrap(),
+        &Method::GET,
+        &headers,
+        &Version::HTTP_11,
+        false,
+        Ipv4Addr::LOCALHOST.into(),
+    )
+    .unwrap();
+    ctx
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn missing_header_is_rejected() {
+    // Arrange
+    let device = device(MissingHeaderAction::Reject, None);
+    let mut ctx = ctx_with_correlation_id(None);
+
+    // Act
+    let result = device.on_request(&mut ctx);
+
+    // Assert
+    let DeviceResult::Respond(resp) = result else {
+        panic!("expected a rejection, got {result:?}");
+    };
+    assert_eq!(resp.status, StatusCode::BAD_REQUEST);
+    assert!(ctx.upstream_headers.is_empty());
+}
+
+#[test]
+fn present_header_is_accepted() {
+    // Arrange
+    let device = device(MissingHeaderAction::Reject, None);
+    let mut ctx = ctx_with_correlation_id(Some("abc"));
+
+    // Act
+    let result = device.on_request(&mut ctx);
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+    assert!(ctx.upstream_headers.is_empty());
+}
+
+#[test]
+fn missing_header_is_populated_from_request_id() {
+    // Arrange
+    let device = device(MissingHeaderAction::Populate, Some("snakeway-{request_id}"));
+    let mut ctx = ctx_with_correlation_id(None);
+    let expected = format!("snakeway-{}", ctx.request_id().unwrap());
+
+    // Act
+    let result = device.on_request(&mut ctx);
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+    assert_eq!(
+        ctx.upstream_headers.get("x-correlation-id").unwrap(),
+        expected.as_str()
+    );
+    assert_eq!(
+        ctx.headers().get("x-correlation-id").unwrap(),
+        expected.as_str()
+    );
+}
+
+#[test]
+fn present_header_is_not_overwritten() {
+    // Arrange
+    let device = device(MissingHeaderAction::Populate, Some("{request_id}"));
+    let mut ctx = ctx_with_correlation_id(Some("abc"));
+
+    // Act
+    let result = device.on_request(&mut ctx);
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+    assert!(ctx.upstream_headers.is_empty());
+    assert_eq!(ctx.headers().get("x-correlation-id").unwrap(), "abc");
+}
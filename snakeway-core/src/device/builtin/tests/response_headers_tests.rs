@@ -0,0 +1,76 @@
+use crate::conf::types::{ResponseHeaderConfig, ResponseHeadersDeviceConfig};
+use crate::ctx::{RequestCtx, RequestId};
+use crate::device::builtin::response_headers::ResponseHeadersDevice;
+use crate::device::core::{Device, DeviceResult};
+use crate::route::RouteId;
+use pretty_assertions::assert_eq;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+fn device(name: &str, value: &str) -> ResponseHeadersDevice {
+    ResponseHeadersDevice::from_config(ResponseHeadersDeviceConfig {
+        enable: true,
+        priority: 0,
+        routes: vec![],
+        headers: vec![ResponseHeaderConfig {
+            name: name.to_string(),
+            value: value.to_string(),
+        }],
+    })
+    .unwrap()
+}
+
+fn routed_ctx() -> RequestCtx {
+    let mut ctx = RequestCtx::empty();
+    ctx.extensions.insert(RequestId::from("req-123"));
+    ctx.service = Some("api".to_string());
+    ctx.route_id = Some(RouteId::service("/api/", "api"));
+    ctx
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn request_id_is_echoed_into_response_header() {
+    // Arrange
+    let device = device("X-Request-Id", "{request_id}");
+    let mut ctx = routed_ctx();
+
+    // Act
+    let result = device.before_proxy(&mut ctx);
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+    assert_eq!(ctx.response_headers.get("x-request-id").unwrap(), "req-123");
+}
+
+#[test]
+fn served_by_header_names_upstream_and_route() {
+    // Arrange
+    let device = device("X-Served-By", "{upstream} via {route}");
+    let mut ctx = routed_ctx();
+
+    // Act
+    device.before_proxy(&mut ctx);
+
+    // Assert
+    assert_eq!(
+        ctx.response_headers.get("x-served-by").unwrap(),
+        "api via /api"
+    );
+}
+
+#[test]
+fn missing_request_data_renders_empty() {
+    // Arrange
+    let device = device("X-Served-By", "{upstream}");
+    let mut ctx = RequestCtx::empty();
+
+    // Act
+    device.before_proxy(&mut ctx);
+
+    // Assert
+    assert_eq!(ctx.response_headers.get("x-served-by").unwrap(), "");
+}
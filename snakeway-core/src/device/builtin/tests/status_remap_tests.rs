@@ -0,0 +1,79 @@
+use crate::conf::types::{StatusRemapConfig, StatusRemapDeviceConfig};
+use crate::ctx::ResponseCtx;
+use crate::device::builtin::status_remap::StatusRemapDevice;
+use crate::device::core::{Device, DeviceResult};
+use http::{HeaderMap, StatusCode, header};
+use pretty_assertions::assert_eq;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+fn device(body: Option<&str>) -> StatusRemapDevice {
+    StatusRemapDevice::from_config(StatusRemapDeviceConfig {
+        enable: true,
+        priority: 0,
+        routes: vec![],
+        remaps: vec![StatusRemapConfig {
+            from: 418,
+            to: 429,
+            body: body.map(str::to_string),
+        }],
+    })
+    .unwrap()
+}
+
+fn upstream_response(status: StatusCode) -> ResponseCtx {
+    ResponseCtx::new(None, status, HeaderMap::new(), Vec::new())
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn configured_status_is_remapped() {
+    // Arrange
+    let device = device(None);
+    let mut ctx = upstream_response(StatusCode::IM_A_TEAPOT);
+
+    // Act
+    let result = device.after_proxy(&mut ctx);
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+    assert_eq!(ctx.status, StatusCode::TOO_MANY_REQUESTS);
+    assert!(ctx.body.is_empty());
+}
+
+#[test]
+fn remap_with_body_replaces_body() {
+    // Arrange
+    let device = device(Some("rate limited"));
+    let mut ctx = upstream_response(StatusCode::IM_A_TEAPOT);
+
+    // Act
+    device.after_proxy(&mut ctx);
+
+    // Assert
+    assert_eq!(ctx.status, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(ctx.body, b"rate limited".to_vec());
+    assert_eq!(
+        ctx.headers.get(header::CONTENT_TYPE).unwrap(),
+        "text/plain; charset=utf-8"
+    );
+}
+
+#[test]
+fn unconfigured_status_passes_through() {
+    // Arrange
+    let device = device(Some("rate limited"));
+    let mut ctx = upstream_response(StatusCode::SERVICE_UNAVAILABLE);
+
+    // Act
+    let result = device.after_proxy(&mut ctx);
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+    assert_eq!(ctx.status, StatusCode::SERVICE_UNAVAILABLE);
+    assert!(ctx.body.is_empty());
+    assert!(ctx.headers.is_empty());
+}
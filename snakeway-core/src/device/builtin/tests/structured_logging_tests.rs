@@ -0,0 +1,110 @@
+use crate::conf::types::StructuredLoggingDeviceConfig;
+use crate::device::builtin::structured_logging::{
+    LogPhase, RouteHeaderCapture, StructuredLoggingDevice,
+};
+use crate::route::RouteId;
+use http::{HeaderMap, HeaderValue};
+use pretty_assertions::assert_eq;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+fn device() -> StructuredLoggingDevice {
+    StructuredLoggingDevice::from_config(StructuredLoggingDeviceConfig {
+        enable: true,
+        redacted_headers: vec!["x-partner-token".to_string()],
+        route_headers: vec![RouteHeaderCapture {
+            path: "/partner/".to_string(),
+            request_headers: vec!["X-Api-Version".to_string(), "X-Partner-Token".to_string()],
+            response_headers: vec!["X-Cache".to_string()],
+        }],
+        ..Default::default()
+    })
+    .unwrap()
+}
+
+fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    for (name, value) in pairs {
+        headers.insert(*name, HeaderValue::from_static(value));
+    }
+    headers
+}
+
+//-----------------------------------------------------------------------------
+// Per-route header capture
+//-----------------------------------------------------------------------------
+#[test]
+fn configured_route_captures_named_request_headers() {
+    // Arrange
+    let device = device();
+    let route = RouteId::service("/partner", "partner-api");
+    let headers = headers(&[("x-api-version", "2024-06-01"), ("x-other", "ignored")]);
+
+    // Act
+    let captured = device.route_headers_json(Some(&route), LogPhase::Request, &headers);
+
+    // Assert
+    assert_eq!(
+        captured.as_deref(),
+        Some(r#"{"x-api-version":"2024-06-01"}"#)
+    );
+}
+
+#[test]
+fn configured_route_captures_named_response_headers() {
+    // Arrange
+    let device = device();
+    let route = RouteId::service("/partner", "partner-api");
+    let headers = headers(&[("x-cache", "HIT"), ("x-api-version", "2024-06-01")]);
+
+    // Act
+    let captured = device.route_headers_json(Some(&route), LogPhase::Response, &headers);
+
+    // Assert
+    assert_eq!(captured.as_deref(), Some(r#"{"x-cache":"HIT"}"#));
+}
+
+#[test]
+fn other_routes_capture_nothing() {
+    // Arrange
+    let device = device();
+    let route = RouteId::service("/api", "api");
+    let headers = headers(&[("x-api-version", "2024-06-01")]);
+
+    // Act
+    let captured = device.route_headers_json(Some(&route), LogPhase::Request, &headers);
+
+    // Assert
+    assert_eq!(captured, None);
+}
+
+#[test]
+fn unrouted_requests_capture_nothing() {
+    // Arrange
+    let device = device();
+    let headers = headers(&[("x-api-version", "2024-06-01")]);
+
+    // Act
+    let captured = device.route_headers_json(None, LogPhase::Request, &headers);
+
+    // Assert
+    assert_eq!(captured, None);
+}
+
+#[test]
+fn captured_headers_respect_redaction() {
+    // Arrange
+    let device = device();
+    let route = RouteId::service("/partner", "partner-api");
+    let headers = headers(&[("x-partner-token", "secret")]);
+
+    // Act
+    let captured = device.route_headers_json(Some(&route), LogPhase::Request, &headers);
+
+    // Assert
+    assert_eq!(
+        captured.as_deref(),
+        Some(r#"{"x-partner-token":"<redacted>"}"#)
+    );
+}
@@ -0,0 +1,85 @@
+use crate::conf::types::UpstreamGroupDeviceConfig;
+use crate::ctx::RequestCtx;
+use crate::device::builtin::upstream_group::UpstreamGroupDevice;
+use crate::device::core::{Device, DeviceResult};
+use http::{HeaderMap, HeaderValue, Method, Version};
+use pretty_assertions::assert_eq;
+use std::net::Ipv4Addr;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+fn device() -> UpstreamGroupDevice {
+    UpstreamGroupDevice::from_config(UpstreamGroupDeviceConfig {
+        enable: true,
+        priority: 0,
+        routes: vec![],
+        header: "X-Tenant".to_string(),
+        groups: [("premium".to_string(), "premium-pool".to_string())].into(),
+    })
+    .unwrap()
+}
+
+fn ctx_with_tenant(tenant: Option<&'static str>) -> RequestCtx {
+    let mut headers = HeaderMap::new();
+    if let Some(tenant) = tenant {
+        headers.insert("x-tenant", HeaderValue::from_static(tenant));
+    }
+
+    let mut ctx = RequestCtx::empty();
+    ctx.hydrate(
+        &"/".parse().unwrap(),
+        &Method::GET,
+        &headers,
+        &Version::HTTP_11,
+        false,
+        Ipv4Addr::LOCALHOST.into(),
+    )
+    .unwrap();
+    ctx
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn mapped_header_value_selects_upstream_group() {
+    // Arrange
+    let device = device();
+    let mut ctx = ctx_with_tenant(Some("premium"));
+
+    // Act
+    let result = device.on_request(&mut ctx);
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+    assert_eq!(ctx.upstream_group.as_deref(), Some("premium-pool"));
+}
+
+#[test]
+fn unmapped_header_value_uses_default_pool() {
+    // Arrange
+    let device = device();
+    let mut ctx = ctx_with_tenant(Some("free"));
+
+    // Act
+    let result = device.on_request(&mut ctx);
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+    assert_eq!(ctx.upstream_group, None);
+}
+
+#[test]
+fn missing_header_uses_default_pool() {
+    // Arrange
+    let device = device();
+    let mut ctx = ctx_with_tenant(None);
+
+    // Act
+    let result = device.on_request(&mut ctx);
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+    assert_eq!(ctx.upstream_group, None);
+}
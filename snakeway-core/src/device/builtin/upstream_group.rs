@@ -0,0 +1,41 @@
+use crate::conf::types::UpstreamGroupDeviceConfig;
+use crate::ctx::RequestCtx;
+use crate::device::core::{Device, DeviceResult};
+use http::HeaderName;
+use std::collections::HashMap;
+
+/// Routes requests to an upstream group based on a request header.
+///
+/// With `header = "X-Tenant"` and `groups = { premium = "premium" }`, requests carrying
+/// `X-Tenant: premium` are sent to the service's `premium` upstreams. Any other request
+/// is left to the default pool.
+pub struct UpstreamGroupDevice {
+    header: HeaderName,
+    groups: HashMap<String, String>,
+}
+
+impl UpstreamGroupDevice {
+    pub fn from_config(cfg: UpstreamGroupDeviceConfig) -> anyhow::Result<Self> {
+        Ok(Self {
+            header: HeaderName::from_bytes(cfg.header.as_bytes())?,
+            groups: cfg.groups,
+        })
+    }
+}
+
+impl Device for UpstreamGroupDevice {
+    fn name(&self) -> &str {
+        "Upstream Group"
+    }
+
+    fn on_request(&self, ctx: &mut RequestCtx) -> DeviceResult {
+        ctx.upstream_group = ctx
+            .headers()
+            .get(&self.header)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| self.groups.get(v.trim()))
+            .cloned();
+
+        DeviceResult::Continue
+    }
+}
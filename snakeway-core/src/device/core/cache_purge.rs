@@ -0,0 +1,20 @@
+/// Which cached entries `Device::purge_cache` drops, by the key the device caches them under.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CachePurge {
+    /// Every entry.
+    All,
+    /// The entry with exactly this key.
+    Key(String),
+    /// Entries whose key starts with this prefix.
+    Prefix(String),
+}
+
+impl CachePurge {
+    pub fn matches(&self, key: &str) -> bool {
+        match self {
+            CachePurge::All => true,
+            CachePurge::Key(k) => key == k,
+            CachePurge::Prefix(prefix) => key.starts_with(prefix.as_str()),
+        }
+    }
+}
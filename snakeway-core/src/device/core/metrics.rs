@@ -0,0 +1,56 @@
+use dashmap::DashMap;
+use http::StatusCode;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Process-wide device metrics, reported by the admin stats endpoint.
+///
+/// Devices are rebuilt on reload, so the counters live outside of them and keep counting.
+pub static DEVICE_METRICS: Lazy<DeviceMetrics> = Lazy::new(DeviceMetrics::default);
+
+#[derive(Debug, Default)]
+pub struct DeviceMetrics {
+    /// Responses devices short-circuited the pipeline with, by device name and status.
+    short_circuits: DashMap<(String, u16), AtomicU64>,
+}
+
+/// How often a device short-circuited with a status.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ShortCircuitCount {
+    pub device: String,
+    pub status: u16,
+    pub count: u64,
+}
+
+impl DeviceMetrics {
+    /// Count a response a device stopped the pipeline with, e.g. a `401` or a redirect.
+    pub fn record_short_circuit(&self, device: &str, status: StatusCode) {
+        self.short_circuits
+            .entry((device.to_string(), status.as_u16()))
+            .or_default()
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn short_circuits(&self, device: &str, status: StatusCode) -> u64 {
+        self.short_circuits
+            .get(&(device.to_string(), status.as_u16()))
+            .map(|c| c.load(Ordering::Relaxed))
+            .unwrap_or(0)
+    }
+
+    /// Every short-circuit counter, ordered by device name, then status.
+    pub fn short_circuit_counts(&self) -> Vec<ShortCircuitCount> {
+        let mut counts: Vec<_> = self
+            .short_circuits
+            .iter()
+            .map(|entry| ShortCircuitCount {
+                device: entry.key().0.clone(),
+                status: entry.key().1,
+                count: entry.value().load(Ordering::Relaxed),
+            })
+            .collect();
+        counts.sort_by(|a, b| (&a.device, a.status).cmp(&(&b.device, b.status)));
+        counts
+    }
+}
@@ -1,11 +1,17 @@
+pub mod cache_purge;
 pub mod errors;
+pub mod metrics;
 pub mod pipeline;
 pub mod registry;
 pub mod result;
+#[cfg(test)]
+mod tests;
 
+pub use self::cache_purge::CachePurge;
 use self::errors::DeviceError;
 pub(crate) use self::result::DeviceResult;
 use crate::ctx::{RequestCtx, ResponseCtx, WsCloseCtx, WsCtx};
+use async_trait::async_trait;
 use bytes::Bytes;
 
 /// A trait representing a processing unit in the HTTP proxy pipeline.
@@ -16,9 +22,18 @@ use bytes::Bytes;
 ///
 /// All methods provide default implementations that simply continue the pipeline,
 /// allowing implementations to override only the methods they care about.
+#[async_trait]
 pub trait Device: Send + Sync {
     fn name(&self) -> &str;
 
+    /// Whether `on_request` and `on_stream_request_body` run on the blocking thread pool.
+    ///
+    /// Devices doing heavy synchronous work opt in, so they do not stall other requests
+    /// sharing the async runtime thread.
+    fn offload(&self) -> bool {
+        false
+    }
+
     /// Called when a request is first received, before any processing.
     ///
     /// This is the first opportunity to inspect or modify the incoming request.
@@ -26,6 +41,14 @@ pub trait Device: Send + Sync {
         DeviceResult::Continue
     }
 
+    /// Called after every device's `on_request`, for devices that must await I/O
+    /// (e.g., calling an external service) before the request can proceed.
+    ///
+    /// This runs on the request path, so implementations must bound their latency.
+    async fn on_request_async(&self, _ctx: &mut RequestCtx) -> DeviceResult {
+        DeviceResult::Continue
+    }
+
     /// Called when a request body is streamed.
     ///
     /// This is the opportunity to inspect or modify the request body as it is streamed.
@@ -59,12 +82,48 @@ pub trait Device: Send + Sync {
         DeviceResult::Continue
     }
 
+    /// Called for each chunk of the response body as it is sent to the client.
+    ///
+    /// Observation only; the chunk cannot be changed. Not called for WebSockets.
+    fn on_stream_response_body(
+        &self,
+        _ctx: &RequestCtx,
+        _chunk: Option<&Bytes>,
+        _end_of_stream: bool,
+    ) {
+    }
+
+    /// Called once the request is finished, after the response has been sent or the request failed.
+    ///
+    /// Body byte counts on the context are final at this point.
+    fn on_complete(&self, _ctx: &RequestCtx) {}
+
     /// Called when a WebSocket connection is opened.
     fn on_ws_open(&self, _ctx: &WsCtx) {}
 
     /// Called when a WebSocket connection is closed.
     fn on_ws_close(&self, _ctx: &WsCloseCtx) {}
 
+    /// Called once during graceful shutdown, after in-flight requests have drained.
+    ///
+    /// Last chance to release resources or flush device-held state.
+    fn shutdown(&self) {}
+
+    /// Called from the admin API to drop cached entries, e.g. a poisoned one, without a restart.
+    ///
+    /// Returns how many entries were dropped. Devices without a cache purge nothing.
+    fn purge_cache(&self, _purge: &CachePurge) -> usize {
+        0
+    }
+
+    /// Called from the admin API to clear state kept in memory, e.g. counters and caches,
+    /// as if the device was just loaded.
+    ///
+    /// Returns false for devices that keep no such state.
+    fn reset(&self) -> bool {
+        false
+    }
+
     /// Called when an error occurs during request processing.
     ///
     /// Provides an opportunity to handle or log errors in the pipeline.
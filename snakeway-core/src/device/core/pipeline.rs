@@ -1,7 +1,11 @@
-use super::{Device, DeviceResult};
+use super::metrics::DEVICE_METRICS;
+use super::{Device, DeviceError, DeviceResult};
 use crate::ctx::{RequestCtx, ResponseCtx, WsCloseCtx, WsCtx};
+use crate::route::Router;
 use bytes::Bytes;
+use std::borrow::Cow;
 use std::sync::Arc;
+use tokio::task::JoinError;
 
 pub struct DevicePipeline;
 
@@ -14,18 +18,78 @@ where
 {
     for dev in devices {
         let dev_ref = dev.as_ref();
-        match f(dev_ref) {
-            DeviceResult::Continue => continue,
-            r @ DeviceResult::Respond(_) => return r,
-            DeviceResult::Error(err) => {
-                dev_ref.on_error(&err);
-                return DeviceResult::Error(err);
-            }
+        if let Some(r) = settle(devices, dev_ref, f(dev_ref)) {
+            return r;
         }
     }
     DeviceResult::Continue
 }
 
+/// Handle one device's result, returning the chain's result if the chain stops here.
+///
+/// Short-circuit responses are counted by device and status,
+/// and record the device that sent them.
+fn settle<D>(devices: &[D], dev: &dyn Device, result: DeviceResult) -> Option<DeviceResult>
+where
+    D: AsRef<dyn Device>,
+{
+    match result {
+        DeviceResult::Continue => None,
+        DeviceResult::Respond(mut resp) => {
+            DEVICE_METRICS.record_short_circuit(dev.name(), resp.status);
+            resp.responded_by = Some(dev.name().to_string());
+            Some(DeviceResult::Respond(resp))
+        }
+        DeviceResult::Error(err) if !err.fatal => {
+            report_non_fatal(devices, &err);
+            None
+        }
+        DeviceResult::Error(err) => {
+            dev.on_error(&err);
+            Some(DeviceResult::Error(err))
+        }
+    }
+}
+
+/// Non-fatal errors do not stop the request; every device in the chain is told about them.
+fn report_non_fatal<D>(devices: &[D], err: &DeviceError)
+where
+    D: AsRef<dyn Device>,
+{
+    for dev in devices {
+        dev.as_ref().on_error(err);
+    }
+}
+
+/// Device pipeline selection
+impl DevicePipeline {
+    /// The devices that run for a request path, in pipeline order.
+    ///
+    /// `scopes` holds the routes each device is scoped to, by position. A scoped device is
+    /// skipped unless one of its routes matches the path the way a route would; unscoped
+    /// devices always run. Without scoped devices, the pipeline is borrowed as is.
+    pub fn scoped<'a>(
+        devices: &'a [Arc<dyn Device>],
+        scopes: &[Option<Arc<Router<()>>>],
+        path: &str,
+    ) -> Cow<'a, [Arc<dyn Device>]> {
+        if scopes.iter().all(Option::is_none) {
+            return Cow::Borrowed(devices);
+        }
+
+        devices
+            .iter()
+            .zip(scopes)
+            .filter(|(_, scope)| {
+                scope
+                    .as_ref()
+                    .is_none_or(|routes| routes.match_route(path).is_ok())
+            })
+            .map(|(dev, _)| dev.clone())
+            .collect()
+    }
+}
+
 /// Device pipeline for WebSocket events
 impl DevicePipeline {
     pub(crate) fn run_on_ws_open(devices: &[Arc<dyn Device>], ctx: &WsCtx) {
@@ -43,10 +107,66 @@ impl DevicePipeline {
 
 /// Device pipeline for HTTP events
 impl DevicePipeline {
+    /// Report an error raised outside of a device hook, e.g. while connecting upstream.
+    pub(crate) fn run_on_error(devices: &[Arc<dyn Device>], err: &DeviceError) {
+        for dev in devices {
+            dev.on_error(err);
+        }
+    }
+
+    pub(crate) fn run_on_stream_response_body(
+        devices: &[Arc<dyn Device>],
+        ctx: &RequestCtx,
+        chunk: Option<&Bytes>,
+        end_of_stream: bool,
+    ) {
+        for dev in devices {
+            dev.on_stream_response_body(ctx, chunk, end_of_stream);
+        }
+    }
+
+    pub(crate) fn run_on_complete(devices: &[Arc<dyn Device>], ctx: &RequestCtx) {
+        for dev in devices {
+            dev.on_complete(ctx);
+        }
+    }
+
     pub fn run_on_request(devices: &[Arc<dyn Device>], ctx: &mut RequestCtx) -> DeviceResult {
         run_device_chain(devices, |dev| dev.on_request(ctx))
     }
 
+    pub async fn run_on_request_async(
+        devices: &[Arc<dyn Device>],
+        ctx: &mut RequestCtx,
+    ) -> DeviceResult {
+        for dev in devices {
+            let result = dev.on_request_async(ctx).await;
+            if let Some(r) = settle(devices, dev.as_ref(), result) {
+                return r;
+            }
+        }
+        DeviceResult::Continue
+    }
+
+    /// Like `run_on_request`, but devices that `offload()` run on the blocking thread pool,
+    /// in their place in the chain.
+    pub async fn run_on_request_offloaded(
+        devices: &[Arc<dyn Device>],
+        ctx: &mut RequestCtx,
+    ) -> DeviceResult {
+        for dev in devices {
+            let result = if dev.offload() {
+                offload_on_request(dev, ctx).await
+            } else {
+                dev.on_request(ctx)
+            };
+            if let Some(r) = settle(devices, dev.as_ref(), result) {
+                return r;
+            }
+        }
+        DeviceResult::Continue
+    }
+
     pub fn on_stream_request_body(
         devices: &[Arc<dyn Device>],
         ctx: &mut RequestCtx,
@@ -58,6 +178,27 @@ impl DevicePipeline {
         })
     }
 
+    /// Like `on_stream_request_body`, but devices that `offload()` run on the blocking thread pool,
+    /// in their place in the chain.
+    pub async fn on_stream_request_body_offloaded(
+        devices: &[Arc<dyn Device>],
+        ctx: &mut RequestCtx,
+        body: &mut Option<Bytes>,
+        end_of_stream: bool,
+    ) -> DeviceResult {
+        for dev in devices {
+            let result = if dev.offload() {
+                offload_on_stream_request_body(dev, ctx, body, end_of_stream).await
+            } else {
+                dev.on_stream_request_body(ctx, body, end_of_stream)
+            };
+            if let Some(r) = settle(devices, dev.as_ref(), result) {
+                return r;
+            }
+        }
+        DeviceResult::Continue
+    }
+
     pub fn run_before_proxy(
         devices: &[impl AsRef<dyn Device>],
         ctx: &mut RequestCtx,
@@ -72,10 +213,76 @@ impl DevicePipeline {
         run_device_chain(devices, |dev| dev.after_proxy(ctx))
     }
 
+    /// Unlike the other hooks, `on_response` runs in reverse pipeline order, so the response
+    /// unwinds through the devices the request passed through: the first device on the request
+    /// is the last to touch the response.
     pub fn run_on_response(
         devices: &[impl AsRef<dyn Device>],
         ctx: &mut ResponseCtx,
     ) -> DeviceResult {
-        run_device_chain(devices, |dev| dev.on_response(ctx))
+        for dev in devices.iter().rev() {
+            let dev_ref = dev.as_ref();
+            if let Some(r) = settle(devices, dev_ref, dev_ref.on_response(ctx)) {
+                return r;
+            }
+        }
+        DeviceResult::Continue
     }
 }
+
+/// Run a device's `on_request` on the blocking thread pool and wait for it.
+///
+/// The blocking task must own what it touches, so the context is moved into it and back.
+async fn offload_on_request(dev: &Arc<dyn Device>, ctx: &mut RequestCtx) -> DeviceResult {
+    let dev = dev.clone();
+    let mut owned = std::mem::take(ctx);
+
+    let joined = tokio::task::spawn_blocking(move || {
+        let result = dev.on_request(&mut owned);
+        (owned, result)
+    })
+    .await;
+
+    match joined {
+        Ok((owned, result)) => {
+            *ctx = owned;
+            result
+        }
+        Err(e) => offload_failed(e),
+    }
+}
+
+/// Run a device's `on_stream_request_body` on the blocking thread pool and wait for it.
+async fn offload_on_stream_request_body(
+    dev: &Arc<dyn Device>,
+    ctx: &mut RequestCtx,
+    body: &mut Option<Bytes>,
+    end_of_stream: bool,
+) -> DeviceResult {
+    let dev = dev.clone();
+    let mut owned = std::mem::take(ctx);
+    let mut chunk = body.take();
+
+    let joined = tokio::task::spawn_blocking(move || {
+        let result = dev.on_stream_request_body(&mut owned, &mut chunk, end_of_stream);
+        (owned, chunk, result)
+    })
+    .await;
+
+    match joined {
+        Ok((owned, chunk, result)) => {
+            *ctx = owned;
+            *body = chunk;
+            result
+        }
+        Err(e) => offload_failed(e),
+    }
+}
+
+/// A panicked hook took the request context with it, so the request cannot continue.
+fn offload_failed(e: JoinError) -> DeviceResult {
+    DeviceResult::Error(DeviceError {
+        message: format!("offloaded device hook failed: {e}"),
+        fatal: true,
+    })
+}
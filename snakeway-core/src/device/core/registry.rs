@@ -1,16 +1,79 @@
 use crate::conf::RuntimeConfig;
 use crate::conf::types::DeviceConfig;
+use crate::device::WasmModuleCache;
+use crate::device::builtin::body_digest::BodyDigestDevice;
+use crate::device::builtin::cors::CorsDevice;
+use crate::device::builtin::deprecation::DeprecationDevice;
+use crate::device::builtin::error_page::ErrorPageDevice;
+use crate::device::builtin::experiment::ExperimentDevice;
+use crate::device::builtin::header_rewrite::HeaderRewriteDevice;
+use crate::device::builtin::idempotency::IdempotencyDevice;
 use crate::device::builtin::identity::IdentityDevice;
+use crate::device::builtin::ip_reputation::IpReputationDevice;
+use crate::device::builtin::jwt_auth::JwtAuthDevice;
+#[cfg(feature = "lua")]
+use crate::device::builtin::lua::LuaDevice;
+use crate::device::builtin::openapi::OpenApiDevice;
+use crate::device::builtin::query_rewrite::QueryRewriteDevice;
+use crate::device::builtin::quota::QuotaDevice;
+use crate::device::builtin::rate_limit::RateLimitDevice;
 use crate::device::builtin::request_filter::RequestFilterDevice;
+use crate::device::builtin::required_headers::RequiredHeadersDevice;
+use crate::device::builtin::response_headers::ResponseHeadersDevice;
+use crate::device::builtin::status_remap::StatusRemapDevice;
 use crate::device::builtin::structured_logging::StructuredLoggingDevice;
+use crate::device::builtin::upstream_group::UpstreamGroupDevice;
 use crate::device::core::Device;
+use crate::device::core::pipeline::DevicePipeline;
+#[cfg(feature = "wasm")]
+use crate::device::plugin::resolve_wasm_module;
 #[cfg(feature = "wasm")]
 use crate::device::wasm::wasm_device::WasmDevice;
-use anyhow::Result;
+use crate::enrichment::geoip::GeoIp;
+use crate::route::Router;
+use anyhow::{Result, bail};
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 pub struct DeviceRegistry {
+    /// Every loaded device, in pipeline order.
     devices: Vec<Arc<dyn Device>>,
+
+    /// Names of `devices`, by position, as listeners attach them.
+    names: Vec<String>,
+
+    /// Devices not attached to any listener - these run on every public listener.
+    global: Pipeline,
+
+    /// Resolved device pipelines for listeners that have devices attached.
+    listener_pipelines: HashMap<String, Pipeline>,
+
+    /// Compiled WASM modules, shared with the registries built on config reload.
+    wasm_modules: Arc<WasmModuleCache>,
+
+    /// The server's GeoIP databases, shared with the registries built on config reload.
+    geoip: Arc<GeoIp>,
+}
+
+/// A device pipeline, with the routes each of its devices is scoped to.
+#[derive(Default)]
+struct Pipeline {
+    devices: Vec<Arc<dyn Device>>,
+
+    /// Routes each device is scoped to, by position. `None` runs on every path.
+    scopes: Vec<Option<Arc<Router<()>>>>,
+}
+
+impl Pipeline {
+    fn push(&mut self, device: Arc<dyn Device>, scope: Option<Arc<Router<()>>>) {
+        self.devices.push(device);
+        self.scopes.push(scope);
+    }
+
+    fn for_path(&self, path: &str) -> Cow<'_, [Arc<dyn Device>]> {
+        DevicePipeline::scoped(&self.devices, &self.scopes, path)
+    }
 }
 
 impl Default for DeviceRegistry {
@@ -21,73 +84,351 @@ impl Default for DeviceRegistry {
 
 impl DeviceRegistry {
     pub fn new() -> Self {
+        Self::with_shared(Arc::default(), Arc::default())
+    }
+
+    /// A registry that loads WASM devices through an existing module cache, and gives identity
+    /// devices the server's GeoIP databases.
+    pub fn with_shared(wasm_modules: Arc<WasmModuleCache>, geoip: Arc<GeoIp>) -> Self {
         Self {
             devices: Vec::new(),
+            names: Vec::new(),
+            global: Pipeline::default(),
+            listener_pipelines: HashMap::new(),
+            wasm_modules,
+            geoip,
         }
     }
 
     pub fn load_from_config(&mut self, cfg: &RuntimeConfig) -> Result<()> {
-        for device_cfg in &cfg.devices {
+        let mut loaded: Vec<(&str, Arc<dyn Device>, Option<Arc<Router<()>>>)> = Vec::new();
+        let mut invalid_wasm_devices = Vec::new();
+
+        // Devices load in pipeline order: ascending priority, then the default order
+        // `parse_devices` pushes them in, which holds among devices of equal priority.
+        for device_cfg in cfg.ordered_devices() {
             if !device_cfg.is_enabled() {
                 continue;
             }
 
-            match device_cfg {
-                // Stateless devices are run before stateful devices as they are cheaper to run.
+            let device: Arc<dyn Device> = match device_cfg {
+                // The CORS device answers preflights, which never carry credentials, so it runs
+                // before any device that could reject them.
+                DeviceConfig::Cors(cfg) => {
+                    let device_config = cfg.clone();
+                    Arc::new(CorsDevice::from_config(device_config)?)
+                }
+
+                // Stateless devices, from here down to the identity device, are run before
+                // stateful devices as they are cheaper to run.
                 // The request filter device specifically must run before the identity device,
                 // as this allows it to short-circuit the request early to avoid unnecessary allocations.
                 DeviceConfig::RequestFilter(cfg) => {
                     let device_config = cfg.clone();
-                    let device = Arc::new(RequestFilterDevice::from_config(device_config)?);
-                    self.devices.push(device);
+                    Arc::new(RequestFilterDevice::from_config(device_config)?)
+                }
+
+                // The OpenAPI device is stateless and rejects malformed requests before they reach
+                // stateful devices. Like the body digest device, it buffers JSON bodies up to its own
+                // max_body_bytes.
+                DeviceConfig::OpenApi(cfg) => {
+                    let device_config = cfg.clone();
+                    Arc::new(OpenApiDevice::from_config(device_config)?)
+                }
+
+                // The body digest device holds back request bodies that carry a digest until
+                // they are verified, so its buffering is bounded by its own max_body_bytes.
+                DeviceConfig::BodyDigest(cfg) => {
+                    let device_config = cfg.clone();
+                    Arc::new(BodyDigestDevice::from_config(device_config)?)
+                }
+
+                // The upstream group device picks an upstream group from a request header.
+                DeviceConfig::UpstreamGroup(cfg) => {
+                    let device_config = cfg.clone();
+                    Arc::new(UpstreamGroupDevice::from_config(device_config)?)
+                }
+
+                // The experiment device assigns a variant by hashing a cookie.
+                DeviceConfig::Experiment(cfg) => {
+                    let device_config = cfg.clone();
+                    Arc::new(ExperimentDevice::from_config(device_config)?)
+                }
+
+                // Header rewrites run right after the identity device, which reads the client's own
+                // headers, so a required header can be filled in and stateful devices read the
+                // rewritten request.
+                DeviceConfig::HeaderRewrite(cfg) => {
+                    let device_config = cfg.clone();
+                    Arc::new(HeaderRewriteDevice::from_config(device_config)?)
+                }
+
+                // The query rewrite device only changes the upstream query, in before_proxy.
+                DeviceConfig::QueryRewrite(cfg) => {
+                    let device_config = cfg.clone();
+                    Arc::new(QueryRewriteDevice::from_config(device_config)?)
+                }
+
+                // Required headers are checked, or filled in, before any stateful device reads them.
+                DeviceConfig::RequiredHeaders(cfg) => {
+                    let device_config = cfg.clone();
+                    Arc::new(RequiredHeadersDevice::from_config(device_config)?)
+                }
+
+                // The status remap device rewrites upstream statuses in after_proxy.
+                DeviceConfig::StatusRemap(cfg) => {
+                    let device_config = cfg.clone();
+                    Arc::new(StatusRemapDevice::from_config(device_config)?)
+                }
+
+                // The error page device replaces the bodies of upstream error responses.
+                DeviceConfig::ErrorPage(cfg) => {
+                    let device_config = cfg.clone();
+                    Arc::new(ErrorPageDevice::from_config(device_config)?)
+                }
+
+                // The response headers device renders its templates from the request context.
+                DeviceConfig::ResponseHeaders(cfg) => {
+                    let device_config = cfg.clone();
+                    Arc::new(ResponseHeadersDevice::from_config(device_config)?)
+                }
+
+                // The deprecation device marks responses for the matched route.
+                DeviceConfig::Deprecation(cfg) => {
+                    let device_config = cfg.clone();
+                    Arc::new(DeprecationDevice::from_config(device_config)?)
                 }
 
                 // Important: The identity device must always be first AFTER stateless devices,
                 // so that it can establish the context of the request BEFORE all other stateful devices run.
                 DeviceConfig::Identity(cfg) => {
                     let device_config = cfg.clone();
-                    let device = Arc::new(IdentityDevice::from_config(device_config)?);
-                    self.devices.push(device);
+                    Arc::new(IdentityDevice::from_config(
+                        device_config,
+                        self.geoip.clone(),
+                    )?)
+                }
+
+                // The rate limit device can count requests by the client IP the identity device
+                // resolved, so it runs right after it, and before devices that do expensive work.
+                DeviceConfig::RateLimit(cfg) => {
+                    let device_config = cfg.clone();
+                    Arc::new(RateLimitDevice::from_config(device_config)?)
+                }
+
+                // The JWT auth device may await a JWKS endpoint, so it runs in the async on_request
+                // phase. It rejects unauthenticated requests before the quota device is asked.
+                DeviceConfig::JwtAuth(cfg) => {
+                    let device_config = cfg.clone();
+                    Arc::new(JwtAuthDevice::from_config(device_config)?)
+                }
+
+                // The quota device relies on the API key only, and awaits an external service,
+                // so it runs in the async on_request phase after every sync device.
+                DeviceConfig::Quota(cfg) => {
+                    let device_config = cfg.clone();
+                    Arc::new(QuotaDevice::from_config(device_config)?)
+                }
+
+                // The IP reputation device may await an external service, and reads the client IP
+                // the identity device resolved, so it also runs in the async on_request phase.
+                DeviceConfig::IpReputation(cfg) => {
+                    let device_config = cfg.clone();
+                    Arc::new(IpReputationDevice::from_config(device_config)?)
+                }
+
+                // The idempotency device makes duplicates await the request holding their key,
+                // so it runs in the async on_request phase, after the sync devices that reject requests.
+                DeviceConfig::Idempotency(cfg) => {
+                    let device_config = cfg.clone();
+                    Arc::new(IdempotencyDevice::from_config(device_config)?)
                 }
 
+                // The Lua device runs a script, so like WASM devices it runs after the builtin
+                // ones, and only when Snakeway is built with the `lua` feature.
+                DeviceConfig::Lua(cfg) => load_lua_device(cfg)?,
+
                 // Wasm devices are loaded dynamically at runtime.
                 // They should be run AFTER all builtin devices, except the logging device.
-                DeviceConfig::Wasm(cfg) => {
-                    self.load_wasm_device(cfg)?;
-                }
+                // Every WASM device is checked before failing, so one error lists them all.
+                DeviceConfig::Wasm(wasm_cfg) => match self.load_wasm_device(wasm_cfg) {
+                    Ok(device) => device,
+                    Err(e) if cfg.server.strict_wasm_devices => {
+                        invalid_wasm_devices.push(format!(
+                            "'{}' ({}): {e:#}",
+                            wasm_cfg.name,
+                            wasm_cfg.path.display()
+                        ));
+                        continue;
+                    }
+                    Err(e) => {
+                        tracing::warn!(
+                            "Disabling WASM device '{}' ({}): {e:#}",
+                            wasm_cfg.name,
+                            wasm_cfg.path.display()
+                        );
+                        continue;
+                    }
+                },
 
                 // Important: The logging device must always be last, so that it can observe all
                 // other devices' outputs.
                 DeviceConfig::StructuredLogging(cfg) => {
                     let device_config = cfg.clone();
-                    let device = Arc::new(StructuredLoggingDevice::from_config(device_config)?);
-                    self.devices.push(device);
+                    Arc::new(StructuredLoggingDevice::from_config(device_config)?)
                 }
+            };
+
+            loaded.push((device_cfg.name(), device, route_scope(device_cfg.routes())?));
+        }
+
+        if !invalid_wasm_devices.is_empty() {
+            bail!(
+                "failed to load WASM devices:\n  {}",
+                invalid_wasm_devices.join("\n  ")
+            );
+        }
+
+        let is_attached = |name: &str| {
+            cfg.listeners
+                .iter()
+                .any(|l| l.devices.iter().any(|d| d == name))
+        };
+
+        // Listener pipelines are a union of global and listener devices that keeps the
+        // pipeline order above, so ordering guarantees hold regardless of attachment.
+        // Admin listeners never run global devices - only the ones attached to them.
+        // `conf dump` reports the same pipelines through `RuntimeConfig::device_pipeline`.
+        // Devices scoped to routes stay in the pipeline, and are skipped per request path.
+        for listener in cfg.listeners.iter().filter(|l| !l.devices.is_empty()) {
+            let mut pipeline = Pipeline::default();
+            for (_, device, scope) in loaded
+                .iter()
+                .filter(|(name, _, _)| cfg.device_runs_on(name, listener))
+            {
+                pipeline.push(device.clone(), scope.clone());
             }
+
+            self.listener_pipelines
+                .insert(listener.name.clone(), pipeline);
+        }
+
+        for (name, device, scope) in loaded {
+            if !is_attached(name) {
+                self.global.push(device.clone(), scope);
+            }
+            self.devices.push(device);
+            self.names.push(name.to_string());
         }
 
         Ok(())
     }
 
+    /// All loaded devices, regardless of where they are attached.
     pub fn all(&self) -> &[Arc<dyn Device>] {
         &self.devices
     }
+
+    /// A loaded device by the name listeners attach it with, e.g. `rate_limit`.
+    pub fn get(&self, name: &str) -> Option<&Arc<dyn Device>> {
+        let index = self.names.iter().position(|n| n == name)?;
+        Some(&self.devices[index])
+    }
+
+    /// All loaded devices with their names, in pipeline order.
+    pub fn named(&self) -> impl Iterator<Item = (&str, &Arc<dyn Device>)> {
+        self.names.iter().map(String::as_str).zip(&self.devices)
+    }
+
+    /// The device pipeline for a public listener: global devices plus devices attached to it.
+    ///
+    /// This includes devices scoped to routes; see `for_request` for the devices a request runs.
+    pub fn for_listener(&self, listener: &str) -> &[Arc<dyn Device>] {
+        &self
+            .listener_pipelines
+            .get(listener)
+            .unwrap_or(&self.global)
+            .devices
+    }
+
+    /// The devices a request to a public listener runs, skipping devices scoped to other routes.
+    pub fn for_request(&self, listener: &str, path: &str) -> Cow<'_, [Arc<dyn Device>]> {
+        self.listener_pipelines
+            .get(listener)
+            .unwrap_or(&self.global)
+            .for_path(path)
+    }
+
+    /// The device pipeline for an admin listener: only devices explicitly attached to it.
+    pub fn for_admin_listener(&self, listener: &str) -> &[Arc<dyn Device>] {
+        self.listener_pipelines
+            .get(listener)
+            .map(|p| p.devices.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// The devices a request to an admin listener runs, skipping devices scoped to other routes.
+    pub fn for_admin_request(&self, listener: &str, path: &str) -> Cow<'_, [Arc<dyn Device>]> {
+        self.listener_pipelines
+            .get(listener)
+            .map(|p| p.for_path(path))
+            .unwrap_or(Cow::Borrowed(&[]))
+    }
+}
+
+/// The routes a device is scoped to, matched like routes. `None` when it runs on every path.
+fn route_scope(routes: &[String]) -> Result<Option<Arc<Router<()>>>> {
+    if routes.is_empty() {
+        return Ok(None);
+    }
+
+    let mut scope = Router::new();
+    for route in routes {
+        scope.add_route(route, ())?;
+    }
+    Ok(Some(Arc::new(scope)))
 }
 
 impl DeviceRegistry {
     #[cfg(feature = "wasm")]
-    fn load_wasm_device(&mut self, cfg: &crate::conf::types::WasmDeviceConfig) -> Result<()> {
-        let device = WasmDevice::load(&cfg.path)?;
+    fn load_wasm_device(
+        &self,
+        cfg: &crate::conf::types::WasmDeviceConfig,
+    ) -> Result<Arc<dyn Device>> {
+        // A plugin bundle brings its own module and default config.
+        let (module, config) = resolve_wasm_module(&cfg.path, cfg.config.as_ref())?;
+        let device = WasmDevice::load(
+            &self.wasm_modules,
+            &module,
+            cfg.fuel,
+            cfg.offload,
+            config.as_ref(),
+        )?;
 
-        self.devices.push(Arc::new(device));
-        Ok(())
+        Ok(Arc::new(device))
     }
 
     #[cfg(not(feature = "wasm"))]
-    fn load_wasm_device(&mut self, cfg: &crate::conf::types::WasmDeviceConfig) -> Result<()> {
+    fn load_wasm_device(
+        &self,
+        cfg: &crate::conf::types::WasmDeviceConfig,
+    ) -> Result<Arc<dyn Device>> {
         Err(anyhow::anyhow!(
             "WASM device '{}' requested, but Snakeway was built without the `wasm` feature",
             cfg.path.display()
         ))
     }
 }
+
+#[cfg(feature = "lua")]
+fn load_lua_device(cfg: &crate::conf::types::LuaDeviceConfig) -> Result<Arc<dyn Device>> {
+    Ok(Arc::new(LuaDevice::from_config(cfg.clone())?))
+}
+
+#[cfg(not(feature = "lua"))]
+fn load_lua_device(cfg: &crate::conf::types::LuaDeviceConfig) -> Result<Arc<dyn Device>> {
+    Err(anyhow::anyhow!(
+        "Lua device '{}' requested, but Snakeway was built without the `lua` feature",
+        cfg.path.display()
+    ))
+}
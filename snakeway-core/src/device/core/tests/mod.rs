@@ -0,0 +1,2 @@
+mod pipeline_tests;
+mod registry_tests;
@@ -0,0 +1,488 @@
+use crate::ctx::{ListenerInfo, RequestCtx, ResponseCtx};
+use crate::device::core::errors::DeviceError;
+use crate::device::core::metrics::DEVICE_METRICS;
+use crate::device::core::pipeline::DevicePipeline;
+use crate::device::core::{Device, DeviceResult};
+use crate::route::Router;
+use async_trait::async_trait;
+use http::{HeaderMap, StatusCode};
+use pretty_assertions::assert_eq;
+use std::borrow::Cow;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+/// Fails `on_request` with the given fatality, e.g. like a WASM device out of fuel.
+struct FailingDevice {
+    fatal: bool,
+}
+
+impl Device for FailingDevice {
+    fn name(&self) -> &str {
+        "Failing"
+    }
+
+    fn on_request(&self, _ctx: &mut RequestCtx) -> DeviceResult {
+        DeviceResult::Error(DeviceError {
+            message: "ran out of fuel".to_string(),
+            fatal: self.fatal,
+        })
+    }
+}
+
+/// Short-circuits `on_request` with a status, like an auth device rejecting a request.
+struct RejectingDevice {
+    name: &'static str,
+    status: StatusCode,
+}
+
+impl Device for RejectingDevice {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn on_request(&self, _ctx: &mut RequestCtx) -> DeviceResult {
+        DeviceResult::Respond(ResponseCtx::new(
+            None,
+            self.status,
+            HeaderMap::new(),
+            Vec::new(),
+        ))
+    }
+}
+
+/// Records the requests it sees and the errors it is told about.
+#[derive(Default)]
+struct RecordingDevice {
+    requests: AtomicUsize,
+    errors: Mutex<Vec<String>>,
+}
+
+impl Device for RecordingDevice {
+    fn name(&self) -> &str {
+        "Recording"
+    }
+
+    fn on_request(&self, _ctx: &mut RequestCtx) -> DeviceResult {
+        self.requests.fetch_add(1, Ordering::SeqCst);
+        DeviceResult::Continue
+    }
+
+    fn on_error(&self, err: &DeviceError) {
+        self.errors.lock().unwrap().push(err.message.clone());
+    }
+}
+
+/// Blocks its thread in `on_request`, like a device doing heavy synchronous work.
+struct HeavyDevice {
+    offload: bool,
+}
+
+const HEAVY_WORK: Duration = Duration::from_millis(300);
+
+impl Device for HeavyDevice {
+    fn name(&self) -> &str {
+        "Heavy"
+    }
+
+    fn offload(&self) -> bool {
+        self.offload
+    }
+
+    fn on_request(&self, ctx: &mut RequestCtx) -> DeviceResult {
+        std::thread::sleep(HEAVY_WORK);
+        ctx.upstream_path = Some("/rewritten".to_string());
+        DeviceResult::Continue
+    }
+}
+
+/// Awaits a timer in `on_request_async`, like a device calling an external service,
+/// then records its name.
+struct SleepingDevice {
+    name: &'static str,
+    delay: Duration,
+    order: Arc<Mutex<Vec<&'static str>>>,
+}
+
+#[async_trait]
+impl Device for SleepingDevice {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    async fn on_request_async(&self, _ctx: &mut RequestCtx) -> DeviceResult {
+        tokio::time::sleep(self.delay).await;
+        self.order.lock().unwrap().push(self.name);
+        DeviceResult::Continue
+    }
+}
+
+/// Records its name when it sees a response.
+struct ResponseOrderDevice {
+    name: &'static str,
+    order: Arc<Mutex<Vec<&'static str>>>,
+}
+
+impl Device for ResponseOrderDevice {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn on_response(&self, _ctx: &mut ResponseCtx) -> DeviceResult {
+        self.order.lock().unwrap().push(self.name);
+        DeviceResult::Continue
+    }
+}
+
+/// Records the listeners it sees, and rejects plaintext requests to admin listeners.
+#[derive(Default)]
+struct ListenerAwareDevice {
+    seen: Mutex<Vec<(String, bool)>>,
+}
+
+impl Device for ListenerAwareDevice {
+    fn name(&self) -> &str {
+        "ListenerAware"
+    }
+
+    fn on_request(&self, ctx: &mut RequestCtx) -> DeviceResult {
+        let name = ctx.listener_name().unwrap_or_default().to_string();
+        self.seen.lock().unwrap().push((name, ctx.is_tls()));
+
+        if ctx.is_admin_listener() && !ctx.is_tls() {
+            return DeviceResult::Respond(ResponseCtx::new(
+                None,
+                StatusCode::FORBIDDEN,
+                HeaderMap::new(),
+                Vec::new(),
+            ));
+        }
+        DeviceResult::Continue
+    }
+}
+
+fn listener(name: &str, admin: bool, tls: bool) -> ListenerInfo {
+    ListenerInfo {
+        name: Arc::from(name),
+        admin,
+        tls,
+    }
+}
+
+/// Run the pipeline alongside a short concurrent task, returning how long that task took.
+async fn run_alongside_concurrent_task(
+    devices: &[Arc<dyn Device>],
+    ctx: &mut RequestCtx,
+) -> Duration {
+    let started = Instant::now();
+    let (_, concurrent_elapsed) = tokio::join!(
+        DevicePipeline::run_on_request_offloaded(devices, ctx),
+        async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            started.elapsed()
+        }
+    );
+    concurrent_elapsed
+}
+
+fn pipeline(fatal: bool) -> (Vec<Arc<dyn Device>>, Arc<RecordingDevice>) {
+    let recorder = Arc::new(RecordingDevice::default());
+    let devices: Vec<Arc<dyn Device>> = vec![
+        recorder.clone(),
+        Arc::new(FailingDevice { fatal }),
+        recorder.clone(),
+    ];
+    (devices, recorder)
+}
+
+fn route_scope(routes: &[&str]) -> Option<Arc<Router<()>>> {
+    let mut scope = Router::new();
+    for route in routes {
+        scope.add_route(route, ()).unwrap();
+    }
+    Some(Arc::new(scope))
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn non_fatal_error_is_reported_and_pipeline_continues() {
+    // Arrange
+    let (devices, recorder) = pipeline(false);
+    let mut ctx = RequestCtx::empty();
+
+    // Act
+    let result = DevicePipeline::run_on_request(&devices, &mut ctx);
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+    assert_eq!(recorder.requests.load(Ordering::SeqCst), 2);
+    assert_eq!(
+        *recorder.errors.lock().unwrap(),
+        vec!["ran out of fuel", "ran out of fuel"]
+    );
+}
+
+#[test]
+fn fatal_error_stops_pipeline() {
+    // Arrange
+    let (devices, recorder) = pipeline(true);
+    let mut ctx = RequestCtx::empty();
+
+    // Act
+    let result = DevicePipeline::run_on_request(&devices, &mut ctx);
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Error(_)));
+    assert_eq!(recorder.requests.load(Ordering::SeqCst), 1);
+    assert!(recorder.errors.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn offloaded_device_does_not_stall_concurrent_requests() {
+    // Arrange
+    let devices: Vec<Arc<dyn Device>> = vec![Arc::new(HeavyDevice { offload: true })];
+    let mut ctx = RequestCtx::empty();
+
+    // Act
+    let concurrent_elapsed = run_alongside_concurrent_task(&devices, &mut ctx).await;
+
+    // Assert
+    assert!(
+        concurrent_elapsed < HEAVY_WORK,
+        "concurrent task took {concurrent_elapsed:?}"
+    );
+    assert_eq!(ctx.upstream_path.as_deref(), Some("/rewritten"));
+}
+
+#[tokio::test]
+async fn inline_device_stalls_concurrent_requests() {
+    // Arrange
+    let devices: Vec<Arc<dyn Device>> = vec![Arc::new(HeavyDevice { offload: false })];
+    let mut ctx = RequestCtx::empty();
+
+    // Act
+    let concurrent_elapsed = run_alongside_concurrent_task(&devices, &mut ctx).await;
+
+    // Assert
+    assert!(concurrent_elapsed >= HEAVY_WORK);
+    assert_eq!(ctx.upstream_path.as_deref(), Some("/rewritten"));
+}
+
+#[tokio::test]
+async fn offloaded_device_keeps_its_place_in_the_chain() {
+    // Arrange
+    let (mut devices, recorder) = pipeline(true);
+    devices.insert(0, Arc::new(HeavyDevice { offload: true }));
+    let mut ctx = RequestCtx::empty();
+
+    // Act
+    let result = DevicePipeline::run_on_request_offloaded(&devices, &mut ctx).await;
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Error(_)));
+    assert_eq!(recorder.requests.load(Ordering::SeqCst), 1);
+    assert_eq!(ctx.upstream_path.as_deref(), Some("/rewritten"));
+}
+
+#[tokio::test]
+async fn async_devices_run_in_order() {
+    // Arrange
+    let order = Arc::new(Mutex::new(Vec::new()));
+    let device = |name, millis| -> Arc<dyn Device> {
+        Arc::new(SleepingDevice {
+            name,
+            delay: Duration::from_millis(millis),
+            order: order.clone(),
+        })
+    };
+    let devices = vec![device("slow", 50), device("fast", 0), device("medium", 20)];
+    let mut ctx = RequestCtx::empty();
+
+    // Act
+    let result = DevicePipeline::run_on_request_async(&devices, &mut ctx).await;
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+    assert_eq!(*order.lock().unwrap(), vec!["slow", "fast", "medium"]);
+}
+
+#[test]
+fn on_response_runs_in_reverse_pipeline_order() {
+    // Arrange
+    let order = Arc::new(Mutex::new(Vec::new()));
+    let device = |name| -> Arc<dyn Device> {
+        Arc::new(ResponseOrderDevice {
+            name,
+            order: order.clone(),
+        })
+    };
+    let devices = vec![device("first"), device("second"), device("third")];
+    let mut resp = ResponseCtx::new(None, StatusCode::OK, HeaderMap::new(), Vec::new());
+
+    // Act
+    let result = DevicePipeline::run_on_response(&devices, &mut resp);
+
+    // Assert
+    assert!(matches!(result, DeviceResult::Continue));
+    assert_eq!(*order.lock().unwrap(), vec!["third", "second", "first"]);
+}
+
+#[tokio::test]
+async fn async_device_does_not_stall_concurrent_requests() {
+    // Arrange
+    let order = Arc::new(Mutex::new(Vec::new()));
+    let devices: Vec<Arc<dyn Device>> = vec![Arc::new(SleepingDevice {
+        name: "slow",
+        delay: HEAVY_WORK,
+        order: order.clone(),
+    })];
+    let mut ctx = RequestCtx::empty();
+    let started = Instant::now();
+
+    // Act
+    let (_, concurrent_elapsed) = tokio::join!(
+        DevicePipeline::run_on_request_async(&devices, &mut ctx),
+        async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            started.elapsed()
+        }
+    );
+
+    // Assert
+    assert!(
+        concurrent_elapsed < HEAVY_WORK,
+        "concurrent task took {concurrent_elapsed:?}"
+    );
+    assert_eq!(*order.lock().unwrap(), vec!["slow"]);
+}
+
+#[test]
+fn scoped_device_only_runs_under_its_routes() {
+    // Arrange
+    let auth = Arc::new(RecordingDevice::default());
+    let logging = Arc::new(RecordingDevice::default());
+    let devices: Vec<Arc<dyn Device>> = vec![auth.clone(), logging.clone()];
+    let scopes = vec![route_scope(&["/api"]), None];
+    let mut ctx = RequestCtx::empty();
+
+    // Act
+    for path in ["/api", "/api/users", "/public", "/apiary"] {
+        let scoped = DevicePipeline::scoped(&devices, &scopes, path);
+        DevicePipeline::run_on_request(&scoped, &mut ctx);
+    }
+
+    // Assert
+    assert_eq!(auth.requests.load(Ordering::SeqCst), 2);
+    assert_eq!(logging.requests.load(Ordering::SeqCst), 4);
+}
+
+#[test]
+fn pipeline_without_scoped_devices_is_borrowed() {
+    // Arrange
+    let devices: Vec<Arc<dyn Device>> = vec![Arc::new(RecordingDevice::default())];
+    let scopes = vec![None];
+
+    // Act
+    let scoped = DevicePipeline::scoped(&devices, &scopes, "/public");
+
+    // Assert
+    assert!(matches!(scoped, Cow::Borrowed(_)));
+}
+
+#[test]
+fn short_circuit_is_counted_by_device_and_status() {
+    // Arrange
+    // Counters are process-wide, so the device name is unique to this test.
+    let name = "ShortCircuitCounted";
+    let devices: Vec<Arc<dyn Device>> = vec![Arc::new(RejectingDevice {
+        name,
+        status: StatusCode::UNAUTHORIZED,
+    })];
+    let mut ctx = RequestCtx::empty();
+
+    // Act
+    DevicePipeline::run_on_request(&devices, &mut ctx);
+    DevicePipeline::run_on_request(&devices, &mut ctx);
+
+    // Assert
+    assert_eq!(
+        DEVICE_METRICS.short_circuits(name, StatusCode::UNAUTHORIZED),
+        2
+    );
+    assert_eq!(
+        DEVICE_METRICS.short_circuits(name, StatusCode::FORBIDDEN),
+        0
+    );
+    assert!(
+        DEVICE_METRICS
+            .short_circuit_counts()
+            .iter()
+            .any(|c| c.device == name && c.status == 401 && c.count == 2)
+    );
+}
+
+#[test]
+fn continuing_device_is_not_counted() {
+    // Arrange
+    let devices: Vec<Arc<dyn Device>> = vec![Arc::new(RecordingDevice::default())];
+    let mut ctx = RequestCtx::empty();
+
+    // Act
+    DevicePipeline::run_on_request(&devices, &mut ctx);
+
+    // Assert
+    assert!(
+        DEVICE_METRICS
+            .short_circuit_counts()
+            .iter()
+            .all(|c| c.device != "Recording")
+    );
+}
+
+#[test]
+fn device_reads_listener_name_and_tls_flag_from_ctx() {
+    // Arrange
+    let device = Arc::new(ListenerAwareDevice::default());
+    let devices: Vec<Arc<dyn Device>> = vec![device.clone()];
+    let mut secure = RequestCtx::for_listener(listener("public-tls", false, true));
+    let mut plain = RequestCtx::for_listener(listener("public-http", false, false));
+
+    // Act
+    let secure_result = DevicePipeline::run_on_request(&devices, &mut secure);
+    let plain_result = DevicePipeline::run_on_request(&devices, &mut plain);
+
+    // Assert
+    assert!(matches!(secure_result, DeviceResult::Continue));
+    assert!(matches!(plain_result, DeviceResult::Continue));
+    assert_eq!(
+        *device.seen.lock().unwrap(),
+        vec![
+            ("public-tls".to_string(), true),
+            ("public-http".to_string(), false)
+        ]
+    );
+}
+
+#[test]
+fn device_branches_on_admin_listener() {
+    // Arrange
+    let devices: Vec<Arc<dyn Device>> = vec![Arc::new(ListenerAwareDevice::default())];
+    let mut admin = RequestCtx::for_listener(listener("admin", true, false));
+    let mut public = RequestCtx::for_listener(listener("public", false, false));
+
+    // Act
+    let admin_result = DevicePipeline::run_on_request(&devices, &mut admin);
+    let public_result = DevicePipeline::run_on_request(&devices, &mut public);
+
+    // Assert
+    assert!(matches!(
+        admin_result,
+        DeviceResult::Respond(resp) if resp.status == StatusCode::FORBIDDEN
+    ));
+    assert!(matches!(public_result, DeviceResult::Continue));
+}
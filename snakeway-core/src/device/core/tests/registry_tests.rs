@@ -0,0 +1,348 @@
+use crate::conf::types::{
+    CorsDeviceConfig, DeviceConfig, IdentityDeviceConfig, ListenerConfig,
+    RequestFilterDeviceConfig, ServerConfig, StatusRemapDeviceConfig,
+};
+use crate::conf::{RuntimeConfig, load_config};
+use crate::device::core::Device;
+use crate::device::core::registry::DeviceRegistry;
+use pretty_assertions::assert_eq;
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Arc;
+use tempfile::tempdir;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+fn listener(name: &str, enable_admin: bool, devices: &[&str]) -> ListenerConfig {
+    ListenerConfig {
+        name: name.to_string(),
+        addr: "127.0.0.1:0".to_string(),
+        tls: None,
+        enable_http2: false,
+        enable_admin,
+        redirect: None,
+        devices: devices.iter().map(|d| d.to_string()).collect(),
+    }
+}
+
+fn runtime_config(listeners: Vec<ListenerConfig>) -> RuntimeConfig {
+    RuntimeConfig {
+        server: ServerConfig {
+            version: 1,
+            threads: None,
+            pid_file: Default::default(),
+            ca_file: String::new(),
+            shutdown_timeout_seconds: 30,
+            shutdown_readiness_grace_seconds: 0,
+            strict_host: false,
+            hide_as_404: false,
+            max_request_body_bytes: None,
+            request_timeout_milliseconds: None,
+            ws_max_connections: None,
+            reuse_port: false,
+            connection_log_sample_rate: 1.0,
+            strict_wasm_devices: true,
+            trusted_proxies: Vec::new(),
+            access_log: false,
+            access_log_format: None,
+            geoip: Default::default(),
+        },
+        listeners,
+        routes: vec![],
+        services: HashMap::new(),
+        devices: vec![
+            DeviceConfig::RequestFilter(RequestFilterDeviceConfig {
+                enable: true,
+                ..Default::default()
+            }),
+            DeviceConfig::Identity(IdentityDeviceConfig {
+                enable: true,
+                ..Default::default()
+            }),
+        ],
+    }
+}
+
+/// A config loaded from disk, so its devices are in the order the devices file is parsed in.
+fn parsed_config(devices: &str) -> RuntimeConfig {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    fs::create_dir_all(root.join("devices.d")).unwrap();
+    fs::create_dir_all(root.join("ingress.d")).unwrap();
+    fs::write(
+        root.join("snakeway.hcl"),
+        r#"
+server {
+  version = 1
+}
+
+include {
+  devices = "devices.d/*.hcl"
+  ingress = "ingress.d/*.hcl"
+}
+"#,
+    )
+    .unwrap();
+    fs::write(root.join("devices.d/devices.hcl"), devices).unwrap();
+    fs::write(
+        root.join("ingress.d/api.hcl"),
+        r#"
+bind = {
+  interface = "127.0.0.1"
+  port      = 8080
+}
+
+services = [
+  {
+    load_balancing_strategy = "round_robin"
+
+    routes = [
+      {
+        path = "/api"
+      }
+    ]
+
+    upstreams = [
+      {
+        endpoint = { host = "127.0.0.1", port = 9001 }
+      }
+    ]
+  }
+]
+"#,
+    )
+    .unwrap();
+    load_config(root).unwrap().config
+}
+
+fn names(devices: &[Arc<dyn Device>]) -> Vec<&str> {
+    devices.iter().map(|d| d.name()).collect()
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn unattached_devices_run_on_every_public_listener() {
+    // Arrange
+    let cfg = runtime_config(vec![
+        listener("listener-0", false, &[]),
+        listener("listener-1", false, &[]),
+    ]);
+    let mut registry = DeviceRegistry::new();
+
+    // Act
+    registry.load_from_config(&cfg).unwrap();
+
+    // Assert
+    assert_eq!(
+        names(registry.for_listener("listener-0")),
+        vec!["Request Filter", "Identity"]
+    );
+    assert_eq!(
+        names(registry.for_listener("listener-1")),
+        vec!["Request Filter", "Identity"]
+    );
+}
+
+#[test]
+fn attached_device_only_runs_on_its_listener() {
+    // Arrange
+    let cfg = runtime_config(vec![
+        listener("listener-0", false, &["request_filter"]),
+        listener("listener-1", false, &[]),
+    ]);
+    let mut registry = DeviceRegistry::new();
+
+    // Act
+    registry.load_from_config(&cfg).unwrap();
+
+    // Assert
+    assert_eq!(
+        names(registry.for_listener("listener-0")),
+        vec!["Request Filter", "Identity"]
+    );
+    assert_eq!(names(registry.for_listener("listener-1")), vec!["Identity"]);
+    assert_eq!(registry.all().len(), 2);
+}
+
+#[test]
+fn admin_listener_only_runs_attached_devices() {
+    // Arrange
+    let cfg = runtime_config(vec![
+        listener("listener-0", false, &[]),
+        listener("admin-listener-0", true, &["request_filter"]),
+    ]);
+    let mut registry = DeviceRegistry::new();
+
+    // Act
+    registry.load_from_config(&cfg).unwrap();
+
+    // Assert
+    assert_eq!(
+        names(registry.for_admin_listener("admin-listener-0")),
+        vec!["Request Filter"]
+    );
+    assert_eq!(names(registry.for_listener("listener-0")), vec!["Identity"]);
+}
+
+#[test]
+fn admin_listener_without_attachments_runs_no_devices() {
+    // Arrange
+    let cfg = runtime_config(vec![
+        listener("listener-0", false, &[]),
+        listener("admin-listener-0", true, &[]),
+    ]);
+    let mut registry = DeviceRegistry::new();
+
+    // Act
+    registry.load_from_config(&cfg).unwrap();
+
+    // Assert
+    assert!(registry.for_admin_listener("admin-listener-0").is_empty());
+}
+
+#[test]
+fn devices_run_in_priority_order() {
+    // Arrange
+    let mut cfg = runtime_config(vec![listener("listener-0", false, &["cors"])]);
+    cfg.devices = vec![
+        DeviceConfig::RequestFilter(RequestFilterDeviceConfig {
+            enable: true,
+            priority: 10,
+            ..Default::default()
+        }),
+        DeviceConfig::Identity(IdentityDeviceConfig {
+            enable: true,
+            ..Default::default()
+        }),
+        DeviceConfig::Cors(CorsDeviceConfig {
+            enable: true,
+            ..Default::default()
+        }),
+        DeviceConfig::StatusRemap(StatusRemapDeviceConfig {
+            enable: true,
+            priority: -5,
+            ..Default::default()
+        }),
+    ];
+    let mut registry = DeviceRegistry::new();
+
+    // Act
+    registry.load_from_config(&cfg).unwrap();
+
+    // Assert
+    assert_eq!(
+        names(registry.for_listener("listener-0")),
+        vec!["Status Remap", "Identity", "CORS", "Request Filter"]
+    );
+    assert_eq!(
+        names(registry.all()),
+        vec!["Status Remap", "Identity", "CORS", "Request Filter"]
+    );
+    let pipeline: Vec<&str> = cfg
+        .device_pipeline(&cfg.listeners[0])
+        .iter()
+        .map(|d| d.name())
+        .collect();
+    assert_eq!(
+        pipeline,
+        vec!["status_remap", "identity", "cors", "request_filter"]
+    );
+}
+
+#[test]
+fn scoped_device_is_skipped_outside_its_routes() {
+    // Arrange
+    let mut cfg = runtime_config(vec![listener("listener-0", false, &[])]);
+    cfg.devices = vec![
+        DeviceConfig::RequestFilter(RequestFilterDeviceConfig {
+            enable: true,
+            routes: vec!["/api".to_string()],
+            ..Default::default()
+        }),
+        DeviceConfig::Identity(IdentityDeviceConfig {
+            enable: true,
+            ..Default::default()
+        }),
+    ];
+    let mut registry = DeviceRegistry::new();
+
+    // Act
+    registry.load_from_config(&cfg).unwrap();
+
+    // Assert
+    assert_eq!(
+        names(&registry.for_request("listener-0", "/api/users")),
+        vec!["Request Filter", "Identity"]
+    );
+    assert_eq!(
+        names(&registry.for_request("listener-0", "/public")),
+        vec!["Identity"]
+    );
+    assert_eq!(
+        names(registry.for_listener("listener-0")),
+        vec!["Request Filter", "Identity"]
+    );
+}
+
+#[test]
+fn devices_are_found_by_name() {
+    // Arrange
+    let cfg = runtime_config(vec![listener("listener-0", false, &[])]);
+    let mut registry = DeviceRegistry::new();
+
+    // Act
+    registry.load_from_config(&cfg).unwrap();
+
+    // Assert
+    assert_eq!(
+        registry.get("identity").map(|device| device.name()),
+        Some("Identity")
+    );
+    assert!(registry.get("rate_limit").is_none());
+    assert_eq!(
+        registry.named().map(|(name, _)| name).collect::<Vec<_>>(),
+        vec!["request_filter", "identity"]
+    );
+}
+
+#[test]
+fn header_rewrite_runs_before_required_headers_are_checked() {
+    // Arrange
+    let cfg = parsed_config(
+        r#"
+required_headers_device = {
+  enable = true
+
+  headers = [{ name = "X-Tenant" }]
+}
+
+header_rewrite_device = {
+  enable = true
+
+  request_set = [{ name = "X-Tenant", value = "default" }]
+}
+
+identity_device = {
+  enable = true
+
+  trusted_proxies   = []
+  enable_geoip      = false
+  enable_user_agent = false
+  ua_engine         = "woothee"
+}
+"#,
+    );
+    let mut registry = DeviceRegistry::new();
+
+    // Act
+    registry.load_from_config(&cfg).unwrap();
+
+    // Assert
+    assert_eq!(
+        names(registry.for_listener(&cfg.listeners[0].name)),
+        vec!["Identity", "Header Rewrite", "Required Headers"]
+    );
+}
@@ -1,5 +1,6 @@
 pub mod builtin;
 pub mod core;
+pub mod plugin;
 #[cfg(feature = "wasm")]
 pub(crate) mod wasm;
 
@@ -7,6 +8,14 @@ use crate::device::core::Device;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+#[cfg(feature = "wasm")]
+pub use wasm::module_cache::WasmModuleCache;
+
+/// Without the `wasm` feature there are no modules to cache.
+#[cfg(not(feature = "wasm"))]
+#[derive(Default)]
+pub struct WasmModuleCache;
+
 #[cfg(not(feature = "wasm"))]
 pub fn load_wasm_device(_device_file_path: &PathBuf) -> anyhow::Result<Arc<dyn Device>> {
     Err(anyhow::anyhow!(
@@ -16,6 +25,13 @@ pub fn load_wasm_device(_device_file_path: &PathBuf) -> anyhow::Result<Arc<dyn D
 
 #[cfg(feature = "wasm")]
 pub fn load_wasm_device(device_file_path: &PathBuf) -> anyhow::Result<Arc<dyn Device>> {
-    let device = crate::device::wasm::wasm_device::WasmDevice::load(device_file_path)?;
+    let (module, config) = plugin::resolve_wasm_module(device_file_path, None)?;
+    let device = crate::device::wasm::wasm_device::WasmDevice::load(
+        &WasmModuleCache::default(),
+        &module,
+        crate::conf::types::DEFAULT_WASM_DEVICE_FUEL,
+        false,
+        config.as_ref(),
+    )?;
     Ok(Arc::new(device))
 }
@@ -0,0 +1,123 @@
+use crate::device::plugin::manifest::{PLUGIN_MANIFEST_FILE, PluginError, PluginManifest};
+use hcl::Value;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A directory holding a `plugin.hcl` manifest and the WASM module it describes.
+#[derive(Debug, Clone)]
+pub struct PluginBundle {
+    pub dir: PathBuf,
+    pub manifest: PluginManifest,
+}
+
+pub fn is_plugin_bundle(path: &Path) -> bool {
+    path.join(PLUGIN_MANIFEST_FILE).is_file()
+}
+
+/// The module to load for a WASM device path, and the device's config with the plugin's
+/// defaults filled in.
+///
+/// A plugin bundle resolves to its module, once checked against this host. Any other path
+/// is the module itself.
+pub fn resolve_wasm_module(
+    path: &Path,
+    config: Option<&Value>,
+) -> Result<(PathBuf, Option<Value>), PluginError> {
+    if !is_plugin_bundle(path) {
+        return Ok((path.to_path_buf(), config.cloned()));
+    }
+
+    let bundle = PluginBundle::open(path)?;
+    bundle.manifest.check_host()?;
+    Ok((
+        bundle.module_path(),
+        bundle.manifest.config_with_defaults(config),
+    ))
+}
+
+impl PluginBundle {
+    /// Open a bundle, checking its module against the checksum in the manifest, if any.
+    pub fn open(dir: &Path) -> Result<Self, PluginError> {
+        let manifest = PluginManifest::read(&dir.join(PLUGIN_MANIFEST_FILE))?;
+        let bundle = Self {
+            dir: dir.to_path_buf(),
+            manifest,
+        };
+
+        let module = bundle.module_path();
+        let bytes = fs::read(&module).map_err(|source| PluginError::Read {
+            path: module.clone(),
+            source,
+        })?;
+        if let Some(expected) = &bundle.manifest.module_sha256
+            && !expected.eq_ignore_ascii_case(&sha256_hex(&bytes))
+        {
+            return Err(PluginError::ChecksumMismatch { path: module });
+        }
+
+        Ok(bundle)
+    }
+
+    pub fn module_path(&self) -> PathBuf {
+        self.dir.join(&self.manifest.module)
+    }
+
+    /// Pack the module a manifest describes into a bundle at `out`.
+    ///
+    /// The manifest is checked against this host, and its `default_config` against its
+    /// `config_schema`. The module is copied next to the manifest, with its checksum recorded.
+    pub fn pack(manifest_path: &Path, out: &Path) -> Result<Self, PluginError> {
+        let mut manifest = PluginManifest::read(manifest_path)?;
+        manifest.check_host()?;
+        if let Some(defaults) = &manifest.default_config {
+            let violations = manifest.config_violations(Some(defaults))?;
+            if !violations.is_empty() {
+                return Err(PluginError::InvalidDefaultConfig(violations));
+            }
+        }
+
+        let module = manifest_path
+            .parent()
+            .unwrap_or(Path::new("."))
+            .join(&manifest.module);
+        let bytes = fs::read(&module).map_err(|source| PluginError::Read {
+            path: module.clone(),
+            source,
+        })?;
+        let Some(file_name) = module.file_name() else {
+            return Err(PluginError::InvalidManifest {
+                path: manifest_path.to_path_buf(),
+                message: format!("module {} has no file name", module.display()),
+            });
+        };
+        manifest.module = PathBuf::from(file_name);
+        manifest.module_sha256 = Some(sha256_hex(&bytes));
+
+        let write = |path: PathBuf, contents: &[u8]| {
+            fs::write(&path, contents).map_err(|source| PluginError::Write { path, source })
+        };
+        fs::create_dir_all(out).map_err(|source| PluginError::Write {
+            path: out.to_path_buf(),
+            source,
+        })?;
+        write(out.join(&manifest.module), &bytes)?;
+        let source = hcl::to_string(&manifest).map_err(|e| PluginError::InvalidManifest {
+            path: manifest_path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+        write(out.join(PLUGIN_MANIFEST_FILE), source.as_bytes())?;
+
+        Ok(Self {
+            dir: out.to_path_buf(),
+            manifest,
+        })
+    }
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
@@ -0,0 +1,173 @@
+use hcl::Value;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File name of the manifest at the root of a plugin bundle.
+pub const PLUGIN_MANIFEST_FILE: &str = "plugin.hcl";
+
+/// Version of the `snakeway:device` WIT package this host implements.
+pub const HOST_ABI_VERSION: &str = "0.2.0";
+
+/// Capabilities this host provides, which a plugin can require:
+///
+/// - `policy`: the request and response hooks
+/// - `ws`: the WebSocket lifecycle hooks
+/// - `config`: the `init` hook, called with the device's config
+/// - `host`: the host functions, e.g. `redirect`
+/// - `wasi`: WASI preview 2
+pub const HOST_CAPABILITIES: [&str; 5] = ["policy", "ws", "config", "host", "wasi"];
+
+/// Describes a WASM device plugin: what it is, what it needs from the host, and how it is
+/// configured.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PluginManifest {
+    pub name: String,
+
+    pub version: String,
+
+    /// Version of the `snakeway:device` WIT package the module was built against.
+    pub abi: String,
+
+    /// Path of the WASM module, relative to the manifest.
+    pub module: PathBuf,
+
+    /// SHA-256 of the module, hex encoded. Set by `plugin pack`, and checked when the bundle
+    /// is opened.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub module_sha256: Option<String>,
+
+    /// Host capabilities the plugin requires, see `HOST_CAPABILITIES`.
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+
+    /// JSON Schema the device's `config` block must match, written as HCL.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_schema: Option<Value>,
+
+    /// Config for the top-level keys the device's `config` block leaves unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_config: Option<Value>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PluginError {
+    #[error("failed to read {path}: {source}")]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("failed to write {path}: {source}")]
+    Write {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("invalid plugin manifest {path}: {message}")]
+    InvalidManifest { path: PathBuf, message: String },
+
+    #[error("plugin ABI {abi} is not supported by this host, which implements {host}", host = HOST_ABI_VERSION)]
+    UnsupportedAbi { abi: String },
+
+    #[error("plugin requires host capabilities this host does not provide: {}", .0.join(", "))]
+    UnsupportedCapabilities(Vec<String>),
+
+    #[error("plugin module {path} does not match the checksum in its manifest")]
+    ChecksumMismatch { path: PathBuf },
+
+    #[error("invalid plugin config_schema: {0}")]
+    InvalidConfigSchema(String),
+
+    #[error("plugin default_config does not match its config_schema: {}", .0.join("; "))]
+    InvalidDefaultConfig(Vec<String>),
+}
+
+impl PluginManifest {
+    pub fn read(path: &Path) -> Result<Self, PluginError> {
+        let s = fs::read_to_string(path).map_err(|source| PluginError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        hcl::from_str(&s).map_err(|e| PluginError::InvalidManifest {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })
+    }
+
+    /// Check that this host implements the plugin's ABI and provides every capability it requires.
+    pub fn check_host(&self) -> Result<(), PluginError> {
+        if !abi_is_supported(&self.abi) {
+            return Err(PluginError::UnsupportedAbi {
+                abi: self.abi.clone(),
+            });
+        }
+
+        let missing: Vec<String> = self
+            .capabilities
+            .iter()
+            .filter(|c| !HOST_CAPABILITIES.contains(&c.as_str()))
+            .cloned()
+            .collect();
+        if !missing.is_empty() {
+            return Err(PluginError::UnsupportedCapabilities(missing));
+        }
+
+        Ok(())
+    }
+
+    /// The device's config, with `default_config` filling in the top-level keys it leaves unset.
+    pub fn config_with_defaults(&self, config: Option<&Value>) -> Option<Value> {
+        match (self.default_config.clone(), config) {
+            (Some(Value::Object(mut defaults)), Some(Value::Object(config))) => {
+                defaults.extend(config.clone());
+                Some(Value::Object(defaults))
+            }
+            (defaults, None) => defaults,
+            (_, Some(config)) => Some(config.clone()),
+        }
+    }
+
+    /// How the config violates `config_schema`, one message per violation.
+    /// A plugin without a schema accepts any config.
+    pub fn config_violations(&self, config: Option<&Value>) -> Result<Vec<String>, PluginError> {
+        let Some(schema) = &self.config_schema else {
+            return Ok(Vec::new());
+        };
+
+        let schema = serde_json::to_value(schema)
+            .map_err(|e| PluginError::InvalidConfigSchema(e.to_string()))?;
+        let validator = jsonschema::validator_for(&schema)
+            .map_err(|e| PluginError::InvalidConfigSchema(e.to_string()))?;
+        let instance = serde_json::to_value(config)
+            .map_err(|e| PluginError::InvalidConfigSchema(e.to_string()))?;
+
+        Ok(validator
+            .iter_errors(&instance)
+            .map(|e| e.to_string())
+            .collect())
+    }
+}
+
+/// ABI versions follow semver, so a plugin runs on a host with the same major version and at
+/// least its minor version. Before 1.0, every minor version is breaking.
+fn abi_is_supported(abi: &str) -> bool {
+    let parse = |version: &str| -> Option<(u64, u64)> {
+        let mut parts = version.split('.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next()?.parse().ok()?;
+        Some((major, minor))
+    };
+
+    match (parse(abi), parse(HOST_ABI_VERSION)) {
+        (Some((0, minor)), Some((0, host_minor))) => minor == host_minor,
+        (Some((major, minor)), Some((host_major, host_minor))) => {
+            major == host_major && minor <= host_minor
+        }
+        _ => false,
+    }
+}
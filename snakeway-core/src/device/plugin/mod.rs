@@ -0,0 +1,9 @@
+mod bundle;
+mod manifest;
+#[cfg(test)]
+mod tests;
+
+pub use bundle::{PluginBundle, is_plugin_bundle, resolve_wasm_module};
+pub use manifest::{
+    HOST_ABI_VERSION, HOST_CAPABILITIES, PLUGIN_MANIFEST_FILE, PluginError, PluginManifest,
+};
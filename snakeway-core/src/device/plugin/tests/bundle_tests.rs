@@ -0,0 +1,142 @@
+use crate::device::plugin::{
+    HOST_ABI_VERSION, PLUGIN_MANIFEST_FILE, PluginBundle, PluginError, is_plugin_bundle,
+    resolve_wasm_module,
+};
+use pretty_assertions::assert_eq;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::tempdir;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+/// Write a module and a manifest describing it to `dir`, and return the manifest path.
+fn write_plugin(dir: &Path, abi: &str, capabilities: &str) -> PathBuf {
+    fs::create_dir_all(dir.join("target")).unwrap();
+    fs::write(dir.join("target/path_blocker.wasm"), "(component)").unwrap();
+
+    let manifest = dir.join(PLUGIN_MANIFEST_FILE);
+    fs::write(
+        &manifest,
+        format!(
+            r#"
+name         = "path_blocker"
+version      = "1.2.0"
+abi          = "{abi}"
+module       = "target/path_blocker.wasm"
+capabilities = {capabilities}
+
+config_schema = {{
+  type       = "object"
+  properties = {{
+    block_paths = {{ type = "array", items = {{ type = "string" }} }}
+  }}
+}}
+
+default_config = {{
+  block_paths = ["/admin"]
+}}
+"#
+        ),
+    )
+    .unwrap();
+    manifest
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn pack_copies_module_and_records_its_checksum() {
+    // Arrange
+    let src = tempdir().unwrap();
+    let manifest = write_plugin(src.path(), HOST_ABI_VERSION, r#"["policy", "config"]"#);
+    let dest = tempdir().unwrap();
+    let out = dest.path().join("path_blocker.plugin");
+
+    // Act
+    let packed = PluginBundle::pack(&manifest, &out).unwrap();
+
+    // Assert
+    assert!(is_plugin_bundle(&out));
+    assert_eq!(packed.module_path(), out.join("path_blocker.wasm"));
+    assert_eq!(
+        fs::read_to_string(packed.module_path()).unwrap(),
+        "(component)"
+    );
+    assert_eq!(
+        packed.manifest.module_sha256.as_ref().map(String::len),
+        Some(64)
+    );
+}
+
+#[test]
+fn inspecting_a_packed_bundle_reads_back_its_manifest() {
+    // Arrange
+    let src = tempdir().unwrap();
+    let manifest = write_plugin(src.path(), HOST_ABI_VERSION, r#"["policy", "config"]"#);
+    let dest = tempdir().unwrap();
+    let out = dest.path().join("path_blocker.plugin");
+    let packed = PluginBundle::pack(&manifest, &out).unwrap();
+
+    // Act
+    let opened = PluginBundle::open(&out).unwrap();
+
+    // Assert
+    assert_eq!(opened.manifest, packed.manifest);
+    assert_eq!(opened.manifest.name, "path_blocker");
+    assert_eq!(opened.manifest.capabilities, vec!["policy", "config"]);
+}
+
+#[test]
+fn opening_a_bundle_with_a_modified_module_fails() {
+    // Arrange
+    let src = tempdir().unwrap();
+    let manifest = write_plugin(src.path(), HOST_ABI_VERSION, r#"["policy"]"#);
+    let dest = tempdir().unwrap();
+    let out = dest.path().join("path_blocker.plugin");
+    PluginBundle::pack(&manifest, &out).unwrap();
+    fs::write(out.join("path_blocker.wasm"), "(component tampered)").unwrap();
+
+    // Act
+    let result = PluginBundle::open(&out);
+
+    // Assert
+    assert!(matches!(result, Err(PluginError::ChecksumMismatch { .. })));
+}
+
+#[test]
+fn pack_rejects_capabilities_the_host_does_not_provide() {
+    // Arrange
+    let src = tempdir().unwrap();
+    let manifest = write_plugin(src.path(), HOST_ABI_VERSION, r#"["policy", "sockets"]"#);
+    let out = src.path().join("path_blocker.plugin");
+
+    // Act
+    let result = PluginBundle::pack(&manifest, &out);
+
+    // Assert
+    assert!(matches!(
+        result,
+        Err(PluginError::UnsupportedCapabilities(_))
+    ));
+    assert!(!out.exists());
+}
+
+#[test]
+fn resolving_a_bundle_fills_in_default_config() {
+    // Arrange
+    let src = tempdir().unwrap();
+    let manifest = write_plugin(src.path(), HOST_ABI_VERSION, r#"["policy", "config"]"#);
+    let dest = tempdir().unwrap();
+    let out = dest.path().join("path_blocker.plugin");
+    PluginBundle::pack(&manifest, &out).unwrap();
+
+    // Act
+    let (module, config) = resolve_wasm_module(&out, None).unwrap();
+
+    // Assert
+    assert_eq!(module, out.join("path_blocker.wasm"));
+    let expected: hcl::Value = hcl::from_str(r#"block_paths = ["/admin"]"#).unwrap();
+    assert_eq!(config, Some(expected));
+}
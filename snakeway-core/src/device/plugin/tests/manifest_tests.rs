@@ -0,0 +1,114 @@
+use crate::device::plugin::{HOST_ABI_VERSION, PluginError, PluginManifest};
+use pretty_assertions::assert_eq;
+use std::path::PathBuf;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+fn manifest(abi: &str, capabilities: &[&str]) -> PluginManifest {
+    PluginManifest {
+        name: "path_blocker".to_string(),
+        version: "1.0.0".to_string(),
+        abi: abi.to_string(),
+        module: PathBuf::from("path_blocker.wasm"),
+        module_sha256: None,
+        capabilities: capabilities.iter().map(|c| c.to_string()).collect(),
+        config_schema: None,
+        default_config: None,
+    }
+}
+
+fn value(source: &str) -> hcl::Value {
+    hcl::from_str(source).unwrap()
+}
+
+fn with_schema(mut manifest: PluginManifest) -> PluginManifest {
+    manifest.config_schema = Some(value(
+        r#"
+type       = "object"
+required   = ["block_paths"]
+properties = {
+  block_paths = { type = "array", items = { type = "string" } }
+  status      = { type = "integer" }
+}
+"#,
+    ));
+    manifest
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn host_supports_its_own_abi_and_capabilities() {
+    // Arrange
+    let manifest = manifest(HOST_ABI_VERSION, &["policy", "config", "host"]);
+
+    // Act
+    let result = manifest.check_host();
+
+    // Assert
+    assert!(result.is_ok());
+}
+
+#[test]
+fn host_rejects_another_pre_release_abi() {
+    // Arrange
+    let manifest = manifest("0.3.0", &["policy"]);
+
+    // Act
+    let result = manifest.check_host();
+
+    // Assert
+    assert!(matches!(result, Err(PluginError::UnsupportedAbi { abi }) if abi == "0.3.0"));
+}
+
+#[test]
+fn host_rejects_unknown_capabilities() {
+    // Arrange
+    let manifest = manifest(HOST_ABI_VERSION, &["policy", "filesystem", "sockets"]);
+
+    // Act
+    let result = manifest.check_host();
+
+    // Assert
+    let Err(PluginError::UnsupportedCapabilities(missing)) = result else {
+        panic!("expected unsupported capabilities, got {result:?}");
+    };
+    assert_eq!(missing, vec!["filesystem", "sockets"]);
+}
+
+#[test]
+fn device_config_overrides_default_config_per_key() {
+    // Arrange
+    let mut manifest = manifest(HOST_ABI_VERSION, &[]);
+    manifest.default_config = Some(value("block_paths = [\"/admin\"]\nstatus = 403"));
+    let config = value("status = 404");
+
+    // Act
+    let merged = manifest.config_with_defaults(Some(&config));
+
+    // Assert
+    assert_eq!(
+        merged,
+        Some(value("block_paths = [\"/admin\"]\nstatus = 404"))
+    );
+}
+
+#[test]
+fn config_is_checked_against_schema() {
+    // Arrange
+    let manifest = with_schema(manifest(HOST_ABI_VERSION, &["config"]));
+    let valid = value(r#"block_paths = ["/admin"]"#);
+    let invalid = value(r#"status = "teapot""#);
+
+    // Act
+    let valid = manifest.config_violations(Some(&valid)).unwrap();
+    let invalid = manifest.config_violations(Some(&invalid)).unwrap();
+    let missing = manifest.config_violations(None).unwrap();
+
+    // Assert
+    assert_eq!(valid, Vec::<String>::new());
+    assert_eq!(invalid.len(), 2, "{invalid:?}");
+    assert_eq!(missing.len(), 1, "{missing:?}");
+}
@@ -0,0 +1,2 @@
+mod bundle_tests;
+mod manifest_tests;
@@ -0,0 +1,80 @@
+use crate::ctx::ResponseCtx;
+use crate::device::wasm::bindings::snakeway::device::host::Host;
+use crate::device::wasm::wasm_device::HostState;
+use http::{HeaderMap, HeaderName, HeaderValue, StatusCode, header};
+
+/// Statuses a device may redirect with.
+const REDIRECT_STATUSES: [u16; 5] = [301, 302, 303, 307, 308];
+
+/// Response a guest shapes through host functions, sent when its hook returns `block`.
+#[derive(Debug, Default)]
+pub(crate) struct BlockResponse {
+    pub(crate) status: Option<StatusCode>,
+    pub(crate) headers: HeaderMap,
+    pub(crate) body: Option<Vec<u8>>,
+}
+
+impl BlockResponse {
+    /// Build the response, falling back to a `403` with "Blocked by device" for unset parts.
+    pub(crate) fn into_response(self, request_id: Option<String>) -> ResponseCtx {
+        let BlockResponse {
+            status,
+            mut headers,
+            body,
+        } = self;
+
+        if body.is_some() && !headers.contains_key(header::CONTENT_TYPE) {
+            headers.insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/octet-stream"),
+            );
+        }
+
+        ResponseCtx::new(
+            request_id,
+            status.unwrap_or(StatusCode::FORBIDDEN),
+            headers,
+            body.unwrap_or_else(|| b"Blocked by device".to_vec()),
+        )
+    }
+}
+
+impl Host for HostState {
+    fn redirect(&mut self, status: u16, location: String) -> Result<(), String> {
+        if !REDIRECT_STATUSES.contains(&status) {
+            return Err(format!("{status} is not a redirect status"));
+        }
+        let status = StatusCode::from_u16(status).map_err(|e| e.to_string())?;
+        let location = HeaderValue::from_str(&location)
+            .map_err(|_| format!("invalid redirect location: {location}"))?;
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::LOCATION, location);
+        self.response = Some(ResponseCtx::new(None, status, headers, Vec::new()));
+        Ok(())
+    }
+
+    fn set_status(&mut self, code: u16) -> Result<(), String> {
+        if !(200..=599).contains(&code) {
+            return Err(format!("{code} is not a valid response status"));
+        }
+        let status = StatusCode::from_u16(code).map_err(|e| e.to_string())?;
+
+        self.block_response.status = Some(status);
+        Ok(())
+    }
+
+    fn set_response_header(&mut self, name: String, value: String) -> Result<(), String> {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| format!("invalid response header name: {name}"))?;
+        let value = HeaderValue::from_str(&value)
+            .map_err(|_| format!("invalid value for response header {name}"))?;
+
+        self.block_response.headers.append(name, value);
+        Ok(())
+    }
+
+    fn set_response_body(&mut self, body: Vec<u8>) {
+        self.block_response.body = Some(body);
+    }
+}
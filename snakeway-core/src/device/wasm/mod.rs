@@ -1,2 +1,6 @@
 pub mod bindings;
+mod host;
+pub mod module_cache;
+#[cfg(test)]
+mod tests;
 pub mod wasm_device;
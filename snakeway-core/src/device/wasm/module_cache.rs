@@ -0,0 +1,95 @@
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::SystemTime;
+use wasmtime::component::Component;
+use wasmtime::{Config, Engine};
+
+/// Compiled WASM components, kept across config reloads so unchanged devices are not recompiled.
+///
+/// Entries are keyed by path. A file whose modification time and size are unchanged is reused
+/// as-is; otherwise it is read and hashed, and only recompiled if its contents changed.
+#[derive(Default)]
+pub struct WasmModuleCache {
+    inner: Mutex<Inner>,
+    compilations: AtomicUsize,
+}
+
+#[derive(Default)]
+struct Inner {
+    /// Created on first use. Components can only be instantiated with the engine that compiled them.
+    engine: Option<Engine>,
+    modules: HashMap<PathBuf, CachedModule>,
+}
+
+struct CachedModule {
+    fingerprint: Option<(SystemTime, u64)>,
+    hash: [u8; 32],
+    component: Component,
+}
+
+impl WasmModuleCache {
+    /// The compiled component for the file, compiling it only if it is new or changed.
+    pub fn load(&self, path: &Path) -> Result<(Engine, Component)> {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let engine = match &inner.engine {
+            Some(engine) => engine.clone(),
+            None => {
+                let mut config = Config::new();
+                config.consume_fuel(true);
+                let engine = Engine::new(&config)?;
+                inner.engine = Some(engine.clone());
+                engine
+            }
+        };
+
+        let fingerprint = fingerprint(path);
+        if let Some(cached) = inner.modules.get(path)
+            && fingerprint.is_some()
+            && cached.fingerprint == fingerprint
+        {
+            return Ok((engine, cached.component.clone()));
+        }
+
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("failed to read WASM module {}", path.display()))?;
+        let hash: [u8; 32] = Sha256::digest(&bytes).into();
+
+        if let Some(cached) = inner.modules.get_mut(path)
+            && cached.hash == hash
+        {
+            cached.fingerprint = fingerprint;
+            return Ok((engine, cached.component.clone()));
+        }
+
+        tracing::info!(path = %path.display(), "compiling WASM module");
+        let component = Component::new(&engine, &bytes)
+            .with_context(|| format!("failed to compile WASM module {}", path.display()))?;
+        self.compilations.fetch_add(1, Ordering::Relaxed);
+
+        inner.modules.insert(
+            path.to_path_buf(),
+            CachedModule {
+                fingerprint,
+                hash,
+                component: component.clone(),
+            },
+        );
+
+        Ok((engine, component))
+    }
+
+    /// Number of modules compiled so far.
+    pub fn compilations(&self) -> usize {
+        self.compilations.load(Ordering::Relaxed)
+    }
+}
+
+/// Modification time and size, used to skip hashing a file that has not been touched.
+fn fingerprint(path: &Path) -> Option<(SystemTime, u64)> {
+    let meta = std::fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
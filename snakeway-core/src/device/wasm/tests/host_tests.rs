@@ -0,0 +1,121 @@
+use crate::device::wasm::bindings::snakeway::device::host::Host;
+use crate::device::wasm::wasm_device::HostState;
+use http::{StatusCode, header};
+use pretty_assertions::assert_eq;
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn redirect_sets_status_and_location() {
+    // Arrange
+    let mut state = HostState::new();
+
+    // Act
+    let result = state.redirect(308, "https://example.com/login".to_string());
+
+    // Assert
+    assert_eq!(result, Ok(()));
+    let resp = state.response.unwrap();
+    assert_eq!(resp.status, StatusCode::PERMANENT_REDIRECT);
+    assert_eq!(
+        resp.headers.get(header::LOCATION).unwrap(),
+        "https://example.com/login"
+    );
+    assert!(resp.body.is_empty());
+}
+
+#[test]
+fn redirect_rejects_non_redirect_status() {
+    // Arrange
+    let mut state = HostState::new();
+
+    // Act
+    let result = state.redirect(200, "https://example.com/".to_string());
+
+    // Assert
+    assert_eq!(result, Err("200 is not a redirect status".to_string()));
+    assert!(state.response.is_none());
+}
+
+#[test]
+fn redirect_rejects_invalid_location() {
+    // Arrange
+    let mut state = HostState::new();
+
+    // Act
+    let result = state.redirect(302, "https://example.com/\nSet-Cookie: x".to_string());
+
+    // Assert
+    assert!(result.is_err());
+    assert!(state.response.is_none());
+}
+
+#[test]
+fn block_response_defaults_to_403() {
+    // Arrange
+    let state = HostState::new();
+
+    // Act
+    let resp = state.block_response.into_response(None);
+
+    // Assert
+    assert_eq!(resp.status, StatusCode::FORBIDDEN);
+    assert_eq!(resp.body, b"Blocked by device".to_vec());
+    assert!(resp.headers.is_empty());
+}
+
+#[test]
+fn block_response_uses_status_headers_and_body() {
+    // Arrange
+    let mut state = HostState::new();
+    let body = br#"{"error":"rate limited"}"#.to_vec();
+
+    // Act
+    state.set_status(429).unwrap();
+    state
+        .set_response_header("content-type".to_string(), "application/json".to_string())
+        .unwrap();
+    state.set_response_body(body.clone());
+    let resp = state
+        .block_response
+        .into_response(Some("req-1".to_string()));
+
+    // Assert
+    assert_eq!(resp.status, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(
+        resp.headers.get(header::CONTENT_TYPE).unwrap(),
+        "application/json"
+    );
+    assert_eq!(resp.body, body);
+    assert_eq!(resp.request_id, Some("req-1".to_string()));
+}
+
+#[test]
+fn set_status_rejects_out_of_range_code() {
+    // Arrange
+    let mut state = HostState::new();
+
+    // Act
+    let result = state.set_status(103);
+
+    // Assert
+    assert_eq!(
+        result,
+        Err("103 is not a valid response status".to_string())
+    );
+    assert!(state.block_response.status.is_none());
+}
+
+#[test]
+fn set_response_header_rejects_invalid_name() {
+    // Arrange
+    let mut state = HostState::new();
+
+    // Act
+    let result = state.set_response_header("bad header".to_string(), "x".to_string());
+
+    // Assert
+    assert!(result.is_err());
+    assert!(state.block_response.headers.is_empty());
+}
@@ -0,0 +1,3 @@
+mod host_tests;
+mod module_cache_tests;
+mod wasm_device_tests;
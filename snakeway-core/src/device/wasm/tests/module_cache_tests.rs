@@ -0,0 +1,140 @@
+use crate::device::wasm::module_cache::WasmModuleCache;
+use crate::runtime::{build_runtime_state, reload_runtime_state};
+use arc_swap::ArcSwap;
+use pretty_assertions::assert_eq;
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+/// The smallest valid component, in text format.
+const EMPTY_COMPONENT: &str = "(component)";
+
+/// Writes a config directory with a single WASM device. `extra` is added to the device block.
+pub(super) fn write_config(root: &Path, module: &Path, extra: &str) {
+    fs::create_dir_all(root.join("devices.d")).unwrap();
+    fs::create_dir_all(root.join("ingress.d")).unwrap();
+    fs::write(
+        root.join("snakeway.hcl"),
+        r#"
+server {
+  version = 1
+}
+
+include {
+  devices = "devices.d/*.hcl"
+  ingress = "ingress.d/*.hcl"
+}
+"#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("ingress.d/api.hcl"),
+        r#"
+bind = {
+  interface    = "127.0.0.1"
+  port         = 8080
+  enable_http2 = false
+}
+
+services = [
+  {
+    routes    = [{ path = "/" }]
+    upstreams = [{ endpoint = { host = "127.0.0.1", port = 9001 } }]
+  }
+]
+"#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("devices.d/wasm.hcl"),
+        format!(
+            r#"
+wasm_devices = [
+  {{
+    enable = true
+    path   = "{}"
+    {}
+  }}
+]
+"#,
+            module.display(),
+            extra
+        ),
+    )
+    .unwrap();
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[tokio::test]
+async fn reloading_unchanged_config_compiles_once() {
+    // Arrange
+    let dir = tempdir().unwrap();
+    let module = dir.path().join("device.wasm");
+    fs::write(&module, EMPTY_COMPONENT).unwrap();
+    write_config(dir.path(), &module, "");
+
+    let validated = crate::conf::load_config(dir.path()).unwrap();
+    let state = ArcSwap::from_pointee(build_runtime_state(&validated.config).unwrap());
+
+    // Act
+    for _ in 0..50 {
+        reload_runtime_state(dir.path(), &state).await.unwrap();
+    }
+
+    // Assert
+    assert_eq!(state.load().devices.all().len(), 1);
+    assert_eq!(state.load().wasm_modules.compilations(), 1);
+}
+
+#[test]
+fn changed_module_is_recompiled() {
+    // Arrange
+    let dir = tempdir().unwrap();
+    let module = dir.path().join("device.wasm");
+    fs::write(&module, EMPTY_COMPONENT).unwrap();
+    let cache = WasmModuleCache::default();
+    cache.load(&module).unwrap();
+
+    // Act
+    fs::write(&module, format!("{EMPTY_COMPONENT} ;; v2")).unwrap();
+    cache.load(&module).unwrap();
+
+    // Assert
+    assert_eq!(cache.compilations(), 2);
+}
+
+#[test]
+fn rewritten_module_with_same_contents_is_reused() {
+    // Arrange
+    let dir = tempdir().unwrap();
+    let module = dir.path().join("device.wasm");
+    fs::write(&module, EMPTY_COMPONENT).unwrap();
+    let cache = WasmModuleCache::default();
+    cache.load(&module).unwrap();
+
+    // Act
+    fs::write(&module, EMPTY_COMPONENT).unwrap();
+    cache.load(&module).unwrap();
+
+    // Assert
+    assert_eq!(cache.compilations(), 1);
+}
+
+#[test]
+fn missing_module_is_an_error() {
+    // Arrange
+    let dir = tempdir().unwrap();
+    let cache = WasmModuleCache::default();
+
+    // Act
+    let result = cache.load(&dir.path().join("missing.wasm"));
+
+    // Assert
+    assert!(result.is_err());
+    assert_eq!(cache.compilations(), 0);
+}
@@ -0,0 +1,245 @@
+use super::module_cache_tests::write_config;
+use crate::ctx::WsCloseCtx;
+use crate::device::wasm::module_cache::WasmModuleCache;
+use crate::device::wasm::wasm_device::WasmDevice;
+use crate::runtime::{ReloadError, build_runtime_state, reload_runtime_state};
+use arc_swap::ArcSwap;
+use pretty_assertions::assert_eq;
+use std::fs;
+use tempfile::tempdir;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+/// Exports `on-ws-close` from the WebSocket hooks interface. The hook accepts its context
+/// and does nothing, so a successful call shows the host found and lifted it.
+const WS_CLOSE_COMPONENT: &str = r#"
+(component
+  (core module $m
+    (memory (export "memory") 1)
+    (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+      i32.const 16)
+    (func (export "on-ws-close") (param i32 i32)))
+  (core instance $i (instantiate $m))
+  (func $on_ws_close (param "ctx" string)
+    (canon lift (core func $i "on-ws-close")
+      (memory $i "memory")
+      (realloc (func $i "realloc"))))
+  (instance $ws (export "on-ws-close" (func $on_ws_close)))
+  (export "snakeway:device/ws@0.2.0" (instance $ws)))
+"#;
+
+/// Exports `init` from the configuration interface. It accepts a JSON object and returns 1
+/// for anything else, including `null` when the device has no config block.
+const CONFIG_COMPONENT: &str = r#"
+(component
+  (core module $m
+    (memory (export "memory") 1)
+    (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+      i32.const 16)
+    (func (export "init") (param i32 i32) (result i32)
+      local.get 0
+      i32.load8_u
+      i32.const 123
+      i32.ne))
+  (core instance $i (instantiate $m))
+  (func $init (param "config" string) (result s32)
+    (canon lift (core func $i "init")
+      (memory $i "memory")
+      (realloc (func $i "realloc"))))
+  (instance $config (export "init" (func $init)))
+  (export "snakeway:device/config@0.2.0" (instance $config)))
+"#;
+
+/// Imports an interface the host does not provide, as a guest built against another ABI would.
+const FOREIGN_ABI_COMPONENT: &str = r#"
+(component
+  (import "snakeway:device/unknown@9.9.9" (instance
+    (export "missing" (func)))))
+"#;
+
+fn try_load(component: &str, config: Option<&hcl::Value>) -> anyhow::Result<WasmDevice> {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("device.wasm");
+    fs::write(&path, component).unwrap();
+    WasmDevice::load(&WasmModuleCache::default(), &path, 1_000_000, false, config)
+}
+
+fn load(component: &str) -> WasmDevice {
+    try_load(component, None).unwrap()
+}
+
+fn close_ctx() -> WsCloseCtx {
+    WsCloseCtx {
+        request_id: Some("req-1".to_string()),
+        path: "/chat".to_string(),
+        bytes_in: 10,
+        bytes_out: 20,
+        error: None,
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn ws_close_hook_is_called_when_exported() {
+    // Arrange
+    let device = load(WS_CLOSE_COMPONENT);
+
+    // Act
+    let called = device.call_ws_hook(device.ws_close.as_ref(), &close_ctx());
+
+    // Assert
+    assert!(called.unwrap());
+    assert!(device.ws_open.is_none());
+}
+
+#[test]
+fn missing_ws_hooks_are_no_ops() {
+    // Arrange
+    let device = load("(component)");
+
+    // Act
+    let called = device.call_ws_hook(device.ws_close.as_ref(), &close_ctx());
+
+    // Assert
+    assert!(!called.unwrap());
+}
+
+#[test]
+fn ws_close_ctx_is_serialized_as_json() {
+    // Act
+    let json = serde_json::to_value(close_ctx()).unwrap();
+
+    // Assert
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "request_id": "req-1",
+            "path": "/chat",
+            "bytes_in": 10,
+            "bytes_out": 20,
+            "error": null,
+        })
+    );
+}
+
+#[test]
+fn init_receives_device_config() {
+    // Arrange
+    let config: hcl::Value = hcl::from_str(r#"block_paths = ["/admin"]"#).unwrap();
+
+    // Act
+    let device = try_load(CONFIG_COMPONENT, Some(&config));
+
+    // Assert
+    assert!(device.unwrap().init.is_some());
+}
+
+#[test]
+fn nonzero_init_fails_load() {
+    // Act
+    let result = try_load(CONFIG_COMPONENT, None);
+
+    // Assert
+    let err = result.err().unwrap().to_string();
+    assert_eq!(err, "WASM device init failed with code 1");
+}
+
+#[tokio::test]
+async fn rejected_config_aborts_reload() {
+    // Arrange
+    let dir = tempdir().unwrap();
+    let module = dir.path().join("device.wasm");
+    fs::write(&module, CONFIG_COMPONENT).unwrap();
+    write_config(
+        dir.path(),
+        &module,
+        r#"config = { block_paths = ["/admin"] }"#,
+    );
+
+    let validated = crate::conf::load_config(dir.path()).unwrap();
+    let state = ArcSwap::from_pointee(build_runtime_state(&validated.config).unwrap());
+
+    // Drop the config block, so init sees `null`.
+    write_config(dir.path(), &module, "");
+
+    // Act
+    let result = reload_runtime_state(dir.path(), &state).await;
+
+    // Assert
+    assert!(matches!(result, Err(ReloadError::Build(_))));
+    assert_eq!(state.load().devices.all().len(), 1);
+}
+
+#[test]
+fn component_with_unknown_imports_fails_load() {
+    // Act
+    let result = try_load(FOREIGN_ABI_COMPONENT, None);
+
+    // Assert
+    let err = result.err().unwrap().to_string();
+    assert_eq!(
+        err,
+        "WASM device imports do not match the Snakeway host ABI"
+    );
+}
+
+#[test]
+fn valid_device_loads_at_startup() {
+    // Arrange
+    let dir = tempdir().unwrap();
+    let module = dir.path().join("device.wasm");
+    fs::write(&module, WS_CLOSE_COMPONENT).unwrap();
+    write_config(dir.path(), &module, r#"name = "guard""#);
+    let validated = crate::conf::load_config(dir.path()).unwrap();
+
+    // Act
+    let state = build_runtime_state(&validated.config).unwrap();
+
+    // Assert
+    assert_eq!(state.devices.all().len(), 1);
+}
+
+#[test]
+fn missing_device_fails_startup_when_strict() {
+    // Arrange
+    let dir = tempdir().unwrap();
+    let module = dir.path().join("device.wasm");
+    fs::write(&module, WS_CLOSE_COMPONENT).unwrap();
+    write_config(dir.path(), &module, r#"name = "guard""#);
+    let validated = crate::conf::load_config(dir.path()).unwrap();
+    fs::remove_file(&module).unwrap();
+
+    // Act
+    let result = build_runtime_state(&validated.config);
+
+    // Assert
+    let err = result.err().unwrap().to_string();
+    assert!(
+        err.starts_with(&format!(
+            "failed to load WASM devices:\n  'guard' ({}): ",
+            module.display()
+        )),
+        "{err}"
+    );
+}
+
+#[test]
+fn missing_device_is_disabled_when_not_strict() {
+    // Arrange
+    let dir = tempdir().unwrap();
+    let module = dir.path().join("device.wasm");
+    fs::write(&module, WS_CLOSE_COMPONENT).unwrap();
+    write_config(dir.path(), &module, r#"name = "guard""#);
+    let mut validated = crate::conf::load_config(dir.path()).unwrap();
+    validated.config.server.strict_wasm_devices = false;
+    fs::remove_file(&module).unwrap();
+
+    // Act
+    let state = build_runtime_state(&validated.config).unwrap();
+
+    // Assert
+    assert!(state.devices.all().is_empty());
+}
@@ -1,40 +1,198 @@
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
 use bytes::Bytes;
-use std::path::PathBuf;
+use serde::Serialize;
+use std::path::Path;
 use wasmtime::{
-    Engine, Store,
-    component::{Component, Linker},
+    Engine, Store, Trap,
+    component::{Component, ComponentExportIndex, HasSelf, Instance, Linker},
 };
 
-use http::{HeaderMap, HeaderName, StatusCode};
+use http::HeaderName;
 use wasmtime::component::ResourceTable;
 use wasmtime_wasi::{WasiCtx, WasiCtxBuilder, WasiCtxView, WasiView, p2::add_to_linker_sync};
 
-use crate::ctx::{RequestCtx, RequestId, ResponseCtx};
+use crate::ctx::{RequestCtx, RequestId, ResponseCtx, WsCloseCtx, WsCtx};
+use crate::device::core::errors::DeviceError;
 use crate::device::core::{Device, result::DeviceResult};
+use crate::device::wasm::host::BlockResponse;
+use crate::device::wasm::module_cache::WasmModuleCache;
 
 use crate::device::wasm::bindings::{
     Snakeway,
     exports::snakeway::device::policy::{BodyChunk, Decision, Header, Request, RequestPatch},
 };
 
+/// Export name of the optional WebSocket hooks interface, see the `snakeway-ws` world.
+const WS_INTERFACE: &str = "snakeway:device/ws@0.2.0";
+
+/// Export name of the optional configuration interface, see the `snakeway-config` world.
+const CONFIG_INTERFACE: &str = "snakeway:device/config@0.2.0";
+
 /// WASM-backed Snakeway device (stateless, per-call execution)
 pub struct WasmDevice {
     engine: Engine,
     component: Component,
+    /// Fuel each hook call may consume before it is aborted.
+    fuel: u64,
+    /// Run hook calls on the blocking thread pool.
+    offload: bool,
+    /// WebSocket hooks, if the guest exports them.
+    pub(crate) ws_open: Option<ComponentExportIndex>,
+    pub(crate) ws_close: Option<ComponentExportIndex>,
+    /// Configuration hook, if the guest exports it.
+    pub(crate) init: Option<ComponentExportIndex>,
+    /// The device's `config` block, serialized as JSON for `init`.
+    config: String,
 }
 
 impl WasmDevice {
-    pub fn load(path: &PathBuf) -> Result<Self> {
-        let engine = Engine::default();
-        let component = Component::from_file(&engine, path)?;
-        Ok(Self { engine, component })
+    /// Load the device, reusing the compiled module from the cache when the file is unchanged.
+    ///
+    /// The component's imports are checked against the host functions here, so a guest built
+    /// against another ABI fails the load instead of every hook call. A guest exporting `init`
+    /// is also instantiated once, so a config it rejects fails the load.
+    pub fn load(
+        modules: &WasmModuleCache,
+        path: &Path,
+        fuel: u64,
+        offload: bool,
+        config: Option<&hcl::Value>,
+    ) -> Result<Self> {
+        let (engine, component) = modules.load(path)?;
+        linker(&engine)?
+            .instantiate_pre(&component)
+            .context("WASM device imports do not match the Snakeway host ABI")?;
+
+        // WebSocket hooks are optional, so guests built against the `snakeway` world still load.
+        let ws = component.get_export_index(None, WS_INTERFACE);
+        let ws_hook = |name| {
+            ws.as_ref()
+                .and_then(|ws| component.get_export_index(Some(ws), name))
+        };
+        let ws_open = ws_hook("on-ws-open");
+        let ws_close = ws_hook("on-ws-close");
+
+        let init = component
+            .get_export_index(None, CONFIG_INTERFACE)
+            .and_then(|cfg| component.get_export_index(Some(&cfg), "init"));
+
+        let device = Self {
+            engine,
+            component,
+            fuel,
+            offload,
+            ws_open,
+            ws_close,
+            init,
+            config: serde_json::to_string(&config)?,
+        };
+
+        if device.init.is_some() {
+            device.instance()?;
+        }
+
+        Ok(device)
+    }
+
+    /// Link WASI and the Snakeway host functions, and create a store with this device's fuel.
+    fn link(&self) -> Result<(Linker<HostState>, Store<HostState>)> {
+        let linker = linker(&self.engine)?;
+        let mut store = Store::new(&self.engine, HostState::new());
+        store.set_fuel(self.fuel)?;
+        Ok((linker, store))
+    }
+
+    /// Instantiate the component and pass it the device config.
+    ///
+    /// Every hook call gets a fresh instance, so `init` runs before each one, not once per device.
+    fn instance(&self) -> Result<(Store<HostState>, Instance)> {
+        let (linker, mut store) = self.link()?;
+        let instance = linker.instantiate(&mut store, &self.component)?;
+
+        if let Some(init) = &self.init {
+            let func = instance.get_typed_func::<(&str,), (i32,)>(&mut store, init)?;
+            let (code,) = func.call(&mut store, (self.config.as_str(),))?;
+            func.post_return(&mut store)?;
+            if code != 0 {
+                bail!("WASM device init failed with code {code}");
+            }
+        }
+
+        Ok((store, instance))
+    }
+
+    /// Instantiate the component with WASI and the Snakeway host functions linked in.
+    fn instantiate(&self) -> Result<(Store<HostState>, Snakeway)> {
+        let (mut store, instance) = self.instance()?;
+        let instance = Snakeway::new(&mut store, &instance)?;
+        Ok((store, instance))
+    }
+
+    /// Call a WebSocket hook with its context serialized to JSON.
+    /// Returns false without instantiating anything if the guest does not export the hook.
+    pub(crate) fn call_ws_hook(
+        &self,
+        hook: Option<&ComponentExportIndex>,
+        ctx: &impl Serialize,
+    ) -> Result<bool> {
+        let Some(hook) = hook else {
+            return Ok(false);
+        };
+        let ctx = serde_json::to_string(ctx)?;
+
+        let (mut store, instance) = self.instance()?;
+        let func = instance.get_typed_func::<(&str,), ()>(&mut store, hook)?;
+        func.call(&mut store, (ctx.as_str(),))?;
+        func.post_return(&mut store)?;
+        Ok(true)
+    }
+
+    /// Fail open when a hook call fails.
+    /// A guest that runs out of fuel is reported to `on_error` handlers, since it is likely stuck in a loop.
+    fn call_failed(&self, hook: &str, e: anyhow::Error) -> DeviceResult {
+        if matches!(e.downcast_ref::<Trap>(), Some(Trap::OutOfFuel)) {
+            tracing::warn!(
+                "WASM {hook} ran out of fuel ({} units), continuing",
+                self.fuel
+            );
+            return DeviceResult::Error(DeviceError {
+                message: format!("WASM device ran out of fuel in {hook}"),
+                fatal: false,
+            });
+        }
+
+        tracing::error!("WASM {hook} failed: {e}");
+        DeviceResult::Continue
     }
 }
 
+/// A linker with WASI and the Snakeway host functions.
+fn linker(engine: &Engine) -> Result<Linker<HostState>> {
+    let mut linker = Linker::new(engine);
+    add_to_linker_sync(&mut linker)?;
+    Snakeway::add_to_linker::<_, HasSelf<_>>(&mut linker, |state: &mut HostState| state)?;
+    Ok(linker)
+}
+
 pub(crate) struct HostState {
     pub(crate) table: ResourceTable,
     pub(crate) wasi: WasiCtx,
+    /// Response a guest short-circuited with through a host function, e.g. `redirect`.
+    pub(crate) response: Option<ResponseCtx>,
+    /// Status, headers, and body the guest set for a `block` decision.
+    pub(crate) block_response: BlockResponse,
+}
+
+impl HostState {
+    pub(crate) fn new() -> Self {
+        Self {
+            table: ResourceTable::new(),
+            // Devices log by writing to stderr.
+            wasi: WasiCtxBuilder::new().inherit_stderr().build(),
+            response: None,
+            block_response: BlockResponse::default(),
+        }
+    }
 }
 
 impl WasiView for HostState {
@@ -51,19 +209,25 @@ impl Device for WasmDevice {
         "WASM Device"
     }
 
+    fn offload(&self) -> bool {
+        self.offload
+    }
+
+    fn on_ws_open(&self, ctx: &WsCtx) {
+        if let Err(e) = self.call_ws_hook(self.ws_open.as_ref(), ctx) {
+            // Nothing to continue or block here, the failure is only logged.
+            let _ = self.call_failed("on_ws_open", e);
+        }
+    }
+
+    fn on_ws_close(&self, ctx: &WsCloseCtx) {
+        if let Err(e) = self.call_ws_hook(self.ws_close.as_ref(), ctx) {
+            let _ = self.call_failed("on_ws_close", e);
+        }
+    }
+
     fn on_request(&self, ctx: &mut RequestCtx) -> DeviceResult {
-        let mut linker = Linker::new(&self.engine);
-        add_to_linker_sync(&mut linker).expect("failed to add WASI to linker");
-
-        let mut store = Store::new(
-            &self.engine,
-            HostState {
-                table: ResourceTable::new(),
-                wasi: WasiCtxBuilder::new().build(),
-            },
-        );
-
-        let instance = match Snakeway::instantiate(&mut store, &self.component, &linker) {
+        let (mut store, instance) = match self.instantiate() {
             Ok(i) => i,
             Err(e) => {
                 tracing::error!("WASM instantiate failed: {e}");
@@ -90,16 +254,20 @@ impl Device for WasmDevice {
             .call_on_request(&mut store, &req)
         {
             Ok(r) => r,
-            Err(e) => {
-                tracing::error!("WASM on_request failed: {e}");
-                return DeviceResult::Continue;
-            }
+            Err(e) => return self.call_failed("on_request", e),
         };
 
+        // A response set through a host function (e.g., a redirect) wins over the decision.
+        if let Some(mut resp) = store.data_mut().response.take() {
+            resp.request_id = ctx.extensions.get::<RequestId>().map(|id| id.0.clone());
+            return DeviceResult::Respond(resp);
+        }
+
         // Enforce decision
         if matches!(result.decision, Decision::Block) {
             let request_id = ctx.extensions.get::<RequestId>().map(|id| id.0.clone());
-            return DeviceResult::Respond(block_403(request_id));
+            let block_response = std::mem::take(&mut store.data_mut().block_response);
+            return DeviceResult::Respond(block_response.into_response(request_id));
         }
 
         // Apply explicit patch intent
@@ -140,18 +308,7 @@ impl Device for WasmDevice {
         maybe_chunk: &mut Option<Bytes>,
         end_of_stream: bool,
     ) -> DeviceResult {
-        let mut linker = Linker::new(&self.engine);
-        add_to_linker_sync(&mut linker).expect("failed to add WASI");
-
-        let mut store = Store::new(
-            &self.engine,
-            HostState {
-                table: ResourceTable::new(),
-                wasi: WasiCtxBuilder::new().build(),
-            },
-        );
-
-        let instance = match Snakeway::instantiate(&mut store, &self.component, &linker) {
+        let (mut store, instance) = match self.instantiate() {
             Ok(i) => i,
             Err(e) => {
                 tracing::error!("WASM instantiate failed: {e}");
@@ -182,15 +339,13 @@ impl Device for WasmDevice {
             .call_on_stream_request_body(&mut store, &req, chunk.as_ref())
         {
             Ok(r) => r,
-            Err(e) => {
-                tracing::error!("WASM body hook failed: {e}");
-                return DeviceResult::Continue;
-            }
+            Err(e) => return self.call_failed("on_stream_request_body", e),
         };
 
         if matches!(result.decision, Decision::Block) {
             let request_id = ctx.extensions.get::<RequestId>().map(|id| id.0.clone());
-            return DeviceResult::Respond(block_403(request_id));
+            let block_response = std::mem::take(&mut store.data_mut().block_response);
+            return DeviceResult::Respond(block_response.into_response(request_id));
         }
 
         DeviceResult::Continue
@@ -208,13 +363,3 @@ impl Device for WasmDevice {
         DeviceResult::Continue
     }
 }
-
-/// Standard 403 response for blocked requests
-fn block_403(request_id: Option<String>) -> ResponseCtx {
-    ResponseCtx::new(
-        request_id,
-        StatusCode::FORBIDDEN,
-        HeaderMap::new(),
-        b"Blocked by device".to_vec(),
-    )
-}
@@ -0,0 +1,167 @@
+use crate::conf::types::GeoIpConfig;
+use arc_swap::ArcSwap;
+use maxminddb::{Mmap, PathElement, Reader};
+use std::net::IpAddr;
+use std::path::Path;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Default)]
+pub struct GeoInfo {
+    /// e.g., US, GB, etc
+    pub country_code: Option<String>,
+    /// Location region
+    pub region: Option<String>,
+    /// Autonomous System Number
+    pub asn: Option<u32>,
+    /// Autonomous System Organization
+    pub aso: Option<String>,
+    /// e.g., wifi, mobile, etc
+    pub connection_type: Option<String>,
+}
+
+impl GeoInfo {
+    pub fn has_some_info(&self) -> bool {
+        self.country_code.is_some()
+            || self.region.is_some()
+            || self.asn.is_some()
+            || self.aso.is_some()
+            || self.connection_type.is_some()
+    }
+}
+
+/// The server's GeoIP databases, which a reload swaps for freshly opened ones.
+///
+/// One handle is shared by every identity device and carried over to the runtime state a
+/// reload builds, so `.mmdb` files replaced on disk take effect on `SIGHUP`. Lookups already
+/// running finish against the databases they started with.
+#[derive(Default)]
+pub struct GeoIp {
+    databases: ArcSwap<GeoIpDatabases>,
+}
+
+impl GeoIp {
+    pub fn open(cfg: &GeoIpConfig) -> anyhow::Result<Self> {
+        Ok(Self::from(GeoIpDatabases::from_config(cfg)?))
+    }
+
+    /// Reopen the databases `cfg` names. If any fails to open, the current ones are kept.
+    pub fn reload(&self, cfg: &GeoIpConfig) -> anyhow::Result<()> {
+        let databases = GeoIpDatabases::from_config(cfg)?;
+        self.databases.store(Arc::new(databases));
+        Ok(())
+    }
+
+    /// Everything the databases know about `ip`, or `None` if none of them know anything.
+    pub fn lookup(&self, ip: IpAddr) -> Option<GeoInfo> {
+        self.databases.load().lookup(ip)
+    }
+}
+
+impl From<GeoIpDatabases> for GeoIp {
+    fn from(databases: GeoIpDatabases) -> Self {
+        Self {
+            databases: ArcSwap::from_pointee(databases),
+        }
+    }
+}
+
+/// Memory-mapped MaxMind databases, any of which may be absent.
+///
+/// Country and region come from a City or Country database, e.g. GeoLite2-City, the ASN and
+/// its organization from an ASN or ISP database, e.g. GeoLite2-ASN.
+#[derive(Default)]
+pub struct GeoIpDatabases {
+    city: Option<Reader<Mmap>>,
+    isp: Option<Reader<Mmap>>,
+    connection_type: Option<Reader<Mmap>>,
+}
+
+impl GeoIpDatabases {
+    pub fn open(
+        city: Option<&Path>,
+        isp: Option<&Path>,
+        connection_type: Option<&Path>,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            city: city.map(open_mmap).transpose()?,
+            isp: isp.map(open_mmap).transpose()?,
+            connection_type: connection_type.map(open_mmap).transpose()?,
+        })
+    }
+
+    pub fn from_config(cfg: &GeoIpConfig) -> anyhow::Result<Self> {
+        Self::open(
+            cfg.city_db.as_deref(),
+            cfg.isp_db.as_deref(),
+            cfg.connection_type_db.as_deref(),
+        )
+    }
+
+    /// Everything the databases know about `ip`, or `None` if none of them know anything.
+    pub fn lookup(&self, ip: IpAddr) -> Option<GeoInfo> {
+        let mut geo = GeoInfo::default();
+
+        //---------------------------------------------------------------------
+        // Country and Region
+        //---------------------------------------------------------------------
+        let lookup = self.city.as_ref().and_then(|reader| reader.lookup(ip).ok());
+
+        if let Some(lookup) = lookup {
+            geo.country_code = lookup
+                .decode_path::<String>(&[PathElement::Key("country"), PathElement::Key("iso_code")])
+                .ok()
+                .flatten();
+
+            geo.region = lookup
+                .decode_path::<String>(&[
+                    PathElement::Key("subdivisions"),
+                    PathElement::Index(0),
+                    PathElement::Key("iso_code"),
+                ])
+                .ok()
+                .flatten();
+        }
+
+        //---------------------------------------------------------------------
+        // ASN
+        //---------------------------------------------------------------------
+        let lookup = self.isp.as_ref().and_then(|reader| reader.lookup(ip).ok());
+
+        if let Some(lookup) = lookup {
+            geo.asn = lookup
+                .decode_path::<u32>(&[PathElement::Key("autonomous_system_number")])
+                .ok()
+                .flatten();
+
+            geo.aso = lookup
+                .decode_path::<String>(&[PathElement::Key("autonomous_system_organization")])
+                .ok()
+                .flatten();
+        }
+
+        //---------------------------------------------------------------------
+        // Connection-type
+        //---------------------------------------------------------------------
+        let lookup = self
+            .connection_type
+            .as_ref()
+            .and_then(|reader| reader.lookup(ip).ok());
+
+        if let Some(lookup) = lookup {
+            geo.connection_type = lookup
+                .decode_path::<String>(&[PathElement::Key("connection_type")])
+                .ok()
+                .flatten();
+        }
+
+        geo.has_some_info().then_some(geo)
+    }
+}
+
+/// Safety note on these memory-mapped GeoIP files...
+/// - File is opened read-only
+/// - Lifetime is bound to the `GeoIpDatabases`, dropped after a reload once no lookup holds it
+/// - Snakeway does not mutate the mmdb file, and updates must replace it rather than write to it
+fn open_mmap(path: &Path) -> anyhow::Result<Reader<Mmap>> {
+    Ok(unsafe { Reader::open_mmap(path)? })
+}
@@ -1 +1,4 @@
+pub mod geoip;
+#[cfg(test)]
+mod tests;
 pub mod user_agent;
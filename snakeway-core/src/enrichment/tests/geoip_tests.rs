@@ -0,0 +1,125 @@
+use crate::conf::types::GeoIpConfig;
+use crate::enrichment::geoip::{GeoIp, GeoIpDatabases};
+use pretty_assertions::assert_eq;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+/// A DB-IP country database, which has the same layout as GeoLite2-Country.
+const COUNTRY_DB: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../integration-tests/fixtures/geoip/dbip-country-lite-2025-12.mmdb"
+);
+
+fn country_db() -> GeoIpDatabases {
+    GeoIpDatabases::open(Some(Path::new(COUNTRY_DB)), None, None).unwrap()
+}
+
+fn country_config() -> GeoIpConfig {
+    GeoIpConfig {
+        city_db: Some(PathBuf::from(COUNTRY_DB)),
+        ..Default::default()
+    }
+}
+
+fn ip(s: &str) -> IpAddr {
+    s.parse().unwrap()
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn known_ips_resolve_to_their_country() {
+    // Arrange
+    let db = country_db();
+
+    for (addr, country) in [
+        ("8.8.8.8", "US"),
+        ("1.1.1.1", "AU"),
+        ("81.2.69.142", "GB"),
+        ("193.0.6.139", "NL"),
+        ("2001:4860:4860::8888", "US"),
+    ] {
+        // Act
+        let geo = db.lookup(ip(addr)).unwrap();
+
+        // Assert
+        assert_eq!(geo.country_code.as_deref(), Some(country), "{addr}");
+    }
+}
+
+#[test]
+fn country_database_has_no_region_or_asn() {
+    // Act
+    let geo = country_db().lookup(ip("8.8.8.8")).unwrap();
+
+    // Assert
+    assert_eq!(geo.region, None);
+    assert_eq!(geo.asn, None);
+    assert_eq!(geo.aso, None);
+}
+
+#[test]
+fn unknown_ip_has_no_geo_info() {
+    // Act
+    let geo = country_db().lookup(ip("127.0.0.1"));
+
+    // Assert
+    assert!(geo.is_none());
+}
+
+#[test]
+fn no_databases_have_no_geo_info() {
+    // Arrange
+    let db = GeoIpDatabases::open(None, None, None).unwrap();
+
+    // Act
+    let geo = db.lookup(ip("8.8.8.8"));
+
+    // Assert
+    assert!(geo.is_none());
+}
+
+#[test]
+fn missing_database_fails_to_open() {
+    // Act
+    let result = GeoIpDatabases::open(Some(Path::new("/non/existent/city.mmdb")), None, None);
+
+    // Assert
+    assert!(result.is_err());
+}
+
+#[test]
+fn reload_swaps_in_the_configured_databases() {
+    // Arrange
+    let geoip = GeoIp::open(&GeoIpConfig::default()).unwrap();
+    let before = geoip.lookup(ip("8.8.8.8"));
+
+    // Act
+    geoip.reload(&country_config()).unwrap();
+
+    // Assert
+    assert!(before.is_none());
+    let after = geoip.lookup(ip("8.8.8.8")).unwrap();
+    assert_eq!(after.country_code.as_deref(), Some("US"));
+}
+
+#[test]
+fn failed_reload_keeps_the_current_databases() {
+    // Arrange
+    let geoip = GeoIp::open(&country_config()).unwrap();
+
+    // Act
+    let result = geoip.reload(&GeoIpConfig {
+        city_db: Some(PathBuf::from("/non/existent/city.mmdb")),
+        ..Default::default()
+    });
+
+    // Assert
+    assert!(result.is_err());
+    let geo = geoip.lookup(ip("8.8.8.8")).unwrap();
+    assert_eq!(geo.country_code.as_deref(), Some("US"));
+}
@@ -0,0 +1,2 @@
+mod geoip_tests;
+mod parse_cache_tests;
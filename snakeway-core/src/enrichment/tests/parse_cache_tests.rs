@@ -0,0 +1,118 @@
+use crate::conf::types::UaEngineKind;
+use crate::enrichment::user_agent::{UaEngine, UaParseCache, build_ua_engine};
+use pretty_assertions::assert_eq;
+use std::num::NonZeroUsize;
+use std::time::Instant;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+const CHROME: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+const IPHONE: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_2 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.2 Mobile/15E148 Safari/604.1";
+const GOOGLEBOT: &str = "Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)";
+
+fn engine() -> UaEngine {
+    build_ua_engine(UaEngineKind::Woothee).unwrap()
+}
+
+fn cache(capacity: usize) -> UaParseCache {
+    UaParseCache::new(NonZeroUsize::new(capacity).unwrap())
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn repeated_user_agents_are_parsed_once() {
+    // Arrange
+    let engine = engine();
+    let cache = cache(16);
+    let traffic = [CHROME, IPHONE, GOOGLEBOT];
+    let iterations = 10_000;
+
+    // Act
+    let started = Instant::now();
+    for _ in 0..iterations {
+        for ua in traffic {
+            cache.parse(&engine, ua);
+        }
+    }
+    let cached = started.elapsed();
+
+    let started = Instant::now();
+    for _ in 0..iterations {
+        for ua in traffic {
+            engine.parse(ua);
+        }
+    }
+    let uncached = started.elapsed();
+
+    // Assert
+    println!(
+        "{iterations} x {} user agents: cached {cached:?}, uncached {uncached:?}",
+        traffic.len()
+    );
+    assert_eq!(cache.misses(), traffic.len());
+    assert_eq!(cache.hits(), traffic.len() * (iterations - 1));
+    assert_eq!(cache.len(), traffic.len());
+}
+
+#[test]
+fn cached_results_match_the_engine() {
+    // Arrange
+    let engine = engine();
+    let cache = cache(16);
+
+    for ua in [CHROME, IPHONE, GOOGLEBOT] {
+        // Act
+        let miss = cache.parse(&engine, ua);
+        let hit = cache.parse(&engine, ua);
+        let parsed = engine.parse(ua);
+
+        // Assert
+        for info in [miss, hit] {
+            assert_eq!(info.device_type.as_str(), parsed.device_type.as_str());
+            assert_eq!(info.is_bot, parsed.is_bot);
+        }
+    }
+}
+
+#[test]
+fn least_recently_used_user_agent_is_evicted_at_capacity() {
+    // Arrange
+    let engine = engine();
+    let cache = cache(2);
+    cache.parse(&engine, CHROME);
+    cache.parse(&engine, IPHONE);
+    // Chrome is now more recently used than the iPhone.
+    cache.parse(&engine, CHROME);
+
+    // Act
+    cache.parse(&engine, GOOGLEBOT);
+    let misses_before = cache.misses();
+    cache.parse(&engine, CHROME);
+    let chrome_missed = cache.misses() > misses_before;
+    cache.parse(&engine, IPHONE);
+    let iphone_missed = cache.misses() > misses_before;
+
+    // Assert
+    assert_eq!(cache.len(), 2);
+    assert!(!chrome_missed);
+    assert!(iphone_missed);
+}
+
+#[test]
+fn unique_user_agents_do_not_grow_the_cache_past_capacity() {
+    // Arrange
+    let engine = engine();
+    let cache = cache(100);
+
+    // Act
+    for i in 0..1_000 {
+        cache.parse(&engine, &format!("scanner/{i}"));
+    }
+
+    // Assert
+    assert_eq!(cache.len(), 100);
+    assert_eq!(cache.hits(), 0);
+}
@@ -1,11 +1,15 @@
+mod parse_cache;
 mod uaparser_engine;
 mod woothee_engine;
 
 use crate::conf::types::UaEngineKind;
+use crate::enrichment::geoip::GeoInfo;
 use crate::enrichment::user_agent::uaparser_engine::UaParserEngine;
 use crate::enrichment::user_agent::woothee_engine::WootheeEngine;
 use std::net::IpAddr;
 
+pub use parse_cache::UaParseCache;
+
 const REGEXES_YAML: &[u8] = include_bytes!("regexes.yaml");
 
 pub fn build_ua_engine(kind: UaEngineKind) -> anyhow::Result<UaEngine> {
@@ -41,30 +45,6 @@ pub struct ClientIdentity {
     pub ua: Option<UserAgentInfo>,
 }
 
-#[derive(Debug, Clone, Default)]
-pub struct GeoInfo {
-    /// e.g., US, GB, etc
-    pub country_code: Option<String>,
-    /// Location region
-    pub region: Option<String>,
-    /// Autonomous System Number
-    pub asn: Option<u32>,
-    /// Autonomous System Organization
-    pub aso: Option<String>,
-    /// e.g., wifi, mobile, etc
-    pub connection_type: Option<String>,
-}
-
-impl GeoInfo {
-    pub fn has_some_info(&self) -> bool {
-        self.country_code.is_some()
-            || self.region.is_some()
-            || self.asn.is_some()
-            || self.aso.is_some()
-            || self.connection_type.is_some()
-    }
-}
-
 #[derive(Debug, Clone)]
 pub struct UserAgentInfo {
     pub device_type: DeviceType,
@@ -0,0 +1,62 @@
+use crate::enrichment::user_agent::{UaEngine, UserAgentInfo};
+use lru::LruCache;
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Parsed user agents, keyed by the user-agent string, so the few strings that dominate real
+/// traffic are parsed once.
+///
+/// The cache holds at most `capacity` strings and evicts the least recently used, so a client
+/// sending a unique user agent per request cannot grow it. The lock is not held while parsing.
+pub struct UaParseCache {
+    entries: Mutex<LruCache<String, UserAgentInfo>>,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+impl UaParseCache {
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            entries: Mutex::new(LruCache::new(capacity)),
+            hits: AtomicUsize::new(0),
+            misses: AtomicUsize::new(0),
+        }
+    }
+
+    /// The parsed user agent, from the cache when possible.
+    pub fn parse(&self, engine: &UaEngine, ua: &str) -> UserAgentInfo {
+        if let Some(info) = self.lock().get(ua) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return info.clone();
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let info = engine.parse(ua);
+        self.lock().put(ua.to_string(), info.clone());
+        info
+    }
+
+    /// Number of cached user agents.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of lookups answered from the cache.
+    pub fn hits(&self) -> usize {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Number of lookups that had to parse.
+    pub fn misses(&self) -> usize {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, LruCache<String, UserAgentInfo>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
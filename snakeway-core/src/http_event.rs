@@ -4,6 +4,7 @@ pub enum HttpEvent {
     BeforeProxy,
     AfterProxy,
     Response,
+    Complete,
 }
 
 impl HttpEvent {
@@ -13,6 +14,7 @@ impl HttpEvent {
             HttpEvent::BeforeProxy => "before_proxy",
             HttpEvent::AfterProxy => "after_proxy",
             HttpEvent::Response => "response",
+            HttpEvent::Complete => "complete",
         }
     }
 }
@@ -4,7 +4,7 @@ pub mod cli;
 pub mod conf;
 pub mod ctx;
 pub mod device;
-mod enrichment;
+pub mod enrichment;
 pub mod http_event;
 pub mod logging;
 pub mod route;
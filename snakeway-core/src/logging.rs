@@ -1,13 +1,21 @@
+use crate::server::TlsHandshakeFailureLayer;
 use std::io::{self, IsTerminal};
+use std::sync::Mutex;
+use tracing_appender::non_blocking::WorkerGuard;
 use tracing_appender::rolling;
+use tracing_subscriber::prelude::*;
 use tracing_subscriber::{EnvFilter, fmt};
 
+/// Guard for the non-blocking file writer, held until logs are flushed on shutdown.
+static LOG_GUARD: Mutex<Option<WorkerGuard>> = Mutex::new(None);
+
 /// Initialize the logging system with JSON formatting and environment-based filtering
 ///
 /// This function sets up the logging infrastructure using tracing-subscriber:
 /// - Uses environment variables for log level filtering (defaults to "info" if not set)
 /// - Configures JSON output format for structured logging
 /// - Flattens event fields for cleaner log output
+/// - Reports failed TLS handshakes as structured `tls_handshake_failed` events
 pub fn init_normal_logging() {
     let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
 
@@ -15,24 +23,33 @@ pub fn init_normal_logging() {
         let appender = rolling::daily(dir, "snakeway.log");
         let (writer, guard) = tracing_appender::non_blocking(appender);
 
-        fmt()
-            .with_env_filter(filter)
-            .json()
-            .flatten_event(true)
-            .with_writer(writer)
+        tracing_subscriber::registry()
+            .with(filter)
+            .with(fmt::layer().json().flatten_event(true).with_writer(writer))
+            .with(TlsHandshakeFailureLayer)
             .init();
 
-        // Keep guard alive for the entire lifetime of the program.
-        std::mem::forget(guard);
+        // Keep guard alive until shutdown flushes it.
+        *LOG_GUARD.lock().unwrap() = Some(guard);
     } else {
-        fmt()
-            .with_env_filter(filter)
-            .json()
-            .flatten_event(true)
+        tracing_subscriber::registry()
+            .with(filter)
+            .with(fmt::layer().json().flatten_event(true))
+            .with(TlsHandshakeFailureLayer)
             .init();
     }
 }
 
+/// Flush buffered logs to their destination.
+///
+/// Dropping the file writer guard blocks until pending log lines are written.
+/// Anything logged afterward is not written to the log file.
+pub fn flush_logs() {
+    if let Ok(mut guard) = LOG_GUARD.lock() {
+        guard.take();
+    }
+}
+
 pub fn init_logging() {
     if std::env::var("TOKIO_CONSOLE").is_ok() {
         // Tokio console logging is specifically for interactive debugging and profiling.
@@ -0,0 +1,54 @@
+use crate::conf::types::UPSTREAM_ACCEPT_ENCODING_STRIP;
+use crate::static_files::render::compression::is_skipped_content_type;
+use http::header;
+use pingora::http::{RequestHeader, ResponseHeader};
+use pingora::prelude::*;
+
+/// Compression level used when a service enables response compression.
+pub(crate) const RESPONSE_COMPRESSION_LEVEL: u32 = 6;
+
+/// Applies the service's `upstream_accept_encoding` to the request sent upstream.
+///
+/// The client's `Accept-Encoding` is left untouched on the downstream side, so Snakeway can
+/// still compress the (identity) upstream response for clients that accept it.
+pub(crate) fn apply_upstream_accept_encoding(
+    upstream: &mut RequestHeader,
+    accept_encoding: Option<&str>,
+) -> Result<()> {
+    match accept_encoding {
+        None => {}
+        Some(UPSTREAM_ACCEPT_ENCODING_STRIP) => {
+            upstream.remove_header(&header::ACCEPT_ENCODING);
+        }
+        Some(value) => {
+            upstream.insert_header(header::ACCEPT_ENCODING, value)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether a proxied response should be sent uncompressed, even though the service
+/// enables response compression.
+///
+/// Responses without a `Content-Length` (e.g. chunked) are only checked by content type.
+pub(crate) fn skip_response_compression(
+    response: &ResponseHeader,
+    skip_content_types: &[String],
+    min_size: u64,
+) -> bool {
+    let content_type = response
+        .headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok());
+    if content_type.is_some_and(|ct| is_skipped_content_type(ct, skip_content_types)) {
+        return true;
+    }
+
+    response
+        .headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .is_some_and(|len| len < min_size)
+}
@@ -1,25 +1,42 @@
-use crate::ctx::RequestCtx;
+use crate::ctx::{ListenerInfo, RequestCtx};
+use crate::device::core::pipeline::DevicePipeline;
+use crate::device::core::result::DeviceResult;
 use crate::proxy::handlers::AdminHandler;
-use crate::server::ReloadHandle;
+use crate::runtime::RuntimeState;
+use crate::server::{ReloadHandle, ShutdownCoordinator};
 use crate::traffic_management::TrafficManager;
 use crate::ws_connection_management::WsConnectionManager;
+use arc_swap::ArcSwap;
 use async_trait::async_trait;
 use pingora::prelude::{HttpPeer, ProxyHttp, Session};
 use pingora::{Custom, Error};
 use std::sync::Arc;
 
 pub struct AdminGateway {
+    listener: ListenerInfo,
+    state: Arc<ArcSwap<RuntimeState>>,
     admin_handler: AdminHandler,
 }
 
 impl AdminGateway {
     pub fn new(
+        listener: ListenerInfo,
+        state: Arc<ArcSwap<RuntimeState>>,
         traffic_manager: Arc<TrafficManager>,
         connection_manager: Arc<WsConnectionManager>,
         reload: Arc<ReloadHandle>,
+        shutdown: Arc<ShutdownCoordinator>,
     ) -> Self {
         Self {
-            admin_handler: AdminHandler::new(traffic_manager, connection_manager, reload),
+            listener,
+            state: state.clone(),
+            admin_handler: AdminHandler::new(
+                state,
+                traffic_manager,
+                connection_manager,
+                reload,
+                shutdown,
+            ),
         }
     }
 }
@@ -30,7 +47,8 @@ impl ProxyHttp for AdminGateway {
 
     fn new_ctx(&self) -> Self::CTX {
         // Minimal ctx - admin requests never enter the proxy lifecycle.
-        RequestCtx::empty()
+        // It is only hydrated when devices are attached to the admin listener.
+        RequestCtx::for_listener(self.listener.clone())
     }
 
     async fn upstream_peer(
@@ -47,8 +65,40 @@ impl ProxyHttp for AdminGateway {
     async fn request_filter(
         &self,
         session: &mut Session,
-        _ctx: &mut Self::CTX,
+        ctx: &mut Self::CTX,
     ) -> pingora::Result<bool> {
+        // Only devices explicitly attached to this admin listener run here.
+        // Without any, admin requests are handled as-is and ctx is never hydrated.
+        let state = self.state.load();
+        if !state
+            .devices
+            .for_admin_listener(&self.listener.name)
+            .is_empty()
+        {
+            ctx.hydrate_from_session(session).map_err(|e| {
+                tracing::warn!(error = %e, "admin request rejected during normalization");
+                e.as_pingora_error()
+            })?;
+
+            let devices = state
+                .devices
+                .for_admin_request(&self.listener.name, ctx.canonical_path());
+            match DevicePipeline::run_on_request_offloaded(&devices, ctx).await {
+                DeviceResult::Continue => {}
+
+                DeviceResult::Respond(resp) => {
+                    session.respond_error(resp.status.as_u16()).await?;
+                    return Ok(true);
+                }
+
+                DeviceResult::Error(err) => {
+                    tracing::error!("device error in admin on_request: {err}");
+                    session.respond_error(500).await?;
+                    return Ok(true);
+                }
+            }
+        }
+
         // AdminGateway is terminal: it always handles the request.
         let path = session.req_header().uri.path().to_owned();
         self.admin_handler.handle(session, &path).await
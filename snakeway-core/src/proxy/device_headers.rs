@@ -0,0 +1,41 @@
+use http::{HeaderMap, HeaderName, HeaderValue};
+use pingora::http::ResponseHeader;
+use pingora::prelude::*;
+
+/// Applies header changes `on_response` devices made to the response context.
+///
+/// Only headers whose values changed are touched, so unchanged headers keep the casing and
+/// order the upstream sent them in.
+pub(crate) fn sync_response_headers(
+    upstream: &mut ResponseHeader,
+    headers: &HeaderMap,
+) -> Result<()> {
+    let removed: Vec<HeaderName> = upstream
+        .headers
+        .keys()
+        .filter(|name| !headers.contains_key(*name))
+        .cloned()
+        .collect();
+    for name in removed {
+        upstream.remove_header(&name);
+    }
+
+    for name in headers.keys() {
+        let values: Vec<&HeaderValue> = headers.get_all(name).iter().collect();
+        let unchanged = upstream
+            .headers
+            .get_all(name)
+            .iter()
+            .eq(values.iter().copied());
+        if unchanged {
+            continue;
+        }
+
+        upstream.remove_header(name);
+        for value in values {
+            upstream.append_header(name.clone(), value.clone())?;
+        }
+    }
+
+    Ok(())
+}
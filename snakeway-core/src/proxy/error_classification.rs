@@ -1,3 +1,4 @@
+use crate::proxy::public_gateway::UPSTREAM_DNS_FAILURE;
 use crate::traffic_management::TransportFailure;
 
 /// Classifies Pingora upstream errors into Snakeway transport failures.
@@ -12,6 +13,9 @@ pub fn classify_pingora_error(err: &pingora::Error) -> TransportFailure {
 
     // Classify specific upstream errors from Pingora.
     match err.etype() {
+        // Resolution phase.
+        Custom(reason) if *reason == UPSTREAM_DNS_FAILURE => TransportFailure::Dns,
+
         // Connect phase.
         ConnectTimedout | ConnectRefused | ConnectNoRoute | ConnectProxyFailure | ConnectError => {
             TransportFailure::Connect
@@ -1,20 +1,66 @@
-use crate::runtime::UpstreamRuntime;
-use crate::server::ReloadHandle;
-use crate::traffic_management::TrafficManager;
+use crate::device::core::CachePurge;
+use crate::device::core::metrics::DEVICE_METRICS;
+use crate::runtime::{RuntimeState, UpstreamRuntime};
+use crate::server::{ReloadHandle, ShutdownCoordinator};
+use crate::traffic_management::{ServiceId, TrafficManager};
 use crate::ws_connection_management::WsConnectionManager;
+use arc_swap::ArcSwap;
 use http::{StatusCode, header};
 use pingora::http::ResponseHeader;
 use pingora::prelude::Session;
 use pingora::{Custom, Error};
+use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::str::FromStr;
 use std::sync::Arc;
 
+/// Same bound config validation puts on upstream weights.
+const MAX_UPSTREAM_WEIGHT: u32 = 1_000;
+
+/// Admin request bodies are small JSON documents.
+const MAX_ADMIN_BODY_BYTES: usize = 4096;
+
 #[derive(Debug, PartialEq)]
 enum AdminEndpoint {
     Health,
+    Ready,
     Upstreams,
     Stats,
     Reload,
+    /// `/admin/lb/{service}/{upstream}/weight`, with the upstream keyed as in `/admin/upstreams`.
+    UpstreamWeight {
+        service: String,
+        upstream: String,
+    },
+    CachePurge,
+    /// `/admin/devices/{device}/reset`, with the device named as listeners attach it.
+    DeviceReset {
+        device: String,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct UpstreamWeightRequest {
+    weight: u32,
+}
+
+/// Purges every cached entry unless it names a `key` or a `prefix`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CachePurgeRequest {
+    key: Option<String>,
+    prefix: Option<String>,
+}
+
+impl CachePurgeRequest {
+    fn into_purge(self) -> Option<CachePurge> {
+        match (self.key, self.prefix) {
+            (None, None) => Some(CachePurge::All),
+            (Some(key), None) => Some(CachePurge::Key(key)),
+            (None, Some(prefix)) => Some(CachePurge::Prefix(prefix)),
+            (Some(_), Some(_)) => None,
+        }
+    }
 }
 
 impl FromStr for AdminEndpoint {
@@ -23,30 +69,90 @@ impl FromStr for AdminEndpoint {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "/admin/health" => Ok(AdminEndpoint::Health),
+            "/admin/ready" => Ok(AdminEndpoint::Ready),
             "/admin/upstreams" => Ok(AdminEndpoint::Upstreams),
             "/admin/stats" => Ok(AdminEndpoint::Stats),
             "/admin/reload" => Ok(AdminEndpoint::Reload),
-            _ => Err("invalid admin endpoint"),
+            "/admin/cache/purge" => Ok(AdminEndpoint::CachePurge),
+            _ => {
+                if let Some(device) = s
+                    .strip_prefix("/admin/devices/")
+                    .and_then(|rest| rest.strip_suffix("/reset"))
+                    .filter(|device| !device.is_empty() && !device.contains('/'))
+                {
+                    return Ok(AdminEndpoint::DeviceReset {
+                        device: device.to_string(),
+                    });
+                }
+
+                // Unix socket upstreams are keyed by path, so the upstream may contain slashes.
+                let (service, upstream) = s
+                    .strip_prefix("/admin/lb/")
+                    .and_then(|rest| rest.strip_suffix("/weight"))
+                    .and_then(|rest| rest.split_once('/'))
+                    .filter(|(service, upstream)| !service.is_empty() && !upstream.is_empty())
+                    .ok_or("invalid admin endpoint")?;
+
+                Ok(AdminEndpoint::UpstreamWeight {
+                    service: service.to_string(),
+                    upstream: upstream.to_string(),
+                })
+            }
         }
     }
 }
 
+/// How `/admin/upstreams` keys an upstream.
+fn upstream_addr(endpoint: &UpstreamRuntime) -> String {
+    match endpoint {
+        UpstreamRuntime::Tcp(tcp) => format!("{}:{}", tcp.host, tcp.port),
+        UpstreamRuntime::Unix(unix) => format!("unix:{}", unix.path),
+    }
+}
+
+/// The `/admin/ready` status and body.
+///
+/// Readiness flips first on shutdown, and listeners keep accepting for the readiness grace
+/// period, so load balancers see `503` with `draining` before connections are refused.
+pub(crate) fn readiness_report(shutdown: &ShutdownCoordinator) -> (StatusCode, serde_json::Value) {
+    let ready = shutdown.is_ready();
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    let report = serde_json::json!({
+        "ready": ready,
+        "draining": shutdown.is_draining(),
+        "in_flight": shutdown.in_flight(),
+        "phase": shutdown.phase().to_string()
+    });
+    (status, report)
+}
+
 pub struct AdminHandler {
+    state: Arc<ArcSwap<RuntimeState>>,
     traffic_manager: Arc<TrafficManager>,
     connection_manager: Arc<WsConnectionManager>,
     reload: Arc<ReloadHandle>,
+    shutdown: Arc<ShutdownCoordinator>,
 }
 
 impl AdminHandler {
     pub fn new(
+        state: Arc<ArcSwap<RuntimeState>>,
         traffic_manager: Arc<TrafficManager>,
         connection_manager: Arc<WsConnectionManager>,
         reload: Arc<ReloadHandle>,
+        shutdown: Arc<ShutdownCoordinator>,
     ) -> Self {
         Self {
+            state,
             traffic_manager,
             connection_manager,
             reload,
+            shutdown,
         }
     }
 
@@ -62,32 +168,39 @@ impl AdminHandler {
                 let mut services = std::collections::HashMap::new();
 
                 for (svc_id, svc_snapshot) in &snapshot.services {
-                    let mut tcp_upstreams = std::collections::HashMap::new();
+                    let mut upstreams = std::collections::HashMap::new();
                     for u in &svc_snapshot.upstreams {
-                        match &u.endpoint {
-                            UpstreamRuntime::Tcp(tcp) => {
-                                let view = self.traffic_manager.get_upstream_view(
-                                    svc_id,
-                                    &u.endpoint.id(),
-                                    include_details,
-                                );
-                                let addr = format!("{}:{}", tcp.host, tcp.port);
-                                tcp_upstreams.insert(addr, view);
-                            }
-                            UpstreamRuntime::Unix(_) => {}
-                        };
+                        let addr = upstream_addr(&u.endpoint);
+                        let view = self.traffic_manager.get_upstream_view(
+                            svc_id,
+                            &u.endpoint.id(),
+                            include_details,
+                        );
+                        upstreams.insert(addr, view);
                     }
-                    services.insert(svc_id.clone(), tcp_upstreams);
+                    services.insert(svc_id.clone(), upstreams);
                 }
 
-                let body = serde_json::to_vec(&serde_json::json!({ "services": services }))
-                    .map_err(|_| Error::new(Custom("json serialization failed")))?;
+                let body = serde_json::to_vec(&serde_json::json!({
+                    "draining": self.shutdown.is_draining(),
+                    "services": services
+                }))
+                .map_err(|_| Error::new(Custom("json serialization failed")))?;
 
                 self.send_json_response(session, StatusCode::OK, body)
                     .await?;
                 Ok(true)
             }
 
+            AdminEndpoint::Ready => {
+                let (status, report) = readiness_report(&self.shutdown);
+                let body = serde_json::to_vec(&report)
+                    .map_err(|_| Error::new(Custom("json serialization failed")))?;
+
+                self.send_json_response(session, status, body).await?;
+                Ok(true)
+            }
+
             AdminEndpoint::Stats => {
                 let traffic = self.traffic_manager.snapshot();
                 let mut traffic_stats = std::collections::HashMap::new();
@@ -143,7 +256,11 @@ impl AdminHandler {
                 let body = serde_json::to_vec(&serde_json::json!({
                     "traffic": traffic_stats,
                     "connections": {
-                        "websocket": ws_connections
+                        "websocket": ws_connections,
+                        "websocket_active": self.connection_manager.total_active()
+                    },
+                    "devices": {
+                        "short_circuits": DEVICE_METRICS.short_circuit_counts()
                     }
                 }))
                 .map_err(|_| Error::new(Custom("json serialization failed")))?;
@@ -154,14 +271,9 @@ impl AdminHandler {
             }
 
             AdminEndpoint::Reload => {
-                let method = session.req_header().method.clone();
-
                 // Return early when not a POST request.
-                if method != http::Method::POST {
-                    let mut resp = ResponseHeader::build(StatusCode::METHOD_NOT_ALLOWED, None)?;
-                    resp.insert_header(header::ALLOW, "POST")?;
-                    resp.insert_header(header::CONTENT_LENGTH, "0")?;
-                    session.write_response_header(Box::new(resp), true).await?;
+                if session.req_header().method != http::Method::POST {
+                    self.send_post_only_response(session).await?;
                     return Ok(true);
                 }
 
@@ -177,9 +289,162 @@ impl AdminHandler {
                     .await?;
                 Ok(true)
             }
+
+            AdminEndpoint::UpstreamWeight { service, upstream } => {
+                // Return early when not a POST request.
+                if session.req_header().method != http::Method::POST {
+                    self.send_post_only_response(session).await?;
+                    return Ok(true);
+                }
+
+                let service_id = ServiceId(service);
+                let upstream_id = self
+                    .traffic_manager
+                    .snapshot()
+                    .services
+                    .get(&service_id)
+                    .and_then(|svc| {
+                        svc.upstreams
+                            .iter()
+                            .find(|u| upstream_addr(&u.endpoint) == upstream)
+                    })
+                    .map(|u| u.endpoint.id());
+                let Some(upstream_id) = upstream_id else {
+                    self.send_json_error(session, StatusCode::NOT_FOUND, "unknown upstream")
+                        .await?;
+                    return Ok(true);
+                };
+
+                let weight = read_json_body::<UpstreamWeightRequest>(session)
+                    .await?
+                    .map(|req| req.weight)
+                    .filter(|weight| (1..=MAX_UPSTREAM_WEIGHT).contains(weight));
+                let Some(weight) = weight else {
+                    let message = format!(
+                        "expected a JSON body with a weight from 1 to {MAX_UPSTREAM_WEIGHT}"
+                    );
+                    self.send_json_error(session, StatusCode::BAD_REQUEST, &message)
+                        .await?;
+                    return Ok(true);
+                };
+
+                if !self
+                    .traffic_manager
+                    .set_upstream_weight(&service_id, &upstream_id, weight)
+                {
+                    // A reload removed the upstream in the meantime.
+                    self.send_json_error(session, StatusCode::NOT_FOUND, "unknown upstream")
+                        .await?;
+                    return Ok(true);
+                }
+
+                let body = serde_json::to_vec(&serde_json::json!({
+                    "service": service_id,
+                    "upstream": upstream,
+                    "weight": weight
+                }))
+                .map_err(|_| Error::new(Custom("json serialization failed")))?;
+
+                self.send_json_response(session, StatusCode::OK, body)
+                    .await?;
+                Ok(true)
+            }
+
+            AdminEndpoint::CachePurge => {
+                // Return early when not a POST request.
+                if session.req_header().method != http::Method::POST {
+                    self.send_post_only_response(session).await?;
+                    return Ok(true);
+                }
+
+                let purge = read_json_body::<CachePurgeRequest>(session)
+                    .await?
+                    .and_then(CachePurgeRequest::into_purge);
+                let Some(purge) = purge else {
+                    let message = "expected no body, or a JSON body with either a key or a prefix";
+                    self.send_json_error(session, StatusCode::BAD_REQUEST, message)
+                        .await?;
+                    return Ok(true);
+                };
+
+                // Devices are listed by name, and only when they dropped something.
+                let state = self.state.load();
+                let purged = state
+                    .devices
+                    .named()
+                    .map(|(name, device)| (name, device.purge_cache(&purge)))
+                    .filter(|(_, count)| *count > 0)
+                    .collect::<BTreeMap<_, _>>();
+                let total: usize = purged.values().sum();
+                tracing::info!(?purge, purged = total, "device caches purged");
+
+                let body = serde_json::to_vec(&serde_json::json!({
+                    "purged": total,
+                    "devices": purged
+                }))
+                .map_err(|_| Error::new(Custom("json serialization failed")))?;
+
+                self.send_json_response(session, StatusCode::OK, body)
+                    .await?;
+                Ok(true)
+            }
+
+            AdminEndpoint::DeviceReset { device } => {
+                // Return early when not a POST request.
+                if session.req_header().method != http::Method::POST {
+                    self.send_post_only_response(session).await?;
+                    return Ok(true);
+                }
+
+                let state = self.state.load();
+                let Some(loaded) = state.devices.get(&device) else {
+                    self.send_json_error(session, StatusCode::NOT_FOUND, "unknown device")
+                        .await?;
+                    return Ok(true);
+                };
+
+                if !loaded.reset() {
+                    let message = "device keeps no state to reset";
+                    self.send_json_error(session, StatusCode::BAD_REQUEST, message)
+                        .await?;
+                    return Ok(true);
+                }
+                tracing::info!(%device, "device state reset");
+
+                let body = serde_json::to_vec(&serde_json::json!({
+                    "device": device,
+                    "reset": true
+                }))
+                .map_err(|_| Error::new(Custom("json serialization failed")))?;
+
+                self.send_json_response(session, StatusCode::OK, body)
+                    .await?;
+                Ok(true)
+            }
         }
     }
 
+    async fn send_post_only_response(&self, session: &mut Session) -> pingora::Result<()> {
+        let mut resp = ResponseHeader::build(StatusCode::METHOD_NOT_ALLOWED, None)?;
+        resp.insert_header(header::ALLOW, "POST")?;
+        resp.insert_header(header::CONTENT_LENGTH, "0")?;
+        session.write_response_header(Box::new(resp), true).await?;
+
+        Ok(())
+    }
+
+    async fn send_json_error(
+        &self,
+        session: &mut Session,
+        status: StatusCode,
+        message: &str,
+    ) -> pingora::Result<()> {
+        let body = serde_json::to_vec(&serde_json::json!({ "error": message }))
+            .map_err(|_| Error::new(Custom("json serialization failed")))?;
+
+        self.send_json_response(session, status, body).await
+    }
+
     async fn send_json_response(
         &self,
         session: &mut Session,
@@ -196,3 +461,22 @@ impl AdminHandler {
         Ok(())
     }
 }
+
+/// Read the request body as JSON, `None` if it is too large or does not parse.
+/// An empty body reads as `{}`, so requests whose fields are all optional may omit it.
+async fn read_json_body<T: for<'de> Deserialize<'de>>(
+    session: &mut Session,
+) -> pingora::Result<Option<T>> {
+    let mut body = Vec::new();
+    while let Some(chunk) = session.read_request_body().await? {
+        if body.len() + chunk.len() > MAX_ADMIN_BODY_BYTES {
+            return Ok(None);
+        }
+        body.extend_from_slice(&chunk);
+    }
+    if body.is_empty() {
+        body.extend_from_slice(b"{}");
+    }
+
+    Ok(serde_json::from_slice(&body).ok())
+}
@@ -1,4 +1,4 @@
-mod admin;
+pub(super) mod admin;
 mod static_file;
 
 pub(crate) use admin::AdminHandler;
@@ -1,8 +1,9 @@
 use crate::ctx::RequestCtx;
-use crate::device::core::registry::DeviceRegistry;
+use crate::device::core::Device;
 use crate::route::RouteEntry;
 use pingora::prelude::Session;
 use pingora::{Custom, Error};
+use std::sync::Arc;
 
 pub struct StaticFileHandler;
 
@@ -13,7 +14,7 @@ impl StaticFileHandler {
         _session: &mut Session,
         _ctx: &RequestCtx,
         _route: &RouteEntry,
-        _devices: &DeviceRegistry,
+        _devices: &[Arc<dyn Device>],
     ) -> pingora::Result<bool> {
         Err(Error::new(Custom("static files disabled")))
     }
@@ -24,13 +25,12 @@ impl StaticFileHandler {
         session: &mut Session,
         ctx: &RequestCtx,
         route: &RouteEntry,
-        devices: &DeviceRegistry,
+        devices: &[Arc<dyn Device>],
     ) -> pingora::Result<bool> {
         use crate::ctx::{RequestId, ResponseCtx};
         use crate::device::core::DeviceResult;
         use crate::device::core::pipeline::DevicePipeline;
         use pingora::http::ResponseHeader;
-        use tokio::io::AsyncReadExt;
 
         // Extract conditional headers for cache validation and content negotiation.
         let conditional = crate::static_files::ConditionalHeaders {
@@ -125,34 +125,28 @@ impl StaticFileHandler {
 
                 crate::static_files::StaticBody::RangedFile {
                     mut file,
-                    mut remaining,
+                    remaining,
                 } => {
-                    const CHUNK_SIZE: usize = 32 * 1024;
-                    let mut buf = bytes::BytesMut::with_capacity(CHUNK_SIZE);
-
-                    while remaining > 0 {
-                        let to_read = std::cmp::min(CHUNK_SIZE as u64, remaining) as usize;
+                    write_file_bytes(session, &mut file, remaining).await?;
+                    session.write_response_body(None, true).await?;
+                }
 
-                        buf.resize(to_read, 0);
+                crate::static_files::StaticBody::MultipartFile {
+                    mut file,
+                    parts,
+                    closing,
+                } => {
+                    use tokio::io::AsyncSeekExt;
 
-                        let n = file
-                            .read(&mut buf[..])
+                    for part in parts {
+                        session.write_response_body(Some(part.head), false).await?;
+                        file.seek(std::io::SeekFrom::Start(part.start))
                             .await
-                            .map_err(|_| Error::new(Custom("static file read error")))?;
-
-                        if n == 0 {
-                            break;
-                        }
-
-                        remaining -= n as u64;
-                        buf.truncate(n);
-
-                        session
-                            .write_response_body(Some(buf.split().freeze()), false)
-                            .await?;
+                            .map_err(|_| Error::new(Custom("static file seek error")))?;
+                        write_file_bytes(session, &mut file, part.len).await?;
                     }
 
-                    session.write_response_body(None, true).await?;
+                    session.write_response_body(Some(closing), true).await?;
                 }
             }
         }
@@ -166,7 +160,7 @@ impl StaticFileHandler {
             Vec::new(),
         );
 
-        match DevicePipeline::run_on_response(devices.all(), &mut resp_ctx) {
+        match DevicePipeline::run_on_response(devices, &mut resp_ctx) {
             DeviceResult::Continue => {}
             DeviceResult::Respond(_) => {}
             DeviceResult::Error(err) => {
@@ -177,3 +171,40 @@ impl StaticFileHandler {
         Ok(true)
     }
 }
+
+/// Stream the next `remaining` bytes of the file to the client, without ending the body.
+#[cfg(feature = "static_files")]
+async fn write_file_bytes(
+    session: &mut Session,
+    file: &mut tokio::fs::File,
+    mut remaining: u64,
+) -> pingora::Result<()> {
+    use tokio::io::AsyncReadExt;
+
+    const CHUNK_SIZE: usize = 32 * 1024;
+    let mut buf = bytes::BytesMut::with_capacity(CHUNK_SIZE);
+
+    while remaining > 0 {
+        let to_read = std::cmp::min(CHUNK_SIZE as u64, remaining) as usize;
+
+        buf.resize(to_read, 0);
+
+        let n = file
+            .read(&mut buf[..])
+            .await
+            .map_err(|_| Error::new(Custom("static file read error")))?;
+
+        if n == 0 {
+            break;
+        }
+
+        remaining -= n as u64;
+        buf.truncate(n);
+
+        session
+            .write_response_body(Some(buf.split().freeze()), false)
+            .await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use http::{HeaderValue, Version};
+use pingora::http::RequestHeader;
+use pingora::prelude::*;
+
+/// Rewrites the configured header names so they are serialized toward the upstream
+/// with exactly the casing given by the operator (e.g. `WWW-Authenticate`).
+///
+/// Only HTTP/1.1 upstream connections are affected - HTTP/2 forces lowercase header names.
+pub(crate) fn apply_upstream_header_case(
+    upstream: &mut RequestHeader,
+    header_names: &[String],
+) -> Result<()> {
+    if upstream.version == Version::HTTP_2 {
+        return Ok(());
+    }
+
+    for name in header_names {
+        let values = upstream
+            .headers
+            .get_all(name.as_str())
+            .iter()
+            .cloned()
+            .collect::<Vec<HeaderValue>>();
+
+        if values.is_empty() {
+            continue;
+        }
+
+        // Re-insert every value under the cased name, preserving multi-value headers.
+        upstream.remove_header(name.as_str());
+        for value in values {
+            upstream.append_header(name.clone(), value)?;
+        }
+    }
+
+    Ok(())
+}
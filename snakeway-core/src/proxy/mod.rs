@@ -1,10 +1,23 @@
+mod accept_encoding;
 mod admin_gateway;
+mod device_headers;
 mod error_classification;
 mod gateway_ctx;
 mod handlers;
+mod header_case;
+mod pipelining;
 mod public_gateway;
 mod redirect_gateway;
+mod request_deadline;
+mod response_idle;
+mod response_pacing;
+mod socket_watchdog;
+#[cfg(test)]
+mod tests;
+mod upstream_dns;
+mod upstream_sni;
 
 pub use admin_gateway::AdminGateway;
+pub(crate) use pipelining::PipelinedHttp;
 pub use public_gateway::PublicGateway;
 pub use redirect_gateway::RedirectGateway;
@@ -0,0 +1,448 @@
+use async_trait::async_trait;
+use pingora::apps::ServerApp;
+use pingora::protocols::raw_connect::ProxyDigest;
+use pingora::protocols::tls::{SslDigest, TlsRef};
+use pingora::protocols::{
+    ALPN, GetProxyDigest, GetSocketDigest, GetTimingDigest, Peek, Shutdown, SocketDigest, Ssl,
+    Stream, TimingDigest, UniqueID, UniqueIDType,
+};
+use pingora::proxy::Session;
+use pingora::server::ShutdownWatch;
+use std::io;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker, ready};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// Largest request head that is framed. Pingora rejects larger heads anyway.
+const MAX_HEAD_BYTES: usize = 1024 * 1024;
+
+/// Largest chunk size or trailer line in a chunked request body.
+const MAX_CHUNK_LINE_BYTES: usize = 4096;
+
+/// Pipelined bytes buffered before the client is no longer read from.
+const MAX_BUFFERED_BYTES: usize = 64 * 1024;
+
+const READ_CHUNK_BYTES: usize = 8 * 1024;
+
+/// Serves HTTP/1.1 connections over a [`PipelinedStream`], so that pipelined requests are
+/// answered in order.
+///
+/// HTTP/2 connections are served as they are, since they multiplex requests themselves.
+pub(crate) struct PipelinedHttp<A> {
+    app: Arc<A>,
+}
+
+impl<A> PipelinedHttp<A> {
+    pub(crate) fn new(app: A) -> Self {
+        Self { app: Arc::new(app) }
+    }
+}
+
+#[async_trait]
+impl<A> ServerApp for PipelinedHttp<A>
+where
+    A: ServerApp + Send + Sync + 'static,
+{
+    async fn process_new(
+        self: &Arc<Self>,
+        stream: Stream,
+        shutdown: &ShutdownWatch,
+    ) -> Option<Stream> {
+        let stream: Stream = if matches!(stream.selected_alpn_proto(), Some(ALPN::H2)) {
+            stream
+        } else {
+            Box::new(PipelinedStream::new(stream))
+        };
+        self.app.process_new(stream, shutdown).await
+    }
+
+    async fn cleanup(&self) {
+        self.app.cleanup().await;
+    }
+}
+
+/// Let a request pipelined behind the one in `session` be read, now that its response is done.
+pub(crate) fn release_pipelined_request(session: &Session) {
+    if let Some(stream) = session.as_downstream().stream()
+        && let Some(stream) = stream.as_any().downcast_ref::<PipelinedStream>()
+    {
+        stream.release();
+    }
+}
+
+/// A client connection that only hands Pingora the request it is serving.
+///
+/// Pingora reads ahead of the request it is serving, and drops what it read past it once the
+/// response is sent. A request pipelined behind another would be lost, or fail the first one if
+/// it arrived while that was being proxied. Requests are framed here instead: once a request
+/// head and its body have been read, anything after them is buffered until the gateway releases
+/// the connection after the response, and is then read as the next request.
+///
+/// Connections that stop carrying framed requests, like upgrades to WebSocket or HTTP/2, are
+/// passed through from then on.
+#[derive(Debug)]
+pub(crate) struct PipelinedStream {
+    inner: Stream,
+    framing: Framing,
+    /// Bytes read from the client that have not been handed to Pingora yet.
+    buffered: Vec<u8>,
+    eof: bool,
+    released: AtomicBool,
+    waker: Mutex<Option<Waker>>,
+}
+
+impl PipelinedStream {
+    pub(crate) fn new(inner: Stream) -> Self {
+        Self {
+            inner,
+            framing: Framing::Head(Vec::new()),
+            buffered: Vec::new(),
+            eof: false,
+            released: AtomicBool::new(false),
+            waker: Mutex::new(None),
+        }
+    }
+
+    /// Start reading the next request once the current one has been read.
+    pub(crate) fn release(&self) {
+        self.released.store(true, Ordering::Release);
+        if let Some(waker) = self.waker.lock().expect("pipelining waker poisoned").take() {
+            waker.wake();
+        }
+    }
+}
+
+impl AsyncRead for PipelinedStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if buf.remaining() == 0 {
+            return Poll::Ready(Ok(()));
+        }
+
+        loop {
+            if matches!(this.framing, Framing::Complete)
+                && this.released.swap(false, Ordering::AcqRel)
+            {
+                this.framing = Framing::Head(Vec::new());
+            }
+            if matches!(this.framing, Framing::PassThrough) && this.buffered.is_empty() {
+                return Pin::new(&mut this.inner).poll_read(cx, buf);
+            }
+
+            let available = this.buffered.len().min(buf.remaining());
+            let taken = this.framing.advance(&this.buffered[..available]);
+            if taken > 0 {
+                buf.put_slice(&this.buffered[..taken]);
+                this.buffered.drain(..taken);
+                return Poll::Ready(Ok(()));
+            }
+
+            if matches!(this.framing, Framing::Complete) {
+                *this.waker.lock().expect("pipelining waker poisoned") = Some(cx.waker().clone());
+                if this.released.load(Ordering::Acquire) {
+                    continue;
+                }
+                // A client that closed the connection is reported at once, unless it pipelined
+                // requests before closing, which are still answered.
+                if this.eof {
+                    return if this.buffered.is_empty() {
+                        Poll::Ready(Ok(()))
+                    } else {
+                        Poll::Pending
+                    };
+                }
+                if this.buffered.len() >= MAX_BUFFERED_BYTES {
+                    return Poll::Pending;
+                }
+            } else if this.eof {
+                return Poll::Ready(Ok(()));
+            }
+
+            let mut chunk = [0u8; READ_CHUNK_BYTES];
+            let mut read = ReadBuf::new(&mut chunk);
+            ready!(Pin::new(&mut this.inner).poll_read(cx, &mut read))?;
+            if read.filled().is_empty() {
+                this.eof = true;
+            } else {
+                this.buffered.extend_from_slice(read.filled());
+            }
+        }
+    }
+}
+
+impl AsyncWrite for PipelinedStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().inner).poll_write(cx, buf)
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().inner).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
+
+#[async_trait]
+impl Shutdown for PipelinedStream {
+    async fn shutdown(&mut self) {
+        self.inner.shutdown().await;
+    }
+}
+
+impl UniqueID for PipelinedStream {
+    fn id(&self) -> UniqueIDType {
+        self.inner.id()
+    }
+}
+
+impl Ssl for PipelinedStream {
+    fn get_ssl(&self) -> Option<&TlsRef> {
+        self.inner.get_ssl()
+    }
+
+    fn get_ssl_digest(&self) -> Option<Arc<SslDigest>> {
+        self.inner.get_ssl_digest()
+    }
+
+    fn selected_alpn_proto(&self) -> Option<ALPN> {
+        self.inner.selected_alpn_proto()
+    }
+}
+
+impl GetTimingDigest for PipelinedStream {
+    fn get_timing_digest(&self) -> Vec<Option<TimingDigest>> {
+        self.inner.get_timing_digest()
+    }
+
+    fn get_read_pending_time(&self) -> Duration {
+        self.inner.get_read_pending_time()
+    }
+
+    fn get_write_pending_time(&self) -> Duration {
+        self.inner.get_write_pending_time()
+    }
+}
+
+impl GetProxyDigest for PipelinedStream {
+    fn get_proxy_digest(&self) -> Option<Arc<ProxyDigest>> {
+        self.inner.get_proxy_digest()
+    }
+
+    fn set_proxy_digest(&mut self, digest: ProxyDigest) {
+        self.inner.set_proxy_digest(digest);
+    }
+}
+
+impl GetSocketDigest for PipelinedStream {
+    fn get_socket_digest(&self) -> Option<Arc<SocketDigest>> {
+        self.inner.get_socket_digest()
+    }
+
+    fn set_socket_digest(&mut self, socket_digest: SocketDigest) {
+        self.inner.set_socket_digest(socket_digest);
+    }
+}
+
+#[async_trait]
+impl Peek for PipelinedStream {
+    async fn try_peek(&mut self, buf: &mut [u8]) -> io::Result<bool> {
+        if self.buffered.is_empty() {
+            return self.inner.try_peek(buf).await;
+        }
+        let n = buf.len().min(self.buffered.len());
+        buf[..n].copy_from_slice(&self.buffered[..n]);
+        Ok(true)
+    }
+}
+
+/// Where the client is in the request being read.
+#[derive(Debug)]
+enum Framing {
+    /// Reading a request head, kept to learn how its body is framed.
+    Head(Vec<u8>),
+    /// Reading a body with this many bytes left.
+    Body(u64),
+    /// Reading a chunked body.
+    Chunked(Chunk),
+    /// The request has been read. What follows waits until it is released.
+    Complete,
+    /// The connection no longer carries framed requests.
+    PassThrough,
+}
+
+#[derive(Debug)]
+enum Chunk {
+    Size(Vec<u8>),
+    Data(u64),
+    DataEnd,
+    Trailer(Vec<u8>),
+}
+
+impl Framing {
+    /// Advance over `bytes`, and return how many of them belong to the current request.
+    fn advance(&mut self, bytes: &[u8]) -> usize {
+        let mut taken = 0;
+        while taken < bytes.len() {
+            let rest = &bytes[taken..];
+            match self {
+                Framing::Complete => break,
+                Framing::PassThrough => return bytes.len(),
+                Framing::Head(head) => {
+                    head.push(rest[0]);
+                    taken += 1;
+                    if head_complete(head) {
+                        // The head is handed over on its own, ahead of its body.
+                        *self = body_framing(head);
+                        return taken;
+                    } else if head.len() > MAX_HEAD_BYTES {
+                        *self = Framing::PassThrough;
+                    }
+                }
+                Framing::Body(left) => {
+                    let n = body_bytes(rest, *left);
+                    taken += n;
+                    *left -= n as u64;
+                    if *left == 0 {
+                        *self = Framing::Complete;
+                    }
+                }
+                Framing::Chunked(Chunk::Data(left)) => {
+                    let n = body_bytes(rest, *left);
+                    taken += n;
+                    *left -= n as u64;
+                    if *left == 0 {
+                        *self = Framing::Chunked(Chunk::DataEnd);
+                    }
+                }
+                Framing::Chunked(Chunk::DataEnd) => {
+                    taken += 1;
+                    if rest[0] == b'\n' {
+                        *self = Framing::Chunked(Chunk::Size(Vec::new()));
+                    }
+                }
+                Framing::Chunked(Chunk::Size(line)) => {
+                    line.push(rest[0]);
+                    taken += 1;
+                    if rest[0] == b'\n' {
+                        *self = match chunk_size(line) {
+                            Some(0) => Framing::Chunked(Chunk::Trailer(Vec::new())),
+                            Some(size) => Framing::Chunked(Chunk::Data(size)),
+                            None => Framing::PassThrough,
+                        };
+                    } else if line.len() > MAX_CHUNK_LINE_BYTES {
+                        *self = Framing::PassThrough;
+                    }
+                }
+                Framing::Chunked(Chunk::Trailer(line)) => {
+                    line.push(rest[0]);
+                    taken += 1;
+                    if rest[0] == b'\n' {
+                        if line.trim_ascii().is_empty() {
+                            *self = Framing::Complete;
+                        } else {
+                            line.clear();
+                        }
+                    } else if line.len() > MAX_CHUNK_LINE_BYTES {
+                        *self = Framing::PassThrough;
+                    }
+                }
+            }
+        }
+        taken
+    }
+}
+
+fn body_bytes(bytes: &[u8], left: u64) -> usize {
+    bytes.len().min(usize::try_from(left).unwrap_or(usize::MAX))
+}
+
+/// Whether `head` ends with the empty line closing a request head. Empty lines before the
+/// request line are skipped, as Pingora skips them.
+fn head_complete(head: &[u8]) -> bool {
+    match head.iter().position(|b| !matches!(b, b'\r' | b'\n')) {
+        Some(start) => head[start..].ends_with(b"\r\n\r\n") || head[start..].ends_with(b"\n\n"),
+        None => false,
+    }
+}
+
+/// How the body after a request head is framed.
+///
+/// Heads Pingora would reject, like ones with conflicting lengths, are passed through, and
+/// Pingora closes their connection after answering them.
+fn body_framing(head: &[u8]) -> Framing {
+    let head = String::from_utf8_lossy(head);
+    let mut lines = head.lines().map(str::trim).filter(|line| !line.is_empty());
+
+    // The cleartext HTTP/2 preface, and tunnels, are not followed by another request.
+    if lines
+        .next()
+        .is_none_or(|line| line.starts_with("PRI ") || line.starts_with("CONNECT "))
+    {
+        return Framing::PassThrough;
+    }
+
+    let mut length = None;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("upgrade") {
+            return Framing::PassThrough;
+        }
+        if name.eq_ignore_ascii_case("transfer-encoding") {
+            let chunked = value
+                .rsplit(',')
+                .next()
+                .is_some_and(|coding| coding.trim().eq_ignore_ascii_case("chunked"));
+            return if chunked {
+                Framing::Chunked(Chunk::Size(Vec::new()))
+            } else {
+                Framing::PassThrough
+            };
+        }
+        if name.eq_ignore_ascii_case("content-length") {
+            match value.parse::<u64>() {
+                Ok(n) if length.is_none_or(|l| l == n) => length = Some(n),
+                _ => return Framing::PassThrough,
+            }
+        }
+    }
+
+    match length {
+        Some(n) if n > 0 => Framing::Body(n),
+        _ => Framing::Complete,
+    }
+}
+
+/// Size of the chunk announced by `line`, ignoring chunk extensions.
+fn chunk_size(line: &[u8]) -> Option<u64> {
+    let line = std::str::from_utf8(line).ok()?;
+    let size = line.split(';').next()?.trim();
+    u64::from_str_radix(size, 16).ok()
+}
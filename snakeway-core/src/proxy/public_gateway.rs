@@ -1,45 +1,85 @@
-use crate::ctx::{RequestCtx, RequestId, ResponseCtx, WsCloseCtx, WsCtx};
+use crate::conf::types::{RequireTlsAction, RequireTlsConfig};
+use crate::ctx::{ListenerInfo, RequestCtx, RequestId, ResponseCtx, WsCloseCtx, WsCtx};
+use crate::device::core::errors::DeviceError;
 use crate::device::core::pipeline::DevicePipeline;
 use crate::device::core::result::DeviceResult;
+use crate::proxy::accept_encoding::{
+    RESPONSE_COMPRESSION_LEVEL, apply_upstream_accept_encoding, skip_response_compression,
+};
+use crate::proxy::device_headers::sync_response_headers;
 use crate::proxy::error_classification::classify_pingora_error;
 use crate::proxy::gateway_ctx::GatewayCtx;
 use crate::proxy::handlers::StaticFileHandler;
+use crate::proxy::header_case::apply_upstream_header_case;
+use crate::proxy::pipelining::release_pipelined_request;
+use crate::proxy::request_deadline::{
+    REQUEST_TIMED_OUT, RequestDeadline, RequestDeadlineWatchdog, RequestTimedOut,
+};
+use crate::proxy::response_idle::{ResponseIdleTimeout, ResponseIdleWatchdog, UpstreamSocket};
+use crate::proxy::response_pacing::ResponsePacer;
+use crate::proxy::upstream_dns::{DnsFailure, UpstreamResolver};
+use crate::proxy::upstream_sni::render_upstream_sni;
 use crate::route::RouteRuntime;
-use crate::runtime::{RuntimeState, UpstreamRuntime};
+use crate::runtime::{RuntimeState, UpstreamRuntime, UpstreamTcpRuntime};
+use crate::server::{
+    AccessLogEntry, SampledConnection, ShutdownCoordinator, log_access, log_websocket_closed,
+    log_websocket_opened,
+};
 use crate::traffic_management::{
-    AdmissionGuard, SelectedUpstream, ServiceId, TrafficDirector, TrafficManager, UpstreamOutcome,
+    AdmissionGuard, SelectedUpstream, ServiceId, TrafficDirector, TrafficManager, TransportFailure,
+    UpstreamOutcome,
 };
 use crate::ws_connection_management::WsConnectionManager;
 use arc_swap::ArcSwap;
 use async_trait::async_trait;
 use bytes::Bytes;
 use http::{StatusCode, Version, header};
+use ipnet::IpNet;
+use pingora::ErrorSource;
 use pingora::http::{RequestHeader, ResponseHeader};
+use pingora::modules::http::compression::ResponseCompression;
 use pingora::prelude::*;
+use std::os::unix::io::RawFd;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Error reason for upstream hostnames that could not be resolved.
+pub(crate) const UPSTREAM_DNS_FAILURE: &str = "upstream DNS resolution failed";
+
+/// Error reported to devices when a response body stalls past `response_idle_timeout_milliseconds`.
+pub(crate) const UPSTREAM_RESPONSE_STALLED: &str = "upstream response body stalled";
+
+/// Response body set by an `after_proxy` device, sent instead of the upstream body.
+#[derive(Debug, Clone)]
+struct ReplacementBody(Bytes);
 
 /// PublicGateway is the core orchestration abstraction in Snakeway.
 /// It wraps Pingora hooks and applies traffic decisions and device lifecycle hooks.
 pub struct PublicGateway {
-    listener: Arc<str>,
+    listener: ListenerInfo,
     gw_ctx: GatewayCtx,
     traffic_director: TrafficDirector,
+    upstream_resolver: UpstreamResolver,
     static_file_handler: StaticFileHandler,
+    shutdown: Arc<ShutdownCoordinator>,
 }
 
 impl PublicGateway {
     pub fn new(
-        listener: Arc<str>,
+        listener: ListenerInfo,
         state: Arc<ArcSwap<RuntimeState>>,
         traffic_manager: Arc<TrafficManager>,
         connection_manager: Arc<WsConnectionManager>,
+        shutdown: Arc<ShutdownCoordinator>,
     ) -> Self {
         let gw_ctx = GatewayCtx::new(state, traffic_manager.clone(), connection_manager);
         Self {
             listener,
             gw_ctx,
             traffic_director: TrafficDirector,
+            upstream_resolver: UpstreamResolver::system(),
             static_file_handler: StaticFileHandler,
+            shutdown,
         }
     }
 }
@@ -50,41 +90,51 @@ impl PublicGateway {
 /// especially because it might change in later Pingora versions.
 ///
 /// 1. new_ctx()
-///    - Allocate empty RequestCtx
+///    - Allocate empty RequestCtx, tagged with the listener
 ///
 /// 2. [unused] early_request_filter()
 ///    - Earliest hook
 ///    - Runs before downstream modules
 ///
 /// 3. request_filter()
+///    - Track the request as in-flight
 ///    - Hydrate ctx from Session
-///    - Run on_request devices
+///    - Redirect or reject plaintext requests to routes requiring TLS
+///    - Run on_request devices, then on_request_async devices
 ///    - Route match (static vs proxy)
+///    - Enable downstream compression if the service asks for it
 ///    - Static responses end here
 ///
 /// 4. request_body_filter()
-///    - Exists on ProxyHttp but not invoked in the main request path
-///    - Downstream request bodies are streamed directly to upstream
+///    - Count request body bytes
+///    - Run on_stream_request_body devices on each chunk
 ///
 /// 5. [unused] proxy_upstream_filter()
 ///    - Final decision whether request is allowed upstream
 ///    - May short-circuit with a response
 ///
 /// 6. upstream_peer()
+///    - Refuse requests already past their deadline
 ///    - Select upstream (TrafficDirector)
 ///    - Circuit admission decision
+///    - Resolve hostname upstreams (DNS failures are retried like connect failures)
 ///    - Create AdmissionGuard if admitted
 ///    - Construct HttpPeer
 ///
-/// 7. [unused] upstream_request_filter()
-///    - (NOTE: no such hook in Pingora; upstream request mutation is implicit)
+/// 7. upstream_request_filter()
+///    - Run before_proxy devices
+///    - Apply the service's upstream header policies, including injected headers
 ///
 /// 8. [Pingora upstream I/O]
 ///    - Connect, TLS, send request, receive response
+///    - connected_to_upstream() records the upstream socket, and starts the request deadline watchdog
 ///
 /// 9. upstream_response_filter()
 ///    - Run after_proxy devices
+///    - Retry statuses the service's retry_policy retries
 ///    - Mutate response headers/status
+///    - Take over the response body if a device replaced it
+///    - Start the response idle watchdog if the upstream has a response_idle_timeout
 ///
 /// 10. [unused] upstream_response_body_filter()
 ///     - Run on each upstream response body chunk
@@ -92,31 +142,48 @@ impl PublicGateway {
 /// 11. [unused] upstream_response_trailer_filter()
 ///     - Run on upstream response trailers (if any)
 ///
-/// 12. [unused] error_while_proxy()
+/// 12. response_body_filter()
+///     - Swap in a device's replacement body
+///     - Count response body bytes
+///     - Run on_stream_response_body devices on each chunk
+///     - Pace each downstream body chunk to the route's response_rate_limit
+///     - Reset the response idle watchdog
+///     - Stop the request deadline watchdog once the body is complete
+///
+/// 13. error_while_proxy()
 ///     - Called if upstream fails mid-stream
+///     - Fail with 504 if the request ran past its deadline
+///     - Retry as the service's retry_policy allows, if nothing was sent downstream yet
 ///
-/// 13. [unused] fail_to_connect()
+/// 14. fail_to_connect()
 ///     - Called if upstream connection cannot be established
+///     - Fail with 504 if the request ran past its deadline
+///     - Retry up to the upstream's max_connect_retries
 ///
-/// 14. fail_to_proxy()
+/// 15. fail_to_proxy()
 ///     - Final error handling hook after retries exhausted
 ///
-/// 15. [unused] suppress_error_log()
+/// 16. [unused] suppress_error_log()
 ///     - Decide whether Pingora logs proxy failure
 ///
-/// 16. [unused] response_filter()
-///     - (NOTE: no downstream response hook; handled via Session APIs)
+/// 17. response_filter()
+///     - Turn downstream compression back off for skipped content types and small responses
+///     - Run on_response devices
+///     - Add device response headers
 ///
-/// 17. logging() ...ALWAYS LAST
+/// 18. logging() ...ALWAYS LAST
 ///     - Capture transport errors
 ///     - Run on_ws_close if needed
+///     - Stop the response idle watchdog, and run on_error devices if it fired
+///     - Record a request that ran past its deadline as an upstream timeout
 ///     - Finalize AdmissionGuard (circuit success/failure)
+///     - Run on_complete devices with the final byte counts
 #[async_trait]
 impl ProxyHttp for PublicGateway {
     type CTX = RequestCtx;
 
     fn new_ctx(&self) -> Self::CTX {
-        RequestCtx::empty()
+        RequestCtx::for_listener(self.listener.clone())
     }
 
     /// Select upstream and enforce protocol rules
@@ -133,18 +200,48 @@ impl ProxyHttp for PublicGateway {
             .ok_or_else(|| Error::new(Custom("no service selected")))?;
         let service_id = ServiceId(service_name.clone());
 
+        // Devices or earlier attempts may have used up the request's time already.
+        let deadline = ctx.extensions.get::<RequestDeadline>().copied();
+        if deadline.is_some_and(|deadline| deadline.passed()) {
+            tracing::warn!("{REQUEST_TIMED_OUT}");
+            return Err(Error::explain(
+                HTTPStatus(StatusCode::GATEWAY_TIMEOUT.as_u16()),
+                REQUEST_TIMED_OUT,
+            ));
+        }
+
         let selected_upstream = self.select_upstream(ctx, &state, &service_id, service_name)?;
         let upstream = selected_upstream.upstream;
 
+        // Hostname upstreams are resolved per connection, so DNS changes are picked up.
+        let resolved_addr = match upstream {
+            UpstreamRuntime::Tcp(UpstreamTcpRuntime {
+                hostname: Some(hostname),
+                port,
+                ..
+            }) => match self.upstream_resolver.resolve(hostname, *port).await {
+                Ok(addr) => Some(addr),
+                Err(failure) => {
+                    return Err(self.fail_to_resolve(
+                        ctx,
+                        &service_id,
+                        upstream,
+                        selected_upstream.cb_started,
+                        failure,
+                    ));
+                }
+            },
+            _ => None,
+        };
+
         // Creating an HttpPeer instance per request may raise an eyebrow, but
         // it is merely a sort of configuration object that is used by Pingora
         // to compute a hash later when its internal pooling logic runs.
         let mut peer = match upstream {
-            UpstreamRuntime::Tcp(tcp) => Ok(HttpPeer::new(
-                tcp.http_peer_addr(),
-                tcp.use_tls,
-                tcp.sni.clone(),
-            )),
+            UpstreamRuntime::Tcp(tcp) => Ok(match resolved_addr {
+                Some(addr) => HttpPeer::new(addr, tcp.use_tls, tcp.sni.clone()),
+                None => HttpPeer::new(tcp.http_peer_addr(), tcp.use_tls, tcp.sni.clone()),
+            }),
             UpstreamRuntime::Unix(unix) => {
                 HttpPeer::new_uds(&unix.path, unix.use_tls, unix.sni.clone()).map_err(|e| {
                     anyhow::anyhow!(
@@ -157,6 +254,47 @@ impl ProxyHttp for PublicGateway {
         }
         .map_err(|_| Error::new(Custom("http peer creation failed")))?;
 
+        // Multi-tenant TLS upstreams may need an SNI that follows the requested host.
+        if upstream.use_tls()
+            && let Some(template) = state
+                .services
+                .get(service_name)
+                .and_then(|s| s.upstream_sni.as_deref())
+        {
+            peer.sni = render_upstream_sni(template, ctx.host()).ok_or_else(|| {
+                tracing::warn!(host = ?ctx.host(), "request host yields an invalid upstream SNI");
+                Error::new(HTTPStatus(StatusCode::BAD_REQUEST.as_u16()))
+            })?;
+        }
+
+        // Apply the upstream's effective connection settings.
+        let connection = upstream.connection();
+        peer.options.connection_timeout = Some(Duration::from_millis(
+            connection.connect_timeout_milliseconds,
+        ));
+        peer.options.read_timeout =
+            Some(Duration::from_millis(connection.read_timeout_milliseconds));
+        peer.options.idle_timeout = Some(Duration::from_secs(connection.idle_timeout_seconds));
+
+        // Connecting and waiting for the response headers stop at the request deadline.
+        if let Some(deadline) = deadline {
+            let remaining = deadline.remaining();
+            peer.options.connection_timeout =
+                peer.options.connection_timeout.map(|t| t.min(remaining));
+            peer.options.read_timeout = peer.options.read_timeout.map(|t| t.min(remaining));
+        }
+
+        ctx.max_connect_retries = connection.max_connect_retries;
+        if connection.response_idle_timeout_milliseconds > 0 {
+            ctx.extensions
+                .insert(ResponseIdleTimeout(Duration::from_millis(
+                    connection.response_idle_timeout_milliseconds,
+                )));
+        } else {
+            // A retry may have selected an upstream without a response idle timeout.
+            ctx.extensions.remove::<ResponseIdleTimeout>();
+        }
+
         // Enforce protocol rules for this upstream and request.
         self.enforce_protocol(&mut peer, ctx, upstream)?;
 
@@ -173,6 +311,7 @@ impl ProxyHttp for PublicGateway {
             let guard = AdmissionGuard::new(
                 self.gw_ctx.traffic_manager.clone(),
                 service_id.clone(),
+                ctx.route_id.as_ref().map(|id| id.path()),
                 upstream.id(),
             );
 
@@ -186,6 +325,9 @@ impl ProxyHttp for PublicGateway {
 
     /// ACCEPT → INSPECT → ROUTE → (RESPOND | PROXY)
     async fn request_filter(&self, session: &mut Session, ctx: &mut Self::CTX) -> Result<bool> {
+        // Count the request as in-flight until ctx is dropped, so shutdown can drain it.
+        ctx.in_flight_guard = Some(self.shutdown.track_request());
+
         ctx.hydrate_from_session(session).map_err(|e| {
             tracing::warn!(error = %e, "request rejected during normalization");
             e.as_pingora_error()
@@ -193,12 +335,35 @@ impl ProxyHttp for PublicGateway {
 
         let state = self.gw_ctx.state();
 
+        // Under strict_host, unknown hosts are rejected instead of falling through to a route.
+        if !state.is_host_allowed(&self.listener.name, ctx.host()) {
+            tracing::warn!(host = ?ctx.host(), "request host is not declared on this listener");
+            session
+                .respond_error(StatusCode::MISDIRECTED_REQUEST.as_u16())
+                .await?;
+            return Ok(true);
+        }
+
+        // Routes requiring TLS are not served over plaintext HTTP, whatever devices would say.
+        if let Some(require_tls) = state.tls_required(&self.listener.name, ctx.canonical_path()) {
+            respond_tls_required(session, ctx, require_tls, &state.trusted_proxies).await?;
+            return Ok(true);
+        }
+
         // Run on_request devices first (applies to both static and upstream requests).
-        match DevicePipeline::run_on_request(state.devices.all(), ctx) {
+        match DevicePipeline::run_on_request_offloaded(
+            &state
+                .devices
+                .for_request(&self.listener.name, ctx.canonical_path()),
+            ctx,
+        )
+        .await
+        {
             DeviceResult::Continue => {}
 
             DeviceResult::Respond(resp) => {
-                session.respond_error(resp.status.as_u16()).await?;
+                let hide_as_404 = state.hides_denials(&self.listener.name, ctx.canonical_path());
+                respond_from_device(session, ctx, resp, hide_as_404).await?;
                 return Ok(true);
             }
 
@@ -209,10 +374,34 @@ impl ProxyHttp for PublicGateway {
             }
         }
 
+        // Then async on_request devices, which may await I/O.
+        match DevicePipeline::run_on_request_async(
+            &state
+                .devices
+                .for_request(&self.listener.name, ctx.canonical_path()),
+            ctx,
+        )
+        .await
+        {
+            DeviceResult::Continue => {}
+
+            DeviceResult::Respond(resp) => {
+                let hide_as_404 = state.hides_denials(&self.listener.name, ctx.canonical_path());
+                respond_from_device(session, ctx, resp, hide_as_404).await?;
+                return Ok(true);
+            }
+
+            DeviceResult::Error(err) => {
+                tracing::error!("device error in on_request_async: {err}");
+                session.respond_error(500).await?;
+                return Ok(true);
+            }
+        }
+
         // Make a decision about the route.
         let router = state
             .routers
-            .get(self.listener.as_ref())
+            .get(self.listener.name.as_ref())
             .ok_or_else(|| Error::new(Custom("no router for listener")))?;
 
         let route = match router.match_route(ctx.canonical_path()) {
@@ -234,8 +423,11 @@ impl ProxyHttp for PublicGateway {
                         .await?;
                     return Ok(true);
                 }
+                let devices = state
+                    .devices
+                    .for_request(&self.listener.name, ctx.canonical_path());
                 self.static_file_handler
-                    .handle(session, ctx, route, &state.devices)
+                    .handle(session, ctx, route, &devices)
                     .await
             }
 
@@ -244,9 +436,41 @@ impl ProxyHttp for PublicGateway {
                 upstream,
                 allow_websocket,
                 ws_max_connections,
+                response_rate_limit,
+                affinity_ttl,
+                max_request_body_bytes,
+                request_timeout,
                 ..
             } => {
                 ctx.route_id = Some(id.clone());
+                ctx.affinity_ttl = *affinity_ttl;
+
+                // WebSocket connections are long-lived, so only plain requests get a deadline.
+                if !ctx.is_upgrade_req()
+                    && let Some(timeout) = request_timeout.or(state.request_timeout)
+                {
+                    ctx.extensions
+                        .insert(RequestDeadline(ctx.started_at + timeout));
+                }
+
+                ctx.max_request_body_bytes =
+                    max_request_body_bytes.or(state.max_request_body_bytes);
+
+                // A body declared too large is refused before the upstream is contacted.
+                if let Some(max) = ctx.max_request_body_bytes
+                    && declared_body_length(session).is_some_and(|len| len > max)
+                {
+                    tracing::warn!(max, "request body too large");
+                    session
+                        .respond_error(StatusCode::PAYLOAD_TOO_LARGE.as_u16())
+                        .await?;
+                    return Ok(true);
+                }
+
+                if let Some(limit) = response_rate_limit {
+                    ctx.extensions
+                        .insert(ResponsePacer::new(*limit, Instant::now()));
+                }
 
                 // If it is a websocket upgrade request, check if the upstream supports websockets.
                 if ctx.is_upgrade_req() {
@@ -257,16 +481,40 @@ impl ProxyHttp for PublicGateway {
                         return Ok(true);
                     }
 
-                    // Acquire a connection slot for ws guard.
-                    let guard = self
-                        .gw_ctx
-                        .connection_manager
-                        .try_acquire(id, ws_max_connections.to_owned())
-                        .ok_or_else(|| Error::new(Custom("too many websocket connections")))?;
+                    // Acquire a connection slot for ws guard, refusing the upgrade when none is left.
+                    let Some(guard) = self.gw_ctx.connection_manager.try_acquire(
+                        id,
+                        ws_max_connections.to_owned(),
+                        state.ws_max_connections,
+                    ) else {
+                        tracing::warn!(route = ?id, "too many websocket connections");
+                        session
+                            .respond_error(StatusCode::SERVICE_UNAVAILABLE.as_u16())
+                            .await?;
+                        return Ok(true);
+                    };
 
                     ctx.ws_guard = Some(guard);
                 }
 
+                // Pingora's downstream compression module is installed disabled; enable it per service.
+                if state
+                    .services
+                    .get(upstream)
+                    .is_some_and(|s| s.enable_response_compression)
+                    && let Some(compression) = session
+                        .downstream_modules_ctx
+                        .get_mut::<ResponseCompression>()
+                {
+                    compression.adjust_level(RESPONSE_COMPRESSION_LEVEL);
+                }
+
+                // Retries are taken from the service's budget through this request's guard.
+                ctx.retry_budget = self
+                    .gw_ctx
+                    .traffic_manager
+                    .track_retry_budget(&ServiceId(upstream.clone()));
+
                 ctx.service = Some(upstream.clone());
                 Ok(false)
             }
@@ -282,11 +530,41 @@ impl ProxyHttp for PublicGateway {
         end_of_stream: bool,
         ctx: &mut Self::CTX,
     ) -> Result<()> {
+        if let Some(chunk) = body {
+            ctx.bytes_in += chunk.len() as u64;
+        }
+
+        // Bodies without a declared length are cut off once they pass the limit.
+        if let Some(max) = ctx.max_request_body_bytes
+            && ctx.bytes_in > max
+        {
+            tracing::warn!(max, "request body too large");
+            // The client is at fault, so the upstream is not counted as failing.
+            if let Some(mut guard) = ctx.admission_guard.take() {
+                guard.success();
+            }
+            return Err(Error::explain(
+                HTTPStatus(StatusCode::PAYLOAD_TOO_LARGE.as_u16()),
+                "request body too large",
+            ));
+        }
+
         let state = self.gw_ctx.state();
-        match DevicePipeline::on_stream_request_body(state.devices.all(), ctx, body, end_of_stream)
+        match DevicePipeline::on_stream_request_body_offloaded(
+            &state
+                .devices
+                .for_request(&self.listener.name, ctx.canonical_path()),
+            ctx,
+            body,
+            end_of_stream,
+        )
+        .await
         {
             DeviceResult::Continue => Ok(()),
-            DeviceResult::Respond(resp) => session.respond_error(resp.status.as_u16()).await,
+            DeviceResult::Respond(resp) => {
+                let hide_as_404 = state.hides_denials(&self.listener.name, ctx.canonical_path());
+                respond_from_device(session, ctx, resp, hide_as_404).await
+            }
             DeviceResult::Error(err) => {
                 tracing::error!("device error on_stream_request_body: {err}");
                 Err(Error::new(Custom("device error on_stream_request_body")))
@@ -315,11 +593,24 @@ impl ProxyHttp for PublicGateway {
 
         let state = self.gw_ctx.state();
 
-        match DevicePipeline::run_before_proxy(state.devices.all(), ctx) {
+        match DevicePipeline::run_before_proxy(
+            &state
+                .devices
+                .for_request(&self.listener.name, ctx.canonical_path()),
+            ctx,
+        ) {
             DeviceResult::Continue => {
                 // Applies upstream intent derived from the request context.
                 upstream.set_method(ctx.method().to_owned());
-                upstream.set_uri(ctx.upstream_path().parse().unwrap());
+                upstream.set_uri(ctx.upstream_uri().parse().unwrap());
+
+                // Remove, then add, headers devices attached to the request (e.g., rewritten headers).
+                for name in &ctx.upstream_remove_headers {
+                    upstream.remove_header(name);
+                }
+                for (name, value) in ctx.upstream_headers.iter() {
+                    upstream.insert_header(name.clone(), value.clone())?;
+                }
 
                 if ctx.is_upgrade_req() {
                     // Upgrade is an HTTP/1.1 mechanism (HTTP/2 forbids it)
@@ -331,6 +622,22 @@ impl ProxyHttp for PublicGateway {
                     upstream.insert_header(header::CONNECTION, "Upgrade")?;
                 }
 
+                if let Some(service) = ctx.service.as_ref().and_then(|s| state.services.get(s)) {
+                    apply_upstream_accept_encoding(
+                        upstream,
+                        service.upstream_accept_encoding.as_deref(),
+                    )?;
+
+                    // Static headers (e.g., API keys) replace anything the client sent under the
+                    // same name. They only exist on the upstream request, never in ctx or logs.
+                    for (name, value) in &service.upstream_request_headers {
+                        upstream.insert_header(name.clone(), value.clone())?;
+                    }
+
+                    // Legacy HTTP/1.1 backends may be picky about header-name casing.
+                    apply_upstream_header_case(upstream, &service.upstream_header_case)?;
+                }
+
                 Ok(())
             }
 
@@ -343,16 +650,55 @@ impl ProxyHttp for PublicGateway {
         }
     }
 
+    /// Remember the upstream socket, so a stalled response body can be aborted,
+    /// and tear the connection down at the request deadline.
+    async fn connected_to_upstream(
+        &self,
+        _session: &mut Session,
+        _reused: bool,
+        _peer: &HttpPeer,
+        fd: RawFd,
+        _digest: Option<&pingora::protocols::Digest>,
+        ctx: &mut Self::CTX,
+    ) -> Result<()> {
+        ctx.extensions.insert(UpstreamSocket(fd));
+
+        // HTTP/2 upstream connections are shared between requests, so those requests only get
+        // the capped connect and read timeouts.
+        if !ctx.is_http2()
+            && let Some(deadline) = ctx.extensions.get::<RequestDeadline>().copied()
+            && let Some(previous) = ctx
+                .extensions
+                .insert(RequestDeadlineWatchdog::start(fd, deadline))
+        {
+            previous.finish();
+        }
+        Ok(())
+    }
+
     /// Snakeway `after_proxy` --> Pingora `upstream_response_filter`
     ///
     /// Intent:
     /// MUTATE RESPONSE HEADERS / STATUS
     async fn upstream_response_filter(
         &self,
-        _session: &mut Session,
+        session: &mut Session,
         upstream: &mut ResponseHeader,
         ctx: &mut Self::CTX,
     ) -> Result<()> {
+        // Feeds the latency average the `ewma` strategy selects on.
+        if let Some(guard) = ctx.admission_guard.as_ref() {
+            guard.response_received();
+        }
+
+        // Nothing was sent downstream yet, so a status the service retries can be retried.
+        let status = upstream.status.as_u16();
+        if self.try_retry(session, ctx, UpstreamOutcome::HttpStatus(status)) {
+            let mut e = Error::new_up(HTTPStatus(status));
+            e.set_retry(true);
+            return Err(e);
+        }
+
         let request_id = ctx.extensions.get::<RequestId>().map(|id| id.0.clone());
         let mut resp_ctx = ResponseCtx::new(
             request_id,
@@ -360,9 +706,15 @@ impl ProxyHttp for PublicGateway {
             upstream.headers.clone(),
             Vec::new(),
         );
+        resp_ctx.route_id = ctx.route_id.clone();
         let state = self.gw_ctx.state();
 
-        match DevicePipeline::run_after_proxy(state.devices.all(), &mut resp_ctx) {
+        match DevicePipeline::run_after_proxy(
+            &state
+                .devices
+                .for_request(&self.listener.name, ctx.canonical_path()),
+            &mut resp_ctx,
+        ) {
             DeviceResult::Continue => {}
             DeviceResult::Respond(_) => {}
             DeviceResult::Error(err) => {
@@ -372,6 +724,20 @@ impl ProxyHttp for PublicGateway {
         }
 
         upstream.set_status(resp_ctx.status)?;
+        sync_response_headers(upstream, &resp_ctx.headers)?;
+
+        // The upstream body is dropped in response_body_filter, so describe the replacement.
+        if !resp_ctx.body.is_empty() {
+            upstream.remove_header(&header::CONTENT_ENCODING);
+            upstream.remove_header(&header::TRANSFER_ENCODING);
+            upstream.remove_header(&header::ETAG);
+            upstream.insert_header(header::CONTENT_LENGTH, resp_ctx.body.len())?;
+            if let Some(content_type) = resp_ctx.headers.get(header::CONTENT_TYPE) {
+                upstream.insert_header(header::CONTENT_TYPE, content_type.clone())?;
+            }
+            ctx.extensions
+                .insert(ReplacementBody(Bytes::from(resp_ctx.body)));
+        }
 
         if ctx.is_upgrade_req() && upstream.status == StatusCode::SWITCHING_PROTOCOLS {
             // WS upgrade completed.
@@ -379,8 +745,45 @@ impl ProxyHttp for PublicGateway {
             // must NOT run for this request.
             ctx.ws_opened = true;
 
+            // The connection stays on this upstream until it closes, whatever its health does
+            // meanwhile. Report the upgrade now, so a long session neither holds a half-open probe
+            // slot nor counts its eventual close against the upstream's circuit.
+            if let Some(mut guard) = ctx.admission_guard.take() {
+                guard.success();
+            }
+
+            let ws = WsCtx {
+                request_id: ctx.request_id(),
+                path: ctx.original_uri_path().to_string(),
+            };
+
+            // Decide once per connection, so the close event follows the open event.
+            let sampled = state.connection_log.sample();
+            if sampled {
+                ctx.extensions.insert(SampledConnection);
+            }
+            log_websocket_opened(&ws, sampled);
+
             // Run WS-open hook.
-            DevicePipeline::run_on_ws_open(self.gw_ctx.state().devices.all(), &WsCtx::default());
+            DevicePipeline::run_on_ws_open(
+                &state
+                    .devices
+                    .for_request(&self.listener.name, ctx.canonical_path()),
+                &ws,
+            );
+        }
+
+        // Upgraded connections are idle by nature, and HTTP/2 upstream connections are shared
+        // between requests, so only HTTP/1.1 response bodies are watched.
+        if !ctx.is_upgrade_req()
+            && !ctx.is_http2()
+            && let (Some(timeout), Some(socket)) = (
+                ctx.extensions.get::<ResponseIdleTimeout>().copied(),
+                ctx.extensions.get::<UpstreamSocket>().copied(),
+            )
+        {
+            ctx.extensions
+                .insert(ResponseIdleWatchdog::start(socket.0, timeout.0));
         }
 
         Ok(())
@@ -392,10 +795,28 @@ impl ProxyHttp for PublicGateway {
     /// FINAL OBSERVATION / METRICS / LOGGING
     async fn response_filter(
         &self,
-        _session: &mut Session,
+        session: &mut Session,
         upstream: &mut ResponseHeader,
         ctx: &mut Self::CTX,
     ) -> Result<()> {
+        let state = self.gw_ctx.state();
+
+        // Downstream modules see the response after this hook, so compression enabled in
+        // request_filter can still be turned off for already-compressed or tiny responses.
+        if let Some(service) = ctx.service.as_ref().and_then(|s| state.services.get(s))
+            && service.enable_response_compression
+            && skip_response_compression(
+                upstream,
+                &service.response_compression_skip_content_types,
+                service.response_compression_min_size,
+            )
+            && let Some(compression) = session
+                .downstream_modules_ctx
+                .get_mut::<ResponseCompression>()
+        {
+            compression.adjust_level(0);
+        }
+
         if ctx.ws_opened || ctx.is_http2() {
             // Do not run on_response devices for WebSockets or HTTP/2.
             // For WebSockets and HTTP/2, this is not a real "response."
@@ -410,8 +831,13 @@ impl ProxyHttp for PublicGateway {
             upstream.headers.clone(),
             Vec::new(),
         );
-        let state = self.gw_ctx.state();
-        match DevicePipeline::run_on_response(state.devices.all(), &mut resp_ctx) {
+        resp_ctx.route_id = ctx.route_id.clone();
+        match DevicePipeline::run_on_response(
+            &state
+                .devices
+                .for_request(&self.listener.name, ctx.canonical_path()),
+            &mut resp_ctx,
+        ) {
             DeviceResult::Continue => {}
             DeviceResult::Respond(_) => {}
             DeviceResult::Error(err) => {
@@ -421,6 +847,12 @@ impl ProxyHttp for PublicGateway {
         }
 
         upstream.set_status(resp_ctx.status)?;
+        sync_response_headers(upstream, &resp_ctx.headers)?;
+
+        // Add headers devices attached to the request (e.g., rate-limit headers).
+        for (name, value) in ctx.response_headers.iter() {
+            upstream.insert_header(name.clone(), value.clone())?;
+        }
 
         let status = upstream.status.as_u16();
         ctx.upstream_outcome = Some(if status >= 500 {
@@ -432,10 +864,131 @@ impl ProxyHttp for PublicGateway {
         Ok(())
     }
 
+    /// Count response body bytes, and pace the body to the route's `response_rate_limit`.
+    /// Pingora waits for the returned delay before sending the chunk downstream.
+    fn response_body_filter(
+        &self,
+        _session: &mut Session,
+        body: &mut Option<Bytes>,
+        end_of_stream: bool,
+        ctx: &mut Self::CTX,
+    ) -> Result<Option<Duration>>
+    where
+        Self::CTX: Send + Sync,
+    {
+        // Swallow the upstream body, and send a device's replacement in its place.
+        if let Some(replacement) = ctx.extensions.get::<ReplacementBody>() {
+            *body = end_of_stream.then(|| replacement.0.clone());
+        }
+
+        if let Some(chunk) = body {
+            ctx.bytes_out += chunk.len() as u64;
+        }
+
+        if !ctx.ws_opened {
+            DevicePipeline::run_on_stream_response_body(
+                &self
+                    .gw_ctx
+                    .state()
+                    .devices
+                    .for_request(&self.listener.name, ctx.canonical_path()),
+                ctx,
+                body.as_ref(),
+                end_of_stream,
+            );
+        }
+
+        let delay = match (ctx.extensions.get_mut::<ResponsePacer>(), body.as_ref()) {
+            (Some(pacer), Some(chunk)) => pacer.delay_for(chunk.len(), Instant::now()),
+            _ => None,
+        };
+
+        // A complete response made its deadline, so its connection can go back to the pool.
+        if end_of_stream && let Some(watchdog) = ctx.extensions.get::<RequestDeadlineWatchdog>() {
+            watchdog.finish();
+        }
+
+        // The upstream is not read while a chunk is paced, so pacing does not count as idle.
+        if let Some(watchdog) = ctx.extensions.get::<ResponseIdleWatchdog>() {
+            if end_of_stream {
+                watchdog.finish();
+            } else {
+                watchdog.touch(delay.unwrap_or_default());
+            }
+        }
+
+        Ok(delay)
+    }
+
+    /// Retry requests whose upstream connection failed before the response was sent downstream,
+    /// as the service's retry policy allows. Each retry runs `upstream_peer` again, so another
+    /// upstream is selected.
+    fn error_while_proxy(
+        &self,
+        peer: &HttpPeer,
+        session: &mut Session,
+        e: Box<Error>,
+        ctx: &mut Self::CTX,
+        client_reused: bool,
+    ) -> Box<Error> {
+        let mut e = e.more_context(format!("Peer: {peer}"));
+
+        // The failed connection is not reused, so it no longer needs watching.
+        let torn_down = ctx
+            .extensions
+            .remove::<RequestDeadlineWatchdog>()
+            .is_some_and(|watchdog| watchdog.finish());
+        if e.esource() == &ErrorSource::Upstream && (torn_down || deadline_passed(ctx)) {
+            return request_timed_out(ctx, e);
+        }
+
+        // Pingora's default: a reused connection that failed is retried on a new one.
+        e.retry
+            .decide_reuse(client_reused && !session.as_ref().retry_buffer_truncated());
+
+        // Errors already marked for retry include statuses retried in upstream_response_filter.
+        if !e.retry()
+            && e.esource() == &ErrorSource::Upstream
+            && self.try_retry(
+                session,
+                ctx,
+                UpstreamOutcome::Transport(classify_pingora_error(&e)),
+            )
+        {
+            e.set_retry(true);
+        }
+        e
+    }
+
+    /// Retry failed connection attempts up to the selected upstream's `max_connect_retries`.
+    /// Each retry runs `upstream_peer` again, so a different upstream may be selected.
+    fn fail_to_connect(
+        &self,
+        _session: &mut Session,
+        _peer: &HttpPeer,
+        ctx: &mut Self::CTX,
+        mut e: Box<Error>,
+    ) -> Box<Error> {
+        if deadline_passed(ctx) {
+            return request_timed_out(ctx, e);
+        }
+
+        if ctx.connect_retries < ctx.max_connect_retries {
+            ctx.connect_retries += 1;
+
+            // The failed attempt is finished here, before the retry admits a new one.
+            if let Some(mut guard) = ctx.admission_guard.take() {
+                guard.failure();
+            }
+            e.set_retry(true);
+        }
+        e
+    }
+
     /// The final step in the Pingora request/response pipeline.
     /// This function is primarily intended for logging,
     /// but it is also used for finalizing request guards.
-    async fn logging(&self, _session: &mut Session, e: Option<&Error>, ctx: &mut Self::CTX)
+    async fn logging(&self, session: &mut Session, e: Option<&Error>, ctx: &mut Self::CTX)
     where
         Self::CTX: Send + Sync,
     {
@@ -443,9 +996,22 @@ impl ProxyHttp for PublicGateway {
         // Pingora guarantees the logging hook is called last, which is the best that can be
         // done in Pingora 0.6.0.
         if ctx.ws_opened {
+            let ws = WsCloseCtx {
+                request_id: ctx.request_id(),
+                path: ctx.original_uri_path().to_string(),
+                bytes_in: ctx.bytes_in,
+                bytes_out: ctx.bytes_out,
+                error: e.map(|err| err.to_string()),
+            };
+            log_websocket_closed(&ws, ctx.extensions.get::<SampledConnection>().is_some());
+
             DevicePipeline::run_on_ws_close(
-                self.gw_ctx.state().devices.all(),
-                &WsCloseCtx::default(),
+                &self
+                    .gw_ctx
+                    .state()
+                    .devices
+                    .for_request(&self.listener.name, ctx.canonical_path()),
+                &ws,
             );
         }
 
@@ -454,12 +1020,115 @@ impl ProxyHttp for PublicGateway {
             ctx.upstream_outcome = Some(UpstreamOutcome::Transport(classify_pingora_error(err)));
         }
 
+        // A stalled body shows up as a closed connection, so record it as the timeout it was.
+        if let Some(watchdog) = ctx.extensions.remove::<ResponseIdleWatchdog>()
+            && watchdog.finish()
+        {
+            tracing::warn!("{UPSTREAM_RESPONSE_STALLED}");
+            ctx.upstream_outcome = Some(UpstreamOutcome::Transport(TransportFailure::Timeout));
+            DevicePipeline::run_on_error(
+                &self
+                    .gw_ctx
+                    .state()
+                    .devices
+                    .for_request(&self.listener.name, ctx.canonical_path()),
+                &DeviceError {
+                    message: UPSTREAM_RESPONSE_STALLED.to_string(),
+                    fatal: false,
+                },
+            );
+        }
+
+        // However the upstream was cut off at the deadline, it was too slow, so record a timeout.
+        if let Some(watchdog) = ctx.extensions.remove::<RequestDeadlineWatchdog>() {
+            watchdog.finish();
+        }
+        if ctx.extensions.get::<RequestTimedOut>().is_some() {
+            ctx.upstream_outcome = Some(UpstreamOutcome::Transport(TransportFailure::Timeout));
+        }
+
         // Finalize request guard...
         self.finalize_admission_guard(ctx);
+
+        DevicePipeline::run_on_complete(
+            &self
+                .gw_ctx
+                .state()
+                .devices
+                .for_request(&self.listener.name, ctx.canonical_path()),
+            ctx,
+        );
+
+        let state = self.gw_ctx.state();
+        if state.access_log {
+            let upstream = ctx
+                .selected_upstream
+                .as_ref()
+                .and_then(|(service, id)| state.upstream_addr(service, *id));
+            log_access(
+                AccessLogEntry::new(
+                    ctx,
+                    session.req_header(),
+                    session.response_written().map(|r| r.status.as_u16()),
+                    upstream,
+                    e.map(|err| err.to_string()),
+                ),
+                state.access_log_format.as_ref(),
+            );
+        }
+
+        // The response is complete, so a request pipelined behind this one can be read.
+        release_pipelined_request(session);
     }
 }
 
 impl PublicGateway {
+    /// Handle an upstream hostname that could not be resolved.
+    ///
+    /// Devices are told through `on_error`, the attempt counts as a circuit breaker failure,
+    /// and it is retried under the same `max_connect_retries` budget as a failed connection,
+    /// so another upstream may be selected.
+    fn fail_to_resolve(
+        &self,
+        ctx: &mut RequestCtx,
+        service_id: &ServiceId,
+        upstream: &UpstreamRuntime,
+        cb_started: bool,
+        failure: DnsFailure,
+    ) -> Box<Error> {
+        tracing::warn!(error = %failure, "upstream DNS resolution failed");
+
+        DevicePipeline::run_on_error(
+            &self
+                .gw_ctx
+                .state()
+                .devices
+                .for_request(&self.listener.name, ctx.canonical_path()),
+            &DeviceError {
+                message: failure.to_string(),
+                fatal: false,
+            },
+        );
+
+        if cb_started {
+            AdmissionGuard::new(
+                self.gw_ctx.traffic_manager.clone(),
+                service_id.clone(),
+                ctx.route_id.as_ref().map(|id| id.path()),
+                upstream.id(),
+            )
+            .failure();
+        }
+
+        let mut e = Error::new_up(Custom(UPSTREAM_DNS_FAILURE));
+        ctx.max_connect_retries = upstream.connection().max_connect_retries;
+        if ctx.connect_retries < ctx.max_connect_retries {
+            ctx.connect_retries += 1;
+            e.set_retry(true);
+        }
+        e
+    }
+
     /// Select an upstream for the given request.
     fn select_upstream<'a>(
         &self,
@@ -523,6 +1192,59 @@ impl PublicGateway {
         Ok(())
     }
 
+    /// Take a retry of the request from its service's retry budget, if the service's retry
+    /// policy retries this outcome. The failed attempt is finished with the outcome, so it
+    /// counts against its upstream, and the retry goes to another upstream while there is one.
+    fn try_retry(&self, session: &Session, ctx: &mut RequestCtx, outcome: UpstreamOutcome) -> bool {
+        let Some((service_id, upstream_id)) = ctx.selected_upstream.clone() else {
+            return false;
+        };
+        let Some(policy) = self.gw_ctx.traffic_manager.retry_policy(&service_id) else {
+            return false;
+        };
+
+        let retries_outcome = match outcome {
+            UpstreamOutcome::HttpStatus(status) => policy.retries_status(status),
+            UpstreamOutcome::Transport(_) => policy.retry_on_connection_error,
+            UpstreamOutcome::Success => false,
+        };
+
+        // Upgrades, responses already sent downstream, and request bodies that outgrew the
+        // retry buffer cannot be replayed.
+        if !retries_outcome
+            || !policy.retries_method(ctx.method())
+            || ctx.is_upgrade_req()
+            || session.response_written().is_some()
+            || session.as_ref().retry_buffer_truncated()
+        {
+            return false;
+        }
+
+        let Some(budget) = ctx.retry_budget.as_mut() else {
+            return false;
+        };
+        if !budget.try_retry(&policy) {
+            tracing::debug!(service = %service_id, "no retries left for the request");
+            return false;
+        }
+        let retry = budget.retries();
+
+        ctx.upstream_outcome = Some(outcome);
+        self.finalize_admission_guard(ctx);
+        ctx.admission_guard = None;
+        ctx.upstream_outcome = None;
+        ctx.retried_upstreams.push(upstream_id);
+
+        tracing::info!(
+            event = "upstream_retry",
+            service = %service_id,
+            upstream = ?upstream_id,
+            retry,
+            outcome = ?outcome
+        );
+        true
+    }
+
     /// Finalizes the request guard by reporting success or failure to the traffic manager.
     ///
     /// This method determines the outcome of the request based on the upstream response
@@ -536,7 +1258,7 @@ impl PublicGateway {
     ///
     /// This is called from the logging hook to ensure it runs after all other processing.
     fn finalize_admission_guard(&self, ctx: &mut RequestCtx) {
-        let (service_id, _) = match ctx.selected_upstream.as_ref() {
+        let (service_id, upstream_id) = match ctx.selected_upstream.as_ref() {
             Some(v) => v,
             None => return,
         };
@@ -571,5 +1293,115 @@ impl PublicGateway {
         } else {
             guard.failure();
         }
+
+        // Outlier detection counts 5xx responses whatever the circuit breaker counts.
+        let error = matches!(
+            ctx.upstream_outcome,
+            Some(UpstreamOutcome::Transport(_)) | Some(UpstreamOutcome::HttpStatus(500..=599))
+        );
+        self.gw_ctx
+            .traffic_manager
+            .outlier_on_end(service_id, upstream_id, error);
+    }
+}
+
+/// Answer a plaintext request to a route requiring TLS: redirect it to the same URL over
+/// HTTPS, or reject it. Requests without a host cannot be redirected, so they are rejected
+/// with `400`.
+async fn respond_tls_required(
+    session: &mut Session,
+    ctx: &RequestCtx,
+    require_tls: RequireTlsConfig,
+    trusted_proxies: &[IpNet],
+) -> Result<()> {
+    match (require_tls.action, ctx.effective_host(trusted_proxies)) {
+        (RequireTlsAction::Redirect, Some(host)) => {
+            let path_and_query = session
+                .req_header()
+                .uri
+                .path_and_query()
+                .map(|pq| pq.as_str())
+                .unwrap_or("/");
+            let location = format!("https://{host}{path_and_query}");
+
+            let mut resp = ResponseHeader::build(require_tls.status(), None)?;
+            resp.insert_header(header::LOCATION, &location)?;
+            resp.insert_header(header::CONTENT_LENGTH, "0")?;
+            session.write_response_header(Box::new(resp), true).await
+        }
+        (RequireTlsAction::Redirect, None) => {
+            session
+                .respond_error(StatusCode::BAD_REQUEST.as_u16())
+                .await
+        }
+        (RequireTlsAction::Reject, _) => session.respond_error(require_tls.status().as_u16()).await,
+    }
+}
+
+fn deadline_passed(ctx: &RequestCtx) -> bool {
+    ctx.extensions
+        .get::<RequestDeadline>()
+        .is_some_and(|deadline| deadline.passed())
+}
+
+/// Fail an upstream attempt cut off by the request deadline with `504`, without retrying it.
+fn request_timed_out(ctx: &mut RequestCtx, e: Box<Error>) -> Box<Error> {
+    tracing::warn!("{REQUEST_TIMED_OUT}");
+    ctx.extensions.insert(RequestTimedOut);
+    Error::because(
+        HTTPStatus(StatusCode::GATEWAY_TIMEOUT.as_u16()),
+        REQUEST_TIMED_OUT,
+        e,
+    )
+}
+
+/// The request body length the client declared in `Content-Length`, if any.
+fn declared_body_length(session: &Session) -> Option<u64> {
+    session
+        .req_header()
+        .headers
+        .get(header::CONTENT_LENGTH)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()
+}
+
+/// Write a response a device short-circuited with.
+/// Devices that set headers get them on the wire; otherwise Pingora's default error response is used.
+///
+/// Under `hide_as_404`, a `403` is answered exactly like a request no route matched,
+/// so the denial does not reveal that the route exists.
+async fn respond_from_device(
+    session: &mut Session,
+    ctx: &mut RequestCtx,
+    resp: ResponseCtx,
+    hide_as_404: bool,
+) -> Result<()> {
+    ctx.responded_by = resp.responded_by.clone();
+
+    if hide_as_404 && resp.status == StatusCode::FORBIDDEN {
+        return session.respond_error(StatusCode::NOT_FOUND.as_u16()).await;
+    }
+
+    if resp.headers.is_empty() {
+        return session.respond_error(resp.status.as_u16()).await;
+    }
+
+    let mut resp_header = ResponseHeader::build(resp.status, Some(resp.headers.len() + 1))?;
+    for (name, value) in resp.headers.iter() {
+        resp_header.append_header(name.clone(), value.clone())?;
+    }
+    resp_header.insert_header(header::CONTENT_LENGTH, resp.body.len().to_string())?;
+
+    let end_of_stream = resp.body.is_empty();
+    session
+        .write_response_header(Box::new(resp_header), end_of_stream)
+        .await?;
+    if !end_of_stream {
+        session
+            .write_response_body(Some(Bytes::from(resp.body)), true)
+            .await?;
     }
+    Ok(())
 }
@@ -1,18 +1,26 @@
 use crate::ctx::RequestCtx;
+use crate::runtime::RuntimeState;
+use arc_swap::ArcSwap;
 use async_trait::async_trait;
+use http::StatusCode;
 use pingora::http::ResponseHeader;
 use pingora::prelude::{HttpPeer, ProxyHttp, Session};
 use pingora::{Custom, Error};
+use std::sync::Arc;
+
+const DEFAULT_HTTPS_PORT: u16 = 443;
 
 pub struct RedirectGateway {
-    destination: String,
+    state: Arc<ArcSwap<RuntimeState>>,
+    https_port: u16,
     response_code: u16,
 }
 
 impl RedirectGateway {
-    pub fn new(to: String, response_code: u16) -> Self {
+    pub fn new(state: Arc<ArcSwap<RuntimeState>>, https_port: u16, response_code: u16) -> Self {
         Self {
-            destination: to,
+            state,
+            https_port,
             response_code,
         }
     }
@@ -41,9 +49,22 @@ impl ProxyHttp for RedirectGateway {
     async fn request_filter(
         &self,
         session: &mut Session,
-        _ctx: &mut Self::CTX,
+        ctx: &mut Self::CTX,
     ) -> pingora::Result<bool> {
         // RedirectGateway is terminal: it always handles the request.
+        // Requests without a usable host cannot be redirected, so they are rejected.
+        let state = self.state.load();
+        let host = match ctx.hydrate_from_session(session) {
+            Ok(()) => ctx.effective_host(&state.trusted_proxies),
+            Err(_) => None,
+        };
+        let Some(host) = host else {
+            session
+                .respond_error(StatusCode::BAD_REQUEST.as_u16())
+                .await?;
+            return Ok(true);
+        };
+
         let mut resp = ResponseHeader::build(self.response_code, None)?;
 
         // Set the redirect destination via the location header.
@@ -53,7 +74,7 @@ impl ProxyHttp for RedirectGateway {
             .path_and_query()
             .map(|pq| pq.as_str())
             .unwrap_or("/");
-        let location = format!("https://{}{}", self.destination, path_and_query);
+        let location = redirect_location(host, self.https_port, path_and_query);
         resp.insert_header("Location", &location)?;
         resp.insert_header("Connection", "close")?;
         resp.insert_header("Content-Length", "0")?;
@@ -63,3 +84,12 @@ impl ProxyHttp for RedirectGateway {
         Ok(true)
     }
 }
+
+/// The HTTPS URL for `path_and_query` on `host`, leaving out the default port.
+pub(crate) fn redirect_location(host: &str, https_port: u16, path_and_query: &str) -> String {
+    if https_port == DEFAULT_HTTPS_PORT {
+        format!("https://{host}{path_and_query}")
+    } else {
+        format!("https://{host}:{https_port}{path_and_query}")
+    }
+}
@@ -0,0 +1,46 @@
+use crate::proxy::socket_watchdog::SocketWatchdog;
+use std::os::unix::io::RawFd;
+use std::time::{Duration, Instant};
+
+/// Error reason for requests that ran past their `request_timeout_milliseconds`.
+pub(crate) const REQUEST_TIMED_OUT: &str = "request timed out";
+
+/// When a proxied request must be complete, from its route's or the server's request timeout.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RequestDeadline(pub Instant);
+
+impl RequestDeadline {
+    /// Time left until the deadline, zero once it has passed.
+    pub(crate) fn remaining(&self) -> Duration {
+        self.0.saturating_duration_since(Instant::now())
+    }
+
+    pub(crate) fn passed(&self) -> bool {
+        self.remaining().is_zero()
+    }
+}
+
+/// Marks a request answered with `504` because it ran past its deadline.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RequestTimedOut;
+
+/// Tears down the upstream connection once the request deadline passes.
+///
+/// Pingora's timeouts apply to each read and write rather than to the whole exchange, so an
+/// upstream trickling its response would never trip them. A [`SocketWatchdog`] shuts down the
+/// upstream socket at the deadline instead. Pingora then fails the request, and the gateway
+/// answers it with `504` if no response was sent yet.
+#[derive(Debug, Clone)]
+pub(crate) struct RequestDeadlineWatchdog(SocketWatchdog);
+
+impl RequestDeadlineWatchdog {
+    pub(crate) fn start(fd: RawFd, deadline: RequestDeadline) -> Self {
+        let deadline = tokio::time::Instant::from_std(deadline.0);
+        Self(SocketWatchdog::start(fd, deadline, REQUEST_TIMED_OUT))
+    }
+
+    /// Stop watching, and return whether the connection was torn down at the deadline.
+    pub(crate) fn finish(&self) -> bool {
+        self.0.finish()
+    }
+}
@@ -0,0 +1,45 @@
+use crate::proxy::socket_watchdog::SocketWatchdog;
+use std::os::unix::io::RawFd;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// The selected upstream's `response_idle_timeout_milliseconds`, when enabled.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ResponseIdleTimeout(pub Duration);
+
+/// Socket of the upstream connection the request was sent on.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct UpstreamSocket(pub RawFd);
+
+/// Aborts a response body that stalls after its headers were received.
+///
+/// Pingora applies one read timeout to the whole upstream response, with no hook between
+/// the headers and the body, so the body is watched by a [`SocketWatchdog`] instead. If no body
+/// bytes arrive for `timeout`, the upstream socket is shut down. Pingora then fails the
+/// response and, since headers were already sent, closes the client connection.
+#[derive(Debug, Clone)]
+pub(crate) struct ResponseIdleWatchdog {
+    timeout: Duration,
+    watchdog: SocketWatchdog,
+}
+
+impl ResponseIdleWatchdog {
+    pub(crate) fn start(fd: RawFd, timeout: Duration) -> Self {
+        Self {
+            timeout,
+            watchdog: SocketWatchdog::start(fd, Instant::now() + timeout, "response body stalled"),
+        }
+    }
+
+    /// Record body progress. `delay` is time the body is held back (e.g. by pacing),
+    /// during which the upstream is not read.
+    pub(crate) fn touch(&self, delay: Duration) {
+        self.watchdog
+            .postpone(Instant::now() + delay + self.timeout);
+    }
+
+    /// Stop watching, and return whether the response was aborted for stalling.
+    pub(crate) fn finish(&self) -> bool {
+        self.watchdog.finish()
+    }
+}
@@ -0,0 +1,38 @@
+use crate::conf::types::ResponseRateLimitConfig;
+use std::time::{Duration, Instant};
+
+/// Token bucket that paces a single response body to the route's `response_rate_limit`.
+///
+/// The bucket starts full, so the first `burst_bytes` go out at full speed. After that, each
+/// chunk is delayed until the bytes sent so far fit the configured rate. Chunks are never
+/// split or buffered; a chunk larger than the bucket simply waits longer.
+#[derive(Debug, Clone)]
+pub(crate) struct ResponsePacer {
+    bytes_per_second: f64,
+    burst_bytes: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl ResponsePacer {
+    pub(crate) fn new(cfg: ResponseRateLimitConfig, now: Instant) -> Self {
+        Self {
+            bytes_per_second: cfg.bytes_per_second as f64,
+            burst_bytes: cfg.burst_bytes as f64,
+            tokens: cfg.burst_bytes as f64,
+            last_refill: now,
+        }
+    }
+
+    /// Take `bytes` from the bucket and return how long to wait before sending them.
+    pub(crate) fn delay_for(&mut self, bytes: usize, now: Instant) -> Option<Duration> {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.last_refill = now;
+        self.tokens = (self.tokens + elapsed * self.bytes_per_second).min(self.burst_bytes);
+        self.tokens -= bytes as f64;
+
+        (self.tokens < 0.0).then(|| Duration::from_secs_f64(-self.tokens / self.bytes_per_second))
+    }
+}
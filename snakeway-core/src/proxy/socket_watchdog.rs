@@ -0,0 +1,90 @@
+use nix::sys::socket::{Shutdown, shutdown};
+use std::os::unix::io::{AsRawFd, BorrowedFd, OwnedFd, RawFd};
+use std::sync::{Arc, Mutex};
+use tokio::task::AbortHandle;
+use tokio::time::Instant;
+
+/// Shuts an upstream socket down from a separate task once a deadline passes.
+///
+/// The upstream request and response idle timeouts are both enforced this way, since Pingora's
+/// own timeouts apply to each read and write rather than to the exchange as a whole.
+///
+/// The socket is shut down through a duplicate of its descriptor, taken in `start` and closed
+/// in `finish`. Pingora may close its own descriptor first, and the number be reused by another
+/// connection, but the duplicate still refers to the watched socket.
+#[derive(Debug, Clone)]
+pub(crate) struct SocketWatchdog {
+    state: Arc<Mutex<WatchState>>,
+    task: Option<AbortHandle>,
+}
+
+#[derive(Debug)]
+struct WatchState {
+    deadline: Instant,
+    /// The watched socket, until the watchdog finishes or the socket is shut down.
+    socket: Option<OwnedFd>,
+    fired: bool,
+}
+
+impl SocketWatchdog {
+    /// Watch `fd`, which must be open, and shut it down at `deadline`.
+    ///
+    /// `reason` says why the socket would be shut down, for the logs. If the descriptor cannot
+    /// be duplicated, nothing is watched.
+    pub(crate) fn start(fd: RawFd, deadline: Instant, reason: &'static str) -> Self {
+        // SAFETY: watchdogs are started while the upstream connection is in use, so `fd` is open.
+        let socket = unsafe { BorrowedFd::borrow_raw(fd) }
+            .try_clone_to_owned()
+            .inspect_err(|e| tracing::debug!(error = %e, reason, "failed to watch upstream"))
+            .ok();
+        let watching = socket.is_some();
+        let state = Arc::new(Mutex::new(WatchState {
+            deadline,
+            socket,
+            fired: false,
+        }));
+
+        let watched = state.clone();
+        let task = watching.then(|| {
+            tokio::spawn(async move {
+                loop {
+                    let deadline = watched.lock().expect("watchdog state poisoned").deadline;
+                    tokio::time::sleep_until(deadline).await;
+
+                    // The lock is held through the shutdown, so `finish` cannot hand the
+                    // connection back to the pool while it is being shut down.
+                    let mut state = watched.lock().expect("watchdog state poisoned");
+                    if Instant::now() < state.deadline {
+                        continue;
+                    }
+                    if let Some(socket) = state.socket.take() {
+                        if let Err(e) = shutdown(socket.as_raw_fd(), Shutdown::Both) {
+                            tracing::debug!(error = %e, reason, "failed to shut down upstream");
+                        }
+                        state.fired = true;
+                    }
+                    return;
+                }
+            })
+            .abort_handle()
+        });
+
+        Self { state, task }
+    }
+
+    /// Move the deadline, unless the socket was already shut down.
+    pub(crate) fn postpone(&self, deadline: Instant) {
+        let mut state = self.state.lock().expect("watchdog state poisoned");
+        state.deadline = deadline;
+    }
+
+    /// Stop watching, and return whether the socket was shut down at the deadline.
+    pub(crate) fn finish(&self) -> bool {
+        let mut state = self.state.lock().expect("watchdog state poisoned");
+        if let Some(task) = &self.task {
+            task.abort();
+        }
+        state.socket = None;
+        state.fired
+    }
+}
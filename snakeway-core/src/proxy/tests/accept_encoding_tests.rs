@@ -0,0 +1,105 @@
+use crate::conf::types::default_skip_content_types;
+use crate::proxy::accept_encoding::{apply_upstream_accept_encoding, skip_response_compression};
+use http::header;
+use pingora::http::{RequestHeader, ResponseHeader};
+use pretty_assertions::assert_eq;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+fn upstream_request(accept_encoding: &str) -> RequestHeader {
+    let mut req = RequestHeader::build("GET", b"/api", None).unwrap();
+    req.insert_header(header::ACCEPT_ENCODING, accept_encoding)
+        .unwrap();
+    req
+}
+
+fn accept_encoding(req: &RequestHeader) -> Option<&str> {
+    req.headers
+        .get(header::ACCEPT_ENCODING)
+        .map(|v| v.to_str().unwrap())
+}
+
+fn upstream_response(content_type: &str, content_length: usize) -> ResponseHeader {
+    let mut resp = ResponseHeader::build(200, None).unwrap();
+    resp.insert_header(header::CONTENT_TYPE, content_type)
+        .unwrap();
+    resp.insert_header(header::CONTENT_LENGTH, content_length.to_string())
+        .unwrap();
+    resp
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn unset_policy_forwards_client_accept_encoding() {
+    // Arrange
+    let mut req = upstream_request("gzip, br");
+
+    // Act
+    apply_upstream_accept_encoding(&mut req, None).unwrap();
+
+    // Assert
+    assert_eq!(accept_encoding(&req), Some("gzip, br"));
+}
+
+#[test]
+fn identity_policy_rewrites_accept_encoding() {
+    // Arrange
+    let mut req = upstream_request("gzip, br");
+
+    // Act
+    apply_upstream_accept_encoding(&mut req, Some("identity")).unwrap();
+
+    // Assert
+    assert_eq!(accept_encoding(&req), Some("identity"));
+}
+
+#[test]
+fn strip_policy_removes_accept_encoding() {
+    // Arrange
+    let mut req = upstream_request("gzip, br");
+
+    // Act
+    apply_upstream_accept_encoding(&mut req, Some("strip")).unwrap();
+
+    // Assert
+    assert_eq!(accept_encoding(&req), None);
+}
+
+#[test]
+fn denylisted_content_type_skips_compression() {
+    // Arrange
+    let resp = upstream_response("image/png", 64 * 1024);
+
+    // Act
+    let skip = skip_response_compression(&resp, &default_skip_content_types(), 1024);
+
+    // Assert
+    assert!(skip);
+}
+
+#[test]
+fn tiny_response_skips_compression() {
+    // Arrange
+    let resp = upstream_response("application/json", 40);
+
+    // Act
+    let skip = skip_response_compression(&resp, &default_skip_content_types(), 1024);
+
+    // Assert
+    assert!(skip);
+}
+
+#[test]
+fn large_text_response_is_compressed() {
+    // Arrange
+    let resp = upstream_response("text/html; charset=utf-8", 64 * 1024);
+
+    // Act
+    let skip = skip_response_compression(&resp, &default_skip_content_types(), 1024);
+
+    // Assert
+    assert!(!skip);
+}
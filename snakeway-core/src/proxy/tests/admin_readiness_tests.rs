@@ -0,0 +1,60 @@
+use crate::proxy::handlers::admin::readiness_report;
+use crate::server::ShutdownCoordinator;
+use http::StatusCode;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use std::time::Duration;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+fn coordinator() -> ShutdownCoordinator {
+    ShutdownCoordinator::new(Duration::from_secs(1), Duration::from_secs(1))
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn running_server_reports_ready() {
+    // Arrange
+    let shutdown = coordinator();
+
+    // Act
+    let (status, report) = readiness_report(&shutdown);
+
+    // Assert
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        report,
+        json!({
+            "ready": true,
+            "draining": false,
+            "in_flight": 0,
+            "phase": "running"
+        })
+    );
+}
+
+#[test]
+fn shutting_down_server_reports_draining_with_in_flight_requests() {
+    // Arrange
+    let shutdown = coordinator();
+    let _in_flight = shutdown.track_request();
+
+    // Act
+    shutdown.initiate();
+    let (status, report) = readiness_report(&shutdown);
+
+    // Assert
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(
+        report,
+        json!({
+            "ready": false,
+            "draining": true,
+            "in_flight": 1,
+            "phase": "not_ready"
+        })
+    );
+}
@@ -0,0 +1,77 @@
+use crate::proxy::device_headers::sync_response_headers;
+use http::{HeaderMap, HeaderValue};
+use pingora::http::ResponseHeader;
+use pretty_assertions::assert_eq;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+fn upstream_response(headers: &[(&str, &str)]) -> ResponseHeader {
+    let mut resp = ResponseHeader::build(200, None).unwrap();
+    for (name, value) in headers {
+        resp.append_header(name.to_string(), *value).unwrap();
+    }
+    resp
+}
+
+fn values(resp: &ResponseHeader, name: &str) -> Vec<String> {
+    resp.headers
+        .get_all(name)
+        .iter()
+        .map(|v| v.to_str().unwrap().to_string())
+        .collect()
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn removed_headers_are_removed() {
+    // Arrange
+    let mut resp = upstream_response(&[("Server", "nginx"), ("Content-Type", "text/plain")]);
+    let mut headers = resp.headers.clone();
+    headers.remove("server");
+
+    // Act
+    sync_response_headers(&mut resp, &headers).unwrap();
+
+    // Assert
+    assert!(resp.headers.get("server").is_none());
+    assert_eq!(values(&resp, "content-type"), vec!["text/plain"]);
+}
+
+#[test]
+fn changed_headers_replace_every_value() {
+    // Arrange
+    let mut resp =
+        upstream_response(&[("Cache-Control", "no-cache"), ("Cache-Control", "private")]);
+    let mut headers = resp.headers.clone();
+    headers.insert("cache-control", HeaderValue::from_static("max-age=60"));
+    headers.append("x-added", HeaderValue::from_static("1"));
+
+    // Act
+    sync_response_headers(&mut resp, &headers).unwrap();
+
+    // Assert
+    assert_eq!(values(&resp, "cache-control"), vec!["max-age=60"]);
+    assert_eq!(values(&resp, "x-added"), vec!["1"]);
+}
+
+#[test]
+fn unchanged_headers_keep_their_casing() {
+    // Arrange
+    let mut resp = upstream_response(&[("X-Legacy-Header", "1")]);
+    let headers: HeaderMap = resp.headers.clone();
+
+    // Act
+    sync_response_headers(&mut resp, &headers).unwrap();
+
+    // Assert
+    let mut wire = Vec::new();
+    resp.header_to_h1_wire(&mut wire);
+    assert!(
+        String::from_utf8(wire)
+            .unwrap()
+            .contains("X-Legacy-Header: 1\r\n")
+    );
+}
@@ -0,0 +1,89 @@
+use crate::proxy::header_case::apply_upstream_header_case;
+use http::Version;
+use pingora::http::RequestHeader;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+fn upstream_request(headers: &[(&str, &str)]) -> RequestHeader {
+    let mut req = RequestHeader::build("GET", b"/api", None).unwrap();
+    for (name, value) in headers {
+        req.append_header(name.to_string(), *value).unwrap();
+    }
+    req
+}
+
+fn h1_wire(req: &RequestHeader) -> String {
+    let mut buf = Vec::new();
+    req.header_to_h1_wire(&mut buf);
+    String::from_utf8(buf).unwrap()
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn configured_header_reaches_h1_upstream_with_requested_casing() {
+    // Arrange
+    let mut req = upstream_request(&[("www-authenticate", "Basic realm=\"legacy\"")]);
+
+    // Act
+    apply_upstream_header_case(&mut req, &["WWW-Authenticate".to_string()]).unwrap();
+
+    // Assert
+    let wire = h1_wire(&req);
+    assert!(wire.contains("WWW-Authenticate: Basic realm=\"legacy\"\r\n"));
+    assert!(!wire.contains("www-authenticate"));
+}
+
+#[test]
+fn multi_value_headers_keep_all_values() {
+    // Arrange
+    let mut req = upstream_request(&[("x-legacy-token", "a"), ("x-legacy-token", "b")]);
+
+    // Act
+    apply_upstream_header_case(&mut req, &["X-LEGACY-TOKEN".to_string()]).unwrap();
+
+    // Assert
+    let wire = h1_wire(&req);
+    assert!(wire.contains("X-LEGACY-TOKEN: a\r\n"));
+    assert!(wire.contains("X-LEGACY-TOKEN: b\r\n"));
+}
+
+#[test]
+fn unconfigured_headers_are_left_untouched() {
+    // Arrange
+    let mut req = upstream_request(&[("x-request-id", "abc"), ("www-authenticate", "Basic")]);
+
+    // Act
+    apply_upstream_header_case(&mut req, &["WWW-Authenticate".to_string()]).unwrap();
+
+    // Assert
+    let wire = h1_wire(&req);
+    assert!(wire.contains("x-request-id: abc\r\n"));
+}
+
+#[test]
+fn missing_configured_header_is_not_added() {
+    // Arrange
+    let mut req = upstream_request(&[("x-request-id", "abc")]);
+
+    // Act
+    apply_upstream_header_case(&mut req, &["WWW-Authenticate".to_string()]).unwrap();
+
+    // Assert
+    assert!(req.headers.get("www-authenticate").is_none());
+}
+
+#[test]
+fn h2_upstreams_are_not_modified() {
+    // Arrange
+    let mut req = upstream_request(&[("www-authenticate", "Basic")]);
+    req.set_version(Version::HTTP_2);
+
+    // Act
+    apply_upstream_header_case(&mut req, &["WWW-Authenticate".to_string()]).unwrap();
+
+    // Assert
+    assert!(h1_wire(&req).contains("www-authenticate: Basic\r\n"));
+}
@@ -0,0 +1,11 @@
+mod accept_encoding_tests;
+mod admin_readiness_tests;
+mod device_headers_tests;
+mod header_case_tests;
+mod pipelining_tests;
+mod redirect_gateway_tests;
+mod request_deadline_tests;
+mod response_idle_tests;
+mod response_pacing_tests;
+mod upstream_dns_tests;
+mod upstream_sni_tests;
@@ -0,0 +1,147 @@
+use crate::proxy::pipelining::PipelinedStream;
+use pretty_assertions::assert_eq;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::UnixStream;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+const WAIT: Duration = Duration::from_millis(100);
+
+/// A framed client connection, and the client end writing `sent` to it.
+async fn client_sending(sent: &[u8]) -> (PipelinedStream, UnixStream) {
+    let (server, mut client) = UnixStream::pair().unwrap();
+    client.write_all(sent).await.unwrap();
+    (PipelinedStream::new(Box::new(server)), client)
+}
+
+/// Read whatever the stream hands over next, or `None` if it holds everything back.
+async fn read_next(stream: &mut PipelinedStream) -> Option<String> {
+    let mut buf = [0u8; 4096];
+    let n = tokio::time::timeout(WAIT, stream.read(&mut buf))
+        .await
+        .ok()?
+        .unwrap();
+    Some(String::from_utf8_lossy(&buf[..n]).into_owned())
+}
+
+/// Read until `expected` bytes were handed over.
+async fn read_request(stream: &mut PipelinedStream, expected: usize) -> String {
+    let mut received = String::new();
+    while received.len() < expected {
+        received += &read_next(stream).await.expect("request was held back");
+    }
+    received
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[tokio::test]
+async fn pipelined_request_is_held_back_until_released() {
+    // Arrange
+    let first = "GET /first HTTP/1.1\r\nHost: localhost\r\n\r\n";
+    let second = "GET /second HTTP/1.1\r\nHost: localhost\r\n\r\n";
+    let (mut stream, _client) = client_sending(format!("{first}{second}").as_bytes()).await;
+
+    // Act
+    let read_first = read_request(&mut stream, first.len()).await;
+    let held_back = read_next(&mut stream).await;
+    stream.release();
+    let read_second = read_request(&mut stream, second.len()).await;
+
+    // Assert
+    assert_eq!(read_first, first);
+    assert_eq!(held_back, None);
+    assert_eq!(read_second, second);
+}
+
+#[tokio::test]
+async fn request_body_is_read_before_the_next_request_is_held_back() {
+    // Arrange
+    let first = "POST /first HTTP/1.1\r\nHost: localhost\r\nContent-Length: 5\r\n\r\nhello";
+    let second = "GET /second HTTP/1.1\r\nHost: localhost\r\n\r\n";
+    let (mut stream, _client) = client_sending(format!("{first}{second}").as_bytes()).await;
+
+    // Act
+    let read_first = read_request(&mut stream, first.len()).await;
+    let held_back = read_next(&mut stream).await;
+    stream.release();
+    let read_second = read_request(&mut stream, second.len()).await;
+
+    // Assert
+    assert_eq!(read_first, first);
+    assert_eq!(held_back, None);
+    assert_eq!(read_second, second);
+}
+
+#[tokio::test]
+async fn chunked_request_body_is_read_before_the_next_request_is_held_back() {
+    // Arrange
+    let first = "POST /first HTTP/1.1\r\nHost: localhost\r\nTransfer-Encoding: chunked\r\n\r\n\
+                 5;ext=1\r\nhello\r\n6\r\n world\r\n0\r\nX-Trailer: yes\r\n\r\n";
+    let second = "GET /second HTTP/1.1\r\nHost: localhost\r\n\r\n";
+    let (mut stream, _client) = client_sending(format!("{first}{second}").as_bytes()).await;
+
+    // Act
+    let read_first = read_request(&mut stream, first.len()).await;
+    let held_back = read_next(&mut stream).await;
+    stream.release();
+    let read_second = read_request(&mut stream, second.len()).await;
+
+    // Assert
+    assert_eq!(read_first, first);
+    assert_eq!(held_back, None);
+    assert_eq!(read_second, second);
+}
+
+#[tokio::test]
+async fn release_before_the_body_is_read_applies_once_it_is() {
+    // Arrange
+    let first = "POST /first HTTP/1.1\r\nHost: localhost\r\nContent-Length: 5\r\n\r\n";
+    let second = "GET /second HTTP/1.1\r\nHost: localhost\r\n\r\n";
+    let (mut stream, _client) = client_sending(format!("{first}hello{second}").as_bytes()).await;
+
+    // Act
+    let head = read_request(&mut stream, first.len()).await;
+    stream.release();
+    let rest = read_request(&mut stream, "hello".len() + second.len()).await;
+
+    // Assert
+    assert_eq!(head, first);
+    assert_eq!(rest, format!("hello{second}"));
+}
+
+#[tokio::test]
+async fn upgraded_connection_is_passed_through() {
+    // Arrange
+    let upgrade =
+        "GET /ws HTTP/1.1\r\nHost: localhost\r\nConnection: Upgrade\r\nUpgrade: websocket\r\n\r\n";
+    let frames = "websocket frames";
+    let (mut stream, _client) = client_sending(format!("{upgrade}{frames}").as_bytes()).await;
+
+    // Act
+    let read_upgrade = read_request(&mut stream, upgrade.len()).await;
+    let read_frames = read_request(&mut stream, frames.len()).await;
+
+    // Assert
+    assert_eq!(read_upgrade, upgrade);
+    assert_eq!(read_frames, frames);
+}
+
+#[tokio::test]
+async fn closed_connection_is_reported_after_the_request() {
+    // Arrange
+    let first = "GET /first HTTP/1.1\r\nHost: localhost\r\n\r\n";
+    let (mut stream, client) = client_sending(first.as_bytes()).await;
+    drop(client);
+
+    // Act
+    let read_first = read_request(&mut stream, first.len()).await;
+    let closed = read_next(&mut stream).await;
+
+    // Assert
+    assert_eq!(read_first, first);
+    assert_eq!(closed.as_deref(), Some(""));
+}
@@ -0,0 +1,20 @@
+use crate::proxy::redirect_gateway::redirect_location;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn redirect_location_leaves_out_the_default_https_port() {
+    // Act
+    let location = redirect_location("example.com", 443, "/login?next=%2F");
+
+    // Assert
+    assert_eq!(location, "https://example.com/login?next=%2F");
+}
+
+#[test]
+fn redirect_location_keeps_other_https_ports() {
+    // Act
+    let location = redirect_location("example.com", 8443, "/");
+
+    // Assert
+    assert_eq!(location, "https://example.com:8443/");
+}
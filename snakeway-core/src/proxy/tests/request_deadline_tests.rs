@@ -0,0 +1,96 @@
+use crate::proxy::request_deadline::{RequestDeadline, RequestDeadlineWatchdog};
+use pretty_assertions::assert_eq;
+use std::io::{ErrorKind, Read};
+use std::os::unix::io::AsRawFd;
+use std::os::unix::net::UnixStream;
+use std::time::{Duration, Instant};
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+const TIMEOUT: Duration = Duration::from_millis(100);
+
+fn deadline_in(timeout: Duration) -> RequestDeadline {
+    RequestDeadline(Instant::now() + timeout)
+}
+
+/// Read one byte on a blocking thread, so the watchdog task keeps running.
+async fn read_byte(mut stream: UnixStream, wait: Duration) -> Result<usize, ErrorKind> {
+    tokio::task::spawn_blocking(move || {
+        stream.set_read_timeout(Some(wait)).unwrap();
+        let mut buf = [0u8; 1];
+        stream.read(&mut buf).map_err(|e| e.kind())
+    })
+    .await
+    .unwrap()
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn deadline_counts_down_to_zero() {
+    // Arrange
+    let future = deadline_in(Duration::from_secs(60));
+    let past = RequestDeadline(Instant::now() - Duration::from_secs(1));
+
+    // Act & Assert
+    assert!(!future.passed());
+    assert!(future.remaining() > Duration::from_secs(59));
+    assert!(past.passed());
+    assert_eq!(past.remaining(), Duration::ZERO);
+}
+
+#[tokio::test]
+async fn connection_is_shut_down_at_the_deadline() {
+    // Arrange
+    let (upstream, peer) = UnixStream::pair().unwrap();
+    let watchdog = RequestDeadlineWatchdog::start(upstream.as_raw_fd(), deadline_in(TIMEOUT));
+
+    // Act
+    let read = read_byte(peer, Duration::from_secs(2)).await;
+
+    // Assert
+    assert_eq!(read, Ok(0));
+    assert!(watchdog.finish());
+}
+
+#[tokio::test]
+async fn reused_descriptor_is_left_open() {
+    // Arrange
+    let (upstream, peer) = UnixStream::pair().unwrap();
+    let watchdog = RequestDeadlineWatchdog::start(upstream.as_raw_fd(), deadline_in(TIMEOUT));
+    drop(upstream);
+    // The lowest free descriptor is handed out, so this pair likely reuses the closed one.
+    let (_other, other_peer) = UnixStream::pair().unwrap();
+
+    // Act
+    let read = read_byte(peer, Duration::from_secs(2)).await;
+    let other_read = read_byte(other_peer, TIMEOUT).await;
+
+    // Assert
+    assert_eq!(read, Ok(0));
+    assert!(watchdog.finish());
+    assert!(
+        other_read.is_err(),
+        "another connection on the same descriptor was shut down"
+    );
+}
+
+#[tokio::test]
+async fn finished_request_is_left_open() {
+    // Arrange
+    let (upstream, peer) = UnixStream::pair().unwrap();
+    let watchdog = RequestDeadlineWatchdog::start(upstream.as_raw_fd(), deadline_in(TIMEOUT));
+
+    // Act
+    let fired = watchdog.finish();
+    let read = read_byte(peer, TIMEOUT * 3).await;
+
+    // Assert
+    assert!(!fired);
+    assert!(
+        read.is_err(),
+        "socket was shut down after the request finished"
+    );
+}
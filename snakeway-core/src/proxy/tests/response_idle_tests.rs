@@ -0,0 +1,109 @@
+use crate::proxy::response_idle::ResponseIdleWatchdog;
+use pretty_assertions::assert_eq;
+use std::io::{ErrorKind, Read};
+use std::os::unix::io::AsRawFd;
+use std::os::unix::net::UnixStream;
+use std::time::Duration;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+const IDLE_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Read one byte on a blocking thread, so the watchdog task keeps running.
+async fn read_byte(mut stream: UnixStream, wait: Duration) -> Result<usize, ErrorKind> {
+    tokio::task::spawn_blocking(move || {
+        stream.set_read_timeout(Some(wait)).unwrap();
+        let mut buf = [0u8; 1];
+        stream.read(&mut buf).map_err(|e| e.kind())
+    })
+    .await
+    .unwrap()
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[tokio::test]
+async fn stalled_response_is_shut_down() {
+    // Arrange
+    let (upstream, peer) = UnixStream::pair().unwrap();
+    let watchdog = ResponseIdleWatchdog::start(upstream.as_raw_fd(), IDLE_TIMEOUT);
+
+    // Act
+    let read = read_byte(peer, Duration::from_secs(2)).await;
+
+    // Assert
+    assert_eq!(read, Ok(0));
+    assert!(watchdog.finish());
+}
+
+#[tokio::test]
+async fn reused_descriptor_is_left_open() {
+    // Arrange
+    let (upstream, peer) = UnixStream::pair().unwrap();
+    let watchdog = ResponseIdleWatchdog::start(upstream.as_raw_fd(), IDLE_TIMEOUT);
+    drop(upstream);
+    // The lowest free descriptor is handed out, so this pair likely reuses the closed one.
+    let (_other, other_peer) = UnixStream::pair().unwrap();
+
+    // Act
+    let read = read_byte(peer, Duration::from_secs(2)).await;
+    let other_read = read_byte(other_peer, IDLE_TIMEOUT).await;
+
+    // Assert
+    assert_eq!(read, Ok(0));
+    assert!(watchdog.finish());
+    assert!(
+        other_read.is_err(),
+        "another connection on the same descriptor was shut down"
+    );
+}
+
+#[tokio::test]
+async fn finished_response_is_left_open() {
+    // Arrange
+    let (upstream, peer) = UnixStream::pair().unwrap();
+    let watchdog = ResponseIdleWatchdog::start(upstream.as_raw_fd(), IDLE_TIMEOUT);
+
+    // Act
+    let fired = watchdog.finish();
+    let read = read_byte(peer, IDLE_TIMEOUT * 3).await;
+
+    // Assert
+    assert!(!fired);
+    assert!(
+        read.is_err(),
+        "socket was shut down after the response finished"
+    );
+}
+
+#[tokio::test]
+async fn body_progress_keeps_response_open() {
+    // Arrange
+    let (upstream, _peer) = UnixStream::pair().unwrap();
+    let watchdog = ResponseIdleWatchdog::start(upstream.as_raw_fd(), IDLE_TIMEOUT);
+
+    // Act
+    for _ in 0..4 {
+        tokio::time::sleep(IDLE_TIMEOUT / 2).await;
+        watchdog.touch(Duration::ZERO);
+    }
+
+    // Assert
+    assert!(!watchdog.finish());
+}
+
+#[tokio::test]
+async fn paced_chunks_do_not_count_as_idle() {
+    // Arrange
+    let (upstream, _peer) = UnixStream::pair().unwrap();
+    let watchdog = ResponseIdleWatchdog::start(upstream.as_raw_fd(), IDLE_TIMEOUT);
+
+    // Act
+    watchdog.touch(IDLE_TIMEOUT * 2);
+    tokio::time::sleep(IDLE_TIMEOUT * 2).await;
+
+    // Assert
+    assert!(!watchdog.finish());
+}
@@ -0,0 +1,89 @@
+use crate::conf::types::ResponseRateLimitConfig;
+use crate::proxy::response_pacing::ResponsePacer;
+use pretty_assertions::assert_eq;
+use std::time::{Duration, Instant};
+
+fn pacer(bytes_per_second: u64, burst_bytes: u64, now: Instant) -> ResponsePacer {
+    ResponsePacer::new(
+        ResponseRateLimitConfig {
+            bytes_per_second,
+            burst_bytes,
+        },
+        now,
+    )
+}
+
+/// Feed `chunks` chunks of `chunk_len` bytes, sleeping (virtually) for each returned delay.
+/// Returns the total time the body took to send.
+fn paced_duration(
+    pacer: &mut ResponsePacer,
+    start: Instant,
+    chunks: usize,
+    chunk_len: usize,
+) -> Duration {
+    let mut now = start;
+    for _ in 0..chunks {
+        if let Some(delay) = pacer.delay_for(chunk_len, now) {
+            now += delay;
+        }
+    }
+    now - start
+}
+
+#[test]
+fn burst_is_sent_without_delay() {
+    // Arrange
+    let now = Instant::now();
+    let mut pacer = pacer(1_000, 4_000, now);
+
+    // Act
+    let delay = pacer.delay_for(4_000, now);
+
+    // Assert
+    assert_eq!(delay, None);
+}
+
+#[test]
+fn bytes_beyond_burst_are_delayed_to_the_rate() {
+    // Arrange
+    let now = Instant::now();
+    let mut pacer = pacer(1_000, 1_000, now);
+
+    // Act
+    let delay = pacer.delay_for(3_000, now);
+
+    // Assert
+    assert_eq!(delay, Some(Duration::from_secs(2)));
+}
+
+#[test]
+fn throttled_response_takes_roughly_the_expected_time() {
+    // Arrange
+    let start = Instant::now();
+    let mut pacer = pacer(64 * 1024, 64 * 1024, start);
+
+    // Act
+    // 1 MiB in 16 KiB chunks, after a 64 KiB burst at 64 KiB/s.
+    let elapsed = paced_duration(&mut pacer, start, 64, 16 * 1024);
+
+    // Assert
+    let expected = Duration::from_secs(15);
+    assert!(
+        elapsed.abs_diff(expected) < Duration::from_millis(10),
+        "expected about {expected:?}, took {elapsed:?}"
+    );
+}
+
+#[test]
+fn idle_time_refills_the_bucket_up_to_burst() {
+    // Arrange
+    let start = Instant::now();
+    let mut pacer = pacer(1_000, 1_000, start);
+    pacer.delay_for(1_000, start);
+
+    // Act
+    let delay = pacer.delay_for(1_000, start + Duration::from_secs(60));
+
+    // Assert
+    assert_eq!(delay, None);
+}
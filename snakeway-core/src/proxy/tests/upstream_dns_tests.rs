@@ -0,0 +1,150 @@
+use crate::proxy::upstream_dns::{DnsCachePolicy, DnsFailure, Resolve, UpstreamResolver};
+use async_trait::async_trait;
+use pretty_assertions::assert_eq;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+/// Fails the first `failures` lookups, then resolves every hostname to 10.0.0.1.
+struct FlakyResolver {
+    failures: u32,
+    lookups: AtomicU32,
+}
+
+impl FlakyResolver {
+    fn new(failures: u32) -> Arc<Self> {
+        Arc::new(Self {
+            failures,
+            lookups: AtomicU32::new(0),
+        })
+    }
+
+    fn lookups(&self) -> u32 {
+        self.lookups.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl Resolve for FlakyResolver {
+    async fn lookup(&self, _host: &str, port: u16) -> std::io::Result<Vec<SocketAddr>> {
+        let lookup = self.lookups.fetch_add(1, Ordering::SeqCst);
+        if lookup < self.failures {
+            return Err(std::io::Error::other(
+                "temporary failure in name resolution",
+            ));
+        }
+        Ok(vec![SocketAddr::from(([10, 0, 0, 1], port))])
+    }
+}
+
+fn policy() -> DnsCachePolicy {
+    DnsCachePolicy {
+        ttl: Duration::from_secs(30),
+        negative_ttl: Duration::from_secs(30),
+        attempts: 3,
+        backoff: Duration::from_millis(1),
+    }
+}
+
+#[tokio::test]
+async fn lookup_that_fails_then_recovers_is_retried() {
+    // Arrange
+    let resolver = FlakyResolver::new(2);
+    let upstreams = UpstreamResolver::new(resolver.clone(), policy());
+
+    // Act
+    let addr = upstreams.resolve("api.internal", 8080).await;
+
+    // Assert
+    assert_eq!(addr, Ok(SocketAddr::from(([10, 0, 0, 1], 8080))));
+    assert_eq!(resolver.lookups(), 3);
+}
+
+#[tokio::test]
+async fn request_eventually_succeeds_after_resolver_outage() {
+    // Arrange
+    let resolver = FlakyResolver::new(3);
+    let upstreams = UpstreamResolver::new(
+        resolver.clone(),
+        DnsCachePolicy {
+            negative_ttl: Duration::ZERO,
+            ..policy()
+        },
+    );
+
+    // Act
+    let first_attempt = upstreams.resolve("api.internal", 8080).await;
+    let retried_attempt = upstreams.resolve("api.internal", 8080).await;
+
+    // Assert
+    assert_eq!(
+        first_attempt,
+        Err(DnsFailure::Lookup("api.internal".to_string()))
+    );
+    assert_eq!(retried_attempt, Ok(SocketAddr::from(([10, 0, 0, 1], 8080))));
+}
+
+#[tokio::test]
+async fn failed_lookup_is_negatively_cached() {
+    // Arrange
+    let resolver = FlakyResolver::new(u32::MAX);
+    let upstreams = UpstreamResolver::new(resolver.clone(), policy());
+    let _ = upstreams.resolve("api.internal", 8080).await;
+
+    // Act
+    let addr = upstreams.resolve("api.internal", 8080).await;
+
+    // Assert
+    assert_eq!(
+        addr,
+        Err(DnsFailure::NegativeCached("api.internal".to_string()))
+    );
+    assert_eq!(resolver.lookups(), 3);
+}
+
+#[tokio::test]
+async fn resolved_addresses_are_cached() {
+    // Arrange
+    let resolver = FlakyResolver::new(0);
+    let upstreams = UpstreamResolver::new(resolver.clone(), policy());
+    let _ = upstreams.resolve("api.internal", 8080).await;
+
+    // Act
+    let addr = upstreams.resolve("api.internal", 8080).await;
+
+    // Assert
+    assert_eq!(addr, Ok(SocketAddr::from(([10, 0, 0, 1], 8080))));
+    assert_eq!(resolver.lookups(), 1);
+}
+
+#[tokio::test]
+async fn last_good_addresses_are_used_while_resolver_fails() {
+    // Arrange
+    struct RecoveredThenDown(AtomicU32);
+
+    #[async_trait]
+    impl Resolve for RecoveredThenDown {
+        async fn lookup(&self, _host: &str, port: u16) -> std::io::Result<Vec<SocketAddr>> {
+            if self.0.fetch_add(1, Ordering::SeqCst) == 0 {
+                return Ok(vec![SocketAddr::from(([10, 0, 0, 2], port))]);
+            }
+            Err(std::io::Error::other("resolver unavailable"))
+        }
+    }
+
+    let upstreams = UpstreamResolver::new(
+        Arc::new(RecoveredThenDown(AtomicU32::new(0))),
+        DnsCachePolicy {
+            ttl: Duration::ZERO,
+            ..policy()
+        },
+    );
+    let _ = upstreams.resolve("api.internal", 8080).await;
+
+    // Act
+    let addr = upstreams.resolve("api.internal", 8080).await;
+
+    // Assert
+    assert_eq!(addr, Ok(SocketAddr::from(([10, 0, 0, 2], 8080))));
+}
@@ -0,0 +1,68 @@
+use crate::proxy::upstream_sni::render_upstream_sni;
+use pingora::upstreams::peer::HttpPeer;
+
+#[test]
+fn host_template_renders_request_host() {
+    // Arrange
+    let template = "{host}";
+
+    // Act
+    let sni = render_upstream_sni(template, Some("Tenant-A.storage.example.com"));
+
+    // Assert
+    assert_eq!(sni.as_deref(), Some("tenant-a.storage.example.com"));
+}
+
+#[test]
+fn host_template_with_suffix_renders_request_host() {
+    // Arrange
+    let template = "{host}.s3.example.com";
+
+    // Act
+    let sni = render_upstream_sni(template, Some("media"));
+
+    // Assert
+    assert_eq!(sni.as_deref(), Some("media.s3.example.com"));
+}
+
+#[test]
+fn static_template_ignores_request_host() {
+    // Arrange
+    let template = "backend.internal";
+
+    // Act
+    let sni = render_upstream_sni(template, Some("tenant-a.example.com"));
+
+    // Assert
+    assert_eq!(sni.as_deref(), Some("backend.internal"));
+}
+
+#[test]
+fn host_template_without_host_is_rejected() {
+    // Act
+    let sni = render_upstream_sni("{host}", None);
+
+    // Assert
+    assert_eq!(sni, None);
+}
+
+#[test]
+fn host_that_is_not_a_legal_hostname_is_rejected() {
+    // Act
+    let sni = render_upstream_sni("{host}", Some("evil_host/..;"));
+
+    // Assert
+    assert_eq!(sni, None);
+}
+
+#[test]
+fn upstream_peer_uses_host_derived_sni() {
+    // Arrange
+    let mut peer = HttpPeer::new(("127.0.0.1", 443), true, "127.0.0.1".to_string());
+
+    // Act
+    peer.sni = render_upstream_sni("{host}", Some("bucket-1.storage.example.com")).unwrap();
+
+    // Assert
+    assert_eq!(peer.sni, "bucket-1.storage.example.com");
+}
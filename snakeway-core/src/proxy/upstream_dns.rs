@@ -0,0 +1,154 @@
+use async_trait::async_trait;
+use dashmap::DashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Resolves upstream hostnames to socket addresses.
+#[async_trait]
+pub(crate) trait Resolve: Send + Sync {
+    async fn lookup(&self, host: &str, port: u16) -> std::io::Result<Vec<SocketAddr>>;
+}
+
+/// Resolves with the operating system's resolver.
+pub(crate) struct SystemResolver;
+
+#[async_trait]
+impl Resolve for SystemResolver {
+    async fn lookup(&self, host: &str, port: u16) -> std::io::Result<Vec<SocketAddr>> {
+        Ok(tokio::net::lookup_host((host, port)).await?.collect())
+    }
+}
+
+/// Why an upstream hostname could not be resolved.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum DnsFailure {
+    #[error("failed to resolve upstream hostname '{0}'")]
+    Lookup(String),
+
+    #[error("upstream hostname '{0}' resolved to no addresses")]
+    NoAddresses(String),
+
+    #[error("upstream hostname '{0}' recently failed to resolve")]
+    NegativeCached(String),
+}
+
+/// How long resolutions are cached and how hard a failed lookup is retried.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct DnsCachePolicy {
+    /// How long resolved addresses are used before they are looked up again.
+    pub ttl: Duration,
+
+    /// How long a failed lookup is remembered before the resolver is asked again.
+    pub negative_ttl: Duration,
+
+    /// Lookups per resolution, including the first.
+    pub attempts: u32,
+
+    /// Delay between lookup attempts.
+    pub backoff: Duration,
+}
+
+impl Default for DnsCachePolicy {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(30),
+            negative_ttl: Duration::from_secs(2),
+            attempts: 3,
+            backoff: Duration::from_millis(50),
+        }
+    }
+}
+
+/// Resolves upstream hostnames at connection time.
+///
+/// A lookup that fails is retried briefly. If it still fails, the last good addresses are
+/// used for as long as the resolver keeps failing, and the failure is remembered for
+/// `negative_ttl` so a resolver outage is not hammered by every request.
+pub(crate) struct UpstreamResolver {
+    resolver: Arc<dyn Resolve>,
+    policy: DnsCachePolicy,
+    resolved: DashMap<(String, u16), (Instant, Vec<SocketAddr>)>,
+    failed: DashMap<(String, u16), Instant>,
+}
+
+impl UpstreamResolver {
+    pub(crate) fn new(resolver: Arc<dyn Resolve>, policy: DnsCachePolicy) -> Self {
+        Self {
+            resolver,
+            policy,
+            resolved: DashMap::new(),
+            failed: DashMap::new(),
+        }
+    }
+
+    pub(crate) fn system() -> Self {
+        Self::new(Arc::new(SystemResolver), DnsCachePolicy::default())
+    }
+
+    pub(crate) async fn resolve(&self, host: &str, port: u16) -> Result<SocketAddr, DnsFailure> {
+        let key = (host.to_string(), port);
+
+        let last_good = self.resolved.get(&key).map(|entry| entry.value().clone());
+        if let Some((resolved_at, addrs)) = &last_good
+            && resolved_at.elapsed() < self.policy.ttl
+        {
+            return Ok(addrs[0]);
+        }
+
+        let recently_failed = self
+            .failed
+            .get(&key)
+            .is_some_and(|failed_at| failed_at.elapsed() < self.policy.negative_ttl);
+
+        let failure = if recently_failed {
+            DnsFailure::NegativeCached(host.to_string())
+        } else {
+            match self.lookup_with_retry(host, port).await {
+                Ok(addrs) => {
+                    let addr = addrs[0];
+                    self.failed.remove(&key);
+                    self.resolved.insert(key, (Instant::now(), addrs));
+                    return Ok(addr);
+                }
+                Err(failure) => {
+                    self.failed.insert(key, Instant::now());
+                    failure
+                }
+            }
+        };
+
+        match last_good {
+            Some((_, addrs)) => {
+                tracing::warn!(host, error = %failure, "using last good upstream addresses");
+                Ok(addrs[0])
+            }
+            None => Err(failure),
+        }
+    }
+
+    async fn lookup_with_retry(
+        &self,
+        host: &str,
+        port: u16,
+    ) -> Result<Vec<SocketAddr>, DnsFailure> {
+        let mut failure = DnsFailure::Lookup(host.to_string());
+
+        for attempt in 0..self.policy.attempts.max(1) {
+            if attempt > 0 {
+                tokio::time::sleep(self.policy.backoff).await;
+            }
+
+            match self.resolver.lookup(host, port).await {
+                Ok(addrs) if !addrs.is_empty() => return Ok(addrs),
+                Ok(_) => failure = DnsFailure::NoAddresses(host.to_string()),
+                Err(e) => {
+                    tracing::debug!(host, attempt, error = %e, "upstream DNS lookup failed");
+                    failure = DnsFailure::Lookup(host.to_string());
+                }
+            }
+        }
+
+        Err(failure)
+    }
+}
@@ -0,0 +1,17 @@
+use crate::conf::types::UPSTREAM_SNI_HOST_PLACEHOLDER;
+use crate::conf::validation::validator::is_valid_hostname;
+
+/// Renders the SNI for a TLS upstream from the service's `upstream_sni` template.
+///
+/// `{host}` is replaced with the request's Host, so multi-tenant backends (e.g. object stores)
+/// see the tenant that was requested. Returns `None` if the template needs a Host and there is
+/// none, or if the result is not a legal hostname - the Host header is client-controlled.
+pub(crate) fn render_upstream_sni(template: &str, host: Option<&str>) -> Option<String> {
+    let sni = if template.contains(UPSTREAM_SNI_HOST_PLACEHOLDER) {
+        template.replace(UPSTREAM_SNI_HOST_PLACEHOLDER, &host?.to_ascii_lowercase())
+    } else {
+        template.to_string()
+    };
+
+    is_valid_hostname(&sni).then_some(sni)
+}
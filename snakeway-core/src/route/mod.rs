@@ -1,5 +1,7 @@
 pub mod router;
+#[cfg(test)]
+mod tests;
 pub mod types;
 
 pub use router::{RouteEntry, Router};
-pub use types::RouteRuntime;
+pub use types::{RouteId, RouteRuntime};
@@ -1,34 +1,43 @@
 use crate::route::types::RouteRuntime;
+use ahash::RandomState;
 use anyhow::{Result, anyhow};
+use std::collections::HashMap;
 
+/// Longest prefix path matching, for routes and anything else scoped to paths.
 #[derive(Debug)]
-pub struct Router {
-    routes: Vec<RouteEntry>,
+pub struct Router<K = RouteRuntime> {
+    routes: Vec<RouteEntry<K>>,
+    /// Compiled lookup table: route path -> index into `routes`.
+    /// Built once at config load, so matching never scans the route list.
+    index: HashMap<Box<str>, usize, RandomState>,
 }
 
 #[derive(Debug)]
-pub struct RouteEntry {
+pub struct RouteEntry<K = RouteRuntime> {
     pub path: String,
-    pub kind: RouteRuntime,
+    pub kind: K,
 }
 
-impl Default for Router {
+impl<K> Default for Router<K> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl Router {
+impl<K> Router<K> {
     pub fn new() -> Self {
-        Self { routes: Vec::new() }
+        Self {
+            routes: Vec::new(),
+            index: HashMap::default(),
+        }
     }
 
-    pub fn add_route(&mut self, path: &str, kind: RouteRuntime) -> Result<()> {
+    pub fn add_route(&mut self, path: &str, kind: K) -> Result<()> {
         if !path.starts_with('/') {
             return Err(anyhow!("route path must start with '/': {}", path));
         }
 
-        if self.routes.iter().any(|r| r.path == path) {
+        if self.index.contains_key(path) {
             return Err(anyhow!("duplicate route path: {}", path));
         }
 
@@ -40,10 +49,57 @@ impl Router {
         // The longest prefix wins --> sort descending by path length.
         self.routes.sort_by(|a, b| b.path.len().cmp(&a.path.len()));
 
+        // Sorting moves entries around, so the index is rebuilt.
+        self.index = self
+            .routes
+            .iter()
+            .enumerate()
+            .map(|(i, r)| (Box::from(r.path.as_str()), i))
+            .collect();
+
         Ok(())
     }
 
-    pub fn match_route(&self, request_path: &str) -> Result<&RouteEntry> {
+    /// Routes in match precedence order, longest path first.
+    pub fn routes(&self) -> &[RouteEntry<K>] {
+        &self.routes
+    }
+
+    /// Match the longest route prefix for the request path.
+    ///
+    /// A route matches when it equals the request path, or when it is a prefix of the request
+    /// path that ends right before a `/` boundary. Instead of testing every route, the candidate
+    /// prefixes of the request path are looked up longest-first, so the cost depends on the number
+    /// of path segments rather than the number of routes.
+    pub fn match_route(&self, request_path: &str) -> Result<&RouteEntry<K>> {
+        if !request_path.starts_with('/') {
+            return Err(anyhow!("invalid request path: {}", request_path));
+        }
+
+        // Exact match is the longest possible candidate.
+        if let Some(route) = self.lookup(request_path) {
+            return Ok(route);
+        }
+
+        // Then every prefix ending right before a `/`, longest first.
+        for (boundary, _) in request_path.rmatch_indices('/') {
+            if let Some(route) = self.lookup(&request_path[..boundary]) {
+                return Ok(route);
+            }
+        }
+
+        // The root route is a catch-all.
+        if let Some(route) = self.lookup("/") {
+            return Ok(route);
+        }
+
+        Err(anyhow!("no route matched path {}", request_path))
+    }
+
+    /// Reference implementation of `match_route` that scans every route.
+    /// Kept for equivalence tests and benchmarks against the compiled lookup.
+    #[cfg(test)]
+    pub(crate) fn match_route_linear(&self, request_path: &str) -> Result<&RouteEntry<K>> {
         if !request_path.starts_with('/') {
             return Err(anyhow!("invalid request path: {}", request_path));
         }
@@ -56,8 +112,13 @@ impl Router {
 
         Err(anyhow!("no route matched path {}", request_path))
     }
+
+    fn lookup(&self, path: &str) -> Option<&RouteEntry<K>> {
+        self.index.get(path).map(|i| &self.routes[*i])
+    }
 }
 
+#[cfg(test)]
 fn path_matches(route_path: &str, request_path: &str) -> bool {
     if route_path == "/" {
         return true;
@@ -0,0 +1,2 @@
+mod router_bench;
+mod router_tests;
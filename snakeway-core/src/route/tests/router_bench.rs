@@ -0,0 +1,52 @@
+//! Router match latency benchmark.
+//!
+//! Ignored by default because timings are only meaningful in release builds:
+//!
+//! `cargo test -p snakeway-core --release router_match_benchmark -- --ignored --nocapture`
+
+use crate::route::tests::router_tests::router_with;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const ROUTE_COUNT: usize = 1000;
+const ITERATIONS: usize = 200_000;
+
+fn bench(requests: &[String], mut f: impl FnMut(&str)) -> Duration {
+    let started = Instant::now();
+    for i in 0..ITERATIONS {
+        f(black_box(&requests[i % requests.len()]));
+    }
+    started.elapsed() / ITERATIONS as u32
+}
+
+#[test]
+#[ignore]
+fn router_match_benchmark() {
+    // Arrange
+    let paths = (0..ROUTE_COUNT)
+        .map(|i| format!("/service-{i}/api"))
+        .collect::<Vec<_>>();
+    let path_refs = paths.iter().map(String::as_str).collect::<Vec<_>>();
+    let router = router_with(&path_refs);
+
+    // Spread requests over the whole table, including the worst case for a linear scan.
+    let requests = (0..ROUTE_COUNT)
+        .rev()
+        .map(|i| format!("/service-{i}/api/users/{i}"))
+        .collect::<Vec<_>>();
+
+    // Act
+    let linear = bench(&requests, |p| {
+        black_box(router.match_route_linear(p).ok());
+    });
+    let compiled = bench(&requests, |p| {
+        black_box(router.match_route(p).ok());
+    });
+
+    // Assert
+    println!("router match ({ROUTE_COUNT} routes): linear={linear:?}/op compiled={compiled:?}/op");
+    assert!(
+        compiled < linear,
+        "compiled matching ({compiled:?}) should beat linear matching ({linear:?})"
+    );
+}
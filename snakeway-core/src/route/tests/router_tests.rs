@@ -0,0 +1,177 @@
+use crate::route::types::RouteId;
+use crate::route::{RouteRuntime, Router};
+use pretty_assertions::assert_eq;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+pub(super) fn service_route(path: &str) -> RouteRuntime {
+    RouteRuntime::Service {
+        id: RouteId::service(path, "svc"),
+        upstream: "svc".to_string(),
+        allow_websocket: false,
+        ws_max_connections: None,
+        response_rate_limit: None,
+        hide_as_404: None,
+        max_request_body_bytes: None,
+        request_timeout: None,
+        require_tls: None,
+        affinity_ttl: None,
+    }
+}
+
+pub(super) fn router_with(paths: &[&str]) -> Router {
+    let mut router = Router::new();
+    for path in paths {
+        router.add_route(path, service_route(path)).unwrap();
+    }
+    router
+}
+
+fn matched(router: &Router, request_path: &str) -> Option<String> {
+    router
+        .match_route(request_path)
+        .ok()
+        .map(|r| r.path.clone())
+}
+
+fn matched_linear(router: &Router, request_path: &str) -> Option<String> {
+    router
+        .match_route_linear(request_path)
+        .ok()
+        .map(|r| r.path.clone())
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn longest_prefix_wins() {
+    // Arrange
+    let router = router_with(&["/", "/api", "/api/v1", "/api/v1/users"]);
+
+    // Act & Assert
+    assert_eq!(
+        matched(&router, "/api/v1/users/42"),
+        Some("/api/v1/users".into())
+    );
+    assert_eq!(matched(&router, "/api/v1/orders"), Some("/api/v1".into()));
+    assert_eq!(matched(&router, "/api/v2"), Some("/api".into()));
+    assert_eq!(matched(&router, "/other"), Some("/".into()));
+}
+
+#[test]
+fn prefix_must_end_on_a_segment_boundary() {
+    // Arrange
+    let router = router_with(&["/api"]);
+
+    // Act & Assert
+    assert_eq!(matched(&router, "/api"), Some("/api".into()));
+    assert_eq!(matched(&router, "/api/"), Some("/api".into()));
+    assert_eq!(matched(&router, "/apiary"), None);
+}
+
+#[test]
+fn root_route_is_a_catch_all() {
+    // Arrange
+    let router = router_with(&["/"]);
+
+    // Act & Assert
+    assert_eq!(matched(&router, "/"), Some("/".into()));
+    assert_eq!(matched(&router, "/anything/at/all"), Some("/".into()));
+}
+
+#[test]
+fn invalid_request_path_is_rejected() {
+    // Arrange
+    let router = router_with(&["/"]);
+
+    // Act
+    let result = router.match_route("no-leading-slash");
+
+    // Assert
+    assert!(result.is_err());
+}
+
+#[test]
+fn duplicate_route_path_is_rejected() {
+    // Arrange
+    let mut router = router_with(&["/api"]);
+
+    // Act
+    let result = router.add_route("/api", service_route("/api"));
+
+    // Assert
+    assert!(result.is_err());
+}
+
+#[test]
+fn compiled_matching_is_identical_to_linear_matching() {
+    // Arrange
+    let routes = [
+        "/",
+        "/api",
+        "/api/",
+        "/api/v1",
+        "/api/v1/users",
+        "/assets",
+        "/assets/img",
+        "/ws",
+        "/a/b/c/d",
+    ];
+    let requests = [
+        "/",
+        "//",
+        "/api",
+        "/api/",
+        "/api//x",
+        "/api/v1",
+        "/api/v1/",
+        "/api/v1/users",
+        "/api/v1/users/1",
+        "/api/v1/usersx",
+        "/api/v2/users",
+        "/apix",
+        "/assets/img/logo.png",
+        "/assets/imgx",
+        "/ws",
+        "/ws/chat",
+        "/a/b/c",
+        "/a/b/c/d/e",
+        "/unknown",
+    ];
+
+    // Test with and without the root catch-all route.
+    for router in [router_with(&routes), router_with(&routes[1..])] {
+        for request in requests {
+            // Act
+            let compiled = matched(&router, request);
+            let linear = matched_linear(&router, request);
+
+            // Assert
+            assert_eq!(compiled, linear, "mismatch for request path {request}");
+        }
+    }
+}
+
+#[test]
+fn compiled_matching_is_identical_to_linear_matching_with_many_routes() {
+    // Arrange
+    let paths = (0..1000)
+        .map(|i| format!("/svc{}/v{}/r{}", i % 50, i % 7, i))
+        .chain((0..50).map(|i| format!("/svc{i}")))
+        .collect::<Vec<_>>();
+    let path_refs = paths.iter().map(String::as_str).collect::<Vec<_>>();
+    let router = router_with(&path_refs);
+
+    for i in 0..2000 {
+        let request = format!("/svc{}/v{}/r{}/item", i % 60, i % 7, i % 1100);
+
+        // Act
+        let compiled = matched(&router, &request);
+        let linear = matched_linear(&router, &request);
+
+        // Assert
+        assert_eq!(compiled, linear, "mismatch for request path {request}");
+    }
+}
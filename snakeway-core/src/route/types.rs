@@ -1,8 +1,11 @@
-use crate::conf::types::{CachePolicy, CompressionOptions};
+use crate::conf::types::{
+    CachePolicy, CompressionOptions, RequireTlsConfig, ResponseRateLimitConfig, SpaFallbackConfig,
+};
 use serde::Serialize;
 use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 #[derive(Debug, Clone)]
 pub enum RouteRuntime {
@@ -12,6 +15,12 @@ pub enum RouteRuntime {
         upstream: String,
         allow_websocket: bool,
         ws_max_connections: Option<usize>,
+        response_rate_limit: Option<ResponseRateLimitConfig>,
+        hide_as_404: Option<bool>,
+        max_request_body_bytes: Option<u64>,
+        request_timeout: Option<Duration>,
+        require_tls: Option<RequireTlsConfig>,
+        affinity_ttl: Option<Duration>,
     },
 
     /// Serve files from the local filesystem
@@ -24,6 +33,9 @@ pub enum RouteRuntime {
         max_file_size: u64,
         static_config: CompressionOptions,
         cache_policy: CachePolicy,
+        hide_as_404: Option<bool>,
+        require_tls: Option<RequireTlsConfig>,
+        spa_fallback: Option<SpaFallbackConfig>,
     },
 }
 
@@ -34,6 +46,22 @@ impl RouteRuntime {
             RouteRuntime::Static { id, .. } => id,
         }
     }
+
+    /// The route's `hide_as_404` override, if it sets one.
+    pub fn hide_as_404(&self) -> Option<bool> {
+        match self {
+            RouteRuntime::Service { hide_as_404, .. } => *hide_as_404,
+            RouteRuntime::Static { hide_as_404, .. } => *hide_as_404,
+        }
+    }
+
+    /// What to do with requests arriving over plaintext HTTP, if the route requires TLS.
+    pub fn require_tls(&self) -> Option<RequireTlsConfig> {
+        match self {
+            RouteRuntime::Service { require_tls, .. } => *require_tls,
+            RouteRuntime::Static { require_tls, .. } => *require_tls,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Serialize)]
@@ -93,4 +121,9 @@ impl RouteId {
     pub fn kind(&self) -> RouteKind {
         self.kind
     }
+
+    /// Route path, without a trailing slash.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
 }
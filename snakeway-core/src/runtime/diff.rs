@@ -0,0 +1,153 @@
+use crate::conf::RuntimeConfig;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// What changed between two runtime configs, e.g. the running one and the one a reload applies.
+///
+/// Items are matched by name. Secrets are compared in their redacted form, so a rotated
+/// secret alone does not show up as a change.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct RuntimeDiff {
+    /// Listeners, by name.
+    pub listeners: ChangeSet,
+    /// Services, by name. Upstream changes are reported under `upstreams` only.
+    pub services: ChangeSet,
+    /// Upstreams, as `<service>/<url>` or `<service>/unix:<path>`.
+    pub upstreams: ChangeSet,
+    /// Devices, by the name used to attach them to listeners.
+    pub devices: ChangeSet,
+}
+
+/// Names of the items added, removed, and changed, each in ascending order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ChangeSet {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+}
+
+impl RuntimeDiff {
+    pub fn between(old: &RuntimeConfig, new: &RuntimeConfig) -> Self {
+        Self {
+            listeners: ChangeSet::between(
+                old.listeners.iter().map(|l| (l.name.clone(), l)).collect(),
+                new.listeners.iter().map(|l| (l.name.clone(), l)).collect(),
+            ),
+            services: ChangeSet::between(
+                services_without_upstreams(old),
+                services_without_upstreams(new),
+            ),
+            upstreams: ChangeSet::between(upstreams(old), upstreams(new)),
+            devices: ChangeSet::between(
+                old.devices
+                    .iter()
+                    .map(|d| (d.name().to_string(), d))
+                    .collect(),
+                new.devices
+                    .iter()
+                    .map(|d| (d.name().to_string(), d))
+                    .collect(),
+            ),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.listeners.is_empty()
+            && self.services.is_empty()
+            && self.upstreams.is_empty()
+            && self.devices.is_empty()
+    }
+}
+
+impl ChangeSet {
+    /// An item changed when its serialized form differs.
+    fn between<T: Serialize>(old: BTreeMap<String, T>, new: BTreeMap<String, T>) -> Self {
+        let mut set = Self::default();
+
+        for (name, old_item) in &old {
+            match new.get(name) {
+                None => set.removed.push(name.clone()),
+                Some(new_item)
+                    if serde_json::to_value(old_item).ok()
+                        != serde_json::to_value(new_item).ok() =>
+                {
+                    set.changed.push(name.clone())
+                }
+                Some(_) => {}
+            }
+        }
+
+        set.added = new
+            .into_keys()
+            .filter(|name| !old.contains_key(name))
+            .collect();
+
+        set
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Services with their upstreams left out, so an upstream change does not count twice.
+fn services_without_upstreams(cfg: &RuntimeConfig) -> BTreeMap<String, serde_json::Value> {
+    cfg.services
+        .iter()
+        .filter_map(|(name, svc)| {
+            let mut svc = serde_json::to_value(svc).ok()?;
+            let fields = svc.as_object_mut()?;
+            fields.remove("tcp_upstreams");
+            fields.remove("unix_upstreams");
+            Some((name.clone(), svc))
+        })
+        .collect()
+}
+
+fn upstreams(cfg: &RuntimeConfig) -> BTreeMap<String, serde_json::Value> {
+    let mut out = BTreeMap::new();
+
+    for (name, svc) in &cfg.services {
+        for u in &svc.tcp_upstreams {
+            if let Ok(value) = serde_json::to_value(u) {
+                out.insert(format!("{name}/{}", u.url), value);
+            }
+        }
+        for u in &svc.unix_upstreams {
+            if let Ok(value) = serde_json::to_value(u) {
+                out.insert(format!("{name}/unix:{}", u.sock), value);
+            }
+        }
+    }
+
+    out
+}
+
+/// One line per change: `+` added, `-` removed, `~` changed.
+impl fmt::Display for RuntimeDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "no changes");
+        }
+
+        for (kind, set) in [
+            ("listener", &self.listeners),
+            ("service", &self.services),
+            ("upstream", &self.upstreams),
+            ("device", &self.devices),
+        ] {
+            for name in &set.added {
+                writeln!(f, "+ {kind} {name}")?;
+            }
+            for name in &set.removed {
+                writeln!(f, "- {kind} {name}")?;
+            }
+            for name in &set.changed {
+                writeln!(f, "~ {kind} {name}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,10 +1,15 @@
+mod diff;
 mod error;
 mod state;
 mod types;
 
+pub use diff::{ChangeSet, RuntimeDiff};
 pub use error::ReloadError;
-pub use state::{build_runtime_state, reload_runtime_state};
+pub use state::{build_runtime_state, build_runtime_state_with_shared, reload_runtime_state};
 pub use types::{
     RuntimeState, ServiceRuntime, UpstreamId, UpstreamRuntime, UpstreamTcpRuntime,
     UpstreamUnixRuntime,
 };
+
+#[cfg(test)]
+mod tests;
@@ -1,23 +1,33 @@
-use crate::conf::types::{RouteConfig, ServiceConfig, UpstreamTcpConfig, UpstreamUnixConfig};
+use crate::conf::types::{
+    AccessLogFormat, RouteConfig, ServiceConfig, UpstreamTcpConfig, UpstreamUnixConfig,
+};
 use crate::conf::{RuntimeConfig, load_config};
+use crate::device::WasmModuleCache;
 use crate::device::core::registry::DeviceRegistry;
+use crate::enrichment::geoip::GeoIp;
 use crate::route::types::RouteId;
 use crate::route::{RouteRuntime, Router};
+use crate::runtime::diff::RuntimeDiff;
 use crate::runtime::error::ReloadError;
 use crate::runtime::types::{UpstreamAddr, UpstreamTcpRuntime, UpstreamUnixRuntime};
 use crate::runtime::{RuntimeState, ServiceRuntime, UpstreamId, UpstreamRuntime};
+use crate::server::ConnectionLogSampler;
 use ahash::RandomState;
 use anyhow::{Result, anyhow};
 use arc_swap::ArcSwap;
-use http::Uri;
-use std::collections::HashMap;
+use http::{HeaderName, HeaderValue, Uri};
+use ipnet::IpNet;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 
+/// Swap in a runtime state built from the config at `config_path`.
+/// Returns what changed relative to the config the current state was built from.
 pub async fn reload_runtime_state(
     config_path: &Path,
     state: &ArcSwap<RuntimeState>,
-) -> Result<(), ReloadError> {
+) -> Result<RuntimeDiff, ReloadError> {
     // Parse and validate config.
     let validated = load_config(config_path)?;
 
@@ -27,45 +37,125 @@ pub async fn reload_runtime_state(
         });
     }
 
-    // Build a new runtime state OFFLINE.
-    let new_state = build_runtime_state(&validated.config)?;
-
-    // Log comparison against current state.
+    // Build a new runtime state OFFLINE, reusing WASM modules that have not changed.
     let old = state.load();
-    let old_routers = old.routers.len();
+    let new_state = build_runtime_state_with_shared(
+        &validated.config,
+        old.wasm_modules.clone(),
+        old.geoip.clone(),
+    )?;
+
+    // Log what changed against current state.
+    let diff = RuntimeDiff::between(&old.config, &new_state.config);
     tracing::info!(
-        old_routers = old_routers,
-        old_devices = old.devices.all().len(),
-        new_routers = new_state.routers.len(),
-        new_devices = new_state.devices.all().len(),
+        listeners_added = ?diff.listeners.added,
+        listeners_removed = ?diff.listeners.removed,
+        listeners_changed = ?diff.listeners.changed,
+        services_added = ?diff.services.added,
+        services_removed = ?diff.services.removed,
+        services_changed = ?diff.services.changed,
+        upstreams_added = ?diff.upstreams.added,
+        upstreams_removed = ?diff.upstreams.removed,
+        upstreams_changed = ?diff.upstreams.changed,
+        devices_added = ?diff.devices.added,
+        devices_removed = ?diff.devices.removed,
+        devices_changed = ?diff.devices.changed,
         "runtime state reloaded"
     );
 
+    // Reopen the GeoIP databases, which the old and new devices share, last of all, so a
+    // failed reload leaves them as they were.
+    new_state.geoip.reload(&validated.config.server.geoip)?;
+
     // Atomic swap (point of no return).
     state.store(Arc::new(new_state));
 
-    Ok(())
+    Ok(diff)
 }
 
 pub fn build_runtime_state(cfg: &RuntimeConfig) -> Result<RuntimeState> {
+    let geoip = GeoIp::open(&cfg.server.geoip)?;
+    build_runtime_state_with_shared(cfg, Arc::default(), Arc::new(geoip))
+}
+
+/// Build a runtime state that compiles WASM devices through an existing module cache,
+/// so a reload only recompiles modules whose files changed, and looks clients up in
+/// existing GeoIP databases.
+pub fn build_runtime_state_with_shared(
+    cfg: &RuntimeConfig,
+    wasm_modules: Arc<WasmModuleCache>,
+    geoip: Arc<GeoIp>,
+) -> Result<RuntimeState> {
     // Routers
     let routers = build_runtime_routers(&cfg.routes)?;
 
-    // Devices
-    let mut devices = DeviceRegistry::new();
+    // Devices. WASM devices that fail to load fail the build unless `strict_wasm_devices` is off.
+    let mut devices = DeviceRegistry::with_shared(wasm_modules.clone(), geoip.clone());
     devices.load_from_config(cfg)?;
     tracing::debug!("Loaded device count = {}", devices.all().len());
 
     // Services
     let services = build_runtime_services(&cfg.services)?;
 
+    // Strict host checking
+    let strict_hosts = cfg
+        .server
+        .strict_host
+        .then(|| build_strict_hosts(&cfg.services));
+
+    // Trusted proxies (already validated)
+    let trusted_proxies = cfg
+        .server
+        .trusted_proxies
+        .iter()
+        .map(|s| s.parse::<IpNet>())
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Access log template (already validated)
+    let access_log_format = cfg
+        .server
+        .access_log_format
+        .as_deref()
+        .map(AccessLogFormat::parse)
+        .transpose()?;
+
     Ok(RuntimeState {
         routers,
         devices,
         services,
+        strict_hosts,
+        hide_as_404: cfg.server.hide_as_404,
+        ws_max_connections: cfg.server.ws_max_connections,
+        max_request_body_bytes: cfg.server.max_request_body_bytes,
+        request_timeout: cfg
+            .server
+            .request_timeout_milliseconds
+            .map(Duration::from_millis),
+        trusted_proxies,
+        access_log: cfg.server.access_log,
+        access_log_format,
+        connection_log: ConnectionLogSampler::new(cfg.server.connection_log_sample_rate),
+        wasm_modules,
+        geoip,
+        config: Arc::new(cfg.clone()),
     })
 }
 
+/// Collect the hosts declared by services, keyed by the listener they are attached to.
+fn build_strict_hosts(
+    services: &HashMap<String, ServiceConfig>,
+) -> HashMap<Arc<str>, HashSet<String>> {
+    let mut out: HashMap<Arc<str>, HashSet<String>> = HashMap::new();
+
+    for svc in services.values() {
+        out.entry(Arc::from(svc.listener.as_str()))
+            .or_default()
+            .extend(svc.hosts.iter().cloned());
+    }
+
+    out
+}
+
 /// Build service runtimes from config services.
 /// The output is a map of service names to their respective runtimes.
 fn build_runtime_services(
@@ -93,6 +183,17 @@ fn build_runtime_services(
                 .collect::<Result<Vec<_>>>()?,
         );
 
+        let upstream_request_headers = svc
+            .upstream_request_headers
+            .iter()
+            .map(|h| {
+                let mut value = HeaderValue::from_str(h.value.expose())
+                    .map_err(|_| anyhow!("invalid value for upstream request header {}", h.name))?;
+                value.set_sensitive(true);
+                Ok((HeaderName::from_bytes(h.name.as_bytes())?, value))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
         out.insert(
             name.clone(),
             ServiceRuntime {
@@ -100,7 +201,19 @@ fn build_runtime_services(
                 upstreams,
                 circuit_breaker_cfg: svc.circuit_breaker.clone(),
                 health_check_cfg: svc.health_check.clone(),
+                ketama_cfg: svc.ketama.clone(),
+                outlier_detection_cfg: svc.outlier_detection.clone(),
+                retry_policy_cfg: svc.retry_policy.clone(),
                 listener: Some(Arc::from(svc.listener.clone())),
+                upstream_header_case: svc.upstream_header_case.clone(),
+                upstream_sni: svc.upstream_sni.clone(),
+                upstream_accept_encoding: svc.upstream_accept_encoding.clone(),
+                enable_response_compression: svc.enable_response_compression,
+                response_compression_skip_content_types: svc
+                    .response_compression_skip_content_types
+                    .clone(),
+                response_compression_min_size: svc.response_compression_min_size,
+                upstream_request_headers,
             },
         );
     }
@@ -123,6 +236,12 @@ pub fn build_runtime_routers(routes: &[RouteConfig]) -> Result<HashMap<Arc<str>,
                 upstream: cfg.service.clone(),
                 allow_websocket: cfg.allow_websocket,
                 ws_max_connections: cfg.ws_max_connections,
+                response_rate_limit: cfg.response_rate_limit,
+                hide_as_404: cfg.hide_as_404,
+                max_request_body_bytes: cfg.max_request_body_bytes,
+                request_timeout: cfg.request_timeout,
+                require_tls: cfg.require_tls,
+                affinity_ttl: cfg.affinity_ttl,
             },
             RouteConfig::Static(cfg) => RouteRuntime::Static {
                 id: RouteId::static_route(&cfg.path, &canonicalize_dir(&cfg.file_dir)),
@@ -133,6 +252,9 @@ pub fn build_runtime_routers(routes: &[RouteConfig]) -> Result<HashMap<Arc<str>,
                 max_file_size: cfg.max_file_size,
                 static_config: cfg.static_config.clone(),
                 cache_policy: cfg.cache_policy.clone(),
+                hide_as_404: cfg.hide_as_404,
+                require_tls: cfg.require_tls,
+                spa_fallback: cfg.spa_fallback.clone(),
             },
         };
 
@@ -171,9 +293,12 @@ fn make_upstream_runtime_from_tcp(cfg: &UpstreamTcpConfig) -> Result<UpstreamRun
         id: make_upstream_id(&addr),
         host: host.clone(),
         port,
+        hostname: cfg.hostname.clone(),
         use_tls: scheme == "https",
         sni: host.clone(),
         weight: cfg.weight,
+        connection: cfg.connection,
+        group: cfg.group.clone(),
     }))
 }
 
@@ -188,6 +313,8 @@ fn make_upstream_runtime_for_unix(cfg: &UpstreamUnixConfig) -> Result<UpstreamRu
         use_tls: cfg.use_tls,
         sni: cfg.sni.clone(),
         weight: cfg.weight,
+        connection: cfg.connection,
+        group: cfg.group.clone(),
     }))
 }
 
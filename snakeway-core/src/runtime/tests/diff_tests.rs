@@ -0,0 +1,186 @@
+use crate::conf::{RuntimeConfig, load_config};
+use crate::runtime::{ChangeSet, RuntimeDiff};
+use pretty_assertions::assert_eq;
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+const DEVICES: &str = r#"
+request_filter_device {
+  enable = true
+
+  deny_methods = ["DELETE"]
+}
+
+required_headers_device {
+  enable = true
+
+  headers = [{ name = "X-Correlation-Id" }]
+}
+"#;
+
+fn ingress(port: u16, upstream_port: u16) -> String {
+    format!(
+        r#"
+bind = {{
+  interface    = "127.0.0.1"
+  port         = {port}
+  enable_http2 = false
+}}
+
+services = [
+  {{
+    load_balancing_strategy = "round_robin"
+
+    routes = [
+      {{
+        path = "/api"
+      }}
+    ]
+
+    upstreams = [
+      {{
+        endpoint = {{ host = "127.0.0.1", port = {upstream_port} }}
+      }}
+    ]
+  }}
+]
+"#
+    )
+}
+
+fn write_config(root: &Path, ingress_files: &[(&str, String)], devices: &str) {
+    fs::create_dir_all(root.join("devices.d")).unwrap();
+    fs::create_dir_all(root.join("ingress.d")).unwrap();
+    fs::write(
+        root.join("snakeway.hcl"),
+        r#"
+server {
+  version = 1
+}
+
+include {
+  devices = "devices.d/*.hcl"
+  ingress = "ingress.d/*.hcl"
+}
+"#,
+    )
+    .unwrap();
+    fs::write(root.join("devices.d/devices.hcl"), devices).unwrap();
+    for (name, contents) in ingress_files {
+        fs::write(root.join("ingress.d").join(name), contents).unwrap();
+    }
+}
+
+fn config(ingress_files: &[(&str, String)], devices: &str) -> RuntimeConfig {
+    let dir = tempdir().unwrap();
+    write_config(dir.path(), ingress_files, devices);
+    load_config(dir.path()).unwrap().config
+}
+
+#[test]
+fn identical_configs_have_an_empty_diff() {
+    // Arrange
+    let old = config(&[("api.hcl", ingress(8080, 9001))], DEVICES);
+    let new = old.clone();
+
+    // Act
+    let diff = RuntimeDiff::between(&old, &new);
+
+    // Assert
+    assert!(diff.is_empty());
+    assert_eq!(diff.to_string(), "no changes\n");
+}
+
+#[test]
+fn added_listener_and_service_are_reported_with_their_upstreams() {
+    // Arrange
+    let old = config(&[("api.hcl", ingress(8080, 9001))], DEVICES);
+    let new = config(
+        &[
+            ("api.hcl", ingress(8080, 9001)),
+            ("tenants.hcl", ingress(8081, 9001)),
+        ],
+        DEVICES,
+    );
+    let added_listener = new
+        .listeners
+        .iter()
+        .find(|l| !old.listeners.iter().any(|o| o.name == l.name))
+        .map(|l| l.name.clone())
+        .unwrap();
+
+    // Act
+    let diff = RuntimeDiff::between(&old, &new);
+
+    // Assert
+    assert_eq!(diff.listeners.added, vec![added_listener]);
+    assert!(diff.listeners.removed.is_empty());
+    assert_eq!(
+        diff.services,
+        ChangeSet {
+            added: vec!["127.0.0.1:8081-service".to_string()],
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        diff.upstreams,
+        ChangeSet {
+            added: vec!["127.0.0.1:8081-service/http://127.0.0.1:9001".to_string()],
+            ..Default::default()
+        }
+    );
+    assert!(diff.devices.is_empty());
+}
+
+#[test]
+fn moved_upstream_is_reported_without_changing_its_service() {
+    // Arrange
+    let old = config(&[("api.hcl", ingress(8080, 9001))], DEVICES);
+    let new = config(&[("api.hcl", ingress(8080, 9002))], DEVICES);
+
+    // Act
+    let diff = RuntimeDiff::between(&old, &new);
+
+    // Assert
+    assert!(diff.services.is_empty());
+    assert_eq!(
+        diff.upstreams,
+        ChangeSet {
+            added: vec!["127.0.0.1:8080-service/http://127.0.0.1:9002".to_string()],
+            removed: vec!["127.0.0.1:8080-service/http://127.0.0.1:9001".to_string()],
+            changed: vec![],
+        }
+    );
+}
+
+#[test]
+fn changed_and_removed_devices_are_reported() {
+    // Arrange
+    let old = config(&[("api.hcl", ingress(8080, 9001))], DEVICES);
+    let devices = r#"
+request_filter_device {
+  enable = true
+
+  deny_methods = ["DELETE", "PUT"]
+}
+"#;
+    let new = config(&[("api.hcl", ingress(8080, 9001))], devices);
+
+    // Act
+    let diff = RuntimeDiff::between(&old, &new);
+
+    // Assert
+    assert_eq!(
+        diff.devices,
+        ChangeSet {
+            added: vec![],
+            removed: vec!["required_headers".to_string()],
+            changed: vec!["request_filter".to_string()],
+        }
+    );
+    assert_eq!(
+        diff.to_string(),
+        "- device required_headers\n~ device request_filter\n"
+    );
+}
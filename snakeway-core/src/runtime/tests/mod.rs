@@ -0,0 +1,2 @@
+mod diff_tests;
+mod reload_tests;
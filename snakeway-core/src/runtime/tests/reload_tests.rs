@@ -0,0 +1,110 @@
+use crate::conf::load_config;
+use crate::runtime::{build_runtime_state, reload_runtime_state};
+use arc_swap::ArcSwap;
+use pretty_assertions::assert_eq;
+use std::fs;
+use std::net::IpAddr;
+use std::path::Path;
+use std::sync::Arc;
+use tempfile::tempdir;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+/// A DB-IP country database, which has the same layout as GeoLite2-Country.
+const COUNTRY_DB: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../integration-tests/fixtures/geoip/dbip-country-lite-2025-12.mmdb"
+);
+
+const DEVICES: &str = r#"
+identity_device = {
+  enable = true
+
+  trusted_proxies   = []
+  enable_geoip      = true
+  enable_user_agent = false
+  ua_engine         = "woothee"
+}
+"#;
+
+const INGRESS: &str = r#"
+bind = {
+  interface = "127.0.0.1"
+  port      = 8080
+}
+
+services = [
+  {
+    load_balancing_strategy = "round_robin"
+
+    routes = [
+      {
+        path = "/api"
+      }
+    ]
+
+    upstreams = [
+      {
+        endpoint = { host = "127.0.0.1", port = 9001 }
+      }
+    ]
+  }
+]
+"#;
+
+/// Write a config whose server block has `server_extra` added to it.
+fn write_config(root: &Path, server_extra: &str) {
+    fs::create_dir_all(root.join("devices.d")).unwrap();
+    fs::create_dir_all(root.join("ingress.d")).unwrap();
+    fs::write(
+        root.join("snakeway.hcl"),
+        format!(
+            r#"
+server {{
+  version = 1
+{server_extra}
+}}
+
+include {{
+  devices = "devices.d/*.hcl"
+  ingress = "ingress.d/*.hcl"
+}}
+"#
+        ),
+    )
+    .unwrap();
+    fs::write(root.join("devices.d/devices.hcl"), DEVICES).unwrap();
+    fs::write(root.join("ingress.d/api.hcl"), INGRESS).unwrap();
+}
+
+fn ip(s: &str) -> IpAddr {
+    s.parse().unwrap()
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[tokio::test]
+async fn reload_reopens_the_geoip_databases_devices_share() {
+    // Arrange
+    let dir = tempdir().unwrap();
+    write_config(dir.path(), "");
+    let config = load_config(dir.path()).unwrap().config;
+    let state = ArcSwap::from_pointee(build_runtime_state(&config).unwrap());
+    let geoip = state.load().geoip.clone();
+    let before = geoip.lookup(ip("8.8.8.8"));
+    write_config(
+        dir.path(),
+        &format!("  geoip {{\n    city_db = \"{COUNTRY_DB}\"\n  }}"),
+    );
+
+    // Act
+    reload_runtime_state(dir.path(), &state).await.unwrap();
+
+    // Assert
+    assert!(before.is_none());
+    assert!(Arc::ptr_eq(&geoip, &state.load().geoip));
+    let after = geoip.lookup(ip("8.8.8.8")).unwrap();
+    assert_eq!(after.country_code.as_deref(), Some("US"));
+}
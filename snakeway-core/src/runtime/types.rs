@@ -1,14 +1,129 @@
-use crate::conf::types::{CircuitBreakerConfig, HealthCheckConfig, LoadBalancingStrategy};
+use crate::conf::RuntimeConfig;
+use crate::conf::types::{
+    AccessLogFormat, CircuitBreakerConfig, HealthCheckConfig, KetamaConfig, LoadBalancingStrategy,
+    OutlierDetectionConfig, RequireTlsConfig, RetryPolicyConfig, UpstreamConnectionConfig,
+};
+use crate::device::WasmModuleCache;
 use crate::device::core::registry::DeviceRegistry;
+use crate::enrichment::geoip::GeoIp;
 use crate::route::Router;
-use std::collections::HashMap;
+use crate::server::ConnectionLogSampler;
+use crate::traffic_management::ServiceId;
+use http::{HeaderName, HeaderValue};
+use ipnet::IpNet;
+use std::collections::{HashMap, HashSet};
 use std::hash::Hash;
 use std::sync::Arc;
+use std::time::Duration;
 
 pub struct RuntimeState {
     pub routers: HashMap<Arc<str>, Router>,
     pub devices: DeviceRegistry,
     pub services: HashMap<String, ServiceRuntime>,
+
+    /// Per listener, the request hosts accepted when `strict_host` is enabled.
+    /// `None` when strict host checking is disabled.
+    pub strict_hosts: Option<HashMap<Arc<str>, HashSet<String>>>,
+
+    /// Answer requests denied by a device with `404` instead of `403`, unless the route overrides it.
+    pub hide_as_404: bool,
+
+    /// Maximum concurrent WebSocket connections across all routes, on top of per-route limits.
+    pub ws_max_connections: Option<usize>,
+
+    /// Largest request body accepted, unless the route overrides it.
+    pub max_request_body_bytes: Option<u64>,
+
+    /// Time a proxied request may take, unless the route overrides it.
+    pub request_timeout: Option<Duration>,
+
+    /// Proxies whose `X-Forwarded-Host` is trusted as the host of redirect URLs.
+    pub trusted_proxies: Vec<IpNet>,
+
+    /// Log one `access` event per request.
+    pub access_log: bool,
+
+    /// The fields access log events are limited to, and how their message renders them.
+    pub access_log_format: Option<AccessLogFormat>,
+
+    /// Decides which connections have their lifecycle events logged.
+    pub connection_log: ConnectionLogSampler,
+
+    /// Compiled WASM modules, carried over to the next state on reload.
+    pub wasm_modules: Arc<WasmModuleCache>,
+
+    /// The server's GeoIP databases, carried over to the next state and reopened on reload.
+    pub geoip: Arc<GeoIp>,
+
+    /// The config this state was built from, diffed against on reload.
+    pub config: Arc<RuntimeConfig>,
+}
+
+impl RuntimeState {
+    /// Returns false if strict host checking is enabled and the request host
+    /// is not declared by any service on the listener.
+    pub fn is_host_allowed(&self, listener: &str, host: Option<&str>) -> bool {
+        let Some(strict_hosts) = &self.strict_hosts else {
+            return true;
+        };
+
+        let Some(host) = host else {
+            return false;
+        };
+
+        // IPv6 literals arrive bracketed, and a trailing dot is the same DNS name.
+        let host = host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .trim_end_matches('.')
+            .to_ascii_lowercase();
+
+        strict_hosts
+            .get(listener)
+            .is_some_and(|hosts| hosts.contains(&host))
+    }
+
+    /// Whether a device denying this request should answer `404` instead of `403`.
+    /// The matched route's setting wins over the server's.
+    pub fn hides_denials(&self, listener: &str, path: &str) -> bool {
+        self.routers
+            .get(listener)
+            .and_then(|router| router.match_route(path).ok())
+            .and_then(|route| route.kind.hide_as_404())
+            .unwrap_or(self.hide_as_404)
+    }
+
+    /// The matched route's TLS requirement, if it has one and the listener is plaintext HTTP.
+    pub fn tls_required(&self, listener: &str, path: &str) -> Option<RequireTlsConfig> {
+        let listener_uses_tls = self
+            .config
+            .listeners
+            .iter()
+            .any(|l| l.name == listener && l.tls.is_some());
+        if listener_uses_tls {
+            return None;
+        }
+
+        self.routers
+            .get(listener)
+            .and_then(|router| router.match_route(path).ok())
+            .and_then(|route| route.kind.require_tls())
+    }
+
+    /// Address of an upstream, e.g. `10.0.0.5:8080` or `unix:/run/app.sock`.
+    pub fn upstream_addr(&self, service: &ServiceId, id: UpstreamId) -> Option<String> {
+        let upstream = self
+            .services
+            .get(&service.0)?
+            .upstreams
+            .iter()
+            .find(|u| u.id() == id)?;
+
+        Some(match upstream {
+            UpstreamRuntime::Tcp(tcp) => format!("{}:{}", tcp.host, tcp.port),
+            UpstreamRuntime::Unix(unix) => format!("unix:{}", unix.path),
+        })
+    }
 }
 
 /// ServiceRuntime encapsulates the state of a service, including its upstream(s) and load balancing strategy.
@@ -18,7 +133,18 @@ pub struct ServiceRuntime {
     pub upstreams: Vec<UpstreamRuntime>,
     pub circuit_breaker_cfg: CircuitBreakerConfig,
     pub health_check_cfg: HealthCheckConfig,
+    pub ketama_cfg: KetamaConfig,
+    pub outlier_detection_cfg: OutlierDetectionConfig,
+    pub retry_policy_cfg: RetryPolicyConfig,
     pub listener: Option<Arc<str>>,
+    pub upstream_header_case: Vec<String>,
+    pub upstream_sni: Option<String>,
+    pub upstream_accept_encoding: Option<String>,
+    pub enable_response_compression: bool,
+    pub response_compression_skip_content_types: Vec<String>,
+    pub response_compression_min_size: u64,
+    /// Injected upstream request headers. Values are marked sensitive, so they are not logged.
+    pub upstream_request_headers: Vec<(HeaderName, HeaderValue)>,
 }
 
 #[derive(Debug, Clone)]
@@ -49,6 +175,21 @@ impl UpstreamRuntime {
         }
     }
 
+    pub fn connection(&self) -> &UpstreamConnectionConfig {
+        match self {
+            UpstreamRuntime::Tcp(u) => &u.connection,
+            UpstreamRuntime::Unix(u) => &u.connection,
+        }
+    }
+
+    /// The upstream group, `None` for the service's default pool.
+    pub fn group(&self) -> Option<&str> {
+        match self {
+            UpstreamRuntime::Tcp(u) => u.group.as_deref(),
+            UpstreamRuntime::Unix(u) => u.group.as_deref(),
+        }
+    }
+
     pub fn authority(&self) -> String {
         match self {
             UpstreamRuntime::Tcp(u) => {
@@ -76,9 +217,13 @@ pub struct UpstreamTcpRuntime {
     pub id: UpstreamId,
     pub host: String,
     pub port: u16,
+    /// Re-resolved at connection time when set, otherwise `host` is used as-is.
+    pub hostname: Option<String>,
     pub use_tls: bool,
     pub sni: String,
     pub weight: u32,
+    pub connection: UpstreamConnectionConfig,
+    pub group: Option<String>,
 }
 
 impl UpstreamTcpRuntime {
@@ -94,4 +239,6 @@ pub struct UpstreamUnixRuntime {
     pub use_tls: bool,
     pub sni: String,
     pub weight: u32,
+    pub connection: UpstreamConnectionConfig,
+    pub group: Option<String>,
 }
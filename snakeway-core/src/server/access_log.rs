@@ -0,0 +1,164 @@
+use crate::conf::types::{AccessLogField, AccessLogFormat, AccessLogPart};
+use crate::ctx::{RequestCtx, RequestId};
+use crate::enrichment::user_agent::ClientIdentity;
+use pingora::http::RequestHeader;
+use std::fmt::{Display, Write};
+use std::net::IpAddr;
+use tracing::field::display;
+
+/// Event name of a request's access log entry.
+pub const ACCESS: &str = "access";
+
+/// One line of the access log, logged once a request is complete.
+///
+/// Log shippers parse these fields, so they are part of Snakeway's log format:
+/// renaming or removing one is a breaking change. Fields without a value are not logged.
+#[derive(Debug, Default)]
+pub struct AccessLogEntry<'a> {
+    pub request_id: Option<&'a str>,
+    pub method: Option<&'a str>,
+    /// The path as the client sent it, without the query.
+    pub path: Option<&'a str>,
+    /// Status of the response sent downstream.
+    pub status: Option<u16>,
+    /// Time from the request arriving to the response being sent.
+    pub latency_ms: Option<u64>,
+    pub service: Option<&'a str>,
+    /// Address of the upstream the request was proxied to.
+    pub upstream: Option<String>,
+    /// Client address found by the identity device, else the peer address.
+    pub client_ip: Option<IpAddr>,
+    /// Device type the identity device read from the User-Agent, e.g. `mobile`.
+    pub ua_device_type: Option<&'static str>,
+    pub bytes_in: Option<u64>,
+    pub bytes_out: Option<u64>,
+    /// The device that answered the request instead of it being proxied.
+    pub device: Option<&'a str>,
+    /// Why the request failed, when Pingora reported an error.
+    pub error: Option<String>,
+}
+
+impl<'a> AccessLogEntry<'a> {
+    /// The method and path come from the request header, as a request rejected during
+    /// normalization never hydrates its context.
+    pub fn new(
+        ctx: &'a RequestCtx,
+        req: &'a RequestHeader,
+        status: Option<u16>,
+        upstream: Option<String>,
+        error: Option<String>,
+    ) -> Self {
+        let identity = ctx.extensions.get::<ClientIdentity>();
+
+        Self {
+            request_id: ctx.extensions.get::<RequestId>().map(|id| id.0.as_str()),
+            method: Some(req.method.as_str()),
+            path: Some(req.uri.path()),
+            status,
+            latency_ms: Some(ctx.started_at.elapsed().as_millis() as u64),
+            service: ctx.service.as_deref(),
+            upstream,
+            client_ip: Some(identity.map_or(ctx.peer_ip, |identity| identity.ip)),
+            ua_device_type: identity
+                .and_then(|identity| identity.ua.as_ref())
+                .map(|ua| ua.device_type.as_str()),
+            bytes_in: Some(ctx.bytes_in),
+            bytes_out: Some(ctx.bytes_out),
+            device: ctx.responded_by.as_deref(),
+            error,
+        }
+    }
+
+    /// Drop the fields the format does not name.
+    pub fn retain(&mut self, format: &AccessLogFormat) {
+        for field in AccessLogField::ALL {
+            if format.includes(field) {
+                continue;
+            }
+            match field {
+                AccessLogField::RequestId => self.request_id = None,
+                AccessLogField::Method => self.method = None,
+                AccessLogField::Path => self.path = None,
+                AccessLogField::Status => self.status = None,
+                AccessLogField::LatencyMs => self.latency_ms = None,
+                AccessLogField::Service => self.service = None,
+                AccessLogField::Upstream => self.upstream = None,
+                AccessLogField::ClientIp => self.client_ip = None,
+                AccessLogField::UaDeviceType => self.ua_device_type = None,
+                AccessLogField::BytesIn => self.bytes_in = None,
+                AccessLogField::BytesOut => self.bytes_out = None,
+                AccessLogField::Device => self.device = None,
+                AccessLogField::Error => self.error = None,
+            }
+        }
+    }
+
+    /// The entry as a line of text, with `-` for fields without a value.
+    pub fn render(&self, format: &AccessLogFormat) -> String {
+        let mut line = String::new();
+        for part in format.parts() {
+            match part {
+                AccessLogPart::Literal(text) => line.push_str(text),
+                AccessLogPart::Field(field) => self.write_field(&mut line, *field),
+            }
+        }
+        line
+    }
+
+    fn write_field(&self, line: &mut String, field: AccessLogField) {
+        let value = match field {
+            AccessLogField::RequestId => shown(&self.request_id),
+            AccessLogField::Method => shown(&self.method),
+            AccessLogField::Path => shown(&self.path),
+            AccessLogField::Status => shown(&self.status),
+            AccessLogField::LatencyMs => shown(&self.latency_ms),
+            AccessLogField::Service => shown(&self.service),
+            AccessLogField::Upstream => shown(&self.upstream),
+            AccessLogField::ClientIp => shown(&self.client_ip),
+            AccessLogField::UaDeviceType => shown(&self.ua_device_type),
+            AccessLogField::BytesIn => shown(&self.bytes_in),
+            AccessLogField::BytesOut => shown(&self.bytes_out),
+            AccessLogField::Device => shown(&self.device),
+            AccessLogField::Error => shown(&self.error),
+        };
+        match value {
+            Some(value) => _ = write!(line, "{value}"),
+            None => line.push('-'),
+        }
+    }
+}
+
+fn shown<T: Display>(value: &Option<T>) -> Option<&dyn Display> {
+    value.as_ref().map(|v| v as &dyn Display)
+}
+
+/// Log a completed request.
+///
+/// With a format, only the fields it names are logged, and the message is the rendered template.
+pub fn log_access(mut entry: AccessLogEntry, format: Option<&AccessLogFormat>) {
+    let message = match format {
+        Some(format) => {
+            entry.retain(format);
+            entry.render(format)
+        }
+        None => ACCESS.to_string(),
+    };
+
+    tracing::info!(
+        event = ACCESS,
+        request_id = entry.request_id,
+        method = entry.method,
+        path = entry.path,
+        status = entry.status,
+        latency_ms = entry.latency_ms,
+        service = entry.service,
+        upstream = entry.upstream.as_deref(),
+        client_ip = entry.client_ip.map(display),
+        ua_device_type = entry.ua_device_type,
+        bytes_in = entry.bytes_in,
+        bytes_out = entry.bytes_out,
+        device = entry.device,
+        error = entry.error.as_deref(),
+        "{message}"
+    );
+}
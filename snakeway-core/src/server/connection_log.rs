@@ -0,0 +1,92 @@
+use crate::ctx::{WsCloseCtx, WsCtx};
+use rand::{Rng, rng};
+
+/// Event name of an upgraded WebSocket connection.
+pub const WEBSOCKET_OPENED: &str = "websocket_opened";
+
+/// Event name of a closed WebSocket connection.
+pub const WEBSOCKET_CLOSED: &str = "websocket_closed";
+
+/// Decides which connections have their lifecycle events logged.
+///
+/// The decision is made once per connection, so its open and close events are either both
+/// logged or both dropped. Failure events bypass sampling: a connection that closes with an
+/// error is always logged, and `tls_handshake_failed` events are never sampled.
+#[derive(Debug, Clone, Copy)]
+pub struct ConnectionLogSampler {
+    rate: f64,
+}
+
+impl ConnectionLogSampler {
+    pub fn new(rate: f64) -> Self {
+        Self {
+            rate: rate.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Roll for a new connection.
+    pub fn sample(&self) -> bool {
+        self.sample_with(rng().random::<f64>())
+    }
+
+    /// Whether a connection with the given roll in `[0, 1)` is sampled.
+    pub fn sample_with(&self, roll: f64) -> bool {
+        roll < self.rate
+    }
+}
+
+impl Default for ConnectionLogSampler {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+/// Marks a request context whose connection was sampled for lifecycle logging.
+#[derive(Debug, Clone, Copy)]
+pub struct SampledConnection;
+
+/// Whether a connection event is logged.
+pub fn should_log(sampled: bool, failure: bool) -> bool {
+    sampled || failure
+}
+
+/// Log an upgraded WebSocket connection, if it was sampled.
+pub fn log_websocket_opened(ws: &WsCtx, sampled: bool) {
+    if !should_log(sampled, false) {
+        return;
+    }
+
+    tracing::info!(
+        event = WEBSOCKET_OPENED,
+        request_id = ws.request_id.as_deref(),
+        path = %ws.path,
+        "websocket opened"
+    );
+}
+
+/// Log a closed WebSocket connection, if it was sampled or closed with an error.
+pub fn log_websocket_closed(ws: &WsCloseCtx, sampled: bool) {
+    if !should_log(sampled, ws.error.is_some()) {
+        return;
+    }
+
+    match &ws.error {
+        Some(error) => tracing::warn!(
+            event = WEBSOCKET_CLOSED,
+            request_id = ws.request_id.as_deref(),
+            path = %ws.path,
+            bytes_in = ws.bytes_in,
+            bytes_out = ws.bytes_out,
+            error = %error,
+            "websocket closed with error"
+        ),
+        None => tracing::info!(
+            event = WEBSOCKET_CLOSED,
+            request_id = ws.request_id.as_deref(),
+            path = %ws.path,
+            bytes_in = ws.bytes_in,
+            bytes_out = ws.bytes_out,
+            "websocket closed"
+        ),
+    }
+}
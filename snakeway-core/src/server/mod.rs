@@ -1,6 +1,19 @@
+mod access_log;
+mod connection_log;
 mod pid;
 mod reload;
 pub mod setup;
+mod shutdown;
+#[cfg(test)]
+mod tests;
+mod tls_handshake;
 
+pub use access_log::{ACCESS, AccessLogEntry, log_access};
+pub use connection_log::{
+    ConnectionLogSampler, SampledConnection, WEBSOCKET_CLOSED, WEBSOCKET_OPENED,
+    log_websocket_closed, log_websocket_opened, should_log,
+};
 pub use reload::ReloadHandle;
 pub use setup::{build_pingora_server, run};
+pub use shutdown::{InFlightGuard, ShutdownCoordinator, ShutdownPhase};
+pub use tls_handshake::{TLS_HANDSHAKE_FAILED, TlsHandshakeFailureLayer, classify_handshake_error};
@@ -1,22 +1,27 @@
 use crate::conf::RuntimeConfig;
 use crate::conf::types::ListenerConfig;
+use crate::ctx::ListenerInfo;
 use crate::device::core::registry::DeviceRegistry;
-use crate::proxy::{AdminGateway, PublicGateway, RedirectGateway};
+use crate::proxy::{AdminGateway, PipelinedHttp, PublicGateway, RedirectGateway};
 use crate::runtime::{ReloadError, RuntimeState, build_runtime_state, reload_runtime_state};
 use crate::server::pid;
 use crate::server::reload::{ReloadEvent, ReloadHandle};
-use crate::traffic_management::{TrafficManager, TrafficSnapshot};
+use crate::server::shutdown::ShutdownCoordinator;
+use crate::traffic_management::{ActiveHealthChecker, TrafficManager, TrafficSnapshot};
 use crate::ws_connection_management::WsConnectionManager;
 use anyhow::{Error, Result};
 use arc_swap::ArcSwap;
 use nix::NixPath;
+use pingora::listeners::TcpSocketOptions;
 use pingora::listeners::tls::TlsSettings;
 use pingora::prelude::*;
 use pingora::server::Server;
 use pingora::server::configuration::ServerConf;
+use pingora::services::listening::Service;
 use std::net::TcpListener;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 /// Run the Pingora server with the given configuration.
 pub fn run(config_path: &str, config: RuntimeConfig) -> Result<()> {
@@ -61,6 +66,37 @@ pub fn run(config_path: &str, config: RuntimeConfig) -> Result<()> {
         }
     });
 
+    // Shutdown wiring
+    let shutdown = Arc::new(ShutdownCoordinator::new(
+        Duration::from_secs(config.server.shutdown_timeout_seconds),
+        Duration::from_secs(config.server.shutdown_readiness_grace_seconds),
+    ));
+
+    control_rt.spawn({
+        let shutdown = shutdown.clone();
+        async move {
+            if let Err(e) = shutdown.listen_for_sigterm().await {
+                tracing::error!(error = %e, "failed to install SIGTERM handler");
+            }
+        }
+    });
+
+    // Spawn shutdown sequence (runs once SIGTERM flips readiness).
+    control_rt.spawn({
+        let shutdown = shutdown.clone();
+        let state = state.clone();
+        let pid_file = config.server.pid_file.clone();
+
+        async move {
+            shutdown.run_sequence(&state).await;
+
+            if !pid_file.is_empty() {
+                pid::remove_pid(&pid_file);
+            }
+            std::process::exit(0);
+        }
+    });
+
     // Spawn reload loop
     control_rt.spawn({
         let mut reload_rx = reload.subscribe();
@@ -120,6 +156,7 @@ pub fn run(config_path: &str, config: RuntimeConfig) -> Result<()> {
         Arc::clone(&traffic_manager),
         Arc::clone(&connection_manager),
         reload.clone(),
+        shutdown.clone(),
     )
     .map_err(|e| {
         tracing::error!(error = %e, "failed to build Pingora server");
@@ -137,8 +174,10 @@ pub fn run(config_path: &str, config: RuntimeConfig) -> Result<()> {
 
     // IMPORTANT:
     // - control_rt must stay in scope so its worker thread lives
-    // - run_forever blocks the main thread as intended
-    server.run_forever();
+    // - run blocks the main thread as intended
+    // - SIGTERM is routed through the shutdown coordinator, so readiness flips before Pingora
+    //   stops accepting
+    server.run(shutdown.run_args());
 }
 
 /// Build the Pingora server.
@@ -148,6 +187,7 @@ pub fn build_pingora_server(
     traffic_manager: Arc<TrafficManager>,
     connection_manager: Arc<WsConnectionManager>,
     reload: Arc<ReloadHandle>,
+    shutdown: Arc<ShutdownCoordinator>,
 ) -> Result<Server, Error> {
     let mut pingora_server_conf =
         ServerConf::new().expect("Could not construct pingora server configuration");
@@ -168,10 +208,18 @@ pub fn build_pingora_server(
         Server::new(None)?
     };
 
-    server.bootstrap();
+    // Bootstrapping sets up Pingora's table of listening sockets, which is keyed by address, so
+    // every endpoint on an address would reuse the first socket bound there. With reuse_port each
+    // worker thread needs a socket of its own, so endpoints bind their sockets themselves.
+    if !config.server.reuse_port {
+        server.bootstrap();
+    }
 
     // Load devices
-    let mut registry = DeviceRegistry::new();
+    let mut registry = DeviceRegistry::with_shared(
+        state.load().wasm_modules.clone(),
+        state.load().geoip.clone(),
+    );
     registry.load_from_config(&config)?;
     tracing::debug!("Loaded device count = {}", registry.all().len());
 
@@ -182,23 +230,50 @@ pub fn build_pingora_server(
     {
         // Build the public HTTP proxy service from Pingora.
         let public_gateway = PublicGateway::new(
-            Arc::from(listener.name.clone()),
+            ListenerInfo::from(listener),
             state.clone(),
             traffic_manager.clone(),
             connection_manager.clone(),
+            shutdown.clone(),
+        );
+        // Pipelined HTTP/1.1 requests are held back until the response before them is complete.
+        let mut public_svc = Service::new(
+            "Pingora HTTP Proxy Service".to_string(),
+            PipelinedHttp::new(http_proxy(&server.configuration, public_gateway)),
         );
-        let mut public_svc = http_proxy_service(&server.configuration, public_gateway);
 
-        match &listener.tls {
-            Some(tls) => {
-                let mut tls_settings = TlsSettings::intermediate(&tls.cert, &tls.key)?;
-                if listener.enable_http2 {
-                    tls_settings.enable_h2();
+        // With reuse_port, every worker thread gets a socket of its own on the listener address,
+        // and the kernel spreads accepted connections across them instead of a single socket.
+        let (sockets, sock_opt) = if config.server.reuse_port {
+            let sock_opt = TcpSocketOptions {
+                so_reuseport: Some(true),
+                ..Default::default()
+            };
+            (server.configuration.threads, Some(sock_opt))
+        } else {
+            (1, None)
+        };
+
+        for _ in 0..sockets {
+            match (&listener.tls, &sock_opt) {
+                (Some(tls), _) => {
+                    let (cert, key) = tls.paths()?;
+                    let mut tls_settings = TlsSettings::intermediate(&cert, &key)?;
+                    if listener.enable_http2 {
+                        tls_settings.enable_h2();
+                    }
+                    public_svc.add_tls_with_settings(
+                        &listener.addr.to_string(),
+                        sock_opt.clone(),
+                        tls_settings,
+                    );
+                }
+                (None, Some(sock_opt)) => {
+                    public_svc.add_tcp_with_settings(&listener.addr.to_string(), sock_opt.clone());
+                }
+                (None, None) => {
+                    public_svc.add_tcp(&listener.addr.to_string());
                 }
-                public_svc.add_tls_with_settings(&listener.addr.to_string(), None, tls_settings);
-            }
-            None => {
-                public_svc.add_tcp(&listener.addr.to_string());
             }
         }
 
@@ -215,23 +290,33 @@ pub fn build_pingora_server(
         if let Some(redirect) = &listener.redirect {
             // Build and register the redirect Pingora HTTP proxy service with a standalone listener.
             let redirect_gateway =
-                RedirectGateway::new(redirect.destination.clone(), redirect.response_code);
+                RedirectGateway::new(state.clone(), redirect.https_port, redirect.response_code);
             let mut redirect_scv = http_proxy_service(&server.configuration, redirect_gateway);
             redirect_scv.add_tcp(&listener.addr);
             server.add_service(redirect_scv);
         }
     }
 
+    // Probe upstreams of services with active health checks in the background.
+    server.add_service(background_service(
+        "active health checks",
+        ActiveHealthChecker::new(traffic_manager.clone()),
+    ));
+
     // Build the admin HTTP proxy service from Pingora.
     for listener in config.listeners.iter().filter(|l| l.enable_admin) {
         if let Some(tls) = &listener.tls {
             let admin_gateway = AdminGateway::new(
+                ListenerInfo::from(listener),
+                state.clone(),
                 traffic_manager.clone(),
                 connection_manager.clone(),
                 reload.clone(),
+                shutdown.clone(),
             );
             let mut admin_svc = http_proxy_service(&server.configuration, admin_gateway);
-            let tls_settings = TlsSettings::intermediate(&tls.cert, &tls.key)?;
+            let (cert, key) = tls.paths()?;
+            let tls_settings = TlsSettings::intermediate(&cert, &key)?;
             admin_svc.add_tls_with_settings(&listener.addr, None, tls_settings);
             // Register admin service.
             server.add_service(admin_svc);
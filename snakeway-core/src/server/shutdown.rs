@@ -0,0 +1,249 @@
+use crate::runtime::RuntimeState;
+use arc_swap::ArcSwap;
+use async_trait::async_trait;
+use pingora::server::{RunArgs, ShutdownSignal, ShutdownSignalWatch};
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::signal::unix::{SignalKind, signal};
+use tokio::sync::watch;
+
+/// Phases of the shutdown sequence, in the order they run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ShutdownPhase {
+    /// Serving traffic and reporting ready.
+    Running,
+    /// Readiness reports not-ready, so load balancers stop sending new traffic.
+    /// Listeners keep accepting for `shutdown_readiness_grace_seconds`.
+    NotReady,
+    /// Listeners stop accepting new connections.
+    StopAccepting,
+    /// In-flight requests are given up to `shutdown_timeout_seconds` to finish.
+    Draining,
+    /// Device `shutdown` hooks run.
+    DeviceShutdown,
+    /// Buffered logs are flushed.
+    FlushLogs,
+    /// The sequence is done and the process may exit.
+    Complete,
+}
+
+impl fmt::Display for ShutdownPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let phase = match self {
+            ShutdownPhase::Running => "running",
+            ShutdownPhase::NotReady => "not_ready",
+            ShutdownPhase::StopAccepting => "stop_accepting",
+            ShutdownPhase::Draining => "draining",
+            ShutdownPhase::DeviceShutdown => "device_shutdown",
+            ShutdownPhase::FlushLogs => "flush_logs",
+            ShutdownPhase::Complete => "complete",
+        };
+        f.write_str(phase)
+    }
+}
+
+/// Orchestrates graceful shutdown.
+///
+/// On SIGTERM the sequence is:
+/// 1. flip readiness to not-ready, and keep serving for the readiness grace period
+/// 2. stop accepting (Pingora closes its listeners)
+/// 3. drain in-flight requests, bounded by the shutdown timeout
+/// 4. run device `shutdown` hooks
+/// 5. flush logs
+///
+/// Every phase transition emits a structured `shutdown phase` event.
+#[derive(Debug)]
+pub struct ShutdownCoordinator {
+    phase: watch::Sender<ShutdownPhase>,
+    in_flight: Arc<watch::Sender<usize>>,
+    timeout: Duration,
+    readiness_grace: Duration,
+}
+
+impl ShutdownCoordinator {
+    /// `readiness_grace` is how long listeners keep accepting once readiness flips,
+    /// so load balancers polling `/admin/ready` see it fail before connections are refused.
+    pub fn new(timeout: Duration, readiness_grace: Duration) -> Self {
+        let (phase, _) = watch::channel(ShutdownPhase::Running);
+        let (in_flight, _) = watch::channel(0);
+        Self {
+            phase,
+            in_flight: Arc::new(in_flight),
+            timeout,
+            readiness_grace,
+        }
+    }
+
+    pub fn phase(&self) -> ShutdownPhase {
+        *self.phase.borrow()
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.phase() == ShutdownPhase::Running
+    }
+
+    /// Whether shutdown has started and in-flight requests may still be finishing.
+    pub fn is_draining(&self) -> bool {
+        matches!(
+            self.phase(),
+            ShutdownPhase::NotReady | ShutdownPhase::StopAccepting | ShutdownPhase::Draining
+        )
+    }
+
+    pub fn in_flight(&self) -> usize {
+        *self.in_flight.borrow()
+    }
+
+    /// Track a request for draining. The request counts as in-flight until the guard drops.
+    pub fn track_request(&self) -> InFlightGuard {
+        self.in_flight.send_modify(|n| *n += 1);
+        InFlightGuard {
+            in_flight: self.in_flight.clone(),
+        }
+    }
+
+    /// Start the shutdown sequence by flipping readiness.
+    /// Calling this more than once is a no-op.
+    pub fn initiate(&self) {
+        let started = self.phase.send_if_modified(|phase| {
+            if *phase != ShutdownPhase::Running {
+                return false;
+            }
+            *phase = ShutdownPhase::NotReady;
+            true
+        });
+
+        if started {
+            self.emit(ShutdownPhase::NotReady);
+        }
+    }
+
+    /// Wait for SIGTERM, then start the shutdown sequence.
+    pub async fn listen_for_sigterm(&self) -> anyhow::Result<()> {
+        let mut term = signal(SignalKind::terminate())?;
+
+        if term.recv().await.is_some() {
+            tracing::info!("SIGTERM received");
+            self.initiate();
+        }
+        Ok(())
+    }
+
+    /// Pingora run arguments that stop accepting once readiness has been flipped
+    /// and the readiness grace period has passed.
+    pub fn run_args(self: &Arc<Self>) -> RunArgs {
+        RunArgs {
+            shutdown_signal: Box::new(PingoraShutdownSignal {
+                coordinator: self.clone(),
+            }),
+        }
+    }
+
+    /// Run the remaining phases once shutdown is initiated and listeners have stopped accepting.
+    /// Returns when the sequence is complete; exiting the process is up to the caller.
+    pub async fn run_sequence(&self, state: &ArcSwap<RuntimeState>) {
+        self.wait_for(ShutdownPhase::StopAccepting).await;
+
+        self.advance(ShutdownPhase::Draining);
+        if !self.drain().await {
+            tracing::warn!(
+                in_flight = self.in_flight(),
+                shutdown_timeout_seconds = self.timeout.as_secs(),
+                "shutdown timeout elapsed with requests still in flight"
+            );
+        }
+
+        self.advance(ShutdownPhase::DeviceShutdown);
+        for device in state.load().devices.all() {
+            device.shutdown();
+        }
+
+        // Nothing is logged after the flush, so the final phase is not emitted.
+        self.advance(ShutdownPhase::FlushLogs);
+        crate::logging::flush_logs();
+        self.phase.send_replace(ShutdownPhase::Complete);
+    }
+
+    /// Wait until the sequence has reached at least the given phase.
+    pub async fn wait_for(&self, phase: ShutdownPhase) {
+        let mut rx = self.phase.subscribe();
+        let _ = rx.wait_for(|current| *current >= phase).await;
+    }
+
+    /// Returns true if every in-flight request finished before the timeout.
+    async fn drain(&self) -> bool {
+        let mut rx = self.in_flight.subscribe();
+        tokio::time::timeout(self.timeout, rx.wait_for(|n| *n == 0))
+            .await
+            .is_ok()
+    }
+
+    fn advance(&self, phase: ShutdownPhase) {
+        self.phase.send_replace(phase);
+        self.emit(phase);
+    }
+
+    fn emit(&self, phase: ShutdownPhase) {
+        tracing::info!(
+            phase = %phase,
+            in_flight = self.in_flight(),
+            "shutdown phase"
+        );
+    }
+}
+
+/// RAII guard for a single in-flight request.
+///
+/// Invariants:
+/// - The in-flight count is incremented exactly once on creation
+/// - The in-flight count is decremented exactly once on Drop
+#[derive(Debug)]
+pub struct InFlightGuard {
+    in_flight: Arc<watch::Sender<usize>>,
+}
+
+impl Drop for InFlightGuard {
+    /// Release the in-flight slot when the request ends.
+    fn drop(&mut self) {
+        self.in_flight.send_modify(|n| *n -= 1);
+    }
+}
+
+/// Hands shutdown signals to Pingora.
+///
+/// SIGTERM is not handled here directly: readiness is flipped first, and only once the
+/// readiness grace period has passed does Pingora receive a graceful termination and stop
+/// accepting connections. The admin API keeps answering until then, reporting `draining`.
+struct PingoraShutdownSignal {
+    coordinator: Arc<ShutdownCoordinator>,
+}
+
+#[async_trait]
+impl ShutdownSignalWatch for PingoraShutdownSignal {
+    async fn recv(&self) -> ShutdownSignal {
+        let mut interrupt =
+            signal(SignalKind::interrupt()).expect("failed to install SIGINT handler");
+        let mut quit = signal(SignalKind::quit()).expect("failed to install SIGQUIT handler");
+
+        let not_ready = async {
+            self.coordinator.wait_for(ShutdownPhase::NotReady).await;
+            tokio::time::sleep(self.coordinator.readiness_grace).await;
+        };
+
+        tokio::select! {
+            _ = not_ready => {
+                self.coordinator.advance(ShutdownPhase::StopAccepting);
+                ShutdownSignal::GracefulTerminate
+            }
+            _ = interrupt.recv() => {
+                tracing::info!("SIGINT received, shutting down immediately");
+                ShutdownSignal::FastShutdown
+            }
+            _ = quit.recv() => {
+                tracing::info!("SIGQUIT received, starting graceful upgrade");
+                ShutdownSignal::GracefulUpgrade
+            }
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use crate::conf::types::{AccessLogFormat, UnknownAccessLogField};
+use crate::server::AccessLogEntry;
+use pretty_assertions::assert_eq;
+use std::net::Ipv4Addr;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+fn entry() -> AccessLogEntry<'static> {
+    AccessLogEntry {
+        request_id: Some("req-1"),
+        method: Some("GET"),
+        path: Some("/api/users"),
+        status: Some(200),
+        latency_ms: Some(12),
+        service: Some("api"),
+        upstream: Some("10.0.0.5:8080".to_string()),
+        client_ip: Some(Ipv4Addr::new(203, 0, 113, 7).into()),
+        ua_device_type: Some("mobile"),
+        bytes_in: Some(0),
+        bytes_out: Some(512),
+        device: None,
+        error: None,
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn custom_template_renders_its_fields() {
+    // Arrange
+    let format = AccessLogFormat::parse(
+        "$remote_addr \"$method $path\" $status $request_time $ua_device_type $upstream $device",
+    )
+    .unwrap();
+
+    // Act
+    let line = entry().render(&format);
+
+    // Assert
+    assert_eq!(
+        line,
+        "203.0.113.7 \"GET /api/users\" 200 12 mobile 10.0.0.5:8080 -"
+    );
+}
+
+#[test]
+fn custom_template_keeps_only_its_fields() {
+    // Arrange
+    let format = AccessLogFormat::parse("$method $status").unwrap();
+    let mut entry = entry();
+
+    // Act
+    entry.retain(&format);
+
+    // Assert
+    assert_eq!(entry.method, Some("GET"));
+    assert_eq!(entry.status, Some(200));
+    assert_eq!(entry.request_id, None);
+    assert_eq!(entry.path, None);
+    assert_eq!(entry.client_ip, None);
+    assert_eq!(entry.upstream, None);
+}
+
+#[test]
+fn dollar_without_a_name_is_literal() {
+    // Arrange
+    let format = AccessLogFormat::parse("$ $status$").unwrap();
+
+    // Act
+    let line = entry().render(&format);
+
+    // Assert
+    assert_eq!(line, "$ 200$");
+}
+
+#[test]
+fn unknown_field_fails_to_parse() {
+    // Act
+    let result = AccessLogFormat::parse("$status $http_referer");
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        UnknownAccessLogField("http_referer".to_string())
+    );
+}
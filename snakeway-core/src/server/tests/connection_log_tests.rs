@@ -0,0 +1,58 @@
+use crate::server::{ConnectionLogSampler, should_log};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn sample_rate_is_honored() {
+    // Arrange
+    let sampler = ConnectionLogSampler::new(0.25);
+    let trials = 20_000;
+
+    // Act
+    let sampled = (0..trials).filter(|_| sampler.sample()).count();
+
+    // Assert
+    let ratio = sampled as f64 / trials as f64;
+    assert!((ratio - 0.25).abs() < 0.02, "sampled ratio was {ratio}");
+}
+
+#[test]
+fn rolls_below_the_rate_are_sampled() {
+    // Arrange
+    let sampler = ConnectionLogSampler::new(0.01);
+
+    // Act
+    let rolls = [0.0, 0.009, 0.01, 0.5].map(|roll| sampler.sample_with(roll));
+
+    // Assert
+    assert_eq!(rolls, [true, true, false, false]);
+}
+
+#[test]
+fn zero_rate_samples_nothing_and_full_rate_samples_everything() {
+    // Arrange
+    let none = ConnectionLogSampler::new(0.0);
+    let all = ConnectionLogSampler::default();
+
+    // Act
+    let none_sampled = (0..1_000).filter(|_| none.sample()).count();
+    let all_sampled = (0..1_000).filter(|_| all.sample()).count();
+
+    // Assert
+    assert_eq!(none_sampled, 0);
+    assert_eq!(all_sampled, 1_000);
+}
+
+#[test]
+fn failure_events_bypass_sampling() {
+    // Arrange
+    let sampler = ConnectionLogSampler::new(0.0);
+    let sampled = sampler.sample();
+
+    // Act
+    let failure = should_log(sampled, true);
+    let lifecycle = should_log(sampled, false);
+
+    // Assert
+    assert!(failure);
+    assert!(!lifecycle);
+}
@@ -0,0 +1,4 @@
+mod access_log_tests;
+mod connection_log_tests;
+mod shutdown_tests;
+mod tls_handshake_tests;
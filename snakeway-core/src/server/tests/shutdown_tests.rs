@@ -0,0 +1,84 @@
+use crate::server::{ShutdownCoordinator, ShutdownPhase};
+use pingora::server::ShutdownSignal;
+use pretty_assertions::assert_eq;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[test]
+fn coordinator_starts_ready() {
+    // Arrange
+    let coordinator = ShutdownCoordinator::new(Duration::from_secs(1), Duration::ZERO);
+
+    // Act
+    let ready = coordinator.is_ready();
+
+    // Assert
+    assert!(ready);
+    assert_eq!(coordinator.phase(), ShutdownPhase::Running);
+}
+
+#[test]
+fn initiate_flips_readiness_once() {
+    // Arrange
+    let coordinator = ShutdownCoordinator::new(Duration::from_secs(1), Duration::ZERO);
+
+    // Act
+    coordinator.initiate();
+    coordinator.initiate();
+
+    // Assert
+    assert!(!coordinator.is_ready());
+    assert_eq!(coordinator.phase(), ShutdownPhase::NotReady);
+}
+
+#[test]
+fn coordinator_is_draining_once_shutdown_starts() {
+    // Arrange
+    let coordinator = ShutdownCoordinator::new(Duration::from_secs(1), Duration::ZERO);
+    let before = coordinator.is_draining();
+
+    // Act
+    coordinator.initiate();
+
+    // Assert
+    assert!(!before);
+    assert!(coordinator.is_draining());
+}
+
+#[tokio::test]
+async fn listeners_keep_accepting_for_the_readiness_grace() {
+    // Arrange
+    let grace = Duration::from_millis(200);
+    let coordinator = Arc::new(ShutdownCoordinator::new(Duration::from_secs(1), grace));
+    let signal = coordinator.run_args().shutdown_signal;
+    let started = Instant::now();
+
+    // Act
+    coordinator.initiate();
+    let (received, phase_during_grace) = tokio::join!(signal.recv(), async {
+        tokio::time::sleep(grace / 2).await;
+        coordinator.phase()
+    });
+
+    // Assert
+    assert_eq!(phase_during_grace, ShutdownPhase::NotReady);
+    assert!(matches!(received, ShutdownSignal::GracefulTerminate));
+    assert!(started.elapsed() >= grace);
+    assert_eq!(coordinator.phase(), ShutdownPhase::StopAccepting);
+}
+
+#[test]
+fn in_flight_guard_releases_on_drop() {
+    // Arrange
+    let coordinator = ShutdownCoordinator::new(Duration::from_secs(1), Duration::ZERO);
+
+    // Act
+    let first = coordinator.track_request();
+    let second = coordinator.track_request();
+    drop(first);
+
+    // Assert
+    assert_eq!(coordinator.in_flight(), 1);
+    drop(second);
+    assert_eq!(coordinator.in_flight(), 0);
+}
@@ -0,0 +1,44 @@
+use crate::server::classify_handshake_error;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn plain_http_on_tls_listener_is_not_tls() {
+    // Act
+    let reason = classify_handshake_error(
+        "TLSHandshakeFailure context: TLS accept() failed: received corrupt message of type InvalidContentType",
+    );
+
+    // Assert
+    assert_eq!(reason, "not_tls");
+}
+
+#[test]
+fn expired_client_certificate_is_classified() {
+    // Act
+    let reason = classify_handshake_error(
+        "TLSHandshakeFailure context: TLS accept() failed: invalid peer certificate: Expired",
+    );
+
+    // Assert
+    assert_eq!(reason, "certificate_expired");
+}
+
+#[test]
+fn protocol_mismatch_is_classified() {
+    // Act
+    let reason = classify_handshake_error(
+        "TLSHandshakeFailure context: TLS accept() failed: peer is incompatible: SupportedVersionsExtensionRequired",
+    );
+
+    // Assert
+    assert_eq!(reason, "protocol_mismatch");
+}
+
+#[test]
+fn unknown_error_is_other() {
+    // Act
+    let reason = classify_handshake_error("TLSHandshakeFailure context: something new");
+
+    // Assert
+    assert_eq!(reason, "other");
+}
@@ -0,0 +1,96 @@
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::Layer;
+use tracing_subscriber::layer::Context;
+
+/// Event name of a failed downstream TLS handshake.
+pub const TLS_HANDSHAKE_FAILED: &str = "tls_handshake_failed";
+
+/// Message Pingora logs when accepting a downstream connection fails.
+const PINGORA_HANDSHAKE_ERROR: &str = "Downstream handshake error";
+
+/// Reports failed downstream TLS handshakes as structured `tls_handshake_failed` events.
+///
+/// Pingora performs the handshake before any proxy hook runs and only logs the failure,
+/// through the `log` crate that tracing-subscriber forwards. This layer picks up those
+/// records and re-emits them with a classified `reason` and the raw `error`.
+/// Pingora does not pass the peer address or SNI along with the error.
+pub struct TlsHandshakeFailureLayer;
+
+impl<S: Subscriber> Layer<S> for TlsHandshakeFailureLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+
+        let Some((_, error)) = visitor.message.split_once(PINGORA_HANDSHAKE_ERROR) else {
+            return;
+        };
+        let error = error.trim();
+
+        tracing::warn!(
+            event = TLS_HANDSHAKE_FAILED,
+            reason = classify_handshake_error(error),
+            error,
+            "tls handshake failed"
+        );
+    }
+}
+
+/// Map a handshake error to a short, stable reason.
+///
+/// Matching is done on rustls and I/O error text, e.g.
+/// `TLS accept() failed: received corrupt message of type InvalidContentType`.
+pub fn classify_handshake_error(error: &str) -> &'static str {
+    let error = error.to_ascii_lowercase();
+    let has = |needles: &[&str]| needles.iter().any(|n| error.contains(n));
+
+    if has(&["invalidcontenttype", "corrupt message"]) {
+        "not_tls"
+    } else if has(&["expired"]) {
+        "certificate_expired"
+    } else if has(&["no certificates"]) {
+        "client_certificate_missing"
+    } else if has(&[
+        "invalid peer certificate",
+        "badcertificate",
+        "unknownca",
+        "certificateunknown",
+    ]) {
+        "certificate_rejected"
+    } else if has(&["unrecognisedname", "unrecognizedname"]) {
+        "unrecognized_sni"
+    } else if has(&["known protocol", "noapplicationprotocol"]) {
+        "alpn_mismatch"
+    } else if has(&[
+        "peer is incompatible",
+        "protocolversion",
+        "handshakefailure",
+    ]) {
+        "protocol_mismatch"
+    } else if has(&["timed out", "timeout"]) {
+        "timeout"
+    } else if has(&["end of file", "eof", "connection reset", "broken pipe"]) {
+        "connection_closed"
+    } else {
+        "other"
+    }
+}
+
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{value:?}");
+        }
+    }
+}
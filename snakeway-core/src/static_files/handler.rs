@@ -18,16 +18,18 @@ pub async fn handle_static_request(
         static_config,
         cache_policy,
         max_file_size,
+        spa_fallback,
         ..
     } = route
     else {
         unreachable!("handle_static_request called with non-static route");
     };
 
-    let resolved = match resolve_static_path(file_dir, path, request_path, *index) {
-        Ok(p) => p,
-        Err(e) => return error_response(map_resolve_error(e)),
-    };
+    let resolved =
+        match resolve_static_path(file_dir, path, request_path, *index, spa_fallback.as_ref()) {
+            Ok(p) => p,
+            Err(e) => return error_response(map_resolve_error(e)),
+        };
 
     match resolved {
         ResolvedStatic::File(path) => render_file(
@@ -1,7 +1,9 @@
 pub mod handler;
-mod render;
+pub(crate) mod render;
 mod resolve;
 mod response;
+#[cfg(test)]
+mod tests;
 
 pub use handler::handle_static_request;
-pub use response::{ConditionalHeaders, ServeError, StaticBody, StaticResponse};
+pub use response::{ConditionalHeaders, FilePart, ServeError, StaticBody, StaticResponse};
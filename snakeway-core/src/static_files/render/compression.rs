@@ -1,7 +1,10 @@
 use crate::conf::types::CompressionOptions;
 use flate2::Compression;
 use flate2::write::GzEncoder;
+use std::fs::Metadata;
 use std::io::Write;
+use std::path::{Path, PathBuf};
+use tokio::fs;
 
 pub enum CompressionEncoding {
     Gzip,
@@ -17,6 +20,22 @@ impl CompressionEncoding {
             CompressionEncoding::Unknown => "unknown encoding",
         }
     }
+
+    /// Extension of a precompressed variant of a file in this encoding, e.g. `br` for `app.js.br`.
+    pub fn file_extension(&self) -> Option<&'static str> {
+        match self {
+            CompressionEncoding::Gzip => Some("gz"),
+            CompressionEncoding::Brotli => Some("br"),
+            CompressionEncoding::Unknown => None,
+        }
+    }
+}
+
+/// A precompressed variant of a file, served in its place.
+pub(crate) struct PrecompressedFile {
+    pub encoding: &'static CompressionEncoding,
+    pub path: PathBuf,
+    pub metadata: Metadata,
 }
 
 pub(crate) fn apply_compression(encoding: &CompressionEncoding, data: &[u8]) -> (Vec<u8>, bool) {
@@ -70,6 +89,25 @@ pub(crate) fn is_compressible_mime(mime: &mime_guess::Mime) -> bool {
     false
 }
 
+/// Check if a content type matches one of the `skip_content_types` patterns.
+/// Patterns are `type/subtype` or `type/*`; parameters such as `charset` are ignored.
+pub(crate) fn is_skipped_content_type(content_type: &str, patterns: &[String]) -> bool {
+    let essence = content_type.split(';').next().unwrap_or("").trim();
+    let Some((type_, subtype)) = essence.split_once('/') else {
+        return false;
+    };
+
+    patterns
+        .iter()
+        .any(|pattern| match pattern.split_once('/') {
+            Some((p_type, "*")) => p_type.eq_ignore_ascii_case(type_),
+            Some((p_type, p_subtype)) => {
+                p_type.eq_ignore_ascii_case(type_) && p_subtype.eq_ignore_ascii_case(subtype)
+            }
+            None => false,
+        })
+}
+
 /// Parse quality value from Accept-Encoding part (e.g., "gzip;q=0.5" -> 0.5)
 fn parse_quality(part: &str) -> f32 {
     part.split(';')
@@ -118,6 +156,64 @@ pub(crate) fn preferred_encoding(accept_encoding: &str) -> Option<&'static Compr
     }
 }
 
+/// Encodings the client accepts and the route enables, the client's preferred one first.
+fn enabled_encodings(
+    accept_encoding: &str,
+    cfg: &CompressionOptions,
+) -> Vec<&'static CompressionEncoding> {
+    let mut encodings = Vec::with_capacity(2);
+
+    if cfg.enable_brotli && accepts_encoding(accept_encoding, CompressionEncoding::Brotli).is_some()
+    {
+        encodings.push(&CompressionEncoding::Brotli);
+    }
+    if cfg.enable_gzip && accepts_encoding(accept_encoding, CompressionEncoding::Gzip).is_some() {
+        encodings.push(&CompressionEncoding::Gzip);
+    }
+
+    if matches!(
+        preferred_encoding(accept_encoding),
+        Some(CompressionEncoding::Gzip)
+    ) {
+        encodings.reverse();
+    }
+
+    encodings
+}
+
+/// Find a precompressed variant of the file at `path` the client accepts, e.g. `app.js.br` or
+/// else `app.js.gz` for `app.js`. Variants larger than `max_file_size` are ignored.
+pub(crate) async fn find_precompressed(
+    path: &Path,
+    accept_encoding: &str,
+    max_file_size: u64,
+    cfg: &CompressionOptions,
+) -> Option<PrecompressedFile> {
+    for encoding in enabled_encodings(accept_encoding, cfg) {
+        let Some(extension) = encoding.file_extension() else {
+            continue;
+        };
+
+        let mut variant = path.as_os_str().to_owned();
+        variant.push(".");
+        variant.push(extension);
+        let variant = PathBuf::from(variant);
+
+        if let Ok(metadata) = fs::metadata(&variant).await
+            && metadata.is_file()
+            && metadata.len() <= max_file_size
+        {
+            return Some(PrecompressedFile {
+                encoding,
+                path: variant,
+                metadata,
+            });
+        }
+    }
+
+    None
+}
+
 /// Compress data using gzip
 pub(crate) fn gzip_compress(data: &[u8]) -> std::io::Result<Vec<u8>> {
     let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
@@ -146,7 +242,9 @@ pub(crate) fn response_varies_by_encoding(
     size: u64,
     cfg: &CompressionOptions,
 ) -> bool {
-    if !is_compressible_mime(mime) {
+    if !is_compressible_mime(mime)
+        || is_skipped_content_type(mime.as_ref(), &cfg.skip_content_types)
+    {
         return false;
     }
 
@@ -1,6 +1,6 @@
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
-use httpdate::parse_http_date;
+use httpdate::{fmt_http_date, parse_http_date};
 
 /// Generate an ETag from file size and modification time.
 /// Format: "size-mtime_secs" (weak ETag using W/ prefix)
@@ -33,8 +33,24 @@ pub(crate) fn etag_matches(etag: &str, if_none_match: &str) -> bool {
     false
 }
 
+/// Format the `Last-Modified` header from the file's modification time.
+///
+/// A modification time in the future (e.g. clock skew) is replaced with `now`, so the
+/// header never claims a date later than the response.
+pub(crate) fn last_modified(file_modified: Option<SystemTime>, now: SystemTime) -> Option<String> {
+    file_modified.map(|t| fmt_http_date(t.min(now)))
+}
+
 /// Check if the file has been modified since the given date.
-pub(crate) fn modified_since(file_modified: Option<SystemTime>, if_modified_since: &str) -> bool {
+///
+/// HTTP dates have 1-second resolution, so both times are compared in whole seconds, whatever
+/// the time zone the header was written in. A file modified within the last second can change
+/// again without its `Last-Modified` changing, so it always counts as modified.
+pub(crate) fn modified_since(
+    file_modified: Option<SystemTime>,
+    if_modified_since: &str,
+    now: SystemTime,
+) -> bool {
     let file_time = match file_modified {
         Some(t) => t,
         None => return true, // Unknown mtime, assume modified
@@ -45,11 +61,19 @@ pub(crate) fn modified_since(file_modified: Option<SystemTime>, if_modified_sinc
         Err(_) => return true, // Invalid header, assume modified
     };
 
-    // A simple comparison like file_time > since_time does not work,
-    // because HTTP dates have 1-second resolution.
-    // Treat sub-second differences as NOT modified.
-    match file_time.duration_since(since_time) {
-        Ok(delta) => delta.as_secs() >= 1,
-        Err(_) => false, // file_time <= since_time, not modified
+    // Modified within the last second, or in the future.
+    let settled = now
+        .duration_since(file_time)
+        .is_ok_and(|age| age >= Duration::from_secs(1));
+    if !settled {
+        return true;
     }
+
+    unix_secs(file_time) > unix_secs(since_time)
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
 }
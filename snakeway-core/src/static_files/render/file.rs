@@ -1,18 +1,20 @@
 use std::path::PathBuf;
+use std::time::SystemTime;
 
 use crate::static_files::render::compression::{
-    CompressionEncoding, apply_compression, is_compressible_mime, preferred_encoding,
-    response_varies_by_encoding,
+    CompressionEncoding, apply_compression, find_precompressed, is_compressible_mime,
+    is_skipped_content_type, preferred_encoding, response_varies_by_encoding,
+};
+use crate::static_files::render::etag::{
+    etag_matches, generate_etag, last_modified, modified_since,
 };
-use crate::static_files::render::etag::{etag_matches, generate_etag, modified_since};
 
 use crate::conf::types::{CachePolicy, CompressionOptions};
 use crate::static_files::render::headers::HeaderBuilder;
-use crate::static_files::render::range::parse_range_header;
+use crate::static_files::render::range::{MultipartByteranges, RangeRequest, parse_range_header};
 use crate::static_files::{ConditionalHeaders, ServeError, StaticBody, StaticResponse};
 use bytes::Bytes;
 use http::StatusCode;
-use httpdate::fmt_http_date;
 use tokio::fs;
 use tokio::io::{AsyncReadExt, AsyncSeekExt};
 
@@ -37,6 +39,27 @@ pub async fn render_file(
         return Err(ServeError::Forbidden);
     }
 
+    // Guess MIME type to set the Content-Type header.
+    let mime = mime_guess::from_path(&path).first_or_octet_stream();
+
+    // Whether the response varies by Accept-Encoding, judged by the original file.
+    let varies_by_encoding = response_varies_by_encoding(&mime, metadata.len(), compression_opts);
+
+    // Serve a precompressed variant (e.g. `app.js.br`) in place of the file, when there is one
+    // the client accepts. Everything below, including the ETag, describes the variant.
+    let precompressed = match conditional.accept_encoding.as_deref() {
+        Some(ae)
+            if !is_skipped_content_type(mime.as_ref(), &compression_opts.skip_content_types) =>
+        {
+            find_precompressed(&path, ae, *max_file_size, compression_opts).await
+        }
+        _ => None,
+    };
+    let (path, metadata) = match &precompressed {
+        Some(variant) => (variant.path.clone(), variant.metadata.clone()),
+        None => (path, metadata),
+    };
+
     // Get modification time for ETag and Last-Modified
     let modified = metadata.modified().ok();
 
@@ -44,23 +67,25 @@ pub async fn render_file(
     let etag = generate_etag(metadata.len(), modified);
 
     // Format Last-Modified header
-    let last_modified = modified.map(fmt_http_date);
+    let now = SystemTime::now();
+    let last_modified = last_modified(modified, now);
 
-    // Check conditional headers for 304 Not Modified response
+    // Check conditional headers for 304 Not Modified response.
+    // If-None-Match takes precedence: If-Modified-Since is ignored when both are sent (RFC 9110).
     let not_modified = match (
         conditional.if_none_match.as_deref(),
         conditional.if_modified_since.as_deref(),
     ) {
         (Some(inm), _) => etag_matches(&etag, inm),
-        (None, Some(ims)) => !modified_since(modified, ims),
+        (None, Some(ims)) => !modified_since(modified, ims, now),
         _ => false,
     };
 
-    // Guess MIME type to set the Content-Type header.
-    let mime = mime_guess::from_path(&path).first_or_octet_stream();
-
     // Determine the preferred compression encoding (brotli > gzip)
-    let preferred_enc = if is_compressible_mime(&mime) {
+    let preferred_enc = if precompressed.is_none()
+        && is_compressible_mime(&mime)
+        && !is_skipped_content_type(mime.as_ref(), &compression_opts.skip_content_types)
+    {
         conditional.accept_encoding.as_ref().and_then(|ae| {
             let size = metadata.len();
 
@@ -95,7 +120,7 @@ pub async fn render_file(
     }
 
     // Add Vary header to indicate response varies based on Accept-Encoding
-    if response_varies_by_encoding(&mime, metadata.len(), compression_opts) {
+    if varies_by_encoding || precompressed.is_some() {
         headers.vary()
     }
 
@@ -112,16 +137,41 @@ pub async fn render_file(
         });
     }
 
-    // compute the range header
-    let mut range = conditional
-        .range
-        .as_deref()
-        .and_then(|r| parse_range_header(r, metadata.len()));
+    if let Some(variant) = &precompressed {
+        headers.content_encoding(variant.encoding.as_str());
+    }
 
-    if preferred_enc.is_some() {
-        range = None;
+    // Compute the range header. Ranges only apply to the file as stored, not to a compressed
+    // encoding of it.
+    let range = match conditional.range.as_deref() {
+        Some(r) if preferred_enc.is_none() && precompressed.is_none() => {
+            parse_range_header(r, metadata.len())
+        }
+        _ => None,
+    };
+
+    // None of the requested ranges overlap the file.
+    if range == Some(RangeRequest::Unsatisfiable) {
+        headers.content_range_unsatisfied(metadata.len());
+        headers.content_length("0");
+        return Ok(StaticResponse {
+            status: StatusCode::RANGE_NOT_SATISFIABLE,
+            headers: headers.build(),
+            body: StaticBody::Empty,
+        });
     }
 
+    // Several ranges are sent as the parts of a multipart/byteranges body.
+    let multipart = match &range {
+        Some(RangeRequest::Multiple(ranges)) => {
+            let multipart = MultipartByteranges::new(ranges, mime.as_ref(), metadata.len());
+            headers.content_type(&multipart.content_type());
+            headers.content_length(&multipart.content_length().to_string());
+            Some(multipart)
+        }
+        _ => None,
+    };
+
     // Grab a file handle.
     let mut file = fs::File::open(&path)
         .await
@@ -158,7 +208,15 @@ pub async fn render_file(
         }
 
         // Apply range header, if the content is not compressed and the header exists.
-        if let Some(range) = range {
+        if let Some(multipart) = multipart {
+            return Ok(StaticResponse {
+                status: StatusCode::PARTIAL_CONTENT,
+                headers: headers.build(),
+                body: StaticBody::Bytes(multipart.render(&buf)),
+            });
+        }
+
+        if let Some(RangeRequest::Single(range)) = range {
             let slice = &buf[range.start as usize..=range.end as usize];
 
             headers.content_range(range, metadata.len());
@@ -184,7 +242,15 @@ pub async fn render_file(
     // Streaming compression is possible, but would require async-compression (or spawn_blocking),
     // would likely use chunked transfer (no Content-Length),
     // and is incompatible with byte-range responses unless serving precompressed variants.
-    if let Some(range) = range {
+    if let Some(multipart) = multipart {
+        return Ok(StaticResponse {
+            status: StatusCode::PARTIAL_CONTENT,
+            headers: headers.build(),
+            body: multipart.into_file_body(file),
+        });
+    }
+
+    if let Some(RangeRequest::Single(range)) = range {
         file.seek(std::io::SeekFrom::Start(range.start))
             .await
             .map_err(|_| ServeError::Io)?;
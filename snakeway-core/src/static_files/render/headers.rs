@@ -46,6 +46,11 @@ impl HeaderBuilder {
         );
     }
 
+    /// `Content-Range` of a `416` response: the length of the file, and no range.
+    pub(crate) fn content_range_unsatisfied(&mut self, len: u64) {
+        self.insert(header::CONTENT_RANGE, &format!("bytes */{len}"));
+    }
+
     pub(crate) fn content_encoding(&mut self, value: &str) {
         self.insert(header::CONTENT_ENCODING, value);
     }
@@ -4,6 +4,8 @@ pub(crate) mod etag;
 mod file;
 mod headers;
 mod range;
+#[cfg(test)]
+mod tests;
 
 pub use directory::render_directory;
 pub use file::render_file;
@@ -1,29 +1,198 @@
-#[derive(Debug)]
+use crate::static_files::{FilePart, StaticBody};
+use bytes::Bytes;
+use tokio::fs;
+
+/// Ranges beyond this many in one header are ignored, and the whole file is served.
+const MAX_RANGES: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ByteRange {
     pub start: u64,
     pub end: u64, // inclusive
 }
 
-pub(crate) fn parse_range_header(header: &str, size: u64) -> Option<ByteRange> {
-    let header = header.trim();
+impl ByteRange {
+    pub(crate) fn len(&self) -> u64 {
+        self.end - self.start + 1
+    }
+}
+
+/// What a `Range` header asks for, against a file of a given size.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum RangeRequest {
+    /// One range, answered with a single-part `206`.
+    Single(ByteRange),
+
+    /// Several disjoint ranges, sorted, answered with a `multipart/byteranges` `206`.
+    Multiple(Vec<ByteRange>),
+
+    /// None of the ranges overlap the file, answered with `416`.
+    Unsatisfiable,
+}
+
+/// Parse a `Range` header, e.g. `bytes=0-99, 200-299, -500`.
+///
+/// Ranges are clamped to the file, and overlapping or adjacent ones are coalesced. Headers
+/// that are malformed, use another unit, or ask for too many ranges return `None`, so the
+/// whole file is served.
+pub(crate) fn parse_range_header(header: &str, size: u64) -> Option<RangeRequest> {
+    let specs = header.trim().strip_prefix("bytes=")?;
+
+    let mut ranges = Vec::new();
+    let mut count = 0;
+    for spec in specs.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        count += 1;
+        if count > MAX_RANGES {
+            return None;
+        }
 
-    if !header.starts_with("bytes=") {
+        if let Some(range) = parse_range_spec(spec, size)? {
+            ranges.push(range);
+        }
+    }
+
+    if count == 0 {
         return None;
     }
 
-    let range = &header[6..];
-    let mut parts = range.split('-');
+    let mut ranges = coalesce(ranges);
+    match ranges.len() {
+        0 => Some(RangeRequest::Unsatisfiable),
+        1 => ranges.pop().map(RangeRequest::Single),
+        _ => Some(RangeRequest::Multiple(ranges)),
+    }
+}
+
+/// Parse one range of a `Range` header.
+///
+/// Returns `None` when the range is malformed, and `Some(None)` when it is well-formed but
+/// does not overlap the file.
+fn parse_range_spec(spec: &str, size: u64) -> Option<Option<ByteRange>> {
+    let (start, end) = spec.split_once('-')?;
+    let (start, end) = (start.trim(), end.trim());
 
-    let start = parts.next()?.parse::<u64>().ok()?;
-    let end = match parts.next() {
-        Some("") => size.saturating_sub(1),
-        Some(v) => v.parse::<u64>().ok()?,
-        None => return None,
+    // Suffix range: the last `n` bytes.
+    if start.is_empty() {
+        let suffix = end.parse::<u64>().ok()?;
+        if suffix == 0 || size == 0 {
+            return Some(None);
+        }
+        return Some(Some(ByteRange {
+            start: size.saturating_sub(suffix),
+            end: size - 1,
+        }));
+    }
+
+    let start = start.parse::<u64>().ok()?;
+    let end = match end {
+        "" => None,
+        v => Some(v.parse::<u64>().ok()?),
     };
 
-    if start > end || end >= size {
+    if end.is_some_and(|end| end < start) {
         return None;
     }
 
-    Some(ByteRange { start, end })
+    if start >= size {
+        return Some(None);
+    }
+
+    Some(Some(ByteRange {
+        start,
+        end: end.map_or(size - 1, |end| end.min(size - 1)),
+    }))
+}
+
+/// Sort ranges and merge the ones that overlap or touch.
+fn coalesce(mut ranges: Vec<ByteRange>) -> Vec<ByteRange> {
+    ranges.sort_by_key(|r| r.start);
+
+    let mut merged: Vec<ByteRange> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end.saturating_add(1) => {
+                last.end = last.end.max(range.end);
+            }
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// Layout of a `multipart/byteranges` body: each range of the file, preceded by its part
+/// headers, then a closing boundary.
+#[derive(Debug)]
+pub(crate) struct MultipartByteranges {
+    pub boundary: String,
+    pub parts: Vec<(Bytes, ByteRange)>,
+    pub closing: Bytes,
+}
+
+impl MultipartByteranges {
+    pub(crate) fn new(ranges: &[ByteRange], content_type: &str, size: u64) -> Self {
+        let boundary = uuid::Uuid::new_v4().simple().to_string();
+
+        let parts = ranges
+            .iter()
+            .map(|range| {
+                let head = format!(
+                    "\r\n--{boundary}\r\nContent-Type: {content_type}\r\n\
+                     Content-Range: bytes {}-{}/{size}\r\n\r\n",
+                    range.start, range.end
+                );
+                (Bytes::from(head), *range)
+            })
+            .collect();
+
+        let closing = Bytes::from(format!("\r\n--{boundary}--\r\n"));
+
+        Self {
+            boundary,
+            parts,
+            closing,
+        }
+    }
+
+    /// Value of the response's `Content-Type` header.
+    pub(crate) fn content_type(&self) -> String {
+        format!("multipart/byteranges; boundary={}", self.boundary)
+    }
+
+    /// Length of the whole body, in bytes.
+    pub(crate) fn content_length(&self) -> u64 {
+        let parts: u64 = self
+            .parts
+            .iter()
+            .map(|(head, range)| head.len() as u64 + range.len())
+            .sum();
+        parts + self.closing.len() as u64
+    }
+
+    /// Render the body from the file's contents, already in memory.
+    pub(crate) fn render(&self, contents: &[u8]) -> Bytes {
+        let mut body = Vec::with_capacity(self.content_length() as usize);
+        for (head, range) in &self.parts {
+            body.extend_from_slice(head);
+            body.extend_from_slice(&contents[range.start as usize..=range.end as usize]);
+        }
+        body.extend_from_slice(&self.closing);
+        Bytes::from(body)
+    }
+
+    /// The body, streamed from the file.
+    pub(crate) fn into_file_body(self, file: fs::File) -> StaticBody {
+        StaticBody::MultipartFile {
+            file,
+            parts: self
+                .parts
+                .into_iter()
+                .map(|(head, range)| FilePart {
+                    head,
+                    start: range.start,
+                    len: range.len(),
+                })
+                .collect(),
+            closing: self.closing,
+        }
+    }
 }
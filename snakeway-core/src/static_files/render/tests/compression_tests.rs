@@ -0,0 +1,75 @@
+use crate::conf::types::{CompressionOptions, CompressionOptsSpec};
+use crate::static_files::render::compression::{
+    is_skipped_content_type, response_varies_by_encoding,
+};
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+fn default_opts() -> CompressionOptions {
+    CompressionOptsSpec::default().into()
+}
+
+fn patterns(list: &[&str]) -> Vec<String> {
+    list.iter().map(|p| p.to_string()).collect()
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn wildcard_pattern_matches_any_subtype() {
+    // Arrange
+    let skip = patterns(&["image/*"]);
+
+    // Act
+    let png = is_skipped_content_type("image/png", &skip);
+    let svg = is_skipped_content_type("image/svg+xml", &skip);
+    let text = is_skipped_content_type("text/plain", &skip);
+
+    // Assert
+    assert!(png);
+    assert!(svg);
+    assert!(!text);
+}
+
+#[test]
+fn exact_pattern_ignores_parameters_and_case() {
+    // Arrange
+    let skip = patterns(&["application/zip"]);
+
+    // Act
+    let zip = is_skipped_content_type("Application/ZIP; name=archive", &skip);
+    let json = is_skipped_content_type("application/json", &skip);
+
+    // Assert
+    assert!(zip);
+    assert!(!json);
+}
+
+#[test]
+fn denylisted_type_does_not_vary_by_encoding() {
+    // Arrange
+    let mut opts = default_opts();
+    opts.skip_content_types = patterns(&["text/css"]);
+
+    // Act
+    let css = response_varies_by_encoding(&mime_guess::mime::TEXT_CSS, 64 * 1024, &opts);
+    let html = response_varies_by_encoding(&mime_guess::mime::TEXT_HTML, 64 * 1024, &opts);
+
+    // Assert
+    assert!(!css);
+    assert!(html);
+}
+
+#[test]
+fn tiny_response_does_not_vary_by_encoding() {
+    // Arrange
+    let opts = default_opts();
+
+    // Act
+    let varies = response_varies_by_encoding(&mime_guess::mime::TEXT_HTML, 40, &opts);
+
+    // Assert
+    assert!(!varies);
+}
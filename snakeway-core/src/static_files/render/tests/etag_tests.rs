@@ -0,0 +1,90 @@
+use crate::static_files::render::etag::{last_modified, modified_since};
+use pretty_assertions::assert_eq;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+/// Sun, 06 Nov 1994 08:49:37 GMT
+fn at(secs: u64, millis: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(784_111_777 + secs) + Duration::from_millis(millis)
+}
+
+const LAST_MODIFIED: &str = "Sun, 06 Nov 1994 08:49:37 GMT";
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn sub_second_mtime_is_not_modified_since_its_own_last_modified() {
+    // Arrange
+    let modified = Some(at(0, 900));
+
+    // Act
+    let result = modified_since(modified, LAST_MODIFIED, at(60, 0));
+
+    // Assert
+    assert!(!result);
+}
+
+#[test]
+fn later_mtime_is_modified_since() {
+    // Arrange
+    let modified = Some(at(1, 0));
+
+    // Act
+    let result = modified_since(modified, LAST_MODIFIED, at(60, 0));
+
+    // Assert
+    assert!(result);
+}
+
+#[test]
+fn obsolete_date_formats_are_understood() {
+    // Arrange
+    let modified = Some(at(0, 0));
+
+    // Act
+    let rfc850 = modified_since(modified, "Sunday, 06-Nov-94 08:49:37 GMT", at(60, 0));
+    let asctime = modified_since(modified, "Sun Nov  6 08:49:37 1994", at(60, 0));
+
+    // Assert
+    assert!(!rfc850);
+    assert!(!asctime);
+}
+
+#[test]
+fn file_modified_within_the_last_second_is_always_modified() {
+    // Arrange
+    let modified = Some(at(0, 200));
+
+    // Act
+    let fresh = modified_since(modified, LAST_MODIFIED, at(0, 900));
+    let settled = modified_since(modified, LAST_MODIFIED, at(1, 200));
+
+    // Assert
+    assert!(fresh);
+    assert!(!settled);
+}
+
+#[test]
+fn invalid_or_missing_dates_are_modified() {
+    // Act
+    let invalid = modified_since(Some(at(0, 0)), "yesterday", at(60, 0));
+    let unknown_mtime = modified_since(None, LAST_MODIFIED, at(60, 0));
+
+    // Assert
+    assert!(invalid);
+    assert!(unknown_mtime);
+}
+
+#[test]
+fn last_modified_is_truncated_and_never_in_the_future() {
+    // Act
+    let past = last_modified(Some(at(0, 900)), at(60, 0));
+    let future = last_modified(Some(at(3600, 0)), at(0, 0));
+
+    // Assert
+    assert_eq!(past.as_deref(), Some(LAST_MODIFIED));
+    assert_eq!(future.as_deref(), Some(LAST_MODIFIED));
+}
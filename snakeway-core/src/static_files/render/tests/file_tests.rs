@@ -0,0 +1,455 @@
+use crate::conf::types::{CachePolicy, CompressionOptions, CompressionOptsSpec};
+use crate::static_files::render::etag::generate_etag;
+use crate::static_files::render::render_file;
+use crate::static_files::{ConditionalHeaders, StaticBody, StaticResponse};
+use http::{HeaderMap, StatusCode, header};
+use pretty_assertions::assert_eq;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use tempfile::{TempDir, tempdir};
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+const ORIGINAL: &[u8] = b"console.log('hello world');";
+const BROTLI: &[u8] = b"brotli bytes";
+const GZIP: &[u8] = b"gzip bytes, a few more of them";
+
+fn cache_policy() -> CachePolicy {
+    CachePolicy {
+        max_age_seconds: 60,
+        public: true,
+        immutable: false,
+    }
+}
+
+fn compression_opts() -> CompressionOptions {
+    CompressionOptsSpec::default().into()
+}
+
+/// A directory with `app.js`, and the given precompressed variants of it.
+fn asset_dir(variants: &[(&str, &[u8])]) -> TempDir {
+    let dir = tempdir().expect("failed to create temp dir");
+    fs::write(dir.path().join("app.js"), ORIGINAL).expect("failed to write app.js");
+    for (name, contents) in variants {
+        fs::write(dir.path().join(name), contents).expect("failed to write variant");
+    }
+    dir
+}
+
+async fn get(dir: &TempDir, accept_encoding: &str) -> StaticResponse {
+    let conditional = ConditionalHeaders {
+        accept_encoding: Some(accept_encoding.to_string()),
+        ..Default::default()
+    };
+
+    render_file(
+        dir.path().join("app.js"),
+        &(1024 * 1024),
+        &conditional,
+        &compression_opts(),
+        &cache_policy(),
+    )
+    .await
+    .expect("failed to render file")
+}
+
+fn body(res: &StaticResponse) -> &[u8] {
+    match &res.body {
+        StaticBody::Bytes(bytes) => bytes,
+        _ => panic!("expected an in-memory body"),
+    }
+}
+
+fn header_value<'a>(headers: &'a HeaderMap, name: header::HeaderName) -> Option<&'a str> {
+    headers.get(name).map(|v| v.to_str().unwrap())
+}
+
+/// A directory with `data.txt`: 1000 bytes cycling through the alphabet.
+fn data_dir() -> TempDir {
+    let dir = tempdir().expect("failed to create temp dir");
+    fs::write(dir.path().join("data.txt"), data()).expect("failed to write data.txt");
+    dir
+}
+
+fn data() -> Vec<u8> {
+    (0..1000u32).map(|i| b'a' + (i % 26) as u8).collect()
+}
+
+async fn get_range(dir: &TempDir, range: &str, opts: &CompressionOptions) -> StaticResponse {
+    let conditional = ConditionalHeaders {
+        range: Some(range.to_string()),
+        ..Default::default()
+    };
+
+    render_file(
+        dir.path().join("data.txt"),
+        &(1024 * 1024),
+        &conditional,
+        opts,
+        &cache_policy(),
+    )
+    .await
+    .expect("failed to render file")
+}
+
+/// Boundary of a `multipart/byteranges` response.
+fn boundary(res: &StaticResponse) -> String {
+    header_value(&res.headers, header::CONTENT_TYPE)
+        .and_then(|ct| ct.strip_prefix("multipart/byteranges; boundary="))
+        .expect("expected a multipart/byteranges response")
+        .to_string()
+}
+
+/// The `multipart/byteranges` body expected for the given ranges of `data.txt`.
+fn multipart_body(boundary: &str, ranges: &[(usize, usize)]) -> Vec<u8> {
+    let data = data();
+    let mut body = Vec::new();
+    for (start, end) in ranges {
+        body.extend_from_slice(
+            format!(
+                "\r\n--{boundary}\r\nContent-Type: text/plain\r\n\
+                 Content-Range: bytes {start}-{end}/1000\r\n\r\n"
+            )
+            .as_bytes(),
+        );
+        body.extend_from_slice(&data[*start..=*end]);
+    }
+    body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+    body
+}
+
+/// A directory with `app.js`, last modified an hour ago.
+fn settled_asset_dir() -> TempDir {
+    let dir = asset_dir(&[]);
+    fs::File::options()
+        .write(true)
+        .open(dir.path().join("app.js"))
+        .and_then(|f| f.set_modified(SystemTime::now() - Duration::from_secs(3600)))
+        .expect("failed to set mtime");
+    dir
+}
+
+async fn get_conditional(dir: &TempDir, conditional: ConditionalHeaders) -> StaticResponse {
+    render_file(
+        dir.path().join("app.js"),
+        &(1024 * 1024),
+        &conditional,
+        &compression_opts(),
+        &cache_policy(),
+    )
+    .await
+    .expect("failed to render file")
+}
+
+fn last_modified_of(path: &Path) -> String {
+    httpdate::fmt_http_date(fs::metadata(path).unwrap().modified().unwrap())
+}
+
+fn etag_of(path: &Path) -> String {
+    let metadata = fs::metadata(path).unwrap();
+    generate_etag(metadata.len(), metadata.modified().ok())
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[tokio::test]
+async fn precompressed_brotli_is_preferred_over_gzip() {
+    // Arrange
+    let dir = asset_dir(&[("app.js.br", BROTLI), ("app.js.gz", GZIP)]);
+
+    // Act
+    let res = get(&dir, "gzip, deflate, br").await;
+
+    // Assert
+    assert_eq!(res.status, StatusCode::OK);
+    assert_eq!(body(&res), BROTLI);
+    assert_eq!(
+        header_value(&res.headers, header::CONTENT_ENCODING),
+        Some("br")
+    );
+    assert_eq!(
+        header_value(&res.headers, header::VARY),
+        Some("Accept-Encoding")
+    );
+    assert_eq!(
+        header_value(&res.headers, header::CONTENT_TYPE),
+        Some("text/javascript")
+    );
+    assert_eq!(
+        header_value(&res.headers, header::ETAG),
+        Some(etag_of(&dir.path().join("app.js.br")).as_str())
+    );
+}
+
+#[tokio::test]
+async fn precompressed_gzip_is_served_when_there_is_no_brotli_variant() {
+    // Arrange
+    let dir = asset_dir(&[("app.js.gz", GZIP)]);
+
+    // Act
+    let res = get(&dir, "br, gzip").await;
+
+    // Assert
+    assert_eq!(res.status, StatusCode::OK);
+    assert_eq!(body(&res), GZIP);
+    assert_eq!(
+        header_value(&res.headers, header::CONTENT_ENCODING),
+        Some("gzip")
+    );
+    assert_eq!(
+        header_value(&res.headers, header::VARY),
+        Some("Accept-Encoding")
+    );
+    assert_eq!(
+        header_value(&res.headers, header::ETAG),
+        Some(etag_of(&dir.path().join("app.js.gz")).as_str())
+    );
+}
+
+#[tokio::test]
+async fn precompressed_variant_is_only_served_in_an_accepted_encoding() {
+    // Arrange
+    let dir = asset_dir(&[("app.js.br", BROTLI)]);
+
+    // Act
+    let res = get(&dir, "gzip").await;
+
+    // Assert
+    assert_eq!(body(&res), ORIGINAL);
+    assert_eq!(header_value(&res.headers, header::CONTENT_ENCODING), None);
+}
+
+#[tokio::test]
+async fn original_is_served_when_no_precompressed_variant_is_available() {
+    // Arrange
+    let dir = asset_dir(&[]);
+
+    // Act
+    let res = get(&dir, "gzip, br").await;
+
+    // Assert
+    assert_eq!(res.status, StatusCode::OK);
+    assert_eq!(body(&res), ORIGINAL);
+    assert_eq!(header_value(&res.headers, header::CONTENT_ENCODING), None);
+    assert_eq!(
+        header_value(&res.headers, header::ETAG),
+        Some(etag_of(&dir.path().join("app.js")).as_str())
+    );
+}
+
+#[tokio::test]
+async fn two_disjoint_ranges_are_served_as_multipart_byteranges() {
+    // Arrange
+    let dir = data_dir();
+
+    // Act
+    let res = get_range(&dir, "bytes=0-9, 500-519", &compression_opts()).await;
+
+    // Assert
+    let boundary = boundary(&res);
+    let expected = multipart_body(&boundary, &[(0, 9), (500, 519)]);
+    assert_eq!(res.status, StatusCode::PARTIAL_CONTENT);
+    assert_eq!(body(&res), expected.as_slice());
+    assert_eq!(
+        header_value(&res.headers, header::CONTENT_LENGTH),
+        Some(expected.len().to_string().as_str())
+    );
+    assert_eq!(header_value(&res.headers, header::CONTENT_RANGE), None);
+}
+
+#[tokio::test]
+async fn overlapping_ranges_are_served_as_one_range() {
+    // Arrange
+    let dir = data_dir();
+
+    // Act
+    let res = get_range(&dir, "bytes=0-99, 50-149", &compression_opts()).await;
+
+    // Assert
+    assert_eq!(res.status, StatusCode::PARTIAL_CONTENT);
+    assert_eq!(body(&res), &data()[0..150]);
+    assert_eq!(
+        header_value(&res.headers, header::CONTENT_RANGE),
+        Some("bytes 0-149/1000")
+    );
+    assert_eq!(
+        header_value(&res.headers, header::CONTENT_TYPE),
+        Some("text/plain")
+    );
+}
+
+#[tokio::test]
+async fn suffix_range_serves_the_last_bytes() {
+    // Arrange
+    let dir = data_dir();
+
+    // Act
+    let res = get_range(&dir, "bytes=-500", &compression_opts()).await;
+
+    // Assert
+    assert_eq!(res.status, StatusCode::PARTIAL_CONTENT);
+    assert_eq!(body(&res), &data()[500..]);
+    assert_eq!(
+        header_value(&res.headers, header::CONTENT_RANGE),
+        Some("bytes 500-999/1000")
+    );
+    assert_eq!(
+        header_value(&res.headers, header::CONTENT_LENGTH),
+        Some("500")
+    );
+}
+
+#[tokio::test]
+async fn unsatisfiable_range_is_answered_with_416() {
+    // Arrange
+    let dir = data_dir();
+
+    // Act
+    let res = get_range(&dir, "bytes=1000-1099, 2000-", &compression_opts()).await;
+
+    // Assert
+    assert_eq!(res.status, StatusCode::RANGE_NOT_SATISFIABLE);
+    assert!(matches!(res.body, StaticBody::Empty));
+    assert_eq!(
+        header_value(&res.headers, header::CONTENT_RANGE),
+        Some("bytes */1000")
+    );
+    assert_eq!(
+        header_value(&res.headers, header::CONTENT_LENGTH),
+        Some("0")
+    );
+}
+
+#[tokio::test]
+async fn multipart_byteranges_of_a_large_file_are_streamed() {
+    // Arrange
+    let dir = data_dir();
+    let mut opts = compression_opts();
+    opts.small_file_threshold = 0;
+
+    // Act
+    let res = get_range(&dir, "bytes=0-9, -10", &opts).await;
+
+    // Assert
+    let boundary = boundary(&res);
+    let StaticBody::MultipartFile { parts, closing, .. } = &res.body else {
+        panic!("expected a streamed multipart body");
+    };
+    let layout: Vec<_> = parts.iter().map(|p| (p.start, p.len)).collect();
+    let streamed: usize = parts
+        .iter()
+        .map(|p| p.head.len() + p.len as usize)
+        .sum::<usize>()
+        + closing.len();
+    assert_eq!(res.status, StatusCode::PARTIAL_CONTENT);
+    assert_eq!(layout, vec![(0, 10), (990, 10)]);
+    assert_eq!(
+        streamed,
+        multipart_body(&boundary, &[(0, 9), (990, 999)]).len()
+    );
+    assert_eq!(
+        header_value(&res.headers, header::CONTENT_LENGTH),
+        Some(streamed.to_string().as_str())
+    );
+}
+
+#[tokio::test]
+async fn if_modified_since_last_modified_returns_304() {
+    // Arrange
+    let dir = settled_asset_dir();
+    let last_modified = last_modified_of(&dir.path().join("app.js"));
+
+    // Act
+    let res = get_conditional(
+        &dir,
+        ConditionalHeaders {
+            if_modified_since: Some(last_modified.clone()),
+            ..Default::default()
+        },
+    )
+    .await;
+
+    // Assert
+    assert_eq!(res.status, StatusCode::NOT_MODIFIED);
+    assert!(matches!(res.body, StaticBody::Empty));
+    assert_eq!(
+        header_value(&res.headers, header::LAST_MODIFIED),
+        Some(last_modified.as_str())
+    );
+}
+
+#[tokio::test]
+async fn if_modified_since_before_last_modified_returns_200() {
+    // Arrange
+    let dir = settled_asset_dir();
+    let earlier = httpdate::fmt_http_date(SystemTime::now() - Duration::from_secs(7200));
+
+    // Act
+    let res = get_conditional(
+        &dir,
+        ConditionalHeaders {
+            if_modified_since: Some(earlier),
+            ..Default::default()
+        },
+    )
+    .await;
+
+    // Assert
+    assert_eq!(res.status, StatusCode::OK);
+    assert_eq!(body(&res), ORIGINAL);
+}
+
+#[tokio::test]
+async fn if_none_match_wins_over_if_modified_since() {
+    // Arrange
+    let dir = settled_asset_dir();
+    let path = dir.path().join("app.js");
+    let earlier = httpdate::fmt_http_date(SystemTime::now() - Duration::from_secs(7200));
+
+    // Act
+    let stale_etag = get_conditional(
+        &dir,
+        ConditionalHeaders {
+            if_none_match: Some("W/\"stale\"".to_string()),
+            if_modified_since: Some(last_modified_of(&path)),
+            ..Default::default()
+        },
+    )
+    .await;
+    let matching_etag = get_conditional(
+        &dir,
+        ConditionalHeaders {
+            if_none_match: Some(etag_of(&path)),
+            if_modified_since: Some(earlier),
+            ..Default::default()
+        },
+    )
+    .await;
+
+    // Assert
+    assert_eq!(stale_etag.status, StatusCode::OK);
+    assert_eq!(matching_etag.status, StatusCode::NOT_MODIFIED);
+}
+
+#[tokio::test]
+async fn freshly_written_file_is_not_served_as_not_modified() {
+    // Arrange
+    let dir = asset_dir(&[]);
+    let last_modified = last_modified_of(&dir.path().join("app.js"));
+
+    // Act
+    let res = get_conditional(
+        &dir,
+        ConditionalHeaders {
+            if_modified_since: Some(last_modified),
+            ..Default::default()
+        },
+    )
+    .await;
+
+    // Assert
+    assert_eq!(res.status, StatusCode::OK);
+}
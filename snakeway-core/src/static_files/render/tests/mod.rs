@@ -0,0 +1,4 @@
+mod compression_tests;
+mod etag_tests;
+mod file_tests;
+mod range_tests;
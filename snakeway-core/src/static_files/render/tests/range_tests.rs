@@ -0,0 +1,126 @@
+use crate::static_files::render::range::{ByteRange, RangeRequest, parse_range_header};
+use pretty_assertions::assert_eq;
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+const SIZE: u64 = 1000;
+
+fn range(start: u64, end: u64) -> ByteRange {
+    ByteRange { start, end }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn single_range_is_parsed() {
+    // Act
+    let parsed = parse_range_header("bytes=0-99", SIZE);
+
+    // Assert
+    assert_eq!(parsed, Some(RangeRequest::Single(range(0, 99))));
+}
+
+#[test]
+fn two_disjoint_ranges_are_parsed_in_order() {
+    // Act
+    let parsed = parse_range_header("bytes=500-599, 0-99", SIZE);
+
+    // Assert
+    assert_eq!(
+        parsed,
+        Some(RangeRequest::Multiple(vec![range(0, 99), range(500, 599)]))
+    );
+}
+
+#[test]
+fn overlapping_and_adjacent_ranges_are_coalesced() {
+    // Act
+    let overlapping = parse_range_header("bytes=0-199,100-299", SIZE);
+    let adjacent = parse_range_header("bytes=0-99,100-199,500-", SIZE);
+
+    // Assert
+    assert_eq!(overlapping, Some(RangeRequest::Single(range(0, 299))));
+    assert_eq!(
+        adjacent,
+        Some(RangeRequest::Multiple(vec![range(0, 199), range(500, 999)]))
+    );
+}
+
+#[test]
+fn suffix_range_selects_the_last_bytes() {
+    // Act
+    let suffix = parse_range_header("bytes=-500", SIZE);
+    let longer_than_file = parse_range_header("bytes=-5000", SIZE);
+
+    // Assert
+    assert_eq!(suffix, Some(RangeRequest::Single(range(500, 999))));
+    assert_eq!(longer_than_file, Some(RangeRequest::Single(range(0, 999))));
+}
+
+#[test]
+fn range_past_the_end_is_clamped() {
+    // Act
+    let parsed = parse_range_header("bytes=900-1999", SIZE);
+
+    // Assert
+    assert_eq!(parsed, Some(RangeRequest::Single(range(900, 999))));
+}
+
+#[test]
+fn ranges_outside_the_file_are_unsatisfiable() {
+    // Act
+    let past_end = parse_range_header("bytes=1000-1099", SIZE);
+    let empty_suffix = parse_range_header("bytes=-0", SIZE);
+    let empty_file = parse_range_header("bytes=0-", 0);
+
+    // Assert
+    assert_eq!(past_end, Some(RangeRequest::Unsatisfiable));
+    assert_eq!(empty_suffix, Some(RangeRequest::Unsatisfiable));
+    assert_eq!(empty_file, Some(RangeRequest::Unsatisfiable));
+}
+
+#[test]
+fn unsatisfiable_ranges_are_dropped_when_others_overlap_the_file() {
+    // Act
+    let parsed = parse_range_header("bytes=2000-2099, 0-9", SIZE);
+
+    // Assert
+    assert_eq!(parsed, Some(RangeRequest::Single(range(0, 9))));
+}
+
+#[test]
+fn malformed_headers_are_ignored() {
+    for header in [
+        "bytes=abc",
+        "bytes=10-5",
+        "bytes=0-9,x-1",
+        "items=0-9",
+        "bytes=",
+    ] {
+        // Act
+        let parsed = parse_range_header(header, SIZE);
+
+        // Assert
+        assert_eq!(parsed, None, "{header}");
+    }
+}
+
+#[test]
+fn too_many_ranges_are_ignored() {
+    // Arrange
+    let header = format!(
+        "bytes={}",
+        (0..65)
+            .map(|i| format!("{}-{}", i * 10, i * 10))
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+
+    // Act
+    let parsed = parse_range_header(&header, SIZE);
+
+    // Assert
+    assert_eq!(parsed, None);
+}
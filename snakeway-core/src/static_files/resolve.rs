@@ -1,3 +1,4 @@
+use crate::conf::types::SpaFallbackConfig;
 use std::path::{Component, Path, PathBuf};
 
 #[derive(Debug)]
@@ -13,11 +14,45 @@ pub enum ResolvedStatic {
     Directory(PathBuf),
 }
 
+/// Resolve a request path to a file or directory under the route's base directory.
+///
+/// With an SPA fallback, paths that match no file, and directories without an index file,
+/// resolve to the fallback document instead.
 pub fn resolve_static_path(
     base_dir: &Path,
     route_prefix: &str,
     request_path: &str,
     index: bool,
+    spa_fallback: Option<&SpaFallbackConfig>,
+) -> Result<ResolvedStatic, ResolveError> {
+    let resolved = resolve_path(base_dir, route_prefix, request_path, index);
+
+    match (resolved, spa_fallback) {
+        (Err(ResolveError::NotFound) | Ok(ResolvedStatic::Directory(_)), Some(fallback))
+            if serves_fallback(fallback, request_path) =>
+        {
+            resolve_path(base_dir, "/", &format!("/{}", fallback.document), false)
+        }
+        (resolved, _) => resolved,
+    }
+}
+
+/// Whether a request path that matches no file gets the fallback document. Paths that look like
+/// assets, with a file extension, do not if missing assets should 404.
+fn serves_fallback(fallback: &SpaFallbackConfig, request_path: &str) -> bool {
+    if !fallback.missing_assets_404 {
+        return true;
+    }
+
+    let last_segment = request_path.rsplit('/').next().unwrap_or_default();
+    Path::new(last_segment).extension().is_none()
+}
+
+fn resolve_path(
+    base_dir: &Path,
+    route_prefix: &str,
+    request_path: &str,
+    index: bool,
 ) -> Result<ResolvedStatic, ResolveError> {
     // Sanity checks
     if !request_path.starts_with('/') || !route_prefix.starts_with('/') {
@@ -21,6 +21,24 @@ pub enum StaticBody {
         file: fs::File,
         remaining: u64,
     },
+
+    /// A `multipart/byteranges` body, for requests for several ranges of a large file.
+    MultipartFile {
+        file: fs::File,
+        parts: Vec<FilePart>,
+        /// Closing boundary, sent after the last part.
+        closing: Bytes,
+    },
+}
+
+/// A part of a `multipart/byteranges` body streamed from a file.
+pub struct FilePart {
+    /// Boundary and part headers, sent before the part's bytes.
+    pub head: Bytes,
+    /// Offset of the part's first byte in the file.
+    pub start: u64,
+    /// Length of the part, in bytes.
+    pub len: u64,
 }
 
 pub struct StaticResponse {
@@ -0,0 +1,161 @@
+use crate::conf::types::SpaFallbackConfig;
+use crate::static_files::resolve::{ResolveError, ResolvedStatic, resolve_static_path};
+use std::fs;
+use std::path::PathBuf;
+use tempfile::{TempDir, tempdir};
+
+//-----------------------------------------------------------------------------
+// Test helpers
+//-----------------------------------------------------------------------------
+
+/// An app with `index.html`, `app.html`, `assets/app.js`, and an `assets/empty` directory.
+fn app_dir() -> TempDir {
+    let dir = tempdir().expect("failed to create temp dir");
+    fs::write(dir.path().join("index.html"), "<html>index</html>").unwrap();
+    fs::write(dir.path().join("app.html"), "<html>app</html>").unwrap();
+    fs::create_dir_all(dir.path().join("assets/empty")).unwrap();
+    fs::write(dir.path().join("assets/app.js"), "console.log('app');").unwrap();
+    dir
+}
+
+fn fallback(document: &str, missing_assets_404: bool) -> SpaFallbackConfig {
+    SpaFallbackConfig {
+        document: document.to_string(),
+        missing_assets_404,
+    }
+}
+
+fn resolve(
+    dir: &TempDir,
+    request_path: &str,
+    spa_fallback: Option<&SpaFallbackConfig>,
+) -> Result<ResolvedStatic, ResolveError> {
+    resolve_static_path(dir.path(), "/app", request_path, true, spa_fallback)
+}
+
+fn file(dir: &TempDir, name: &str) -> PathBuf {
+    dir.path().join(name).canonicalize().unwrap()
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[test]
+fn deep_path_falls_back_to_index_html() {
+    // Arrange
+    let dir = app_dir();
+    let spa = fallback("index.html", true);
+
+    // Act
+    let resolved = resolve(&dir, "/app/users/42/settings", Some(&spa));
+
+    // Assert
+    assert!(matches!(resolved, Ok(ResolvedStatic::File(p)) if p == file(&dir, "index.html")));
+}
+
+#[test]
+fn existing_file_is_served_instead_of_fallback() {
+    // Arrange
+    let dir = app_dir();
+    let spa = fallback("index.html", true);
+
+    // Act
+    let resolved = resolve(&dir, "/app/assets/app.js", Some(&spa));
+
+    // Assert
+    assert!(matches!(resolved, Ok(ResolvedStatic::File(p)) if p == file(&dir, "assets/app.js")));
+}
+
+#[test]
+fn missing_asset_is_not_found_with_fallback() {
+    // Arrange
+    let dir = app_dir();
+    let spa = fallback("index.html", true);
+
+    // Act
+    let js = resolve(&dir, "/app/assets/missing.js", Some(&spa));
+    let css = resolve(&dir, "/app/styles/missing.css", Some(&spa));
+
+    // Assert
+    assert!(matches!(js, Err(ResolveError::NotFound)));
+    assert!(matches!(css, Err(ResolveError::NotFound)));
+}
+
+#[test]
+fn missing_asset_falls_back_when_assets_are_not_404() {
+    // Arrange
+    let dir = app_dir();
+    let spa = fallback("index.html", false);
+
+    // Act
+    let resolved = resolve(&dir, "/app/assets/missing.js", Some(&spa));
+
+    // Assert
+    assert!(matches!(resolved, Ok(ResolvedStatic::File(p)) if p == file(&dir, "index.html")));
+}
+
+#[test]
+fn directory_without_index_falls_back() {
+    // Arrange
+    let dir = app_dir();
+    let spa = fallback("index.html", true);
+
+    // Act
+    let resolved = resolve(&dir, "/app/assets/empty", Some(&spa));
+
+    // Assert
+    assert!(matches!(resolved, Ok(ResolvedStatic::File(p)) if p == file(&dir, "index.html")));
+}
+
+#[test]
+fn configured_fallback_document_is_served() {
+    // Arrange
+    let dir = app_dir();
+    let spa = fallback("app.html", true);
+
+    // Act
+    let resolved = resolve(&dir, "/app/dashboard", Some(&spa));
+
+    // Assert
+    assert!(matches!(resolved, Ok(ResolvedStatic::File(p)) if p == file(&dir, "app.html")));
+}
+
+#[test]
+fn missing_fallback_document_is_not_found() {
+    // Arrange
+    let dir = app_dir();
+    let spa = fallback("missing.html", true);
+
+    // Act
+    let resolved = resolve(&dir, "/app/dashboard", Some(&spa));
+
+    // Assert
+    assert!(matches!(resolved, Err(ResolveError::NotFound)));
+}
+
+#[test]
+fn missing_path_is_not_found_without_fallback() {
+    // Arrange
+    let dir = app_dir();
+
+    // Act
+    let resolved = resolve(&dir, "/app/users/42", None);
+    let directory = resolve(&dir, "/app/assets/empty", None);
+
+    // Assert
+    assert!(matches!(resolved, Err(ResolveError::NotFound)));
+    assert!(matches!(directory, Ok(ResolvedStatic::Directory(_))));
+}
+
+#[test]
+fn traversal_is_still_forbidden_with_fallback() {
+    // Arrange
+    let dir = app_dir();
+    let spa = fallback("index.html", false);
+
+    // Act
+    let resolved = resolve(&dir, "/app/../secret", Some(&spa));
+
+    // Assert
+    assert!(matches!(resolved, Err(ResolveError::Forbidden)));
+}
@@ -1,3 +1,4 @@
+use crate::conf::types::CircuitBreakerIsolation;
 use crate::traffic_management::HealthStatus;
 use crate::traffic_management::circuit::{CircuitBreakerParams, CircuitState};
 use serde::Serialize;
@@ -22,6 +23,9 @@ pub struct CircuitBreakerParamsView {
     pub half_open_max_requests: u32,
     pub success_threshold: u32,
     pub count_http_5xx_as_failure: bool,
+    pub isolation: CircuitBreakerIsolation,
+    pub open_backoff_multiplier: Option<u32>,
+    pub max_open_duration_milliseconds: Option<u64>,
 }
 
 impl From<&CircuitBreakerParams> for CircuitBreakerParamsView {
@@ -33,6 +37,12 @@ impl From<&CircuitBreakerParams> for CircuitBreakerParamsView {
             half_open_max_requests: p.half_open_max_requests,
             success_threshold: p.success_threshold,
             count_http_5xx_as_failure: p.count_http_5xx_as_failure,
+            isolation: p.isolation,
+            open_backoff_multiplier: p.open_backoff.as_ref().map(|b| b.multiplier),
+            max_open_duration_milliseconds: p
+                .open_backoff
+                .as_ref()
+                .map(|b| b.max_open_duration.as_millis() as u64),
         }
     }
 }
@@ -41,6 +51,7 @@ impl From<&CircuitBreakerParams> for CircuitBreakerParamsView {
 pub struct CircuitBreakerDetailsView {
     pub consecutive_failures: u32,
     pub opened_at_rfc3339: Option<String>,
+    pub reopens: u32,
     pub half_open_in_flight: u32,
     pub half_open_successes: u32,
 }
@@ -1,27 +1,45 @@
 use crate::runtime::UpstreamId;
 use crate::traffic_management::{ServiceId, TrafficManager};
 use std::sync::Arc;
+use std::time::Instant;
 
 #[derive(Debug)]
 pub struct AdmissionGuard {
     tm: Arc<TrafficManager>,
     service_id: ServiceId,
+    /// Route part of the circuit key, set only when the service isolates circuits by route.
+    circuit_route: Option<String>,
     upstream_id: UpstreamId,
+    started: Instant,
     finished: bool,
 }
 
 impl AdmissionGuard {
-    pub fn new(tm: Arc<TrafficManager>, service_id: ServiceId, upstream_id: UpstreamId) -> Self {
+    pub fn new(
+        tm: Arc<TrafficManager>,
+        service_id: ServiceId,
+        route: Option<&str>,
+        upstream_id: UpstreamId,
+    ) -> Self {
         tm.on_request_start(&service_id, &upstream_id);
+        let circuit_route = tm.circuit_route(&service_id, route);
 
         Self {
             tm,
             service_id,
+            circuit_route,
             upstream_id,
+            started: Instant::now(),
             finished: false,
         }
     }
 
+    /// Record the time from admission to the upstream's response headers as a latency sample.
+    pub fn response_received(&self) {
+        self.tm
+            .record_latency(&self.service_id, &self.upstream_id, self.started.elapsed());
+    }
+
     pub fn success(&mut self) {
         self.finish(true);
     }
@@ -41,8 +59,13 @@ impl AdmissionGuard {
             self.tm.report_failure(&self.service_id, &self.upstream_id);
         }
 
-        self.tm
-            .circuit_on_end(&self.service_id, &self.upstream_id, true, success);
+        self.tm.circuit_on_end(
+            &self.service_id,
+            self.circuit_route.as_deref(),
+            &self.upstream_id,
+            true,
+            success,
+        );
 
         self.tm.on_request_end(&self.service_id, &self.upstream_id);
 
@@ -0,0 +1,56 @@
+use crate::ctx::RequestCtx;
+use crate::traffic_management::{
+    ServiceId, TrafficManager, decision::*, snapshot::*, strategy::TrafficStrategy,
+};
+use rand::{Rng, rng};
+use std::time::Duration;
+
+/// Latency-aware selection.
+///
+/// Two distinct upstreams are picked at random and the one with the lower cost wins, so traffic
+/// skews toward fast upstreams without all of it herding onto the single fastest one. The cost is
+/// the upstream's average response latency times its in-flight requests plus one. An upstream
+/// that has not answered yet costs nothing, so it is tried early.
+#[derive(Debug, Default)]
+pub struct Ewma;
+
+impl TrafficStrategy for Ewma {
+    fn decide(
+        &self,
+        _req: &RequestCtx,
+        service_id: &ServiceId,
+        healthy: &[UpstreamSnapshot],
+        traffic_manager: &TrafficManager,
+    ) -> Option<TrafficDecision> {
+        let upstream = match healthy.len() {
+            0 => return None,
+            1 => &healthy[0],
+            n => {
+                let mut rng = rng();
+                let a = rng.random_range(0..n);
+                let mut b = rng.random_range(0..n - 1);
+                if b >= a {
+                    b += 1;
+                }
+
+                [&healthy[a], &healthy[b]].into_iter().min_by_key(|u| {
+                    let id = u.endpoint.id();
+                    let latency = traffic_manager
+                        .latency(service_id, &id)
+                        .unwrap_or(Duration::ZERO);
+                    let in_flight = traffic_manager.active_requests(service_id, &id);
+                    (
+                        latency.as_nanos() * (in_flight as u128 + 1),
+                        id, // Deterministic tie-break.
+                    )
+                })?
+            }
+        };
+
+        Some(TrafficDecision {
+            upstream_id: upstream.endpoint.id(),
+            reason: DecisionReason::Ewma,
+            cb_started: true,
+        })
+    }
+}
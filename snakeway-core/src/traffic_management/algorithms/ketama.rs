@@ -0,0 +1,111 @@
+use crate::conf::types::KetamaConfig;
+use crate::ctx::RequestCtx;
+use crate::enrichment::user_agent::ClientIdentity;
+use crate::runtime::UpstreamId;
+use crate::traffic_management::{
+    ServiceId, TrafficManager,
+    decision::{DecisionReason, TrafficDecision},
+    snapshot::UpstreamSnapshot,
+    strategy::TrafficStrategy,
+};
+use ahash::RandomState;
+use http::HeaderName;
+use std::hash::Hash;
+
+/// Fixed seeds, so keys land on the same upstream across restarts and processes.
+static HASHER: RandomState = RandomState::with_seeds(5, 6, 7, 8);
+
+fn hash_to_u64<T: Hash>(value: &T) -> u64 {
+    HASHER.hash_one(value)
+}
+
+/// A consistent hash ring over a service's upstreams.
+///
+/// Each upstream owns `replicas × weight` points on the ring, and a key goes to the owner of
+/// the first point at or after its hash. Points are placed by upstream id, which is derived
+/// from the upstream's address, so adding or removing an upstream only moves the keys on the
+/// points it gains or loses.
+#[derive(Debug)]
+pub struct KetamaRing {
+    points: Vec<(u64, UpstreamId)>,
+    key_header: Option<HeaderName>,
+}
+
+impl KetamaRing {
+    pub fn build(cfg: &KetamaConfig, upstreams: &[UpstreamSnapshot]) -> Self {
+        let mut points: Vec<(u64, UpstreamId)> = upstreams
+            .iter()
+            .flat_map(|u| {
+                let id = u.endpoint.id();
+                (0..cfg.replicas.saturating_mul(u.weight)).map(move |i| (hash_to_u64(&(id, i)), id))
+            })
+            .collect();
+        points.sort_unstable();
+
+        Self {
+            points,
+            key_header: cfg
+                .key_header
+                .as_deref()
+                .and_then(|h| HeaderName::from_bytes(h.as_bytes()).ok()),
+        }
+    }
+
+    /// The owner of the key, skipping points of upstreams that are not eligible,
+    /// e.g. because they are unhealthy.
+    pub fn owner(&self, key: &str, eligible: impl Fn(UpstreamId) -> bool) -> Option<UpstreamId> {
+        let hash = hash_to_u64(&key);
+        let start = self.points.partition_point(|(point, _)| *point < hash);
+
+        self.points[start..]
+            .iter()
+            .chain(&self.points[..start])
+            .map(|(_, id)| *id)
+            .find(|id| eligible(*id))
+    }
+
+    /// The configured header's value, or the client IP for requests without it.
+    fn key(&self, req: &RequestCtx) -> String {
+        if let Some(v) = self
+            .key_header
+            .as_ref()
+            .and_then(|name| req.headers().get(name))
+            .and_then(|h| h.to_str().ok())
+            .filter(|v| !v.is_empty())
+        {
+            return v.to_owned();
+        }
+
+        match req.extensions.get::<ClientIdentity>() {
+            Some(identity) => identity.ip.to_string(),
+            None => req.peer_ip.to_string(),
+        }
+    }
+}
+
+/// Consistent hashing for cache-affinity backends.
+///
+/// The traffic manager keeps a ring per service and rebuilds it when a reload changes the
+/// upstreams, so a request only costs a binary search instead of scoring every upstream.
+#[derive(Debug, Default)]
+pub struct Ketama;
+
+impl TrafficStrategy for Ketama {
+    fn decide(
+        &self,
+        req: &RequestCtx,
+        service_id: &ServiceId,
+        healthy: &[UpstreamSnapshot],
+        traffic_manager: &TrafficManager,
+    ) -> Option<TrafficDecision> {
+        let ring = traffic_manager.ketama_ring(service_id)?;
+        let key = ring.key(req);
+        let upstream_id = ring.owner(&key, |id| healthy.iter().any(|u| u.endpoint.id() == id))?;
+
+        Some(TrafficDecision {
+            upstream_id,
+            reason: DecisionReason::Ketama,
+            cb_started: true,
+        })
+    }
+}
@@ -1,10 +1,14 @@
+mod ewma;
 mod failover;
+mod ketama;
 mod random;
 mod request_pressure;
 mod round_robin;
 mod sticky_hash;
 
+pub use ewma::*;
 pub use failover::*;
+pub use ketama::*;
 pub use random::*;
 pub use request_pressure::*;
 pub use round_robin::*;
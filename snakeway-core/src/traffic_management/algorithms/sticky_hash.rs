@@ -7,7 +7,14 @@ use crate::traffic_management::{
     strategy::TrafficStrategy,
 };
 use ahash::RandomState;
+use http::header;
 use std::hash::Hash;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Cookie carrying an explicit stickiness key.
+///
+/// Browsers cannot set headers on a WebSocket upgrade, but they do send cookies.
+pub const STICKY_COOKIE: &str = "snakeway_sticky";
 
 #[derive(Debug, Default)]
 pub struct StickyHash;
@@ -28,8 +35,9 @@ impl StickyHash {
     ///
     /// Priority:
     /// 1. Explicit header (`x-sticky-key`)
-    /// 2. Identity device (if enabled)
-    /// 3. Raw peer IP (always exists)
+    /// 2. Explicit cookie (`snakeway_sticky`)
+    /// 3. Identity device (if enabled)
+    /// 4. Raw peer IP (always exists)
     fn resolve_sticky_key(&self, req: &RequestCtx) -> Option<String> {
         if let Some(v) = req
             .headers()
@@ -40,6 +48,20 @@ impl StickyHash {
             return Some(v.to_owned());
         }
 
+        if let Some(v) = req
+            .headers()
+            .get_all(header::COOKIE)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(';'))
+            .filter_map(|pair| pair.trim().split_once('='))
+            .find(|(name, _)| *name == STICKY_COOKIE)
+            .map(|(_, value)| value.trim_matches('"'))
+            .filter(|v| !v.is_empty())
+        {
+            return Some(v.to_owned());
+        }
+
         if let Some(identity) = req.extensions.get::<ClientIdentity>() {
             return Some(identity.ip.to_string());
         }
@@ -47,17 +69,53 @@ impl StickyHash {
         Some(req.peer_ip.to_string())
     }
 
+    /// Affinity period `now` falls in for `key`, when the route limits affinity to a TTL.
+    ///
+    /// Periods are counted from the Unix epoch, so every instance agrees on them, but each key's
+    /// periods are shifted by an offset derived from the key. Keys are then reselected at
+    /// different moments rather than all at once on an epoch boundary.
+    pub(crate) fn affinity_period(
+        &self,
+        key: &str,
+        ttl: Option<Duration>,
+        now: SystemTime,
+    ) -> Option<u128> {
+        let ttl = ttl?.as_millis().max(1);
+        let offset = u128::from(self.hash_to_u64(&key)) % ttl;
+        let since_epoch = now.duration_since(UNIX_EPOCH).unwrap_or_default();
+        Some((since_epoch.as_millis() + offset) / ttl)
+    }
+
     /// Rendezvous hashing: choose the upstream with the highest score.
+    ///
+    /// Within an affinity period, the period is hashed in too, so each period is a fresh
+    /// selection for the key.
     fn rendezvous<'a>(
         &self,
         key: &str,
+        period: Option<u128>,
         upstreams: &'a [UpstreamSnapshot],
     ) -> Option<&'a UpstreamSnapshot> {
         upstreams.iter().max_by_key(|u| {
             // Combine sticky key and upstream identity
-            self.hash_to_u64(&(key, u.endpoint.id()))
+            match period {
+                Some(period) => self.hash_to_u64(&(key, period, u.endpoint.id())),
+                None => self.hash_to_u64(&(key, u.endpoint.id())),
+            }
         })
     }
+
+    /// Choose the upstream for the request, as of `now`.
+    pub(crate) fn select<'a>(
+        &self,
+        req: &RequestCtx,
+        healthy: &'a [UpstreamSnapshot],
+        now: SystemTime,
+    ) -> Option<&'a UpstreamSnapshot> {
+        let key = self.resolve_sticky_key(req)?;
+        let period = self.affinity_period(&key, req.affinity_ttl, now);
+        self.rendezvous(&key, period, healthy)
+    }
 }
 
 impl TrafficStrategy for StickyHash {
@@ -72,8 +130,7 @@ impl TrafficStrategy for StickyHash {
             return None;
         }
 
-        let key = self.resolve_sticky_key(req)?;
-        let upstream = self.rendezvous(&key, healthy)?;
+        let upstream = self.select(req, healthy, SystemTime::now())?;
 
         Some(TrafficDecision {
             upstream_id: upstream.endpoint.id(),
@@ -1,3 +1,4 @@
+use crate::conf::types::CircuitBreakerIsolation;
 use crate::runtime::UpstreamId;
 use crate::traffic_management::ServiceId;
 use std::time::{Duration, Instant, SystemTime};
@@ -11,6 +12,28 @@ pub struct CircuitBreakerParams {
     pub half_open_max_requests: u32,
     pub success_threshold: u32,
     pub count_http_5xx_as_failure: bool,
+    pub isolation: CircuitBreakerIsolation,
+    pub open_backoff: Option<CircuitBreakerBackoffParams>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerBackoffParams {
+    pub multiplier: u32,
+    pub max_open_duration: Duration,
+}
+
+impl CircuitBreakerParams {
+    /// How long the circuit stays open after re-opening `reopens` times in a row from
+    /// half-open. Grows by the backoff multiplier each time, up to its max open duration.
+    pub fn open_duration_after(&self, reopens: u32) -> Duration {
+        match &self.open_backoff {
+            Some(backoff) => self
+                .open_duration
+                .saturating_mul(backoff.multiplier.saturating_pow(reopens))
+                .min(backoff.max_open_duration),
+            None => self.open_duration,
+        }
+    }
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, serde::Serialize)]
@@ -33,6 +56,9 @@ pub struct CircuitBreaker {
     pub(crate) opened_at_instant: Option<Instant>,
     pub(crate) opened_at_system: Option<SystemTime>,
 
+    /// Times the circuit re-opened from half-open since it was last closed.
+    pub(crate) reopens: u32,
+
     // HalfOpen
     pub(crate) half_open_in_flight: u32,
     pub(crate) half_open_successes: u32,
@@ -45,6 +71,7 @@ impl CircuitBreaker {
             consecutive_failures: 0,
             opened_at_instant: None,
             opened_at_system: None,
+            reopens: 0,
             half_open_in_flight: 0,
             half_open_successes: 0,
         }
@@ -72,7 +99,7 @@ impl CircuitBreaker {
                     }
                 };
 
-                if opened_at.elapsed() >= p.open_duration {
+                if opened_at.elapsed() >= p.open_duration_after(self.reopens) {
                     // Circuit disabled = no auto recovery
                     if !p.enable_auto_recovery {
                         // remain open until external reset (health)
@@ -104,7 +131,11 @@ impl CircuitBreaker {
             }
 
             CircuitState::HalfOpen => {
-                if self.half_open_in_flight < p.half_open_max_requests {
+                // Admit no more probes than it takes to close the circuit, so it closes only
+                // once every probe of this half-open period has succeeded.
+                if self.half_open_in_flight < p.half_open_max_requests
+                    && self.half_open_in_flight + self.half_open_successes < p.success_threshold
+                {
                     self.half_open_in_flight += 1;
                     true
                 } else {
@@ -165,10 +196,13 @@ impl CircuitBreaker {
     pub(crate) fn trip_open(
         &mut self,
         ids: (&ServiceId, &UpstreamId),
-        _p: &CircuitBreakerParams,
+        p: &CircuitBreakerParams,
         reason: &'static str,
     ) {
         let old_state = self.state;
+        if old_state == CircuitState::HalfOpen {
+            self.reopens = self.reopens.saturating_add(1);
+        }
         self.state = CircuitState::Open;
         self.opened_at_instant = Some(Instant::now());
         self.opened_at_system = Some(SystemTime::now());
@@ -184,7 +218,8 @@ impl CircuitBreaker {
             from = ?old_state,
             to = ?self.state,
             reason = reason,
-            failures = failures
+            failures = failures,
+            open_duration_ms = p.open_duration_after(self.reopens).as_millis() as u64
         );
     }
 
@@ -193,6 +228,7 @@ impl CircuitBreaker {
         self.state = CircuitState::Closed;
         self.opened_at_instant = None;
         self.opened_at_system = None;
+        self.reopens = 0;
         self.consecutive_failures = 0;
         self.half_open_in_flight = 0;
         self.half_open_successes = 0;
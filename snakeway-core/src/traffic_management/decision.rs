@@ -7,6 +7,8 @@ pub enum DecisionReason {
     AdmissionPressure,
     Random,
     StickyHash,
+    Ewma,
+    Ketama,
     NoStrategyDecision,
 }
 
@@ -4,8 +4,10 @@ use crate::traffic_management::{
 };
 use once_cell::sync::Lazy;
 
+static EWMA: Lazy<Ewma> = Lazy::new(Ewma::default);
 static FAILOVER: Lazy<Failover> = Lazy::new(Failover::default);
 static HASH: Lazy<StickyHash> = Lazy::new(StickyHash::default);
+static KETAMA: Lazy<Ketama> = Lazy::new(Ketama::default);
 static REQUEST_PRESSURE: Lazy<RequestPressure> = Lazy::new(RequestPressure::default);
 static RANDOM: Lazy<Random> = Lazy::new(Random::default);
 static ROUND_ROBIN: Lazy<RoundRobin> = Lazy::new(RoundRobin::default);
@@ -26,22 +28,46 @@ impl TrafficDirector {
             .get(service_id)
             .ok_or(TrafficError::UnknownService)?;
 
-        // Purely filter on health status.
+        // A device may pick an upstream group. Requests without one, or with a group this
+        // service does not have, go to the default pool of ungrouped upstreams.
+        let group = req.upstream_group.as_deref().filter(|g| {
+            service
+                .upstreams
+                .iter()
+                .any(|u| u.endpoint.group() == Some(*g))
+        });
+
+        // Purely filter on group, health status and outlier ejection.
         let mut healthy_candidates: Vec<_> = service
             .upstreams
             .iter()
+            .filter(|u| u.endpoint.group() == group)
             .filter(|u| {
                 traffic_manager
                     .health_status(service_id, &u.endpoint.id())
                     .healthy
             })
-            .cloned()
+            .filter(|u| !traffic_manager.is_ejected(service_id, &u.endpoint.id()))
+            .map(|u| UpstreamSnapshot {
+                // Weights set through the admin API win over the configured ones.
+                weight: traffic_manager.upstream_weight(service_id, u),
+                ..u.clone()
+            })
             .collect();
 
         if healthy_candidates.is_empty() {
             return Err(TrafficError::NoHealthyUpstreams);
         }
 
+        // Retries go to upstreams the request has not failed on, while there are any.
+        let untried = |u: &UpstreamSnapshot| !req.retried_upstreams.contains(&u.endpoint.id());
+        if healthy_candidates.iter().any(untried) {
+            healthy_candidates.retain(untried);
+        }
+
+        // Circuits may be isolated per route, see `CircuitBreakerIsolation`.
+        let route = req.route_id.as_ref().map(|id| id.path());
+
         // Select strategy.
         let strategy: &dyn TrafficStrategy = match service.strategy {
             LoadBalancingStrategy::Failover => &*FAILOVER,
@@ -49,6 +75,8 @@ impl TrafficDirector {
             LoadBalancingStrategy::RequestPressure => &*REQUEST_PRESSURE,
             LoadBalancingStrategy::StickyHash => &*HASH,
             LoadBalancingStrategy::Random => &*RANDOM,
+            LoadBalancingStrategy::Ewma => &*EWMA,
+            LoadBalancingStrategy::Ketama => &*KETAMA,
         };
 
         // Pick upstream and circuit admission
@@ -61,7 +89,7 @@ impl TrafficDirector {
                     cb_started: true,
                 });
 
-            if traffic_manager.circuit_allows(service_id, &decision.upstream_id) {
+            if traffic_manager.circuit_allows(service_id, route, &decision.upstream_id) {
                 return Ok(decision);
             }
 
@@ -0,0 +1,183 @@
+use crate::runtime::{UpstreamId, UpstreamRuntime};
+use crate::traffic_management::{ActiveHealthCheckParams, ServiceId, TrafficManager};
+use anyhow::anyhow;
+use async_trait::async_trait;
+use dashmap::DashMap;
+use http::{Method, header};
+use pingora::connectors::http::Connector;
+use pingora::http::RequestHeader;
+use pingora::server::ShutdownWatch;
+use pingora::services::background::BackgroundService;
+use pingora::upstreams::peer::HttpPeer;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How often the checker looks for upstreams that are due a probe.
+const PROBE_SCHEDULER_TICK: Duration = Duration::from_millis(50);
+
+/// Probes the upstreams of services with active health checks, and reports the results to
+/// the traffic manager, which takes upstreams failing their probes out of rotation.
+///
+/// Services and upstreams are read from the traffic manager's snapshot on every tick,
+/// so the checker follows reloads.
+pub struct ActiveHealthChecker {
+    traffic_manager: Arc<TrafficManager>,
+    connector: Arc<Connector>,
+    next_probe: DashMap<(ServiceId, UpstreamId), Instant>,
+}
+
+impl ActiveHealthChecker {
+    pub fn new(traffic_manager: Arc<TrafficManager>) -> Self {
+        Self {
+            traffic_manager,
+            connector: Arc::new(Connector::new(None)),
+            next_probe: DashMap::new(),
+        }
+    }
+
+    /// Start a probe of every upstream that is due one. Probes run concurrently,
+    /// so a slow upstream does not hold up the others.
+    fn probe_due_upstreams(&self) {
+        let now = Instant::now();
+        let snapshot = self.traffic_manager.snapshot();
+
+        self.next_probe.retain(|(service_id, upstream_id), _| {
+            snapshot.services.get(service_id).is_some_and(|svc| {
+                svc.upstreams
+                    .iter()
+                    .any(|u| u.endpoint.id() == *upstream_id)
+            })
+        });
+
+        for (service_id, svc) in &snapshot.services {
+            let Some(params) = self
+                .traffic_manager
+                .health_params
+                .get(service_id)
+                .map(|p| p.clone())
+            else {
+                continue;
+            };
+            if !params.enable {
+                continue;
+            }
+            let Some(interval) = params.active.as_ref().map(|a| a.interval) else {
+                continue;
+            };
+
+            for upstream in &svc.upstreams {
+                let due = {
+                    let mut next = self
+                        .next_probe
+                        .entry((service_id.clone(), upstream.endpoint.id()))
+                        .or_insert(now);
+                    if *next > now {
+                        false
+                    } else {
+                        *next = now + interval;
+                        true
+                    }
+                };
+                if !due {
+                    continue;
+                }
+
+                let traffic_manager = self.traffic_manager.clone();
+                let connector = self.connector.clone();
+                let service_id = service_id.clone();
+                let upstream = upstream.endpoint.clone();
+                let params = params.clone();
+                tokio::spawn(async move {
+                    let Some(active) = &params.active else {
+                        return;
+                    };
+                    let success = probe(&connector, &upstream, active).await;
+                    traffic_manager.report_probe(&service_id, &upstream.id(), success);
+                });
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl BackgroundService for ActiveHealthChecker {
+    async fn start(&self, mut shutdown: ShutdownWatch) {
+        let mut ticker = tokio::time::interval(PROBE_SCHEDULER_TICK);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = shutdown.changed() => return,
+                _ = ticker.tick() => self.probe_due_upstreams(),
+            }
+        }
+    }
+}
+
+/// Whether the upstream answers the probe with the expected status, within the timeout.
+async fn probe(
+    connector: &Connector,
+    upstream: &UpstreamRuntime,
+    active: &ActiveHealthCheckParams,
+) -> bool {
+    match tokio::time::timeout(active.timeout, send_probe(connector, upstream, active)).await {
+        Ok(Ok(status)) if status == active.expected_status => true,
+        Ok(Ok(status)) => {
+            tracing::debug!(
+                upstream = %upstream.authority(),
+                status,
+                "health probe got an unexpected status"
+            );
+            false
+        }
+        Ok(Err(e)) => {
+            tracing::debug!(
+                upstream = %upstream.authority(),
+                error = %e,
+                "health probe failed"
+            );
+            false
+        }
+        Err(_) => {
+            tracing::debug!(upstream = %upstream.authority(), "health probe timed out");
+            false
+        }
+    }
+}
+
+/// Send the probe over a new connection, so connecting is checked too, and return the status.
+async fn send_probe(
+    connector: &Connector,
+    upstream: &UpstreamRuntime,
+    active: &ActiveHealthCheckParams,
+) -> anyhow::Result<u16> {
+    let mut peer = match upstream {
+        UpstreamRuntime::Tcp(tcp) => {
+            let host = tcp.hostname.as_deref().unwrap_or(&tcp.host);
+            let addr = tokio::net::lookup_host((host, tcp.port))
+                .await?
+                .next()
+                .ok_or_else(|| anyhow!("{host} did not resolve to any address"))?;
+            HttpPeer::new(addr, tcp.use_tls, tcp.sni.clone())
+        }
+        UpstreamRuntime::Unix(unix) => {
+            HttpPeer::new_uds(&unix.path, unix.use_tls, unix.sni.clone())?
+        }
+    };
+    peer.options.connection_timeout = Some(active.timeout);
+
+    let mut req = RequestHeader::build(Method::GET, active.path.as_bytes(), None)?;
+    req.insert_header(header::HOST, upstream.authority())?;
+
+    let (mut session, _reused) = connector.get_http_session(&peer).await?;
+    session.write_request_header(Box::new(req)).await?;
+    session.finish_request_body().await?;
+    session.read_response_header().await?;
+
+    let status = session
+        .response_header()
+        .ok_or_else(|| anyhow!("upstream sent no response header"))?
+        .status;
+
+    Ok(status.as_u16())
+}
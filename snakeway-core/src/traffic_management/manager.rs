@@ -1,10 +1,19 @@
+use crate::conf::types::{CircuitBreakerIsolation, LoadBalancingStrategy};
 use crate::runtime::UpstreamId;
 use crate::traffic_management::admin::{
     AdminUpstreamView, CircuitBreakerDetailsView, CircuitBreakerParamsView,
 };
-use crate::traffic_management::circuit::{CircuitBreaker, CircuitBreakerParams, CircuitState};
+use crate::traffic_management::algorithms::KetamaRing;
+use crate::traffic_management::circuit::{
+    CircuitBreaker, CircuitBreakerBackoffParams, CircuitBreakerParams, CircuitState,
+};
+use crate::traffic_management::outlier::{OutlierDetectionParams, OutlierDetector};
+use crate::traffic_management::retry::{RetryBudget, RetryBudgetGuard, RetryPolicyParams};
 use crate::traffic_management::snapshot::TrafficSnapshot;
-use crate::traffic_management::{HealthCheckParams, HealthStatus, ServiceId, UpstreamSnapshot};
+use crate::traffic_management::{
+    ActiveHealthCheckParams, HealthCheckParams, HealthStatus, LatencyStats, ServiceId,
+    UpstreamSnapshot,
+};
 use arc_swap::ArcSwap;
 use dashmap::DashMap;
 use std::collections::HashSet;
@@ -21,6 +30,7 @@ pub enum UpstreamOutcome {
 
 #[derive(Debug, Clone, Copy)]
 pub enum TransportFailure {
+    Dns,
     Connect,
     Timeout,
     Reset,
@@ -39,6 +49,14 @@ enum HealthState {
     },
 }
 
+/// Active health check state of an upstream endpoint
+#[derive(Debug, Clone, Copy)]
+struct ProbeState {
+    healthy: bool,
+    consecutive_failures: u32,
+    consecutive_successes: u32,
+}
+
 /// Weighted Round Robin state.
 #[derive(Debug, Clone)]
 struct WrrState {
@@ -50,6 +68,13 @@ struct WrrState {
     total_weight: i64,
 }
 
+/// Weight of the newest sample in the latency EWMA, in tenths.
+const LATENCY_EWMA_SAMPLE_TENTHS: u32 = 3;
+
+/// Identifies circuit breaker state: an upstream of a service, plus the route path
+/// when the service isolates circuits by route.
+pub type CircuitKey = (ServiceId, Option<String>, UpstreamId);
+
 #[derive(Debug)]
 pub struct TrafficManager {
     snapshot: ArcSwap<TrafficSnapshot>,
@@ -60,22 +85,46 @@ pub struct TrafficManager {
     /// Per-upstream weighted round-robin state
     wrr_state: DashMap<ServiceId, WrrState>,
 
+    /// Per-upstream response latency
+    latency: DashMap<(ServiceId, UpstreamId), LatencyStats>,
+
+    /// Per-service consistent hash rings, for services using the ketama strategy
+    ketama_rings: DashMap<ServiceId, Arc<KetamaRing>>,
+
     /// Per-upstream health state
     upstream_health: DashMap<(ServiceId, UpstreamId), HealthState>,
 
+    /// Per-upstream active health check state
+    probe_health: DashMap<(ServiceId, UpstreamId), ProbeState>,
+
     /// Per-upstream counters
     total_requests: DashMap<(ServiceId, UpstreamId), AtomicU32>,
     total_successes: DashMap<(ServiceId, UpstreamId), AtomicU32>,
     total_failures: DashMap<(ServiceId, UpstreamId), AtomicU32>,
 
-    /// Per-upstream circuit breaker state machine
-    pub circuit: DashMap<(ServiceId, UpstreamId), CircuitBreaker>,
+    /// Per-upstream circuit breaker state machine, also per route for services isolating by route
+    pub circuit: DashMap<CircuitKey, CircuitBreaker>,
 
     /// Per-service circuit breaker parameters (cloned from snapshot)
     pub circuit_params: DashMap<ServiceId, Arc<CircuitBreakerParams>>,
 
     /// Per-service health check parameters (cloned from snapshot)
     pub health_params: DashMap<ServiceId, Arc<HealthCheckParams>>,
+
+    /// Per-upstream outlier detection state
+    outliers: DashMap<(ServiceId, UpstreamId), OutlierDetector>,
+
+    /// Per-service outlier detection parameters (cloned from snapshot)
+    pub outlier_params: DashMap<ServiceId, Arc<OutlierDetectionParams>>,
+
+    /// Per-service retry budget
+    retry_budgets: DashMap<ServiceId, Arc<RetryBudget>>,
+
+    /// Per-service retry policy parameters (cloned from snapshot)
+    pub retry_params: DashMap<ServiceId, Arc<RetryPolicyParams>>,
+
+    /// Per-upstream weights set through the admin API, until the next reload
+    weight_overrides: DashMap<(ServiceId, UpstreamId), u32>,
 }
 
 impl TrafficManager {
@@ -84,13 +133,21 @@ impl TrafficManager {
             snapshot: ArcSwap::from_pointee(initial.clone()),
             active_requests: DashMap::new(),
             wrr_state: DashMap::new(),
+            latency: DashMap::new(),
+            ketama_rings: DashMap::new(),
             upstream_health: DashMap::new(),
+            probe_health: DashMap::new(),
             total_requests: DashMap::new(),
             total_successes: DashMap::new(),
             total_failures: DashMap::new(),
             circuit: DashMap::new(),
             circuit_params: DashMap::new(),
             health_params: DashMap::new(),
+            outliers: DashMap::new(),
+            outlier_params: DashMap::new(),
+            retry_budgets: DashMap::new(),
+            retry_params: DashMap::new(),
+            weight_overrides: DashMap::new(),
         };
 
         tm.update(initial);
@@ -125,6 +182,19 @@ impl TrafficManager {
                 .unwrap_or(false)
         });
 
+        // Cleanup latency averages
+        self.latency.retain(|(service_id, upstream_id), _| {
+            new_snapshot
+                .services
+                .get(service_id)
+                .map(|svc| {
+                    svc.upstreams
+                        .iter()
+                        .any(|u| u.endpoint.id() == *upstream_id)
+                })
+                .unwrap_or(false)
+        });
+
         // Cleanup health state
         self.upstream_health.retain(|(service_id, upstream_id), _| {
             new_snapshot
@@ -138,6 +208,21 @@ impl TrafficManager {
                 .unwrap_or(false)
         });
 
+        // Cleanup active health check state, including services that no longer probe
+        self.probe_health.retain(|(service_id, upstream_id), _| {
+            new_snapshot
+                .services
+                .get(service_id)
+                .map(|svc| {
+                    svc.health_check_cfg.active.is_some()
+                        && svc
+                            .upstreams
+                            .iter()
+                            .any(|u| u.endpoint.id() == *upstream_id)
+                })
+                .unwrap_or(false)
+        });
+
         // Cleanup total counters
         self.total_requests.retain(|(service_id, upstream_id), _| {
             new_snapshot
@@ -173,15 +258,34 @@ impl TrafficManager {
                 .unwrap_or(false)
         });
 
-        // Cleanup circuit breaker state
-        self.circuit.retain(|(service_id, upstream_id), _| {
+        // Cleanup circuit breaker state, including state keyed for a different isolation
+        self.circuit.retain(|(service_id, route, upstream_id), _| {
             new_snapshot
                 .services
                 .get(service_id)
                 .map(|svc| {
-                    svc.upstreams
-                        .iter()
-                        .any(|u| u.endpoint.id() == *upstream_id)
+                    let route_keyed =
+                        svc.circuit_breaker_cfg.isolation == CircuitBreakerIsolation::Route;
+                    route.is_some() == route_keyed
+                        && svc
+                            .upstreams
+                            .iter()
+                            .any(|u| u.endpoint.id() == *upstream_id)
+                })
+                .unwrap_or(false)
+        });
+
+        // Cleanup outlier detection state, including services that no longer detect outliers
+        self.outliers.retain(|(service_id, upstream_id), _| {
+            new_snapshot
+                .services
+                .get(service_id)
+                .map(|svc| {
+                    svc.outlier_detection_cfg.enable
+                        && svc
+                            .upstreams
+                            .iter()
+                            .any(|u| u.endpoint.id() == *upstream_id)
                 })
                 .unwrap_or(false)
         });
@@ -194,6 +298,24 @@ impl TrafficManager {
         self.health_params
             .retain(|service_id, _| valid_services.contains(service_id));
 
+        // Cleanup outlier detection parameters
+        self.outlier_params
+            .retain(|service_id, _| valid_services.contains(service_id));
+
+        // Cleanup retry budgets and parameters. Requests in flight keep their budget until
+        // they end, so a budget dropped here is not reused by new requests.
+        self.retry_budgets
+            .retain(|service_id, _| valid_services.contains(service_id));
+        self.retry_params
+            .retain(|service_id, _| valid_services.contains(service_id));
+
+        // Weights set through the admin API are reverted to the configured ones
+        self.weight_overrides.clear();
+
+        // Cleanup hash rings
+        self.ketama_rings
+            .retain(|service_id, _| valid_services.contains(service_id));
+
         for (svc_id, svc) in new_snapshot.services.iter() {
             // Clone circuit breaker params...
             let params = CircuitBreakerParams {
@@ -205,6 +327,17 @@ impl TrafficManager {
                 half_open_max_requests: svc.circuit_breaker_cfg.half_open_max_requests,
                 success_threshold: svc.circuit_breaker_cfg.success_threshold,
                 count_http_5xx_as_failure: svc.circuit_breaker_cfg.count_http_5xx_as_failure,
+                isolation: svc.circuit_breaker_cfg.isolation,
+                open_backoff: svc
+                    .circuit_breaker_cfg
+                    .open_backoff
+                    .as_ref()
+                    .map(|backoff| CircuitBreakerBackoffParams {
+                        multiplier: backoff.multiplier,
+                        max_open_duration: Duration::from_millis(
+                            backoff.max_open_duration_milliseconds,
+                        ),
+                    }),
             };
             self.circuit_params.insert(svc_id.clone(), Arc::new(params));
 
@@ -215,10 +348,55 @@ impl TrafficManager {
                 unhealthy_cooldown: Duration::from_secs(
                     svc.health_check_cfg.unhealthy_cooldown_seconds,
                 ),
+                active: svc.health_check_cfg.active.as_ref().map(|active| {
+                    ActiveHealthCheckParams {
+                        path: active.path.clone(),
+                        interval: Duration::from_millis(active.interval_milliseconds),
+                        timeout: Duration::from_millis(active.timeout_milliseconds),
+                        expected_status: active.expected_status,
+                        failure_threshold: active.failure_threshold,
+                        success_threshold: active.success_threshold,
+                    }
+                }),
             };
 
             self.health_params
                 .insert(svc_id.clone(), Arc::new(health_params));
+
+            // And, clone outlier detection params...
+            let outlier_params = OutlierDetectionParams {
+                enable: svc.outlier_detection_cfg.enable,
+                consecutive_5xx: svc.outlier_detection_cfg.consecutive_5xx,
+                base_ejection_time: Duration::from_millis(
+                    svc.outlier_detection_cfg.base_ejection_time_milliseconds,
+                ),
+                max_ejection_time: Duration::from_millis(
+                    svc.outlier_detection_cfg.max_ejection_time_milliseconds,
+                ),
+            };
+            self.outlier_params
+                .insert(svc_id.clone(), Arc::new(outlier_params));
+
+            // And, clone retry policy params...
+            let retry_params = RetryPolicyParams {
+                enable: svc.retry_policy_cfg.enable,
+                max_retries: svc.retry_policy_cfg.max_retries,
+                retry_on_status: svc.retry_policy_cfg.retry_on_status.clone(),
+                retry_on_connection_error: svc.retry_policy_cfg.retry_on_connection_error,
+                retry_non_idempotent: svc.retry_policy_cfg.retry_non_idempotent,
+                budget_percent: svc.retry_policy_cfg.budget_percent,
+                min_retry_concurrency: svc.retry_policy_cfg.min_retry_concurrency,
+            };
+            self.retry_params
+                .insert(svc_id.clone(), Arc::new(retry_params));
+
+            // And, rebuild the hash ring, as the upstreams may have changed.
+            if matches!(svc.strategy, LoadBalancingStrategy::Ketama) {
+                let ring = KetamaRing::build(&svc.ketama_cfg, &svc.upstreams);
+                self.ketama_rings.insert(svc_id.clone(), Arc::new(ring));
+            } else {
+                self.ketama_rings.remove(svc_id);
+            }
         }
 
         self.snapshot.store(Arc::new(new_snapshot));
@@ -317,6 +495,100 @@ impl TrafficManager {
     }
 }
 
+/// Latency API
+impl TrafficManager {
+    /// Fold a response latency sample into the upstream's moving average.
+    pub fn record_latency(
+        &self,
+        service_id: &ServiceId,
+        upstream_id: &UpstreamId,
+        sample: Duration,
+    ) {
+        self.latency
+            .entry((service_id.clone(), *upstream_id))
+            .and_modify(|stats| {
+                stats.ewma = (stats.ewma * (10 - LATENCY_EWMA_SAMPLE_TENTHS)
+                    + sample * LATENCY_EWMA_SAMPLE_TENTHS)
+                    / 10;
+            })
+            .or_insert(LatencyStats { ewma: sample });
+    }
+
+    /// The upstream's average response latency, `None` until it has answered a request.
+    pub fn latency(&self, service_id: &ServiceId, upstream_id: &UpstreamId) -> Option<Duration> {
+        self.latency
+            .get(&(service_id.clone(), *upstream_id))
+            .map(|stats| stats.ewma)
+    }
+}
+
+/// Hash Ring API
+impl TrafficManager {
+    /// The service's hash ring, `None` unless it uses the ketama strategy.
+    pub fn ketama_ring(&self, service_id: &ServiceId) -> Option<Arc<KetamaRing>> {
+        self.ketama_rings.get(service_id).map(|ring| ring.clone())
+    }
+}
+
+/// Weight API
+impl TrafficManager {
+    /// Override the configured weight of an upstream, effective immediately, until the next
+    /// reload. Returns `false` if the service has no such upstream.
+    pub fn set_upstream_weight(
+        &self,
+        service_id: &ServiceId,
+        upstream_id: &UpstreamId,
+        weight: u32,
+    ) -> bool {
+        let snapshot = self.snapshot();
+        let Some(svc) = snapshot.services.get(service_id) else {
+            return false;
+        };
+        if !svc
+            .upstreams
+            .iter()
+            .any(|u| u.endpoint.id() == *upstream_id)
+        {
+            return false;
+        }
+
+        self.weight_overrides
+            .insert((service_id.clone(), *upstream_id), weight);
+
+        // The ring places points by weight, so it is rebuilt with the new one.
+        if matches!(svc.strategy, LoadBalancingStrategy::Ketama) {
+            let upstreams: Vec<UpstreamSnapshot> = svc
+                .upstreams
+                .iter()
+                .map(|u| UpstreamSnapshot {
+                    weight: self.upstream_weight(service_id, u),
+                    ..u.clone()
+                })
+                .collect();
+            let ring = KetamaRing::build(&svc.ketama_cfg, &upstreams);
+            self.ketama_rings.insert(service_id.clone(), Arc::new(ring));
+        }
+
+        tracing::info!(
+            event = "upstream_weight_changed",
+            service = %service_id,
+            upstream = ?upstream_id,
+            weight
+        );
+
+        true
+    }
+
+    /// The upstream's live weight: the one set through the admin API, if any, or else
+    /// the configured one.
+    pub fn upstream_weight(&self, service_id: &ServiceId, upstream: &UpstreamSnapshot) -> u32 {
+        self.weight_overrides
+            .get(&(service_id.clone(), upstream.endpoint.id()))
+            .map(|w| *w)
+            .unwrap_or(upstream.weight)
+    }
+}
+
 /// Health API
 impl TrafficManager {
     pub fn report_failure(&self, service_id: &ServiceId, upstream_id: &UpstreamId) {
@@ -378,16 +650,31 @@ impl TrafficManager {
             && consecutive_failures >= health_params.failure_threshold
             && let Some(params) = self.circuit_params.get(service_id)
         {
-            let mut cb = self
-                .circuit
-                .entry((service_id.clone(), *upstream_id))
-                .or_default();
-
-            if cb.state() != CircuitState::Open {
-                // Health failures are allowed to force the circuit open,
-                // even when auto-recovery is disabled. In that case, only
-                // health recovery can close it again.
-                cb.trip_open((service_id, upstream_id), &params, "health_failed");
+            // Health failures are allowed to force the circuit open,
+            // even when auto-recovery is disabled. In that case, only
+            // health recovery can close it again.
+            // Health belongs to the upstream, so every route's circuit to it is opened.
+            match params.isolation {
+                CircuitBreakerIsolation::Service => {
+                    let mut cb = self
+                        .circuit
+                        .entry((service_id.clone(), None, *upstream_id))
+                        .or_default();
+                    if cb.state() != CircuitState::Open {
+                        cb.trip_open((service_id, upstream_id), &params, "health_failed");
+                    }
+                }
+                CircuitBreakerIsolation::Route => {
+                    for mut cb in self
+                        .circuit
+                        .iter_mut()
+                        .filter(|cb| cb.key().0 == *service_id && cb.key().2 == *upstream_id)
+                    {
+                        if cb.state() != CircuitState::Open {
+                            cb.trip_open((service_id, upstream_id), &params, "health_failed");
+                        }
+                    }
+                }
             }
         }
     }
@@ -421,6 +708,15 @@ impl TrafficManager {
 
         let key = (service_id.clone(), *upstream_id);
 
+        // Upstreams failing their probes stay out until they pass them again.
+        if self
+            .probe_health
+            .get(&key)
+            .is_some_and(|probe| !probe.healthy)
+        {
+            return HealthStatus { healthy: false };
+        }
+
         let healthy = if let Some(mut entry) = self.upstream_health.get_mut(&key) {
             match &*entry {
                 HealthState::Healthy => true,
@@ -447,16 +743,178 @@ impl TrafficManager {
     }
 }
 
+/// Active Health API
+impl TrafficManager {
+    /// Record the result of probing an upstream. Failing `failure_threshold` probes in a row
+    /// takes it out of rotation, and passing `success_threshold` in a row puts it back.
+    pub fn report_probe(&self, service_id: &ServiceId, upstream_id: &UpstreamId, success: bool) {
+        let Some(health_params) = self.health_params.get(service_id).map(|p| p.clone()) else {
+            return;
+        };
+        let Some(active) = &health_params.active else {
+            return;
+        };
+
+        let mut entry = self
+            .probe_health
+            .entry((service_id.clone(), *upstream_id))
+            .or_insert(ProbeState {
+                healthy: true,
+                consecutive_failures: 0,
+                consecutive_successes: 0,
+            });
+
+        if success {
+            entry.consecutive_failures = 0;
+            entry.consecutive_successes = entry.consecutive_successes.saturating_add(1);
+            if !entry.healthy && entry.consecutive_successes >= active.success_threshold {
+                entry.healthy = true;
+                tracing::info!(
+                    event = "health_transition",
+                    service = %service_id,
+                    upstream = ?upstream_id,
+                    healthy = true,
+                    successes = entry.consecutive_successes
+                );
+            }
+        } else {
+            entry.consecutive_successes = 0;
+            entry.consecutive_failures = entry.consecutive_failures.saturating_add(1);
+            if entry.healthy && entry.consecutive_failures >= active.failure_threshold {
+                entry.healthy = false;
+                tracing::warn!(
+                    event = "health_transition",
+                    service = %service_id,
+                    upstream = ?upstream_id,
+                    healthy = false,
+                    failures = entry.consecutive_failures
+                );
+            }
+        }
+    }
+}
+
+/// Outlier Detection API
+impl TrafficManager {
+    /// Called once per request, with whether the upstream answered with a 5xx or a
+    /// connection error. Ejects the upstream once it reaches the service's threshold,
+    /// unless it is the last upstream of the service still in rotation.
+    pub fn outlier_on_end(&self, service_id: &ServiceId, upstream_id: &UpstreamId, error: bool) {
+        let Some(params) = self.outlier_params.get(service_id).map(|p| p.clone()) else {
+            return;
+        };
+        if !params.enable {
+            return;
+        }
+
+        let key = (service_id.clone(), *upstream_id);
+        let reached_threshold = self
+            .outliers
+            .entry(key.clone())
+            .or_default()
+            .record(&params, error);
+        if !reached_threshold {
+            return;
+        }
+
+        // Ejecting every upstream would fail all requests, which is worse than sending
+        // them to a failing upstream.
+        let others_in_rotation = self
+            .snapshot()
+            .services
+            .get(service_id)
+            .map(|svc| {
+                svc.upstreams
+                    .iter()
+                    .map(|u| u.endpoint.id())
+                    .filter(|id| id != upstream_id && !self.is_ejected(service_id, id))
+                    .count()
+            })
+            .unwrap_or(0);
+        if others_in_rotation == 0 {
+            return;
+        }
+
+        if let Some(mut outlier) = self.outliers.get_mut(&key) {
+            let ejection_time = outlier.eject(&params);
+            tracing::warn!(
+                event = "outlier_ejection",
+                service = %service_id,
+                upstream = ?upstream_id,
+                ejections = outlier.ejections,
+                ejection_time_ms = ejection_time.as_millis() as u64
+            );
+        }
+    }
+
+    /// Whether the upstream is ejected, and must not receive requests.
+    pub fn is_ejected(&self, service_id: &ServiceId, upstream_id: &UpstreamId) -> bool {
+        self.outliers
+            .get(&(service_id.clone(), *upstream_id))
+            .is_some_and(|outlier| outlier.is_ejected())
+    }
+}
+
+/// Retry API
+impl TrafficManager {
+    /// The service's retry policy, if it retries requests.
+    pub fn retry_policy(&self, service_id: &ServiceId) -> Option<Arc<RetryPolicyParams>> {
+        self.retry_params
+            .get(service_id)
+            .map(|p| p.clone())
+            .filter(|p| p.enable)
+    }
+
+    /// Count a request against the service's retry budget, until the returned guard is
+    /// dropped. Retries of the request are taken from the budget through the guard.
+    pub fn track_retry_budget(&self, service_id: &ServiceId) -> Option<RetryBudgetGuard> {
+        self.retry_policy(service_id)?;
+
+        let budget = self
+            .retry_budgets
+            .entry(service_id.clone())
+            .or_default()
+            .clone();
+        Some(budget.track_request())
+    }
+
+    /// Retries of the service's requests in flight.
+    pub fn active_retries(&self, service_id: &ServiceId) -> u32 {
+        self.retry_budgets
+            .get(service_id)
+            .map(|b| b.active_retries())
+            .unwrap_or(0)
+    }
+}
+
 /// Circuit Breaker API
 impl TrafficManager {
+    /// The route part of a circuit key: the route path if the service isolates circuits by route.
+    pub fn circuit_route(&self, service_id: &ServiceId, route: Option<&str>) -> Option<String> {
+        let params = self.circuit_params.get(service_id)?;
+        match params.isolation {
+            CircuitBreakerIsolation::Service => None,
+            CircuitBreakerIsolation::Route => route.map(str::to_string),
+        }
+    }
+
     /// Called by director when selecting an upstream.
-    pub fn circuit_allows(&self, service_id: &ServiceId, upstream_id: &UpstreamId) -> bool {
+    pub fn circuit_allows(
+        &self,
+        service_id: &ServiceId,
+        route: Option<&str>,
+        upstream_id: &UpstreamId,
+    ) -> bool {
         let params = match self.circuit_params.get(service_id) {
             Some(p) => p.clone(),
             None => return true, // fail-open: no config means no circuit
         };
 
-        let key = (service_id.clone(), *upstream_id);
+        let key = (
+            service_id.clone(),
+            self.circuit_route(service_id, route),
+            *upstream_id,
+        );
         let mut entry = self.circuit.entry(key).or_default();
         entry.allow_request((service_id, upstream_id), &params)
     }
@@ -466,6 +924,7 @@ impl TrafficManager {
     pub fn circuit_on_end(
         &self,
         service_id: &ServiceId,
+        route: Option<&str>,
         upstream_id: &UpstreamId,
         started: bool,
         success: bool,
@@ -475,18 +934,51 @@ impl TrafficManager {
             None => return,
         };
 
-        let key = (service_id.clone(), *upstream_id);
+        let key = (
+            service_id.clone(),
+            self.circuit_route(service_id, route),
+            *upstream_id,
+        );
         let mut entry = self.circuit.entry(key).or_default();
         entry.on_request_end((service_id, upstream_id), &params, started, success);
     }
 
-    pub fn circuit_state(&self, service_id: &ServiceId, upstream_id: &UpstreamId) -> CircuitState {
+    pub fn circuit_state(
+        &self,
+        service_id: &ServiceId,
+        route: Option<&str>,
+        upstream_id: &UpstreamId,
+    ) -> CircuitState {
         self.circuit
-            .get(&(service_id.clone(), *upstream_id))
+            .get(&(
+                service_id.clone(),
+                self.circuit_route(service_id, route),
+                *upstream_id,
+            ))
             .map(|c| c.state())
             .unwrap_or(CircuitState::Closed)
     }
 
+    /// The circuit reported for an upstream. Under route isolation that is the most
+    /// severe of its routes' circuits, so an upstream open on any route shows as open.
+    fn upstream_circuit(
+        &self,
+        service_id: &ServiceId,
+        upstream_id: &UpstreamId,
+    ) -> Option<CircuitBreaker> {
+        let severity = |state: CircuitState| match state {
+            CircuitState::Closed => 0,
+            CircuitState::HalfOpen => 1,
+            CircuitState::Open => 2,
+        };
+
+        self.circuit
+            .iter()
+            .filter(|c| c.key().0 == *service_id && c.key().2 == *upstream_id)
+            .max_by_key(|c| severity(c.state()))
+            .map(|c| c.value().clone())
+    }
+
     pub fn total_requests(&self, service_id: &ServiceId, upstream_id: &UpstreamId) -> u32 {
         self.total_requests
             .get(&(service_id.clone(), *upstream_id))
@@ -536,8 +1028,7 @@ impl TrafficManager {
         };
 
         let (circuit_state, circuit_details) = self
-            .circuit
-            .get(&(service_id.clone(), *upstream_id))
+            .upstream_circuit(service_id, upstream_id)
             .map(|c| {
                 let details = if include_details {
                     Some(CircuitBreakerDetailsView {
@@ -545,6 +1036,7 @@ impl TrafficManager {
                         opened_at_rfc3339: c
                             .opened_at_system
                             .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339()),
+                        reopens: c.reopens,
                         half_open_in_flight: c.half_open_in_flight,
                         half_open_successes: c.half_open_successes,
                     })
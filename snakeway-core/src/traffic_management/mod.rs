@@ -3,7 +3,10 @@ pub mod algorithms;
 pub mod circuit;
 mod decision;
 mod director;
+mod health_check;
 mod manager;
+pub mod outlier;
+pub mod retry;
 mod snapshot;
 mod strategy;
 mod types;
@@ -15,6 +18,7 @@ mod tests;
 pub use admission_guard::*;
 pub use decision::SelectedUpstream;
 pub use director::*;
+pub use health_check::ActiveHealthChecker;
 pub use manager::*;
 pub use snapshot::*;
 pub use types::*;
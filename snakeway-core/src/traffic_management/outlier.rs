@@ -0,0 +1,78 @@
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone)]
+pub struct OutlierDetectionParams {
+    pub enable: bool,
+    pub consecutive_5xx: u32,
+    pub base_ejection_time: Duration,
+    pub max_ejection_time: Duration,
+}
+
+/// Passive outlier detection state of an upstream.
+///
+/// Counts the errors the upstream answers in a row, and ejects it from rotation once they reach
+/// the threshold. Repeated ejections back off exponentially, up to the max ejection time.
+#[derive(Debug, Clone, Default)]
+pub struct OutlierDetector {
+    pub(crate) consecutive_errors: u32,
+
+    /// Ejections since the upstream last stayed in rotation for the max ejection time.
+    pub(crate) ejections: u32,
+
+    /// End of the current or last ejection.
+    pub(crate) ejected_until: Option<Instant>,
+}
+
+impl OutlierDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_ejected(&self) -> bool {
+        self.ejected_until
+            .is_some_and(|until| Instant::now() < until)
+    }
+
+    /// Record how a request ended. Returns whether the upstream has reached the error
+    /// threshold, and should be ejected.
+    ///
+    /// Requests still in flight when the upstream was ejected are ignored, so they cannot
+    /// extend the ejection.
+    pub fn record(&mut self, p: &OutlierDetectionParams, error: bool) -> bool {
+        if self.is_ejected() {
+            return false;
+        }
+
+        if error {
+            self.consecutive_errors = self.consecutive_errors.saturating_add(1);
+        } else {
+            self.consecutive_errors = 0;
+        }
+
+        self.consecutive_errors >= p.consecutive_5xx
+    }
+
+    /// Eject the upstream, and return how long for.
+    pub fn eject(&mut self, p: &OutlierDetectionParams) -> Duration {
+        let now = Instant::now();
+
+        // An upstream that behaved for a while after its last ejection starts over.
+        if self
+            .ejected_until
+            .is_some_and(|until| now.saturating_duration_since(until) >= p.max_ejection_time)
+        {
+            self.ejections = 0;
+        }
+
+        let ejection_time = p
+            .base_ejection_time
+            .saturating_mul(2u32.saturating_pow(self.ejections))
+            .min(p.max_ejection_time);
+
+        self.ejections = self.ejections.saturating_add(1);
+        self.ejected_until = Some(now + ejection_time);
+        self.consecutive_errors = 0;
+
+        ejection_time
+    }
+}
@@ -0,0 +1,106 @@
+use http::Method;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+#[derive(Debug, Clone)]
+pub struct RetryPolicyParams {
+    pub enable: bool,
+    pub max_retries: u32,
+    pub retry_on_status: Vec<u16>,
+    pub retry_on_connection_error: bool,
+    pub retry_non_idempotent: bool,
+    pub budget_percent: u32,
+    pub min_retry_concurrency: u32,
+}
+
+impl RetryPolicyParams {
+    /// Whether requests with this method may be retried. Only idempotent methods are,
+    /// unless the policy retries non-idempotent ones too.
+    pub fn retries_method(&self, method: &Method) -> bool {
+        self.retry_non_idempotent || is_idempotent(method)
+    }
+
+    pub fn retries_status(&self, status: u16) -> bool {
+        self.retry_on_status.contains(&status)
+    }
+}
+
+/// Methods a request may be sent with more than once, with the same effect as sending it once.
+fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE | Method::PUT | Method::DELETE
+    )
+}
+
+/// Retry budget of a service: the requests and retries it has in flight.
+///
+/// Retries in flight are capped at a percentage of the requests in flight, so retries can add
+/// at most that much load to a service that fails every request.
+#[derive(Debug, Default)]
+pub struct RetryBudget {
+    active_requests: AtomicU32,
+    active_retries: AtomicU32,
+}
+
+impl RetryBudget {
+    /// Count a request against the budget, until the returned guard is dropped.
+    pub fn track_request(self: &Arc<Self>) -> RetryBudgetGuard {
+        self.active_requests.fetch_add(1, Ordering::Relaxed);
+
+        RetryBudgetGuard {
+            budget: self.clone(),
+            retries: 0,
+        }
+    }
+
+    /// Take a retry from the budget, if one is left.
+    fn try_start_retry(&self, p: &RetryPolicyParams) -> bool {
+        let active_requests = u64::from(self.active_requests.load(Ordering::Relaxed));
+        let budget = (active_requests * u64::from(p.budget_percent) / 100)
+            .max(u64::from(p.min_retry_concurrency));
+
+        self.active_retries
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |retries| {
+                (u64::from(retries) < budget).then_some(retries + 1)
+            })
+            .is_ok()
+    }
+
+    pub fn active_retries(&self) -> u32 {
+        self.active_retries.load(Ordering::Relaxed)
+    }
+}
+
+/// Counts a request, and the retries it was allowed, against its service's retry budget
+/// until dropped.
+#[derive(Debug)]
+pub struct RetryBudgetGuard {
+    budget: Arc<RetryBudget>,
+    retries: u32,
+}
+
+impl RetryBudgetGuard {
+    /// Take a retry of this request from the budget, if the policy allows another one.
+    pub fn try_retry(&mut self, p: &RetryPolicyParams) -> bool {
+        if self.retries >= p.max_retries || !self.budget.try_start_retry(p) {
+            return false;
+        }
+
+        self.retries += 1;
+        true
+    }
+
+    pub fn retries(&self) -> u32 {
+        self.retries
+    }
+}
+
+impl Drop for RetryBudgetGuard {
+    fn drop(&mut self) {
+        self.budget.active_requests.fetch_sub(1, Ordering::Relaxed);
+        self.budget
+            .active_retries
+            .fetch_sub(self.retries, Ordering::Relaxed);
+    }
+}
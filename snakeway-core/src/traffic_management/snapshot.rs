@@ -17,6 +17,9 @@ pub struct ServiceSnapshot {
     pub upstreams: Vec<UpstreamSnapshot>,
     pub circuit_breaker_cfg: crate::conf::types::CircuitBreakerConfig,
     pub health_check_cfg: crate::conf::types::HealthCheckConfig,
+    pub ketama_cfg: crate::conf::types::KetamaConfig,
+    pub outlier_detection_cfg: crate::conf::types::OutlierDetectionConfig,
+    pub retry_policy_cfg: crate::conf::types::RetryPolicyConfig,
 }
 
 /// Immutable, control-plane snapshot of traffic topology and health.
@@ -51,6 +54,9 @@ impl TrafficSnapshot {
                     upstreams,
                     circuit_breaker_cfg: svc.circuit_breaker_cfg.clone(),
                     health_check_cfg: svc.health_check_cfg.clone(),
+                    ketama_cfg: svc.ketama_cfg.clone(),
+                    outlier_detection_cfg: svc.outlier_detection_cfg.clone(),
+                    retry_policy_cfg: svc.retry_policy_cfg.clone(),
                 },
             );
         }
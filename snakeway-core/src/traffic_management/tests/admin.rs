@@ -20,9 +20,12 @@ fn test_admin_view_counters() {
                     id: upstream_id,
                     host: "127.0.0.1".into(),
                     port: 8080,
+                    hostname: None,
                     use_tls: false,
                     sni: "localhost".into(),
                     weight: 1,
+                    connection: Default::default(),
+                    group: None,
                 }),
                 latency: None,
                 weight: 1,
@@ -32,6 +35,9 @@ fn test_admin_view_counters() {
                 enable: true,
                 ..Default::default()
             },
+            ketama_cfg: Default::default(),
+            outlier_detection_cfg: Default::default(),
+            retry_policy_cfg: Default::default(),
         },
     );
 
@@ -70,9 +76,12 @@ fn test_admin_view_circuit_details() {
                     id: upstream_id,
                     host: "127.0.0.1".into(),
                     port: 8080,
+                    hostname: None,
                     use_tls: false,
                     sni: "localhost".into(),
                     weight: 1,
+                    connection: Default::default(),
+                    group: None,
                 }),
                 latency: None,
                 weight: 1,
@@ -83,6 +92,9 @@ fn test_admin_view_circuit_details() {
                 ..Default::default()
             },
             health_check_cfg: Default::default(),
+            ketama_cfg: Default::default(),
+            outlier_detection_cfg: Default::default(),
+            retry_policy_cfg: Default::default(),
         },
     );
 
@@ -91,8 +103,8 @@ fn test_admin_view_circuit_details() {
     manager.update(snapshot); // To populate circuit_params
 
     // Trip the circuit
-    manager.circuit_on_end(&service_id, &upstream_id, true, false);
-    manager.circuit_on_end(&service_id, &upstream_id, true, false);
+    manager.circuit_on_end(&service_id, None, &upstream_id, true, false);
+    manager.circuit_on_end(&service_id, None, &upstream_id, true, false);
 
     let view = manager.get_upstream_view(&service_id, &upstream_id, true);
 
@@ -125,15 +137,21 @@ fn test_metrics_persistence_on_reload() {
                     id: upstream_id,
                     host: "127.0.0.1".into(),
                     port: 8080,
+                    hostname: None,
                     use_tls: false,
                     sni: "localhost".into(),
                     weight: 1,
+                    connection: Default::default(),
+                    group: None,
                 }),
                 latency: None,
                 weight: 1,
             }],
             circuit_breaker_cfg: Default::default(),
             health_check_cfg: Default::default(),
+            ketama_cfg: Default::default(),
+            outlier_detection_cfg: Default::default(),
+            retry_policy_cfg: Default::default(),
         },
     );
 
@@ -168,15 +186,21 @@ fn test_metrics_persistence_on_reload() {
                     id: upstream_id2,
                     host: "127.0.0.1".into(),
                     port: 8081,
+                    hostname: None,
                     use_tls: false,
                     sni: "localhost".into(),
                     weight: 1,
+                    connection: Default::default(),
+                    group: None,
                 }),
                 latency: None,
                 weight: 1,
             }],
             circuit_breaker_cfg: Default::default(),
             health_check_cfg: Default::default(),
+            ketama_cfg: Default::default(),
+            outlier_detection_cfg: Default::default(),
+            retry_policy_cfg: Default::default(),
         },
     );
     manager.update(TrafficSnapshot {
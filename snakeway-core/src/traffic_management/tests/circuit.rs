@@ -11,9 +11,28 @@ fn params() -> CircuitBreakerParams {
         half_open_max_requests: 1,
         success_threshold: 2,
         count_http_5xx_as_failure: true,
+        isolation: Default::default(),
+        open_backoff: None,
     }
 }
 
+fn backoff_params() -> CircuitBreakerParams {
+    CircuitBreakerParams {
+        open_backoff: Some(CircuitBreakerBackoffParams {
+            multiplier: 2,
+            max_open_duration: Duration::from_millis(300),
+        }),
+        ..params()
+    }
+}
+
+fn trip(cb: &mut CircuitBreaker, ids: (&ServiceId, &UpstreamId), p: &CircuitBreakerParams) {
+    for _ in 0..p.failure_threshold {
+        cb.on_request_end(ids, p, true, false);
+    }
+    assert_eq!(cb.state(), CircuitState::Open);
+}
+
 fn ids() -> (ServiceId, UpstreamId) {
     (ServiceId("test".into()), UpstreamId(1))
 }
@@ -129,3 +148,92 @@ fn test_cb_disabled() {
     assert_eq!(cb.state(), CircuitState::Closed);
     assert!(cb.allow_request(ids_ref, &p));
 }
+
+#[test]
+fn test_cb_half_open_admits_exactly_success_threshold_probes() {
+    let mut cb = CircuitBreaker::new();
+    let mut p = params();
+    p.half_open_max_requests = 5;
+    let ids = ids();
+    let ids_ref = (&ids.0, &ids.1);
+
+    trip(&mut cb, ids_ref, &p);
+    std::thread::sleep(Duration::from_millis(110));
+
+    // Two probes close the circuit, so a third is denied even with room for five in flight.
+    assert!(cb.allow_request(ids_ref, &p));
+    assert!(cb.allow_request(ids_ref, &p));
+    assert!(!cb.allow_request(ids_ref, &p));
+    assert_eq!(cb.state(), CircuitState::HalfOpen);
+
+    cb.on_request_end(ids_ref, &p, true, true);
+    assert!(!cb.allow_request(ids_ref, &p));
+    cb.on_request_end(ids_ref, &p, true, true);
+    assert_eq!(cb.state(), CircuitState::Closed);
+}
+
+#[test]
+fn test_cb_full_cycle_closes_and_starts_over() {
+    let mut cb = CircuitBreaker::new();
+    let p = backoff_params();
+    let ids = ids();
+    let ids_ref = (&ids.0, &ids.1);
+
+    // Closed -> Open
+    trip(&mut cb, ids_ref, &p);
+
+    // Open -> HalfOpen -> Open, for twice as long
+    std::thread::sleep(Duration::from_millis(110));
+    assert!(cb.allow_request(ids_ref, &p));
+    cb.on_request_end(ids_ref, &p, true, false);
+    assert_eq!(cb.state(), CircuitState::Open);
+    assert_eq!(cb.reopens, 1);
+
+    // Open -> HalfOpen -> Closed
+    std::thread::sleep(Duration::from_millis(210));
+    assert!(cb.allow_request(ids_ref, &p));
+    cb.on_request_end(ids_ref, &p, true, true);
+    assert!(cb.allow_request(ids_ref, &p));
+    cb.on_request_end(ids_ref, &p, true, true);
+    assert_eq!(cb.state(), CircuitState::Closed);
+    assert_eq!(cb.reopens, 0);
+
+    // Tripping again opens for the base open duration.
+    trip(&mut cb, ids_ref, &p);
+    std::thread::sleep(Duration::from_millis(110));
+    assert!(cb.allow_request(ids_ref, &p));
+    assert_eq!(cb.state(), CircuitState::HalfOpen);
+}
+
+#[test]
+fn test_cb_failed_probes_back_off_open_duration() {
+    let mut cb = CircuitBreaker::new();
+    let p = backoff_params();
+    let ids = ids();
+    let ids_ref = (&ids.0, &ids.1);
+
+    trip(&mut cb, ids_ref, &p);
+    std::thread::sleep(Duration::from_millis(110));
+
+    // A failed probe re-opens the circuit for 200ms.
+    assert!(cb.allow_request(ids_ref, &p));
+    cb.on_request_end(ids_ref, &p, true, false);
+    assert_eq!(cb.state(), CircuitState::Open);
+
+    std::thread::sleep(Duration::from_millis(110));
+    assert!(!cb.allow_request(ids_ref, &p));
+    std::thread::sleep(Duration::from_millis(100));
+    assert!(cb.allow_request(ids_ref, &p));
+    assert_eq!(cb.state(), CircuitState::HalfOpen);
+}
+
+#[test]
+fn test_cb_open_duration_backoff_is_capped() {
+    let p = backoff_params();
+
+    assert_eq!(p.open_duration_after(0), Duration::from_millis(100));
+    assert_eq!(p.open_duration_after(1), Duration::from_millis(200));
+    assert_eq!(p.open_duration_after(2), Duration::from_millis(300));
+    assert_eq!(p.open_duration_after(40), Duration::from_millis(300));
+    assert_eq!(params().open_duration_after(3), Duration::from_millis(100));
+}
@@ -1,6 +1,10 @@
-use crate::conf::types::LoadBalancingStrategy;
+use crate::conf::types::{
+    CircuitBreakerIsolation, KetamaConfig, LoadBalancingStrategy, OutlierDetectionConfig,
+};
 use crate::ctx::{NormalizedPath, RequestCtx};
-use crate::runtime::{UpstreamId, UpstreamRuntime, UpstreamTcpRuntime};
+use crate::route::RouteId;
+use crate::runtime::{UpstreamId, UpstreamRuntime, UpstreamTcpRuntime, UpstreamUnixRuntime};
+use crate::traffic_management::algorithms::{KetamaRing, StickyHash};
 use crate::traffic_management::circuit::CircuitBreakerParams;
 use crate::traffic_management::decision::TrafficDecision;
 use crate::traffic_management::strategy::TrafficStrategy;
@@ -11,9 +15,11 @@ use crate::traffic_management::{
     snapshot::{ServiceSnapshot, TrafficSnapshot, UpstreamSnapshot},
     types::*,
 };
-use std::collections::HashMap;
+use http::{HeaderMap, HeaderValue, Method, Version, header};
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, Ipv4Addr};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 // ---------------------------
 // Helpers
@@ -26,15 +32,92 @@ fn dummy_request() -> RequestCtx {
     ctx
 }
 
+fn routed_request(route: &str) -> RequestCtx {
+    let mut ctx = dummy_request();
+    ctx.route_id = Some(RouteId::service(route, "svc"));
+    ctx
+}
+
+/// A WebSocket upgrade from `peer_ip`, optionally carrying a `Cookie` header.
+fn ws_upgrade(peer_ip: IpAddr, cookie: Option<&'static str>) -> RequestCtx {
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CONNECTION, HeaderValue::from_static("Upgrade"));
+    headers.insert(header::UPGRADE, HeaderValue::from_static("websocket"));
+    if let Some(cookie) = cookie {
+        headers.insert(header::COOKIE, HeaderValue::from_static(cookie));
+    }
+
+    let mut ctx = RequestCtx::empty();
+    ctx.hydrate(
+        &"/ws".parse().unwrap(),
+        &Method::GET,
+        &headers,
+        &Version::HTTP_11,
+        true,
+        peer_ip,
+    )
+    .unwrap();
+    ctx
+}
+
+/// A GET from `peer_ip`, carrying `X-Cache-Key` when a key is given.
+fn keyed_request(peer_ip: IpAddr, key: Option<&'static str>) -> RequestCtx {
+    let mut headers = HeaderMap::new();
+    if let Some(key) = key {
+        headers.insert("x-cache-key", HeaderValue::from_static(key));
+    }
+
+    let mut ctx = RequestCtx::empty();
+    ctx.hydrate(
+        &"/".parse().unwrap(),
+        &Method::GET,
+        &headers,
+        &Version::HTTP_11,
+        false,
+        peer_ip,
+    )
+    .unwrap();
+    ctx
+}
+
 fn upstream(id: u16) -> UpstreamSnapshot {
     UpstreamSnapshot {
         endpoint: UpstreamRuntime::Tcp(UpstreamTcpRuntime {
             id: UpstreamId(id as u32),
             host: "127.0.0.1".to_string(),
             port: id,
+            hostname: None,
+            use_tls: false,
+            sni: "localhost".to_string(),
+            weight: 1,
+            connection: Default::default(),
+            group: None,
+        }),
+        latency: Some(LatencyStats {
+            ewma: Duration::from_millis(10),
+        }),
+        weight: 1,
+    }
+}
+
+fn grouped_upstream(id: u16, group: &str) -> UpstreamSnapshot {
+    let mut upstream = upstream(id);
+    if let UpstreamRuntime::Tcp(tcp) = &mut upstream.endpoint {
+        tcp.group = Some(group.to_string());
+    }
+    upstream
+}
+
+fn unix_upstream(id: u16) -> UpstreamSnapshot {
+    UpstreamSnapshot {
+        endpoint: UpstreamRuntime::Unix(UpstreamUnixRuntime {
+            id: UpstreamId(id as u32),
+            path: format!("/tmp/snakeway-{id}.sock"),
             use_tls: false,
             sni: "localhost".to_string(),
             weight: 1,
+            connection: Default::default(),
+            group: None,
         }),
         latency: Some(LatencyStats {
             ewma: Duration::from_millis(10),
@@ -62,14 +145,46 @@ fn snapshot_with_service(
                 half_open_max_requests: 1,
                 success_threshold: 2,
                 count_http_5xx_as_failure: true,
+                isolation: CircuitBreakerIsolation::Service,
+                open_backoff: None,
             },
             health_check_cfg: crate::conf::types::HealthCheckConfig::default(),
+            ketama_cfg: Default::default(),
+            outlier_detection_cfg: Default::default(),
+            retry_policy_cfg: Default::default(),
         },
     );
 
     TrafficSnapshot { services }
 }
 
+/// A failover service over upstreams 1 and 2, with circuits kept per `isolation`.
+fn failover_manager(
+    service_id: &ServiceId,
+    isolation: CircuitBreakerIsolation,
+) -> (TrafficSnapshot, TrafficManager) {
+    let mut snapshot = snapshot_with_service(
+        service_id.clone(),
+        vec![upstream(1), upstream(2)],
+        LoadBalancingStrategy::Failover,
+    );
+    snapshot
+        .services
+        .get_mut(service_id)
+        .unwrap()
+        .circuit_breaker_cfg
+        .isolation = isolation;
+    let manager = TrafficManager::new(snapshot.clone());
+    (snapshot, manager)
+}
+
+/// Fail enough requests on a route to open upstream 1's circuit.
+fn trip_upstream_1(manager: &TrafficManager, service_id: &ServiceId, route: &str) {
+    for _ in 0..3 {
+        manager.circuit_on_end(service_id, Some(route), &UpstreamId(1), true, false);
+    }
+}
+
 /// ---------------------------
 /// Tests
 /// ---------------------------
@@ -109,6 +224,7 @@ fn no_healthy_upstreams_returns_error() {
             enable: true,
             failure_threshold: 3,
             unhealthy_cooldown: Duration::from_secs(10),
+            active: None,
         }),
     );
     let director = TrafficDirector;
@@ -145,6 +261,7 @@ fn single_healthy_upstream_is_selected() {
             enable: true,
             failure_threshold: 3,
             unhealthy_cooldown: Duration::from_secs(10),
+            active: None,
         }),
     );
     let director = TrafficDirector;
@@ -163,6 +280,165 @@ fn single_healthy_upstream_is_selected() {
     assert_eq!(decision.upstream_id, UpstreamId(2));
 }
 
+#[test]
+fn upstream_failing_active_probes_is_skipped_until_it_passes_them_again() {
+    // Arrange
+    let service_id = ServiceId("svc".into());
+    let snapshot = snapshot_with_service(
+        service_id.clone(),
+        vec![upstream(1), upstream(2)],
+        LoadBalancingStrategy::Failover,
+    );
+    let manager = TrafficManager::new(snapshot.clone());
+    manager.health_params.insert(
+        service_id.clone(),
+        Arc::new(HealthCheckParams {
+            enable: true,
+            failure_threshold: 3,
+            unhealthy_cooldown: Duration::from_secs(10),
+            active: Some(ActiveHealthCheckParams {
+                path: "/healthz".to_string(),
+                interval: Duration::from_secs(1),
+                timeout: Duration::from_millis(500),
+                expected_status: 200,
+                failure_threshold: 2,
+                success_threshold: 2,
+            }),
+        }),
+    );
+    let director = TrafficDirector;
+    let decide = || {
+        director
+            .decide(&dummy_request(), &snapshot, &service_id, &manager)
+            .expect("decision")
+            .upstream_id
+    };
+
+    // Act
+    manager.report_probe(&service_id, &UpstreamId(1), false);
+    let below_failure_threshold = decide();
+    manager.report_probe(&service_id, &UpstreamId(1), false);
+    let failed = decide();
+    manager.report_probe(&service_id, &UpstreamId(1), true);
+    let below_success_threshold = decide();
+    manager.report_probe(&service_id, &UpstreamId(1), true);
+    let recovered = decide();
+
+    // Assert
+    assert_eq!(below_failure_threshold, UpstreamId(1));
+    assert_eq!(failed, UpstreamId(2));
+    assert_eq!(below_success_threshold, UpstreamId(2));
+    assert_eq!(recovered, UpstreamId(1));
+}
+
+/// A failover service over the given upstreams, ejecting upstreams after 3 errors in a row.
+fn outlier_manager(
+    service_id: &ServiceId,
+    upstreams: Vec<UpstreamSnapshot>,
+) -> (TrafficSnapshot, TrafficManager) {
+    let mut snapshot = snapshot_with_service(
+        service_id.clone(),
+        upstreams,
+        LoadBalancingStrategy::Failover,
+    );
+    snapshot
+        .services
+        .get_mut(service_id)
+        .unwrap()
+        .outlier_detection_cfg = OutlierDetectionConfig {
+        enable: true,
+        consecutive_5xx: 3,
+        base_ejection_time_milliseconds: 100,
+        max_ejection_time_milliseconds: 1000,
+    };
+    let manager = TrafficManager::new(snapshot.clone());
+    (snapshot, manager)
+}
+
+#[test]
+fn error_burst_ejects_upstream_until_its_ejection_time_passes() {
+    // Arrange
+    let service_id = ServiceId("svc".into());
+    let (snapshot, manager) = outlier_manager(&service_id, vec![upstream(1), upstream(2)]);
+    let director = TrafficDirector;
+    let decide = || {
+        director
+            .decide(&dummy_request(), &snapshot, &service_id, &manager)
+            .expect("decision")
+            .upstream_id
+    };
+
+    // Act
+    manager.outlier_on_end(&service_id, &UpstreamId(1), true);
+    manager.outlier_on_end(&service_id, &UpstreamId(1), true);
+    let below_threshold = decide();
+    manager.outlier_on_end(&service_id, &UpstreamId(1), true);
+    let ejected = decide();
+    std::thread::sleep(Duration::from_millis(120));
+    let recovered = decide();
+
+    // Assert
+    assert_eq!(below_threshold, UpstreamId(1));
+    assert_eq!(ejected, UpstreamId(2));
+    assert_eq!(recovered, UpstreamId(1));
+}
+
+#[test]
+fn successful_response_resets_the_error_count() {
+    // Arrange
+    let service_id = ServiceId("svc".into());
+    let (_, manager) = outlier_manager(&service_id, vec![upstream(1), upstream(2)]);
+
+    // Act
+    manager.outlier_on_end(&service_id, &UpstreamId(1), true);
+    manager.outlier_on_end(&service_id, &UpstreamId(1), true);
+    manager.outlier_on_end(&service_id, &UpstreamId(1), false);
+    manager.outlier_on_end(&service_id, &UpstreamId(1), true);
+    manager.outlier_on_end(&service_id, &UpstreamId(1), true);
+
+    // Assert
+    assert!(!manager.is_ejected(&service_id, &UpstreamId(1)));
+}
+
+#[test]
+fn last_upstream_in_rotation_is_never_ejected() {
+    // Arrange
+    let service_id = ServiceId("svc".into());
+    let (_, manager) = outlier_manager(&service_id, vec![upstream(1), upstream(2)]);
+
+    // Act
+    for _ in 0..3 {
+        manager.outlier_on_end(&service_id, &UpstreamId(1), true);
+    }
+    for _ in 0..3 {
+        manager.outlier_on_end(&service_id, &UpstreamId(2), true);
+    }
+
+    // Assert
+    assert!(manager.is_ejected(&service_id, &UpstreamId(1)));
+    assert!(!manager.is_ejected(&service_id, &UpstreamId(2)));
+}
+
+#[test]
+fn errors_are_ignored_when_outlier_detection_is_disabled() {
+    // Arrange
+    let service_id = ServiceId("svc".into());
+    let snapshot = snapshot_with_service(
+        service_id.clone(),
+        vec![upstream(1), upstream(2)],
+        LoadBalancingStrategy::Failover,
+    );
+    let manager = TrafficManager::new(snapshot);
+
+    // Act
+    for _ in 0..10 {
+        manager.outlier_on_end(&service_id, &UpstreamId(1), true);
+    }
+
+    // Assert
+    assert!(!manager.is_ejected(&service_id, &UpstreamId(1)));
+}
+
 #[test]
 fn strategy_decision_is_respected() {
     // Arrange
@@ -275,15 +551,17 @@ fn director_respects_circuit_breaker() {
         half_open_max_requests: svc_snapshot.circuit_breaker_cfg.half_open_max_requests,
         success_threshold: svc_snapshot.circuit_breaker_cfg.success_threshold,
         count_http_5xx_as_failure: svc_snapshot.circuit_breaker_cfg.count_http_5xx_as_failure,
+        isolation: svc_snapshot.circuit_breaker_cfg.isolation,
+        open_backoff: None,
     };
     manager
         .circuit_params
         .insert(service_id.clone(), std::sync::Arc::new(params));
 
     // Trip circuit for upstream 1
-    manager.circuit_on_end(&service_id, &UpstreamId(1), true, false);
-    manager.circuit_on_end(&service_id, &UpstreamId(1), true, false);
-    manager.circuit_on_end(&service_id, &UpstreamId(1), true, false);
+    manager.circuit_on_end(&service_id, None, &UpstreamId(1), true, false);
+    manager.circuit_on_end(&service_id, None, &UpstreamId(1), true, false);
+    manager.circuit_on_end(&service_id, None, &UpstreamId(1), true, false);
 
     // Act
     let decision = director
@@ -294,3 +572,731 @@ fn director_respects_circuit_breaker() {
     // Should pick upstream 2 because 1's circuit is open
     assert_eq!(decision.upstream_id, UpstreamId(2));
 }
+
+#[test]
+fn half_open_circuit_admits_exactly_success_threshold_probes_across_threads() {
+    // Arrange
+    let service_id = ServiceId("svc".into());
+    let snapshot = snapshot_with_service(
+        service_id.clone(),
+        vec![upstream(1)],
+        LoadBalancingStrategy::RoundRobin,
+    );
+    let manager = TrafficManager::new(snapshot);
+    let params = CircuitBreakerParams {
+        enable_auto_recovery: true,
+        failure_threshold: 1,
+        open_duration: Duration::from_millis(10),
+        half_open_max_requests: 16,
+        success_threshold: 3,
+        count_http_5xx_as_failure: true,
+        isolation: CircuitBreakerIsolation::Service,
+        open_backoff: None,
+    };
+    manager
+        .circuit_params
+        .insert(service_id.clone(), Arc::new(params));
+    manager.circuit_on_end(&service_id, None, &UpstreamId(1), true, false);
+    std::thread::sleep(Duration::from_millis(20));
+
+    // Act
+    let admitted = std::thread::scope(|scope| {
+        let probes: Vec<_> = (0..16)
+            .map(|_| scope.spawn(|| manager.circuit_allows(&service_id, None, &UpstreamId(1))))
+            .collect();
+        probes
+            .into_iter()
+            .filter(|probe| probe.join().expect("probe thread"))
+            .count()
+    });
+
+    // Assert
+    assert_eq!(admitted, 3);
+}
+
+#[test]
+fn upstream_group_selects_grouped_upstreams() {
+    // Arrange
+    let service_id = ServiceId("svc".into());
+    let snapshot = snapshot_with_service(
+        service_id.clone(),
+        vec![upstream(1), grouped_upstream(2, "premium")],
+        LoadBalancingStrategy::Failover,
+    );
+    let manager = TrafficManager::new(snapshot.clone());
+    let director = TrafficDirector;
+    let mut req = dummy_request();
+    req.upstream_group = Some("premium".to_string());
+
+    // Act
+    let decision = director
+        .decide(&req, &snapshot, &service_id, &manager)
+        .expect("decision");
+
+    // Assert
+    assert_eq!(decision.upstream_id, UpstreamId(2));
+}
+
+#[test]
+fn requests_without_upstream_group_use_default_pool() {
+    // Arrange
+    let service_id = ServiceId("svc".into());
+    let snapshot = snapshot_with_service(
+        service_id.clone(),
+        vec![grouped_upstream(1, "premium"), upstream(2)],
+        LoadBalancingStrategy::Failover,
+    );
+    let manager = TrafficManager::new(snapshot.clone());
+    let director = TrafficDirector;
+
+    // Act
+    let decision = director
+        .decide(&dummy_request(), &snapshot, &service_id, &manager)
+        .expect("decision");
+
+    // Assert
+    assert_eq!(decision.upstream_id, UpstreamId(2));
+}
+
+#[test]
+fn unknown_upstream_group_falls_back_to_default_pool() {
+    // Arrange
+    let service_id = ServiceId("svc".into());
+    let snapshot = snapshot_with_service(
+        service_id.clone(),
+        vec![grouped_upstream(1, "premium"), upstream(2)],
+        LoadBalancingStrategy::Failover,
+    );
+    let manager = TrafficManager::new(snapshot.clone());
+    let director = TrafficDirector;
+    let mut req = dummy_request();
+    req.upstream_group = Some("enterprise".to_string());
+
+    // Act
+    let decision = director
+        .decide(&req, &snapshot, &service_id, &manager)
+        .expect("decision");
+
+    // Assert
+    assert_eq!(decision.upstream_id, UpstreamId(2));
+}
+
+#[test]
+fn unhealthy_unix_upstream_is_ejected_like_tcp() {
+    // Arrange
+    let service_id = ServiceId("svc".into());
+    let snapshot = snapshot_with_service(
+        service_id.clone(),
+        vec![unix_upstream(1), upstream(2)],
+        LoadBalancingStrategy::Failover,
+    );
+    let manager = TrafficManager::new(snapshot.clone());
+    manager.health_params.insert(
+        service_id.clone(),
+        Arc::new(HealthCheckParams {
+            enable: true,
+            failure_threshold: 3,
+            unhealthy_cooldown: Duration::from_secs(10),
+            active: None,
+        }),
+    );
+    let director = TrafficDirector;
+
+    // Mark the unix upstream unhealthy
+    manager.report_failure(&service_id, &UpstreamId(1));
+    manager.report_failure(&service_id, &UpstreamId(1));
+    manager.report_failure(&service_id, &UpstreamId(1));
+
+    // Act
+    let decision = director
+        .decide(&dummy_request(), &snapshot, &service_id, &manager)
+        .expect("decision");
+
+    // Assert
+    assert_eq!(decision.upstream_id, UpstreamId(2));
+}
+
+#[test]
+fn route_isolated_circuit_does_not_affect_other_routes() {
+    // Arrange
+    let service_id = ServiceId("svc".into());
+    let (snapshot, manager) = failover_manager(&service_id, CircuitBreakerIsolation::Route);
+    let director = TrafficDirector;
+    trip_upstream_1(&manager, &service_id, "/api");
+
+    // Act
+    let tripped = director
+        .decide(&routed_request("/api"), &snapshot, &service_id, &manager)
+        .expect("decision");
+    let other = director
+        .decide(&routed_request("/admin"), &snapshot, &service_id, &manager)
+        .expect("decision");
+
+    // Assert
+    assert_eq!(tripped.upstream_id, UpstreamId(2));
+    assert_eq!(other.upstream_id, UpstreamId(1));
+}
+
+#[test]
+fn service_shared_circuit_affects_every_route() {
+    // Arrange
+    let service_id = ServiceId("svc".into());
+    let (snapshot, manager) = failover_manager(&service_id, CircuitBreakerIsolation::Service);
+    let director = TrafficDirector;
+    trip_upstream_1(&manager, &service_id, "/api");
+
+    // Act
+    let tripped = director
+        .decide(&routed_request("/api"), &snapshot, &service_id, &manager)
+        .expect("decision");
+    let other = director
+        .decide(&routed_request("/admin"), &snapshot, &service_id, &manager)
+        .expect("decision");
+
+    // Assert
+    assert_eq!(tripped.upstream_id, UpstreamId(2));
+    assert_eq!(other.upstream_id, UpstreamId(2));
+}
+
+#[test]
+fn admin_view_reports_most_severe_route_circuit() {
+    // Arrange
+    let service_id = ServiceId("svc".into());
+    let (_, manager) = failover_manager(&service_id, CircuitBreakerIsolation::Route);
+    manager.circuit_on_end(&service_id, Some("/admin"), &UpstreamId(1), true, true);
+    trip_upstream_1(&manager, &service_id, "/api");
+
+    // Act
+    let view = manager.get_upstream_view(&service_id, &UpstreamId(1), false);
+
+    // Assert
+    assert_eq!(
+        view.circuit,
+        crate::traffic_management::circuit::CircuitState::Open
+    );
+}
+
+#[test]
+fn repeated_ws_upgrades_from_one_client_hit_the_same_upstream() {
+    // Arrange
+    let service_id = ServiceId("svc".into());
+    let snapshot = snapshot_with_service(
+        service_id.clone(),
+        (1..=4).map(upstream).collect(),
+        LoadBalancingStrategy::StickyHash,
+    );
+    let manager = TrafficManager::new(snapshot.clone());
+    let director = TrafficDirector;
+    let client = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 7));
+
+    // Act
+    let decisions: Vec<_> = (0..5)
+        .map(|_| {
+            director
+                .decide(&ws_upgrade(client, None), &snapshot, &service_id, &manager)
+                .expect("decision")
+        })
+        .collect();
+
+    // Assert
+    assert!(
+        decisions
+            .iter()
+            .all(|d| d.upstream_id == decisions[0].upstream_id)
+    );
+    assert!(
+        decisions
+            .iter()
+            .all(|d| d.reason == DecisionReason::StickyHash)
+    );
+}
+
+#[test]
+fn sticky_cookie_pins_ws_upgrades_across_client_addresses() {
+    // Arrange
+    let service_id = ServiceId("svc".into());
+    let snapshot = snapshot_with_service(
+        service_id.clone(),
+        (1..=4).map(upstream).collect(),
+        LoadBalancingStrategy::StickyHash,
+    );
+    let manager = TrafficManager::new(snapshot.clone());
+    let director = TrafficDirector;
+    let cookie = Some("theme=dark; snakeway_sticky=session-42");
+
+    // Act
+    let upstreams: Vec<_> = (1..=5)
+        .map(|host| {
+            let client = IpAddr::V4(Ipv4Addr::new(10, 0, 0, host));
+            director
+                .decide(
+                    &ws_upgrade(client, cookie),
+                    &snapshot,
+                    &service_id,
+                    &manager,
+                )
+                .expect("decision")
+                .upstream_id
+        })
+        .collect();
+
+    // Assert
+    assert!(upstreams.iter().all(|id| *id == upstreams[0]));
+}
+
+/// Upstream the sticky hash strategy picks for each of 32 clients, as of `now`.
+fn sticky_picks(ttl: Option<Duration>, now: SystemTime) -> Vec<UpstreamId> {
+    (1..=32).map(|host| sticky_pick(host, ttl, now)).collect()
+}
+
+/// Upstream the sticky hash strategy picks for client `10.0.0.<host>`, as of `now`.
+fn sticky_pick(host: u8, ttl: Option<Duration>, now: SystemTime) -> UpstreamId {
+    let upstreams: Vec<_> = (1..=4).map(upstream).collect();
+    let mut req = ws_upgrade(IpAddr::V4(Ipv4Addr::new(10, 0, 0, host)), None);
+    req.affinity_ttl = ttl;
+    StickyHash
+        .select(&req, &upstreams, now)
+        .expect("selection")
+        .endpoint
+        .id()
+}
+
+/// First millisecond at or after `from` where a new affinity period starts for `key`.
+fn affinity_period_start(key: &str, ttl: Duration, from: SystemTime) -> SystemTime {
+    let period = |at| StickyHash.affinity_period(key, Some(ttl), at);
+    let start = period(from);
+    (0..=ttl.as_millis() as u64)
+        .map(|ms| from + Duration::from_millis(ms))
+        .find(|at| period(*at) != start)
+        .expect("a period starts within one ttl")
+}
+
+#[test]
+fn sticky_affinity_is_honored_within_the_ttl() {
+    // Arrange
+    let ttl = Duration::from_secs(60);
+    let from = UNIX_EPOCH + Duration::from_secs(600);
+
+    for host in 1..=32 {
+        let start = affinity_period_start(&format!("10.0.0.{host}"), ttl, from);
+
+        // Act
+        let first = sticky_pick(host, Some(ttl), start);
+        let last = sticky_pick(host, Some(ttl), start + ttl - Duration::from_millis(1));
+
+        // Assert
+        assert_eq!(first, last, "client 10.0.0.{host} moved within its period");
+    }
+}
+
+#[test]
+fn sticky_affinity_periods_last_the_full_ttl_and_are_staggered_per_key() {
+    // Arrange
+    let ttl = Duration::from_secs(60);
+    let from = UNIX_EPOCH + Duration::from_secs(600);
+
+    // Act
+    let starts: Vec<_> = (1..=32)
+        .map(|host| {
+            let key = format!("10.0.0.{host}");
+            let start = affinity_period_start(&key, ttl, from);
+            let period = |at| StickyHash.affinity_period(&key, Some(ttl), at);
+            (
+                start,
+                period(start),
+                period(start + ttl - Duration::from_millis(1)),
+                period(start + ttl),
+            )
+        })
+        .collect();
+
+    // Assert
+    for (_, first, last, next) in &starts {
+        assert_eq!(first, last);
+        assert_ne!(first, next);
+    }
+    let distinct: HashSet<_> = starts.iter().map(|(start, ..)| *start).collect();
+    assert!(
+        distinct.len() > 1,
+        "every key's period starts at the same moment"
+    );
+}
+
+#[test]
+fn sticky_affinity_expires_after_the_ttl() {
+    // Arrange
+    let ttl = Some(Duration::from_secs(60));
+    let period_start = UNIX_EPOCH + Duration::from_secs(600);
+
+    // Act
+    let before = sticky_picks(ttl, period_start);
+    let after = sticky_picks(ttl, period_start + Duration::from_secs(60));
+
+    // Assert
+    let reselected = before.iter().zip(&after).filter(|(b, a)| b != a).count();
+    assert!(reselected > 0, "no client was given a fresh selection");
+}
+
+#[test]
+fn sticky_affinity_never_expires_without_a_ttl() {
+    // Arrange
+    let start = UNIX_EPOCH + Duration::from_secs(600);
+
+    // Act
+    let before = sticky_picks(None, start);
+    let after = sticky_picks(None, start + Duration::from_secs(86_400));
+
+    // Assert
+    assert_eq!(before, after);
+}
+
+#[test]
+fn ewma_skews_selection_toward_the_faster_upstream() {
+    // Arrange
+    let service_id = ServiceId("svc".into());
+    let snapshot = snapshot_with_service(
+        service_id.clone(),
+        vec![upstream(1), upstream(2), upstream(3)],
+        LoadBalancingStrategy::Ewma,
+    );
+    let manager = TrafficManager::new(snapshot.clone());
+    let director = TrafficDirector;
+    for _ in 0..10 {
+        manager.record_latency(&service_id, &UpstreamId(1), Duration::from_millis(5));
+        manager.record_latency(&service_id, &UpstreamId(2), Duration::from_millis(50));
+        manager.record_latency(&service_id, &UpstreamId(3), Duration::from_millis(500));
+    }
+
+    // Act
+    let mut picks: HashMap<UpstreamId, usize> = HashMap::new();
+    for _ in 0..1000 {
+        let decision = director
+            .decide(&dummy_request(), &snapshot, &service_id, &manager)
+            .expect("decision");
+        assert_eq!(decision.reason, DecisionReason::Ewma);
+        *picks.entry(decision.upstream_id).or_default() += 1;
+    }
+
+    // Assert
+    let picks_of = |id: u32| picks.get(&UpstreamId(id)).copied().unwrap_or(0);
+    assert!(picks_of(1) > picks_of(2));
+    assert_eq!(picks_of(3), 0);
+}
+
+#[test]
+fn ewma_tries_an_upstream_without_latency_samples() {
+    // Arrange
+    let service_id = ServiceId("svc".into());
+    let snapshot = snapshot_with_service(
+        service_id.clone(),
+        vec![upstream(1), upstream(2)],
+        LoadBalancingStrategy::Ewma,
+    );
+    let manager = TrafficManager::new(snapshot.clone());
+    let director = TrafficDirector;
+    manager.record_latency(&service_id, &UpstreamId(1), Duration::from_millis(5));
+
+    // Act
+    let decision = director
+        .decide(&dummy_request(), &snapshot, &service_id, &manager)
+        .expect("decision");
+
+    // Assert
+    assert_eq!(decision.upstream_id, UpstreamId(2));
+}
+
+#[test]
+fn latency_samples_are_averaged_with_more_weight_on_history() {
+    // Arrange
+    let service_id = ServiceId("svc".into());
+    let snapshot = snapshot_with_service(
+        service_id.clone(),
+        vec![upstream(1)],
+        LoadBalancingStrategy::Ewma,
+    );
+    let manager = TrafficManager::new(snapshot);
+
+    // Act
+    manager.record_latency(&service_id, &UpstreamId(1), Duration::from_millis(10));
+    manager.record_latency(&service_id, &UpstreamId(1), Duration::from_millis(20));
+
+    // Assert
+    assert_eq!(
+        manager.latency(&service_id, &UpstreamId(1)),
+        Some(Duration::from_millis(13))
+    );
+}
+
+#[test]
+fn ketama_removing_one_of_four_upstreams_remaps_about_a_quarter_of_keys() {
+    // Arrange
+    let cfg = KetamaConfig::default();
+    let four: Vec<_> = (1..=4).map(upstream).collect();
+    let ring_of_four = KetamaRing::build(&cfg, &four);
+    let ring_of_three = KetamaRing::build(&cfg, &four[..3]);
+    let keys: Vec<String> = (0..10_000).map(|i| format!("object-{i}")).collect();
+
+    // Act
+    let moved: Vec<(UpstreamId, UpstreamId)> = keys
+        .iter()
+        .map(|key| {
+            (
+                ring_of_four.owner(key, |_| true).unwrap(),
+                ring_of_three.owner(key, |_| true).unwrap(),
+            )
+        })
+        .filter(|(before, after)| before != after)
+        .collect();
+
+    // Assert
+    let fraction = moved.len() as f64 / keys.len() as f64;
+    assert!((0.18..0.32).contains(&fraction), "{fraction}");
+    assert!(moved.iter().all(|(before, _)| *before == UpstreamId(4)));
+}
+
+#[test]
+fn ketama_ring_is_rebuilt_when_reload_removes_an_upstream() {
+    // Arrange
+    let service_id = ServiceId("svc".into());
+    let snapshot = snapshot_with_service(
+        service_id.clone(),
+        (1..=4).map(upstream).collect(),
+        LoadBalancingStrategy::Ketama,
+    );
+    let manager = TrafficManager::new(snapshot.clone());
+    let director = TrafficDirector;
+    let clients: Vec<IpAddr> = (0..=255)
+        .map(|host| IpAddr::V4(Ipv4Addr::new(10, 0, 0, host)))
+        .collect();
+    let decide = |snapshot: &TrafficSnapshot| -> Vec<UpstreamId> {
+        clients
+            .iter()
+            .map(|client| {
+                let decision = director
+                    .decide(
+                        &keyed_request(*client, None),
+                        snapshot,
+                        &service_id,
+                        &manager,
+                    )
+                    .expect("decision");
+                assert_eq!(decision.reason, DecisionReason::Ketama);
+                decision.upstream_id
+            })
+            .collect()
+    };
+    let before = decide(&snapshot);
+
+    // Act
+    let reloaded = snapshot_with_service(
+        service_id.clone(),
+        (1..=3).map(upstream).collect(),
+        LoadBalancingStrategy::Ketama,
+    );
+    manager.update(reloaded.clone());
+    let after = decide(&reloaded);
+
+    // Assert
+    assert!(before.contains(&UpstreamId(4)));
+    assert!(!after.contains(&UpstreamId(4)));
+    for (before, after) in before.iter().zip(&after) {
+        if *before != UpstreamId(4) {
+            assert_eq!(before, after);
+        }
+    }
+}
+
+#[test]
+fn ketama_key_header_pins_requests_across_client_addresses() {
+    // Arrange
+    let service_id = ServiceId("svc".into());
+    let mut snapshot = snapshot_with_service(
+        service_id.clone(),
+        (1..=4).map(upstream).collect(),
+        LoadBalancingStrategy::Ketama,
+    );
+    snapshot.services.get_mut(&service_id).unwrap().ketama_cfg = KetamaConfig {
+        replicas: 40,
+        key_header: Some("x-cache-key".to_string()),
+    };
+    let manager = TrafficManager::new(snapshot.clone());
+    let director = TrafficDirector;
+
+    // Act
+    let upstreams: Vec<_> = (1..=5)
+        .map(|host| {
+            let client = IpAddr::V4(Ipv4Addr::new(10, 0, 0, host));
+            director
+                .decide(
+                    &keyed_request(client, Some("thumbnails/42.png")),
+                    &snapshot,
+                    &service_id,
+                    &manager,
+                )
+                .expect("decision")
+                .upstream_id
+        })
+        .collect();
+
+    // Assert
+    assert!(upstreams.iter().all(|id| *id == upstreams[0]));
+}
+
+#[test]
+fn adjusting_upstream_weight_shifts_round_robin_distribution() {
+    // Arrange
+    let service_id = ServiceId("svc".into());
+    let snapshot = snapshot_with_service(
+        service_id.clone(),
+        vec![upstream(1), upstream(2)],
+        LoadBalancingStrategy::RoundRobin,
+    );
+    let manager = TrafficManager::new(snapshot.clone());
+    let director = TrafficDirector;
+    let picks_of_upstream_2 = || {
+        (0..40)
+            .map(|_| {
+                director
+                    .decide(&dummy_request(), &snapshot, &service_id, &manager)
+                    .expect("decision")
+                    .upstream_id
+            })
+            .filter(|id| *id == UpstreamId(2))
+            .count()
+    };
+    let before = picks_of_upstream_2();
+
+    // Act
+    let adjusted = manager.set_upstream_weight(&service_id, &UpstreamId(2), 3);
+    let after = picks_of_upstream_2();
+
+    // Assert
+    assert!(adjusted);
+    assert_eq!(before, 20);
+    assert_eq!(after, 30);
+}
+
+#[test]
+fn adjusted_upstream_weight_reverts_on_reload() {
+    // Arrange
+    let service_id = ServiceId("svc".into());
+    let snapshot = snapshot_with_service(
+        service_id.clone(),
+        vec![upstream(1), upstream(2)],
+        LoadBalancingStrategy::RoundRobin,
+    );
+    let manager = TrafficManager::new(snapshot.clone());
+    manager.set_upstream_weight(&service_id, &UpstreamId(2), 3);
+
+    // Act
+    manager.update(snapshot.clone());
+
+    // Assert
+    assert_eq!(manager.upstream_weight(&service_id, &upstream(2)), 1);
+}
+
+#[test]
+fn adjusting_weight_of_unknown_upstream_is_rejected() {
+    // Arrange
+    let service_id = ServiceId("svc".into());
+    let snapshot = snapshot_with_service(
+        service_id.clone(),
+        vec![upstream(1), upstream(2)],
+        LoadBalancingStrategy::RoundRobin,
+    );
+    let manager = TrafficManager::new(snapshot);
+
+    // Act
+    let unknown_upstream = manager.set_upstream_weight(&service_id, &UpstreamId(3), 3);
+    let unknown_service =
+        manager.set_upstream_weight(&ServiceId("other".into()), &UpstreamId(1), 3);
+
+    // Assert
+    assert!(!unknown_upstream);
+    assert!(!unknown_service);
+}
+
+#[test]
+fn adjusting_upstream_weight_rebuilds_the_ketama_ring() {
+    // Arrange
+    let service_id = ServiceId("svc".into());
+    let snapshot = snapshot_with_service(
+        service_id.clone(),
+        vec![upstream(1), upstream(2)],
+        LoadBalancingStrategy::Ketama,
+    );
+    let manager = TrafficManager::new(snapshot.clone());
+    let director = TrafficDirector;
+    let picks_of_upstream_2 = || {
+        (0..=255)
+            .map(|host| IpAddr::V4(Ipv4Addr::new(10, 0, 0, host)))
+            .map(|client| {
+                director
+                    .decide(
+                        &keyed_request(client, None),
+                        &snapshot,
+                        &service_id,
+                        &manager,
+                    )
+                    .expect("decision")
+                    .upstream_id
+            })
+            .filter(|id| *id == UpstreamId(2))
+            .count()
+    };
+    let before = picks_of_upstream_2();
+
+    // Act
+    manager.set_upstream_weight(&service_id, &UpstreamId(2), 10);
+    let after = picks_of_upstream_2();
+
+    // Assert
+    assert!(after > before);
+    assert!(after > 200);
+}
+
+#[test]
+fn retry_avoids_upstreams_the_request_failed_on() {
+    // Arrange
+    let service_id = ServiceId("svc".into());
+    let snapshot = snapshot_with_service(
+        service_id.clone(),
+        vec![upstream(1), upstream(2)],
+        LoadBalancingStrategy::Failover,
+    );
+    let manager = TrafficManager::new(snapshot.clone());
+    let director = TrafficDirector;
+    let mut req = dummy_request();
+    req.retried_upstreams.push(UpstreamId(1));
+
+    // Act
+    let decision = director
+        .decide(&req, &snapshot, &service_id, &manager)
+        .expect("decision");
+
+    // Assert
+    assert_eq!(decision.upstream_id, UpstreamId(2));
+}
+
+#[test]
+fn retry_reuses_failed_upstream_when_no_other_is_left() {
+    // Arrange
+    let service_id = ServiceId("svc".into());
+    let snapshot = snapshot_with_service(
+        service_id.clone(),
+        vec![upstream(1), upstream(2)],
+        LoadBalancingStrategy::Failover,
+    );
+    let manager = TrafficManager::new(snapshot.clone());
+    let director = TrafficDirector;
+    let mut req = dummy_request();
+    req.retried_upstreams = vec![UpstreamId(1), UpstreamId(2)];
+
+    // Act
+    let decision = director
+        .decide(&req, &snapshot, &service_id, &manager)
+        .expect("decision");
+
+    // Assert
+    assert_eq!(decision.upstream_id, UpstreamId(1));
+}
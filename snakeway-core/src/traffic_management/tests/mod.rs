@@ -1,3 +1,5 @@
 mod admin;
 mod circuit;
 mod director;
+mod outlier;
+mod retry;
@@ -0,0 +1,81 @@
+use crate::traffic_management::outlier::*;
+use std::time::Duration;
+
+fn params() -> OutlierDetectionParams {
+    OutlierDetectionParams {
+        enable: true,
+        consecutive_5xx: 3,
+        base_ejection_time: Duration::from_millis(50),
+        max_ejection_time: Duration::from_millis(150),
+    }
+}
+
+/// Record errors until the detector reaches its threshold, then eject.
+fn burst(od: &mut OutlierDetector, p: &OutlierDetectionParams) -> Duration {
+    assert!(!od.record(p, true));
+    assert!(!od.record(p, true));
+    assert!(od.record(p, true));
+    od.eject(p)
+}
+
+#[test]
+fn test_od_ejects_at_threshold() {
+    let mut od = OutlierDetector::new();
+    let p = params();
+
+    assert!(!od.is_ejected());
+
+    let ejection_time = burst(&mut od, &p);
+    assert_eq!(ejection_time, Duration::from_millis(50));
+    assert!(od.is_ejected());
+
+    // Wait for the ejection to end
+    std::thread::sleep(Duration::from_millis(60));
+    assert!(!od.is_ejected());
+}
+
+#[test]
+fn test_od_ignores_requests_in_flight_during_ejection() {
+    let mut od = OutlierDetector::new();
+    let p = params();
+
+    burst(&mut od, &p);
+
+    // Responses from requests started before the ejection do not count
+    assert!(!od.record(&p, true));
+    assert!(!od.record(&p, true));
+    assert!(!od.record(&p, true));
+    assert_eq!(od.consecutive_errors, 0);
+}
+
+#[test]
+fn test_od_backoff_doubles_up_to_max() {
+    let mut od = OutlierDetector::new();
+    let p = params();
+
+    assert_eq!(burst(&mut od, &p), Duration::from_millis(50));
+    std::thread::sleep(Duration::from_millis(60));
+
+    assert_eq!(burst(&mut od, &p), Duration::from_millis(100));
+    std::thread::sleep(Duration::from_millis(110));
+
+    // 200ms, capped at the max
+    assert_eq!(burst(&mut od, &p), Duration::from_millis(150));
+    assert_eq!(od.ejections, 3);
+}
+
+#[test]
+fn test_od_backoff_resets_after_staying_in_rotation() {
+    let mut od = OutlierDetector::new();
+    let p = params();
+
+    burst(&mut od, &p);
+    std::thread::sleep(Duration::from_millis(60));
+    assert_eq!(burst(&mut od, &p), Duration::from_millis(100));
+
+    // Ejection ends, then the upstream stays in rotation for the max ejection time
+    std::thread::sleep(Duration::from_millis(110 + 150));
+
+    assert_eq!(burst(&mut od, &p), Duration::from_millis(50));
+    assert_eq!(od.ejections, 1);
+}
@@ -0,0 +1,88 @@
+use crate::traffic_management::retry::*;
+use http::Method;
+use std::sync::Arc;
+
+fn params() -> RetryPolicyParams {
+    RetryPolicyParams {
+        enable: true,
+        max_retries: 2,
+        retry_on_status: vec![502, 503],
+        retry_on_connection_error: true,
+        retry_non_idempotent: false,
+        budget_percent: 50,
+        min_retry_concurrency: 1,
+    }
+}
+
+#[test]
+fn test_retry_stops_at_max_retries() {
+    let budget = Arc::new(RetryBudget::default());
+    let p = RetryPolicyParams {
+        min_retry_concurrency: 10,
+        ..params()
+    };
+
+    let mut guard = budget.track_request();
+
+    assert!(guard.try_retry(&p));
+    assert!(guard.try_retry(&p));
+    assert!(!guard.try_retry(&p));
+    assert_eq!(guard.retries(), 2);
+}
+
+#[test]
+fn test_retry_budget_is_a_share_of_requests_in_flight() {
+    let budget = Arc::new(RetryBudget::default());
+    let p = params();
+
+    // 50% of 4 requests in flight
+    let mut guards: Vec<_> = (0..4).map(|_| budget.track_request()).collect();
+
+    assert!(guards[0].try_retry(&p));
+    assert!(guards[1].try_retry(&p));
+    assert!(!guards[2].try_retry(&p));
+    assert!(!guards[3].try_retry(&p));
+    assert_eq!(budget.active_retries(), 2);
+}
+
+#[test]
+fn test_retry_budget_allows_min_retry_concurrency() {
+    let budget = Arc::new(RetryBudget::default());
+    let p = params();
+
+    // 50% of a single request rounds down to none, the minimum still allows one
+    let mut guard = budget.track_request();
+
+    assert!(guard.try_retry(&p));
+    assert!(!guard.try_retry(&p));
+}
+
+#[test]
+fn test_retry_budget_is_returned_when_request_ends() {
+    let budget = Arc::new(RetryBudget::default());
+    let p = params();
+
+    let mut guard = budget.track_request();
+    assert!(guard.try_retry(&p));
+    drop(guard);
+    assert_eq!(budget.active_retries(), 0);
+
+    let mut guard = budget.track_request();
+    assert!(guard.try_retry(&p));
+}
+
+#[test]
+fn test_retry_only_idempotent_methods_by_default() {
+    let p = params();
+
+    assert!(p.retries_method(&Method::GET));
+    assert!(p.retries_method(&Method::PUT));
+    assert!(!p.retries_method(&Method::POST));
+    assert!(!p.retries_method(&Method::PATCH));
+
+    let p = RetryPolicyParams {
+        retry_non_idempotent: true,
+        ..params()
+    };
+    assert!(p.retries_method(&Method::POST));
+}
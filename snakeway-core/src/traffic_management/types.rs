@@ -33,4 +33,15 @@ pub struct HealthCheckParams {
     pub enable: bool,
     pub failure_threshold: u32,
     pub unhealthy_cooldown: Duration,
+    pub active: Option<ActiveHealthCheckParams>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ActiveHealthCheckParams {
+    pub path: String,
+    pub interval: Duration,
+    pub timeout: Duration,
+    pub expected_status: u16,
+    pub failure_threshold: u32,
+    pub success_threshold: u32,
 }
@@ -9,19 +9,24 @@ use std::sync::Arc;
 #[derive(Debug)]
 pub struct WsConnectionGuard {
     state: Arc<WsRouteConnectionState>,
+    global: Arc<WsRouteConnectionState>,
 }
 
 impl WsConnectionGuard {
-    /// Create a guard for an already-acquired slot.
+    /// Create a guard for an already-acquired route and global slot.
     /// This is intentionally restricted to prevent bypassing limits.
-    pub(crate) fn new_acquired(state: Arc<WsRouteConnectionState>) -> Self {
-        Self { state }
+    pub(crate) fn new_acquired(
+        state: Arc<WsRouteConnectionState>,
+        global: Arc<WsRouteConnectionState>,
+    ) -> Self {
+        Self { state, global }
     }
 }
 
 impl Drop for WsConnectionGuard {
-    /// Release the slot when the request ends.
+    /// Release both slots when the request ends, after on_ws_close has run.
     fn drop(&mut self) {
         self.state.release();
+        self.global.release();
     }
 }
@@ -10,14 +10,15 @@ use std::sync::Arc;
 #[derive(Debug, Default)]
 pub struct WsConnectionManager {
     routes: DashMap<RouteId, Arc<WsRouteConnectionState>>,
+
+    /// Connections across all routes. Its limit comes from the current config on every acquire.
+    global: Arc<WsRouteConnectionState>,
 }
 
 impl WsConnectionManager {
     /// Create a new, empty manager.
     pub fn new() -> Self {
-        Self {
-            routes: DashMap::new(),
-        }
+        Self::default()
     }
 
     /// Get (or lazily create) the connection state for a route.
@@ -34,17 +35,27 @@ impl WsConnectionManager {
             .clone()
     }
 
-    /// Attempt to acquire a connection slot for the given route.
+    /// Attempt to acquire a connection slot for the given route, and one under the global limit.
     ///
-    /// On success, returns a ConnectionGuard that will release the slot on Drop.
-    pub fn try_acquire(&self, route_id: &RouteId, max: Option<usize>) -> Option<WsConnectionGuard> {
+    /// On success, returns a ConnectionGuard that will release both slots on Drop.
+    pub fn try_acquire(
+        &self,
+        route_id: &RouteId,
+        max: Option<usize>,
+        global_max: Option<usize>,
+    ) -> Option<WsConnectionGuard> {
+        if !self.global.try_acquire_up_to(global_max) {
+            return None;
+        }
+
         let state = self.route_state(route_id, max);
 
         if !state.try_acquire() {
+            self.global.release();
             return None;
         }
 
-        Some(WsConnectionGuard::new_acquired(state))
+        Some(WsConnectionGuard::new_acquired(state, self.global.clone()))
     }
 
     /// Get the current active connection count for a route.
@@ -55,6 +66,11 @@ impl WsConnectionManager {
             .map(|state| state.active())
             .unwrap_or(0)
     }
+
+    /// Get the current active connection count across all routes.
+    pub fn total_active(&self) -> usize {
+        self.global.active()
+    }
 }
 
 pub struct RouteConnectionSnapshot {
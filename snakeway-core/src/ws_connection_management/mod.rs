@@ -1,6 +1,8 @@
 mod guard;
 mod manager;
 mod state;
+#[cfg(test)]
+mod tests;
 
 pub use guard::WsConnectionGuard;
 pub use manager::WsConnectionManager;
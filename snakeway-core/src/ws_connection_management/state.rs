@@ -1,6 +1,6 @@
 use std::sync::atomic::{AtomicUsize, Ordering};
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct WsRouteConnectionState {
     active: AtomicUsize,
     max: Option<usize>,
@@ -17,7 +17,13 @@ impl WsRouteConnectionState {
     /// Attempt to acquire one connection slot.
     /// On success, the counter is incremented atomically.
     pub fn try_acquire(&self) -> bool {
-        match self.max {
+        self.try_acquire_up_to(self.max)
+    }
+
+    /// Attempt to acquire one connection slot under a limit given by the caller,
+    /// e.g. one that can change on reload.
+    pub fn try_acquire_up_to(&self, max: Option<usize>) -> bool {
+        match max {
             None => {
                 // Unlimited
                 self.active.fetch_add(1, Ordering::Relaxed);
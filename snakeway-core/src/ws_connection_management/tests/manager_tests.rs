@@ -0,0 +1,68 @@
+use crate::route::types::RouteId;
+use crate::ws_connection_management::WsConnectionManager;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn route_limit_refuses_connections_over_max() {
+    // Arrange
+    let manager = WsConnectionManager::new();
+    let route = RouteId::service("/ws", "api");
+    let _held = manager.try_acquire(&route, Some(1), None).unwrap();
+
+    // Act
+    let refused = manager.try_acquire(&route, Some(1), None);
+
+    // Assert
+    assert!(refused.is_none());
+    assert_eq!(manager.active(&route), 1);
+    assert_eq!(manager.total_active(), 1);
+}
+
+#[test]
+fn global_limit_applies_across_routes() {
+    // Arrange
+    let manager = WsConnectionManager::new();
+    let chat = RouteId::service("/chat", "api");
+    let feed = RouteId::service("/feed", "api");
+    let _held = manager.try_acquire(&chat, None, Some(1)).unwrap();
+
+    // Act
+    let refused = manager.try_acquire(&feed, None, Some(1));
+
+    // Assert
+    assert!(refused.is_none());
+    assert_eq!(manager.active(&feed), 0);
+    assert_eq!(manager.total_active(), 1);
+}
+
+#[test]
+fn refused_route_slot_releases_global_slot() {
+    // Arrange
+    let manager = WsConnectionManager::new();
+    let route = RouteId::service("/ws", "api");
+    let _held = manager.try_acquire(&route, Some(1), Some(10)).unwrap();
+
+    // Act
+    let refused = manager.try_acquire(&route, Some(1), Some(10));
+
+    // Assert
+    assert!(refused.is_none());
+    assert_eq!(manager.total_active(), 1);
+}
+
+#[test]
+fn dropping_guard_frees_both_slots() {
+    // Arrange
+    let manager = WsConnectionManager::new();
+    let route = RouteId::service("/ws", "api");
+    let guard = manager.try_acquire(&route, Some(1), Some(1)).unwrap();
+
+    // Act
+    drop(guard);
+    let reacquired = manager.try_acquire(&route, Some(1), Some(1));
+
+    // Assert
+    assert!(reacquired.is_some());
+    assert_eq!(manager.active(&route), 1);
+    assert_eq!(manager.total_active(), 1);
+}
@@ -40,6 +40,14 @@ enum Command {
 
         #[arg(long)]
         stats: bool,
+
+        /// Latency histogram bucket upper bounds for --stats, in ms, e.g. "1,5,10,50"
+        #[arg(long, value_delimiter = ',')]
+        latency_buckets: Vec<u64>,
+
+        /// How much --stats shows below the summary
+        #[arg(long, value_enum, default_value_t = cli::logs::StatsDetail::Adaptive)]
+        detail: cli::logs::StatsDetail,
     },
 
     /// Reload a running Snakeway instance (SIGHUP)
@@ -47,6 +55,14 @@ enum Command {
         /// Path to pid file
         #[arg(long, default_value = "/tmp/snakeway.pid")]
         pid_file: String,
+
+        /// Path to the Snakeway config directory being reloaded
+        #[arg(long, default_value = "config")]
+        config: String,
+
+        /// Print the changes from this config directory to --config before reloading
+        #[arg(long)]
+        diff_from: Option<String>,
     },
 
     /// Run the Snakeway proxy (default)
@@ -72,6 +88,16 @@ fn main() {
                     std::process::exit(1);
                 }
             }
+            cli::conf::ConfigCmd::Lint {
+                path,
+                quiet,
+                format,
+            } => {
+                if let Err(e) = cli::conf::lint(path, quiet, format) {
+                    eprintln!("Invalid configuration\n\n{e}");
+                    std::process::exit(1);
+                }
+            }
             cli::conf::ConfigCmd::Dump {
                 path,
                 json,
@@ -83,12 +109,30 @@ fn main() {
                     std::process::exit(1);
                 }
             }
+            cli::conf::ConfigCmd::Route { cmd } => {
+                if let Err(e) = cli::conf::route(cmd) {
+                    eprintln!("route error: {e}");
+                    std::process::exit(1);
+                }
+            }
+            cli::conf::ConfigCmd::Schema { file } => {
+                if let Err(e) = cli::conf::schema(file) {
+                    eprintln!("Failed to print schema: {e}");
+                    std::process::exit(1);
+                }
+            }
             cli::conf::ConfigCmd::Init { path } => {
                 cli::conf::init(path).expect("Failed to initialize config directory");
             }
         },
 
-        Some(Command::Logs { pretty, raw, stats }) => {
+        Some(Command::Logs {
+            pretty,
+            raw,
+            stats,
+            latency_buckets,
+            detail,
+        }) => {
             let mode = if raw {
                 LogMode::Raw
             } else if pretty {
@@ -98,7 +142,15 @@ fn main() {
             } else {
                 default_log_mode()
             };
-            cli::logs::run_logs(mode).expect("Failed to run logs command");
+            let latency_buckets = if latency_buckets.is_empty() {
+                cli::logs::DEFAULT_LATENCY_BUCKETS_MS.to_vec()
+            } else {
+                latency_buckets
+            };
+            if let Err(e) = cli::logs::run_logs(mode, &latency_buckets, detail) {
+                eprintln!("logs error: {e}");
+                std::process::exit(1);
+            }
         }
 
         Some(Command::Plugin { cmd }) => {
@@ -110,10 +162,18 @@ fn main() {
             }
         }
 
-        Some(Command::Reload { pid_file }) => {
+        Some(Command::Reload {
+            pid_file,
+            config,
+            diff_from,
+        }) => {
             init_logging();
 
-            if let Err(e) = cli::reload::run(&pid_file) {
+            if let Err(e) = cli::reload::run(
+                &pid_file,
+                Path::new(&config),
+                diff_from.as_deref().map(Path::new),
+            ) {
                 eprintln!("reload failed: {e}");
                 std::process::exit(1);
             }